@@ -0,0 +1,216 @@
+//! Corpora shared by benchmarks.
+//!
+//! By default a corpus is generated with a Zipf like word distribution, so it needs no network.
+//! With feature `bench-wiki` a subset of the wiki_560w dataset used by C++ benchmarks is loaded,
+//! files are downloaded by `curl` into `TANTIVY_SEARCH_BENCH_DATA` (default `target/bench-data`).
+//! `TANTIVY_SEARCH_BENCH_DOCS` overrides number of docs in the corpus.
+#![allow(dead_code)]
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::ffi::CString;
+use std::ptr;
+use tantivy_search::*;
+
+// Column indexed by benchmarks.
+pub static TEXT_COLUMN: &str = "text";
+
+// Queries executed by a search benchmark.
+pub static QUERY_TERMS_SIZE: usize = 64;
+
+pub struct Corpus {
+    pub docs: Vec<String>,
+    pub query_terms: Vec<String>,
+}
+
+fn num_docs(default_docs: usize) -> usize {
+    std::env::var("TANTIVY_SEARCH_BENCH_DOCS")
+        .ok()
+        .and_then(|docs| docs.parse::<usize>().ok())
+        .unwrap_or(default_docs)
+}
+
+#[cfg(not(feature = "bench-wiki"))]
+pub fn load_corpus() -> Corpus {
+    generated_corpus(num_docs(20_000))
+}
+
+#[cfg(feature = "bench-wiki")]
+pub fn load_corpus() -> Corpus {
+    wiki::wiki_corpus(num_docs(100_000))
+}
+
+/// Deterministic corpus, word `i` of the vocabulary is drawn with probability about `1 / (i + 1)`.
+pub fn generated_corpus(num_docs: usize) -> Corpus {
+    let mut rng = StdRng::seed_from_u64(42);
+    let vocabulary: Vec<String> = (0..20_000)
+        .map(|_| {
+            let len = rng.gen_range(3..10);
+            (0..len)
+                .map(|_| rng.gen_range(b'a'..=b'z') as char)
+                .collect()
+        })
+        .collect();
+    let max_rank = (vocabulary.len() as f64).ln();
+    let docs: Vec<String> = (0..num_docs)
+        .map(|_| {
+            let words = rng.gen_range(20..120);
+            (0..words)
+                .map(|_| {
+                    let rank = (rng.gen::<f64>() * max_rank).exp() as usize - 1;
+                    vocabulary[rank.min(vocabulary.len() - 1)].as_str()
+                })
+                .collect::<Vec<&str>>()
+                .join(" ")
+        })
+        .collect();
+    // Mix frequent and rare terms like real queries.
+    let query_terms: Vec<String> = (0..QUERY_TERMS_SIZE)
+        .map(|i| vocabulary[(i * i * 5) % vocabulary.len()].clone())
+        .collect();
+    Corpus { docs, query_terms }
+}
+
+#[cfg(feature = "bench-wiki")]
+mod wiki {
+    use super::{Corpus, QUERY_TERMS_SIZE};
+    use serde::de::{DeserializeSeed, IgnoredAny, SeqAccess, Visitor};
+    use serde::Deserialize;
+    use std::fs::File;
+    use std::io::BufReader;
+    use std::path::{Path, PathBuf};
+    use std::process::Command;
+
+    static DATASET_URL: &str =
+        "https://mqdb-release-1253802058.cos.ap-beijing.myqcloud.com/datasets/wiki_560w.json";
+    static QUERY_TERMS_URL: &str =
+        "https://mqdb-release-1253802058.cos.ap-beijing.myqcloud.com/datasets/query_terms.json";
+
+    #[derive(Deserialize)]
+    struct WikiDoc {
+        body: String,
+    }
+
+    #[derive(Deserialize)]
+    struct QueryTerms {
+        terms: Vec<String>,
+    }
+
+    // Read the first `limit` docs of a json array, remaining docs are skipped without allocating.
+    struct FirstDocs {
+        limit: usize,
+    }
+
+    impl<'de> DeserializeSeed<'de> for FirstDocs {
+        type Value = Vec<String>;
+
+        fn deserialize<D: serde::Deserializer<'de>>(self, d: D) -> Result<Vec<String>, D::Error> {
+            d.deserialize_seq(self)
+        }
+    }
+
+    impl<'de> Visitor<'de> for FirstDocs {
+        type Value = Vec<String>;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("an array of wiki docs")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<String>, A::Error> {
+            let mut docs: Vec<String> = Vec::with_capacity(self.limit);
+            while docs.len() < self.limit {
+                match seq.next_element::<WikiDoc>()? {
+                    Some(doc) => docs.push(doc.body),
+                    None => return Ok(docs),
+                }
+            }
+            while seq.next_element::<IgnoredAny>()?.is_some() {}
+            Ok(docs)
+        }
+    }
+
+    fn data_directory() -> PathBuf {
+        match std::env::var("TANTIVY_SEARCH_BENCH_DATA") {
+            Ok(directory) => PathBuf::from(directory),
+            Err(_) => Path::new(env!("CARGO_MANIFEST_DIR")).join("target/bench-data"),
+        }
+    }
+
+    // Download `url` into data directory once, later runs reuse the file.
+    fn download(url: &str) -> PathBuf {
+        let directory = data_directory();
+        let file_name = url.rsplit('/').next().unwrap();
+        let path = directory.join(file_name);
+        if path.exists() {
+            return path;
+        }
+        std::fs::create_dir_all(&directory).expect("Can't create bench data directory");
+        let partial = directory.join(format!("{}.partial", file_name));
+        let status = Command::new("curl")
+            .args(["-fL", "--retry", "3", "-o"])
+            .arg(&partial)
+            .arg(url)
+            .status()
+            .expect("Can't run curl to download bench data");
+        assert!(status.success(), "Failed to download {}", url);
+        std::fs::rename(&partial, &path).expect("Can't move downloaded bench data");
+        path
+    }
+
+    pub fn wiki_corpus(num_docs: usize) -> Corpus {
+        let dataset = File::open(download(DATASET_URL)).expect("Can't open wiki dataset");
+        let mut deserializer = serde_json::Deserializer::from_reader(BufReader::new(dataset));
+        let docs: Vec<String> = FirstDocs { limit: num_docs }
+            .deserialize(&mut deserializer)
+            .expect("Can't parse wiki dataset");
+
+        let query_terms = File::open(download(QUERY_TERMS_URL)).expect("Can't open query terms");
+        let query_terms: QueryTerms =
+            serde_json::from_reader(BufReader::new(query_terms)).expect("Can't parse query terms");
+        Corpus {
+            docs,
+            query_terms: query_terms
+                .terms
+                .into_iter()
+                .take(QUERY_TERMS_SIZE)
+                .collect(),
+        }
+    }
+}
+
+/// Open index directory through C API, caller closes the handle.
+pub fn open_handle(index_path: &str) -> *mut IndexHandle {
+    let index_path = CString::new(index_path).unwrap();
+    let mut handle: *mut IndexHandle = ptr::null_mut();
+    assert_eq!(
+        tantivy_search_index_open(index_path.as_ptr(), &mut handle),
+        ErrorCode::Ok
+    );
+    handle
+}
+
+/// Create index with `TEXT_COLUMN` and add all `docs`, row_id is the position of doc.
+pub fn index_docs(handle: *mut IndexHandle, docs: &[String]) {
+    let column_name = CString::new(TEXT_COLUMN).unwrap();
+    let column_names = [column_name.as_ptr()];
+    assert_eq!(
+        tantivy_search_index_create(handle, column_names.as_ptr(), 1, ptr::null()),
+        ErrorCode::Ok
+    );
+    for (row_id, doc) in docs.iter().enumerate() {
+        // Wiki bodies may contain nul bytes, they can't be passed as C strings.
+        let doc = CString::new(doc.replace('\0', " ")).unwrap();
+        let column_docs = [doc.as_ptr()];
+        assert_eq!(
+            tantivy_search_index_add_document(
+                handle,
+                row_id as u64,
+                column_names.as_ptr(),
+                column_docs.as_ptr(),
+                1
+            ),
+            ErrorCode::Ok
+        );
+    }
+    assert_eq!(tantivy_search_index_commit(handle), ErrorCode::Ok);
+}
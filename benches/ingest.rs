@@ -0,0 +1,32 @@
+//! Ingest throughput of the index writer bridge, from index creation to commit.
+mod common;
+
+use common::{index_docs, load_corpus, open_handle};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use tantivy_search::*;
+use tempfile::TempDir;
+
+fn bench_ingest(c: &mut Criterion) {
+    let corpus = load_corpus();
+
+    let mut group = c.benchmark_group("ingest");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(corpus.docs.len() as u64));
+    group.bench_function("index_and_commit", |b| {
+        b.iter_batched(
+            || TempDir::new().unwrap(),
+            |temp_directory| {
+                let handle = open_handle(temp_directory.path().to_str().unwrap());
+                index_docs(handle, &corpus.docs);
+                assert_eq!(tantivy_search_index_close(handle), ErrorCode::Ok);
+                // Directory is removed outside of measurement.
+                temp_directory
+            },
+            BatchSize::PerIteration,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_ingest);
+criterion_main!(benches);
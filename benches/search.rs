@@ -0,0 +1,117 @@
+//! Latency of queries on the cached reader: bitmap collection, top-k BM25 search and regex.
+//! Top-k results are returned in library allocated json or written to host allocated arrays.
+//! Each iteration runs all query terms of the corpus once.
+mod common;
+
+use common::{index_docs, load_corpus, open_handle, TEXT_COLUMN};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::ffi::CString;
+use tantivy_search::*;
+use tempfile::TempDir;
+
+fn free_buffer(error_code: ErrorCode, buffer: ByteBuffer) {
+    assert_eq!(error_code, ErrorCode::Ok);
+    tantivy_search_byte_buffer_free(buffer);
+}
+
+fn bench_search(c: &mut Criterion) {
+    let corpus = load_corpus();
+    let temp_directory = TempDir::new().unwrap();
+    let handle = open_handle(temp_directory.path().to_str().unwrap());
+    index_docs(handle, &corpus.docs);
+    assert_eq!(tantivy_search_index_load_reader(handle), ErrorCode::Ok);
+
+    let column_name = CString::new(TEXT_COLUMN).unwrap();
+    let terms: Vec<CString> = corpus
+        .query_terms
+        .iter()
+        .map(|term| CString::new(term.as_str()).unwrap())
+        .collect();
+    // Infix patterns of query terms, e.g. `%ncien%`.
+    let patterns: Vec<CString> = corpus
+        .query_terms
+        .iter()
+        .map(|term| {
+            let chars: Vec<char> = term.to_lowercase().chars().collect();
+            let infix: String = match chars.len() {
+                0..=2 => chars.iter().collect(),
+                len => chars[1..len - 1].iter().collect(),
+            };
+            CString::new(format!("%{}%", infix)).unwrap()
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("search");
+    group.throughput(Throughput::Elements(terms.len() as u64));
+    group.bench_function("term_bitmap", |b| {
+        b.iter(|| {
+            for term in &terms {
+                let mut buffer = ByteBuffer::empty();
+                let error_code = tantivy_search_index_query_term_bitmap(
+                    handle,
+                    column_name.as_ptr(),
+                    term.as_ptr(),
+                    &mut buffer,
+                );
+                free_buffer(error_code, buffer);
+            }
+        })
+    });
+    for topk in [10u32, 100] {
+        group.bench_with_input(BenchmarkId::new("bm25_topk", topk), &topk, |b, topk| {
+            b.iter(|| {
+                for term in &terms {
+                    let mut buffer = ByteBuffer::empty();
+                    let error_code =
+                        tantivy_search_index_search_bm25(handle, term.as_ptr(), *topk, &mut buffer);
+                    free_buffer(error_code, buffer);
+                }
+            })
+        });
+        // Same search written to buffers reused across queries.
+        let mut row_ids: Vec<u64> = vec![0; topk as usize];
+        let mut scores: Vec<f32> = vec![0.0; topk as usize];
+        group.bench_with_input(
+            BenchmarkId::new("bm25_topk_into", topk),
+            &topk,
+            |b, topk| {
+                b.iter(|| {
+                    for term in &terms {
+                        let mut count: usize = 0;
+                        let error_code = tantivy_search_index_search_bm25_into(
+                            handle,
+                            term.as_ptr(),
+                            *topk,
+                            row_ids.as_mut_ptr(),
+                            scores.as_mut_ptr(),
+                            row_ids.len(),
+                            &mut count,
+                        );
+                        assert_eq!(error_code, ErrorCode::Ok);
+                    }
+                })
+            },
+        );
+    }
+    group.sample_size(10);
+    group.bench_function("regex_bitmap", |b| {
+        b.iter(|| {
+            for pattern in &patterns {
+                let mut buffer = ByteBuffer::empty();
+                let error_code = tantivy_search_index_regex_term_bitmap(
+                    handle,
+                    column_name.as_ptr(),
+                    pattern.as_ptr(),
+                    &mut buffer,
+                );
+                free_buffer(error_code, buffer);
+            }
+        })
+    });
+    group.finish();
+
+    assert_eq!(tantivy_search_index_close(handle), ErrorCode::Ok);
+}
+
+criterion_group!(benches, bench_search);
+criterion_main!(benches);
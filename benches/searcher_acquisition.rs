@@ -0,0 +1,106 @@
+//! Searcher acquisition must not dominate small query latency.
+//!
+//! `reader_per_call` opens a new `IndexReader` for every query, as searches used to do,
+//! `cached_reader` reuses one reader with `ReloadPolicy::Manual` like the reader cache,
+//! `capi_term_bitmap` is a small query through the public C API on the cached reader.
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::ffi::CString;
+use std::ptr;
+use tantivy::collector::Count;
+use tantivy::query::TermQuery;
+use tantivy::schema::IndexRecordOption;
+use tantivy::{Index, IndexReader, ReloadPolicy, Term};
+use tantivy_search::*;
+use tempfile::TempDir;
+
+static NUM_DOCS: u64 = 10_000;
+
+fn build_index(index_path: &str) -> *mut IndexHandle {
+    let index_path = CString::new(index_path).unwrap();
+    let column_name = CString::new("text").unwrap();
+    let column_names = [column_name.as_ptr()];
+
+    let mut handle: *mut IndexHandle = ptr::null_mut();
+    assert_eq!(
+        tantivy_search_index_open(index_path.as_ptr(), &mut handle),
+        ErrorCode::Ok
+    );
+    assert_eq!(
+        tantivy_search_index_create(handle, column_names.as_ptr(), 1, ptr::null()),
+        ErrorCode::Ok
+    );
+    for row_id in 0..NUM_DOCS {
+        let doc = CString::new(format!("doc {} group{}", row_id, row_id % 100)).unwrap();
+        let column_docs = [doc.as_ptr()];
+        assert_eq!(
+            tantivy_search_index_add_document(
+                handle,
+                row_id,
+                column_names.as_ptr(),
+                column_docs.as_ptr(),
+                1
+            ),
+            ErrorCode::Ok
+        );
+    }
+    assert_eq!(tantivy_search_index_commit(handle), ErrorCode::Ok);
+    assert_eq!(tantivy_search_index_load_reader(handle), ErrorCode::Ok);
+    handle
+}
+
+fn bench_searcher_acquisition(c: &mut Criterion) {
+    let temp_directory = TempDir::new().unwrap();
+    let index_path = temp_directory.path().to_str().unwrap();
+    let handle = build_index(index_path);
+
+    let index = Index::open_in_dir(index_path).unwrap();
+    let text_field = index.schema().get_field("text").unwrap();
+    let term_query = TermQuery::new(
+        Term::from_field_text(text_field, "group7"),
+        IndexRecordOption::Basic,
+    );
+
+    let mut group = c.benchmark_group("searcher_acquisition");
+    group.bench_function("reader_per_call", |b| {
+        b.iter(|| {
+            let searcher = index.reader().unwrap().searcher();
+            black_box(searcher.search(&term_query, &Count).unwrap())
+        })
+    });
+
+    let reader: IndexReader = index
+        .reader_builder()
+        .reload_policy(ReloadPolicy::Manual)
+        .try_into()
+        .unwrap();
+    group.bench_function("cached_reader", |b| {
+        b.iter(|| {
+            let searcher = reader.searcher();
+            black_box(searcher.search(&term_query, &Count).unwrap())
+        })
+    });
+
+    let column_name = CString::new("text").unwrap();
+    let term = CString::new("group7").unwrap();
+    group.bench_function("capi_term_bitmap", |b| {
+        b.iter(|| {
+            let mut buffer = ByteBuffer::empty();
+            assert_eq!(
+                tantivy_search_index_query_term_bitmap(
+                    handle,
+                    column_name.as_ptr(),
+                    term.as_ptr(),
+                    &mut buffer
+                ),
+                ErrorCode::Ok
+            );
+            tantivy_search_byte_buffer_free(black_box(buffer));
+        })
+    });
+    group.finish();
+
+    assert_eq!(tantivy_search_index_close(handle), ErrorCode::Ok);
+}
+
+criterion_group!(benches, bench_searcher_acquisition);
+criterion_main!(benches);
@@ -17,4 +17,12 @@ fn main() {
     build.compile("tantivy_search");
     println!("cargo:rerun-if-changed=src/lib.rs");
     println!("cargo:rerun-if-changed=.cargo/config.toml");
+
+    // gRPC sidecar service stubs.
+    #[cfg(feature = "grpc-server")]
+    {
+        tonic_build::compile_protos("proto/tantivy_search.proto")
+            .expect("Failed to compile proto/tantivy_search.proto");
+        println!("cargo:rerun-if-changed=proto/tantivy_search.proto");
+    }
 }
@@ -7,10 +7,326 @@
 #include <stdint.h>
 #include <stdlib.h>
 
+/// Error codes returned by plain C API, detail can be fetched by `tantivy_search_last_error`.
+enum class TantivySearchErrorCode : int32_t {
+    TANTIVY_SEARCH_ERROR_CODE_OK = 0,
+    TANTIVY_SEARCH_ERROR_CODE_INVALID_ARGUMENT = 1,
+    TANTIVY_SEARCH_ERROR_CODE_INDEX_NOT_EXISTS = 2,
+    TANTIVY_SEARCH_ERROR_CODE_BACKPRESSURE = 3,
+    TANTIVY_SEARCH_ERROR_CODE_INTERNAL_ERROR = 4,
+    TANTIVY_SEARCH_ERROR_CODE_OPEN_FILES_BUDGET_EXCEEDED = 5,
+    TANTIVY_SEARCH_ERROR_CODE_OUT_OF_DISK_SPACE = 6,
+    TANTIVY_SEARCH_ERROR_CODE_INCOMPATIBLE_INDEX_VERSION = 7,
+    TANTIVY_SEARCH_ERROR_CODE_QUERY_TOO_EXPENSIVE = 8,
+    TANTIVY_SEARCH_ERROR_CODE_BUFFER_TOO_SMALL = 9,
+};
+
+/// Opaque handle of an index directory, created by `tantivy_search_index_open`.
+struct TantivySearchIndexHandle;
+
+using TantivySearchCommitCallback = void(*)(const char*, uint64_t, uint64_t, uint64_t);
+
+using TantivySearchMergeCallback = void(*)(const char*, int32_t, uint64_t, uint64_t);
+
+using TantivySearchDiskSpaceCallback = void(*)(const char*, uint64_t, uint64_t);
+
+using TantivySearchQueryAuditCallback = void(*)(uint64_t, const char*, const char*, const char*, uint64_t, uint64_t);
+
+/// Mergeable segment passed to host merge policy callback.
+/// `segment_id` is the uuid hex string of segment, it's only valid during the callback.
+struct TantivySearchMergeSegmentInfo {
+    const char *segment_id;
+    uint64_t num_docs;
+    uint64_t num_deleted_docs;
+    uint64_t bytes;
+};
+
+using TantivySearchMergePolicyCallback = void(*)(const char*, const TantivySearchMergeSegmentInfo*, size_t, int64_t*);
+
+using TantivySearchBuildProgressCallback = void(*)(const char*, uint64_t, uint64_t, uint64_t, int64_t);
+
+using TantivySearchTokenSink = void(*)(void*, size_t, size_t);
+
+using TantivySearchTokenizeCallback = int32_t(*)(void*, const char*, size_t, void*, TantivySearchTokenSink);
+
+using TantivySearchReindexFetchCallback = int32_t(*)(void*, uint64_t, const char*const *, size_t, const char**);
+
 using TantivySearchLogCallback = void(*)(int32_t, const char*, const char*);
 
+/// Byte buffer allocated by this library, must be released by `tantivy_search_byte_buffer_free`.
+struct TantivySearchByteBuffer {
+    uint8_t *data;
+    size_t len;
+    size_t capacity;
+};
+
 extern "C" {
 
+/// Creates a handle of index directory, it doesn't load writer or reader.
+/// The handle must be released by `tantivy_search_index_close`.
+TantivySearchErrorCode tantivy_search_index_open(const char *index_path,
+                                                 TantivySearchIndexHandle **out_handle);
+
+/// Frees index writer and reader of this handle, and releases the handle itself.
+/// The handle is always released, even when an error is returned, it must not be closed again.
+TantivySearchErrorCode tantivy_search_index_close(TantivySearchIndexHandle *handle);
+
+/// Creates (or recreates) index with given columns, `index_json_parameter` can be nullptr.
+TantivySearchErrorCode tantivy_search_index_create(const TantivySearchIndexHandle *handle,
+                                                   const char *const *column_names,
+                                                   size_t column_count,
+                                                   const char *index_json_parameter);
+
+/// Loads index writer of an existing index.
+TantivySearchErrorCode tantivy_search_index_load_writer(const TantivySearchIndexHandle *handle);
+
+/// Adds a document, `column_names` and `column_docs` must have the same `column_count`.
+TantivySearchErrorCode tantivy_search_index_add_document(const TantivySearchIndexHandle *handle,
+                                                         uint64_t row_id,
+                                                         const char *const *column_names,
+                                                         const char *const *column_docs,
+                                                         size_t column_count);
+
+/// Deletes documents by row ids, deletion is committed immediately.
+TantivySearchErrorCode tantivy_search_index_delete_row_ids(const TantivySearchIndexHandle *handle,
+                                                           const uint64_t *row_ids,
+                                                           size_t row_id_count);
+
+/// Commits index writer, loaded reader will be reloaded.
+TantivySearchErrorCode tantivy_search_index_commit(const TantivySearchIndexHandle *handle);
+
+/// Updates runtime settings of loaded writer and reader without reopening index.
+/// `settings_json` is like `{"slow_query_threshold_ms": 200, "auto_commit_interval_secs": 5}`.
+TantivySearchErrorCode tantivy_search_index_update_settings(const TantivySearchIndexHandle *handle,
+                                                            const char *settings_json);
+
+/// Re-indexes the index in place with `index_json_parameter` as its new tokenizer settings,
+/// e.g. after users change analyzers of a table. Alive rows are indexed in row_id order into a
+/// sibling directory which is then swapped with the index, loaded writer and reader are reloaded.
+/// Rows are read from stored columns, or fetched from source by `fetch_callback` when it's not
+/// nullptr: it's invoked with (user_data, row_id, column_names, column_count, out_texts), host
+/// points `out_texts[i]` at nul-terminated text of column i, valid until the callback is invoked
+/// again, and returns 0.
+/// A positive status skips the row, a negative one fails re-indexing and keeps the old index.
+/// Rows indexed are written to `out_rows` if it's not nullptr.
+TantivySearchErrorCode tantivy_search_index_reindex(const TantivySearchIndexHandle *handle,
+                                                    const char *index_json_parameter,
+                                                    TantivySearchReindexFetchCallback fetch_callback,
+                                                    void *user_data,
+                                                    uint64_t *out_rows);
+
+/// Rewrites segments of an index written by an older version in current format and records
+/// current versions, a writer is loaded meanwhile if none is. Whether the index was upgraded is
+/// written to `out_upgraded` if it's not nullptr, false means it's up to date.
+/// Fails with `INCOMPATIBLE_INDEX_VERSION` if the index was written by a newer version.
+TantivySearchErrorCode tantivy_search_index_upgrade(const TantivySearchIndexHandle *handle,
+                                                    bool *out_upgraded);
+
+/// Lists every index held in writer and reader caches.
+/// Indexes are written to `out_buffer` as utf-8 json: `[{"index_path":"/data/a","num_docs":100, ...}, ...]`.
+TantivySearchErrorCode tantivy_search_list_indexes(TantivySearchByteBuffer *out_buffer);
+
+/// Shuts the library down on clean exit of host: background janitor is stopped, scheduled
+/// commits are waited for `timeout_millis` (0 waits forever), then all index writers and readers
+/// are freed and caches are cleared. `policy` is `commit` or `abort`, it decides whether pending
+/// writes are committed or discarded, nullptr commits them. Number of writers and readers freed
+/// is written to `out_freed` if it's not nullptr. Writers failed to commit are kept loaded, so
+/// shutdown can be retried with `abort`. Index handles stay valid and must still be closed.
+TantivySearchErrorCode tantivy_search_shutdown(const char *policy,
+                                               uint64_t timeout_millis,
+                                               uint64_t *out_freed);
+
+/// Reports memory held by the allocator of this library, `jemalloc` or `mimalloc` if it's built
+/// with the feature of the same name, counters an allocator doesn't report are 0.
+/// Stats are written to `out_buffer` as utf-8 json: `{"allocator":"jemalloc","allocated_bytes":1024,
+/// "active_bytes":4096,"resident_bytes":8192,"retained_bytes":0}`.
+TantivySearchErrorCode tantivy_search_allocator_stats(TantivySearchByteBuffer *out_buffer);
+
+/// Pins internal threads to `cpus`, `cpu_count` 0 unpins them. Threads are named
+/// `tantivy-search-*`, `tantivy-ingest-*` and `tantivy-merge-*`, pinning is supported on
+/// linux only.
+TantivySearchErrorCode tantivy_search_set_thread_cpu_set(const uint32_t *cpus, size_t cpu_count);
+
+/// Returns memory retained by the allocator of this library but not in use to the OS,
+/// e.g. after large merges.
+TantivySearchErrorCode tantivy_search_purge_allocator();
+
+/// Installs or replaces the commit callback, it's invoked after every successful commit
+/// with (index_path, opstamp, num_docs, segment_count), `num_docs` and `segment_count`
+/// describe committed searchable segments. The callback runs on the committing thread,
+/// auto commit threads included, so it should return quickly.
+TantivySearchErrorCode tantivy_search_set_commit_callback(TantivySearchCommitCallback callback);
+
+/// Removes the commit callback.
+TantivySearchErrorCode tantivy_search_remove_commit_callback();
+
+/// Installs or replaces the merge callback, it's invoked with
+/// (index_path, event, segment_count, num_docs), `event` is 0 for start, 1 for finish, 2 for failure.
+/// Background merges of uncommitted segments only report start, tantivy doesn't report
+/// their completion. The callback runs on merging or committing thread, it should return quickly.
+TantivySearchErrorCode tantivy_search_set_merge_callback(TantivySearchMergeCallback callback);
+
+/// Removes the merge callback.
+TantivySearchErrorCode tantivy_search_remove_merge_callback();
+
+/// Installs or replaces the disk space callback, it's invoked with
+/// (index_path, required_bytes, available_bytes) when a commit or merge is refused because its
+/// estimated footprint doesn't fit in free disk space. Refused explicit commits fail with
+/// `OutOfDiskSpace`, refused background merges are retried on next segment update.
+TantivySearchErrorCode tantivy_search_set_disk_space_callback(TantivySearchDiskSpaceCallback callback);
+
+/// Removes the disk space callback.
+TantivySearchErrorCode tantivy_search_remove_disk_space_callback();
+
+/// Installs or replaces the merge policy callback, background merges of all index writers are
+/// decided by host instead of builtin log merge policy, deterministic builds never merge in background.
+/// The callback is invoked with (index_path, segments, segment_count, merge_groups), host writes
+/// a group into `merge_groups[i]` for `segments[i]`, segments sharing a non-negative group are
+/// merged together, -1 (the initial value) keeps the segment. Merges are still throttled.
+TantivySearchErrorCode tantivy_search_set_merge_policy_callback(TantivySearchMergePolicyCallback callback);
+
+/// Removes the merge policy callback, builtin merge policy takes effect again.
+TantivySearchErrorCode tantivy_search_remove_merge_policy_callback();
+
+/// Registers or replaces a tokenizer implemented by host, columns configured with
+/// `{"type": "custom", "name": name}` use it. The callback is invoked with
+/// (user_data, text, text_len, sink, emit), host calls `emit(sink, offset_from, offset_to)` with
+/// byte offsets of each token in order and returns 0, a non-zero status skips the text.
+/// It may run on any indexing or search thread, `user_data` must stay valid until unregistered.
+/// Register it before indexes using it are created or loaded.
+TantivySearchErrorCode tantivy_search_register_tokenizer(const char *name,
+                                                         TantivySearchTokenizeCallback callback,
+                                                         void *user_data);
+
+/// Unregisters a host tokenizer, indexes already loaded keep using it.
+TantivySearchErrorCode tantivy_search_unregister_tokenizer(const char *name);
+
+/// Installs or replaces the build progress callback, it's invoked with
+/// (index_path, docs_indexed, total_docs, bytes_indexed, eta_millis) while build progress is
+/// tracked, see `ffi_start_build_progress`. `eta_millis` is -1 if it can't be estimated.
+/// The callback runs on the indexing thread, it should return quickly.
+TantivySearchErrorCode tantivy_search_set_build_progress_callback(TantivySearchBuildProgressCallback callback);
+
+/// Removes the build progress callback, progress can still be queried by `ffi_get_build_progress`.
+TantivySearchErrorCode tantivy_search_remove_build_progress_callback();
+
+/// Loads (or reloads) index reader, it's required before searching.
+TantivySearchErrorCode tantivy_search_index_load_reader(const TantivySearchIndexHandle *handle);
+
+/// Frees index reader of this handle, the handle itself is still valid.
+TantivySearchErrorCode tantivy_search_index_free_reader(const TantivySearchIndexHandle *handle);
+
+/// Loads readers of all indexes under `root_dir` after a restart, so first queries don't pay
+/// for loading them. Files of indexes committed within `hot_within_secs` are read to warm page
+/// cache, 0 warms nothing. `num_threads` 0 loads on available parallelism. Indexes failing
+/// validation are skipped, summary is written to `out_buffer` as utf-8 json:
+/// `{"discovered":3,"loaded":["/data/a", ...],"warmed":[...],"warmed_bytes":1024,"failed":[...]}`.
+TantivySearchErrorCode tantivy_search_bootstrap(const char *root_dir,
+                                                uint64_t hot_within_secs,
+                                                size_t num_threads,
+                                                TantivySearchByteBuffer *out_buffer);
+
+/// Executes BM25 search over all columns.
+/// Results are written to `out_buffer` as utf-8 json: `[{"row_id":1,"score":0.5}, ...]`.
+TantivySearchErrorCode tantivy_search_index_search_bm25(const TantivySearchIndexHandle *handle,
+                                                        const char *query,
+                                                        uint32_t topk,
+                                                        TantivySearchByteBuffer *out_buffer);
+
+/// Executes BM25 search over all columns, results are written to host allocated arrays,
+/// so hot query paths don't allocate and free buffers across the boundary.
+/// `out_row_ids` and `out_scores` hold `capacity` values, `out_scores` can be nullptr if scores
+/// aren't needed. `out_count` receives the number of results, ranked best first. If they exceed
+/// `capacity`, nothing is written and `BUFFER_TOO_SMALL` is returned, a capacity of `topk`
+/// always fits.
+TantivySearchErrorCode tantivy_search_index_search_bm25_into(const TantivySearchIndexHandle *handle,
+                                                             const char *query,
+                                                             uint32_t topk,
+                                                             uint64_t *out_row_ids,
+                                                             float *out_scores,
+                                                             size_t capacity,
+                                                             size_t *out_count);
+
+/// Executes term query on a column.
+/// Results are written to `out_buffer` as u8 bitmap, bit `n` is set when row `n` matches.
+TantivySearchErrorCode tantivy_search_index_query_term_bitmap(const TantivySearchIndexHandle *handle,
+                                                              const char *column_name,
+                                                              const char *term,
+                                                              TantivySearchByteBuffer *out_buffer);
+
+/// Executes regex query on a column, `pattern` is a LIKE pattern, e.g. `%ancient%`.
+/// Results are written to `out_buffer` as u8 bitmap, bit `n` is set when row `n` matches.
+TantivySearchErrorCode tantivy_search_index_regex_term_bitmap(const TantivySearchIndexHandle *handle,
+                                                              const char *column_name,
+                                                              const char *pattern,
+                                                              TantivySearchByteBuffer *out_buffer);
+
+/// Executes term query on a column, the u8 bitmap of results is written to host allocated
+/// `out_bitmap` of `capacity` bytes, bit `n` is set when row `n` matches.
+/// `out_len` receives the bitmap length, if it exceeds `capacity` nothing is written and
+/// `BUFFER_TOO_SMALL` is returned.
+TantivySearchErrorCode tantivy_search_index_query_term_bitmap_into(const TantivySearchIndexHandle *handle,
+                                                                   const char *column_name,
+                                                                   const char *term,
+                                                                   uint8_t *out_bitmap,
+                                                                   size_t capacity,
+                                                                   size_t *out_len);
+
+/// Executes regex query on a column, `pattern` is a LIKE pattern, e.g. `%ancient%`.
+/// Same as `tantivy_search_index_query_term_bitmap_into`, the bitmap is written to host
+/// allocated `out_bitmap`.
+TantivySearchErrorCode tantivy_search_index_regex_term_bitmap_into(const TantivySearchIndexHandle *handle,
+                                                                   const char *column_name,
+                                                                   const char *pattern,
+                                                                   uint8_t *out_bitmap,
+                                                                   size_t capacity,
+                                                                   size_t *out_len);
+
+/// Runs analyzer of a field on `text`, `index_path_or_config` is an index directory or index json parameter.
+/// Tokens are written to `out_buffer` as utf-8 json:
+/// `[{"token":"hello","position":0,"offset_from":0,"offset_to":5}, ...]`.
+TantivySearchErrorCode tantivy_search_analyze(const char *index_path_or_config,
+                                              const char *field,
+                                              const char *text,
+                                              TantivySearchByteBuffer *out_buffer);
+
+/// Normalizes a raw query value the same way a field is indexed,
+/// the normalized term is written to `out_buffer` as utf-8 bytes.
+TantivySearchErrorCode tantivy_search_normalize_term(const char *index_path_or_config,
+                                                     const char *field,
+                                                     const char *value,
+                                                     TantivySearchByteBuffer *out_buffer);
+
+/// Installs or replaces the query audit callback, it's invoked with
+/// (timestamp_millis, index_path, principal, query, hits, latency_micros) for each search
+/// sampled by `tantivy_search_configure_query_audit`, `principal` is empty if none is bound.
+/// The callback runs on searching thread, it should return quickly.
+TantivySearchErrorCode tantivy_search_set_query_audit_callback(TantivySearchQueryAuditCallback callback);
+
+/// Removes the query audit callback.
+TantivySearchErrorCode tantivy_search_remove_query_audit_callback();
+
+/// Records `sample_rate` in [0, 1] of searches of all indexes into query audit log, rate 0
+/// disables auditing. Records are passed to the query audit callback and appended to
+/// `file_path` as json lines, `file_path` can be nullptr to keep no file.
+TantivySearchErrorCode tantivy_search_configure_query_audit(const char *file_path,
+                                                            double sample_rate);
+
+/// Limits cost of queries of all indexes, 0 means unlimited. A query over any limit isn't
+/// executed, it fails with `QUERY_TOO_EXPENSIVE`. Limits are terms of a term set or parsed
+/// query, automaton states of a LIKE/regex pattern, terms a LIKE/regex pattern expands to in a
+/// segment, and row ids of a bitmap query result.
+TantivySearchErrorCode tantivy_search_set_query_cost_limits(uint64_t max_clause_count,
+                                                            uint64_t max_regex_states,
+                                                            uint64_t max_expanded_terms,
+                                                            uint64_t max_bitmap_size);
+
+/// Returns the last error message of current thread, it's valid until next C API call on the same thread.
+const char *tantivy_search_last_error();
+
+/// Releases a byte buffer returned by this library.
+void tantivy_search_byte_buffer_free(TantivySearchByteBuffer buffer);
+
 bool tantivy_search_log4rs_initialize(const char *log_directory,
                                       const char *log_level,
                                       bool log_in_file,
@@ -27,6 +343,7 @@ bool tantivy_search_log4rs_initialize(const char *log_directory,
 /// - `console_dispaly`: Enables logging to the console if set to true.
 /// - `only_tantivy_search`: Only record `target=tantivy_search` log content.
 /// - `callback`: A callback function, typically provided by ClickHouse.
+///   It replaces the previous callback, so this function can be called again to reload config.
 ///
 /// Returns:
 /// - `true` if the logger is successfully initialized, `false` otherwise.
@@ -37,6 +354,34 @@ bool tantivy_search_log4rs_initialize_with_callback(const char *log_directory,
                                                     bool only_record_tantivy_search,
                                                     TantivySearchLogCallback callback);
 
+/// Installs or replaces the log callback without touching logger config.
+///
+/// Returns:
+/// - `true` if the callback is installed, `false` otherwise.
+bool tantivy_search_update_log_callback(TantivySearchLogCallback callback);
+
+/// Removes the log callback, logs are still recorded by log4rs.
+/// If a fallback file logger is configured, it takes effect after removal.
+///
+/// Returns:
+/// - `true` if the callback is removed, `false` otherwise.
+bool tantivy_search_remove_log_callback();
+
+/// Initializes the built-in file logger, which is used when no log callback is registered.
+///
+/// Arguments:
+/// - `log_file_path`: The log file path, rotated files are named `{log_file_path}.{index}`.
+/// - `log_level`: The logging level to use. Supported levels: info, debug, trace, error, warning.
+/// - `max_file_size`: Rotate log file after it exceeds this size in bytes.
+/// - `rotation_count`: The number of rotated log files to keep.
+///
+/// Returns:
+/// - `true` if the config is valid and saved, `false` otherwise.
+bool tantivy_search_fallback_logger_initialize(const char *log_file_path,
+                                               const char *log_level,
+                                               uint64_t max_file_size,
+                                               uint32_t rotation_count);
+
 } // extern "C"
 
 #endif // TANTIVY_SEARCH_H
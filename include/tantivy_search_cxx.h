@@ -714,8 +714,39 @@ struct RowIdWithScore;
 struct BM25Result;
 struct BoolResult;
 struct StringResult;
+struct U64Result;
 struct DocWithFreq;
 struct FieldTokenNums;
+struct AnalyzedToken;
+struct AnalyzedTokensResult;
+struct IndexHealth;
+struct IndexHealthResult;
+struct AllocatorStats;
+struct AllocatorStatsResult;
+struct SubsystemLiveness;
+struct LivenessResult;
+struct IndexSizeEstimate;
+struct IndexSizeEstimateResult;
+struct BuildProgress;
+struct BuildProgressResult;
+struct SegmentDeleteStat;
+struct SegmentDeleteStatsResult;
+struct GroupTopDoc;
+struct GroupTopDocsResult;
+struct MatchedOffset;
+struct MatchedOffsetsResult;
+struct MatchedTerm;
+struct MatchedTermsTopDoc;
+struct MatchedTermsTopDocsResult;
+struct TopDocsWithTotalHits;
+struct TopDocsWithTotalHitsResult;
+struct StoredDoc;
+struct StoredDocsResult;
+struct QueryIdsResult;
+struct CardinalityResult;
+struct PercentilesResult;
+struct RowIdRange;
+struct AdaptiveBitmapResult;
 struct Statistics;
 
 #ifndef CXXBRIDGE1_STRUCT_RowIdWithScore
@@ -764,6 +795,17 @@ struct StringResult final {
 };
 #endif // CXXBRIDGE1_STRUCT_StringResult
 
+#ifndef CXXBRIDGE1_STRUCT_U64Result
+#define CXXBRIDGE1_STRUCT_U64Result
+struct U64Result final {
+  ::std::uint64_t result;
+  ::std::int32_t error_code;
+  ::rust::String error_msg;
+
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_U64Result
+
 #ifndef CXXBRIDGE1_STRUCT_DocWithFreq
 #define CXXBRIDGE1_STRUCT_DocWithFreq
 struct DocWithFreq final {
@@ -789,6 +831,368 @@ struct FieldTokenNums final {
 };
 #endif // CXXBRIDGE1_STRUCT_FieldTokenNums
 
+#ifndef CXXBRIDGE1_STRUCT_AnalyzedToken
+#define CXXBRIDGE1_STRUCT_AnalyzedToken
+struct AnalyzedToken final {
+  ::rust::String token;
+  ::std::uint64_t position;
+  ::std::uint64_t offset_from;
+  ::std::uint64_t offset_to;
+
+  bool operator==(AnalyzedToken const &) const noexcept;
+  bool operator!=(AnalyzedToken const &) const noexcept;
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_AnalyzedToken
+
+#ifndef CXXBRIDGE1_STRUCT_AnalyzedTokensResult
+#define CXXBRIDGE1_STRUCT_AnalyzedTokensResult
+struct AnalyzedTokensResult final {
+  ::rust::Vec<::AnalyzedToken> result;
+  ::std::int32_t error_code;
+  ::rust::String error_msg;
+
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_AnalyzedTokensResult
+
+#ifndef CXXBRIDGE1_STRUCT_IndexHealth
+#define CXXBRIDGE1_STRUCT_IndexHealth
+struct IndexHealth final {
+  ::rust::String canonical_path;
+  bool writer_alive;
+  bool writer_busy;
+  bool reader_loaded;
+  ::std::uint64_t reader_generation;
+  ::std::uint64_t last_commit_opstamp;
+  ::std::uint64_t lock_contention_count;
+  ::rust::String last_error;
+  ::std::uint64_t row_id_mapping_bytes;
+
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_IndexHealth
+
+#ifndef CXXBRIDGE1_STRUCT_IndexHealthResult
+#define CXXBRIDGE1_STRUCT_IndexHealthResult
+struct IndexHealthResult final {
+  ::IndexHealth result;
+  ::std::int32_t error_code;
+  ::rust::String error_msg;
+
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_IndexHealthResult
+
+#ifndef CXXBRIDGE1_STRUCT_AllocatorStats
+#define CXXBRIDGE1_STRUCT_AllocatorStats
+struct AllocatorStats final {
+  ::rust::String allocator;
+  ::std::uint64_t allocated_bytes;
+  ::std::uint64_t active_bytes;
+  ::std::uint64_t resident_bytes;
+  ::std::uint64_t retained_bytes;
+
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_AllocatorStats
+
+#ifndef CXXBRIDGE1_STRUCT_AllocatorStatsResult
+#define CXXBRIDGE1_STRUCT_AllocatorStatsResult
+struct AllocatorStatsResult final {
+  ::AllocatorStats result;
+  ::std::int32_t error_code;
+  ::rust::String error_msg;
+
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_AllocatorStatsResult
+
+#ifndef CXXBRIDGE1_STRUCT_SubsystemLiveness
+#define CXXBRIDGE1_STRUCT_SubsystemLiveness
+struct SubsystemLiveness final {
+  ::rust::String name;
+  bool running;
+  bool alive;
+  ::std::uint64_t last_heartbeat_millis;
+
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_SubsystemLiveness
+
+#ifndef CXXBRIDGE1_STRUCT_LivenessResult
+#define CXXBRIDGE1_STRUCT_LivenessResult
+struct LivenessResult final {
+  ::rust::Vec<::SubsystemLiveness> result;
+  ::std::int32_t error_code;
+  ::rust::String error_msg;
+
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_LivenessResult
+
+#ifndef CXXBRIDGE1_STRUCT_IndexSizeEstimate
+#define CXXBRIDGE1_STRUCT_IndexSizeEstimate
+struct IndexSizeEstimate final {
+  ::std::uint64_t sample_docs;
+  ::std::uint64_t sample_index_bytes;
+  ::std::uint64_t estimated_index_bytes;
+  ::std::uint64_t estimated_num_tokens;
+  ::std::uint64_t estimated_reader_memory_bytes;
+  ::std::uint64_t estimated_writer_memory_bytes;
+
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_IndexSizeEstimate
+
+#ifndef CXXBRIDGE1_STRUCT_IndexSizeEstimateResult
+#define CXXBRIDGE1_STRUCT_IndexSizeEstimateResult
+struct IndexSizeEstimateResult final {
+  ::IndexSizeEstimate result;
+  ::std::int32_t error_code;
+  ::rust::String error_msg;
+
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_IndexSizeEstimateResult
+
+#ifndef CXXBRIDGE1_STRUCT_BuildProgress
+#define CXXBRIDGE1_STRUCT_BuildProgress
+struct BuildProgress final {
+  ::std::uint64_t total_docs;
+  ::std::uint64_t docs_indexed;
+  ::std::uint64_t bytes_indexed;
+  ::std::uint64_t elapsed_millis;
+  double docs_per_sec;
+  ::std::int64_t eta_millis;
+
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_BuildProgress
+
+#ifndef CXXBRIDGE1_STRUCT_BuildProgressResult
+#define CXXBRIDGE1_STRUCT_BuildProgressResult
+struct BuildProgressResult final {
+  ::BuildProgress result;
+  ::std::int32_t error_code;
+  ::rust::String error_msg;
+
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_BuildProgressResult
+
+#ifndef CXXBRIDGE1_STRUCT_SegmentDeleteStat
+#define CXXBRIDGE1_STRUCT_SegmentDeleteStat
+struct SegmentDeleteStat final {
+  ::rust::String segment_id;
+  ::std::uint32_t max_doc;
+  ::std::uint32_t num_deleted_docs;
+  float deleted_ratio;
+
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_SegmentDeleteStat
+
+#ifndef CXXBRIDGE1_STRUCT_SegmentDeleteStatsResult
+#define CXXBRIDGE1_STRUCT_SegmentDeleteStatsResult
+struct SegmentDeleteStatsResult final {
+  ::rust::Vec<::SegmentDeleteStat> result;
+  ::std::int32_t error_code;
+  ::rust::String error_msg;
+
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_SegmentDeleteStatsResult
+
+#ifndef CXXBRIDGE1_STRUCT_GroupTopDoc
+#define CXXBRIDGE1_STRUCT_GroupTopDoc
+struct GroupTopDoc final {
+  ::std::uint64_t group_key;
+  ::std::uint64_t hit_count;
+  ::RowIdWithScore top_doc;
+
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_GroupTopDoc
+
+#ifndef CXXBRIDGE1_STRUCT_GroupTopDocsResult
+#define CXXBRIDGE1_STRUCT_GroupTopDocsResult
+struct GroupTopDocsResult final {
+  ::rust::Vec<::GroupTopDoc> result;
+  ::std::int32_t error_code;
+  ::rust::String error_msg;
+
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_GroupTopDocsResult
+
+#ifndef CXXBRIDGE1_STRUCT_MatchedOffset
+#define CXXBRIDGE1_STRUCT_MatchedOffset
+struct MatchedOffset final {
+  ::rust::String field;
+  ::rust::String term;
+  ::std::uint64_t start;
+  ::std::uint64_t end;
+
+  bool operator==(MatchedOffset const &) const noexcept;
+  bool operator!=(MatchedOffset const &) const noexcept;
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_MatchedOffset
+
+#ifndef CXXBRIDGE1_STRUCT_MatchedOffsetsResult
+#define CXXBRIDGE1_STRUCT_MatchedOffsetsResult
+struct MatchedOffsetsResult final {
+  ::rust::Vec<::MatchedOffset> result;
+  ::std::int32_t error_code;
+  ::rust::String error_msg;
+
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_MatchedOffsetsResult
+
+#ifndef CXXBRIDGE1_STRUCT_MatchedTerm
+#define CXXBRIDGE1_STRUCT_MatchedTerm
+struct MatchedTerm final {
+  ::rust::String field;
+  ::rust::String term;
+
+  bool operator==(MatchedTerm const &) const noexcept;
+  bool operator!=(MatchedTerm const &) const noexcept;
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_MatchedTerm
+
+#ifndef CXXBRIDGE1_STRUCT_MatchedTermsTopDoc
+#define CXXBRIDGE1_STRUCT_MatchedTermsTopDoc
+struct MatchedTermsTopDoc final {
+  ::RowIdWithScore top_doc;
+  ::rust::Vec<::MatchedTerm> matched_terms;
+
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_MatchedTermsTopDoc
+
+#ifndef CXXBRIDGE1_STRUCT_MatchedTermsTopDocsResult
+#define CXXBRIDGE1_STRUCT_MatchedTermsTopDocsResult
+struct MatchedTermsTopDocsResult final {
+  ::rust::Vec<::MatchedTermsTopDoc> result;
+  ::std::int32_t error_code;
+  ::rust::String error_msg;
+
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_MatchedTermsTopDocsResult
+
+#ifndef CXXBRIDGE1_STRUCT_TopDocsWithTotalHits
+#define CXXBRIDGE1_STRUCT_TopDocsWithTotalHits
+struct TopDocsWithTotalHits final {
+  ::rust::Vec<::RowIdWithScore> top_docs;
+  ::std::uint64_t total_hits;
+  bool total_hits_exact;
+
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_TopDocsWithTotalHits
+
+#ifndef CXXBRIDGE1_STRUCT_TopDocsWithTotalHitsResult
+#define CXXBRIDGE1_STRUCT_TopDocsWithTotalHitsResult
+struct TopDocsWithTotalHitsResult final {
+  ::TopDocsWithTotalHits result;
+  ::std::int32_t error_code;
+  ::rust::String error_msg;
+
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_TopDocsWithTotalHitsResult
+
+#ifndef CXXBRIDGE1_STRUCT_StoredDoc
+#define CXXBRIDGE1_STRUCT_StoredDoc
+struct StoredDoc final {
+  ::std::uint64_t row_id;
+  ::rust::Vec<::rust::String> values;
+
+  bool operator==(StoredDoc const &) const noexcept;
+  bool operator!=(StoredDoc const &) const noexcept;
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_StoredDoc
+
+#ifndef CXXBRIDGE1_STRUCT_StoredDocsResult
+#define CXXBRIDGE1_STRUCT_StoredDocsResult
+struct StoredDocsResult final {
+  ::rust::Vec<::StoredDoc> result;
+  ::std::int32_t error_code;
+  ::rust::String error_msg;
+
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_StoredDocsResult
+
+#ifndef CXXBRIDGE1_STRUCT_QueryIdsResult
+#define CXXBRIDGE1_STRUCT_QueryIdsResult
+struct QueryIdsResult final {
+  ::rust::Vec<::std::uint64_t> result;
+  ::std::int32_t error_code;
+  ::rust::String error_msg;
+
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_QueryIdsResult
+
+#ifndef CXXBRIDGE1_STRUCT_CardinalityResult
+#define CXXBRIDGE1_STRUCT_CardinalityResult
+struct CardinalityResult final {
+  ::std::uint64_t estimate;
+  ::rust::Vec<::std::uint8_t> sketch;
+  ::std::int32_t error_code;
+  ::rust::String error_msg;
+
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_CardinalityResult
+
+#ifndef CXXBRIDGE1_STRUCT_PercentilesResult
+#define CXXBRIDGE1_STRUCT_PercentilesResult
+struct PercentilesResult final {
+  ::rust::Vec<double> values;
+  ::rust::Vec<::std::uint8_t> digest;
+  ::std::int32_t error_code;
+  ::rust::String error_msg;
+
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_PercentilesResult
+
+#ifndef CXXBRIDGE1_STRUCT_RowIdRange
+#define CXXBRIDGE1_STRUCT_RowIdRange
+struct RowIdRange final {
+  ::std::uint64_t start;
+  ::std::uint64_t end;
+
+  bool operator==(RowIdRange const &) const noexcept;
+  bool operator!=(RowIdRange const &) const noexcept;
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_RowIdRange
+
+#ifndef CXXBRIDGE1_STRUCT_AdaptiveBitmapResult
+#define CXXBRIDGE1_STRUCT_AdaptiveBitmapResult
+struct AdaptiveBitmapResult final {
+  ::rust::String strategy;
+  ::std::uint64_t count;
+  ::rust::Vec<::std::uint8_t> row_ids;
+  ::std::uint64_t range_start;
+  ::std::uint64_t range_end;
+  ::rust::Vec<::std::uint32_t> excluded_row_ids;
+  ::rust::Vec<::RowIdRange> ranges;
+  ::std::int32_t error_code;
+  ::rust::String error_msg;
+
+  using IsRelocatable = ::std::true_type;
+};
+#endif // CXXBRIDGE1_STRUCT_AdaptiveBitmapResult
+
 #ifndef CXXBRIDGE1_STRUCT_Statistics
 #define CXXBRIDGE1_STRUCT_Statistics
 struct Statistics final {
@@ -802,6 +1206,28 @@ struct Statistics final {
 
 ::BoolResult ffi_varify_index_parameter(::std::string const &index_json_parameter) noexcept;
 
+// Set how invalid utf-8 in ingested text and query strings is handled.
+// arguments:
+// - `policy`: `reject` (default), `lossy` replaces invalid sequences with U+FFFD,
+//   `skip_row` skips ingested rows with `result` false and `error_code` 0.
+::BoolResult ffi_set_utf8_policy(::std::string const &policy) noexcept;
+
+// Record tracing spans into a chrome-trace or folded-stack file for a window of time.
+// Requires `tracing-spans` feature, otherwise an error is returned.
+// arguments:
+// - `output_path`: trace output file path.
+// - `format`: `chrome` or `folded`.
+// - `duration_secs`: profiling window in seconds.
+::BoolResult ffi_start_trace_profiling(::std::string const &output_path, ::std::string const &format, ::std::uint64_t duration_secs) noexcept;
+
+// Stop trace profiling window in advance and flush output file.
+// Returns false if no profiling window is running.
+::BoolResult ffi_stop_trace_profiling() noexcept;
+
+// Render internal metrics (cache sizes, query latencies, ingest rates, merge activity)
+// in prometheus text exposition format.
+::rust::String ffi_metrics_prometheus() noexcept;
+
 // Create tantivy index.
 // arguments:
 // - `index_path`: index directory.
@@ -809,12 +1235,120 @@ struct Statistics final {
 // - `index_json_parameter`: config index with json.
 ::BoolResult ffi_create_index_with_parameter(::std::string const &index_path, ::std::vector<::std::string> const &column_names, ::std::string const &index_json_parameter) noexcept;
 
+// Create tantivy index with build mode.
+// arguments:
+// - `index_path`: index directory.
+// - `column_names`: which columns will be used to build index.
+// - `index_json_parameter`: config index with json.
+// - `deterministic_build`: build byte-comparable index for the same input,
+//   segment ids are derived when index writer is freed.
+::BoolResult ffi_create_index_with_build_mode(::std::string const &index_path, ::std::vector<::std::string> const &column_names, ::std::string const &index_json_parameter, bool deterministic_build) noexcept;
+
 // Create tantivy index by default.
 // argements:
 // - `index_path`: index directory.
 // - `column_names`: which columns will be used to build index.
 ::BoolResult ffi_create_index(::std::string const &index_path, ::std::vector<::std::string> const &column_names) noexcept;
 
+// Estimate index size and memory requirements without a full build.
+// A sample is indexed in RAM with the given config and projected to `total_docs`.
+// arguments:
+// - `column_names`: which columns will be used to build index.
+// - `index_json_parameter`: config index with json.
+// - `sample_docs`: sample documents row by row, each row has `column_names.size()` values.
+// - `total_docs`: number of documents the index will be built with.
+::IndexSizeEstimateResult ffi_estimate_index_size(::std::vector<::std::string> const &column_names, ::std::string const &index_json_parameter, ::std::vector<::std::string> const &sample_docs, ::std::uint64_t total_docs) noexcept;
+
+// Start tracking bulk build progress of a loaded index writer, previous progress is discarded.
+// Docs added after it are counted, ETA is estimated by average throughput.
+// arguments:
+// - `index_path`: index directory.
+// - `total_docs`: docs the build will add, 0 means unknown and ETA is -1.
+// - `report_interval_millis`: interval of reporting to build progress callback, 0 disables reports.
+::BoolResult ffi_start_build_progress(::std::string const &index_path, ::std::uint64_t total_docs, ::std::uint64_t report_interval_millis) noexcept;
+
+// Get bulk build progress, it should be started by `ffi_start_build_progress`.
+// arguments:
+// - `index_path`: index directory.
+::BuildProgressResult ffi_get_build_progress(::std::string const &index_path) noexcept;
+
+// Stop tracking bulk build progress, `false` if it's not started.
+// arguments:
+// - `index_path`: index directory.
+::BoolResult ffi_stop_build_progress(::std::string const &index_path) noexcept;
+
+// Get the row_id an interrupted bulk build should resume from.
+// Every commit records max row_id added before it as build checkpoint, rows are expected
+// to be added in row_id order, so rows before the returned row_id are durable.
+// It's read from committed index files, index writer doesn't need to be loaded.
+// arguments:
+// - `index_path`: index directory.
+::U64Result ffi_get_build_checkpoint(::std::string const &index_path) noexcept;
+
+// Create a multi-part build, it indexes several independent indexes concurrently on one
+// thread pool. Writers of all parts share the memory budget, each claims an equal slice.
+// Existing indexes in `index_paths` are recreated. Return the build id.
+// arguments:
+// - `index_paths`: index directory of each part.
+// - `column_names`: columns of all parts.
+// - `index_json_parameter`: tokenizer parameter of all parts.
+// - `memory_budget_bytes`: memory shared by writers, each part requires at least 15MB.
+// - `num_threads`: threads of the build pool, 0 means available parallelism.
+::U64Result ffi_create_multi_part_build(::std::vector<::std::string> const &index_paths, ::std::vector<::std::string> const &column_names, ::std::string const &index_json_parameter, ::std::uint64_t memory_budget_bytes, ::std::uint64_t num_threads) noexcept;
+
+// Index a batch holding rows of several parts, parts are indexed in parallel and rows
+// of a part keep their order. Column strings are laid out same as `ffi_index_column_batch`.
+// arguments:
+// - `build_id`: multi-part build id.
+// - `part_ids`: position in `index_paths` of the part each row belongs to, align with row_ids.
+// - `row_ids`: row_id of each row.
+// - `column_data`: concatenated utf-8 strings of all columns.
+// - `column_offsets`: offsets of strings in `column_data`, `column_names.len() * row_ids.len() + 1` elements.
+::BoolResult ffi_multi_part_build_index_batch(::std::uint64_t build_id, ::rust::Slice<::std::uint64_t const> part_ids, ::rust::Slice<::std::uint64_t const> row_ids, ::rust::Slice<::std::uint8_t const> column_data, ::rust::Slice<::std::uint64_t const> column_offsets) noexcept;
+
+// Commit and free writers of all parts, the build id is invalid afterwards.
+// arguments:
+// - `build_id`: multi-part build id.
+::BoolResult ffi_finish_multi_part_build(::std::uint64_t build_id) noexcept;
+
+// Start a sorted build of a loaded index writer, for hosts feeding rows out of row_id order
+// e.g. from parallel readers. Rows added by `ffi_sorted_build_add_batch` are buffered, sorted
+// and spilled to disk beyond the memory limit, then indexed in row_id order by
+// `ffi_finish_sorted_build`, so sorted index guarantees hold. Rows of equal row_id keep the
+// order they were added in.
+// arguments:
+// - `index_path`: index directory.
+// - `column_names`: columns of added rows.
+// - `memory_limit_bytes`: bytes of rows buffered in memory before they are spilled.
+// - `spill_directory`: directory of spilled rows, empty means system temporary directory.
+::BoolResult ffi_start_sorted_build(::std::string const &index_path, ::std::vector<::std::string> const &column_names, ::std::uint64_t memory_limit_bytes, ::std::string const &spill_directory) noexcept;
+
+// Buffer rows of a sorted build, batches can be added concurrently in any row_id order.
+// Column strings are laid out same as `ffi_index_column_batch`.
+// arguments:
+// - `index_path`: index directory.
+// - `row_ids`: row_id of each row.
+// - `column_data`: concatenated utf-8 strings of all columns.
+// - `column_offsets`: offsets of strings in `column_data`, `column_names.len() * row_ids.len() + 1` elements.
+::BoolResult ffi_sorted_build_add_batch(::std::string const &index_path, ::rust::Slice<::std::uint64_t const> row_ids, ::rust::Slice<::std::uint8_t const> column_data, ::rust::Slice<::std::uint64_t const> column_offsets) noexcept;
+
+// Index rows of a sorted build in row_id order and commit them, return rows indexed.
+// The build is over afterwards even if it failed, spilled rows are removed.
+// `error_code` is 1 under writer backpressure and 2 when commit doesn't fit in disk space.
+// arguments:
+// - `index_path`: index directory.
+::U64Result ffi_finish_sorted_build(::std::string const &index_path) noexcept;
+
+// Re-index an index in place with new tokenizer settings, e.g. after users change analyzers
+// of a table. Alive rows are indexed from stored columns in row_id order into a sibling
+// directory which is then swapped with the index, loaded writer and reader are reloaded.
+// Columns not stored can be re-indexed by `tantivy_search_index_reindex` of C API, which
+// fetches them from source. The old index is kept if re-indexing fails. Return rows indexed.
+// arguments:
+// - `index_path`: index directory.
+// - `index_json_parameter`: new tokenizer parameter of the index.
+::U64Result ffi_reindex_index(::std::string const &index_path, ::std::string const &index_json_parameter) noexcept;
+
 // Index multi column docs with given rowId.
 // arguments:
 // - `index_path`: index directory.
@@ -823,6 +1357,50 @@ struct Statistics final {
 // - `column_docs`: align with column_names.
 ::BoolResult ffi_index_multi_column_docs(::std::string const &index_path, ::std::uint64_t row_id, ::std::vector<::std::string> const &column_names, ::std::vector<::std::string> const &column_docs) noexcept;
 
+// Index multi column docs borrowed from caller's buffer, avoid copying each doc
+// into intermediate strings.
+// arguments:
+// - `index_path`: index directory.
+// - `row_id`: row_id given by ClickHouse.
+// - `column_names`: align with column_lengths.
+// - `column_data`: utf-8 docs of all columns, stored consecutively.
+// - `column_lengths`: byte length of each column doc in `column_data`.
+::BoolResult ffi_index_multi_column_borrowed_docs(::std::string const &index_path, ::std::uint64_t row_id, ::std::vector<::std::string> const &column_names, ::rust::Slice<::std::uint8_t const> column_data, ::rust::Slice<::std::uint64_t const> column_lengths) noexcept;
+
+// Index a column batch at once, documents are built in parallel.
+// arguments:
+// - `index_path`: index directory.
+// - `row_ids`: row_ids of the batch.
+// - `column_names`: columns of the batch.
+// - `column_data`: utf-8 strings of all columns, column by column.
+// - `column_offsets`: `column_names.size() * row_ids.size() + 1` offsets in `column_data`,
+//   string of column `c` row `r` is `[offsets[c * rows + r], offsets[c * rows + r + 1])`.
+::BoolResult ffi_index_column_batch(::std::string const &index_path, ::rust::Slice<::std::uint64_t const> row_ids, ::std::vector<::std::string> const &column_names, ::rust::Slice<::std::uint8_t const> column_data, ::rust::Slice<::std::uint64_t const> column_offsets) noexcept;
+
+// Enable automatic row_id assignment, row_ids continue from max committed row_id.
+// Explicitly given row_ids aren't coordinated with assigned ones, don't mix them in an index.
+// Return the next row_id to be assigned.
+// arguments:
+// - `index_path`: index directory.
+::U64Result ffi_enable_auto_row_id(::std::string const &index_path) noexcept;
+
+// Index multi column docs with a row_id assigned by index writer, return the assigned row_id.
+// Row skipped by utf-8 policy doesn't consume a row_id, `error_code` is 0 and `error_msg` isn't empty.
+// arguments:
+// - `index_path`: index directory.
+// - `column_names`: align with column_docs.
+// - `column_docs`: align with column_names.
+::U64Result ffi_index_multi_column_docs_auto_row_id(::std::string const &index_path, ::std::vector<::std::string> const &column_names, ::std::vector<::std::string> const &column_docs) noexcept;
+
+// Index a column batch with consecutive row_ids assigned by index writer,
+// return the first assigned row_id, rows skipped by utf-8 policy still consume their row_ids.
+// arguments:
+// - `index_path`: index directory.
+// - `column_names`: columns of the batch.
+// - `column_data`: utf-8 strings of all columns, column by column.
+// - `column_offsets`: `column_names.size() * rows + 1` offsets in `column_data`.
+::U64Result ffi_index_column_batch_auto_row_id(::std::string const &index_path, ::std::vector<::std::string> const &column_names, ::rust::Slice<::std::uint8_t const> column_data, ::rust::Slice<::std::uint64_t const> column_offsets) noexcept;
+
 // Index multi column docs with given rowId.
 // arguments:
 // - `index_path`: index directory.
@@ -838,32 +1416,108 @@ struct Statistics final {
 ::BoolResult ffi_index_multi_type_column_docs(::std::string const &index_path, ::std::uint64_t row_id, ::std::vector<::std::string> const &text_column_names, ::std::vector<::std::string> const &text_column_docs, ::std::vector<::std::string> const &i64_column_names, ::std::vector<::std::int64_t> const &i64_column_docs, ::std::vector<::std::string> const &f64_column_names, ::std::vector<double> const &f64_column_docs, ::std::vector<::std::string> const &bytes_column_names, ::std::vector<::std::string> const &bytes_column_docs) noexcept;
 
 // Delete a group of rowIds.
+// Returns `error_code` 2 if the deletion commit doesn't fit in free disk space.
 // arguments:
 // - `index_path`: index directory.
 // - `row_ids`: a group of rowIds need be deleted.
 ::BoolResult ffi_delete_row_ids(::std::string const &index_path, ::std::vector<::std::uint64_t> const &row_ids) noexcept;
 
 // Commit index writer
+// Returns `error_code` 2 without writing anything if the commit doesn't fit in free disk space.
 // arguments:
 // - `index_path`: index directory.
 ::BoolResult ffi_index_writer_commit(::std::string const &index_path) noexcept;
 
+// Schedule a commit on background commit pool, return a ticket for `ffi_wait_commit`.
+// Host can overlap the fsync heavy commit with other work.
+// arguments:
+// - `index_path`: index directory.
+::U64Result ffi_commit_async(::std::string const &index_path) noexcept;
+
+// Wait a commit scheduled by `ffi_commit_async`, `false` if it's still running after timeout
+// and the ticket can be waited again. Once commit ended, its result is returned and ticket is released.
+// arguments:
+// - `ticket`: ticket returned by `ffi_commit_async`.
+// - `timeout_millis`: max time to wait, 0 waits until commit ended.
+::BoolResult ffi_wait_commit(::std::uint64_t ticket, ::std::uint64_t timeout_millis) noexcept;
+
+// Schedule a commit on background commit pool without waiting it, failure is only logged.
+// arguments:
+// - `index_path`: index directory.
+::BoolResult ffi_commit_nowait(::std::string const &index_path) noexcept;
+
 // Free index writer
 // arguments:
 // - `index_path`: index directory.
 ::BoolResult ffi_free_index_writer(::std::string const &index_path) noexcept;
 
 // Load index writer
+// Returns `error_code` 3 if the index was written by a newer incompatible version.
 // arguments:
 // - `index_path`: index directory.
 ::BoolResult ffi_load_index_writer(::std::string const &index_path) noexcept;
 
+// Load index writer, try to break a stale writer lock left by a crashed process.
+// arguments:
+// - `index_path`: index directory.
+// - `break_stale_lock`: whether break writer lock when it's owner process has died.
+::BoolResult ffi_load_index_writer_with_lock_recovery(::std::string const &index_path, bool break_stale_lock) noexcept;
+
+// Enable background auto commit for an index writer.
+// arguments:
+// - `index_path`: index directory.
+// - `interval_secs`: commit every `interval_secs` seconds, 0 means disable it.
+// - `max_docs`: commit every `max_docs` operations since last commit, 0 means disable it.
+::BoolResult ffi_enable_auto_commit(::std::string const &index_path, ::std::uint64_t interval_secs, ::std::uint64_t max_docs) noexcept;
+
+// Disable background auto commit for an index writer.
+// arguments:
+// - `index_path`: index directory.
+::BoolResult ffi_disable_auto_commit(::std::string const &index_path) noexcept;
+
+// Update runtime settings of a loaded index without reopening it.
+// Supported settings: `slow_query_threshold_ms` (0 disables slow query log, requires reader),
+// `auto_commit_interval_secs` (0 disables interval commit, requires writer),
+// `commit_durability` of committed files (`fsync` by default, `fdatasync` or `none`
+// for rebuilds which can be run again after a crash, requires writer),
+// `direct_io` writing new segment files with O_DIRECT to keep bulk builds out of page
+// cache (`false` by default, buffered writes where it's unsupported, requires writer),
+// `similarity` of ranked search (`bm25`, `tf_only` or `constant`, requires reader)
+// `bm25_k1`, `bm25_b` of `bm25` similarity (requires reader)
+// and `persistent_query_cache_mb` of bitmap query results persisted next to index
+// (0 disables it, requires reader).
+// arguments:
+// - `index_path`: index directory.
+// - `settings_json`: settings to update, e.g. `{"slow_query_threshold_ms": 200}`.
+::BoolResult ffi_update_index_settings(::std::string const &index_path, ::std::string const &settings_json) noexcept;
+
+// Configure index writer backpressure, zero disables the corresponding check.
+// When writer is under backpressure, `ffi_index_multi_column_docs` and
+// `ffi_index_multi_type_column_docs` return `error_code` 1 instead of -1.
+// arguments:
+// - `index_path`: index directory.
+// - `max_uncommitted_bytes`: max estimated bytes indexed since last commit.
+// - `max_segments`: max searchable segments, exceeding it means merges are far behind.
+// - `block_timeout_millis`: how long to wait for backpressure relieved before fail.
+::BoolResult ffi_set_writer_backpressure(::std::string const &index_path, ::std::uint64_t max_uncommitted_bytes, ::std::uint64_t max_segments, ::std::uint64_t block_timeout_millis) noexcept;
+
+// Verify row_ids of ingested documents, duplicated row_ids break row_id bitmap semantics.
+// Checker is seeded with row_ids already committed in the index.
+// arguments:
+// - `index_path`: index directory.
+// - `mode`: `disabled` (default), `increasing` requires strictly increasing row_ids,
+//   `unique` allows any order but rejects duplicates.
+// - `reject`: reject violating documents if true, otherwise only log and count them.
+::BoolResult ffi_set_row_id_check(::std::string const &index_path, ::std::string const &mode, bool reject) noexcept;
+
 // Do index reader reload
+// Commits made through this library reload the reader, call it for commits of other processes.
 // arguments:
 // - `index_path`: index directory.
 ::BoolResult ffi_index_reader_reload(::std::string const &index_path) noexcept;
 
 // Load index reader
+// Returns `error_code` 3 if the index was written by a newer incompatible version.
 // arguments:
 // - `index_path`: index directory.
 ::BoolResult ffi_load_index_reader(::std::string const &index_path) noexcept;
@@ -873,11 +1527,122 @@ struct Statistics final {
 // - `index_path`: index directory.
 ::BoolResult ffi_free_index_reader(::std::string const &index_path) noexcept;
 
+// Load readers of all indexes under `root_dir` after a restart, so first queries
+// don't pay for loading them. Indexes failing validation are skipped and reported.
+// Returns a json summary with `discovered`, `loaded`, `warmed`, `warmed_bytes` and `failed`.
+// arguments:
+// - `root_dir`: directory index directories are discovered under.
+// - `hot_within_secs`: warm files of indexes committed within it, 0 warms nothing.
+// - `num_threads`: threads loading indexes, 0 means available parallelism.
+::StringResult ffi_bootstrap_indexes(::std::string const &root_dir, ::std::uint64_t hot_within_secs, ::std::uint64_t num_threads) noexcept;
+
+// Assign an index to a namespace, e.g. all indexes of one database on a multi-tenant host.
+// An index belongs to one namespace at most, registering again moves it.
+// arguments:
+// - `index_path`: index directory, it doesn't need to be loaded yet.
+// - `namespace_name`: namespace name, can't be empty.
+::BoolResult ffi_register_index_namespace(::std::string const &index_path, ::std::string const &namespace_name) noexcept;
+
+// List namespaces with at least one registered index.
+::rust::Vec<::rust::String> ffi_list_namespaces() noexcept;
+
+// List index directories registered in a namespace.
+// arguments:
+// - `namespace_name`: namespace name.
+::rust::Vec<::rust::String> ffi_list_namespace_indexes(::std::string const &namespace_name) noexcept;
+
+// Free index writers and readers of all indexes in a namespace, return number of indexes freed.
+// arguments:
+// - `namespace_name`: namespace name.
+::U64Result ffi_free_namespace(::std::string const &namespace_name) noexcept;
+
+// Start a background janitor unloading index writers and readers not accessed for a while,
+// pending writes are committed before writer is unloaded. Previous janitor is replaced.
+// Readers holding pinned searchers or lightweight deleted row_ids are kept.
+// arguments:
+// - `idle_timeout_secs`: unload indexes not accessed for this many seconds, should be positive.
+// - `check_interval_secs`: how often caches are checked, should be positive.
+::BoolResult ffi_enable_idle_index_unload(::std::uint64_t idle_timeout_secs, ::std::uint64_t check_interval_secs) noexcept;
+
+// Stop the idle index janitor, `false` if it's not running.
+::BoolResult ffi_disable_idle_index_unload() noexcept;
+
+// Shut the library down on clean exit of host, so no internal thread outlives it and no
+// destructor runs against a loaded index. Background janitor is stopped, commits scheduled by
+// `ffi_commit_async` and `ffi_commit_nowait` are waited, then all index writers and readers
+// are freed and caches are cleared. Nothing is freed if commits are still running after
+// timeout. Writers failed to commit are kept loaded and the error is returned, shutdown can
+// be retried with `abort`. Return number of index writers and readers freed.
+// arguments:
+// - `policy`: `commit` commits pending writes of writers, `abort` discards them.
+// - `timeout_millis`: how long running commits are waited, 0 waits forever.
+::U64Result ffi_shutdown(::std::string const &policy, ::std::uint64_t timeout_millis) noexcept;
+
 // Get indexed docs numbers.
 // arguments:
 // - `index_path`: index directory.
 ::std::uint64_t ffi_get_indexed_doc_counts(::std::string const &index_path) noexcept;
 
+// Register lightweight deleted row ids, they will be excluded from all search results.
+// arguments:
+// - `index_path`: index directory.
+// - `row_ids`: row ids deleted by host.
+::BoolResult ffi_register_deleted_row_ids(::std::string const &index_path, ::std::vector<::std::uint64_t> const &row_ids) noexcept;
+
+// Clear all registered lightweight deleted row ids.
+// arguments:
+// - `index_path`: index directory.
+::BoolResult ffi_clear_deleted_row_ids(::std::string const &index_path) noexcept;
+
+// Restrict searches of `principal` to rows matching `sentence`, it's ANDed into every
+// search the principal issues on this index. A previous filter of it is replaced.
+// Filters are kept by loaded reader, they survive reloads but not `ffi_free_index_reader`.
+// arguments:
+// - `index_path`: index directory.
+// - `principal`: principal id bound by `ffi_set_search_principal`.
+// - `sentence`: stored query, parsed as `ffi_register_percolator_query` sentences.
+::BoolResult ffi_register_security_filter_query(::std::string const &index_path, ::std::string const &principal, ::std::string const &sentence) noexcept;
+
+// Restrict searches of `principal` to rows of a bitmap, as `ffi_register_security_filter_query`.
+// arguments:
+// - `index_path`: index directory.
+// - `principal`: principal id bound by `ffi_set_search_principal`.
+// - `u8_bitmap`: rowIds the principal may see given by u8 bitmap.
+::BoolResult ffi_register_security_filter_bitmap(::std::string const &index_path, ::std::string const &principal, ::std::vector<::std::uint8_t> const &u8_bitmap) noexcept;
+
+// Remove security filter of `principal`, result is false if it has none.
+// Searches of the principal see nothing until a filter is registered again.
+// arguments:
+// - `index_path`: index directory.
+// - `principal`: principal id.
+::BoolResult ffi_unregister_security_filter(::std::string const &index_path, ::std::string const &principal) noexcept;
+
+// Bind `principal` to searches of the calling thread until it's changed, its security
+// filter is ANDed into every search and stored fields read of the thread. A principal
+// without filter on the searched index sees nothing.
+// arguments:
+// - `principal`: principal id, empty unbinds the principal and searches are unfiltered.
+::BoolResult ffi_set_search_principal(::std::string const &principal) noexcept;
+
+// Record a sampled fraction of searches of all indexes into query audit log, each record
+// is (timestamp, index, principal, query, hit count, latency). Records are passed to the
+// audit callback installed by `tantivy_search_set_query_audit_callback` and appended to
+// `file_path` as json lines.
+// arguments:
+// - `file_path`: audit log file, empty keeps no file.
+// - `sample_rate`: fraction of searches recorded in [0, 1], 0 disables auditing.
+::BoolResult ffi_configure_query_audit(::std::string const &file_path, double sample_rate) noexcept;
+
+// Limit cost of queries of all indexes, a query over any limit isn't executed, it fails
+// with `QueryTooExpensive` error. Limits protect a shared server from pathological queries.
+// arguments:
+// - `max_clause_count`: terms of a term set or parsed query, 0 means unlimited.
+// - `max_regex_states`: automaton states of a LIKE/regex pattern, 0 means unlimited.
+// - `max_expanded_terms`: terms a LIKE/regex pattern, prefix ones included, expands to
+//   in a segment, 0 means unlimited.
+// - `max_bitmap_size`: row ids of a bitmap query result, 0 means unlimited.
+::BoolResult ffi_set_query_cost_limits(::std::uint64_t max_clause_count, ::std::uint64_t max_regex_states, ::std::uint64_t max_expanded_terms, ::std::uint64_t max_bitmap_size) noexcept;
+
 // Execute single term query with given rowId range.
 // arguments:
 // - `index_path`: index directory.
@@ -928,6 +1693,25 @@ struct Statistics final {
 // - `terms`: terms need to be searched.
 ::rust::Vec<::std::uint8_t> ffi_query_terms_bitmap(::std::string const &index_path, ::std::string const &column_name, ::std::vector<::std::string> const &terms) noexcept;
 
+// Execute a group of terms query, matched rowIds are returned in a representation
+// picked by `mode`, `strategy` of result tells which one:
+// - `empty`: short-circuited, no row has any term.
+// - `count`: only `count` is set.
+// - `bitmap`: `row_ids` is rowIds u8 bitmap.
+// - `dense_range`: rowIds in [`range_start`, `range_end`) except `excluded_row_ids`.
+// - `ranges`: rowIds are runs in `ranges`, each of them is [`start`, `end`).
+// `count` is always set.
+// arguments:
+// - `index_path`: index directory.
+// - `column_name`: which column will execute search.
+// - `terms`: terms need to be searched.
+// - `mode`: `bitmap`, `dense_range`, `count` or `auto`. `auto` estimates selectivity by
+//   term doc frequencies before execution, more than half of rows matched is a dense range.
+// - `need_row_ids`: whether `auto` mode returns rowIds, otherwise rows are only counted.
+// - `accept_ranges`: whether rowIds can be returned as `ranges`, they're returned so
+//   when smaller, e.g. matches are contiguous in index sorted by row_id.
+::AdaptiveBitmapResult ffi_query_terms_adaptive(::std::string const &index_path, ::std::string const &column_name, ::std::vector<::std::string> const &terms, ::std::string const &mode, bool need_row_ids, bool accept_ranges) noexcept;
+
 // Execute a sentence query and return rowIds u8 bitmap.
 // arguments:
 // - `index_path`: index directory.
@@ -942,6 +1726,35 @@ struct Statistics final {
 // - `pattern`: pattern should be given by ClickHouse.
 ::rust::Vec<::std::uint8_t> ffi_regex_term_bitmap(::std::string const &index_path, ::std::string const &column_name, ::std::string const &pattern) noexcept;
 
+// Execute a date range query and return rowIds u8 bitmap.
+// Bounds are inclusive and truncated to precision of the date column.
+// A bound can also be a local date `2024-03-01` or date time `2024-03-01 08:30:00`
+// in host timezone, a local date upper bound covers the whole day.
+// arguments:
+// - `index_path`: index directory.
+// - `column_name`: date column will execute search.
+// - `lower`: lower bound, empty means unbounded.
+// - `upper`: upper bound, empty means unbounded.
+// - `utc_offset_seconds`: offset of host timezone, e.g. 28800 for UTC+8.
+::rust::Vec<::std::uint8_t> ffi_query_date_range_bitmap(::std::string const &index_path, ::std::string const &column_name, ::std::string const &lower, ::std::string const &upper, ::std::int32_t utc_offset_seconds) noexcept;
+
+// Execute a geo bounding box query and return rowIds u8 bitmap.
+// The box crosses the antimeridian if `min_lon` is larger than `max_lon`.
+// arguments:
+// - `index_path`: index directory.
+// - `column_name`: geo point column will execute search.
+// - `min_lat`, `min_lon`: south west corner in degrees.
+// - `max_lat`, `max_lon`: north east corner in degrees.
+::rust::Vec<::std::uint8_t> ffi_query_geo_bounding_box_bitmap(::std::string const &index_path, ::std::string const &column_name, double min_lat, double min_lon, double max_lat, double max_lon) noexcept;
+
+// Execute a geo distance query and return rowIds u8 bitmap.
+// arguments:
+// - `index_path`: index directory.
+// - `column_name`: geo point column will execute search.
+// - `lat`, `lon`: center in degrees.
+// - `radius_meters`: rows within this distance of center are matched.
+::rust::Vec<::std::uint8_t> ffi_query_geo_distance_bitmap(::std::string const &index_path, ::std::string const &column_name, double lat, double lon, double radius_meters) noexcept;
+
 // Execute a regex query and return rowIds u8 bitmap.
 // arguments:
 // - `index_path`: index directory.
@@ -976,6 +1789,332 @@ struct Statistics final {
 // - `colunm_names`: for multi column search.
 ::BM25Result ffi_bm25_search_with_column_names(::std::string const &index_path, ::std::string const &sentence, ::std::uint32_t topk, ::std::vector<::std::uint64_t> const &alived_ids, bool query_with_filter, bool query_with_id_range, ::std::uint64_t start_id, ::std::uint64_t end_id, ::std::vector<::std::string> const &column_names) noexcept;
 
+// Execute BM25 search in filter mode, BM25 score is not computed and all results score 1.0.
+// Faster when only matched rowIds are needed, e.g. high frequency terms.
+// arguments:
+// - `index_path`: index directory.
+// - `sentence`: from DingoDB TextSearch function.
+// - `topk`: only return top k matched results, in no particular order.
+// - `alived_ids`: alived rowIds given by u64 vector.
+// - `query_with_filter`: whether use alived_ids or not.
+// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+// - `start_id`: id range start.
+// - `end_id`: id range end.
+// - `colunm_names`: for multi column search.
+::BM25Result ffi_bm25_filter_search(::std::string const &index_path, ::std::string const &sentence, ::std::uint32_t topk, ::std::vector<::std::uint64_t> const &alived_ids, bool query_with_filter, bool query_with_id_range, ::std::uint64_t start_id, ::std::uint64_t end_id, ::std::vector<::std::string> const &column_names) noexcept;
+
+// Execute BM25 search scored with given BM25 parameters instead of the index default ones.
+// arguments:
+// - `index_path`: index directory.
+// - `sentence`: from DingoDB TextSearch function.
+// - `topk`: only return top k related results.
+// - `alived_ids`: alived rowIds given by u64 vector.
+// - `query_with_filter`: whether use alived_ids or not.
+// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+// - `start_id`: id range start.
+// - `end_id`: id range end.
+// - `colunm_names`: for multi column search.
+// - `k1`: term frequency saturation, non-negative, tantivy uses 1.2.
+// - `b`: document length normalization in [0, 1], tantivy uses 0.75.
+::BM25Result ffi_bm25_search_with_bm25_params(::std::string const &index_path, ::std::string const &sentence, ::std::uint32_t topk, ::std::vector<::std::uint64_t> const &alived_ids, bool query_with_filter, bool query_with_id_range, ::std::uint64_t start_id, ::std::uint64_t end_id, ::std::vector<::std::string> const &column_names, float k1, float b) noexcept;
+
+// Execute BM25 search with a pinned searcher, results are consistent with that generation.
+// arguments:
+// - `index_path`: index directory.
+// - `sentence`: from DingoDB TextSearch function.
+// - `topk`: only return top k related results.
+// - `alived_ids`: alived rowIds given by u64 vector.
+// - `query_with_filter`: whether use alived_ids or not.
+// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+// - `start_id`: id range start.
+// - `end_id`: id range end.
+// - `colunm_names`: for multi column search.
+// - `generation`: searcher generation returned by `ffi_pin_searcher`.
+::BM25Result ffi_bm25_search_at_generation(::std::string const &index_path, ::std::string const &sentence, ::std::uint32_t topk, ::std::vector<::std::uint64_t> const &alived_ids, bool query_with_filter, bool query_with_id_range, ::std::uint64_t start_id, ::std::uint64_t end_id, ::std::vector<::std::string> const &column_names, ::std::uint64_t generation) noexcept;
+
+// Execute BM25 search over `column_names`, scores of a column are multiplied by its boost.
+// arguments:
+// - `index_path`: index directory.
+// - `sentence`: from DingoDB TextSearch function.
+// - `topk`: only return top k related results.
+// - `alived_ids`: alived rowIds given by u64 vector.
+// - `query_with_filter`: whether use alived_ids or not.
+// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+// - `start_id`: id range start.
+// - `end_id`: id range end.
+// - `column_names`: text columns to search.
+// - `boosts`: positive boost of each column in `column_names`, e.g. 3.0 for title and 1.0 for body.
+::BM25Result ffi_bm25_search_with_field_boosts(::std::string const &index_path, ::std::string const &sentence, ::std::uint32_t topk, ::std::vector<::std::uint64_t> const &alived_ids, bool query_with_filter, bool query_with_id_range, ::std::uint64_t start_id, ::std::uint64_t end_id, ::std::vector<::std::string> const &column_names, ::std::vector<float> const &boosts) noexcept;
+
+// Execute BM25 search for one page, results are ranked by score desc and row_id asc.
+// arguments:
+// - `index_path`: index directory.
+// - `sentence`: from DingoDB TextSearch function.
+// - `topk`: page size.
+// - `offset`: number of ranked results to skip.
+// - `has_search_after`: whether use (`after_score`, `after_row_id`) cursor or not.
+// - `after_score`: score of the last result in previous page.
+// - `after_row_id`: row_id of the last result in previous page.
+// - `alived_ids`: alived rowIds given by u64 vector.
+// - `query_with_filter`: whether use alived_ids or not.
+// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+// - `start_id`: id range start.
+// - `end_id`: id range end.
+// - `colunm_names`: for multi column search.
+::BM25Result ffi_bm25_search_page(::std::string const &index_path, ::std::string const &sentence, ::std::uint32_t topk, ::std::uint32_t offset, bool has_search_after, float after_score, ::std::uint64_t after_row_id, ::std::vector<::std::uint64_t> const &alived_ids, bool query_with_filter, bool query_with_id_range, ::std::uint64_t start_id, ::std::uint64_t end_id, ::std::vector<::std::string> const &column_names) noexcept;
+
+// Execute ranked search with a query of the JSON query DSL.
+// e.g. `{"type": "dis_max", "tie_breaker": 0.2, "queries": [
+//     {"type": "match", "field": "title", "query": "rust"},
+//     {"type": "match", "field": "body", "query": "rust"}]}`
+// arguments:
+// - `index_path`: index directory.
+// - `query_json`: query DSL json.
+// - `topk`: only return top k related results.
+// - `alived_ids`: alived rowIds given by u64 vector.
+// - `query_with_filter`: whether use alived_ids or not.
+// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+// - `start_id`: id range start.
+// - `end_id`: id range end.
+::BM25Result ffi_bm25_search_with_query_dsl(::std::string const &index_path, ::std::string const &query_json, ::std::uint32_t topk, ::std::vector<::std::uint64_t> const &alived_ids, bool query_with_filter, bool query_with_id_range, ::std::uint64_t start_id, ::std::uint64_t end_id) noexcept;
+
+// Search rows whose vectors are nearest to `vector`, scored by metric of the vector column.
+// Only rows matching `filter_sentence` are searched, so text filter and vector similarity
+// are combined in one search.
+// arguments:
+// - `index_path`: index directory.
+// - `column_name`: vector column will execute search.
+// - `vector`: query vector, it should have dims of the column.
+// - `topk`: only return top k nearest results.
+// - `filter_sentence`: sentence rows should match, empty means all rows are searched.
+// - `alived_ids`: alived rowIds given by u64 vector.
+// - `query_with_filter`: whether use alived_ids or not.
+// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+// - `start_id`: id range start.
+// - `end_id`: id range end.
+::BM25Result ffi_knn_search(::std::string const &index_path, ::std::string const &column_name, ::std::vector<float> const &vector, ::std::uint32_t topk, ::std::string const &filter_sentence, ::std::vector<::std::uint64_t> const &alived_ids, bool query_with_filter, bool query_with_id_range, ::std::uint64_t start_id, ::std::uint64_t end_id) noexcept;
+
+// Search rows by dot product of query term weights and term weights of a sparse vector
+// column, e.g. weights of a SPLADE model. Only rows matching `filter_sentence` are searched.
+// arguments:
+// - `index_path`: index directory.
+// - `column_name`: sparse vector column will execute search.
+// - `query_json`: query term weights as a JSON object, e.g. `{"apple": 1.2, "fruit": 0.4}`.
+// - `topk`: only return top k results.
+// - `filter_sentence`: sentence rows should match, empty means all rows are searched.
+// - `alived_ids`: alived rowIds given by u64 vector.
+// - `query_with_filter`: whether use alived_ids or not.
+// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+// - `start_id`: id range start.
+// - `end_id`: id range end.
+::BM25Result ffi_sparse_vector_search(::std::string const &index_path, ::std::string const &column_name, ::std::string const &query_json, ::std::uint32_t topk, ::std::string const &filter_sentence, ::std::vector<::std::uint64_t> const &alived_ids, bool query_with_filter, bool query_with_id_range, ::std::uint64_t start_id, ::std::uint64_t end_id) noexcept;
+
+// Execute BM25 search and order results by a numeric or date fast field.
+// BM25 score is the tiebreak, docs missing `order_field` are ranked last.
+// arguments:
+// - `index_path`: index directory.
+// - `sentence`: from DingoDB TextSearch function.
+// - `topk`: only return top k results.
+// - `order_field`: numeric or date fast field used to order results.
+// - `ascending`: order by ascending field value or not.
+// - `alived_ids`: alived rowIds given by u64 vector.
+// - `query_with_filter`: whether use alived_ids or not.
+// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+// - `start_id`: id range start.
+// - `end_id`: id range end.
+// - `colunm_names`: for multi column search.
+::BM25Result ffi_bm25_search_order_by_field(::std::string const &index_path, ::std::string const &sentence, ::std::uint32_t topk, ::std::string const &order_field, bool ascending, ::std::vector<::std::uint64_t> const &alived_ids, bool query_with_filter, bool query_with_id_range, ::std::uint64_t start_id, ::std::uint64_t end_id, ::std::vector<::std::string> const &column_names) noexcept;
+
+// Execute BM25 search keeping at most `max_per_key` results per value of a fast field,
+// e.g. at most 2 results per domain. Docs missing the field share one key.
+// arguments:
+// - `index_path`: index directory.
+// - `sentence`: from DingoDB TextSearch function.
+// - `topk`: only return top k results.
+// - `diversify_field`: numeric or date fast field used to diversify results.
+// - `max_per_key`: max number of results for each value of `diversify_field`.
+// - `alived_ids`: alived rowIds given by u64 vector.
+// - `query_with_filter`: whether use alived_ids or not.
+// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+// - `start_id`: id range start.
+// - `end_id`: id range end.
+// - `colunm_names`: for multi column search.
+::BM25Result ffi_bm25_search_diversified(::std::string const &index_path, ::std::string const &sentence, ::std::uint32_t topk, ::std::string const &diversify_field, ::std::uint32_t max_per_key, ::std::vector<::std::uint64_t> const &alived_ids, bool query_with_filter, bool query_with_id_range, ::std::uint64_t start_id, ::std::uint64_t end_id, ::std::vector<::std::string> const &column_names) noexcept;
+
+// Execute BM25 search and collapse results by a u64 fast field, e.g. best hit per user_id.
+// Each group returns its best hit and hit count, groups are ranked by score of best hit.
+// arguments:
+// - `index_path`: index directory.
+// - `sentence`: from DingoDB TextSearch function.
+// - `topk`: only return top k groups.
+// - `group_field`: u64 fast field used to group results.
+// - `alived_ids`: alived rowIds given by u64 vector.
+// - `query_with_filter`: whether use alived_ids or not.
+// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+// - `start_id`: id range start.
+// - `end_id`: id range end.
+// - `colunm_names`: for multi column search.
+::GroupTopDocsResult ffi_bm25_search_grouped(::std::string const &index_path, ::std::string const &sentence, ::std::uint32_t topk, ::std::string const &group_field, ::std::vector<::std::uint64_t> const &alived_ids, bool query_with_filter, bool query_with_id_range, ::std::uint64_t start_id, ::std::uint64_t end_id, ::std::vector<::std::string> const &column_names) noexcept;
+
+// Execute BM25 search and report query terms matched in each result, e.g. to render
+// "matched on: title(foo), body(bar)" without analyzing docs again.
+// Terms matched in a language sub-field are reported in its column.
+// arguments:
+// - `index_path`: index directory.
+// - `sentence`: from DingoDB TextSearch function.
+// - `topk`: only return top k related results.
+// - `alived_ids`: alived rowIds given by u64 vector.
+// - `query_with_filter`: whether use alived_ids or not.
+// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+// - `start_id`: id range start.
+// - `end_id`: id range end.
+// - `colunm_names`: for multi column search.
+::MatchedTermsTopDocsResult ffi_bm25_search_with_matched_terms(::std::string const &index_path, ::std::string const &sentence, ::std::uint32_t topk, ::std::vector<::std::uint64_t> const &alived_ids, bool query_with_filter, bool query_with_id_range, ::std::uint64_t start_id, ::std::uint64_t end_id, ::std::vector<::std::string> const &column_names) noexcept;
+
+// Execute BM25 search and count total hits in the same pass, e.g. to show
+// "1-10 of ~12,400" without running another count query.
+// Total hits are distinct row_ids matching the query and filters. Unless
+// `exact_total_hits`, docs which can't rank in topk are skipped and total hits may be
+// a lower bound, `total_hits_exact` tells whether they are exact.
+// arguments:
+// - `index_path`: index directory.
+// - `sentence`: from DingoDB TextSearch function.
+// - `topk`: only return top k related results.
+// - `alived_ids`: alived rowIds given by u64 vector.
+// - `query_with_filter`: whether use alived_ids or not.
+// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+// - `start_id`: id range start.
+// - `end_id`: id range end.
+// - `colunm_names`: for multi column search.
+// - `exact_total_hits`: score every matched doc to count total hits exactly.
+::TopDocsWithTotalHitsResult ffi_bm25_search_with_total_hits(::std::string const &index_path, ::std::string const &sentence, ::std::uint32_t topk, ::std::vector<::std::uint64_t> const &alived_ids, bool query_with_filter, bool query_with_id_range, ::std::uint64_t start_id, ::std::uint64_t end_id, ::std::vector<::std::string> const &column_names, bool exact_total_hits) noexcept;
+
+// Read stored fields of given row_ids, only requested fields are loaded.
+// Values of each doc keep the order of `field_names`, missing values are empty strings.
+// Row_ids not found or deleted are skipped.
+// arguments:
+// - `index_path`: index directory.
+// - `row_ids`: row_ids to read.
+// - `field_names`: stored fields to read, fields not stored are rejected.
+::StoredDocsResult ffi_get_stored_fields(::std::string const &index_path, ::std::vector<::std::uint64_t> const &row_ids, ::std::vector<::std::string> const &field_names) noexcept;
+
+// Byte offsets of query terms matched in stored text of a doc, e.g. to highlight the
+// original text kept by host. Offsets are ordered by column and position.
+// Columns should be stored, a row_id not found or deleted has no offsets.
+// arguments:
+// - `index_path`: index directory.
+// - `row_id`: doc to read.
+// - `sentence`: query sentence, parsed like sentences of BM25 search.
+// - `colunm_names`: columns to highlight, all text columns if it's empty.
+::MatchedOffsetsResult ffi_get_matched_offsets(::std::string const &index_path, ::std::uint64_t row_id, ::std::string const &sentence, ::std::vector<::std::string> const &column_names) noexcept;
+
+// Store a query for reverse search, it's persisted in the index directory.
+// A query with the same id is replaced.
+// arguments:
+// - `index_path`: index directory.
+// - `query_id`: id returned by `ffi_percolate` when the query matches.
+// - `sentence`: query sentence, parsed like sentences of BM25 search.
+::BoolResult ffi_register_percolator_query(::std::string const &index_path, ::std::uint64_t query_id, ::std::string const &sentence) noexcept;
+
+// Remove a stored query, `false` if it isn't stored.
+// arguments:
+// - `index_path`: index directory.
+// - `query_id`: id of the query.
+::BoolResult ffi_unregister_percolator_query(::std::string const &index_path, ::std::uint64_t query_id) noexcept;
+
+// Return ids of stored queries matching a doc in ascending order, the doc isn't indexed.
+// Text and numeric columns are matched, other columns are ignored.
+// arguments:
+// - `index_path`: index directory.
+// - `column_names`: columns of the doc.
+// - `column_docs`: values of `column_names`.
+::QueryIdsResult ffi_percolate(::std::string const &index_path, ::std::vector<::std::string> const &column_names, ::std::vector<::std::string> const &column_docs) noexcept;
+
+// Estimate the number of distinct values of a fast field among docs matching sentence.
+// The serialized HyperLogLog sketch is returned to merge estimates of index parts.
+// arguments:
+// - `index_path`: index directory.
+// - `sentence`: from DingoDB TextSearch function.
+// - `field_name`: numeric or date fast field whose distinct values are counted.
+// - `alived_ids`: alived rowIds given by u64 vector.
+// - `query_with_filter`: whether use alived_ids or not.
+// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+// - `start_id`: id range start.
+// - `end_id`: id range end.
+// - `colunm_names`: for multi column search.
+::CardinalityResult ffi_estimate_cardinality(::std::string const &index_path, ::std::string const &sentence, ::std::string const &field_name, ::std::vector<::std::uint64_t> const &alived_ids, bool query_with_filter, bool query_with_id_range, ::std::uint64_t start_id, ::std::uint64_t end_id, ::std::vector<::std::string> const &column_names) noexcept;
+
+// Merge two sketches returned by `ffi_estimate_cardinality`, values in both are counted once.
+// arguments:
+// - `left`: serialized sketch.
+// - `right`: serialized sketch of the same precision.
+::CardinalityResult ffi_merge_cardinality_sketches(::std::vector<::std::uint8_t> const &left, ::std::vector<::std::uint8_t> const &right) noexcept;
+
+// Estimate percentiles of a numeric fast field among docs matching sentence.
+// Values are NaN if no doc has the field, the serialized t-digest is returned
+// to merge percentiles of index parts.
+// arguments:
+// - `index_path`: index directory.
+// - `sentence`: from DingoDB TextSearch function.
+// - `field_name`: u64, i64 or f64 fast field.
+// - `percents`: percentiles to estimate in [0, 100], e.g. 50, 95 and 99.
+// - `alived_ids`: alived rowIds given by u64 vector.
+// - `query_with_filter`: whether use alived_ids or not.
+// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+// - `start_id`: id range start.
+// - `end_id`: id range end.
+// - `colunm_names`: for multi column search.
+::PercentilesResult ffi_estimate_percentiles(::std::string const &index_path, ::std::string const &sentence, ::std::string const &field_name, ::std::vector<double> const &percents, ::std::vector<::std::uint64_t> const &alived_ids, bool query_with_filter, bool query_with_id_range, ::std::uint64_t start_id, ::std::uint64_t end_id, ::std::vector<::std::string> const &column_names) noexcept;
+
+// Merge two digests returned by `ffi_estimate_percentiles` and estimate percentiles of both.
+// arguments:
+// - `left`: serialized digest.
+// - `right`: serialized digest of the same compression.
+// - `percents`: percentiles to estimate in [0, 100].
+::PercentilesResult ffi_merge_percentiles_digests(::std::vector<::std::uint8_t> const &left, ::std::vector<::std::uint8_t> const &right, ::std::vector<double> const &percents) noexcept;
+
+// Pin current searcher of index reader, return its generation.
+// Pinned searcher is kept until `ffi_unpin_searcher`, even if reader reloaded.
+// arguments:
+// - `index_path`: index directory.
+::U64Result ffi_pin_searcher(::std::string const &index_path) noexcept;
+
+// Unpin a searcher generation.
+// arguments:
+// - `index_path`: index directory.
+// - `generation`: searcher generation returned by `ffi_pin_searcher`.
+::BoolResult ffi_unpin_searcher(::std::string const &index_path, ::std::uint64_t generation) noexcept;
+
+// Acquire a handle pinning current searcher of index reader, return an opaque handle id.
+// Steps of a multi-step operation (count, search, fetch) using the handle see one
+// consistent searcher, even if reader reloaded meanwhile. Release it by `ffi_release_searcher_handle`.
+// arguments:
+// - `index_path`: index directory.
+::U64Result ffi_acquire_searcher_handle(::std::string const &index_path) noexcept;
+
+// Release a searcher handle, `false` if it doesn't exist or has been released.
+// arguments:
+// - `handle`: handle returned by `ffi_acquire_searcher_handle`.
+::BoolResult ffi_release_searcher_handle(::std::uint64_t handle) noexcept;
+
+// Count docs visible to the searcher of a handle.
+// arguments:
+// - `handle`: handle returned by `ffi_acquire_searcher_handle`.
+::U64Result ffi_searcher_handle_num_docs(::std::uint64_t handle) noexcept;
+
+// Execute BM25 search for one page of results with the searcher of a handle.
+// Arguments are same as `ffi_bm25_search_page` except `handle`.
+// arguments:
+// - `handle`: handle returned by `ffi_acquire_searcher_handle`.
+::BM25Result ffi_bm25_search_with_handle(::std::uint64_t handle, ::std::string const &sentence, ::std::uint32_t topk, ::std::uint32_t offset, bool has_search_after, float after_score, ::std::uint64_t after_row_id, ::std::vector<::std::uint64_t> const &alived_ids, bool query_with_filter, bool query_with_id_range, ::std::uint64_t start_id, ::std::uint64_t end_id, ::std::vector<::std::string> const &column_names) noexcept;
+
+// Read stored fields of given row_ids with the searcher of a handle, same as `ffi_get_stored_fields`.
+// arguments:
+// - `handle`: handle returned by `ffi_acquire_searcher_handle`.
+// - `row_ids`: row_ids to read.
+// - `field_names`: stored fields to read, fields not stored are rejected.
+::StoredDocsResult ffi_get_stored_fields_with_handle(::std::uint64_t handle, ::std::vector<::std::uint64_t> const &row_ids, ::std::vector<::std::string> const &field_names) noexcept;
+
 // Get doc freq for current part.
 // arguments:
 // - `index_path`: index directory.
@@ -1001,3 +2140,122 @@ struct Statistics final {
 // arguments:
 // - `index_path`: index directory.
 ::StringResult ffi_get_index_json_parameter(::std::string const &index_path) noexcept;
+
+// Dry-run analyzer of a field, return tokens with position and byte offsets.
+// It's used to debug why a query doesn't match, nothing is indexed.
+// arguments:
+// - `index_path_or_config`: index directory, or index json parameter.
+// - `field`: column name, columns not configured use default analyzer.
+// - `text`: text to be analyzed.
+::AnalyzedTokensResult ffi_analyze(::std::string const &index_path_or_config, ::std::string const &field, ::std::string const &text) noexcept;
+
+// Normalize a raw query value the same way a field is indexed, e.g. lowercase it
+// for a `keyword_lowercase` field, so exact match filters hit the indexed term.
+// Empty string is returned if analyzer drops the value, error if it's split into terms.
+// arguments:
+// - `index_path_or_config`: index directory, or index json parameter.
+// - `field`: column name, columns not configured use default analyzer.
+// - `value`: raw value of an exact match filter.
+::StringResult ffi_normalize_term(::std::string const &index_path_or_config, ::std::string const &field, ::std::string const &value) noexcept;
+
+// Get health status of a cached index, include writer and reader.
+// `writer_busy` means writer is held by a commit or merge, health check doesn't wait for it.
+// `canonical_path` is the index directory with relative paths and symlinks resolved,
+// every spelling of it refers to the same cached writer and reader.
+// arguments:
+// - `index_path`: index directory.
+::IndexHealthResult ffi_get_index_health(::std::string const &index_path) noexcept;
+
+// Get liveness of background subsystems: `idle_janitor` of process, `auto_commit` and `merges`
+// of index writer. `running` is false if a subsystem is disabled or idle, `alive` is false
+// if its thread has exited, `last_heartbeat_millis` is unix millis it last made progress.
+// arguments:
+// - `index_path`: index directory, empty only reports process subsystems.
+::LivenessResult ffi_get_liveness(::std::string const &index_path) noexcept;
+
+// Liveness probe, false if a running subsystem is dead or hasn't made progress recently.
+// arguments:
+// - `index_path`: index directory, empty only probes process subsystems.
+// - `max_heartbeat_age_millis`: heartbeats older than it are stale.
+::BoolResult ffi_probe_liveness(::std::string const &index_path, ::std::uint64_t max_heartbeat_age_millis) noexcept;
+
+// Readiness probe, true if index is loaded and its writer, if loaded, accepts documents.
+// arguments:
+// - `index_path`: index directory.
+::BoolResult ffi_probe_readiness(::std::string const &index_path) noexcept;
+
+// List every index held in writer and reader caches as json array, each item has
+// `index_path`, `canonical_path`, `namespace`, `writer_loaded`, `reader_loaded`, `num_docs`,
+// `writer_uncommitted_bytes`, `reader_memory_bytes`, `open_files` and `last_access_millis`.
+::StringResult ffi_list_indexes() noexcept;
+
+// Limit file descriptors held open by the process, loading an index writer or reader
+// past the budget fails instead of hitting the process file descriptor limit.
+// Loaded indexes are kept when the budget is lowered.
+// arguments:
+// - `max_open_files`: files budget, 0 means unlimited.
+::BoolResult ffi_set_max_open_files(::std::uint64_t max_open_files) noexcept;
+
+// Get file descriptors held open by the process.
+::U64Result ffi_get_open_files() noexcept;
+
+// Limit merge I/O of all index writers so merges don't starve host disk bandwidth.
+// Budget is taken by input segments when a merge starts, merges over budget are deferred
+// to next segment update, explicit merges wait for the budget.
+// arguments:
+// - `max_mb_per_sec`: merge I/O budget in MB/s, 0 means unlimited.
+::BoolResult ffi_set_merge_throttle(::std::uint64_t max_mb_per_sec) noexcept;
+
+// Keep free disk space for host, commits and merges of all index writers whose estimated
+// footprint doesn't fit in free disk space minus the reserve are refused before writing.
+// arguments:
+// - `min_free_mb`: free disk space to keep in MB, 0 only keeps room for the footprint.
+::BoolResult ffi_set_disk_space_reserve(::std::uint64_t min_free_mb) noexcept;
+
+// Set threads merging per-segment row_id bitmaps of bitmap queries in parallel,
+// merging dominates bitmap queries over indexes of many segments.
+// arguments:
+// - `num_threads`: merge threads, 0 merges bitmaps on calling thread.
+::BoolResult ffi_set_bitmap_union_threads(::std::uint64_t num_threads) noexcept;
+
+// Pin internal threads to a CPU set, so cgroup management of host can constrain CPU usage
+// of the library. Threads are named `tantivy-search-*`, `tantivy-ingest-*` and
+// `tantivy-merge-*` by what they do, writer and merge threads spawned by tantivy are
+// renamed on linux. Pinning is supported on linux only.
+// arguments:
+// - `cpus`: CPU numbers threads may run on, empty unpins threads.
+::BoolResult ffi_set_thread_cpu_set(::std::vector<::std::uint32_t> const &cpus) noexcept;
+
+// Get memory held by the allocator of this library in bytes. Allocations are served by
+// jemalloc or mimalloc if the library is built with feature `jemalloc` or `mimalloc`.
+// - `allocator`: `jemalloc`, `mimalloc` or `system`.
+// - `allocated_bytes`: allocated by the library, 0 for mimalloc.
+// - `active_bytes`: pages holding allocations, committed pages for mimalloc.
+// - `resident_bytes`: resident pages of allocator, resident set of process for mimalloc.
+// - `retained_bytes`: kept for reuse instead of returned to the OS, 0 for mimalloc.
+// The system allocator doesn't report statistics, all of them are 0.
+::AllocatorStatsResult ffi_get_allocator_stats() noexcept;
+
+// Return memory retained by the allocator of this library but not in use to the OS,
+// e.g. after large merges. The system allocator is trimmed on linux with glibc.
+::BoolResult ffi_purge_allocator() noexcept;
+
+// Get deleted docs statistics of each searchable segment.
+// arguments:
+// - `index_path`: index directory.
+::SegmentDeleteStatsResult ffi_get_segment_delete_stats(::std::string const &index_path) noexcept;
+
+// Merge segments whose deleted docs ratio exceeds `min_deleted_ratio` to reclaim space.
+// Returns `error_code` 2 if the merged segment doesn't fit in free disk space.
+// arguments:
+// - `index_path`: index directory.
+// - `min_deleted_ratio`: segments with deleted ratio >= it will be merged, range (0, 1].
+::BoolResult ffi_vacuum_index(::std::string const &index_path, float min_deleted_ratio) noexcept;
+
+// Rewrite segments of an index written by an older version in current format and record
+// current versions, returns false if it's up to date. A writer is loaded meanwhile if
+// none is. Returns `error_code` 2 if rewritten segments don't fit in free disk space,
+// 3 if the index was written by a newer incompatible version.
+// arguments:
+// - `index_path`: index directory.
+::BoolResult ffi_upgrade_index(::std::string const &index_path) noexcept;
@@ -0,0 +1,12 @@
+package io.dingodb.tantivy;
+
+/** Result of BM25 search. */
+public final class RowIdWithScore {
+    public final long rowId;
+    public final float score;
+
+    public RowIdWithScore(long rowId, float score) {
+        this.rowId = rowId;
+        this.score = score;
+    }
+}
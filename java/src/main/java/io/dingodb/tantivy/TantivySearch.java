@@ -0,0 +1,47 @@
+package io.dingodb.tantivy;
+
+/**
+ * Native methods of tantivy_search, requires the library built with `jni` feature as a shared library:
+ * {@code cargo rustc --release --features jni --crate-type cdylib}.
+ * Failures are thrown as {@link RuntimeException}.
+ */
+public final class TantivySearch {
+    static {
+        System.loadLibrary("tantivy_search");
+    }
+
+    private TantivySearch() {}
+
+    // Index APIs.
+    public static native boolean createIndex(String indexPath, String[] columnNames);
+
+    public static native boolean createIndexWithParameter(
+            String indexPath, String[] columnNames, String indexJsonParameter);
+
+    public static native boolean indexMultiColumnDocs(
+            String indexPath, long rowId, String[] columnNames, String[] columnDocs);
+
+    public static native boolean deleteRowIds(String indexPath, long[] rowIds);
+
+    public static native boolean commitIndex(String indexPath);
+
+    public static native boolean loadIndexWriter(String indexPath);
+
+    public static native boolean freeIndexWriter(String indexPath);
+
+    // Search APIs.
+    public static native boolean loadIndexReader(String indexPath);
+
+    public static native boolean freeIndexReader(String indexPath);
+
+    public static native boolean indexReaderReload(String indexPath);
+
+    public static native long getIndexedDocCounts(String indexPath);
+
+    /** Returns u8 bitmap, bit `n` is set when row `n` matches. */
+    public static native byte[] queryTermBitmap(String indexPath, String columnName, String term);
+
+    /** Empty or null `columnNames` means search all columns. */
+    public static native RowIdWithScore[] bm25Search(
+            String indexPath, String sentence, int topk, String[] columnNames);
+}
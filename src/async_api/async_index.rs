@@ -0,0 +1,93 @@
+use super::async_pool::spawn_index_task;
+use crate::common::errors::TantivySearchError;
+use crate::index::implements::api_index_impl::{
+    commit_index, delete_row_ids, free_index_writer, index_multi_column_docs, vacuum_index,
+};
+
+/// Index a batch of documents on index pool, each document is `(row_id, column_docs)`.
+pub async fn index_multi_column_docs_async(
+    index_path: &str,
+    column_names: Vec<String>,
+    documents: Vec<(u64, Vec<String>)>,
+) -> Result<bool, TantivySearchError> {
+    let index_path: String = index_path.to_string();
+    spawn_index_task(move || {
+        for (row_id, column_docs) in documents.iter() {
+            index_multi_column_docs(&index_path, *row_id, &column_names, column_docs)?;
+        }
+        Ok(true)
+    })
+    .await
+}
+
+/// Delete row ids and commit on index pool.
+pub async fn delete_row_ids_async(
+    index_path: &str,
+    row_ids: Vec<u64>,
+) -> Result<bool, TantivySearchError> {
+    let index_path: String = index_path.to_string();
+    spawn_index_task(move || delete_row_ids(&index_path, &row_ids)).await
+}
+
+/// Commit index writer on index pool, loaded reader will be reloaded.
+pub async fn commit_index_async(index_path: &str) -> Result<bool, TantivySearchError> {
+    let index_path: String = index_path.to_string();
+    spawn_index_task(move || commit_index(&index_path)).await
+}
+
+/// Merge segments whose deleted ratio reaches `min_deleted_ratio` on index pool.
+pub async fn vacuum_index_async(
+    index_path: &str,
+    min_deleted_ratio: f32,
+) -> Result<bool, TantivySearchError> {
+    let index_path: String = index_path.to_string();
+    spawn_index_task(move || vacuum_index(&index_path, min_deleted_ratio)).await
+}
+
+/// Wait merging threads and free index writer on index pool.
+pub async fn free_index_writer_async(index_path: &str) -> Result<bool, TantivySearchError> {
+    let index_path: String = index_path.to_string();
+    spawn_index_task(move || free_index_writer(&index_path)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::tests::{create_3column_names, index_3column_docs_with_index_writer_bridge};
+    use crate::index::implements::api_index_impl::create_index;
+    use crate::TEST_MUTEX;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_commit_index_async() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+
+        runtime.block_on(async {
+            assert!(commit_index_async(temp_directory_str).await.is_err());
+            assert!(create_index(temp_directory_str, &create_3column_names()).is_ok());
+            index_3column_docs_with_index_writer_bridge(temp_directory_str, false);
+            let documents = vec![(
+                100,
+                vec!["col1".to_string(), "col2".to_string(), "col3".to_string()],
+            )];
+            assert!(index_multi_column_docs_async(
+                temp_directory_str,
+                create_3column_names(),
+                documents
+            )
+            .await
+            .unwrap());
+            assert!(commit_index_async(temp_directory_str).await.unwrap());
+            assert!(delete_row_ids_async(temp_directory_str, vec![100])
+                .await
+                .unwrap());
+            assert!(vacuum_index_async(temp_directory_str, 0.0).await.is_err());
+            assert!(free_index_writer_async(temp_directory_str).await.unwrap());
+        });
+    }
+}
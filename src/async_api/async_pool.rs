@@ -0,0 +1,132 @@
+use crate::common::errors::TantivySearchError;
+use crate::common::thread_affinity::{thread_pool_builder, ThreadKind};
+use once_cell::sync::OnceCell;
+use rayon::ThreadPool;
+use std::future::Future;
+use tokio::sync::oneshot;
+
+// Default threads of index pool, commit and merge are heavy but not frequent.
+static DEFAULT_INDEX_THREADS: usize = 2;
+
+pub struct AsyncPools {
+    index_pool: ThreadPool,
+    search_pool: ThreadPool,
+}
+
+static ASYNC_POOLS: OnceCell<AsyncPools> = OnceCell::new();
+
+fn build_pool(
+    kind: ThreadKind,
+    name: &'static str,
+    num_threads: usize,
+) -> Result<ThreadPool, String> {
+    thread_pool_builder(kind, name)
+        .num_threads(num_threads)
+        .build()
+        .map_err(|e| format!("Failed to build {} pool: {}", kind.thread_name(name), e))
+}
+
+fn build_async_pools(index_threads: usize, search_threads: usize) -> Result<AsyncPools, String> {
+    if index_threads == 0 || search_threads == 0 {
+        return Err("Threads number of async pools minimum is 1".to_string());
+    }
+    Ok(AsyncPools {
+        index_pool: build_pool(ThreadKind::Ingest, "async", index_threads)?,
+        search_pool: build_pool(ThreadKind::Search, "async", search_threads)?,
+    })
+}
+
+/// Configure threads of async pools, it should be called before any async API is used.
+pub fn configure_async_pools(
+    index_threads: usize,
+    search_threads: usize,
+) -> Result<(), TantivySearchError> {
+    let pools = build_async_pools(index_threads, search_threads)
+        .map_err(TantivySearchError::InvalidArgument)?;
+    ASYNC_POOLS.set(pools).map_err(|_| {
+        TantivySearchError::InternalError("Async pools have already been initialized".to_string())
+    })
+}
+
+fn async_pools() -> Result<&'static AsyncPools, TantivySearchError> {
+    ASYNC_POOLS
+        .get_or_try_init(|| {
+            let search_threads = std::thread::available_parallelism()
+                .map(|threads| threads.get())
+                .unwrap_or(4);
+            build_async_pools(DEFAULT_INDEX_THREADS, search_threads)
+        })
+        .map_err(TantivySearchError::InternalError)
+}
+
+fn spawn_on<T, F>(
+    pool: Result<&'static ThreadPool, TantivySearchError>,
+    task: F,
+) -> impl Future<Output = Result<T, TantivySearchError>>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, TantivySearchError> + Send + 'static,
+{
+    let (sender, receiver) = oneshot::channel();
+    let spawned = pool.map(|pool| {
+        pool.spawn(move || {
+            // Receiver may be dropped when future is cancelled.
+            let _ = sender.send(task());
+        })
+    });
+    async move {
+        spawned?;
+        receiver.await.map_err(|_| {
+            TantivySearchError::InternalError("Async task has been dropped".to_string())
+        })?
+    }
+}
+
+// Run blocking index task (commit, merge, ...) on index pool.
+pub(crate) fn spawn_index_task<T, F>(task: F) -> impl Future<Output = Result<T, TantivySearchError>>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, TantivySearchError> + Send + 'static,
+{
+    spawn_on(async_pools().map(|pools| &pools.index_pool), task)
+}
+
+// Run blocking search task on search pool.
+pub(crate) fn spawn_search_task<T, F>(
+    task: F,
+) -> impl Future<Output = Result<T, TantivySearchError>>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, TantivySearchError> + Send + 'static,
+{
+    spawn_on(async_pools().map(|pools| &pools.search_pool), task)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_async_pools() {
+        assert!(build_async_pools(0, 1).is_err());
+        assert!(build_async_pools(1, 0).is_err());
+        assert!(build_async_pools(1, 1).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_spawn_task() {
+        let result = spawn_index_task(|| Ok(std::thread::current().name().map(String::from))).await;
+        assert!(result
+            .unwrap()
+            .unwrap_or_default()
+            .starts_with("tantivy-ingest-async"));
+
+        let result: Result<u64, TantivySearchError> =
+            spawn_search_task(|| Err(TantivySearchError::InvalidArgument("test".to_string())))
+                .await;
+        assert!(matches!(
+            result,
+            Err(TantivySearchError::InvalidArgument(_))
+        ));
+    }
+}
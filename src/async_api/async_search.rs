@@ -0,0 +1,83 @@
+use super::async_pool::spawn_search_task;
+use crate::common::errors::TantivySearchError;
+use crate::ffi::RowIdWithScore;
+use crate::search::implements::api_clickhouse_impl::query_term_bitmap;
+use crate::search::implements::api_common_impl::load_index_reader;
+use crate::search::implements::api_dingo_impl::bm25_search_with_column_names;
+
+/// Load (or reload) index reader on search pool.
+pub async fn load_index_reader_async(index_path: &str) -> Result<bool, TantivySearchError> {
+    let index_path: String = index_path.to_string();
+    spawn_search_task(move || load_index_reader(&index_path)).await
+}
+
+/// Execute term query on search pool, returns u8 bitmap of matched row ids.
+pub async fn query_term_bitmap_async(
+    index_path: &str,
+    column_name: &str,
+    term: &str,
+) -> Result<Vec<u8>, TantivySearchError> {
+    let index_path: String = index_path.to_string();
+    let column_name: String = column_name.to_string();
+    let term: String = term.to_string();
+    spawn_search_task(move || query_term_bitmap(&index_path, &column_name, &term)).await
+}
+
+/// Execute BM25 search on search pool, empty `column_names` means all columns.
+pub async fn bm25_search_async(
+    index_path: &str,
+    sentence: &str,
+    topk: u32,
+    column_names: Vec<String>,
+    need_doc: bool,
+) -> Result<Vec<RowIdWithScore>, TantivySearchError> {
+    let index_path: String = index_path.to_string();
+    let sentence: String = sentence.to_string();
+    spawn_search_task(move || {
+        bm25_search_with_column_names(
+            &index_path,
+            &sentence,
+            topk,
+            &vec![],
+            false,
+            false,
+            0,
+            0,
+            need_doc,
+            &column_names,
+        )
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::tests::index_3column_docs_with_threads_merge;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_search_async() {
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        index_3column_docs_with_threads_merge(temp_directory_str);
+
+        assert!(
+            query_term_bitmap_async(temp_directory_str, "col1", "Ancient")
+                .await
+                .is_err()
+        );
+        assert!(load_index_reader_async(temp_directory_str).await.unwrap());
+
+        // Row 0 and row 4 contain `Ancient`.
+        let bitmap = query_term_bitmap_async(temp_directory_str, "col1", "Ancient")
+            .await
+            .unwrap();
+        assert_eq!(bitmap[0], 17);
+
+        let results = bm25_search_async(temp_directory_str, "Ancient", 10, vec![], false)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+    }
+}
@@ -0,0 +1,8 @@
+//! Async Rust API, blocking index and search calls are executed on dedicated pools.
+pub mod async_index;
+pub mod async_pool;
+pub mod async_search;
+
+pub use async_index::*;
+pub use async_pool::configure_async_pools;
+pub use async_search::*;
@@ -0,0 +1,17 @@
+//! Command line tool of tantivy_search, run `tantivy-search-cli help` for usage.
+use std::process::ExitCode;
+use tantivy_search::cli::{cli_app, run_command};
+
+fn main() -> ExitCode {
+    let matches = cli_app().get_matches();
+    match run_command(&matches) {
+        Ok(output) => {
+            println!("{}", output);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
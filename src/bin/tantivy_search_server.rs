@@ -0,0 +1,21 @@
+//! gRPC sidecar of tantivy_search, usage: `tantivy-search-server <config.json>`.
+use std::process::ExitCode;
+use tantivy_search::grpc_server::run_server;
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let config_path = match std::env::args().nth(1) {
+        Some(config_path) => config_path,
+        None => {
+            eprintln!("Usage: tantivy-search-server <config.json>");
+            return ExitCode::FAILURE;
+        }
+    };
+    match run_server(&config_path).await {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
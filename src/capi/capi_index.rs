@@ -0,0 +1,737 @@
+use super::capi_types::*;
+use crate::common::constants::{
+    BuildProgressCallback, CommitCallback, DiskSpaceCallback, MergeCallback, MergePolicyCallback,
+    ReindexFetchCallback, TokenizeCallback, BUILD_PROGRESS_CALLBACK, COMMIT_CALLBACK,
+    CUSTOM_TOKENIZERS, DISK_SPACE_CALLBACK, MERGE_CALLBACK, MERGE_POLICY_CALLBACK,
+};
+use crate::index::implements::api_index_impl::{
+    commit_index, create_index_with_parameter, delete_row_ids, free_index_writer,
+    get_allocator_stats, index_multi_column_docs, list_loaded_indexes, load_index_writer,
+    purge_allocator, set_thread_cpu_set, shutdown, update_index_settings, upgrade_index,
+};
+use crate::index::implements::reindex::{reindex_index, FetchColumns};
+use crate::search::implements::api_common_impl::free_index_reader;
+use crate::tokenizer::callback_tokenizer::CallbackTokenizer;
+use libc::{c_char, c_void};
+use serde_json::json;
+use std::ffi::{CStr, CString};
+
+/// Creates a handle of index directory, it doesn't load writer or reader.
+/// The handle must be released by `tantivy_search_index_close`.
+#[no_mangle]
+pub extern "C" fn tantivy_search_index_open(
+    index_path: *const c_char,
+    out_handle: *mut *mut IndexHandle,
+) -> ErrorCode {
+    capi_call("tantivy_search_index_open", || {
+        let index_path: String = c_str_to_string(index_path, "index_path")?;
+        if out_handle.is_null() {
+            return Err(invalid_argument("`out_handle` can't be nullptr"));
+        }
+        let handle = Box::new(IndexHandle { index_path });
+        unsafe { *out_handle = Box::into_raw(handle) };
+        Ok(())
+    })
+}
+
+/// Frees index writer and reader of this handle, and releases the handle itself.
+/// The handle is always released, even when an error is returned, it must not be closed again.
+#[no_mangle]
+pub extern "C" fn tantivy_search_index_close(handle: *mut IndexHandle) -> ErrorCode {
+    capi_call("tantivy_search_index_close", || {
+        if handle.is_null() {
+            return Ok(());
+        }
+        let handle: Box<IndexHandle> = unsafe { Box::from_raw(handle) };
+        // Reader is freed even if freeing writer fails, error of writer is reported first.
+        let writer_result = free_index_writer(&handle.index_path);
+        let reader_result = free_index_reader(&handle.index_path);
+        writer_result.and(reader_result).map_err(error_code_of)?;
+        Ok(())
+    })
+}
+
+/// Creates (or recreates) index with given columns, `index_json_parameter` can be nullptr.
+#[no_mangle]
+pub extern "C" fn tantivy_search_index_create(
+    handle: *const IndexHandle,
+    column_names: *const *const c_char,
+    column_count: usize,
+    index_json_parameter: *const c_char,
+) -> ErrorCode {
+    capi_call("tantivy_search_index_create", || {
+        let handle: &IndexHandle = handle_ref(handle)?;
+        let column_names: Vec<String> =
+            c_str_array_to_vec(column_names, column_count, "column_names")?;
+        let index_json_parameter: String = if index_json_parameter.is_null() {
+            "{}".to_string()
+        } else {
+            c_str_to_string(index_json_parameter, "index_json_parameter")?
+        };
+        create_index_with_parameter(&handle.index_path, &column_names, &index_json_parameter)
+            .map_err(error_code_of)?;
+        Ok(())
+    })
+}
+
+/// Loads index writer of an existing index.
+#[no_mangle]
+pub extern "C" fn tantivy_search_index_load_writer(handle: *const IndexHandle) -> ErrorCode {
+    capi_call("tantivy_search_index_load_writer", || {
+        let handle: &IndexHandle = handle_ref(handle)?;
+        load_index_writer(&handle.index_path).map_err(error_code_of)?;
+        Ok(())
+    })
+}
+
+/// Adds a document, `column_names` and `column_docs` must have the same `column_count`.
+#[no_mangle]
+pub extern "C" fn tantivy_search_index_add_document(
+    handle: *const IndexHandle,
+    row_id: u64,
+    column_names: *const *const c_char,
+    column_docs: *const *const c_char,
+    column_count: usize,
+) -> ErrorCode {
+    capi_call("tantivy_search_index_add_document", || {
+        let handle: &IndexHandle = handle_ref(handle)?;
+        let column_names: Vec<String> =
+            c_str_array_to_vec(column_names, column_count, "column_names")?;
+        let column_docs: Vec<String> =
+            c_str_array_to_vec(column_docs, column_count, "column_docs")?;
+        index_multi_column_docs(&handle.index_path, row_id, &column_names, &column_docs)
+            .map_err(error_code_of)?;
+        Ok(())
+    })
+}
+
+/// Deletes documents by row ids, deletion is committed immediately.
+#[no_mangle]
+pub extern "C" fn tantivy_search_index_delete_row_ids(
+    handle: *const IndexHandle,
+    row_ids: *const u64,
+    row_id_count: usize,
+) -> ErrorCode {
+    capi_call("tantivy_search_index_delete_row_ids", || {
+        let handle: &IndexHandle = handle_ref(handle)?;
+        if row_ids.is_null() && row_id_count > 0 {
+            return Err(invalid_argument("`row_ids` can't be nullptr"));
+        }
+        let row_ids: Vec<u64> = if row_id_count == 0 {
+            Vec::new()
+        } else {
+            unsafe { std::slice::from_raw_parts(row_ids, row_id_count) }.to_vec()
+        };
+        delete_row_ids(&handle.index_path, &row_ids).map_err(error_code_of)?;
+        Ok(())
+    })
+}
+
+/// Commits index writer, loaded reader will be reloaded.
+#[no_mangle]
+pub extern "C" fn tantivy_search_index_commit(handle: *const IndexHandle) -> ErrorCode {
+    capi_call("tantivy_search_index_commit", || {
+        let handle: &IndexHandle = handle_ref(handle)?;
+        commit_index(&handle.index_path).map_err(error_code_of)?;
+        Ok(())
+    })
+}
+
+/// Updates runtime settings of loaded writer and reader without reopening index.
+/// `settings_json` is like `{"slow_query_threshold_ms": 200, "auto_commit_interval_secs": 5}`.
+#[no_mangle]
+pub extern "C" fn tantivy_search_index_update_settings(
+    handle: *const IndexHandle,
+    settings_json: *const c_char,
+) -> ErrorCode {
+    capi_call("tantivy_search_index_update_settings", || {
+        let handle: &IndexHandle = handle_ref(handle)?;
+        let settings_json: String = c_str_to_string(settings_json, "settings_json")?;
+        update_index_settings(&handle.index_path, &settings_json).map_err(error_code_of)?;
+        Ok(())
+    })
+}
+
+/// Re-indexes the index in place with `index_json_parameter` as its new tokenizer settings,
+/// e.g. after users change analyzers of a table. Alive rows are indexed in row_id order into a
+/// sibling directory which is then swapped with the index, loaded writer and reader are reloaded.
+/// Rows are read from stored columns, or fetched from source by `fetch_callback` when it's not
+/// nullptr: it's invoked with (user_data, row_id, column_names, column_count, out_texts), host
+/// points `out_texts[i]` at nul-terminated text of column i, valid until the callback is invoked
+/// again, and returns 0.
+/// A positive status skips the row, a negative one fails re-indexing and keeps the old index.
+/// Rows indexed are written to `out_rows` if it's not nullptr.
+#[no_mangle]
+pub extern "C" fn tantivy_search_index_reindex(
+    handle: *const IndexHandle,
+    index_json_parameter: *const c_char,
+    fetch_callback: Option<ReindexFetchCallback>,
+    user_data: *mut c_void,
+    out_rows: *mut u64,
+) -> ErrorCode {
+    capi_call("tantivy_search_index_reindex", || {
+        let handle: &IndexHandle = handle_ref(handle)?;
+        let index_json_parameter: String =
+            c_str_to_string(index_json_parameter, "index_json_parameter")?;
+        let fetch = fetch_callback.map(|callback| {
+            move |row_id: u64, column_names: &[String]| {
+                fetch_reindex_columns(callback, user_data, row_id, column_names)
+            }
+        });
+        let fetch_columns: Option<&FetchColumns> =
+            fetch.as_ref().map(|fetch| fetch as &FetchColumns);
+        let rows: u64 = reindex_index(&handle.index_path, &index_json_parameter, fetch_columns)
+            .map_err(error_code_of)?;
+        if !out_rows.is_null() {
+            unsafe { *out_rows = rows };
+        }
+        Ok(())
+    })
+}
+
+// Fetch source texts of a row through host callback, `None` if host skips it.
+fn fetch_reindex_columns(
+    callback: ReindexFetchCallback,
+    user_data: *mut c_void,
+    row_id: u64,
+    column_names: &[String],
+) -> Result<Option<Vec<String>>, String> {
+    let column_names: Vec<CString> = column_names
+        .iter()
+        .map(|name| CString::new(name.as_str()).unwrap_or_default())
+        .collect();
+    let column_name_ptrs: Vec<*const c_char> =
+        column_names.iter().map(|name| name.as_ptr()).collect();
+    let mut texts: Vec<*const c_char> = vec![std::ptr::null(); column_names.len()];
+    let status: i32 = callback(
+        user_data,
+        row_id,
+        column_name_ptrs.as_ptr(),
+        column_name_ptrs.len(),
+        texts.as_mut_ptr(),
+    );
+    if status > 0 {
+        return Ok(None);
+    }
+    if status < 0 {
+        return Err(format!("fetch callback failed with status {}", status));
+    }
+    texts
+        .iter()
+        .map(|text| {
+            if text.is_null() {
+                return Ok(String::new());
+            }
+            // Safety: host keeps texts valid until the callback is invoked again.
+            unsafe { CStr::from_ptr(*text) }
+                .to_str()
+                .map(|text| text.to_string())
+                .map_err(|e| format!("fetched text is not valid utf-8: {}", e))
+        })
+        .collect::<Result<Vec<String>, String>>()
+        .map(Some)
+}
+
+/// Rewrites segments of an index written by an older version in current format and records
+/// current versions, a writer is loaded meanwhile if none is. Whether the index was upgraded is
+/// written to `out_upgraded` if it's not nullptr, false means it's up to date.
+/// Fails with `INCOMPATIBLE_INDEX_VERSION` if the index was written by a newer version.
+#[no_mangle]
+pub extern "C" fn tantivy_search_index_upgrade(
+    handle: *const IndexHandle,
+    out_upgraded: *mut bool,
+) -> ErrorCode {
+    capi_call("tantivy_search_index_upgrade", || {
+        let handle: &IndexHandle = handle_ref(handle)?;
+        let upgraded: bool = upgrade_index(&handle.index_path).map_err(error_code_of)?;
+        if !out_upgraded.is_null() {
+            unsafe { *out_upgraded = upgraded };
+        }
+        Ok(())
+    })
+}
+
+/// Lists every index held in writer and reader caches.
+/// Indexes are written to `out_buffer` as utf-8 json: `[{"index_path":"/data/a","num_docs":100, ...}, ...]`.
+#[no_mangle]
+pub extern "C" fn tantivy_search_list_indexes(out_buffer: *mut ByteBuffer) -> ErrorCode {
+    capi_call("tantivy_search_list_indexes", || {
+        if out_buffer.is_null() {
+            return Err(invalid_argument("`out_buffer` can't be nullptr"));
+        }
+        let indexes_json: String = list_loaded_indexes().map_err(error_code_of)?;
+        unsafe { *out_buffer = ByteBuffer::from_vec(indexes_json.into_bytes()) };
+        Ok(())
+    })
+}
+
+/// Shuts the library down on clean exit of host: background janitor is stopped, scheduled
+/// commits are waited for `timeout_millis` (0 waits forever), then all index writers and readers
+/// are freed and caches are cleared. `policy` is `commit` or `abort`, it decides whether pending
+/// writes are committed or discarded, nullptr commits them. Number of writers and readers freed
+/// is written to `out_freed` if it's not nullptr. Writers failed to commit are kept loaded, so
+/// shutdown can be retried with `abort`. Index handles stay valid and must still be closed.
+#[no_mangle]
+pub extern "C" fn tantivy_search_shutdown(
+    policy: *const c_char,
+    timeout_millis: u64,
+    out_freed: *mut u64,
+) -> ErrorCode {
+    capi_call("tantivy_search_shutdown", || {
+        let policy: String = if policy.is_null() {
+            "commit".to_string()
+        } else {
+            c_str_to_string(policy, "policy")?
+        };
+        let freed: u64 = shutdown(&policy, timeout_millis).map_err(error_code_of)?;
+        if !out_freed.is_null() {
+            unsafe { *out_freed = freed };
+        }
+        Ok(())
+    })
+}
+
+/// Reports memory held by the allocator of this library, `jemalloc` or `mimalloc` if it's built
+/// with the feature of the same name, counters an allocator doesn't report are 0.
+/// Stats are written to `out_buffer` as utf-8 json: `{"allocator":"jemalloc","allocated_bytes":1024,
+/// "active_bytes":4096,"resident_bytes":8192,"retained_bytes":0}`.
+#[no_mangle]
+pub extern "C" fn tantivy_search_allocator_stats(out_buffer: *mut ByteBuffer) -> ErrorCode {
+    capi_call("tantivy_search_allocator_stats", || {
+        if out_buffer.is_null() {
+            return Err(invalid_argument("`out_buffer` can't be nullptr"));
+        }
+        let stats = get_allocator_stats().map_err(error_code_of)?;
+        let stats_json: String = json!({
+            "allocator": stats.allocator,
+            "allocated_bytes": stats.allocated_bytes,
+            "active_bytes": stats.active_bytes,
+            "resident_bytes": stats.resident_bytes,
+            "retained_bytes": stats.retained_bytes,
+        })
+        .to_string();
+        unsafe { *out_buffer = ByteBuffer::from_vec(stats_json.into_bytes()) };
+        Ok(())
+    })
+}
+
+/// Pins internal threads to `cpus`, `cpu_count` 0 unpins them. Threads are named
+/// `tantivy-search-*`, `tantivy-ingest-*` and `tantivy-merge-*`, pinning is supported on
+/// linux only.
+#[no_mangle]
+pub extern "C" fn tantivy_search_set_thread_cpu_set(
+    cpus: *const u32,
+    cpu_count: usize,
+) -> ErrorCode {
+    capi_call("tantivy_search_set_thread_cpu_set", || {
+        if cpus.is_null() && cpu_count > 0 {
+            return Err(invalid_argument("`cpus` can't be nullptr"));
+        }
+        let cpus: Vec<u32> = if cpu_count == 0 {
+            Vec::new()
+        } else {
+            unsafe { std::slice::from_raw_parts(cpus, cpu_count) }.to_vec()
+        };
+        set_thread_cpu_set(&cpus).map_err(error_code_of)?;
+        Ok(())
+    })
+}
+
+/// Returns memory retained by the allocator of this library but not in use to the OS,
+/// e.g. after large merges.
+#[no_mangle]
+pub extern "C" fn tantivy_search_purge_allocator() -> ErrorCode {
+    capi_call("tantivy_search_purge_allocator", || {
+        purge_allocator().map_err(error_code_of)?;
+        Ok(())
+    })
+}
+
+/// Installs or replaces the commit callback, it's invoked after every successful commit
+/// with (index_path, opstamp, num_docs, segment_count), `num_docs` and `segment_count`
+/// describe committed searchable segments. The callback runs on the committing thread,
+/// auto commit threads included, so it should return quickly.
+#[no_mangle]
+pub extern "C" fn tantivy_search_set_commit_callback(callback: CommitCallback) -> ErrorCode {
+    capi_call("tantivy_search_set_commit_callback", || {
+        COMMIT_CALLBACK.set(callback);
+        Ok(())
+    })
+}
+
+/// Removes the commit callback.
+#[no_mangle]
+pub extern "C" fn tantivy_search_remove_commit_callback() -> ErrorCode {
+    capi_call("tantivy_search_remove_commit_callback", || {
+        COMMIT_CALLBACK.clear();
+        Ok(())
+    })
+}
+
+/// Installs or replaces the merge callback, it's invoked with
+/// (index_path, event, segment_count, num_docs), `event` is 0 for start, 1 for finish, 2 for failure.
+/// Background merges of uncommitted segments only report start, tantivy doesn't report
+/// their completion. The callback runs on merging or committing thread, it should return quickly.
+#[no_mangle]
+pub extern "C" fn tantivy_search_set_merge_callback(callback: MergeCallback) -> ErrorCode {
+    capi_call("tantivy_search_set_merge_callback", || {
+        MERGE_CALLBACK.set(callback);
+        Ok(())
+    })
+}
+
+/// Removes the merge callback.
+#[no_mangle]
+pub extern "C" fn tantivy_search_remove_merge_callback() -> ErrorCode {
+    capi_call("tantivy_search_remove_merge_callback", || {
+        MERGE_CALLBACK.clear();
+        Ok(())
+    })
+}
+
+/// Installs or replaces the disk space callback, it's invoked with
+/// (index_path, required_bytes, available_bytes) when a commit or merge is refused because its
+/// estimated footprint doesn't fit in free disk space. Refused explicit commits fail with
+/// `OutOfDiskSpace`, refused background merges are retried on next segment update.
+#[no_mangle]
+pub extern "C" fn tantivy_search_set_disk_space_callback(callback: DiskSpaceCallback) -> ErrorCode {
+    capi_call("tantivy_search_set_disk_space_callback", || {
+        DISK_SPACE_CALLBACK.set(callback);
+        Ok(())
+    })
+}
+
+/// Removes the disk space callback.
+#[no_mangle]
+pub extern "C" fn tantivy_search_remove_disk_space_callback() -> ErrorCode {
+    capi_call("tantivy_search_remove_disk_space_callback", || {
+        DISK_SPACE_CALLBACK.clear();
+        Ok(())
+    })
+}
+
+/// Installs or replaces the merge policy callback, background merges of all index writers are
+/// decided by host instead of builtin log merge policy, deterministic builds never merge in background.
+/// The callback is invoked with (index_path, segments, segment_count, merge_groups), host writes
+/// a group into `merge_groups[i]` for `segments[i]`, segments sharing a non-negative group are
+/// merged together, -1 (the initial value) keeps the segment. Merges are still throttled.
+#[no_mangle]
+pub extern "C" fn tantivy_search_set_merge_policy_callback(
+    callback: MergePolicyCallback,
+) -> ErrorCode {
+    capi_call("tantivy_search_set_merge_policy_callback", || {
+        MERGE_POLICY_CALLBACK.set(callback);
+        Ok(())
+    })
+}
+
+/// Removes the merge policy callback, builtin merge policy takes effect again.
+#[no_mangle]
+pub extern "C" fn tantivy_search_remove_merge_policy_callback() -> ErrorCode {
+    capi_call("tantivy_search_remove_merge_policy_callback", || {
+        MERGE_POLICY_CALLBACK.clear();
+        Ok(())
+    })
+}
+
+/// Registers or replaces a tokenizer implemented by host, columns configured with
+/// `{"type": "custom", "name": name}` use it. The callback is invoked with
+/// (user_data, text, text_len, sink, emit), host calls `emit(sink, offset_from, offset_to)` with
+/// byte offsets of each token in order and returns 0, a non-zero status skips the text.
+/// It may run on any indexing or search thread, `user_data` must stay valid until unregistered.
+/// Register it before indexes using it are created or loaded.
+#[no_mangle]
+pub extern "C" fn tantivy_search_register_tokenizer(
+    name: *const c_char,
+    callback: TokenizeCallback,
+    user_data: *mut c_void,
+) -> ErrorCode {
+    capi_call("tantivy_search_register_tokenizer", || {
+        let name: String = c_str_to_string(name, "name")?;
+        if name.is_empty() {
+            return Err(invalid_argument("`name` can't be empty"));
+        }
+        CUSTOM_TOKENIZERS.register(&name, CallbackTokenizer::new(callback, user_data));
+        Ok(())
+    })
+}
+
+/// Unregisters a host tokenizer, indexes already loaded keep using it.
+#[no_mangle]
+pub extern "C" fn tantivy_search_unregister_tokenizer(name: *const c_char) -> ErrorCode {
+    capi_call("tantivy_search_unregister_tokenizer", || {
+        let name: String = c_str_to_string(name, "name")?;
+        if !CUSTOM_TOKENIZERS.unregister(&name) {
+            return Err(invalid_argument(&format!(
+                "tokenizer `{}` is not registered",
+                name
+            )));
+        }
+        Ok(())
+    })
+}
+
+/// Installs or replaces the build progress callback, it's invoked with
+/// (index_path, docs_indexed, total_docs, bytes_indexed, eta_millis) while build progress is
+/// tracked, see `ffi_start_build_progress`. `eta_millis` is -1 if it can't be estimated.
+/// The callback runs on the indexing thread, it should return quickly.
+#[no_mangle]
+pub extern "C" fn tantivy_search_set_build_progress_callback(
+    callback: BuildProgressCallback,
+) -> ErrorCode {
+    capi_call("tantivy_search_set_build_progress_callback", || {
+        BUILD_PROGRESS_CALLBACK.set(callback);
+        Ok(())
+    })
+}
+
+/// Removes the build progress callback, progress can still be queried by `ffi_get_build_progress`.
+#[no_mangle]
+pub extern "C" fn tantivy_search_remove_build_progress_callback() -> ErrorCode {
+    capi_call("tantivy_search_remove_build_progress_callback", || {
+        BUILD_PROGRESS_CALLBACK.clear();
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::bridge::index_writer_merge_events::MergeEvent;
+    use crate::{FFI_INDEX_WRITER_CACHE, TEST_MUTEX};
+    use std::ffi::{CStr, CString};
+    use std::sync::Mutex;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_capi_index_lifecycle() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+        let temp_directory = TempDir::new().unwrap();
+        let index_path = CString::new(temp_directory.path().to_str().unwrap()).unwrap();
+
+        // Invalid arguments.
+        assert_eq!(
+            tantivy_search_index_open(std::ptr::null(), std::ptr::null_mut()),
+            ErrorCode::InvalidArgument
+        );
+        assert_eq!(
+            tantivy_search_index_commit(std::ptr::null()),
+            ErrorCode::InvalidArgument
+        );
+
+        let mut handle: *mut IndexHandle = std::ptr::null_mut();
+        assert_eq!(
+            tantivy_search_index_open(index_path.as_ptr(), &mut handle),
+            ErrorCode::Ok
+        );
+        assert!(!handle.is_null());
+
+        let column_names = vec![CString::new("col1").unwrap()];
+        let column_name_ptrs: Vec<*const c_char> =
+            column_names.iter().map(|name| name.as_ptr()).collect();
+        assert_eq!(
+            tantivy_search_index_create(handle, column_name_ptrs.as_ptr(), 1, std::ptr::null()),
+            ErrorCode::Ok
+        );
+
+        let doc = CString::new("Ancient empires rise and fall").unwrap();
+        let doc_ptrs: Vec<*const c_char> = vec![doc.as_ptr()];
+        assert_eq!(
+            tantivy_search_index_add_document(
+                handle,
+                0,
+                column_name_ptrs.as_ptr(),
+                doc_ptrs.as_ptr(),
+                1
+            ),
+            ErrorCode::Ok
+        );
+        assert_eq!(tantivy_search_index_commit(handle), ErrorCode::Ok);
+
+        let row_ids: Vec<u64> = vec![0];
+        assert_eq!(
+            tantivy_search_index_delete_row_ids(handle, row_ids.as_ptr(), row_ids.len()),
+            ErrorCode::Ok
+        );
+        assert_eq!(tantivy_search_index_close(handle), ErrorCode::Ok);
+    }
+
+    #[test]
+    fn test_capi_thread_cpu_set() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+        assert_eq!(
+            tantivy_search_set_thread_cpu_set(std::ptr::null(), 1),
+            ErrorCode::InvalidArgument
+        );
+        let cpus: Vec<u32> = vec![u32::MAX];
+        assert_eq!(
+            tantivy_search_set_thread_cpu_set(cpus.as_ptr(), cpus.len()),
+            ErrorCode::InvalidArgument
+        );
+        // Unpinned threads stay unpinned.
+        assert_eq!(
+            tantivy_search_set_thread_cpu_set(std::ptr::null(), 0),
+            ErrorCode::Ok
+        );
+    }
+
+    static COMMITS: Mutex<Vec<(String, u64, u64, u64)>> = Mutex::new(Vec::new());
+
+    extern "C" fn record_commit(
+        index_path: *const c_char,
+        opstamp: u64,
+        num_docs: u64,
+        segment_count: u64,
+    ) {
+        let index_path = unsafe { CStr::from_ptr(index_path) }
+            .to_string_lossy()
+            .to_string();
+        COMMITS
+            .lock()
+            .unwrap()
+            .push((index_path, opstamp, num_docs, segment_count));
+    }
+
+    #[test]
+    fn test_capi_commit_callback() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+        let temp_directory = TempDir::new().unwrap();
+        let index_path_str = temp_directory.path().to_str().unwrap();
+        let index_path = CString::new(index_path_str).unwrap();
+        let commits_of_index = || -> Vec<(String, u64, u64, u64)> {
+            COMMITS
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|commit| commit.0 == index_path_str)
+                .cloned()
+                .collect()
+        };
+
+        let mut handle: *mut IndexHandle = std::ptr::null_mut();
+        assert_eq!(
+            tantivy_search_index_open(index_path.as_ptr(), &mut handle),
+            ErrorCode::Ok
+        );
+        let column_names = vec![CString::new("col1").unwrap()];
+        let column_name_ptrs: Vec<*const c_char> =
+            column_names.iter().map(|name| name.as_ptr()).collect();
+        assert_eq!(
+            tantivy_search_index_create(handle, column_name_ptrs.as_ptr(), 1, std::ptr::null()),
+            ErrorCode::Ok
+        );
+        assert_eq!(
+            tantivy_search_set_commit_callback(record_commit),
+            ErrorCode::Ok
+        );
+
+        let doc = CString::new("Commit hooks notify the host").unwrap();
+        let doc_ptrs: Vec<*const c_char> = vec![doc.as_ptr()];
+        for row_id in 0..2u64 {
+            assert_eq!(
+                tantivy_search_index_add_document(
+                    handle,
+                    row_id,
+                    column_name_ptrs.as_ptr(),
+                    doc_ptrs.as_ptr(),
+                    1
+                ),
+                ErrorCode::Ok
+            );
+        }
+        assert_eq!(tantivy_search_index_commit(handle), ErrorCode::Ok);
+        let commits = commits_of_index();
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].2, 2);
+        assert!(commits[0].3 >= 1);
+
+        // Removed callback isn't invoked anymore.
+        assert_eq!(tantivy_search_remove_commit_callback(), ErrorCode::Ok);
+        assert_eq!(tantivy_search_index_commit(handle), ErrorCode::Ok);
+        assert_eq!(commits_of_index().len(), 1);
+        assert_eq!(tantivy_search_index_close(handle), ErrorCode::Ok);
+    }
+
+    static MERGES: Mutex<Vec<(String, i32, u64, u64)>> = Mutex::new(Vec::new());
+
+    extern "C" fn record_merge(
+        index_path: *const c_char,
+        event: i32,
+        segment_count: u64,
+        num_docs: u64,
+    ) {
+        let index_path = unsafe { CStr::from_ptr(index_path) }
+            .to_string_lossy()
+            .to_string();
+        MERGES
+            .lock()
+            .unwrap()
+            .push((index_path, event, segment_count, num_docs));
+    }
+
+    #[test]
+    fn test_capi_merge_callback() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+        let temp_directory = TempDir::new().unwrap();
+        let index_path_str = temp_directory.path().to_str().unwrap();
+        let index_path = CString::new(index_path_str).unwrap();
+
+        let mut handle: *mut IndexHandle = std::ptr::null_mut();
+        assert_eq!(
+            tantivy_search_index_open(index_path.as_ptr(), &mut handle),
+            ErrorCode::Ok
+        );
+        let column_names = vec![CString::new("col1").unwrap()];
+        let column_name_ptrs: Vec<*const c_char> =
+            column_names.iter().map(|name| name.as_ptr()).collect();
+        assert_eq!(
+            tantivy_search_index_create(handle, column_name_ptrs.as_ptr(), 1, std::ptr::null()),
+            ErrorCode::Ok
+        );
+        // Two commits produce two segments.
+        let doc = CString::new("Merge events are reported").unwrap();
+        let doc_ptrs: Vec<*const c_char> = vec![doc.as_ptr()];
+        for row_id in 0..2u64 {
+            assert_eq!(
+                tantivy_search_index_add_document(
+                    handle,
+                    row_id,
+                    column_name_ptrs.as_ptr(),
+                    doc_ptrs.as_ptr(),
+                    1
+                ),
+                ErrorCode::Ok
+            );
+            assert_eq!(tantivy_search_index_commit(handle), ErrorCode::Ok);
+        }
+
+        assert_eq!(
+            tantivy_search_set_merge_callback(record_merge),
+            ErrorCode::Ok
+        );
+        let index_writer_bridge = FFI_INDEX_WRITER_CACHE
+            .get_index_writer_bridge(index_path_str.to_string())
+            .unwrap();
+        let segment_ids = index_writer_bridge.index.searchable_segment_ids().unwrap();
+        assert_eq!(segment_ids.len(), 2);
+        assert!(index_writer_bridge.merge_segments(&segment_ids).is_ok());
+        drop(index_writer_bridge);
+        assert_eq!(tantivy_search_remove_merge_callback(), ErrorCode::Ok);
+
+        let merges: Vec<(i32, u64, u64)> = MERGES
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|merge| merge.0 == index_path_str)
+            .map(|merge| (merge.1, merge.2, merge.3))
+            .collect();
+        assert_eq!(
+            merges,
+            vec![
+                (MergeEvent::Start as i32, 2, 2),
+                (MergeEvent::Finish as i32, 2, 2)
+            ]
+        );
+        assert_eq!(tantivy_search_index_close(handle), ErrorCode::Ok);
+    }
+}
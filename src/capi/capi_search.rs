@@ -0,0 +1,559 @@
+use super::capi_types::*;
+use crate::common::constants::{QueryAuditCallback, QUERY_AUDIT};
+use crate::common::errors::TantivySearchError;
+use crate::common::query_cost_guard::QueryCostLimits;
+use crate::ffi::RowIdWithScore;
+use crate::search::implements::api_clickhouse_impl::{query_term_bitmap, regex_term_bitmap};
+use crate::search::implements::api_common_impl::{
+    analyze_text, bootstrap_indexes, configure_query_audit, free_index_reader, load_index_reader,
+    normalize_term, set_query_cost_limits,
+};
+use crate::search::implements::api_dingo_impl::bm25_search_with_column_names;
+use libc::c_char;
+use serde_json::json;
+
+fn write_buffer(out_buffer: *mut ByteBuffer, bytes: Vec<u8>) -> Result<(), ErrorCode> {
+    if out_buffer.is_null() {
+        return Err(invalid_argument("`out_buffer` can't be nullptr"));
+    }
+    unsafe { *out_buffer = ByteBuffer::from_vec(bytes) };
+    Ok(())
+}
+
+// Write `count` of results to `out_count`, results are only written to host buffers of
+// `capacity` values if they all fit, otherwise host can retry with buffers of `out_count` values.
+fn write_count(out_count: *mut usize, count: usize, capacity: usize) -> Result<(), ErrorCode> {
+    if out_count.is_null() {
+        return Err(invalid_argument("`out_count` can't be nullptr"));
+    }
+    unsafe { *out_count = count };
+    if count > capacity {
+        set_last_error(format!(
+            "{} results don't fit in buffer of capacity {}",
+            count, capacity
+        ));
+        return Err(ErrorCode::BufferTooSmall);
+    }
+    Ok(())
+}
+
+fn check_out_array<T>(out_array: *mut T, capacity: usize, name: &str) -> Result<(), ErrorCode> {
+    if capacity > 0 && out_array.is_null() {
+        return Err(invalid_argument(&format!("`{}` can't be nullptr", name)));
+    }
+    Ok(())
+}
+
+// Copy `values` into host array `out_array`, which holds at least `values.len()` values.
+fn copy_into<T: Copy>(values: impl ExactSizeIterator<Item = T>, out_array: *mut T) {
+    let len: usize = values.len();
+    if len == 0 {
+        return;
+    }
+    let out_array: &mut [T] = unsafe { std::slice::from_raw_parts_mut(out_array, len) };
+    for (out, value) in out_array.iter_mut().zip(values) {
+        *out = value;
+    }
+}
+
+/// Loads (or reloads) index reader, it's required before searching.
+#[no_mangle]
+pub extern "C" fn tantivy_search_index_load_reader(handle: *const IndexHandle) -> ErrorCode {
+    capi_call("tantivy_search_index_load_reader", || {
+        let handle: &IndexHandle = handle_ref(handle)?;
+        load_index_reader(&handle.index_path).map_err(error_code_of)?;
+        Ok(())
+    })
+}
+
+/// Frees index reader of this handle, the handle itself is still valid.
+#[no_mangle]
+pub extern "C" fn tantivy_search_index_free_reader(handle: *const IndexHandle) -> ErrorCode {
+    capi_call("tantivy_search_index_free_reader", || {
+        let handle: &IndexHandle = handle_ref(handle)?;
+        free_index_reader(&handle.index_path).map_err(error_code_of)?;
+        Ok(())
+    })
+}
+
+/// Loads readers of all indexes under `root_dir` after a restart, so first queries don't pay
+/// for loading them. Files of indexes committed within `hot_within_secs` are read to warm page
+/// cache, 0 warms nothing. `num_threads` 0 loads on available parallelism. Indexes failing
+/// validation are skipped, summary is written to `out_buffer` as utf-8 json:
+/// `{"discovered":3,"loaded":["/data/a", ...],"warmed":[...],"warmed_bytes":1024,"failed":[...]}`.
+#[no_mangle]
+pub extern "C" fn tantivy_search_bootstrap(
+    root_dir: *const c_char,
+    hot_within_secs: u64,
+    num_threads: usize,
+    out_buffer: *mut ByteBuffer,
+) -> ErrorCode {
+    capi_call("tantivy_search_bootstrap", || {
+        let root_dir: String = c_str_to_string(root_dir, "root_dir")?;
+        let summary: String =
+            bootstrap_indexes(&root_dir, hot_within_secs, num_threads).map_err(error_code_of)?;
+        write_buffer(out_buffer, summary.into_bytes())
+    })
+}
+
+/// Executes BM25 search over all columns.
+/// Results are written to `out_buffer` as utf-8 json: `[{"row_id":1,"score":0.5}, ...]`.
+#[no_mangle]
+pub extern "C" fn tantivy_search_index_search_bm25(
+    handle: *const IndexHandle,
+    query: *const c_char,
+    topk: u32,
+    out_buffer: *mut ByteBuffer,
+) -> ErrorCode {
+    capi_call("tantivy_search_index_search_bm25", || {
+        let handle: &IndexHandle = handle_ref(handle)?;
+        let query: String = c_str_to_string(query, "query")?;
+        let results: Vec<RowIdWithScore> = bm25_search_with_column_names(
+            &handle.index_path,
+            &query,
+            topk,
+            &vec![],
+            false,
+            false,
+            0,
+            0,
+            false,
+            &vec![],
+        )
+        .map_err(error_code_of)?;
+        let results: Vec<serde_json::Value> = results
+            .iter()
+            .map(|result| json!({"row_id": result.row_id, "score": result.score}))
+            .collect();
+        let bytes: Vec<u8> = serde_json::to_vec(&results).map_err(|e| {
+            error_code_of(TantivySearchError::InternalError(format!(
+                "Failed to serialize search results: {}",
+                e
+            )))
+        })?;
+        write_buffer(out_buffer, bytes)
+    })
+}
+
+/// Executes BM25 search over all columns, results are written to host allocated arrays,
+/// so hot query paths don't allocate and free buffers across the boundary.
+/// `out_row_ids` and `out_scores` hold `capacity` values, `out_scores` can be nullptr if scores
+/// aren't needed. `out_count` receives the number of results, ranked best first. If they exceed
+/// `capacity`, nothing is written and `BUFFER_TOO_SMALL` is returned, a capacity of `topk`
+/// always fits.
+#[no_mangle]
+pub extern "C" fn tantivy_search_index_search_bm25_into(
+    handle: *const IndexHandle,
+    query: *const c_char,
+    topk: u32,
+    out_row_ids: *mut u64,
+    out_scores: *mut f32,
+    capacity: usize,
+    out_count: *mut usize,
+) -> ErrorCode {
+    capi_call("tantivy_search_index_search_bm25_into", || {
+        let handle: &IndexHandle = handle_ref(handle)?;
+        let query: String = c_str_to_string(query, "query")?;
+        check_out_array(out_row_ids, capacity, "out_row_ids")?;
+        let results: Vec<RowIdWithScore> = bm25_search_with_column_names(
+            &handle.index_path,
+            &query,
+            topk,
+            &vec![],
+            false,
+            false,
+            0,
+            0,
+            false,
+            &vec![],
+        )
+        .map_err(error_code_of)?;
+        write_count(out_count, results.len(), capacity)?;
+        copy_into(results.iter().map(|result| result.row_id), out_row_ids);
+        if !out_scores.is_null() {
+            copy_into(results.iter().map(|result| result.score), out_scores);
+        }
+        Ok(())
+    })
+}
+
+/// Executes term query on a column.
+/// Results are written to `out_buffer` as u8 bitmap, bit `n` is set when row `n` matches.
+#[no_mangle]
+pub extern "C" fn tantivy_search_index_query_term_bitmap(
+    handle: *const IndexHandle,
+    column_name: *const c_char,
+    term: *const c_char,
+    out_buffer: *mut ByteBuffer,
+) -> ErrorCode {
+    capi_call("tantivy_search_index_query_term_bitmap", || {
+        let handle: &IndexHandle = handle_ref(handle)?;
+        let column_name: String = c_str_to_string(column_name, "column_name")?;
+        let term: String = c_str_to_string(term, "term")?;
+        let bitmap: Vec<u8> =
+            query_term_bitmap(&handle.index_path, &column_name, &term).map_err(error_code_of)?;
+        write_buffer(out_buffer, bitmap)
+    })
+}
+
+/// Executes regex query on a column, `pattern` is a LIKE pattern, e.g. `%ancient%`.
+/// Results are written to `out_buffer` as u8 bitmap, bit `n` is set when row `n` matches.
+#[no_mangle]
+pub extern "C" fn tantivy_search_index_regex_term_bitmap(
+    handle: *const IndexHandle,
+    column_name: *const c_char,
+    pattern: *const c_char,
+    out_buffer: *mut ByteBuffer,
+) -> ErrorCode {
+    capi_call("tantivy_search_index_regex_term_bitmap", || {
+        let handle: &IndexHandle = handle_ref(handle)?;
+        let column_name: String = c_str_to_string(column_name, "column_name")?;
+        let pattern: String = c_str_to_string(pattern, "pattern")?;
+        let bitmap: Vec<u8> =
+            regex_term_bitmap(&handle.index_path, &column_name, &pattern).map_err(error_code_of)?;
+        write_buffer(out_buffer, bitmap)
+    })
+}
+
+/// Executes term query on a column, the u8 bitmap of results is written to host allocated
+/// `out_bitmap` of `capacity` bytes, bit `n` is set when row `n` matches.
+/// `out_len` receives the bitmap length, if it exceeds `capacity` nothing is written and
+/// `BUFFER_TOO_SMALL` is returned.
+#[no_mangle]
+pub extern "C" fn tantivy_search_index_query_term_bitmap_into(
+    handle: *const IndexHandle,
+    column_name: *const c_char,
+    term: *const c_char,
+    out_bitmap: *mut u8,
+    capacity: usize,
+    out_len: *mut usize,
+) -> ErrorCode {
+    capi_call("tantivy_search_index_query_term_bitmap_into", || {
+        let handle: &IndexHandle = handle_ref(handle)?;
+        let column_name: String = c_str_to_string(column_name, "column_name")?;
+        let term: String = c_str_to_string(term, "term")?;
+        check_out_array(out_bitmap, capacity, "out_bitmap")?;
+        let bitmap: Vec<u8> =
+            query_term_bitmap(&handle.index_path, &column_name, &term).map_err(error_code_of)?;
+        write_count(out_len, bitmap.len(), capacity)?;
+        copy_into(bitmap.into_iter(), out_bitmap);
+        Ok(())
+    })
+}
+
+/// Executes regex query on a column, `pattern` is a LIKE pattern, e.g. `%ancient%`.
+/// Same as `tantivy_search_index_query_term_bitmap_into`, the bitmap is written to host
+/// allocated `out_bitmap`.
+#[no_mangle]
+pub extern "C" fn tantivy_search_index_regex_term_bitmap_into(
+    handle: *const IndexHandle,
+    column_name: *const c_char,
+    pattern: *const c_char,
+    out_bitmap: *mut u8,
+    capacity: usize,
+    out_len: *mut usize,
+) -> ErrorCode {
+    capi_call("tantivy_search_index_regex_term_bitmap_into", || {
+        let handle: &IndexHandle = handle_ref(handle)?;
+        let column_name: String = c_str_to_string(column_name, "column_name")?;
+        let pattern: String = c_str_to_string(pattern, "pattern")?;
+        check_out_array(out_bitmap, capacity, "out_bitmap")?;
+        let bitmap: Vec<u8> =
+            regex_term_bitmap(&handle.index_path, &column_name, &pattern).map_err(error_code_of)?;
+        write_count(out_len, bitmap.len(), capacity)?;
+        copy_into(bitmap.into_iter(), out_bitmap);
+        Ok(())
+    })
+}
+
+/// Runs analyzer of a field on `text`, `index_path_or_config` is an index directory or index json parameter.
+/// Tokens are written to `out_buffer` as utf-8 json:
+/// `[{"token":"hello","position":0,"offset_from":0,"offset_to":5}, ...]`.
+#[no_mangle]
+pub extern "C" fn tantivy_search_analyze(
+    index_path_or_config: *const c_char,
+    field: *const c_char,
+    text: *const c_char,
+    out_buffer: *mut ByteBuffer,
+) -> ErrorCode {
+    capi_call("tantivy_search_analyze", || {
+        let index_path_or_config: String =
+            c_str_to_string(index_path_or_config, "index_path_or_config")?;
+        let field: String = c_str_to_string(field, "field")?;
+        let text: String = c_str_to_string(text, "text")?;
+        let tokens = analyze_text(&index_path_or_config, &field, &text).map_err(error_code_of)?;
+        let tokens: Vec<serde_json::Value> = tokens
+            .iter()
+            .map(|token| {
+                json!({
+                    "token": token.token,
+                    "position": token.position,
+                    "offset_from": token.offset_from,
+                    "offset_to": token.offset_to
+                })
+            })
+            .collect();
+        let bytes: Vec<u8> = serde_json::to_vec(&tokens).map_err(|e| {
+            error_code_of(TantivySearchError::InternalError(format!(
+                "Failed to serialize analyzed tokens: {}",
+                e
+            )))
+        })?;
+        write_buffer(out_buffer, bytes)
+    })
+}
+
+/// Normalizes a raw query value the same way a field is indexed,
+/// the normalized term is written to `out_buffer` as utf-8 bytes.
+#[no_mangle]
+pub extern "C" fn tantivy_search_normalize_term(
+    index_path_or_config: *const c_char,
+    field: *const c_char,
+    value: *const c_char,
+    out_buffer: *mut ByteBuffer,
+) -> ErrorCode {
+    capi_call("tantivy_search_normalize_term", || {
+        let index_path_or_config: String =
+            c_str_to_string(index_path_or_config, "index_path_or_config")?;
+        let field: String = c_str_to_string(field, "field")?;
+        let value: String = c_str_to_string(value, "value")?;
+        let term: String =
+            normalize_term(&index_path_or_config, &field, &value).map_err(error_code_of)?;
+        write_buffer(out_buffer, term.into_bytes())
+    })
+}
+
+/// Installs or replaces the query audit callback, it's invoked with
+/// (timestamp_millis, index_path, principal, query, hits, latency_micros) for each search
+/// sampled by `tantivy_search_configure_query_audit`, `principal` is empty if none is bound.
+/// The callback runs on searching thread, it should return quickly.
+#[no_mangle]
+pub extern "C" fn tantivy_search_set_query_audit_callback(
+    callback: QueryAuditCallback,
+) -> ErrorCode {
+    capi_call("tantivy_search_set_query_audit_callback", || {
+        QUERY_AUDIT.callback().set(callback);
+        Ok(())
+    })
+}
+
+/// Removes the query audit callback.
+#[no_mangle]
+pub extern "C" fn tantivy_search_remove_query_audit_callback() -> ErrorCode {
+    capi_call("tantivy_search_remove_query_audit_callback", || {
+        QUERY_AUDIT.callback().clear();
+        Ok(())
+    })
+}
+
+/// Records `sample_rate` in [0, 1] of searches of all indexes into query audit log, rate 0
+/// disables auditing. Records are passed to the query audit callback and appended to
+/// `file_path` as json lines, `file_path` can be nullptr to keep no file.
+#[no_mangle]
+pub extern "C" fn tantivy_search_configure_query_audit(
+    file_path: *const c_char,
+    sample_rate: f64,
+) -> ErrorCode {
+    capi_call("tantivy_search_configure_query_audit", || {
+        let file_path: String = match file_path.is_null() {
+            true => String::new(),
+            false => c_str_to_string(file_path, "file_path")?,
+        };
+        configure_query_audit(&file_path, sample_rate).map_err(error_code_of)?;
+        Ok(())
+    })
+}
+
+/// Limits cost of queries of all indexes, 0 means unlimited. A query over any limit isn't
+/// executed, it fails with `QUERY_TOO_EXPENSIVE`. Limits are terms of a term set or parsed
+/// query, automaton states of a LIKE/regex pattern, terms a LIKE/regex pattern expands to in a
+/// segment, and row ids of a bitmap query result.
+#[no_mangle]
+pub extern "C" fn tantivy_search_set_query_cost_limits(
+    max_clause_count: u64,
+    max_regex_states: u64,
+    max_expanded_terms: u64,
+    max_bitmap_size: u64,
+) -> ErrorCode {
+    capi_call("tantivy_search_set_query_cost_limits", || {
+        set_query_cost_limits(QueryCostLimits {
+            max_clause_count,
+            max_regex_states,
+            max_expanded_terms,
+            max_bitmap_size,
+        })
+        .map_err(error_code_of)?;
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capi::capi_index::{tantivy_search_index_close, tantivy_search_index_open};
+    use crate::common::tests::index_3column_docs_with_threads_merge;
+    use crate::TEST_MUTEX;
+    use std::ffi::CString;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_capi_search() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        index_3column_docs_with_threads_merge(temp_directory_str);
+
+        let index_path = CString::new(temp_directory_str).unwrap();
+        let mut handle: *mut IndexHandle = std::ptr::null_mut();
+        assert_eq!(
+            tantivy_search_index_open(index_path.as_ptr(), &mut handle),
+            ErrorCode::Ok
+        );
+
+        // Reader is not loaded.
+        let column_name = CString::new("col1").unwrap();
+        let term = CString::new("Ancient").unwrap();
+        let mut buffer = ByteBuffer::empty();
+        assert_eq!(
+            tantivy_search_index_query_term_bitmap(
+                handle,
+                column_name.as_ptr(),
+                term.as_ptr(),
+                &mut buffer
+            ),
+            ErrorCode::InternalError
+        );
+
+        assert_eq!(tantivy_search_index_load_reader(handle), ErrorCode::Ok);
+        assert_eq!(
+            tantivy_search_index_query_term_bitmap(
+                handle,
+                column_name.as_ptr(),
+                term.as_ptr(),
+                &mut buffer
+            ),
+            ErrorCode::Ok
+        );
+        // Row 0 and row 4 contain `Ancient`.
+        assert_eq!(unsafe { *buffer.data }, 17);
+        tantivy_search_byte_buffer_free(buffer);
+
+        let pattern = CString::new("%ncien%").unwrap();
+        let mut buffer = ByteBuffer::empty();
+        assert_eq!(
+            tantivy_search_index_regex_term_bitmap(
+                handle,
+                column_name.as_ptr(),
+                pattern.as_ptr(),
+                &mut buffer
+            ),
+            ErrorCode::Ok
+        );
+        assert_eq!(unsafe { *buffer.data }, 17);
+        tantivy_search_byte_buffer_free(buffer);
+
+        let query = CString::new("Ancient").unwrap();
+        let mut buffer = ByteBuffer::empty();
+        assert_eq!(
+            tantivy_search_index_search_bm25(handle, query.as_ptr(), 10, &mut buffer),
+            ErrorCode::Ok
+        );
+        let json: &[u8] = unsafe { std::slice::from_raw_parts(buffer.data, buffer.len) };
+        let results: Vec<serde_json::Value> = serde_json::from_slice(json).unwrap();
+        assert_eq!(results.len(), 2);
+        tantivy_search_byte_buffer_free(buffer);
+
+        // Results written to host buffers.
+        let mut row_ids = [u64::MAX; 4];
+        let mut scores = [0f32; 4];
+        let mut count: usize = 0;
+        assert_eq!(
+            tantivy_search_index_search_bm25_into(
+                handle,
+                query.as_ptr(),
+                10,
+                row_ids.as_mut_ptr(),
+                scores.as_mut_ptr(),
+                row_ids.len(),
+                &mut count
+            ),
+            ErrorCode::Ok
+        );
+        assert_eq!(count, 2);
+        let mut found: Vec<u64> = row_ids[..count].to_vec();
+        found.sort();
+        assert_eq!(found, vec![0, 4]);
+        assert!(scores[0] >= scores[1] && scores[1] > 0.0);
+        assert_eq!(row_ids[2], u64::MAX);
+        // Nothing is written to a buffer too small, count tells the capacity required.
+        let mut row_id = [u64::MAX; 1];
+        assert_eq!(
+            tantivy_search_index_search_bm25_into(
+                handle,
+                query.as_ptr(),
+                10,
+                row_id.as_mut_ptr(),
+                std::ptr::null_mut(),
+                row_id.len(),
+                &mut count
+            ),
+            ErrorCode::BufferTooSmall
+        );
+        assert_eq!((count, row_id[0]), (2, u64::MAX));
+        assert_eq!(
+            tantivy_search_index_search_bm25_into(
+                handle,
+                query.as_ptr(),
+                10,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                4,
+                &mut count
+            ),
+            ErrorCode::InvalidArgument
+        );
+
+        let mut bitmap = [0u8; 8];
+        let mut len: usize = 0;
+        assert_eq!(
+            tantivy_search_index_query_term_bitmap_into(
+                handle,
+                column_name.as_ptr(),
+                term.as_ptr(),
+                bitmap.as_mut_ptr(),
+                bitmap.len(),
+                &mut len
+            ),
+            ErrorCode::Ok
+        );
+        assert!((1..=bitmap.len()).contains(&len));
+        assert_eq!(bitmap[0], 17);
+        let mut bitmap = [0u8; 8];
+        assert_eq!(
+            tantivy_search_index_regex_term_bitmap_into(
+                handle,
+                column_name.as_ptr(),
+                pattern.as_ptr(),
+                bitmap.as_mut_ptr(),
+                bitmap.len(),
+                &mut len
+            ),
+            ErrorCode::Ok
+        );
+        assert_eq!(bitmap[0], 17);
+        assert_eq!(
+            tantivy_search_index_regex_term_bitmap_into(
+                handle,
+                column_name.as_ptr(),
+                pattern.as_ptr(),
+                bitmap.as_mut_ptr(),
+                0,
+                &mut len
+            ),
+            ErrorCode::BufferTooSmall
+        );
+
+        assert_eq!(tantivy_search_index_free_reader(handle), ErrorCode::Ok);
+        assert_eq!(tantivy_search_index_close(handle), ErrorCode::Ok);
+    }
+}
@@ -0,0 +1,225 @@
+use crate::common::errors::{IndexSearcherError, TantivySearchError};
+use libc::c_char;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::mem::ManuallyDrop;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+/// Error codes returned by plain C API, detail can be fetched by `tantivy_search_last_error`.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    Ok = 0,
+    InvalidArgument = 1,
+    IndexNotExists = 2,
+    Backpressure = 3,
+    InternalError = 4,
+    OpenFilesBudgetExceeded = 5,
+    OutOfDiskSpace = 6,
+    IncompatibleIndexVersion = 7,
+    QueryTooExpensive = 8,
+    BufferTooSmall = 9,
+}
+
+impl From<&TantivySearchError> for ErrorCode {
+    fn from(error: &TantivySearchError) -> Self {
+        match error {
+            TantivySearchError::InvalidArgument(_) | TantivySearchError::CxxConvertError(_) => {
+                ErrorCode::InvalidArgument
+            }
+            TantivySearchError::IndexNotExists(_) => ErrorCode::IndexNotExists,
+            TantivySearchError::Backpressure(_) => ErrorCode::Backpressure,
+            TantivySearchError::OpenFilesBudgetExceeded(_) => ErrorCode::OpenFilesBudgetExceeded,
+            TantivySearchError::OutOfDiskSpace(_) => ErrorCode::OutOfDiskSpace,
+            TantivySearchError::IncompatibleIndexVersion(_) => ErrorCode::IncompatibleIndexVersion,
+            TantivySearchError::IndexSearcherError(IndexSearcherError::QueryTooExpensive(_)) => {
+                ErrorCode::QueryTooExpensive
+            }
+            _ => ErrorCode::InternalError,
+        }
+    }
+}
+
+/// Opaque handle of an index directory, created by `tantivy_search_index_open`.
+pub struct IndexHandle {
+    pub(crate) index_path: String,
+}
+
+/// Byte buffer allocated by this library, must be released by `tantivy_search_byte_buffer_free`.
+#[repr(C)]
+pub struct ByteBuffer {
+    pub data: *mut u8,
+    pub len: usize,
+    pub capacity: usize,
+}
+
+impl ByteBuffer {
+    pub fn empty() -> Self {
+        ByteBuffer {
+            data: std::ptr::null_mut(),
+            len: 0,
+            capacity: 0,
+        }
+    }
+
+    pub fn from_vec(bytes: Vec<u8>) -> Self {
+        let mut bytes = ManuallyDrop::new(bytes);
+        ByteBuffer {
+            data: bytes.as_mut_ptr(),
+            len: bytes.len(),
+            capacity: bytes.capacity(),
+        }
+    }
+}
+
+thread_local! {
+    // Last error message of current thread.
+    static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+pub(crate) fn set_last_error(message: String) {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = message);
+}
+
+pub(crate) fn clear_last_error() {
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = CString::default());
+}
+
+// Record error as last error of current thread, and convert it to error code.
+pub(crate) fn error_code_of(error: TantivySearchError) -> ErrorCode {
+    let error_code = ErrorCode::from(&error);
+    set_last_error(error.to_string());
+    error_code
+}
+
+pub(crate) fn invalid_argument(message: &str) -> ErrorCode {
+    set_last_error(message.to_string());
+    ErrorCode::InvalidArgument
+}
+
+// Convert nullable C string to Rust String.
+pub(crate) fn c_str_to_string(value: *const c_char, name: &str) -> Result<String, ErrorCode> {
+    if value.is_null() {
+        return Err(invalid_argument(&format!("`{}` can't be nullptr", name)));
+    }
+    match unsafe { CStr::from_ptr(value) }.to_str() {
+        Ok(value) => Ok(value.to_owned()),
+        Err(e) => Err(invalid_argument(&format!(
+            "`{}` is not valid utf-8: {}",
+            name, e
+        ))),
+    }
+}
+
+// Convert C string array to Rust Vec<String>.
+pub(crate) fn c_str_array_to_vec(
+    values: *const *const c_char,
+    count: usize,
+    name: &str,
+) -> Result<Vec<String>, ErrorCode> {
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+    if values.is_null() {
+        return Err(invalid_argument(&format!("`{}` can't be nullptr", name)));
+    }
+    let values: &[*const c_char] = unsafe { std::slice::from_raw_parts(values, count) };
+    values
+        .iter()
+        .map(|value| c_str_to_string(*value, name))
+        .collect()
+}
+
+// Run C API body, panics are caught here and never unwind into C callers.
+pub(crate) fn capi_call<F: FnOnce() -> Result<(), ErrorCode>>(
+    function: &str,
+    body: F,
+) -> ErrorCode {
+    clear_last_error();
+    match catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(())) => ErrorCode::Ok,
+        Ok(Err(error_code)) => error_code,
+        Err(_) => {
+            set_last_error(format!("Unexpected panic in `{}`", function));
+            ErrorCode::InternalError
+        }
+    }
+}
+
+pub(crate) fn handle_ref<'a>(handle: *const IndexHandle) -> Result<&'a IndexHandle, ErrorCode> {
+    if handle.is_null() {
+        return Err(invalid_argument("`handle` can't be nullptr"));
+    }
+    Ok(unsafe { &*handle })
+}
+
+/// Returns the last error message of current thread, it's valid until next C API call on the same thread.
+#[no_mangle]
+pub extern "C" fn tantivy_search_last_error() -> *const c_char {
+    LAST_ERROR.with(|last_error| last_error.borrow().as_ptr())
+}
+
+/// Releases a byte buffer returned by this library.
+#[no_mangle]
+pub extern "C" fn tantivy_search_byte_buffer_free(buffer: ByteBuffer) {
+    if buffer.data.is_null() {
+        return;
+    }
+    unsafe {
+        drop(Vec::from_raw_parts(
+            buffer.data,
+            buffer.len,
+            buffer.capacity,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_code_of() {
+        let code = error_code_of(TantivySearchError::IndexNotExists("/tmp/none".to_string()));
+        assert_eq!(code, ErrorCode::IndexNotExists);
+        let message = unsafe { CStr::from_ptr(tantivy_search_last_error()) };
+        assert!(message.to_str().unwrap().contains("/tmp/none"));
+        clear_last_error();
+        let message = unsafe { CStr::from_ptr(tantivy_search_last_error()) };
+        assert!(message.to_str().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_c_str_array_to_vec() {
+        let values = vec![CString::new("col1").unwrap(), CString::new("col2").unwrap()];
+        let pointers: Vec<*const c_char> = values.iter().map(|value| value.as_ptr()).collect();
+        let result = c_str_array_to_vec(pointers.as_ptr(), pointers.len(), "columns").unwrap();
+        assert_eq!(result, vec!["col1".to_string(), "col2".to_string()]);
+        assert_eq!(
+            c_str_array_to_vec(std::ptr::null(), 1, "columns").unwrap_err(),
+            ErrorCode::InvalidArgument
+        );
+    }
+
+    #[test]
+    fn test_capi_call_catch_panic() {
+        assert_eq!(capi_call("ok", || Ok(())), ErrorCode::Ok);
+        assert_eq!(
+            capi_call("invalid", || Err(invalid_argument("invalid"))),
+            ErrorCode::InvalidArgument
+        );
+        assert_eq!(
+            capi_call("panic", || panic!("test panic")),
+            ErrorCode::InternalError
+        );
+    }
+
+    #[test]
+    fn test_byte_buffer() {
+        let buffer = ByteBuffer::from_vec(vec![1u8, 2, 3]);
+        assert_eq!(buffer.len, 3);
+        tantivy_search_byte_buffer_free(buffer);
+        tantivy_search_byte_buffer_free(ByteBuffer::empty());
+    }
+}
@@ -0,0 +1,3 @@
+pub mod capi_index;
+pub mod capi_search;
+pub mod capi_types;
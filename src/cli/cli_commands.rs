@@ -0,0 +1,213 @@
+use crate::common::constants::{FFI_INDEX_SEARCHER_CACHE, FFI_INDEX_WRITER_CACHE};
+use crate::index::implements::api_index_impl::{
+    free_index_writer, get_segment_delete_stats, load_index_writer,
+};
+use crate::search::implements::api_common_impl::{
+    free_index_reader, get_index_json_parameter, get_index_meta_json, get_indexed_doc_counts,
+    load_index_reader,
+};
+use crate::search::implements::api_dingo_impl::{bm25_search_with_column_names, get_doc_freq};
+use clap::ArgMatches;
+use std::fmt::Write;
+
+/// Execute a parsed subcommand, returns text to be printed.
+pub fn run_command(matches: &ArgMatches) -> Result<String, String> {
+    match matches.subcommand() {
+        ("inspect", Some(args)) => inspect(index_path_of(args)),
+        ("search", Some(args)) => {
+            let topk: u32 = args
+                .value_of("topk")
+                .unwrap_or("10")
+                .parse()
+                .map_err(|e| format!("Invalid `topk`: {}", e))?;
+            let column_names: Vec<String> = args
+                .values_of("columns")
+                .map(|values| values.map(|value| value.to_string()).collect())
+                .unwrap_or_default();
+            search(
+                index_path_of(args),
+                args.value_of("sentence").unwrap_or_default(),
+                topk,
+                &column_names,
+            )
+        }
+        ("terms", Some(args)) => terms(
+            index_path_of(args),
+            args.value_of("sentence").unwrap_or_default(),
+        ),
+        ("validate", Some(args)) => validate(index_path_of(args)),
+        ("merge", Some(args)) => merge(index_path_of(args)),
+        (command, _) => Err(format!("Unknown command `{}`", command)),
+    }
+}
+
+fn index_path_of<'a>(args: &'a ArgMatches) -> &'a str {
+    args.value_of("index_path").unwrap_or_default()
+}
+
+// Run `body` with index reader loaded, reader is always freed after `body`.
+fn with_index_reader<F>(index_path: &str, body: F) -> Result<String, String>
+where
+    F: FnOnce() -> Result<String, String>,
+{
+    load_index_reader(index_path).map_err(|e| e.to_string())?;
+    let result = body();
+    let _ = free_index_reader(index_path);
+    result
+}
+
+pub fn inspect(index_path: &str) -> Result<String, String> {
+    with_index_reader(index_path, || {
+        let mut output = String::new();
+        let parameter = get_index_json_parameter(index_path).map_err(|e| e.to_string())?;
+        let doc_counts = get_indexed_doc_counts(index_path).map_err(|e| e.to_string())?;
+        let segment_delete_stats =
+            get_segment_delete_stats(index_path).map_err(|e| e.to_string())?;
+        let _ = writeln!(output, "index_path: {}", index_path);
+        let _ = writeln!(output, "parameter: {}", parameter);
+        let _ = writeln!(output, "doc_counts: {}", doc_counts);
+        let _ = writeln!(output, "segments: {}", segment_delete_stats.len());
+        for stat in segment_delete_stats.iter() {
+            let _ = writeln!(
+                output,
+                "  {} max_doc:{} deleted:{} deleted_ratio:{:.3}",
+                stat.segment_id, stat.max_doc, stat.num_deleted_docs, stat.deleted_ratio
+            );
+        }
+        Ok(output.trim_end().to_string())
+    })
+}
+
+pub fn search(
+    index_path: &str,
+    sentence: &str,
+    topk: u32,
+    column_names: &Vec<String>,
+) -> Result<String, String> {
+    with_index_reader(index_path, || {
+        let results = bm25_search_with_column_names(
+            index_path,
+            sentence,
+            topk,
+            &vec![],
+            false,
+            false,
+            0,
+            0,
+            true,
+            column_names,
+        )
+        .map_err(|e| e.to_string())?;
+        let mut output = String::new();
+        for result in results.iter() {
+            let _ = writeln!(
+                output,
+                "row_id:{} score:{:.4} docs:{:?}",
+                result.row_id, result.score, result.docs
+            );
+        }
+        let _ = write!(output, "{} results", results.len());
+        Ok(output)
+    })
+}
+
+pub fn terms(index_path: &str, sentence: &str) -> Result<String, String> {
+    with_index_reader(index_path, || {
+        let doc_with_freqs = get_doc_freq(index_path, sentence).map_err(|e| e.to_string())?;
+        let mut output = String::new();
+        for doc_with_freq in doc_with_freqs.iter() {
+            let _ = writeln!(
+                output,
+                "term:{} field_id:{} doc_freq:{}",
+                doc_with_freq.term_str, doc_with_freq.field_id, doc_with_freq.doc_freq
+            );
+        }
+        Ok(output.trim_end().to_string())
+    })
+}
+
+pub fn validate(index_path: &str) -> Result<String, String> {
+    with_index_reader(index_path, || {
+        get_index_meta_json(index_path).map_err(|e| e.to_string())?;
+        let index_reader_bridge =
+            FFI_INDEX_SEARCHER_CACHE.get_index_reader_bridge(index_path.to_string())?;
+        let corrupted_files = index_reader_bridge
+            .index
+            .validate_checksum()
+            .map_err(|e| format!("Failed to validate checksum: {}", e))?;
+        if !corrupted_files.is_empty() {
+            return Err(format!("Corrupted files: {:?}", corrupted_files));
+        }
+        Ok(format!("{} is valid", index_path))
+    })
+}
+
+pub fn merge(index_path: &str) -> Result<String, String> {
+    load_index_writer(index_path).map_err(|e| e.to_string())?;
+    let result = merge_all_segments(index_path);
+    // Waits merging threads before writer released.
+    let _ = free_index_writer(index_path);
+    result
+}
+
+fn merge_all_segments(index_path: &str) -> Result<String, String> {
+    let index_writer_bridge =
+        FFI_INDEX_WRITER_CACHE.get_index_writer_bridge(index_path.to_string())?;
+    let segment_ids = index_writer_bridge
+        .index
+        .searchable_segment_ids()
+        .map_err(|e| format!("Failed to load segment ids: {}", e))?;
+    if segment_ids.len() < 2 {
+        return Ok(format!("{} segments, nothing to merge", segment_ids.len()));
+    }
+    index_writer_bridge.merge_segments(&segment_ids)?;
+    Ok(format!("Merged {} segments into one", segment_ids.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::cli_app;
+    use crate::common::tests::{create_3column_names, index_3column_docs_with_index_writer_bridge};
+    use crate::index::implements::api_index_impl::create_index;
+    use crate::TEST_MUTEX;
+    use tempfile::TempDir;
+
+    fn run(args: Vec<&str>) -> Result<String, String> {
+        run_command(&cli_app().get_matches_from(args))
+    }
+
+    #[test]
+    fn test_cli_commands() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        assert!(create_index(temp_directory_str, &create_3column_names()).unwrap());
+        index_3column_docs_with_index_writer_bridge(temp_directory_str, true);
+        assert!(free_index_writer(temp_directory_str).unwrap());
+
+        let output = run(vec!["cli", "inspect", temp_directory_str]).unwrap();
+        assert!(output.contains("doc_counts: 5"));
+
+        let output = run(vec![
+            "cli",
+            "search",
+            temp_directory_str,
+            "Ancient",
+            "--topk",
+            "3",
+            "--columns",
+            "col1,col2",
+        ])
+        .unwrap();
+        assert!(output.ends_with("2 results"));
+
+        let output = run(vec!["cli", "terms", temp_directory_str, "Ancient"]).unwrap();
+        assert!(output.contains("doc_freq:2"));
+
+        assert!(run(vec!["cli", "validate", temp_directory_str]).is_ok());
+        assert!(run(vec!["cli", "merge", temp_directory_str]).is_ok());
+        assert!(run(vec!["cli", "validate", temp_directory_str]).is_ok());
+        assert!(run(vec!["cli", "inspect", "/not/exists"]).is_err());
+    }
+}
@@ -0,0 +1,60 @@
+//! `tantivy-search-cli`, inspect and query an index directory on disk.
+pub mod cli_commands;
+
+use clap::{App, AppSettings, Arg, SubCommand};
+pub use cli_commands::run_command;
+
+fn index_path_arg() -> Arg<'static, 'static> {
+    Arg::with_name("index_path")
+        .help("Index directory")
+        .required(true)
+        .index(1)
+}
+
+pub fn cli_app() -> App<'static, 'static> {
+    App::new("tantivy-search-cli")
+        .about("Inspect and query tantivy_search index directories")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(
+            SubCommand::with_name("inspect")
+                .about("Print index parameter, doc counts and segments")
+                .arg(index_path_arg()),
+        )
+        .subcommand(
+            SubCommand::with_name("search")
+                .about("Run BM25 search")
+                .arg(index_path_arg())
+                .arg(Arg::with_name("sentence").required(true).index(2))
+                .arg(
+                    Arg::with_name("topk")
+                        .short("k")
+                        .long("topk")
+                        .takes_value(true)
+                        .default_value("10"),
+                )
+                .arg(
+                    Arg::with_name("columns")
+                        .short("c")
+                        .long("columns")
+                        .help("Comma separated column names, default all columns")
+                        .takes_value(true)
+                        .use_delimiter(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("terms")
+                .about("Print doc freq of each term tokenized from sentence")
+                .arg(index_path_arg())
+                .arg(Arg::with_name("sentence").required(true).index(2)),
+        )
+        .subcommand(
+            SubCommand::with_name("validate")
+                .about("Verify index can be loaded and files checksum")
+                .arg(index_path_arg()),
+        )
+        .subcommand(
+            SubCommand::with_name("merge")
+                .about("Merge all segments into one")
+                .arg(index_path_arg()),
+        )
+}
@@ -0,0 +1,151 @@
+use crate::ffi::AllocatorStats;
+
+// Allocations of this library are served by jemalloc or mimalloc when one of the features is
+// enabled, host allocations are not affected. Allocator symbols are prefixed, they don't
+// interpose `malloc` of host process.
+#[cfg(all(feature = "jemalloc", feature = "mimalloc"))]
+compile_error!("features `jemalloc` and `mimalloc` can't be enabled together");
+
+#[cfg(feature = "jemalloc")]
+#[global_allocator]
+static GLOBAL_ALLOCATOR: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
+
+#[cfg(feature = "mimalloc")]
+#[global_allocator]
+static GLOBAL_ALLOCATOR: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+/// Reports and releases memory held by the allocator of this library. The default allocator
+/// keeps gigabytes freed by large merges mapped inside host process, purging returns memory
+/// which isn't in use to the OS.
+pub struct AllocatorMemory;
+
+#[cfg(feature = "jemalloc")]
+impl AllocatorMemory {
+    pub fn name() -> &'static str {
+        "jemalloc"
+    }
+
+    pub fn stats() -> Result<AllocatorStats, String> {
+        use tikv_jemalloc_ctl::{epoch, stats};
+        // Statistics are cached by jemalloc until epoch is advanced.
+        epoch::advance().map_err(|e| format!("Failed to refresh jemalloc stats: {}", e))?;
+        let read = |value: Result<usize, tikv_jemalloc_ctl::Error>| {
+            value
+                .map(|bytes| bytes as u64)
+                .map_err(|e| format!("Failed to read jemalloc stats: {}", e))
+        };
+        Ok(AllocatorStats {
+            allocator: Self::name().to_string(),
+            allocated_bytes: read(stats::allocated::read())?,
+            active_bytes: read(stats::active::read())?,
+            resident_bytes: read(stats::resident::read())?,
+            retained_bytes: read(stats::retained::read())?,
+        })
+    }
+
+    pub fn purge() -> Result<(), String> {
+        // Purge dirty pages of all arenas.
+        let name: String = format!("arena.{}.purge\0", tikv_jemalloc_sys::MALLCTL_ARENAS_ALL);
+        let code: libc::c_int = unsafe {
+            tikv_jemalloc_sys::mallctl(
+                name.as_ptr() as *const libc::c_char,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                0,
+            )
+        };
+        if code != 0 {
+            return Err(format!(
+                "Failed to purge jemalloc arenas, error code {}",
+                code
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "mimalloc")]
+impl AllocatorMemory {
+    pub fn name() -> &'static str {
+        "mimalloc"
+    }
+
+    pub fn stats() -> Result<AllocatorStats, String> {
+        let mut elapsed_msecs: usize = 0;
+        let mut user_msecs: usize = 0;
+        let mut system_msecs: usize = 0;
+        let mut current_rss: usize = 0;
+        let mut peak_rss: usize = 0;
+        let mut current_commit: usize = 0;
+        let mut peak_commit: usize = 0;
+        let mut page_faults: usize = 0;
+        unsafe {
+            libmimalloc_sys::mi_process_info(
+                &mut elapsed_msecs,
+                &mut user_msecs,
+                &mut system_msecs,
+                &mut current_rss,
+                &mut peak_rss,
+                &mut current_commit,
+                &mut peak_commit,
+                &mut page_faults,
+            );
+        }
+        // mimalloc doesn't count allocated bytes without its statistics build.
+        Ok(AllocatorStats {
+            allocator: Self::name().to_string(),
+            allocated_bytes: 0,
+            active_bytes: current_commit as u64,
+            resident_bytes: current_rss as u64,
+            retained_bytes: 0,
+        })
+    }
+
+    pub fn purge() -> Result<(), String> {
+        unsafe { libmimalloc_sys::mi_collect(true) };
+        Ok(())
+    }
+}
+
+#[cfg(not(any(feature = "jemalloc", feature = "mimalloc")))]
+impl AllocatorMemory {
+    pub fn name() -> &'static str {
+        "system"
+    }
+
+    /// System allocator doesn't report statistics, all counters are 0.
+    pub fn stats() -> Result<AllocatorStats, String> {
+        Ok(AllocatorStats {
+            allocator: Self::name().to_string(),
+            allocated_bytes: 0,
+            active_bytes: 0,
+            resident_bytes: 0,
+            retained_bytes: 0,
+        })
+    }
+
+    pub fn purge() -> Result<(), String> {
+        // glibc keeps freed memory of its heap, only trimming returns it to the OS.
+        #[cfg(all(target_os = "linux", target_env = "gnu"))]
+        unsafe {
+            libc::malloc_trim(0);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allocator_memory() {
+        let stats: AllocatorStats = AllocatorMemory::stats().unwrap();
+        assert_eq!(stats.allocator, AllocatorMemory::name());
+        assert!(stats.active_bytes >= stats.allocated_bytes);
+        let buffer: Vec<u8> = vec![1u8; 64 << 20];
+        drop(buffer);
+        assert!(AllocatorMemory::purge().is_ok());
+    }
+}
@@ -0,0 +1,138 @@
+use std::sync::{Arc, RwLock};
+
+use rayon::prelude::*;
+use rayon::ThreadPool;
+use roaring::{MultiOps, RoaringBitmap};
+
+use crate::common::thread_affinity::{thread_pool_builder, ThreadKind};
+
+// Default threads are bounded, merging is short and shares cores with searches.
+const DEFAULT_BITMAP_UNION_MAX_THREADS: usize = 4;
+// Fewer bitmaps are merged on calling thread, parallel merge doesn't pay off for them.
+const PARALLEL_UNION_MIN_BITMAPS: usize = 16;
+
+/// Process wide pool merging per-segment row_id bitmaps of bitmap queries, merging dominates
+/// queries over indexes of 100+ segments. Bitmaps are split into one chunk per thread, chunks
+/// are merged in parallel and then merged together.
+pub struct BitmapUnionPool {
+    // `None` merges bitmaps on calling thread.
+    pool: RwLock<Option<Arc<ThreadPool>>>,
+}
+
+fn build_pool(num_threads: usize) -> Result<Option<Arc<ThreadPool>>, String> {
+    if num_threads == 0 {
+        return Ok(None);
+    }
+    thread_pool_builder(ThreadKind::Search, "union")
+        .num_threads(num_threads)
+        .build()
+        .map(|pool| Some(Arc::new(pool)))
+        .map_err(|e| format!("Failed to build bitmap union pool: {}", e))
+}
+
+impl BitmapUnionPool {
+    pub fn new() -> Self {
+        let num_threads: usize = std::thread::available_parallelism()
+            .map(|threads| threads.get())
+            .unwrap_or(1)
+            .min(DEFAULT_BITMAP_UNION_MAX_THREADS);
+        BitmapUnionPool {
+            pool: RwLock::new(build_pool(num_threads).unwrap_or(None)),
+        }
+    }
+
+    pub fn num_threads(&self) -> usize {
+        match self.pool.read() {
+            Ok(pool) => pool
+                .as_ref()
+                .map(|pool| pool.current_num_threads())
+                .unwrap_or(0),
+            Err(_) => 0,
+        }
+    }
+
+    /// Replace pool with one of `num_threads`, 0 merges bitmaps on calling thread.
+    /// Merges running on previous pool are finished by it.
+    pub fn set_num_threads(&self, num_threads: usize) -> Result<(), String> {
+        let pool: Option<Arc<ThreadPool>> = build_pool(num_threads)?;
+        *self
+            .pool
+            .write()
+            .map_err(|e| format!("Lock error: {}", e))? = pool;
+        Ok(())
+    }
+
+    /// Union of `bitmaps`, containers of the same key are merged into the largest bitmap.
+    pub fn union(&self, bitmaps: Vec<RoaringBitmap>) -> RoaringBitmap {
+        let pool: Option<Arc<ThreadPool>> = match self.pool.read() {
+            Ok(pool) => pool.clone(),
+            Err(_) => None,
+        };
+        let pool: Arc<ThreadPool> = match pool {
+            Some(pool)
+                if pool.current_num_threads() > 1
+                    && bitmaps.len() >= PARALLEL_UNION_MIN_BITMAPS =>
+            {
+                pool
+            }
+            _ => return bitmaps.union(),
+        };
+        let chunk_size: usize = bitmaps.len().div_ceil(pool.current_num_threads());
+        let mut chunks: Vec<Vec<RoaringBitmap>> = Vec::new();
+        for (idx, bitmap) in bitmaps.into_iter().enumerate() {
+            if idx % chunk_size == 0 {
+                chunks.push(Vec::with_capacity(chunk_size));
+            }
+            if let Some(chunk) = chunks.last_mut() {
+                chunk.push(bitmap);
+            }
+        }
+        pool.install(|| {
+            chunks
+                .into_par_iter()
+                .map(|chunk| chunk.union())
+                .collect::<Vec<RoaringBitmap>>()
+        })
+        .union()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bitmap_union_pool() {
+        // Overlapping bitmaps, each of them has sparse values and a run.
+        let bitmaps = || {
+            (0..100u32)
+                .map(|idx| {
+                    let mut bitmap = RoaringBitmap::new();
+                    bitmap.insert(idx * 100_000);
+                    bitmap.insert_range(idx * 1000..idx * 1000 + 2000);
+                    bitmap
+                })
+                .collect::<Vec<RoaringBitmap>>()
+        };
+        let union_of = |count: usize| {
+            let mut expected = RoaringBitmap::new();
+            for bitmap in bitmaps().into_iter().take(count) {
+                expected |= bitmap;
+            }
+            expected
+        };
+
+        let pool = BitmapUnionPool::new();
+        for num_threads in [0, 1, 3] {
+            assert!(pool.set_num_threads(num_threads).is_ok());
+            assert_eq!(pool.num_threads(), num_threads);
+            assert_eq!(pool.union(bitmaps()), union_of(100));
+            // Few bitmaps are merged on calling thread.
+            assert_eq!(
+                pool.union(bitmaps().into_iter().take(5).collect()),
+                union_of(5)
+            );
+        }
+        assert!(pool.union(Vec::new()).is_empty());
+    }
+}
@@ -0,0 +1,179 @@
+use crate::common::constants::{
+    BuildProgressCallback, CommitCallback, DiskSpaceCallback, LogCallback, MergeCallback,
+    MergePolicyCallback, QueryAuditCallback,
+};
+use std::marker::PhantomData;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+/// Host callback function pointer types which can be held by `CallbackCell`.
+pub trait CallbackFn: Copy {
+    fn into_raw(self) -> *mut ();
+
+    /// # Safety
+    /// `raw` must be a non-null pointer returned by `into_raw` of the same type.
+    unsafe fn from_raw(raw: *mut ()) -> Self;
+}
+
+// Only function pointer types may implement `CallbackFn`, the casts below don't compile for others.
+macro_rules! callback_fn {
+    ($($callback:ty),* $(,)?) => {
+        $(
+            impl CallbackFn for $callback {
+                fn into_raw(self) -> *mut () {
+                    self as *mut ()
+                }
+
+                unsafe fn from_raw(raw: *mut ()) -> Self {
+                    std::mem::transmute::<*mut (), $callback>(raw)
+                }
+            }
+        )*
+    };
+}
+
+callback_fn!(
+    LogCallback,
+    CommitCallback,
+    BuildProgressCallback,
+    MergeCallback,
+    MergePolicyCallback,
+    DiskSpaceCallback,
+    QueryAuditCallback,
+);
+
+/// Holds a host callback, it can be installed, replaced and removed at runtime.
+///
+/// The callback is a plain function pointer stored in an `AtomicPtr`, so swapping it
+/// is a single atomic operation. Readers copy the pointer out before calling it, and a
+/// function pointer owns no memory, so a replaced callback needs no deferred reclamation.
+/// Each callback kind adds its own `notify` to its cell.
+pub struct CallbackCell<F: CallbackFn> {
+    callback: AtomicPtr<()>,
+    _callback: PhantomData<F>,
+}
+
+impl<F: CallbackFn> CallbackCell<F> {
+    pub const fn new() -> Self {
+        CallbackCell {
+            callback: AtomicPtr::new(ptr::null_mut()),
+            _callback: PhantomData,
+        }
+    }
+
+    fn to_callback(raw: *mut ()) -> Option<F> {
+        if raw.is_null() {
+            None
+        } else {
+            // Safety: only `F` function pointers are stored in this cell.
+            Some(unsafe { F::from_raw(raw) })
+        }
+    }
+
+    // Current callback, `None` means no callback installed.
+    pub fn get(&self) -> Option<F> {
+        Self::to_callback(self.callback.load(Ordering::Acquire))
+    }
+
+    // Install callback only if there is none yet, returns the effective callback.
+    pub fn get_or_init<I: FnOnce() -> F>(&self, f: I) -> F {
+        if let Some(callback) = self.get() {
+            return callback;
+        }
+        let new_callback = f();
+        match self.callback.compare_exchange(
+            ptr::null_mut(),
+            new_callback.into_raw(),
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => new_callback,
+            Err(current) => Self::to_callback(current).unwrap_or(new_callback),
+        }
+    }
+
+    // Install or replace callback, returns the previous one.
+    pub fn set(&self, callback: F) -> Option<F> {
+        Self::to_callback(self.callback.swap(callback.into_raw(), Ordering::AcqRel))
+    }
+
+    // Remove callback, returns the previous one.
+    pub fn clear(&self) -> Option<F> {
+        Self::to_callback(self.callback.swap(ptr::null_mut(), Ordering::AcqRel))
+    }
+}
+
+impl<F: CallbackFn> Default for CallbackCell<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libc::c_char;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::Arc;
+    use std::thread;
+
+    static CALLBACK_A_COUNT: AtomicUsize = AtomicUsize::new(0);
+    static CALLBACK_B_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    extern "C" fn callback_a(_level: i32, _info: *const c_char, _message: *const c_char) {
+        CALLBACK_A_COUNT.fetch_add(1, Ordering::SeqCst);
+    }
+
+    extern "C" fn callback_b(_level: i32, _info: *const c_char, _message: *const c_char) {
+        CALLBACK_B_COUNT.fetch_add(1, Ordering::SeqCst);
+    }
+
+    #[test]
+    fn test_set_and_clear_callback() {
+        let cell = CallbackCell::<LogCallback>::new();
+        assert!(cell.get().is_none());
+
+        assert!(cell.set(callback_a).is_none());
+        assert!(cell.get().is_some());
+        // `get_or_init` keeps the installed callback.
+        cell.get_or_init(|| callback_b)(0, ptr::null(), ptr::null());
+        assert_eq!(CALLBACK_B_COUNT.load(Ordering::SeqCst), 0);
+
+        assert!(cell.set(callback_b).is_some());
+        cell.get().unwrap()(0, ptr::null(), ptr::null());
+        assert_eq!(CALLBACK_B_COUNT.load(Ordering::SeqCst), 1);
+
+        assert!(cell.clear().is_some());
+        assert!(cell.get().is_none());
+        assert!(cell.clear().is_none());
+    }
+
+    #[test]
+    fn test_swap_callback_concurrently() {
+        let cell = Arc::new(CallbackCell::<LogCallback>::new());
+        cell.set(callback_a);
+
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let cell = Arc::clone(&cell);
+                thread::spawn(move || {
+                    for _ in 0..1000 {
+                        if let Some(callback) = cell.get() {
+                            callback(0, ptr::null(), ptr::null());
+                        }
+                    }
+                })
+            })
+            .collect();
+        for i in 0..1000 {
+            match i % 3 {
+                0 => cell.set(callback_a),
+                1 => cell.set(callback_b),
+                _ => cell.clear(),
+            };
+        }
+        for reader in readers {
+            assert!(reader.join().is_ok());
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use crate::utils::path_utils::PathUtils;
+use flurry::HashMap;
+
+/// Canonical identity of index paths, the key of index writer and reader caches and namespaces.
+/// Relative paths and symlinks are resolved through file system, so a symlinked path (like a
+/// detached part linked into another table) and its target never load two writers of one index.
+/// Caches are looked up on every call, so resolutions are memoized by path spelling, and
+/// loading an index resolves its spelling again in case the symlink has been retargeted.
+pub struct CanonicalIndexPaths {
+    // Lexically normalized spelling -> canonical path.
+    resolved: HashMap<String, String>,
+}
+
+impl CanonicalIndexPaths {
+    pub fn new() -> Self {
+        Self {
+            resolved: HashMap::new(),
+        }
+    }
+
+    /// Canonical path of `index_path`, the normalized spelling if it doesn't exist yet.
+    pub fn canonical_of(&self, index_path: &str) -> String {
+        let spelling: String = PathUtils::index_key(index_path);
+        if let Some(canonical_path) = self.resolved.pin().get(&spelling) {
+            return canonical_path.clone();
+        }
+        self.resolve(spelling)
+    }
+
+    /// Resolve `index_path` again regardless of memoized resolution, used when index is loaded.
+    pub fn register(&self, index_path: &str) -> String {
+        self.resolve(PathUtils::index_key(index_path))
+    }
+
+    /// Forget spellings resolved to `canonical_path`, they are resolved again on next use.
+    pub fn forget(&self, canonical_path: &str) {
+        let pinned = self.resolved.pin();
+        let spellings: Vec<String> = pinned
+            .iter()
+            .filter(|(_, value)| value.as_str() == canonical_path)
+            .map(|(spelling, _)| spelling.clone())
+            .collect();
+        for spelling in spellings {
+            pinned.remove(&spelling);
+        }
+    }
+
+    // Missing paths aren't memoized, they may be created and resolved later.
+    fn resolve(&self, spelling: String) -> String {
+        match PathUtils::canonical_key(&spelling) {
+            Some(canonical_path) => {
+                self.resolved.pin().insert(spelling, canonical_path.clone());
+                canonical_path
+            }
+            None => spelling,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_canonical_index_paths() {
+        let temp_directory = TempDir::new().unwrap();
+        let target = temp_directory.path().join("target");
+        let retargeted = temp_directory.path().join("retargeted");
+        let link = temp_directory.path().join("link");
+        std::fs::create_dir(&target).unwrap();
+        std::fs::create_dir(&retargeted).unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+        let link_str: &str = link.to_str().unwrap();
+        let target_key: String = PathUtils::canonical_key(target.to_str().unwrap()).unwrap();
+        let retargeted_key: String =
+            PathUtils::canonical_key(retargeted.to_str().unwrap()).unwrap();
+
+        let paths = CanonicalIndexPaths::new();
+        assert_eq!(paths.canonical_of(link_str), target_key);
+        assert_eq!(paths.canonical_of(&format!("{}/./", link_str)), target_key);
+        assert_eq!(
+            paths.canonical_of(&format!("{}/missing", link_str)),
+            format!("{}/missing", link_str)
+        );
+
+        // Memoized resolution is refreshed once index is registered again.
+        std::fs::remove_file(&link).unwrap();
+        std::os::unix::fs::symlink(&retargeted, &link).unwrap();
+        assert_eq!(paths.canonical_of(link_str), target_key);
+        assert_eq!(paths.register(link_str), retargeted_key);
+        assert_eq!(paths.canonical_of(link_str), retargeted_key);
+
+        paths.forget(&retargeted_key);
+        assert!(paths.resolved.pin().is_empty());
+    }
+}
@@ -1,7 +1,29 @@
+use crate::common::bitmap_union_pool::BitmapUnionPool;
 use crate::common::cache::flurry_cache::FlurryCache;
+use crate::common::canonical_index_paths::CanonicalIndexPaths;
+use crate::common::disk_space_guard::{DiskSpaceCallbackCell, DiskSpaceGuard};
+use crate::common::idle_index_janitor::IdleIndexJanitor;
+use crate::common::index_namespaces::IndexNamespaces;
+use crate::common::metrics::Metrics;
+use crate::common::open_files_budget::OpenFilesBudget;
+use crate::common::query_audit::QueryAudit;
+use crate::common::query_cost_guard::QueryCostGuard;
+use crate::common::single_flight::SingleFlight;
+use crate::common::thread_affinity::ThreadAffinity;
+use crate::common::utf8_policy::Utf8PolicyCell;
 use crate::index::bridge::index_writer_bridge_cache::IndexWriterBridgeCache;
+use crate::index::bridge::index_writer_build_progress::BuildProgressCallbackCell;
+use crate::index::bridge::index_writer_commit_hook::CommitCallbackCell;
+use crate::index::bridge::index_writer_merge_events::{MergeCallbackCell, MergeThrottle};
+use crate::index::bridge::index_writer_merge_policy::{MergePolicyCallbackCell, MergeSegmentInfo};
+use crate::index::implements::multi_part_builder::MultiPartBuilds;
+use crate::logger::log_callback::LogCallbackCell;
 use crate::logger::logger_bridge::TantivySearchLogger;
+use crate::logger::logger_config::FallbackLoggerConfig;
 use crate::search::bridge::index_reader_bridge_cache::IndexReaderBridgeCache;
+use crate::search::bridge::index_reader_searcher_handles::SearcherHandles;
+use crate::search::implements::strategy::query_key::BitmapFlightKey;
+use crate::tokenizer::callback_tokenizer::CustomTokenizers;
 use cxx::vector::VectorElement;
 use cxx::CxxString;
 use cxx::CxxVector;
@@ -23,6 +45,7 @@ use super::converter::CxxElementStrategy;
 use super::converter::CxxVectorStrategy;
 use super::converter::CxxVectorStringStrategy;
 use super::converter::CxxVectorStringToBytesStrategy;
+use super::errors::IndexSearcherError;
 
 // Cache queries results.
 // The cache's key is composed of reader.address, query_str, index_directory, use_regex.
@@ -34,26 +57,157 @@ pub static CACHE_FOR_SKIP_INDEX: Lazy<
 // Custom index settings file name.
 pub static INDEX_INFO_FILE_NAME: &str = "custom_index_setting.json";
 
+// Version of files this crate keeps in index directory besides tantivy's, bumped on changes
+// older versions can't read. Indexes written before versions were recorded have version 0.
+pub static INDEX_FORMAT_VERSION: u32 = 1;
+
+// Queries registered for reverse search of an index.
+pub static PERCOLATOR_QUERIES_FILE_NAME: &str = "percolator_queries.json";
+
+// Directory of persisted bitmap query results in index directory.
+pub static QUERY_RESULT_CACHE_DIR_NAME: &str = ".tantivy-search-query-cache";
+
+// Tantivy index writer lock file name.
+pub static WRITER_LOCK_FILE_NAME: &str = ".tantivy-writer.lock";
+
+// Index writer owner file name, records owner pid and heartbeat.
+pub static WRITER_OWNER_FILE_NAME: &str = ".tantivy-search-writer.owner";
+
+// FFI error code for index writer backpressure, other errors use -1.
+pub static BACKPRESSURE_ERROR_CODE: i32 = 1;
+
+// FFI error code for commits and merges refused by disk space guard.
+pub static OUT_OF_DISK_SPACE_ERROR_CODE: i32 = 2;
+
+// FFI error code for loading an index written by a newer incompatible version.
+pub static INCOMPATIBLE_INDEX_VERSION_ERROR_CODE: i32 = 3;
+
 // Log callback function type.
 pub type LogCallback = extern "C" fn(i32, *const c_char, *const c_char);
 
+// Commit callback function type, arguments are (index_path, opstamp, num_docs, segment_count).
+pub type CommitCallback = extern "C" fn(*const c_char, u64, u64, u64);
+
+// Build progress callback function type, arguments are
+// (index_path, docs_indexed, total_docs, bytes_indexed, eta_millis).
+pub type BuildProgressCallback = extern "C" fn(*const c_char, u64, u64, u64, i64);
+
+// Merge callback function type, arguments are (index_path, event, segment_count, num_docs).
+pub type MergeCallback = extern "C" fn(*const c_char, i32, u64, u64);
+
+// Disk space callback function type, arguments are (index_path, required_bytes, available_bytes).
+pub type DiskSpaceCallback = extern "C" fn(*const c_char, u64, u64);
+
+// Query audit callback function type, arguments are
+// (timestamp_millis, index_path, principal, query, hits, latency_micros).
+pub type QueryAuditCallback =
+    extern "C" fn(u64, *const c_char, *const c_char, *const c_char, u64, u64);
+
+// Merge policy callback function type, arguments are (index_path, segments, segment_count, merge_groups).
+pub type MergePolicyCallback =
+    extern "C" fn(*const c_char, *const MergeSegmentInfo, usize, *mut i64);
+
+// Token sink passed to tokenize callback, arguments are (sink, offset_from, offset_to).
+pub type TokenSink = extern "C" fn(*mut c_void, usize, usize);
+
+// Tokenize callback function type, arguments are (user_data, text, text_len, sink, emit),
+// host calls `emit(sink, offset_from, offset_to)` for each token and returns 0 on success.
+pub type TokenizeCallback =
+    extern "C" fn(*mut c_void, *const c_char, usize, *mut c_void, TokenSink) -> i32;
+
+// Re-index fetch callback function type, arguments are
+// (user_data, row_id, column_names, column_count, out_texts), host points `out_texts[i]` at
+// source text of column i and returns 0, a positive status skips the row, a negative one fails.
+pub type ReindexFetchCallback =
+    extern "C" fn(*mut c_void, u64, *const *const c_char, usize, *mut *const c_char) -> i32;
+
 // Empty log callback.
 pub extern "C" fn empty_log_callback(_level: i32, _info: *const c_char, _message: *const c_char) {
     // do nothing
 }
 // Log4rs handler, related with logger.
 pub static LOG4RS_HANDLE: OnceCell<log4rs::Handle> = OnceCell::new();
-// Log callback function lazy init.
-pub static LOG_CALLBACK: OnceCell<LogCallback> = OnceCell::new();
+// Built-in file logger config, applied when no log callback is registered.
+pub static FALLBACK_LOGGER_CONFIG: Lazy<Mutex<Option<FallbackLoggerConfig>>> =
+    Lazy::new(|| Mutex::new(None));
+// Log callback function, can be replaced or removed at runtime.
+pub static LOG_CALLBACK: LogCallbackCell = LogCallbackCell::new();
+
+// Commit callback function, invoked after every successful commit.
+pub static COMMIT_CALLBACK: CommitCallbackCell = CommitCallbackCell::new();
+
+// Build progress callback function, invoked periodically while docs are added.
+pub static BUILD_PROGRESS_CALLBACK: BuildProgressCallbackCell = BuildProgressCallbackCell::new();
+
+// Merge callback function, invoked when segment merges start and end.
+pub static MERGE_CALLBACK: MergeCallbackCell = MergeCallbackCell::new();
+
+// Merge policy callback function, background merges are decided by host when it's installed.
+pub static MERGE_POLICY_CALLBACK: MergePolicyCallbackCell = MergePolicyCallbackCell::new();
+
+// Disk space callback function, invoked when a commit or merge is refused for disk space.
+pub static DISK_SPACE_CALLBACK: DiskSpaceCallbackCell = DiskSpaceCallbackCell::new();
+
+// Free disk space kept by commits and merges of all index writers.
+pub static DISK_SPACE_GUARD: DiskSpaceGuard = DiskSpaceGuard::new();
+
+// Merge I/O budget shared by all index writers.
+pub static MERGE_THROTTLE: Lazy<MergeThrottle> = Lazy::new(|| MergeThrottle::new());
 
 // Cache store IndexWriterBridgeCache.
 pub static FFI_INDEX_WRITER_CACHE: Lazy<IndexWriterBridgeCache> =
     Lazy::new(|| IndexWriterBridgeCache::new());
 
+// Cost limits of queries of all index readers.
+pub static QUERY_COST_GUARD: QueryCostGuard = QueryCostGuard::new();
+
+// Audit log of sampled searches of all index readers.
+pub static QUERY_AUDIT: Lazy<QueryAudit> = Lazy::new(|| QueryAudit::new());
+
+// Process wide metrics, rendered by `tantivy_metrics_prometheus`.
+pub static METRICS: Lazy<Metrics> = Lazy::new(|| Metrics::new());
+
+/// Policy for invalid utf-8 in incoming text, applied in FFI conversion layer.
+pub static UTF8_POLICY: Utf8PolicyCell = Utf8PolicyCell::new();
+
 // Cache store IndexReaderBridgeCache.
 pub static FFI_INDEX_SEARCHER_CACHE: Lazy<IndexReaderBridgeCache> =
     Lazy::new(|| IndexReaderBridgeCache::new());
 
+// Canonical identity of index paths, keys of writer and reader caches.
+pub static CANONICAL_INDEX_PATHS: Lazy<CanonicalIndexPaths> =
+    Lazy::new(|| CanonicalIndexPaths::new());
+
+// Namespace of index paths in writer and reader caches.
+pub static INDEX_NAMESPACES: Lazy<IndexNamespaces> = Lazy::new(|| IndexNamespaces::new());
+
+// Budget of file descriptors held open by the process.
+pub static OPEN_FILES_BUDGET: OpenFilesBudget = OpenFilesBudget::new();
+
+// CPU set internal threads are pinned to.
+pub static THREAD_AFFINITY: ThreadAffinity = ThreadAffinity::new();
+
+// Pool merging per-segment bitmaps of bitmap queries.
+pub static BITMAP_UNION_POOL: Lazy<BitmapUnionPool> = Lazy::new(|| BitmapUnionPool::new());
+
+// Bitmap queries in flight, identical concurrent queries share one execution.
+pub static BITMAP_QUERY_FLIGHTS: Lazy<
+    SingleFlight<BitmapFlightKey, Result<Arc<RoaringBitmap>, IndexSearcherError>>,
+> = Lazy::new(|| SingleFlight::new());
+
+// Background janitor unloading idle index writers and readers, `None` if it's disabled.
+pub static IDLE_INDEX_JANITOR: Lazy<Mutex<Option<IdleIndexJanitor>>> =
+    Lazy::new(|| Mutex::new(None));
+
+// Searcher handles acquired by host.
+pub static SEARCHER_HANDLES: Lazy<SearcherHandles> = Lazy::new(|| SearcherHandles::new());
+
+// Multi-part index builds in progress.
+pub static MULTI_PART_BUILDS: Lazy<MultiPartBuilds> = Lazy::new(|| MultiPartBuilds::new());
+
+// Tokenizers implemented by host callbacks.
+pub static CUSTOM_TOKENIZERS: Lazy<CustomTokenizers> = Lazy::new(|| CustomTokenizers::new());
+
 /// Convert 'CxxString' to 'String'
 pub static CXX_STRING_CONERTER: Lazy<Converter<CxxString, String, CxxElementStrategy>> =
     Lazy::new(|| Converter::new(CxxElementStrategy));
@@ -1,8 +1,8 @@
-use std::str::Utf8Error;
-
 use cxx::{vector::VectorElement, CxxString, CxxVector};
 
+use super::constants::UTF8_POLICY;
 use super::errors::CxxConvertError;
+use super::utf8_policy::Sanitized;
 
 pub trait ConvertStrategy<T, U> {
     fn convert(&self, item: &T) -> Result<U, CxxConvertError>;
@@ -20,10 +20,18 @@ where
     }
 }
 
+// Invalid utf-8 is handled by `UTF8_POLICY`.
+fn sanitize_cxx_string(item: &CxxString) -> Result<String, CxxConvertError> {
+    match UTF8_POLICY.sanitize(item.as_bytes()) {
+        Ok(Sanitized::Text(text)) => Ok(text.into_owned()),
+        Ok(Sanitized::SkipRow(e)) => Err(CxxConvertError::Utf8SkipRow(e)),
+        Err(e) => Err(CxxConvertError::Utf8Error(e)),
+    }
+}
+
 impl ConvertStrategy<CxxString, String> for CxxElementStrategy {
     fn convert(&self, item: &CxxString) -> Result<String, CxxConvertError> {
-        let result: Result<String, Utf8Error> = item.to_str().map(|t| t.to_string());
-        result.map_err(CxxConvertError::Utf8Error)
+        sanitize_cxx_string(item)
     }
 }
 
@@ -59,14 +67,7 @@ pub struct CxxVectorStringStrategy;
 
 impl ConvertStrategy<CxxVector<CxxString>, Vec<String>> for CxxVectorStringStrategy {
     fn convert(&self, items: &CxxVector<CxxString>) -> Result<Vec<String>, CxxConvertError> {
-        items
-            .iter()
-            .map(|item| {
-                item.to_str()
-                    .map(|t| t.to_string())
-                    .map_err(CxxConvertError::Utf8Error)
-            })
-            .collect()
+        items.iter().map(sanitize_cxx_string).collect()
     }
 }
 
@@ -0,0 +1,132 @@
+use crate::common::callback_cell::CallbackCell;
+use crate::common::constants::{DiskSpaceCallback, DISK_SPACE_CALLBACK, METRICS};
+use std::ffi::CString;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// Room for `meta.json`, `.managed.json` and delete files written besides segments.
+const META_FILES_RESERVE_BYTES: u64 = 1024 * 1024;
+
+// Start of errors returned by `DiskSpaceGuard::check`.
+const OUT_OF_DISK_SPACE_PREFIX: &str = "Not enough disk space, ";
+
+/// Process wide guard refusing commits and merges whose estimated footprint doesn't fit in free
+/// disk space, so they fail before tantivy starts writing instead of running out of space
+/// halfway and leaving a truncated `meta.json` behind. `min_free_bytes` is kept free on top of
+/// the footprint for host and other processes sharing the disk.
+pub struct DiskSpaceGuard {
+    min_free_bytes: AtomicU64,
+}
+
+impl DiskSpaceGuard {
+    pub const fn new() -> Self {
+        DiskSpaceGuard {
+            min_free_bytes: AtomicU64::new(0),
+        }
+    }
+
+    pub fn min_free_bytes(&self) -> u64 {
+        self.min_free_bytes.load(Ordering::Relaxed)
+    }
+
+    pub fn set_min_free_bytes(&self, min_free_bytes: u64) {
+        self.min_free_bytes.store(min_free_bytes, Ordering::Relaxed);
+    }
+
+    /// Check that `footprint_bytes` can be written into `index_path`.
+    /// Host disk space callback is notified when it can't, unknown free space passes the check.
+    pub fn check(&self, index_path: &str, footprint_bytes: u64) -> Result<(), String> {
+        let available_bytes: u64 = match available_bytes(Path::new(index_path)) {
+            Some(available_bytes) => available_bytes,
+            None => return Ok(()),
+        };
+        let required_bytes: u64 = footprint_bytes
+            .saturating_add(META_FILES_RESERVE_BYTES)
+            .saturating_add(self.min_free_bytes());
+        if available_bytes >= required_bytes {
+            return Ok(());
+        }
+        METRICS.out_of_disk_space.fetch_add(1, Ordering::Relaxed);
+        DISK_SPACE_CALLBACK.notify(index_path, required_bytes, available_bytes);
+        Err(format!(
+            "{}{} bytes required, but only {} bytes available, index_path:[{}]",
+            OUT_OF_DISK_SPACE_PREFIX, required_bytes, available_bytes, index_path
+        ))
+    }
+
+    /// Whether `error` is a refusal returned by `check`, so callers can tell commits and merges
+    /// refused for disk space apart from other writer failures.
+    pub fn is_out_of_disk_space(error: &str) -> bool {
+        error.starts_with(OUT_OF_DISK_SPACE_PREFIX)
+    }
+}
+
+impl Default for DiskSpaceGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Bytes available to unprivileged users on file system of `path`, `None` if it's unknown.
+#[cfg(unix)]
+pub fn available_bytes(path: &Path) -> Option<u64> {
+    use std::os::unix::ffi::OsStrExt;
+    let path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // Safety: `path` is nul terminated and `stat` is a valid out pointer.
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    Some((stat.f_bavail as u64).saturating_mul(stat.f_frsize as u64))
+}
+
+#[cfg(not(unix))]
+pub fn available_bytes(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// Holds the host disk space callback, invoked when a commit or merge is refused by
+/// `DiskSpaceGuard`.
+pub type DiskSpaceCallbackCell = CallbackCell<DiskSpaceCallback>;
+
+impl DiskSpaceCallbackCell {
+    // Invoke callback, `index_path` containing nul byte is passed as empty string.
+    pub fn notify(&self, index_path: &str, required_bytes: u64, available_bytes: u64) {
+        if let Some(callback) = self.get() {
+            let index_path = CString::new(index_path).unwrap_or_default();
+            callback(index_path.as_ptr(), required_bytes, available_bytes);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_disk_space_guard() {
+        let temp_directory: TempDir = TempDir::new().unwrap();
+        let index_path: &str = temp_directory.path().to_str().unwrap();
+        let guard = DiskSpaceGuard::new();
+
+        if cfg!(unix) {
+            assert!(available_bytes(temp_directory.path()).unwrap() > 0);
+        }
+        assert!(guard.check(index_path, 0).is_ok());
+
+        // Nothing fits once reserve exceeds any disk.
+        guard.set_min_free_bytes(u64::MAX);
+        assert_eq!(guard.min_free_bytes(), u64::MAX);
+        if cfg!(unix) {
+            let error: String = guard.check(index_path, 1).unwrap_err();
+            assert!(DiskSpaceGuard::is_out_of_disk_space(&error));
+        }
+        assert!(!DiskSpaceGuard::is_out_of_disk_space("Lock error"));
+
+        // Free space of missing directory is unknown, the check passes.
+        assert!(guard
+            .check(&format!("{}/missing/index", index_path), 1)
+            .is_ok());
+    }
+}
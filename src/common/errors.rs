@@ -41,6 +41,8 @@ pub enum IndexUtilsError {
     ReadFileError(String),
     #[error("Failed to write file. '{0}'")]
     WriteFileError(String),
+    #[error("Failed to handle index writer lock. '{0}'")]
+    WriterLockError(String),
 }
 
 #[derive(Debug, Clone, Error)]
@@ -65,6 +67,10 @@ pub enum IndexSearcherError {
 
     #[error("Query parse error. '{0}'")]
     QueryParserError(String),
+
+    /// Query exceeds cost limits of `QueryCostGuard`, it hasn't been executed.
+    #[error("Query too expensive. '{0}'")]
+    QueryTooExpensive(String),
 }
 
 #[derive(Debug, Clone, Error)]
@@ -76,6 +82,8 @@ pub enum CxxConvertError {
     CxxElementConvertError(String),
     #[error("Failed to convert CxxString to Rust String: {0}")]
     Utf8Error(#[from] Utf8Error),
+    #[error("Row skipped for invalid utf-8: {0}")]
+    Utf8SkipRow(Utf8Error),
 }
 
 /// The library's error enum
@@ -107,4 +115,20 @@ pub enum TantivySearchError {
 
     #[error("An invalid argument was passed: '{0}'")]
     InvalidArgument(String),
+
+    /// Index writer can't accept more documents now, caller should throttle and retry later.
+    #[error("Index writer backpressure: '{0}'")]
+    Backpressure(String),
+
+    /// Loading the index would exceed open files budget, caller should free some indexes first.
+    #[error("Open files budget exceeded: '{0}'")]
+    OpenFilesBudgetExceeded(String),
+
+    /// Commit or merge doesn't fit in free disk space, nothing has been written.
+    #[error("Out of disk space: '{0}'")]
+    OutOfDiskSpace(String),
+
+    /// Index was written by a version this library can't read, nothing has been opened.
+    #[error("Incompatible index version: '{0}'")]
+    IncompatibleIndexVersion(String),
 }
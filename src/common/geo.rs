@@ -0,0 +1,201 @@
+// Mean earth radius used by haversine distance.
+const EARTH_RADIUS_METERS: f64 = 6_371_008.8;
+
+/// A point on earth in degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoPoint {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+impl GeoPoint {
+    pub fn new(lat: f64, lon: f64) -> Result<Self, String> {
+        if !(-90.0..=90.0).contains(&lat) {
+            return Err(format!("latitude should be in [-90, 90], got {}", lat));
+        }
+        if !(-180.0..=180.0).contains(&lon) {
+            return Err(format!("longitude should be in [-180, 180], got {}", lon));
+        }
+        Ok(GeoPoint { lat, lon })
+    }
+
+    /// Parse `lat,lon` in degrees, e.g. `31.2304,121.4737`.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let invalid = || format!("Can't parse `{}` as geo point `lat,lon`", value);
+        let (lat, lon) = value.split_once(',').ok_or_else(invalid)?;
+        let lat: f64 = lat.trim().parse().map_err(|_| invalid())?;
+        let lon: f64 = lon.trim().parse().map_err(|_| invalid())?;
+        GeoPoint::new(lat, lon)
+    }
+
+    /// Morton code interleaving latitude and longitude quantized to 32 bits each,
+    /// precision is about 1cm.
+    pub fn encode(&self) -> u64 {
+        let lat: u32 = quantize(self.lat, 90.0);
+        let lon: u32 = quantize(self.lon, 180.0);
+        spread_bits(lon) | (spread_bits(lat) << 1)
+    }
+
+    pub fn decode(code: u64) -> Self {
+        GeoPoint {
+            lat: dequantize(compact_bits(code >> 1), 90.0),
+            lon: dequantize(compact_bits(code), 180.0),
+        }
+    }
+
+    /// Haversine distance in meters.
+    pub fn distance_meters(&self, other: &GeoPoint) -> f64 {
+        let d_lat: f64 = (other.lat - self.lat).to_radians();
+        let d_lon: f64 = (other.lon - self.lon).to_radians();
+        let a: f64 = (d_lat / 2.0).sin().powi(2)
+            + self.lat.to_radians().cos()
+                * other.lat.to_radians().cos()
+                * (d_lon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_METERS * a.sqrt().min(1.0).asin()
+    }
+}
+
+// Map `[-range, range]` to `[0, u32::MAX]`.
+fn quantize(degrees: f64, range: f64) -> u32 {
+    ((degrees + range) / (2.0 * range) * u32::MAX as f64).round() as u32
+}
+
+fn dequantize(quantized: u32, range: f64) -> f64 {
+    quantized as f64 / u32::MAX as f64 * (2.0 * range) - range
+}
+
+// Move bit `i` of `value` to bit `2i`.
+fn spread_bits(value: u32) -> u64 {
+    let mut x: u64 = value as u64;
+    x = (x | (x << 16)) & 0x0000_FFFF_0000_FFFF;
+    x = (x | (x << 8)) & 0x00FF_00FF_00FF_00FF;
+    x = (x | (x << 4)) & 0x0F0F_0F0F_0F0F_0F0F;
+    x = (x | (x << 2)) & 0x3333_3333_3333_3333;
+    (x | (x << 1)) & 0x5555_5555_5555_5555
+}
+
+// Inverse of `spread_bits`, odd bits are ignored.
+fn compact_bits(code: u64) -> u32 {
+    let mut x: u64 = code & 0x5555_5555_5555_5555;
+    x = (x | (x >> 1)) & 0x3333_3333_3333_3333;
+    x = (x | (x >> 2)) & 0x0F0F_0F0F_0F0F_0F0F;
+    x = (x | (x >> 4)) & 0x00FF_00FF_00FF_00FF;
+    x = (x | (x >> 8)) & 0x0000_FFFF_0000_FFFF;
+    ((x | (x >> 16)) & 0x0000_0000_FFFF_FFFF) as u32
+}
+
+/// Area geo points are filtered by.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeoShape {
+    /// Box between `min` and `max` corners, it crosses the antimeridian
+    /// if `min.lon` is larger than `max.lon`.
+    BoundingBox { min: GeoPoint, max: GeoPoint },
+    /// Circle of `radius_meters` around `center`.
+    Distance {
+        center: GeoPoint,
+        radius_meters: f64,
+    },
+}
+
+impl GeoShape {
+    pub fn bounding_box(min: GeoPoint, max: GeoPoint) -> Result<Self, String> {
+        if min.lat > max.lat {
+            return Err(format!(
+                "min latitude {} is larger than max latitude {}",
+                min.lat, max.lat
+            ));
+        }
+        Ok(GeoShape::BoundingBox { min, max })
+    }
+
+    pub fn distance(center: GeoPoint, radius_meters: f64) -> Result<Self, String> {
+        if !radius_meters.is_finite() || radius_meters < 0.0 {
+            return Err(format!(
+                "radius should be a non-negative number, got {}",
+                radius_meters
+            ));
+        }
+        Ok(GeoShape::Distance {
+            center,
+            radius_meters,
+        })
+    }
+
+    pub fn contains(&self, point: &GeoPoint) -> bool {
+        match self {
+            GeoShape::BoundingBox { min, max } => {
+                let in_lat: bool = point.lat >= min.lat && point.lat <= max.lat;
+                let in_lon: bool = match min.lon <= max.lon {
+                    true => point.lon >= min.lon && point.lon <= max.lon,
+                    false => point.lon >= min.lon || point.lon <= max.lon,
+                };
+                in_lat && in_lon
+            }
+            GeoShape::Distance {
+                center,
+                radius_meters,
+            } => center.distance_meters(point) <= *radius_meters,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_geo_point_encode() {
+        for (lat, lon) in [
+            (31.2304, 121.4737),
+            (-90.0, -180.0),
+            (90.0, 180.0),
+            (0.0, 0.0),
+        ] {
+            let point = GeoPoint::new(lat, lon).unwrap();
+            let decoded = GeoPoint::decode(point.encode());
+            assert!((decoded.lat - lat).abs() < 1e-7);
+            assert!((decoded.lon - lon).abs() < 1e-7);
+        }
+        assert_eq!(
+            GeoPoint::parse(" 31.2304, 121.4737").unwrap(),
+            GeoPoint::new(31.2304, 121.4737).unwrap()
+        );
+        assert!(GeoPoint::parse("91,0").is_err());
+        assert!(GeoPoint::parse("31.2304").is_err());
+        assert!(GeoPoint::parse("a,b").is_err());
+    }
+
+    #[test]
+    fn test_geo_shape_contains() {
+        let shanghai = GeoPoint::new(31.2304, 121.4737).unwrap();
+        let beijing = GeoPoint::new(39.9042, 116.4074).unwrap();
+        // Shanghai to Beijing is about 1067km.
+        let distance = shanghai.distance_meters(&beijing);
+        assert!((distance - 1_067_000.0).abs() < 5_000.0);
+        assert!(GeoShape::distance(shanghai, 1_100_000.0)
+            .unwrap()
+            .contains(&beijing));
+        assert!(!GeoShape::distance(shanghai, 1_000_000.0)
+            .unwrap()
+            .contains(&beijing));
+        assert!(GeoShape::distance(shanghai, -1.0).is_err());
+
+        let china = GeoShape::bounding_box(
+            GeoPoint::new(18.0, 73.0).unwrap(),
+            GeoPoint::new(54.0, 135.0).unwrap(),
+        )
+        .unwrap();
+        assert!(china.contains(&shanghai));
+        assert!(!china.contains(&GeoPoint::new(51.5, -0.12).unwrap()));
+        // Box crossing the antimeridian.
+        let pacific = GeoShape::bounding_box(
+            GeoPoint::new(-30.0, 170.0).unwrap(),
+            GeoPoint::new(30.0, -170.0).unwrap(),
+        )
+        .unwrap();
+        assert!(pacific.contains(&GeoPoint::new(0.0, 179.0).unwrap()));
+        assert!(pacific.contains(&GeoPoint::new(0.0, -175.0).unwrap()));
+        assert!(!pacific.contains(&GeoPoint::new(0.0, 0.0).unwrap()));
+        assert!(GeoShape::bounding_box(beijing, shanghai).is_err());
+    }
+}
@@ -0,0 +1,49 @@
+use crate::utils::time_utils::TimeUtils;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Last time a background task made progress, shared between the task and liveness probes.
+#[derive(Debug, Default)]
+pub struct Heartbeat {
+    // Unix timestamp in millis, 0 if the task hasn't made progress yet.
+    last_millis: AtomicU64,
+}
+
+impl Heartbeat {
+    pub fn new() -> Self {
+        let heartbeat = Heartbeat::default();
+        heartbeat.beat();
+        heartbeat
+    }
+
+    pub fn beat(&self) {
+        self.last_millis
+            .store(TimeUtils::now_millis(), Ordering::Relaxed);
+    }
+
+    pub fn last_millis(&self) -> u64 {
+        self.last_millis.load(Ordering::Relaxed)
+    }
+
+    /// Whether last beat is at most `max_age_millis` before `now_millis`.
+    pub fn is_fresh(&self, now_millis: u64, max_age_millis: u64) -> bool {
+        now_millis.saturating_sub(self.last_millis()) <= max_age_millis
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heartbeat() {
+        let heartbeat = Heartbeat::default();
+        assert_eq!(heartbeat.last_millis(), 0);
+        let now_millis = TimeUtils::now_millis();
+        assert!(!heartbeat.is_fresh(now_millis, 60_000));
+
+        heartbeat.beat();
+        assert!(heartbeat.last_millis() >= now_millis);
+        assert!(heartbeat.is_fresh(now_millis, 0));
+        assert!(!heartbeat.is_fresh(heartbeat.last_millis() + 1001, 1000));
+    }
+}
@@ -0,0 +1,312 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashSet};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// Seed of node levels, graphs built from the same vectors are identical.
+const LEVEL_SEED: u64 = 0x5eed_4a5f;
+
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    score: f32,
+    node: u32,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score
+            .total_cmp(&other.score)
+            .then_with(|| other.node.cmp(&self.node))
+    }
+}
+
+/// HNSW graph of nodes `0..len`, compared by a similarity where larger means closer.
+/// Vectors aren't kept in the graph, they're read by the similarity closures given.
+pub struct HnswGraph {
+    m: usize,
+    // Neighbors of each node, indexed by node and then level.
+    neighbors: Vec<Vec<Vec<u32>>>,
+    entry_point: Option<u32>,
+    max_level: usize,
+}
+
+impl HnswGraph {
+    /// Build graph of `len` nodes, `similarity(a, b)` compares two nodes.
+    pub fn build(
+        len: usize,
+        m: usize,
+        ef_construction: usize,
+        similarity: impl Fn(u32, u32) -> f32,
+    ) -> Self {
+        let mut graph: HnswGraph = HnswGraph {
+            m,
+            neighbors: Vec::with_capacity(len),
+            entry_point: None,
+            max_level: 0,
+        };
+        let mut rng: StdRng = StdRng::seed_from_u64(LEVEL_SEED);
+        let level_factor: f64 = 1.0 / (m as f64).ln();
+        for node in 0..len as u32 {
+            let level: usize = (-(1.0 - rng.gen::<f64>()).ln() * level_factor) as usize;
+            graph.insert(node, level, ef_construction, &similarity);
+        }
+        graph
+    }
+
+    /// Up to `ef` nodes closest to query, best first. `score(node)` compares a node with query,
+    /// nodes rejected by `accept` are traversed but never returned.
+    pub fn search(
+        &self,
+        ef: usize,
+        score: impl Fn(u32) -> f32,
+        accept: impl Fn(u32) -> bool,
+    ) -> Vec<(u32, f32)> {
+        let mut entry: u32 = match self.entry_point {
+            Some(entry) => entry,
+            None => return Vec::new(),
+        };
+        for level in (1..=self.max_level).rev() {
+            entry = self.greedy_closest(entry, level, &score);
+        }
+        self.search_level(&[entry], ef, 0, &score, &accept)
+            .into_iter()
+            .map(|candidate| (candidate.node, candidate.score))
+            .collect()
+    }
+
+    pub fn memory_usage(&self) -> usize {
+        self.neighbors
+            .iter()
+            .flatten()
+            .map(|links| links.capacity() * std::mem::size_of::<u32>())
+            .sum()
+    }
+
+    fn max_neighbors(&self, level: usize) -> usize {
+        match level {
+            0 => self.m * 2,
+            _ => self.m,
+        }
+    }
+
+    fn insert(
+        &mut self,
+        node: u32,
+        level: usize,
+        ef_construction: usize,
+        similarity: &impl Fn(u32, u32) -> f32,
+    ) {
+        self.neighbors.push(vec![Vec::new(); level + 1]);
+        let mut entry: u32 = match self.entry_point {
+            Some(entry) => entry,
+            None => {
+                self.entry_point = Some(node);
+                self.max_level = level;
+                return;
+            }
+        };
+        let score = |other: u32| similarity(node, other);
+        for upper_level in (level + 1..=self.max_level).rev() {
+            entry = self.greedy_closest(entry, upper_level, &score);
+        }
+
+        let mut entries: Vec<u32> = vec![entry];
+        for current_level in (0..=level.min(self.max_level)).rev() {
+            let candidates: Vec<Candidate> =
+                self.search_level(&entries, ef_construction, current_level, &score, &|_| true);
+            let max_neighbors: usize = self.max_neighbors(current_level);
+            let selected: Vec<u32> = candidates
+                .iter()
+                .take(max_neighbors)
+                .map(|candidate| candidate.node)
+                .collect();
+            for neighbor in selected.iter().copied() {
+                let links: &mut Vec<u32> = &mut self.neighbors[neighbor as usize][current_level];
+                links.push(node);
+                if links.len() > max_neighbors {
+                    // Keep the closest links of neighbor.
+                    let mut scored_links: Vec<Candidate> = links
+                        .iter()
+                        .map(|link| Candidate {
+                            score: similarity(neighbor, *link),
+                            node: *link,
+                        })
+                        .collect();
+                    scored_links.sort_by(|a, b| b.cmp(a));
+                    *links = scored_links
+                        .into_iter()
+                        .take(max_neighbors)
+                        .map(|candidate| candidate.node)
+                        .collect();
+                }
+            }
+            self.neighbors[node as usize][current_level] = selected;
+            entries = candidates.iter().map(|candidate| candidate.node).collect();
+        }
+
+        if level > self.max_level {
+            self.entry_point = Some(node);
+            self.max_level = level;
+        }
+    }
+
+    // Walk to the closest node of `level` from `entry`.
+    fn greedy_closest(&self, entry: u32, level: usize, score: &impl Fn(u32) -> f32) -> u32 {
+        let mut closest: Candidate = Candidate {
+            score: score(entry),
+            node: entry,
+        };
+        loop {
+            let current: u32 = closest.node;
+            for neighbor in self.neighbors[current as usize][level].iter().copied() {
+                let neighbor_score: f32 = score(neighbor);
+                if neighbor_score > closest.score {
+                    closest = Candidate {
+                        score: neighbor_score,
+                        node: neighbor,
+                    };
+                }
+            }
+            if closest.node == current {
+                return closest.node;
+            }
+        }
+    }
+
+    // Best first search of `level`, returns up to `ef` accepted nodes, best first.
+    fn search_level(
+        &self,
+        entries: &[u32],
+        ef: usize,
+        level: usize,
+        score: &impl Fn(u32) -> f32,
+        accept: &impl Fn(u32) -> bool,
+    ) -> Vec<Candidate> {
+        let mut visited: HashSet<u32> = entries.iter().copied().collect();
+        let mut candidates: BinaryHeap<Candidate> = BinaryHeap::new();
+        // Worst result on top, so it's replaced by better ones.
+        let mut results: BinaryHeap<Reverse<Candidate>> = BinaryHeap::new();
+        let push_result = |results: &mut BinaryHeap<Reverse<Candidate>>, candidate: Candidate| {
+            results.push(Reverse(candidate));
+            if results.len() > ef {
+                results.pop();
+            }
+        };
+
+        for entry in entries.iter().copied() {
+            let candidate: Candidate = Candidate {
+                score: score(entry),
+                node: entry,
+            };
+            candidates.push(candidate);
+            if accept(entry) {
+                push_result(&mut results, candidate);
+            }
+        }
+
+        while let Some(candidate) = candidates.pop() {
+            let worst_score: Option<f32> = match results.len() >= ef {
+                true => results.peek().map(|worst| worst.0.score),
+                false => None,
+            };
+            if worst_score.is_some_and(|worst_score| candidate.score < worst_score) {
+                break;
+            }
+            for neighbor in self.neighbors[candidate.node as usize][level]
+                .iter()
+                .copied()
+            {
+                if !visited.insert(neighbor) {
+                    continue;
+                }
+                let neighbor_candidate: Candidate = Candidate {
+                    score: score(neighbor),
+                    node: neighbor,
+                };
+                let is_full: bool = results.len() >= ef;
+                if !is_full
+                    || results
+                        .peek()
+                        .is_some_and(|worst| neighbor_candidate.score > worst.0.score)
+                {
+                    candidates.push(neighbor_candidate);
+                    if accept(neighbor) {
+                        push_result(&mut results, neighbor_candidate);
+                    }
+                }
+            }
+        }
+
+        let mut results: Vec<Candidate> = results.into_iter().map(|result| result.0).collect();
+        results.sort_by(|a, b| b.cmp(a));
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn negative_distance(a: &[f32], b: &[f32]) -> f32 {
+        -a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum::<f32>()
+    }
+
+    #[test]
+    fn test_hnsw_graph_search() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let vectors: Vec<Vec<f32>> = (0..1000)
+            .map(|_| (0..8).map(|_| rng.gen::<f32>()).collect())
+            .collect();
+        let graph = HnswGraph::build(vectors.len(), 8, 64, |a, b| {
+            negative_distance(&vectors[a as usize], &vectors[b as usize])
+        });
+        assert!(graph.memory_usage() > 0);
+
+        // Recall of top 10 compared with exact search.
+        let mut found = 0;
+        for query in vectors.iter().take(20) {
+            let score = |node: u32| negative_distance(query, &vectors[node as usize]);
+            let mut exact: Vec<u32> = (0..vectors.len() as u32).collect();
+            exact.sort_by(|a, b| score(*b).total_cmp(&score(*a)));
+            let approximate: Vec<u32> = graph
+                .search(32, score, |_| true)
+                .into_iter()
+                .take(10)
+                .map(|(node, _)| node)
+                .collect();
+            found += exact[..10]
+                .iter()
+                .filter(|node| approximate.contains(node))
+                .count();
+        }
+        assert!(found >= 180, "recall too low: {}/200", found);
+
+        // Rejected nodes are never returned.
+        let results = graph.search(
+            10,
+            |node| negative_distance(&vectors[0], &vectors[node as usize]),
+            |node| node % 2 == 1,
+        );
+        assert_eq!(results.len(), 10);
+        assert!(results.iter().all(|(node, _)| node % 2 == 1));
+        assert!(results.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+
+        let empty = HnswGraph::build(0, 8, 64, |_, _| 0.0);
+        assert!(empty.search(10, |_| 0.0, |_| true).is_empty());
+    }
+}
@@ -0,0 +1,150 @@
+/// Precision of sketches built by searches, `2^14` registers give a standard error about 0.8%.
+pub const HLL_PRECISION: u8 = 14;
+
+const HLL_MIN_PRECISION: u8 = 4;
+const HLL_MAX_PRECISION: u8 = 18;
+// First byte of serialized sketches, bumped when the layout or the hash changes.
+const HLL_FORMAT_VERSION: u8 = 1;
+
+/// HyperLogLog sketch estimating the number of distinct u64 values inserted.
+/// Sketches of the same precision are merged by keeping max registers, so segments
+/// and index parts are counted separately and merged later.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HyperLogLog {
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    pub fn new(precision: u8) -> Result<Self, String> {
+        if !(HLL_MIN_PRECISION..=HLL_MAX_PRECISION).contains(&precision) {
+            return Err(format!(
+                "HyperLogLog precision should be in [{}, {}], got {}",
+                HLL_MIN_PRECISION, HLL_MAX_PRECISION, precision
+            ));
+        }
+        Ok(HyperLogLog {
+            precision,
+            registers: vec![0; 1 << precision],
+        })
+    }
+
+    pub fn insert(&mut self, value: u64) {
+        let hash: u64 = fmix64(value);
+        let index: usize = (hash >> (64 - self.precision)) as usize;
+        // Position of the first 1 bit in remaining bits, bounded when they are all 0.
+        let rank: u8 =
+            ((hash << self.precision).leading_zeros() + 1).min(65 - self.precision as u32) as u8;
+        if self.registers[index] < rank {
+            self.registers[index] = rank;
+        }
+    }
+
+    pub fn merge(&mut self, other: &HyperLogLog) -> Result<(), String> {
+        if self.precision != other.precision {
+            return Err(format!(
+                "Can't merge HyperLogLog of precision {} into precision {}",
+                other.precision, self.precision
+            ));
+        }
+        for (register, other_register) in self.registers.iter_mut().zip(&other.registers) {
+            *register = (*register).max(*other_register);
+        }
+        Ok(())
+    }
+
+    pub fn estimate(&self) -> u64 {
+        let num_registers: f64 = self.registers.len() as f64;
+        let alpha: f64 = 0.7213 / (1.0 + 1.079 / num_registers);
+        let harmonic_sum: f64 = self
+            .registers
+            .iter()
+            .map(|register| 2f64.powi(-(*register as i32)))
+            .sum();
+        let estimate: f64 = alpha * num_registers * num_registers / harmonic_sum;
+        let zero_registers: usize = self
+            .registers
+            .iter()
+            .filter(|register| **register == 0)
+            .count();
+        // Linear counting is more accurate for small cardinalities. Hashes have 64 bits,
+        // so large cardinalities need no correction.
+        if estimate <= 2.5 * num_registers && zero_registers > 0 {
+            return (num_registers * (num_registers / zero_registers as f64).ln()).round() as u64;
+        }
+        estimate.round() as u64
+    }
+
+    /// Serialized sketch: format version, precision and registers.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = Vec::with_capacity(2 + self.registers.len());
+        bytes.push(HLL_FORMAT_VERSION);
+        bytes.push(self.precision);
+        bytes.extend_from_slice(&self.registers);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < 2 || bytes[0] != HLL_FORMAT_VERSION {
+            return Err("Not a HyperLogLog sketch of a supported version".to_string());
+        }
+        let mut sketch: HyperLogLog = HyperLogLog::new(bytes[1])?;
+        if bytes.len() != 2 + sketch.registers.len() {
+            return Err(format!(
+                "HyperLogLog sketch of precision {} should have {} bytes, got {}",
+                sketch.precision,
+                2 + sketch.registers.len(),
+                bytes.len()
+            ));
+        }
+        let max_rank: u8 = 65 - sketch.precision;
+        if bytes[2..].iter().any(|register| *register > max_rank) {
+            return Err("HyperLogLog sketch has invalid registers".to_string());
+        }
+        sketch.registers.copy_from_slice(&bytes[2..]);
+        Ok(sketch)
+    }
+}
+
+// Finalizer of MurmurHash3, values are spread over all bits the same way on every host.
+#[inline]
+fn fmix64(mut value: u64) -> u64 {
+    value ^= value >> 33;
+    value = value.wrapping_mul(0xff51afd7ed558ccd);
+    value ^= value >> 33;
+    value = value.wrapping_mul(0xc4ceb9fe1a85ec53);
+    value ^= value >> 33;
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hyper_log_log() {
+        let mut sketch = HyperLogLog::new(HLL_PRECISION).unwrap();
+        assert_eq!(sketch.estimate(), 0);
+        for value in 0..100u64 {
+            sketch.insert(value);
+            sketch.insert(value);
+        }
+        assert_eq!(sketch.estimate(), 100);
+
+        // Overlapping values of another part are counted once.
+        let mut other = HyperLogLog::new(HLL_PRECISION).unwrap();
+        for value in 50..100_000u64 {
+            other.insert(value);
+        }
+        sketch.merge(&other).unwrap();
+        let estimate = sketch.estimate() as f64;
+        assert!((estimate - 100_000.0).abs() / 100_000.0 < 0.03);
+
+        let restored = HyperLogLog::from_bytes(&sketch.to_bytes()).unwrap();
+        assert_eq!(restored, sketch);
+        assert!(HyperLogLog::from_bytes(&sketch.to_bytes()[..100]).is_err());
+        assert!(HyperLogLog::from_bytes(&[]).is_err());
+        assert!(HyperLogLog::new(30).is_err());
+        assert!(sketch.merge(&HyperLogLog::new(10).unwrap()).is_err());
+    }
+}
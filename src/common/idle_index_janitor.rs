@@ -0,0 +1,88 @@
+use super::heartbeat::Heartbeat;
+use super::thread_affinity::{spawn_thread, ThreadKind};
+use crate::index::implements::api_index_impl::unload_idle_indexes;
+use crate::logger::logger_bridge::TantivySearchLogger;
+use crate::{common::constants::LOG_CALLBACK, DEBUG, INFO};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+// How often the janitor checks whether it has been stopped.
+static IDLE_JANITOR_TICK_MILLIS: u64 = 200;
+
+/// Index writers and readers not accessed for `idle_timeout_secs` are unloaded,
+/// caches are checked every `check_interval_secs` seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdleUnloadConfig {
+    pub idle_timeout_secs: u64,
+    pub check_interval_secs: u64,
+}
+
+impl IdleUnloadConfig {
+    pub fn is_valid(&self) -> bool {
+        self.idle_timeout_secs > 0 && self.check_interval_secs > 0
+    }
+}
+
+/// Handle of the background janitor which unloads idle indexes.
+pub struct IdleIndexJanitor {
+    pub config: IdleUnloadConfig,
+    // Beats every tick, it goes stale if unloading indexes gets stuck.
+    pub heartbeat: Arc<Heartbeat>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl IdleIndexJanitor {
+    pub fn spawn(config: IdleUnloadConfig) -> Result<Self, String> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = stop.clone();
+        let heartbeat = Arc::new(Heartbeat::new());
+        let task_heartbeat = heartbeat.clone();
+        let thread = spawn_thread(ThreadKind::Search, "idle-janitor", move || {
+            Self::run(config, stop_flag, task_heartbeat)
+        })
+        .map_err(|e| format!("Failed to spawn idle index janitor thread: {}", e))?;
+        Ok(IdleIndexJanitor {
+            config,
+            heartbeat,
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    fn run(config: IdleUnloadConfig, stop: Arc<AtomicBool>, heartbeat: Arc<Heartbeat>) {
+        let check_interval = Duration::from_secs(config.check_interval_secs);
+        let mut next_check = Instant::now() + check_interval;
+        loop {
+            thread::sleep(Duration::from_millis(IDLE_JANITOR_TICK_MILLIS));
+            if stop.load(Ordering::Acquire) {
+                break;
+            }
+            heartbeat.beat();
+            if Instant::now() < next_check {
+                continue;
+            }
+            let unloaded = unload_idle_indexes(config.idle_timeout_secs.saturating_mul(1000));
+            DEBUG!(function:"idle_index_janitor", "Unloaded {} idle index bridges", unloaded);
+            next_check = Instant::now() + check_interval;
+        }
+        INFO!(function:"idle_index_janitor", "Idle index janitor has been stopped.");
+    }
+
+    /// Whether the background thread is still running, it only exits after being stopped.
+    pub fn is_alive(&self) -> bool {
+        self.thread
+            .as_ref()
+            .map_or(false, |thread| !thread.is_finished())
+    }
+
+    /// Stop the background janitor and wait it finished.
+    pub fn stop(mut self) {
+        self.stop.store(true, Ordering::Release);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
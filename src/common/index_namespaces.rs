@@ -0,0 +1,87 @@
+use crate::common::constants::CANONICAL_INDEX_PATHS;
+use flurry::HashMap;
+
+/// Namespace of each index path, used by multi-tenant hosts to group indexes of one database.
+/// Index writer and reader caches are still keyed by path, an index path belongs to one
+/// namespace at most, so (namespace, path) identifies an index as path alone does.
+pub struct IndexNamespaces {
+    namespaces: HashMap<String, String>,
+}
+
+impl IndexNamespaces {
+    pub fn new() -> Self {
+        Self {
+            namespaces: HashMap::new(),
+        }
+    }
+
+    // Assign `key` to `namespace`, previous namespace of `key` is replaced.
+    pub fn register(&self, namespace: &str, key: &str) {
+        let canonical_key: String = CANONICAL_INDEX_PATHS.canonical_of(key);
+        self.namespaces
+            .pin()
+            .insert(canonical_key, namespace.to_string());
+    }
+
+    pub fn unregister(&self, key: &str) {
+        let canonical_key: String = CANONICAL_INDEX_PATHS.canonical_of(key);
+        self.namespaces.pin().remove(&canonical_key);
+    }
+
+    pub fn namespace_of(&self, key: &str) -> Option<String> {
+        let canonical_key: String = CANONICAL_INDEX_PATHS.canonical_of(key);
+        self.namespaces.pin().get(&canonical_key).cloned()
+    }
+
+    // Index paths of `namespace`, sorted.
+    pub fn keys_of(&self, namespace: &str) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .namespaces
+            .pin()
+            .iter()
+            .filter(|(_, value)| value.as_str() == namespace)
+            .map(|(key, _)| key.clone())
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    // All namespaces with at least one index, sorted.
+    pub fn all_namespaces(&self) -> Vec<String> {
+        let mut namespaces: Vec<String> = self.namespaces.pin().values().cloned().collect();
+        namespaces.sort();
+        namespaces.dedup();
+        namespaces
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_namespaces() {
+        let namespaces = IndexNamespaces::new();
+        namespaces.register("tenant_a", "/data/a/index_1/");
+        namespaces.register("tenant_a", "/data/a/index_0");
+        namespaces.register("tenant_b", "/data/b/index_0");
+
+        assert_eq!(
+            namespaces.namespace_of("/data/a/index_1"),
+            Some("tenant_a".to_string())
+        );
+        assert_eq!(
+            namespaces.keys_of("tenant_a"),
+            vec!["/data/a/index_0", "/data/a/index_1"]
+        );
+        assert_eq!(namespaces.all_namespaces(), vec!["tenant_a", "tenant_b"]);
+
+        // Register again moves index to another namespace.
+        namespaces.register("tenant_b", "/data/a/index_1");
+        assert_eq!(namespaces.keys_of("tenant_a"), vec!["/data/a/index_0"]);
+
+        namespaces.unregister("/data/a/index_0");
+        assert!(namespaces.namespace_of("/data/a/index_0").is_none());
+        assert_eq!(namespaces.all_namespaces(), vec!["tenant_b"]);
+    }
+}
@@ -0,0 +1,429 @@
+use crate::common::allocator::AllocatorMemory;
+use crate::common::constants::{
+    CACHE_FOR_SKIP_INDEX, FFI_INDEX_SEARCHER_CACHE, FFI_INDEX_WRITER_CACHE, METRICS,
+};
+use crate::index::bridge::index_writer_bridge::IndexWriterBridge;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+// Latency buckets upper bounds in seconds.
+static LATENCY_BUCKETS: [f64; 12] = [
+    0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 5.0,
+];
+
+/// Cumulative latency histogram with fixed buckets.
+pub struct LatencyHistogram {
+    buckets: Vec<AtomicU64>,
+    sum_micros: AtomicU64,
+    count: AtomicU64,
+}
+
+impl LatencyHistogram {
+    pub fn new() -> Self {
+        LatencyHistogram {
+            buckets: LATENCY_BUCKETS.iter().map(|_| AtomicU64::new(0)).collect(),
+            sum_micros: AtomicU64::new(0),
+            count: AtomicU64::new(0),
+        }
+    }
+
+    pub fn observe(&self, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        if let Some(index) = LATENCY_BUCKETS.iter().position(|bound| seconds <= *bound) {
+            self.buckets[index].fetch_add(1, Ordering::Relaxed);
+        }
+        self.sum_micros
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    fn render(&self, output: &mut String, name: &str, help: &str) {
+        let _ = writeln!(output, "# HELP {} {}", name, help);
+        let _ = writeln!(output, "# TYPE {} histogram", name);
+        let mut cumulative: u64 = 0;
+        for (bound, bucket) in LATENCY_BUCKETS.iter().zip(self.buckets.iter()) {
+            cumulative += bucket.load(Ordering::Relaxed);
+            let _ = writeln!(output, "{}_bucket{{le=\"{}\"}} {}", name, bound, cumulative);
+        }
+        let count = self.count();
+        let _ = writeln!(output, "{}_bucket{{le=\"+Inf\"}} {}", name, count);
+        let sum_seconds = self.sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+        let _ = writeln!(output, "{}_sum {}", name, sum_seconds);
+        let _ = writeln!(output, "{}_count {}", name, count);
+    }
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Process wide counters of tantivy-search, gauges are collected when rendering.
+pub struct Metrics {
+    pub documents_indexed: AtomicU64,
+    pub bytes_indexed: AtomicU64,
+    pub delete_operations: AtomicU64,
+    pub commits: AtomicU64,
+    pub commit_errors: AtomicU64,
+    pub merges: AtomicU64,
+    pub merged_segments: AtomicU64,
+    pub queries: AtomicU64,
+    pub query_errors: AtomicU64,
+    pub shared_queries: AtomicU64,
+    pub expensive_queries_rejected: AtomicU64,
+    pub query_result_cache_hits: AtomicU64,
+    pub orphan_bytes_reclaimed: AtomicU64,
+    pub out_of_disk_space: AtomicU64,
+    pub utf8_rejected: AtomicU64,
+    pub utf8_replaced: AtomicU64,
+    pub utf8_skipped_rows: AtomicU64,
+    pub row_id_violations: AtomicU64,
+    pub commit_latency: LatencyHistogram,
+    pub query_latency: LatencyHistogram,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Metrics {
+            documents_indexed: AtomicU64::new(0),
+            bytes_indexed: AtomicU64::new(0),
+            delete_operations: AtomicU64::new(0),
+            commits: AtomicU64::new(0),
+            commit_errors: AtomicU64::new(0),
+            merges: AtomicU64::new(0),
+            merged_segments: AtomicU64::new(0),
+            queries: AtomicU64::new(0),
+            query_errors: AtomicU64::new(0),
+            shared_queries: AtomicU64::new(0),
+            expensive_queries_rejected: AtomicU64::new(0),
+            query_result_cache_hits: AtomicU64::new(0),
+            orphan_bytes_reclaimed: AtomicU64::new(0),
+            out_of_disk_space: AtomicU64::new(0),
+            utf8_rejected: AtomicU64::new(0),
+            utf8_replaced: AtomicU64::new(0),
+            utf8_skipped_rows: AtomicU64::new(0),
+            row_id_violations: AtomicU64::new(0),
+            commit_latency: LatencyHistogram::new(),
+            query_latency: LatencyHistogram::new(),
+        }
+    }
+
+    pub fn record_commit(&self, elapsed: Duration, success: bool) {
+        if success {
+            self.commits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.commit_errors.fetch_add(1, Ordering::Relaxed);
+        }
+        self.commit_latency.observe(elapsed);
+    }
+
+    pub fn record_query(&self, elapsed: Duration, success: bool) {
+        self.queries.fetch_add(1, Ordering::Relaxed);
+        if !success {
+            self.query_errors.fetch_add(1, Ordering::Relaxed);
+        }
+        self.query_latency.observe(elapsed);
+    }
+
+    pub fn record_merge(&self, merged_segments: usize) {
+        self.merges.fetch_add(1, Ordering::Relaxed);
+        self.merged_segments
+            .fetch_add(merged_segments as u64, Ordering::Relaxed);
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn render_single(output: &mut String, name: &str, metric_type: &str, help: &str, value: u64) {
+    let _ = writeln!(output, "# HELP {} {}", name, help);
+    let _ = writeln!(output, "# TYPE {} {}", name, metric_type);
+    let _ = writeln!(output, "{} {}", name, value);
+}
+
+// Escape label value according to prometheus text format.
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn render_index_gauge(output: &mut String, name: &str, help: &str, values: &[(String, u64)]) {
+    let _ = writeln!(output, "# HELP {} {}", name, help);
+    let _ = writeln!(output, "# TYPE {} gauge", name);
+    for (index_path, value) in values {
+        let _ = writeln!(
+            output,
+            "{}{{index_path=\"{}\"}} {}",
+            name,
+            escape_label_value(index_path),
+            value
+        );
+    }
+}
+
+fn collect_writer_gauge<F: Fn(&IndexWriterBridge) -> u64>(
+    writers: &[(String, Arc<IndexWriterBridge>)],
+    value: F,
+) -> Vec<(String, u64)> {
+    writers
+        .iter()
+        .map(|(index_path, bridge)| (index_path.clone(), value(bridge.as_ref())))
+        .collect()
+}
+
+/// Render all internal metrics in prometheus text exposition format.
+pub fn tantivy_metrics_prometheus() -> String {
+    let mut output = String::new();
+    let counters: [(&str, &str, &AtomicU64); 13] = [
+        (
+            "tantivy_search_documents_indexed_total",
+            "Documents added to index writers.",
+            &METRICS.documents_indexed,
+        ),
+        (
+            "tantivy_search_indexed_bytes_total",
+            "Estimated bytes of documents added to index writers.",
+            &METRICS.bytes_indexed,
+        ),
+        (
+            "tantivy_search_delete_operations_total",
+            "Delete operations applied to index writers.",
+            &METRICS.delete_operations,
+        ),
+        (
+            "tantivy_search_commits_total",
+            "Successful index writer commits.",
+            &METRICS.commits,
+        ),
+        (
+            "tantivy_search_commit_errors_total",
+            "Failed index writer commits.",
+            &METRICS.commit_errors,
+        ),
+        (
+            "tantivy_search_merges_total",
+            "Explicit segment merges.",
+            &METRICS.merges,
+        ),
+        (
+            "tantivy_search_merged_segments_total",
+            "Segments consumed by explicit merges.",
+            &METRICS.merged_segments,
+        ),
+        (
+            "tantivy_search_queries_total",
+            "Executed search queries.",
+            &METRICS.queries,
+        ),
+        (
+            "tantivy_search_query_errors_total",
+            "Failed search queries.",
+            &METRICS.query_errors,
+        ),
+        (
+            "tantivy_search_shared_queries_total",
+            "Queries sharing result of an identical query in flight.",
+            &METRICS.shared_queries,
+        ),
+        (
+            "tantivy_search_expensive_queries_rejected_total",
+            "Queries refused for exceeding query cost limits.",
+            &METRICS.expensive_queries_rejected,
+        ),
+        (
+            "tantivy_search_query_result_cache_hits_total",
+            "Queries answered by results persisted next to index.",
+            &METRICS.query_result_cache_hits,
+        ),
+        (
+            "tantivy_search_orphan_bytes_reclaimed_total",
+            "Bytes of files left by crashed processes removed on index load.",
+            &METRICS.orphan_bytes_reclaimed,
+        ),
+        (
+            "tantivy_search_out_of_disk_space_total",
+            "Commits and merges refused for insufficient disk space.",
+            &METRICS.out_of_disk_space,
+        ),
+        (
+            "tantivy_search_utf8_rejected_total",
+            "Incoming texts rejected for invalid utf-8.",
+            &METRICS.utf8_rejected,
+        ),
+        (
+            "tantivy_search_utf8_replaced_total",
+            "Incoming texts with invalid utf-8 replaced lossily.",
+            &METRICS.utf8_replaced,
+        ),
+        (
+            "tantivy_search_utf8_skipped_rows_total",
+            "Ingested rows skipped for invalid utf-8.",
+            &METRICS.utf8_skipped_rows,
+        ),
+        (
+            "tantivy_search_row_id_violations_total",
+            "Ingested row_ids out of order or duplicated.",
+            &METRICS.row_id_violations,
+        ),
+    ];
+    for (name, help, counter) in counters {
+        render_single(
+            &mut output,
+            name,
+            "counter",
+            help,
+            counter.load(Ordering::Relaxed),
+        );
+    }
+
+    METRICS.commit_latency.render(
+        &mut output,
+        "tantivy_search_commit_latency_seconds",
+        "Latency of index writer commits.",
+    );
+    METRICS.query_latency.render(
+        &mut output,
+        "tantivy_search_query_latency_seconds",
+        "Latency of search queries.",
+    );
+
+    render_single(
+        &mut output,
+        "tantivy_search_index_writers",
+        "gauge",
+        "Index writers held in cache.",
+        FFI_INDEX_WRITER_CACHE.bridge_count() as u64,
+    );
+    render_single(
+        &mut output,
+        "tantivy_search_index_readers",
+        "gauge",
+        "Index readers held in cache.",
+        FFI_INDEX_SEARCHER_CACHE.bridge_count() as u64,
+    );
+    render_single(
+        &mut output,
+        "tantivy_search_skip_index_cache_entries",
+        "gauge",
+        "Entries of skip index query cache.",
+        CACHE_FOR_SKIP_INDEX.len() as u64,
+    );
+    if let Ok(stats) = AllocatorMemory::stats() {
+        render_single(
+            &mut output,
+            "tantivy_search_allocator_allocated_bytes",
+            "gauge",
+            "Bytes allocated by tantivy-search, 0 if allocator doesn't report it.",
+            stats.allocated_bytes,
+        );
+        render_single(
+            &mut output,
+            "tantivy_search_allocator_resident_bytes",
+            "gauge",
+            "Resident bytes mapped by allocator of tantivy-search, 0 if allocator doesn't report it.",
+            stats.resident_bytes,
+        );
+        render_single(
+            &mut output,
+            "tantivy_search_allocator_retained_bytes",
+            "gauge",
+            "Bytes retained by allocator of tantivy-search instead of returned to the OS.",
+            stats.retained_bytes,
+        );
+    }
+
+    let writers = FFI_INDEX_WRITER_CACHE.all_index_writer_bridges();
+    render_index_gauge(
+        &mut output,
+        "tantivy_search_uncommitted_operations",
+        "Add/delete operations since last commit.",
+        &collect_writer_gauge(&writers, |bridge| bridge.uncommitted_operations()),
+    );
+    render_index_gauge(
+        &mut output,
+        "tantivy_search_uncommitted_bytes",
+        "Estimated bytes indexed since last commit.",
+        &collect_writer_gauge(&writers, |bridge| {
+            bridge.stats.uncommitted_bytes.load(Ordering::Relaxed)
+        }),
+    );
+    render_index_gauge(
+        &mut output,
+        "tantivy_search_searchable_segments",
+        "Searchable segments after last commit.",
+        &collect_writer_gauge(&writers, |bridge| {
+            bridge.stats.searchable_segments.load(Ordering::Relaxed)
+        }),
+    );
+    render_index_gauge(
+        &mut output,
+        "tantivy_search_writer_lock_contentions",
+        "Times index writer lock was contended.",
+        &collect_writer_gauge(&writers, |bridge| {
+            bridge.stats.lock_contention_count.load(Ordering::Relaxed)
+        }),
+    );
+
+    let readers: Vec<(String, u64)> = FFI_INDEX_SEARCHER_CACHE
+        .all_index_reader_bridges()
+        .iter()
+        .map(|(index_path, bridge)| (index_path.clone(), bridge.row_id_mapping_bytes()))
+        .collect();
+    render_index_gauge(
+        &mut output,
+        "tantivy_search_row_id_mapping_bytes",
+        "Memory held by DocId and row_id mapping cache of index reader.",
+        &readers,
+    );
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_histogram() {
+        let histogram = LatencyHistogram::new();
+        histogram.observe(Duration::from_micros(300));
+        histogram.observe(Duration::from_millis(20));
+        histogram.observe(Duration::from_secs(10));
+        assert_eq!(histogram.count(), 3);
+
+        let mut output = String::new();
+        histogram.render(&mut output, "test_latency_seconds", "Test latency.");
+        assert!(output.contains("# TYPE test_latency_seconds histogram"));
+        assert!(output.contains("test_latency_seconds_bucket{le=\"0.0005\"} 1"));
+        assert!(output.contains("test_latency_seconds_bucket{le=\"0.025\"} 2"));
+        assert!(output.contains("test_latency_seconds_bucket{le=\"5\"} 2"));
+        assert!(output.contains("test_latency_seconds_bucket{le=\"+Inf\"} 3"));
+        assert!(output.contains("test_latency_seconds_count 3"));
+    }
+
+    #[test]
+    fn test_escape_label_value() {
+        assert_eq!(escape_label_value("/tmp/a\"b\\c\n"), "/tmp/a\\\"b\\\\c\\n");
+    }
+
+    #[test]
+    fn test_tantivy_metrics_prometheus() {
+        METRICS.record_query(Duration::from_millis(1), true);
+        let output = tantivy_metrics_prometheus();
+        assert!(output.contains("# TYPE tantivy_search_queries_total counter"));
+        assert!(output.contains("# TYPE tantivy_search_query_latency_seconds histogram"));
+        assert!(output.contains("# TYPE tantivy_search_index_writers gauge"));
+        assert!(output.contains("# TYPE tantivy_search_uncommitted_operations gauge"));
+    }
+}
@@ -1,5 +1,24 @@
+pub mod allocator;
+pub mod bitmap_union_pool;
 pub mod cache;
+pub mod callback_cell;
+pub mod canonical_index_paths;
 pub mod constants;
 pub mod converter;
+pub mod disk_space_guard;
 pub mod errors;
+pub mod geo;
+pub mod heartbeat;
+pub mod hnsw;
+pub mod hyper_log_log;
+pub mod idle_index_janitor;
+pub mod index_namespaces;
+pub mod metrics;
+pub mod open_files_budget;
+pub mod query_audit;
+pub mod query_cost_guard;
+pub mod single_flight;
+pub mod t_digest;
 pub mod tests;
+pub mod thread_affinity;
+pub mod utf8_policy;
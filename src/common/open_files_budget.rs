@@ -0,0 +1,133 @@
+use std::fs;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Kind of the index bridge being loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexBridgeKind {
+    Writer,
+    Reader,
+}
+
+impl IndexBridgeKind {
+    fn name(&self) -> &'static str {
+        match self {
+            IndexBridgeKind::Writer => "writer",
+            IndexBridgeKind::Reader => "reader",
+        }
+    }
+
+    /// Descriptors held by a loaded bridge of this kind. Segment files are memory mapped and
+    /// their descriptors are closed once mapped, a writer only keeps its lock file open.
+    pub fn held_descriptors(&self) -> u64 {
+        match self {
+            IndexBridgeKind::Writer => 1,
+            IndexBridgeKind::Reader => 0,
+        }
+    }
+
+    // Descriptors needed while loading: the held ones and one segment file being mapped.
+    fn required_descriptors(&self) -> u64 {
+        self.held_descriptors() + 1
+    }
+}
+
+/// Process wide budget of open file descriptors, 0 means unlimited. Loads of index writers and
+/// readers past the budget are refused instead of letting process hit EMFILE in the middle of
+/// a query. Descriptors are counted from the process descriptor table, so a load costs one
+/// directory listing regardless of how many indexes are loaded.
+pub struct OpenFilesBudget {
+    max_open_files: AtomicU64,
+}
+
+impl OpenFilesBudget {
+    pub const fn new() -> Self {
+        OpenFilesBudget {
+            max_open_files: AtomicU64::new(0),
+        }
+    }
+
+    pub fn max_open_files(&self) -> u64 {
+        self.max_open_files.load(Ordering::Relaxed)
+    }
+
+    pub fn set_max_open_files(&self, max_open_files: u64) {
+        self.max_open_files.store(max_open_files, Ordering::Relaxed);
+    }
+
+    // Descriptors held open by the process, 0 on platforms listing neither `/proc/self/fd` nor
+    // `/dev/fd`, budget isn't enforced there.
+    pub fn open_files(&self) -> u64 {
+        let entries = match fs::read_dir("/proc/self/fd").or_else(|_| fs::read_dir("/dev/fd")) {
+            Ok(entries) => entries,
+            Err(_) => return 0,
+        };
+        // The listing holds a descriptor of its own.
+        (entries.count() as u64).saturating_sub(1)
+    }
+
+    /// Check whether an index can be loaded as `kind` within budget.
+    pub fn check_load(&self, index_path: &str, kind: IndexBridgeKind) -> Result<(), String> {
+        let max_open_files = self.max_open_files();
+        if max_open_files == 0 {
+            return Ok(());
+        }
+        Self::fits(
+            max_open_files,
+            self.open_files(),
+            kind.required_descriptors(),
+        )
+        .map_err(|e| {
+            format!(
+                "Can't load index {} of [{}], {}, free some indexes or raise the budget",
+                kind.name(),
+                index_path,
+                e
+            )
+        })
+    }
+
+    fn fits(max_open_files: u64, held: u64, required: u64) -> Result<(), String> {
+        if held.saturating_add(required) > max_open_files {
+            return Err(format!(
+                "{} files held open and {} files required exceed budget {}",
+                held, required, max_open_files
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_load() {
+        // Budget is local, global budget used by loads in other tests isn't touched.
+        let budget = OpenFilesBudget::new();
+        assert!(budget
+            .check_load("/data/index", IndexBridgeKind::Writer)
+            .is_ok());
+
+        // Test process holds at least its standard streams.
+        #[cfg(target_os = "linux")]
+        {
+            assert!(budget.open_files() >= 3);
+            budget.set_max_open_files(1);
+            assert!(budget
+                .check_load("/data/index", IndexBridgeKind::Reader)
+                .is_err());
+        }
+        budget.set_max_open_files(u64::MAX);
+        assert!(budget
+            .check_load("/data/index", IndexBridgeKind::Writer)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_fits() {
+        assert!(OpenFilesBudget::fits(10, 4, 6).is_ok());
+        assert!(OpenFilesBudget::fits(10, 5, 6).is_err());
+        assert!(OpenFilesBudget::fits(10, u64::MAX, 1).is_err());
+    }
+}
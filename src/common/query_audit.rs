@@ -0,0 +1,236 @@
+use crate::common::callback_cell::CallbackCell;
+use crate::common::constants::QueryAuditCallback;
+use rand::Rng;
+use serde_json::json;
+use std::ffi::CString;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A search recorded by `QueryAudit`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryAuditRecord {
+    pub timestamp_millis: u64,
+    pub index_path: String,
+    /// Principal bound to the searching thread, empty if none.
+    pub principal: String,
+    pub query: String,
+    pub hits: u64,
+    pub latency: Duration,
+}
+
+impl QueryAuditRecord {
+    pub fn new(
+        index_path: &str,
+        principal: &str,
+        query: String,
+        hits: u64,
+        latency: Duration,
+    ) -> Self {
+        let timestamp_millis: u64 = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since_epoch| since_epoch.as_millis() as u64);
+        QueryAuditRecord {
+            timestamp_millis,
+            index_path: index_path.to_string(),
+            principal: principal.to_string(),
+            query,
+            hits,
+            latency,
+        }
+    }
+
+    /// One line of audit log file.
+    pub fn to_json_line(&self) -> String {
+        let mut line: String = json!({
+            "timestamp_millis": self.timestamp_millis,
+            "index_path": self.index_path,
+            "principal": self.principal,
+            "query": self.query,
+            "hits": self.hits,
+            "latency_micros": self.latency.as_micros() as u64,
+        })
+        .to_string();
+        line.push('\n');
+        line
+    }
+}
+
+/// Process wide audit log of searches, required by hosts with compliance requirements.
+/// A sampled fraction of searches is passed to host audit callback and appended to an audit
+/// log file as json lines. Auditing is off until a sampling rate above 0 and a sink are set.
+pub struct QueryAudit {
+    // Bits of f64 sampling rate in [0, 1].
+    sample_rate: AtomicU64,
+    file: Mutex<Option<File>>,
+    callback: QueryAuditCallbackCell,
+}
+
+impl QueryAudit {
+    pub fn new() -> Self {
+        QueryAudit {
+            sample_rate: AtomicU64::new(0f64.to_bits()),
+            file: Mutex::new(None),
+            callback: QueryAuditCallbackCell::new(),
+        }
+    }
+
+    pub fn sample_rate(&self) -> f64 {
+        f64::from_bits(self.sample_rate.load(Ordering::Relaxed))
+    }
+
+    /// Audit `sample_rate` of searches into audit log file `file_path`, `None` keeps no file.
+    /// Records are appended to an existing file.
+    pub fn configure(&self, file_path: Option<&str>, sample_rate: f64) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&sample_rate) {
+            return Err(format!(
+                "sample_rate must be in [0, 1], but got {}",
+                sample_rate
+            ));
+        }
+        let file: Option<File> = match file_path {
+            Some(file_path) => Some(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(file_path)
+                    .map_err(|e| format!("Can't open audit log file {}: {}", file_path, e))?,
+            ),
+            None => None,
+        };
+        *self.file.lock().map_err(|e| format!("Lock error: {}", e))? = file;
+        self.sample_rate
+            .store(sample_rate.to_bits(), Ordering::Relaxed);
+        Ok(())
+    }
+
+    pub fn callback(&self) -> &QueryAuditCallbackCell {
+        &self.callback
+    }
+
+    /// Whether a search should be recorded, decided before its query text is built.
+    pub fn sampled(&self) -> bool {
+        let sample_rate: f64 = self.sample_rate();
+        if sample_rate <= 0.0 || !self.has_sink() {
+            return false;
+        }
+        sample_rate >= 1.0 || rand::thread_rng().gen::<f64>() < sample_rate
+    }
+
+    fn has_sink(&self) -> bool {
+        self.callback.get().is_some() || self.file.lock().map_or(false, |file| file.is_some())
+    }
+
+    /// Pass `record` to audit callback and audit log file.
+    pub fn record(&self, record: &QueryAuditRecord) -> Result<(), String> {
+        self.callback.notify(record);
+        let mut file = self.file.lock().map_err(|e| format!("Lock error: {}", e))?;
+        if let Some(file) = file.as_mut() {
+            // A single write keeps lines of concurrent searches whole.
+            file.write_all(record.to_json_line().as_bytes())
+                .map_err(|e| format!("Can't write audit log file: {}", e))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for QueryAudit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Holds the host audit callback.
+pub type QueryAuditCallbackCell = CallbackCell<QueryAuditCallback>;
+
+impl QueryAuditCallbackCell {
+    // Invoke callback, strings containing nul byte are passed as empty strings.
+    pub fn notify(&self, record: &QueryAuditRecord) {
+        if let Some(callback) = self.get() {
+            let index_path = CString::new(record.index_path.as_str()).unwrap_or_default();
+            let principal = CString::new(record.principal.as_str()).unwrap_or_default();
+            let query = CString::new(record.query.as_str()).unwrap_or_default();
+            callback(
+                record.timestamp_millis,
+                index_path.as_ptr(),
+                principal.as_ptr(),
+                query.as_ptr(),
+                record.hits,
+                record.latency.as_micros() as u64,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libc::c_char;
+    use std::ffi::CStr;
+    use std::sync::atomic::AtomicUsize;
+    use tempfile::TempDir;
+
+    static AUDITED: AtomicUsize = AtomicUsize::new(0);
+    static AUDITED_HITS: AtomicU64 = AtomicU64::new(0);
+
+    extern "C" fn audit_callback(
+        _timestamp_millis: u64,
+        _index_path: *const c_char,
+        principal: *const c_char,
+        query: *const c_char,
+        hits: u64,
+        _latency_micros: u64,
+    ) {
+        let principal: &str = unsafe { CStr::from_ptr(principal) }.to_str().unwrap();
+        let query: &str = unsafe { CStr::from_ptr(query) }.to_str().unwrap();
+        assert_eq!((principal, query), ("alice", "text:hello"));
+        AUDITED.fetch_add(1, Ordering::SeqCst);
+        AUDITED_HITS.fetch_add(hits, Ordering::SeqCst);
+    }
+
+    #[test]
+    fn test_query_audit() {
+        let temp_directory: TempDir = TempDir::new().unwrap();
+        let file_path = temp_directory.path().join("audit.log");
+        let audit = QueryAudit::new();
+        // Off by default, and without a sink.
+        assert!(!audit.sampled());
+        assert!(audit.configure(None, 1.5).is_err());
+        audit.configure(None, 1.0).unwrap();
+        assert!(!audit.sampled());
+
+        audit
+            .configure(Some(file_path.to_str().unwrap()), 1.0)
+            .unwrap();
+        assert!(audit.sampled());
+        assert!(audit.callback().set(audit_callback).is_none());
+        let record = QueryAuditRecord::new(
+            "/data/index",
+            "alice",
+            "text:hello".to_string(),
+            3,
+            Duration::from_micros(1500),
+        );
+        audit.record(&record).unwrap();
+        audit.record(&record).unwrap();
+        assert_eq!(AUDITED.load(Ordering::SeqCst), 2);
+        assert_eq!(AUDITED_HITS.load(Ordering::SeqCst), 6);
+
+        let lines: Vec<serde_json::Value> = std::fs::read_to_string(&file_path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["principal"], "alice");
+        assert_eq!(lines[0]["hits"], 3);
+        assert_eq!(lines[0]["latency_micros"], 1500);
+
+        // Nothing is sampled at rate 0.
+        audit.configure(None, 0.0).unwrap();
+        assert!((0..100).all(|_| !audit.sampled()));
+        assert!(audit.callback().clear().is_some());
+    }
+}
@@ -0,0 +1,178 @@
+use crate::common::constants::METRICS;
+use regex_syntax::hir::{Hir, HirKind};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Cost limits of a single query, 0 means unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueryCostLimits {
+    /// Leaf clauses of a query, i.e. terms of term sets and parsed sentences.
+    pub max_clause_count: u64,
+    /// States of the automaton a regex or LIKE pattern is compiled into.
+    pub max_regex_states: u64,
+    /// Index terms a regex or LIKE pattern (prefix patterns included) expands to in a segment.
+    pub max_expanded_terms: u64,
+    /// Row ids of a bitmap query result.
+    pub max_bitmap_size: u64,
+}
+
+/// Process wide guard refusing pathological queries before they are executed, so a single user
+/// query can't exhaust CPU and memory of a server shared by many users. Refused queries fail
+/// with `IndexSearcherError::QueryTooExpensive`. No limit is set by default.
+pub struct QueryCostGuard {
+    max_clause_count: AtomicU64,
+    max_regex_states: AtomicU64,
+    max_expanded_terms: AtomicU64,
+    max_bitmap_size: AtomicU64,
+}
+
+impl QueryCostGuard {
+    pub const fn new() -> Self {
+        QueryCostGuard {
+            max_clause_count: AtomicU64::new(0),
+            max_regex_states: AtomicU64::new(0),
+            max_expanded_terms: AtomicU64::new(0),
+            max_bitmap_size: AtomicU64::new(0),
+        }
+    }
+
+    pub fn limits(&self) -> QueryCostLimits {
+        QueryCostLimits {
+            max_clause_count: self.max_clause_count.load(Ordering::Relaxed),
+            max_regex_states: self.max_regex_states.load(Ordering::Relaxed),
+            max_expanded_terms: self.max_expanded_terms.load(Ordering::Relaxed),
+            max_bitmap_size: self.max_bitmap_size.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn set_limits(&self, limits: QueryCostLimits) {
+        self.max_clause_count
+            .store(limits.max_clause_count, Ordering::Relaxed);
+        self.max_regex_states
+            .store(limits.max_regex_states, Ordering::Relaxed);
+        self.max_expanded_terms
+            .store(limits.max_expanded_terms, Ordering::Relaxed);
+        self.max_bitmap_size
+            .store(limits.max_bitmap_size, Ordering::Relaxed);
+    }
+
+    pub fn check_clause_count(&self, clause_count: u64) -> Result<(), String> {
+        Self::check("clauses", clause_count, &self.max_clause_count)
+    }
+
+    /// Check automaton states of regex `pattern`, a pattern which can't be parsed passes,
+    /// it's reported when the query is built.
+    pub fn check_regex_states(&self, pattern: &str) -> Result<(), String> {
+        if self.max_regex_states.load(Ordering::Relaxed) == 0 {
+            return Ok(());
+        }
+        let hir: Hir = match regex_syntax::parse(pattern) {
+            Ok(hir) => hir,
+            Err(_) => return Ok(()),
+        };
+        Self::check("regex states", regex_states(&hir), &self.max_regex_states)
+    }
+
+    pub fn check_expanded_terms(&self, expanded_terms: u64) -> Result<(), String> {
+        Self::check("expanded terms", expanded_terms, &self.max_expanded_terms)
+    }
+
+    pub fn check_bitmap_size(&self, bitmap_size: u64) -> Result<(), String> {
+        Self::check("bitmap row ids", bitmap_size, &self.max_bitmap_size)
+    }
+
+    /// Limit of expanded terms, counting terms can stop past it. 0 if unlimited.
+    pub fn max_expanded_terms(&self) -> u64 {
+        self.max_expanded_terms.load(Ordering::Relaxed)
+    }
+
+    /// Limit of bitmap row ids, 0 if unlimited.
+    pub fn max_bitmap_size(&self) -> u64 {
+        self.max_bitmap_size.load(Ordering::Relaxed)
+    }
+
+    fn check(name: &str, cost: u64, limit: &AtomicU64) -> Result<(), String> {
+        let limit: u64 = limit.load(Ordering::Relaxed);
+        if limit == 0 || cost <= limit {
+            return Ok(());
+        }
+        METRICS
+            .expensive_queries_rejected
+            .fetch_add(1, Ordering::Relaxed);
+        Err(format!("{} {} exceed limit {}", cost, name, limit))
+    }
+}
+
+impl Default for QueryCostGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// States of Thompson NFA of `hir` with bounded repetitions unrolled, a lower bound of states
+// of the DFA term dictionary is intersected with. Counted repetitions such as `(a|b){1000}`
+// are what makes regex automatons explode.
+fn regex_states(hir: &Hir) -> u64 {
+    match hir.kind() {
+        HirKind::Empty | HirKind::Look(_) => 0,
+        HirKind::Literal(literal) => literal.0.len() as u64,
+        HirKind::Class(_) => 1,
+        HirKind::Repetition(repetition) => {
+            let copies: u64 = match repetition.max {
+                Some(max) => max as u64,
+                None => repetition.min as u64 + 1,
+            };
+            regex_states(&repetition.sub)
+                .saturating_mul(copies.max(1))
+                .saturating_add(1)
+        }
+        HirKind::Capture(capture) => regex_states(&capture.sub),
+        HirKind::Concat(subs) => subs
+            .iter()
+            .fold(0, |states, sub| states.saturating_add(regex_states(sub))),
+        HirKind::Alternation(subs) => subs
+            .iter()
+            .fold(1, |states, sub| states.saturating_add(regex_states(sub))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_cost_guard() {
+        let guard = QueryCostGuard::new();
+        // Unlimited by default.
+        assert_eq!(guard.limits(), QueryCostLimits::default());
+        assert!(guard.check_clause_count(u64::MAX).is_ok());
+        assert!(guard.check_regex_states("(a|b){1000}").is_ok());
+
+        guard.set_limits(QueryCostLimits {
+            max_clause_count: 10,
+            max_regex_states: 100,
+            max_expanded_terms: 50,
+            max_bitmap_size: 1000,
+        });
+        assert!(guard.check_clause_count(10).is_ok());
+        assert!(guard
+            .check_clause_count(11)
+            .unwrap_err()
+            .contains("11 clauses exceed limit 10"));
+        assert!(guard.check_regex_states("abc.*").is_ok());
+        assert!(guard.check_regex_states("(a|b){1000}").is_err());
+        assert!(guard.check_regex_states("(unclosed").is_ok());
+        assert!(guard.check_expanded_terms(50).is_ok());
+        assert!(guard.check_expanded_terms(51).is_err());
+        assert!(guard.check_bitmap_size(1001).is_err());
+        assert_eq!(guard.max_expanded_terms(), 50);
+    }
+
+    #[test]
+    fn test_regex_states() {
+        let states = |pattern: &str| regex_states(&regex_syntax::parse(pattern).unwrap());
+        assert_eq!(states("abc"), 3);
+        assert_eq!(states("abc|de"), 6);
+        assert!(states("a{200}") > 200);
+        assert!(states("(ab){10}c") > states("(ab)*c"));
+    }
+}
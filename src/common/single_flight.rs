@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+
+/// Deduplicate concurrent executions of the same key. First caller of a key runs it, callers
+/// arriving while it runs wait and share its result, so a burst of identical expensive queries
+/// runs once. Results aren't kept after the run finishes.
+pub struct SingleFlight<K, V> {
+    flights: Mutex<HashMap<K, Arc<Flight<V>>>>,
+}
+
+struct Flight<V> {
+    state: Mutex<FlightState<V>>,
+    landed: Condvar,
+}
+
+struct FlightState<V> {
+    landed: bool,
+    // `None` after landing if the leader panicked.
+    result: Option<V>,
+}
+
+// Publish result of a leader and remove its flight, also when the leader panics.
+struct Landing<'a, K: Eq + Hash, V> {
+    single_flight: &'a SingleFlight<K, V>,
+    key: Option<K>,
+    flight: Arc<Flight<V>>,
+    result: Option<V>,
+}
+
+impl<'a, K: Eq + Hash, V> Drop for Landing<'a, K, V> {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            lock(&self.single_flight.flights).remove(&key);
+        }
+        let mut state = lock(&self.flight.state);
+        state.landed = true;
+        state.result = self.result.take();
+        self.flight.landed.notify_all();
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> SingleFlight<K, V> {
+    pub fn new() -> Self {
+        SingleFlight {
+            flights: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Run `f` for `key` unless it's running, returns result and whether it was shared.
+    pub fn run<F: FnOnce() -> V>(&self, key: K, f: F) -> (V, bool) {
+        let (flight, leader) = {
+            let mut flights = lock(&self.flights);
+            match flights.get(&key) {
+                Some(flight) => (flight.clone(), false),
+                None => {
+                    let flight: Arc<Flight<V>> = Arc::new(Flight {
+                        state: Mutex::new(FlightState {
+                            landed: false,
+                            result: None,
+                        }),
+                        landed: Condvar::new(),
+                    });
+                    flights.insert(key.clone(), flight.clone());
+                    (flight, true)
+                }
+            }
+        };
+        if !leader {
+            let mut state = lock(&flight.state);
+            while !state.landed {
+                state = flight.landed.wait(state).unwrap_or_else(|e| e.into_inner());
+            }
+            if let Some(result) = state.result.clone() {
+                return (result, true);
+            }
+            // Leader panicked, run it alone.
+            drop(state);
+            return (f(), false);
+        }
+        let mut landing = Landing {
+            single_flight: self,
+            key: Some(key),
+            flight,
+            result: None,
+        };
+        let result: V = f();
+        landing.result = Some(result.clone());
+        (result, false)
+    }
+
+    /// Keys running now.
+    pub fn in_flight(&self) -> usize {
+        lock(&self.flights).len()
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Default for SingleFlight<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn test_single_flight() {
+        let single_flight: Arc<SingleFlight<String, u64>> = Arc::new(SingleFlight::new());
+        let runs = Arc::new(AtomicUsize::new(0));
+        let (release, released) = mpsc::channel::<()>();
+
+        // Leader blocks until followers are waiting for it.
+        let leader = {
+            let single_flight = single_flight.clone();
+            let runs = runs.clone();
+            thread::spawn(move || {
+                single_flight.run("regex".to_string(), || {
+                    runs.fetch_add(1, Ordering::SeqCst);
+                    released.recv().unwrap();
+                    42
+                })
+            })
+        };
+        while single_flight.in_flight() == 0 {
+            thread::sleep(Duration::from_millis(1));
+        }
+        let followers: Vec<_> = (0..4)
+            .map(|_| {
+                let single_flight = single_flight.clone();
+                let runs = runs.clone();
+                thread::spawn(move || {
+                    single_flight.run("regex".to_string(), || {
+                        runs.fetch_add(1, Ordering::SeqCst);
+                        0
+                    })
+                })
+            })
+            .collect();
+        // Flight is held by map, leader and 4 waiting followers.
+        while lock(&single_flight.flights)
+            .get("regex")
+            .map_or(0, |flight| Arc::strong_count(flight))
+            < 6
+        {
+            thread::sleep(Duration::from_millis(1));
+        }
+        release.send(()).unwrap();
+
+        assert_eq!(leader.join().unwrap(), (42, false));
+        for follower in followers {
+            assert_eq!(follower.join().unwrap(), (42, true));
+        }
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert_eq!(single_flight.in_flight(), 0);
+
+        // Finished results aren't kept, other keys run on their own.
+        assert_eq!(single_flight.run("regex".to_string(), || 7), (7, false));
+        assert_eq!(single_flight.run("term".to_string(), || 8), (8, false));
+    }
+}
@@ -0,0 +1,267 @@
+use std::f64::consts::PI;
+
+/// Compression of digests built by searches, about `compression` centroids are kept.
+pub const TDIGEST_COMPRESSION: f64 = 100.0;
+
+// First byte of serialized digests, bumped when the layout changes.
+const TDIGEST_FORMAT_VERSION: u8 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Centroid {
+    mean: f64,
+    weight: u64,
+}
+
+/// T-digest of f64 values, clusters of values are small near both tails so extreme
+/// percentiles stay accurate. Digests are merged by compressing their centroids together,
+/// so segments and index parts are summarized separately and merged later.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TDigest {
+    compression: f64,
+    // Sorted by mean after `compress`.
+    centroids: Vec<Centroid>,
+    // Values not compressed into centroids yet.
+    buffer: Vec<f64>,
+    min: f64,
+    max: f64,
+}
+
+impl TDigest {
+    pub fn new(compression: f64) -> Result<Self, String> {
+        if !compression.is_finite() || compression < 10.0 {
+            return Err(format!(
+                "t-digest compression should be at least 10, got {}",
+                compression
+            ));
+        }
+        Ok(TDigest {
+            compression,
+            centroids: Vec::new(),
+            buffer: Vec::new(),
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        })
+    }
+
+    /// NaN values are ignored.
+    pub fn insert(&mut self, value: f64) {
+        if value.is_nan() {
+            return;
+        }
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.buffer.push(value);
+        if self.buffer.len() >= 5 * self.compression as usize {
+            self.compress();
+        }
+    }
+
+    pub fn merge(&mut self, other: &TDigest) -> Result<(), String> {
+        if self.compression != other.compression {
+            return Err(format!(
+                "Can't merge t-digest of compression {} into compression {}",
+                other.compression, self.compression
+            ));
+        }
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.centroids.extend_from_slice(&other.centroids);
+        self.buffer.extend_from_slice(&other.buffer);
+        self.compress();
+        Ok(())
+    }
+
+    pub fn count(&self) -> u64 {
+        self.centroids
+            .iter()
+            .map(|centroid| centroid.weight)
+            .sum::<u64>()
+            + self.buffer.len() as u64
+    }
+
+    /// Value at `percent` in `[0, 100]`, NaN if no value was inserted.
+    pub fn percentile(&mut self, percent: f64) -> f64 {
+        self.compress();
+        let total: f64 = self.count() as f64;
+        if self.centroids.is_empty() {
+            return f64::NAN;
+        }
+        let rank: f64 = (percent / 100.0).clamp(0.0, 1.0) * total;
+        let first: &Centroid = &self.centroids[0];
+        let last: &Centroid = &self.centroids[self.centroids.len() - 1];
+        // Values of a centroid are spread around its mean, interpolate between centers.
+        if rank <= first.weight as f64 / 2.0 {
+            return interpolate(self.min, first.mean, rank / (first.weight as f64 / 2.0));
+        }
+        if rank >= total - last.weight as f64 / 2.0 {
+            let tail: f64 = last.weight as f64 / 2.0;
+            return interpolate(last.mean, self.max, 1.0 - (total - rank) / tail);
+        }
+        let mut center: f64 = first.weight as f64 / 2.0;
+        for pair in self.centroids.windows(2) {
+            let next_center: f64 = center + (pair[0].weight + pair[1].weight) as f64 / 2.0;
+            if rank <= next_center {
+                return interpolate(
+                    pair[0].mean,
+                    pair[1].mean,
+                    (rank - center) / (next_center - center),
+                );
+            }
+            center = next_center;
+        }
+        last.mean
+    }
+
+    /// Serialized digest: format version, compression, min, max and centroids.
+    pub fn to_bytes(&mut self) -> Vec<u8> {
+        self.compress();
+        let mut bytes: Vec<u8> = Vec::with_capacity(29 + self.centroids.len() * 16);
+        bytes.push(TDIGEST_FORMAT_VERSION);
+        bytes.extend_from_slice(&self.compression.to_le_bytes());
+        bytes.extend_from_slice(&self.min.to_le_bytes());
+        bytes.extend_from_slice(&self.max.to_le_bytes());
+        bytes.extend_from_slice(&(self.centroids.len() as u32).to_le_bytes());
+        for centroid in &self.centroids {
+            bytes.extend_from_slice(&centroid.mean.to_le_bytes());
+            bytes.extend_from_slice(&centroid.weight.to_le_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < 29 || bytes[0] != TDIGEST_FORMAT_VERSION {
+            return Err("Not a t-digest of a supported version".to_string());
+        }
+        let read_u64 = |offset: usize| -> u64 {
+            let mut word: [u8; 8] = [0; 8];
+            word.copy_from_slice(&bytes[offset..offset + 8]);
+            u64::from_le_bytes(word)
+        };
+        let mut digest: TDigest = TDigest::new(f64::from_bits(read_u64(1)))?;
+        digest.min = f64::from_bits(read_u64(9));
+        digest.max = f64::from_bits(read_u64(17));
+        let mut num_centroids: [u8; 4] = [0; 4];
+        num_centroids.copy_from_slice(&bytes[25..29]);
+        let num_centroids: usize = u32::from_le_bytes(num_centroids) as usize;
+        if bytes.len() != 29 + num_centroids * 16 {
+            return Err(format!(
+                "t-digest of {} centroids should have {} bytes, got {}",
+                num_centroids,
+                29 + num_centroids * 16,
+                bytes.len()
+            ));
+        }
+        for idx in 0..num_centroids {
+            let offset: usize = 29 + idx * 16;
+            let centroid = Centroid {
+                mean: f64::from_bits(read_u64(offset)),
+                weight: read_u64(offset + 8),
+            };
+            if centroid.mean.is_nan() || centroid.weight == 0 {
+                return Err("t-digest has invalid centroids".to_string());
+            }
+            digest.centroids.push(centroid);
+        }
+        if digest
+            .centroids
+            .windows(2)
+            .any(|pair| pair[0].mean > pair[1].mean)
+        {
+            return Err("t-digest centroids are not sorted".to_string());
+        }
+        Ok(digest)
+    }
+
+    // Merge buffered values and centroids from smallest mean, a centroid grows while it stays in
+    // one unit of scale function `k(q) = compression / 2π * asin(2q - 1)`.
+    fn compress(&mut self) {
+        if self.buffer.is_empty()
+            && self
+                .centroids
+                .windows(2)
+                .all(|pair| pair[0].mean <= pair[1].mean)
+        {
+            return;
+        }
+        let mut pending: Vec<Centroid> = std::mem::take(&mut self.centroids);
+        pending.extend(self.buffer.drain(..).map(|value| Centroid {
+            mean: value,
+            weight: 1,
+        }));
+        pending.sort_by(|left, right| left.mean.total_cmp(&right.mean));
+        let total: f64 = pending.iter().map(|centroid| centroid.weight as f64).sum();
+
+        let mut merged: Vec<Centroid> = Vec::with_capacity(self.compression as usize * 2);
+        let mut weight_before: f64 = 0.0;
+        let mut weight_limit: f64 = total * self.max_quantile(0.0);
+        let mut current: Centroid = pending[0];
+        for next in pending.into_iter().skip(1) {
+            if weight_before + (current.weight + next.weight) as f64 <= weight_limit {
+                let weight: u64 = current.weight + next.weight;
+                current.mean += (next.mean - current.mean) * next.weight as f64 / weight as f64;
+                current.weight = weight;
+            } else {
+                weight_before += current.weight as f64;
+                weight_limit = total * self.max_quantile(weight_before / total);
+                merged.push(current);
+                current = next;
+            }
+        }
+        merged.push(current);
+        self.centroids = merged;
+    }
+
+    // Largest quantile of a centroid starting at quantile `q`.
+    #[inline]
+    fn max_quantile(&self, q: f64) -> f64 {
+        let scale: f64 = self.compression / (2.0 * PI);
+        let k: f64 = scale * (2.0 * q - 1.0).clamp(-1.0, 1.0).asin() + 1.0;
+        if k >= scale * PI / 2.0 {
+            return 1.0;
+        }
+        ((k / scale).sin() + 1.0) / 2.0
+    }
+}
+
+#[inline]
+fn interpolate(from: f64, to: f64, fraction: f64) -> f64 {
+    from + (to - from) * fraction.clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_t_digest() {
+        let mut digest = TDigest::new(TDIGEST_COMPRESSION).unwrap();
+        assert!(digest.percentile(50.0).is_nan());
+        for value in 1..=5000 {
+            digest.insert(value as f64);
+        }
+        assert_eq!(digest.count(), 5000);
+        assert_eq!(digest.percentile(0.0), 1.0);
+        assert_eq!(digest.percentile(100.0), 5000.0);
+        for (percent, expected) in [(50.0, 2500.0), (95.0, 4750.0), (99.0, 4950.0)] {
+            assert!((digest.percentile(percent) - expected).abs() < 5000.0 * 0.01);
+        }
+
+        // Digest of another part is merged.
+        let mut other = TDigest::new(TDIGEST_COMPRESSION).unwrap();
+        for value in 5001..=10000 {
+            other.insert(value as f64);
+        }
+        digest.merge(&other).unwrap();
+        assert_eq!(digest.count(), 10000);
+        assert!((digest.percentile(50.0) - 5000.0).abs() < 10000.0 * 0.01);
+        assert!((digest.percentile(99.0) - 9900.0).abs() < 10000.0 * 0.005);
+
+        let mut restored = TDigest::from_bytes(&digest.to_bytes()).unwrap();
+        assert_eq!(restored, digest);
+        assert_eq!(restored.percentile(99.0), digest.percentile(99.0));
+        assert!(TDigest::from_bytes(&digest.to_bytes()[..40]).is_err());
+        assert!(TDigest::from_bytes(&[]).is_err());
+        assert!(TDigest::new(1.0).is_err());
+        assert!(digest.merge(&TDigest::new(200.0).unwrap()).is_err());
+    }
+}
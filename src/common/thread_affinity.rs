@@ -0,0 +1,375 @@
+use crate::common::constants::THREAD_AFFINITY;
+use crate::logger::logger_bridge::TantivySearchLogger;
+use crate::{common::constants::LOG_CALLBACK, WARNING};
+use rayon::ThreadPoolBuilder;
+use std::io;
+use std::sync::RwLock;
+use std::thread::{self, JoinHandle};
+
+/// Category of an internal thread. Threads are named `{prefix}-{name}` so host profilers and
+/// cgroup tooling can attribute CPU usage of the library, linux keeps the first 15 bytes of a
+/// thread name and every prefix fits in them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThreadKind {
+    /// Searches, bitmap unions and reader maintenance.
+    Search,
+    /// Indexing, commits and builds.
+    Ingest,
+    /// Segment merges.
+    Merge,
+}
+
+impl ThreadKind {
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            ThreadKind::Search => "tantivy-search",
+            ThreadKind::Ingest => "tantivy-ingest",
+            ThreadKind::Merge => "tantivy-merge",
+        }
+    }
+
+    pub fn thread_name(&self, name: &str) -> String {
+        format!("{}-{}", self.prefix(), name)
+    }
+}
+
+// Threads spawned by tantivy itself, by the prefix of their name, they are renamed into the
+// category of the library.
+const TANTIVY_THREADS: [(&str, ThreadKind); 5] = [
+    ("thrd-tantivy-se", ThreadKind::Search),
+    ("thrd-tantivy-in", ThreadKind::Ingest),
+    ("docstore-compre", ThreadKind::Ingest),
+    ("segment_updater", ThreadKind::Merge),
+    ("merge_thread_", ThreadKind::Merge),
+];
+
+// Longest thread name kept by linux.
+const MAX_THREAD_NAME_BYTES: usize = 15;
+
+/// Rayon pool builder whose threads are named `{prefix}-{name}-{index}` and pinned to the
+/// configured CPU set when they start.
+pub fn thread_pool_builder(kind: ThreadKind, name: &'static str) -> ThreadPoolBuilder {
+    ThreadPoolBuilder::new()
+        .thread_name(move |index| kind.thread_name(&format!("{}-{}", name, index)))
+        .start_handler(|_| pin_started_thread())
+}
+
+/// Spawn a thread named `{prefix}-{name}` pinned to the configured CPU set.
+pub fn spawn_thread<F, T>(kind: ThreadKind, name: &str, task: F) -> io::Result<JoinHandle<T>>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    thread::Builder::new()
+        .name(kind.thread_name(name))
+        .spawn(move || {
+            pin_started_thread();
+            task()
+        })
+}
+
+fn pin_started_thread() {
+    if let Err(e) = THREAD_AFFINITY.pin_current_thread() {
+        WARNING!(function: "ThreadAffinity", "Failed to pin thread {:?}: {}", thread::current().name(), e);
+    }
+}
+
+/// Process wide CPU set internal threads are pinned to, so hosts can keep the library off
+/// cores reserved for other work. Threads are unpinned by default. Pinning is supported on
+/// linux only.
+pub struct ThreadAffinity {
+    // `None` leaves threads unpinned.
+    cpus: RwLock<Option<Vec<usize>>>,
+}
+
+impl ThreadAffinity {
+    pub const fn new() -> Self {
+        ThreadAffinity {
+            cpus: RwLock::new(None),
+        }
+    }
+
+    pub fn cpus(&self) -> Option<Vec<usize>> {
+        self.cpus.read().ok().and_then(|cpus| cpus.clone())
+    }
+
+    /// Pin internal threads to `cpus`, running threads included. Empty `cpus` unpins them,
+    /// they may run on any CPU of the process again.
+    pub fn set_cpus(&self, cpus: &[usize]) -> Result<(), String> {
+        let cpus: Option<Vec<usize>> = if cpus.is_empty() {
+            None
+        } else {
+            let mut cpus: Vec<usize> = cpus.to_vec();
+            cpus.sort_unstable();
+            cpus.dedup();
+            platform::check_cpus(&cpus)?;
+            Some(cpus)
+        };
+        *self
+            .cpus
+            .write()
+            .map_err(|e| format!("Lock error: {}", e))? = cpus.clone();
+        platform::rename_tantivy_threads()?;
+        self.apply(cpus.as_deref())
+    }
+
+    /// Pin calling thread to the configured CPU set, it's a no-op when threads are unpinned.
+    pub fn pin_current_thread(&self) -> Result<(), String> {
+        match self.cpus() {
+            Some(cpus) => platform::set_affinity(0, Some(&cpus)),
+            None => Ok(()),
+        }
+    }
+
+    /// Rename threads tantivy spawned, i.e. indexing and merge threads of index writers and
+    /// threads of search executors, into categories of the library and pin them. It should be
+    /// called after such threads are spawned. Returns renamed threads.
+    pub fn adopt_threads(&self) -> Result<usize, String> {
+        let renamed: usize = platform::rename_tantivy_threads()?;
+        if let Some(cpus) = self.cpus() {
+            self.apply(Some(&cpus))?;
+        }
+        Ok(renamed)
+    }
+
+    // Set affinity of every internal thread, `None` restores affinity of the process.
+    fn apply(&self, cpus: Option<&[usize]>) -> Result<(), String> {
+        for tid in platform::library_threads()? {
+            match platform::set_affinity(tid, cpus) {
+                Ok(()) => {}
+                // Thread may exit meanwhile.
+                Err(_) if !platform::thread_exists(tid) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for ThreadAffinity {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// New name of a tantivy thread named `name`, `None` if it isn't spawned by tantivy.
+fn adopted_name(name: &str) -> Option<String> {
+    TANTIVY_THREADS.iter().find_map(|(prefix, kind)| {
+        name.strip_prefix(prefix).map(|suffix| {
+            let mut adopted: String = kind.thread_name(suffix);
+            adopted.truncate(MAX_THREAD_NAME_BYTES);
+            adopted
+        })
+    })
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{adopted_name, ThreadKind};
+    use std::fs;
+    use std::io;
+    use std::mem;
+
+    fn last_error() -> String {
+        io::Error::last_os_error().to_string()
+    }
+
+    // CPUs the process may run on, i.e. affinity of its main thread.
+    fn process_cpus() -> Result<libc::cpu_set_t, String> {
+        let mut cpu_set: libc::cpu_set_t = unsafe { mem::zeroed() };
+        let code: libc::c_int = unsafe {
+            libc::sched_getaffinity(
+                libc::getpid(),
+                mem::size_of::<libc::cpu_set_t>(),
+                &mut cpu_set,
+            )
+        };
+        if code != 0 {
+            return Err(format!(
+                "Failed to get process CPU affinity: {}",
+                last_error()
+            ));
+        }
+        Ok(cpu_set)
+    }
+
+    pub fn check_cpus(cpus: &[usize]) -> Result<(), String> {
+        if let Some(cpu) = cpus.iter().find(|cpu| **cpu >= libc::CPU_SETSIZE as usize) {
+            return Err(format!(
+                "CPU {} exceeds maximum {}",
+                cpu,
+                libc::CPU_SETSIZE - 1
+            ));
+        }
+        let process_cpus: libc::cpu_set_t = process_cpus()?;
+        if !cpus
+            .iter()
+            .any(|cpu| unsafe { libc::CPU_ISSET(*cpu, &process_cpus) })
+        {
+            return Err(format!(
+                "None of CPUs {:?} is available to the process",
+                cpus
+            ));
+        }
+        Ok(())
+    }
+
+    // Set affinity of thread `tid`, 0 is the calling thread.
+    pub fn set_affinity(tid: libc::pid_t, cpus: Option<&[usize]>) -> Result<(), String> {
+        let cpu_set: libc::cpu_set_t = match cpus {
+            Some(cpus) => {
+                let mut cpu_set: libc::cpu_set_t = unsafe { mem::zeroed() };
+                for cpu in cpus {
+                    unsafe { libc::CPU_SET(*cpu, &mut cpu_set) };
+                }
+                cpu_set
+            }
+            None => process_cpus()?,
+        };
+        let code: libc::c_int =
+            unsafe { libc::sched_setaffinity(tid, mem::size_of::<libc::cpu_set_t>(), &cpu_set) };
+        if code != 0 {
+            return Err(format!(
+                "Failed to set CPU affinity of thread {}: {}",
+                tid,
+                last_error()
+            ));
+        }
+        Ok(())
+    }
+
+    // Threads of the process with their names.
+    fn threads() -> Result<Vec<(libc::pid_t, String)>, String> {
+        let entries = fs::read_dir("/proc/self/task")
+            .map_err(|e| format!("Failed to list threads: {}", e))?;
+        Ok(entries
+            .filter_map(|entry| {
+                let tid: libc::pid_t = entry.ok()?.file_name().to_str()?.parse().ok()?;
+                let name: String =
+                    fs::read_to_string(format!("/proc/self/task/{}/comm", tid)).ok()?;
+                Some((tid, name.trim_end().to_string()))
+            })
+            .collect())
+    }
+
+    pub fn thread_exists(tid: libc::pid_t) -> bool {
+        fs::metadata(format!("/proc/self/task/{}", tid)).is_ok()
+    }
+
+    pub fn library_threads() -> Result<Vec<libc::pid_t>, String> {
+        let prefixes =
+            [ThreadKind::Search, ThreadKind::Ingest, ThreadKind::Merge].map(|kind| kind.prefix());
+        Ok(threads()?
+            .into_iter()
+            .filter(|(_, name)| prefixes.iter().any(|prefix| name.starts_with(prefix)))
+            .map(|(tid, _)| tid)
+            .collect())
+    }
+
+    pub fn rename_tantivy_threads() -> Result<usize, String> {
+        let mut renamed: usize = 0;
+        for (tid, name) in threads()? {
+            if let Some(adopted) = adopted_name(&name) {
+                // Thread may exit meanwhile.
+                if fs::write(format!("/proc/self/task/{}/comm", tid), adopted).is_ok() {
+                    renamed += 1;
+                }
+            }
+        }
+        Ok(renamed)
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    pub fn check_cpus(_cpus: &[usize]) -> Result<(), String> {
+        Err("CPU affinity isn't supported on this platform".to_string())
+    }
+
+    pub fn set_affinity(_tid: i32, cpus: Option<&[usize]>) -> Result<(), String> {
+        match cpus {
+            Some(_) => check_cpus(&[]),
+            None => Ok(()),
+        }
+    }
+
+    pub fn thread_exists(_tid: i32) -> bool {
+        false
+    }
+
+    pub fn library_threads() -> Result<Vec<i32>, String> {
+        Ok(Vec::new())
+    }
+
+    pub fn rename_tantivy_threads() -> Result<usize, String> {
+        Ok(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thread_names() {
+        assert_eq!(
+            ThreadKind::Ingest.thread_name("commit-0"),
+            "tantivy-ingest-commit-0"
+        );
+        assert_eq!(
+            adopted_name("merge_thread_3").as_deref(),
+            Some("tantivy-merge-3")
+        );
+        assert_eq!(
+            adopted_name("thrd-tantivy-in").as_deref(),
+            Some("tantivy-ingest-")
+        );
+        assert_eq!(adopted_name("tokio-runtime-w"), None);
+
+        let pool = thread_pool_builder(ThreadKind::Search, "test")
+            .num_threads(1)
+            .build()
+            .unwrap();
+        let name = pool.install(|| thread::current().name().map(String::from));
+        assert_eq!(name.as_deref(), Some("tantivy-search-test-0"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_thread_affinity() {
+        let affinity = ThreadAffinity::new();
+        assert!(affinity.pin_current_thread().is_ok());
+        assert!(affinity.set_cpus(&[libc::CPU_SETSIZE as usize]).is_err());
+        assert_eq!(affinity.cpus(), None);
+
+        // A thread named by tantivy is renamed when it's adopted.
+        let (started, wait_started) = std::sync::mpsc::channel();
+        let (stop, wait_stop) = std::sync::mpsc::channel::<()>();
+        let merge_thread = thread::Builder::new()
+            .name("merge_thread_7".to_string())
+            .spawn(move || {
+                started
+                    .send(unsafe { libc::syscall(libc::SYS_gettid) } as libc::pid_t)
+                    .unwrap();
+                let _ = wait_stop.recv();
+            })
+            .unwrap();
+        let tid: libc::pid_t = wait_started.recv().unwrap();
+        assert!(affinity.adopt_threads().unwrap() >= 1);
+        let name: String =
+            std::fs::read_to_string(format!("/proc/self/task/{}/comm", tid)).unwrap();
+        assert_eq!(name.trim_end(), "tantivy-merge-7");
+
+        // Pin library threads to the first CPU available, then unpin them.
+        let cpu: usize = (0..libc::CPU_SETSIZE as usize)
+            .find(|cpu| platform::check_cpus(&[*cpu]).is_ok())
+            .unwrap();
+        affinity.set_cpus(&[cpu, cpu]).unwrap();
+        assert_eq!(affinity.cpus(), Some(vec![cpu]));
+        affinity.set_cpus(&[]).unwrap();
+        assert_eq!(affinity.cpus(), None);
+
+        stop.send(()).unwrap();
+        merge_thread.join().unwrap();
+    }
+}
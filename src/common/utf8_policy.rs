@@ -0,0 +1,127 @@
+use crate::common::constants::METRICS;
+use std::borrow::Cow;
+use std::str::Utf8Error;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// How invalid utf-8 in incoming text (ingested docs and query strings) is handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Utf8Policy {
+    /// Fail the call with an utf-8 error.
+    Reject = 0,
+    /// Replace invalid sequences with `U+FFFD`.
+    LossyReplace = 1,
+    /// Skip the ingested row containing invalid text, query strings are rejected.
+    SkipRow = 2,
+}
+
+impl Utf8Policy {
+    pub fn from_name(name: &str) -> Option<Utf8Policy> {
+        match name.to_lowercase().as_str() {
+            "reject" => Some(Utf8Policy::Reject),
+            "lossy" | "lossy_replace" | "lossy-replace" => Some(Utf8Policy::LossyReplace),
+            "skip_row" | "skip-row" => Some(Utf8Policy::SkipRow),
+            _ => None,
+        }
+    }
+
+    fn from_u8(value: u8) -> Utf8Policy {
+        match value {
+            1 => Utf8Policy::LossyReplace,
+            2 => Utf8Policy::SkipRow,
+            _ => Utf8Policy::Reject,
+        }
+    }
+}
+
+/// Result of sanitizing a piece of text.
+#[derive(Debug, PartialEq)]
+pub enum Sanitized<'a> {
+    Text(Cow<'a, str>),
+    SkipRow(Utf8Error),
+}
+
+/// Process wide utf-8 policy, it's applied in FFI conversion layer.
+pub struct Utf8PolicyCell {
+    policy: AtomicU8,
+}
+
+impl Utf8PolicyCell {
+    pub const fn new() -> Self {
+        Utf8PolicyCell {
+            policy: AtomicU8::new(Utf8Policy::Reject as u8),
+        }
+    }
+
+    pub fn get(&self) -> Utf8Policy {
+        Utf8Policy::from_u8(self.policy.load(Ordering::Relaxed))
+    }
+
+    pub fn set(&self, policy: Utf8Policy) {
+        self.policy.store(policy as u8, Ordering::Relaxed);
+    }
+
+    /// Sanitize `bytes` with current policy, valid text is borrowed without copy.
+    pub fn sanitize<'a>(&self, bytes: &'a [u8]) -> Result<Sanitized<'a>, Utf8Error> {
+        let error: Utf8Error = match std::str::from_utf8(bytes) {
+            Ok(text) => return Ok(Sanitized::Text(Cow::Borrowed(text))),
+            Err(e) => e,
+        };
+        match self.get() {
+            Utf8Policy::Reject => {
+                METRICS.utf8_rejected.fetch_add(1, Ordering::Relaxed);
+                Err(error)
+            }
+            Utf8Policy::LossyReplace => {
+                METRICS.utf8_replaced.fetch_add(1, Ordering::Relaxed);
+                Ok(Sanitized::Text(String::from_utf8_lossy(bytes)))
+            }
+            Utf8Policy::SkipRow => {
+                METRICS.utf8_skipped_rows.fetch_add(1, Ordering::Relaxed);
+                Ok(Sanitized::SkipRow(error))
+            }
+        }
+    }
+}
+
+impl Default for Utf8PolicyCell {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utf8_policy_sanitize() {
+        let cell = Utf8PolicyCell::new();
+        let invalid: &[u8] = b"abc\xffdef";
+        assert_eq!(
+            cell.sanitize(b"abc").unwrap(),
+            Sanitized::Text(Cow::Borrowed("abc"))
+        );
+        assert!(cell.sanitize(invalid).is_err());
+
+        cell.set(Utf8Policy::LossyReplace);
+        assert_eq!(
+            cell.sanitize(invalid).unwrap(),
+            Sanitized::Text(Cow::Owned("abc\u{FFFD}def".to_string()))
+        );
+
+        cell.set(Utf8Policy::SkipRow);
+        assert!(matches!(
+            cell.sanitize(invalid).unwrap(),
+            Sanitized::SkipRow(_)
+        ));
+        assert_eq!(cell.get(), Utf8Policy::SkipRow);
+
+        assert_eq!(
+            Utf8Policy::from_name("lossy-replace"),
+            Some(Utf8Policy::LossyReplace)
+        );
+        assert_eq!(Utf8Policy::from_name("skip_row"), Some(Utf8Policy::SkipRow));
+        assert_eq!(Utf8Policy::from_name("unknown"), None);
+    }
+}
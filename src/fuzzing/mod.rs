@@ -0,0 +1,144 @@
+//! Entry points of fuzz targets in `fuzz/`, enabled by feature `fuzzing`.
+//!
+//! Each entry takes arbitrary bytes the same way FFI receives host input. Returning an error is
+//! fine for malformed input, a panic means the input could crash the host process.
+use crate::search::implements::strategy::query_strategy::{
+    BM25QueryStrategy64, ParserQueryStrategy, QueryExecutor, QueryStrategy, RegexQueryStrategy,
+    SentenceQueryStrategy, SingleTermQueryStrategy, TermSetQueryStrategy,
+};
+use crate::search::utils::convert_utils::ConvertUtils;
+use crate::tokenizer::tokenizer_utils::TokenizerUtils;
+use once_cell::sync::Lazy;
+use roaring::RoaringBitmap;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tantivy::schema::{Schema, FAST, INDEXED, TEXT};
+use tantivy::tokenizer::TokenStream;
+use tantivy::{Index, IndexReader, TantivyDocument};
+
+// Small in-memory index searched by `fuzz_query`.
+static FUZZ_READER: Lazy<IndexReader> = Lazy::new(|| {
+    let mut schema_builder = Schema::builder();
+    let row_id_field = schema_builder.add_u64_field("row_id", FAST | INDEXED);
+    let text_field = schema_builder.add_text_field("text", TEXT);
+    let index = Index::create_in_ram(schema_builder.build());
+    let mut writer = index
+        .writer_with_num_threads(1, 15_000_000)
+        .expect("Can't create fuzz index writer");
+    let docs = [
+        "Ancient empires rise and fall, shaping history's course.",
+        "Artistic expressions reflect diverse cultural heritages.",
+        "古代帝国的兴衰塑造了历史的进程",
+        "",
+    ];
+    for (row_id, text) in docs.iter().enumerate() {
+        let mut doc = TantivyDocument::default();
+        doc.add_u64(row_id_field, row_id as u64);
+        doc.add_text(text_field, text);
+        writer
+            .add_document(doc)
+            .expect("Can't add fuzz index document");
+    }
+    writer.commit().expect("Can't commit fuzz index");
+    index.reader().expect("Can't create fuzz index reader")
+});
+
+/// Build and execute a query from host input, first byte chooses the query strategy.
+pub fn fuzz_query(data: &[u8]) {
+    let (selector, input) = match data.split_first() {
+        Some((selector, input)) => (*selector, String::from_utf8_lossy(input).to_string()),
+        None => return,
+    };
+    let searcher = FUZZ_READER.searcher();
+    let terms: Vec<String> = input.split_whitespace().map(str::to_string).collect();
+    let alived_ids: Vec<u64> = ConvertUtils::u8_bitmap_to_row_ids64(input.as_bytes());
+    let column_names: Vec<String> = vec!["text".to_string()];
+    let (topk, offset, flags) = (selector as u32 % 17, selector as u32 % 3, selector & 0xf0);
+    let search_after: Option<(f32, u64)> = match flags & 0x10 {
+        0 => None,
+        _ => Some((selector as f32 / 7.0, selector as u64)),
+    };
+
+    let bitmap_strategy: Option<Box<dyn QueryStrategy<Arc<RoaringBitmap>>>> = match selector % 6 {
+        0 => Some(Box::new(SingleTermQueryStrategy {
+            column_name: "text",
+            term: &input,
+        })),
+        1 => Some(Box::new(TermSetQueryStrategy {
+            column_name: "text",
+            terms: &terms,
+        })),
+        2 => Some(Box::new(SentenceQueryStrategy {
+            column_name: "text",
+            sentence: &input,
+        })),
+        3 => Some(Box::new(ParserQueryStrategy {
+            column_name: "text",
+            sentence: &input,
+        })),
+        4 => Some(Box::new(RegexQueryStrategy {
+            column_name: "text",
+            pattern: &input,
+        })),
+        _ => None,
+    };
+    match bitmap_strategy {
+        Some(strategy) => {
+            let _ = QueryExecutor::new(strategy.as_ref()).execute(&searcher);
+        }
+        None => {
+            let strategy = BM25QueryStrategy64 {
+                sentence: &input,
+                topk: &topk,
+                alived_ids: &alived_ids,
+                query_with_filter: &(flags & 0x20 != 0),
+                query_with_id_range: &(flags & 0x40 != 0),
+                start_id: &(selector as u64 % 3),
+                end_id: &(selector as u64 % 5),
+                need_doc: &(flags & 0x80 != 0),
+                column_names: &column_names,
+                offset: &offset,
+                search_after: &search_after,
+                field_boosts: &HashMap::new(),
+                similarity: &None,
+                diversify: &None,
+            };
+            let _ = QueryExecutor::new(&strategy).execute(&searcher);
+        }
+    }
+}
+
+/// Parse index json parameter from host input, index and query analyzers of text fields are run.
+pub fn fuzz_index_parameter(data: &[u8]) {
+    let json = String::from_utf8_lossy(data);
+    let _ = TokenizerUtils::varify_json_parameter(&json);
+    let _ = TokenizerUtils::parse_index_sort(&json);
+    if let Ok(col_tokenizer_map) = TokenizerUtils::parse_tokenizer_json_to_config_map(&json) {
+        for tokenizer_config in col_tokenizer_map.values() {
+            if tokenizer_config.is_text_field {
+                for mut text_analyzer in [
+                    tokenizer_config.text_analyzer.clone(),
+                    tokenizer_config.query_analyzer(),
+                ] {
+                    text_analyzer
+                        .token_stream("Ancient empires rise and fall, 古代帝国的兴衰")
+                        .process(&mut |_| {});
+                }
+            }
+        }
+    }
+}
+
+/// Decode u8 bitmap from host input, decoded row ids should match bits of the bitmap.
+pub fn fuzz_u8_bitmap(data: &[u8]) {
+    let row_ids: Vec<u64> = ConvertUtils::u8_bitmap_to_row_ids64(data);
+    let bits: u32 = data.iter().map(|byte| byte.count_ones()).sum();
+    assert_eq!(row_ids.len(), bits as usize);
+    assert!(row_ids.windows(2).all(|w| w[0] < w[1]));
+    assert!(row_ids.iter().all(|row_id| *row_id < data.len() as u64 * 8));
+
+    let row_ids: Vec<u32> = ConvertUtils::u8_bitmap_to_row_ids(data);
+    let encoded: Vec<u8> = ConvertUtils::row_ids_to_u8_bitmap(&row_ids);
+    assert_eq!(encoded.as_slice(), &data[..encoded.len()]);
+    assert!(data[encoded.len()..].iter().all(|byte| *byte == 0));
+}
@@ -0,0 +1,34 @@
+//! gRPC sidecar, serves search and ingest API of indexes declared in a config file.
+pub mod search_service;
+pub mod server_config;
+
+pub mod pb {
+    tonic::include_proto!("tantivy_search");
+}
+
+use crate::logger::logger_bridge::TantivySearchLogger;
+use crate::{common::constants::LOG_CALLBACK, INFO};
+use pb::tantivy_search_service_server::TantivySearchServiceServer;
+pub use search_service::SearchService;
+pub use server_config::{ServedIndexConfig, ServerConfig};
+use std::net::SocketAddr;
+
+/// Load indexes from `config_path` and serve them until the server stops.
+pub async fn run_server(config_path: &str) -> Result<(), String> {
+    let config = ServerConfig::from_file(config_path)?;
+    let listen_addr: SocketAddr = config
+        .listen_addr
+        .parse()
+        .map_err(|e| format!("Invalid listen_addr `{}`: {}", config.listen_addr, e))?;
+    let service = SearchService::load(&config).map_err(|e| e.to_string())?;
+    INFO!(
+        "Sidecar serving {} indexes on {}",
+        config.indexes.len(),
+        listen_addr
+    );
+    tonic::transport::Server::builder()
+        .add_service(TantivySearchServiceServer::new(service))
+        .serve(listen_addr)
+        .await
+        .map_err(|e| format!("Sidecar server error: {}", e))
+}
@@ -0,0 +1,219 @@
+use super::pb;
+use super::pb::tantivy_search_service_server::TantivySearchService;
+use super::server_config::{ServedIndexConfig, ServerConfig};
+use crate::async_api::*;
+use crate::common::errors::{IndexSearcherError, TantivySearchError};
+use crate::index::implements::api_index_impl::load_index_writer;
+use crate::logger::logger_bridge::TantivySearchLogger;
+use crate::search::implements::api_common_impl::load_index_reader;
+use crate::{common::constants::LOG_CALLBACK, INFO};
+use std::collections::HashMap;
+use tonic::{Request, Response, Status};
+
+fn to_status(error: TantivySearchError) -> Status {
+    match error {
+        TantivySearchError::InvalidArgument(_) => Status::invalid_argument(error.to_string()),
+        TantivySearchError::IndexNotExists(_) => Status::not_found(error.to_string()),
+        TantivySearchError::IncompatibleIndexVersion(_) => {
+            Status::failed_precondition(error.to_string())
+        }
+        TantivySearchError::Backpressure(_)
+        | TantivySearchError::OpenFilesBudgetExceeded(_)
+        | TantivySearchError::OutOfDiskSpace(_)
+        | TantivySearchError::IndexSearcherError(IndexSearcherError::QueryTooExpensive(_)) => {
+            Status::resource_exhausted(error.to_string())
+        }
+        _ => Status::internal(error.to_string()),
+    }
+}
+
+pub struct SearchService {
+    indexes: HashMap<String, ServedIndexConfig>,
+}
+
+impl SearchService {
+    /// Load readers (and writers of writable indexes) declared in config.
+    pub fn load(config: &ServerConfig) -> Result<Self, TantivySearchError> {
+        let mut indexes: HashMap<String, ServedIndexConfig> = HashMap::new();
+        for index in config.indexes.iter() {
+            if index.writable {
+                load_index_writer(&index.index_path)?;
+            }
+            load_index_reader(&index.index_path)?;
+            INFO!(
+                "Sidecar loaded index `{}`:[{}], writable:{}",
+                index.index_name,
+                index.index_path,
+                index.writable
+            );
+            indexes.insert(index.index_name.clone(), index.clone());
+        }
+        Ok(SearchService { indexes })
+    }
+
+    fn index(&self, index_name: &str) -> Result<&ServedIndexConfig, Status> {
+        self.indexes
+            .get(index_name)
+            .ok_or_else(|| Status::not_found(format!("Index `{}` is not served", index_name)))
+    }
+
+    fn writable_index(&self, index_name: &str) -> Result<&ServedIndexConfig, Status> {
+        let index = self.index(index_name)?;
+        if !index.writable {
+            return Err(Status::failed_precondition(format!(
+                "Index `{}` is read only",
+                index_name
+            )));
+        }
+        Ok(index)
+    }
+}
+
+#[tonic::async_trait]
+impl TantivySearchService for SearchService {
+    async fn bm25_search(
+        &self,
+        request: Request<pb::Bm25SearchRequest>,
+    ) -> Result<Response<pb::Bm25SearchResponse>, Status> {
+        let request = request.into_inner();
+        let index = self.index(&request.index_name)?;
+        let results = bm25_search_async(
+            &index.index_path,
+            &request.sentence,
+            request.topk,
+            request.column_names,
+            request.need_doc,
+        )
+        .await
+        .map_err(to_status)?;
+        let results = results
+            .into_iter()
+            .map(|result| pb::RowIdWithScore {
+                row_id: result.row_id,
+                score: result.score,
+                docs: result.docs,
+            })
+            .collect();
+        Ok(Response::new(pb::Bm25SearchResponse { results }))
+    }
+
+    async fn query_term_bitmap(
+        &self,
+        request: Request<pb::QueryTermBitmapRequest>,
+    ) -> Result<Response<pb::BitmapResponse>, Status> {
+        let request = request.into_inner();
+        let index = self.index(&request.index_name)?;
+        let bitmap =
+            query_term_bitmap_async(&index.index_path, &request.column_name, &request.term)
+                .await
+                .map_err(to_status)?;
+        Ok(Response::new(pb::BitmapResponse { bitmap }))
+    }
+
+    async fn index_documents(
+        &self,
+        request: Request<pb::IndexDocumentsRequest>,
+    ) -> Result<Response<pb::StatusResponse>, Status> {
+        let request = request.into_inner();
+        let index = self.writable_index(&request.index_name)?;
+        let documents: Vec<(u64, Vec<String>)> = request
+            .documents
+            .into_iter()
+            .map(|document| (document.row_id, document.column_docs))
+            .collect();
+        let result =
+            index_multi_column_docs_async(&index.index_path, request.column_names, documents)
+                .await
+                .map_err(to_status)?;
+        Ok(Response::new(pb::StatusResponse { result }))
+    }
+
+    async fn delete_row_ids(
+        &self,
+        request: Request<pb::DeleteRowIdsRequest>,
+    ) -> Result<Response<pb::StatusResponse>, Status> {
+        let request = request.into_inner();
+        let index = self.writable_index(&request.index_name)?;
+        let result = delete_row_ids_async(&index.index_path, request.row_ids)
+            .await
+            .map_err(to_status)?;
+        Ok(Response::new(pb::StatusResponse { result }))
+    }
+
+    async fn commit(
+        &self,
+        request: Request<pb::IndexRequest>,
+    ) -> Result<Response<pb::StatusResponse>, Status> {
+        let index = self.writable_index(&request.get_ref().index_name)?;
+        let result = commit_index_async(&index.index_path)
+            .await
+            .map_err(to_status)?;
+        Ok(Response::new(pb::StatusResponse { result }))
+    }
+
+    async fn reload_reader(
+        &self,
+        request: Request<pb::IndexRequest>,
+    ) -> Result<Response<pb::StatusResponse>, Status> {
+        let index = self.index(&request.get_ref().index_name)?;
+        let result = load_index_reader_async(&index.index_path)
+            .await
+            .map_err(to_status)?;
+        Ok(Response::new(pb::StatusResponse { result }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::tests::index_3column_docs_with_threads_merge;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_search_service() {
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        index_3column_docs_with_threads_merge(temp_directory_str);
+
+        let config = ServerConfig {
+            listen_addr: "127.0.0.1:0".to_string(),
+            indexes: vec![ServedIndexConfig {
+                index_name: "test".to_string(),
+                index_path: temp_directory_str.to_string(),
+                writable: false,
+            }],
+        };
+        let service = SearchService::load(&config).unwrap();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let response = service
+                .query_term_bitmap(Request::new(pb::QueryTermBitmapRequest {
+                    index_name: "test".to_string(),
+                    column_name: "col1".to_string(),
+                    term: "Ancient".to_string(),
+                }))
+                .await
+                .unwrap();
+            // Row 0 and row 4 contain `Ancient`.
+            assert_eq!(response.get_ref().bitmap[0], 17);
+
+            let status = service
+                .commit(Request::new(pb::IndexRequest {
+                    index_name: "test".to_string(),
+                }))
+                .await
+                .unwrap_err();
+            assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+
+            let status = service
+                .reload_reader(Request::new(pb::IndexRequest {
+                    index_name: "unknown".to_string(),
+                }))
+                .await
+                .unwrap_err();
+            assert_eq!(status.code(), tonic::Code::NotFound);
+        });
+    }
+}
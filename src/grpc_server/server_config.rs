@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+/// Index served by sidecar, `writable` index also accepts ingest requests.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServedIndexConfig {
+    pub index_name: String,
+    pub index_path: String,
+    #[serde(default)]
+    pub writable: bool,
+}
+
+/// Sidecar server config, loaded from a json file:
+/// `{"listen_addr": "127.0.0.1:50051", "indexes": [{"index_name": "...", "index_path": "...", "writable": true}]}`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServerConfig {
+    pub listen_addr: String,
+    pub indexes: Vec<ServedIndexConfig>,
+}
+
+impl ServerConfig {
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let config: ServerConfig =
+            serde_json::from_str(json).map_err(|e| format!("Invalid server config: {}", e))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn from_file(config_path: &str) -> Result<Self, String> {
+        let json = fs::read_to_string(config_path)
+            .map_err(|e| format!("Failed to read server config `{}`: {}", config_path, e))?;
+        Self::from_json(&json)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let mut index_names: HashMap<&str, &str> = HashMap::new();
+        for index in self.indexes.iter() {
+            if index.index_name.is_empty() || index.index_path.is_empty() {
+                return Err("`index_name` and `index_path` can't be empty".to_string());
+            }
+            if let Some(previous) = index_names.insert(&index.index_name, &index.index_path) {
+                return Err(format!(
+                    "Duplicated index_name `{}` for `{}` and `{}`",
+                    index.index_name, previous, index.index_path
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_server_config_from_json() {
+        let config = ServerConfig::from_json(
+            r#"{"listen_addr": "127.0.0.1:50051", "indexes": [{"index_name": "a", "index_path": "/tmp/a"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(config.indexes.len(), 1);
+        assert!(!config.indexes[0].writable);
+
+        assert!(ServerConfig::from_json("{}").is_err());
+        assert!(ServerConfig::from_json(
+            r#"{"listen_addr": "127.0.0.1:50051", "indexes": [
+                {"index_name": "a", "index_path": "/tmp/a"},
+                {"index_name": "a", "index_path": "/tmp/b"}]}"#,
+        )
+        .is_err());
+    }
+}
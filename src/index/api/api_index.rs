@@ -1,12 +1,39 @@
+use crate::common::constants::BACKPRESSURE_ERROR_CODE;
+use crate::common::constants::INCOMPATIBLE_INDEX_VERSION_ERROR_CODE;
+use crate::common::constants::OUT_OF_DISK_SPACE_ERROR_CODE;
+use crate::common::constants::UTF8_POLICY;
+use crate::common::errors::{CxxConvertError, TantivySearchError};
+use crate::common::utf8_policy::Sanitized;
+use crate::ffi::AllocatorStats;
+use crate::ffi::BuildProgress;
+use crate::ffi::IndexHealth;
+use crate::ffi::IndexSizeEstimate;
 use crate::index::implements::api_index_impl::*;
+use crate::index::implements::commit_tickets::{commit_async, commit_nowait, wait_commit};
+use crate::index::implements::reindex::reindex_index;
 use crate::logger::logger_bridge::TantivySearchLogger;
-use crate::BoolResult;
-use crate::{common::constants::LOG_CALLBACK, ERROR};
+use crate::{common::constants::LOG_CALLBACK, ERROR, WARNING};
 use crate::{
     cxx_vector_converter, CXX_STRING_CONERTER, CXX_VECTOR_STRING_CONERTER,
-    CXX_VECTOR_STRING_TO_BYTES_CONERTER,
+    CXX_VECTOR_STRING_TO_BYTES_CONERTER, INDEX_NAMESPACES,
+};
+use crate::{
+    AllocatorStatsResult, BoolResult, BuildProgressResult, IndexHealthResult,
+    IndexSizeEstimateResult, LivenessResult, SegmentDeleteStatsResult, StringResult, U64Result,
 };
 use cxx::{CxxString, CxxVector};
+use std::borrow::Cow;
+use std::str::Utf8Error;
+
+// Row skipped by utf-8 policy isn't an error, `result` is false since nothing is indexed.
+fn skipped_row_result(row_id: u64, error: Utf8Error) -> BoolResult {
+    WARNING!(function: "skipped_row_result", "Skip row_id {} for invalid utf-8: {}", row_id, error);
+    BoolResult {
+        result: false,
+        error_code: 0,
+        error_msg: format!("Row {} skipped for invalid utf-8: {}", row_id, error),
+    }
+}
 
 pub fn ffi_create_index_with_parameter(
     index_path: &CxxString,
@@ -71,6 +98,75 @@ pub fn ffi_create_index_with_parameter(
     }
 }
 
+pub fn ffi_create_index_with_build_mode(
+    index_path: &CxxString,
+    column_names: &CxxVector<CxxString>,
+    index_json_parameter: &CxxString,
+    deterministic_build: bool,
+) -> BoolResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_create_index_with_build_mode", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(column_names) {
+        Ok(names) => names,
+        Err(e) => {
+            ERROR!(function: "ffi_create_index_with_build_mode", "Can't convert 'column_names', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'column_names', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let index_json_parameter: String = match CXX_STRING_CONERTER.convert(index_json_parameter) {
+        Ok(json) => json,
+        Err(e) => {
+            ERROR!(function: "ffi_create_index_with_build_mode", "Can't convert 'index_json_parameter', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert 'index_json_parameter', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match create_index_with_build_mode(
+        &index_path,
+        &column_names,
+        &index_json_parameter,
+        deterministic_build,
+    ) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_create_index_with_build_mode", "Error creating index: {}", e);
+            let error_msg_for_cxx: String = format!("Error creating index: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
 pub fn ffi_create_index(index_path: &CxxString, column_names: &CxxVector<CxxString>) -> BoolResult {
     let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
         Ok(path) => path,
@@ -150,6 +246,7 @@ pub fn ffi_index_multi_column_docs(
 
     let column_docs: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(column_docs) {
         Ok(docs) => docs,
+        Err(CxxConvertError::Utf8SkipRow(e)) => return skipped_row_result(row_id, e),
         Err(e) => {
             ERROR!(function: "ffi_index_multi_column_docs", "Can't convert 'column_docs', message: {}", e);
             let error_msg_for_cxx: String = format!("Can't convert 'column_docs', message: {}", e);
@@ -191,31 +288,30 @@ pub fn ffi_index_multi_column_docs(
         Err(e) => {
             ERROR!(function: "ffi_index_multi_column_docs", "Error indexing multi-column docs: {}", e);
             let error_msg_for_cxx: String = format!("Error indexing multi-column docs: {}", e);
+            let error_code = match e {
+                TantivySearchError::Backpressure(_) => BACKPRESSURE_ERROR_CODE,
+                _ => -1,
+            };
             return BoolResult {
                 result: false,
-                error_code: -1,
+                error_code,
                 error_msg: error_msg_for_cxx,
             };
         }
     }
 }
 
-pub fn ffi_index_multi_type_column_docs(
+pub fn ffi_index_multi_column_borrowed_docs(
     index_path: &CxxString,
     row_id: u64,
-    text_column_names: &CxxVector<CxxString>,
-    text_column_docs: &CxxVector<CxxString>,
-    i64_column_names: &CxxVector<CxxString>,
-    i64_column_docs: &CxxVector<i64>,
-    f64_column_names: &CxxVector<CxxString>,
-    f64_column_docs: &CxxVector<f64>,
-    bytes_column_names: &CxxVector<CxxString>,
-    bytes_column_docs: &CxxVector<CxxString>,
+    column_names: &CxxVector<CxxString>,
+    column_data: &[u8],
+    column_lengths: &[u64],
 ) -> BoolResult {
     let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
         Ok(path) => path,
         Err(e) => {
-            ERROR!(function: "ffi_index_multi_column_docs", "Can't convert 'index_path', message: {}", e);
+            ERROR!(function: "ffi_index_multi_column_borrowed_docs", "Can't convert 'index_path', message: {}", e);
             let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
             return BoolResult {
                 result: false,
@@ -225,13 +321,11 @@ pub fn ffi_index_multi_type_column_docs(
         }
     };
 
-    let text_column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(text_column_names)
-    {
+    let column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(column_names) {
         Ok(names) => names,
         Err(e) => {
-            ERROR!(function: "ffi_index_multi_column_docs", "Can't convert 'text_column_names', message: {}", e);
-            let error_msg_for_cxx: String =
-                format!("Can't convert 'text_column_names', message: {}", e);
+            ERROR!(function: "ffi_index_multi_column_borrowed_docs", "Can't convert 'column_names', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'column_names', message: {}", e);
             return BoolResult {
                 result: false,
                 error_code: -1,
@@ -240,12 +334,13 @@ pub fn ffi_index_multi_type_column_docs(
         }
     };
 
-    let text_column_docs: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(text_column_docs) {
-        Ok(docs) => docs,
+    // Borrow each column doc from `column_data`, valid utf-8 is not copied.
+    let column_docs: Vec<Cow<str>> = match split_borrowed_docs(column_data, column_lengths) {
+        Ok(Ok(docs)) => docs,
+        Ok(Err(e)) => return skipped_row_result(row_id, e),
         Err(e) => {
-            ERROR!(function: "ffi_index_multi_column_docs", "Can't convert 'text_column_docs', message: {}", e);
-            let error_msg_for_cxx: String =
-                format!("Can't convert 'text_column_docs', message: {}", e);
+            ERROR!(function: "ffi_index_multi_column_borrowed_docs", "Can't borrow 'column_data', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't borrow 'column_data', message: {}", e);
             return BoolResult {
                 result: false,
                 error_code: -1,
@@ -254,105 +349,71 @@ pub fn ffi_index_multi_type_column_docs(
         }
     };
 
-    if text_column_names.len() != text_column_docs.len() {
-        ERROR!(function: "ffi_index_multi_column_docs", "text_column_names size doesn't match text_column_docs size");
-        let error_msg_for_cxx: String =
-            "text_column_names size doesn't match text_column_docs size".to_string();
-        return BoolResult {
-            result: false,
-            error_code: -1,
-            error_msg: error_msg_for_cxx,
-        };
-    }
-
-    let i64_column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(i64_column_names) {
-        Ok(names) => names,
+    let column_docs: Vec<&str> = column_docs.iter().map(|doc| doc.as_ref()).collect();
+    match index_multi_column_borrowed_docs(&index_path, row_id, &column_names, &column_docs) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
         Err(e) => {
-            ERROR!(function: "ffi_index_multi_column_docs", "Can't convert 'i64_column_names', message: {}", e);
-            let error_msg_for_cxx: String =
-                format!("Can't convert 'i64_column_names', message: {}", e);
-            return BoolResult {
-                result: false,
-                error_code: -1,
-                error_msg: error_msg_for_cxx,
+            ERROR!(function: "ffi_index_multi_column_borrowed_docs", "Error indexing multi-column docs: {}", e);
+            let error_msg_for_cxx: String = format!("Error indexing multi-column docs: {}", e);
+            let error_code = match e {
+                TantivySearchError::Backpressure(_) => BACKPRESSURE_ERROR_CODE,
+                _ => -1,
             };
-        }
-    };
-
-    // convert CxxVector<i64> to Vec<i64>
-    let i64_column_docs = match cxx_vector_converter::<i64>().convert(i64_column_docs) {
-        Ok(docs) => docs,
-        Err(e) => {
-            ERROR!(function: "ffi_index_multi_column_docs", "Can't convert 'i64_column_docs', message: {}", e);
-            let error_msg_for_cxx: String =
-                format!("Can't convert 'i64_column_docs', message: {}", e);
             return BoolResult {
                 result: false,
-                error_code: -1,
+                error_code,
                 error_msg: error_msg_for_cxx,
             };
         }
-    };
-
-    if i64_column_names.len() != i64_column_docs.len() {
-        ERROR!(function: "ffi_index_multi_column_docs", "i64_column_names size doesn't match i64_column_docs size");
-        let error_msg_for_cxx: String =
-            "i64_column_names size doesn't match i64_column_docs size".to_string();
-        return BoolResult {
-            result: false,
-            error_code: -1,
-            error_msg: error_msg_for_cxx,
-        };
     }
+}
 
-    let f64_column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(f64_column_names) {
-        Ok(names) => names,
-        Err(e) => {
-            ERROR!(function: "ffi_index_multi_column_docs", "Can't convert 'f64_column_names', message: {}", e);
-            let error_msg_for_cxx: String =
-                format!("Can't convert 'f64_column_names', message: {}", e);
-            return BoolResult {
-                result: false,
-                error_code: -1,
-                error_msg: error_msg_for_cxx,
-            };
-        }
-    };
-
-    // convert CxxVector<f64> to Vec<f64>
-    let f64_column_docs = match cxx_vector_converter::<f64>().convert(f64_column_docs) {
-        Ok(docs) => docs,
-        Err(e) => {
-            ERROR!(function: "ffi_index_multi_column_docs", "Can't convert 'f64_column_docs', message: {}", e);
-            let error_msg_for_cxx: String =
-                format!("Can't convert 'f64_column_docs', message: {}", e);
-            return BoolResult {
-                result: false,
-                error_code: -1,
-                error_msg: error_msg_for_cxx,
-            };
+// Split `column_data` into consecutive docs by `column_lengths`, sanitized by `UTF8_POLICY`.
+// Inner error means the row should be skipped.
+fn split_borrowed_docs<'a>(
+    column_data: &'a [u8],
+    column_lengths: &[u64],
+) -> Result<Result<Vec<Cow<'a, str>>, Utf8Error>, String> {
+    let total_length: Option<u64> = column_lengths
+        .iter()
+        .try_fold(0u64, |total, length| total.checked_add(*length));
+    if total_length != Some(column_data.len() as u64) {
+        return Err(format!(
+            "sum of column_lengths {:?} doesn't match column_data size {}",
+            total_length,
+            column_data.len()
+        ));
+    }
+    let mut column_docs: Vec<Cow<str>> = Vec::with_capacity(column_lengths.len());
+    let mut offset: usize = 0;
+    for (column_idx, length) in column_lengths.iter().enumerate() {
+        let bytes = &column_data[offset..offset + *length as usize];
+        match UTF8_POLICY.sanitize(bytes) {
+            Ok(Sanitized::Text(text)) => column_docs.push(text),
+            Ok(Sanitized::SkipRow(e)) => return Ok(Err(e)),
+            Err(e) => return Err(format!("invalid utf-8 in column {}: {}", column_idx, e)),
         }
-    };
-
-    if f64_column_names.len() != f64_column_docs.len() {
-        ERROR!(function: "ffi_index_multi_column_docs", "f64_column_names size doesn't match f64_column_docs size");
-        let error_msg_for_cxx: String =
-            "f64_column_names size doesn't match f64_column_docs size".to_string();
-        return BoolResult {
-            result: false,
-            error_code: -1,
-            error_msg: error_msg_for_cxx,
-        };
+        offset += *length as usize;
     }
+    Ok(Ok(column_docs))
+}
 
-    let bytes_column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER
-        .convert(bytes_column_names)
-    {
-        Ok(names) => names,
+pub fn ffi_index_column_batch(
+    index_path: &CxxString,
+    row_ids: &[u64],
+    column_names: &CxxVector<CxxString>,
+    column_data: &[u8],
+    column_offsets: &[u64],
+) -> BoolResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
         Err(e) => {
-            ERROR!(function: "ffi_index_multi_column_docs", "Can't convert 'bytes_column_names', message: {}", e);
-            let error_msg_for_cxx: String =
-                format!("Can't convert 'bytes_column_names', message: {}", e);
+            ERROR!(function: "ffi_index_column_batch", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
             return BoolResult {
                 result: false,
                 error_code: -1,
@@ -361,14 +422,11 @@ pub fn ffi_index_multi_type_column_docs(
         }
     };
 
-    let bytes_column_docs: Vec<Vec<u8>> = match CXX_VECTOR_STRING_TO_BYTES_CONERTER
-        .convert(bytes_column_docs)
-    {
-        Ok(docs) => docs,
+    let column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(column_names) {
+        Ok(names) => names,
         Err(e) => {
-            ERROR!(function: "ffi_index_multi_column_docs", "Can't convert 'bytes_column_docs', message: {}", e);
-            let error_msg_for_cxx: String =
-                format!("Can't convert 'bytes_column_docs', message: {}", e);
+            ERROR!(function: "ffi_index_column_batch", "Can't convert 'column_names', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'column_names', message: {}", e);
             return BoolResult {
                 result: false,
                 error_code: -1,
@@ -377,48 +435,12 @@ pub fn ffi_index_multi_type_column_docs(
         }
     };
 
-    if bytes_column_names.len() != bytes_column_docs.len() {
-        ERROR!(function: "ffi_index_multi_column_docs", "bytes_column_names size doesn't match bytes_column_docs size");
-        let error_msg_for_cxx: String =
-            "bytes_column_names size doesn't match bytes_column_docs size".to_string();
-        return BoolResult {
-            result: false,
-            error_code: -1,
-            error_msg: error_msg_for_cxx,
-        };
-    }
-
-    if (text_column_names.len()
-        + i64_column_names.len()
-        + f64_column_names.len()
-        + bytes_column_names.len())
-        == 0
-        || (text_column_docs.len()
-            + i64_column_docs.len()
-            + f64_column_docs.len()
-            + bytes_column_docs.len())
-            == 0
-    {
-        ERROR!(function: "ffi_index_multi_column_docs", "column_names and column_docs can't be empty");
-        let error_msg_for_cxx: String = "column_names and column_docs can't be empty".to_string();
-        return BoolResult {
-            result: false,
-            error_code: -1,
-            error_msg: error_msg_for_cxx,
-        };
-    }
-
-    match index_multi_type_column_docs(
+    match index_column_batch(
         &index_path,
-        row_id,
-        &text_column_names,
-        &text_column_docs,
-        &i64_column_names,
-        &i64_column_docs,
-        &f64_column_names,
-        &f64_column_docs,
-        &bytes_column_names,
-        &bytes_column_docs,
+        row_ids,
+        &column_names,
+        column_data,
+        column_offsets,
     ) {
         Ok(status) => BoolResult {
             result: status,
@@ -426,34 +448,448 @@ pub fn ffi_index_multi_type_column_docs(
             error_msg: String::new(),
         },
         Err(e) => {
-            ERROR!(function: "ffi_index_multi_column_docs", "Error indexing multi-column docs: {}", e);
-            let error_msg_for_cxx: String = format!("Error indexing multi-column docs: {}", e);
+            ERROR!(function: "ffi_index_column_batch", "Error indexing column batch: {}", e);
+            let error_msg_for_cxx: String = format!("Error indexing column batch: {}", e);
+            let error_code = match e {
+                TantivySearchError::Backpressure(_) => BACKPRESSURE_ERROR_CODE,
+                _ => -1,
+            };
             return BoolResult {
                 result: false,
-                error_code: -1,
+                error_code,
                 error_msg: error_msg_for_cxx,
             };
         }
     }
 }
 
-pub fn ffi_delete_row_ids(index_path: &CxxString, row_ids: &CxxVector<u64>) -> BoolResult {
+// Row skipped by utf-8 policy doesn't consume a row_id, `error_msg` tells it's skipped.
+fn skipped_auto_row_result(error: Utf8Error) -> U64Result {
+    WARNING!(function: "skipped_auto_row_result", "Skip row for invalid utf-8: {}", error);
+    U64Result {
+        result: 0,
+        error_code: 0,
+        error_msg: format!("Row skipped for invalid utf-8: {}", error),
+    }
+}
+
+pub fn ffi_enable_auto_row_id(index_path: &CxxString) -> U64Result {
     let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
-        Ok(path) => path,
+        Ok(value) => value,
         Err(e) => {
-            ERROR!(function: "ffi_delete_row_ids", "Can't convert 'index_path', message: {}", e);
+            ERROR!(function: "ffi_enable_auto_row_id", "Can't convert 'index_path', message: {}", e);
             let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
-            return BoolResult {
-                result: false,
+            return U64Result {
+                result: 0,
                 error_code: -1,
                 error_msg: error_msg_for_cxx,
             };
         }
     };
 
-    let row_ids: Vec<u64> = match cxx_vector_converter::<u64>().convert(row_ids) {
-        Ok(ids) => ids,
-        Err(e) => {
+    match enable_auto_row_id(&index_path) {
+        Ok(row_id) => U64Result {
+            result: row_id,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_enable_auto_row_id", "Error enabling auto row_id: {}", e);
+            let error_msg_for_cxx: String = format!("Error enabling auto row_id: {}", e);
+            let error_code = match e {
+                TantivySearchError::Backpressure(_) => BACKPRESSURE_ERROR_CODE,
+                _ => -1,
+            };
+            return U64Result {
+                result: 0,
+                error_code,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_index_multi_column_docs_auto_row_id(
+    index_path: &CxxString,
+    column_names: &CxxVector<CxxString>,
+    column_docs: &CxxVector<CxxString>,
+) -> U64Result {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(value) => value,
+        Err(e) => {
+            ERROR!(function: "ffi_index_multi_column_docs_auto_row_id", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return U64Result {
+                result: 0,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(column_names) {
+        Ok(value) => value,
+        Err(e) => {
+            ERROR!(function: "ffi_index_multi_column_docs_auto_row_id", "Can't convert 'column_names', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'column_names', message: {}", e);
+            return U64Result {
+                result: 0,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let column_docs: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(column_docs) {
+        Ok(value) => value,
+        Err(CxxConvertError::Utf8SkipRow(e)) => return skipped_auto_row_result(e),
+        Err(e) => {
+            ERROR!(function: "ffi_index_multi_column_docs_auto_row_id", "Can't convert 'column_docs', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'column_docs', message: {}", e);
+            return U64Result {
+                result: 0,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match index_multi_column_docs_auto_row_id(&index_path, &column_names, &column_docs) {
+        Ok(row_id) => U64Result {
+            result: row_id,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_index_multi_column_docs_auto_row_id", "Error indexing docs with auto row_id: {}", e);
+            let error_msg_for_cxx: String = format!("Error indexing docs with auto row_id: {}", e);
+            let error_code = match e {
+                TantivySearchError::Backpressure(_) => BACKPRESSURE_ERROR_CODE,
+                _ => -1,
+            };
+            return U64Result {
+                result: 0,
+                error_code,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_index_column_batch_auto_row_id(
+    index_path: &CxxString,
+    column_names: &CxxVector<CxxString>,
+    column_data: &[u8],
+    column_offsets: &[u64],
+) -> U64Result {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(value) => value,
+        Err(e) => {
+            ERROR!(function: "ffi_index_column_batch_auto_row_id", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return U64Result {
+                result: 0,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(column_names) {
+        Ok(value) => value,
+        Err(e) => {
+            ERROR!(function: "ffi_index_column_batch_auto_row_id", "Can't convert 'column_names', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'column_names', message: {}", e);
+            return U64Result {
+                result: 0,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match index_column_batch_auto_row_id(&index_path, &column_names, column_data, column_offsets) {
+        Ok(row_id) => U64Result {
+            result: row_id,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_index_column_batch_auto_row_id", "Error indexing column batch with auto row_id: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Error indexing column batch with auto row_id: {}", e);
+            let error_code = match e {
+                TantivySearchError::Backpressure(_) => BACKPRESSURE_ERROR_CODE,
+                _ => -1,
+            };
+            return U64Result {
+                result: 0,
+                error_code,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_index_multi_type_column_docs(
+    index_path: &CxxString,
+    row_id: u64,
+    text_column_names: &CxxVector<CxxString>,
+    text_column_docs: &CxxVector<CxxString>,
+    i64_column_names: &CxxVector<CxxString>,
+    i64_column_docs: &CxxVector<i64>,
+    f64_column_names: &CxxVector<CxxString>,
+    f64_column_docs: &CxxVector<f64>,
+    bytes_column_names: &CxxVector<CxxString>,
+    bytes_column_docs: &CxxVector<CxxString>,
+) -> BoolResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_index_multi_column_docs", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let text_column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(text_column_names)
+    {
+        Ok(names) => names,
+        Err(e) => {
+            ERROR!(function: "ffi_index_multi_column_docs", "Can't convert 'text_column_names', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert 'text_column_names', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let text_column_docs: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(text_column_docs) {
+        Ok(docs) => docs,
+        Err(CxxConvertError::Utf8SkipRow(e)) => return skipped_row_result(row_id, e),
+        Err(e) => {
+            ERROR!(function: "ffi_index_multi_column_docs", "Can't convert 'text_column_docs', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert 'text_column_docs', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    if text_column_names.len() != text_column_docs.len() {
+        ERROR!(function: "ffi_index_multi_column_docs", "text_column_names size doesn't match text_column_docs size");
+        let error_msg_for_cxx: String =
+            "text_column_names size doesn't match text_column_docs size".to_string();
+        return BoolResult {
+            result: false,
+            error_code: -1,
+            error_msg: error_msg_for_cxx,
+        };
+    }
+
+    let i64_column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(i64_column_names) {
+        Ok(names) => names,
+        Err(e) => {
+            ERROR!(function: "ffi_index_multi_column_docs", "Can't convert 'i64_column_names', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert 'i64_column_names', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    // convert CxxVector<i64> to Vec<i64>
+    let i64_column_docs = match cxx_vector_converter::<i64>().convert(i64_column_docs) {
+        Ok(docs) => docs,
+        Err(e) => {
+            ERROR!(function: "ffi_index_multi_column_docs", "Can't convert 'i64_column_docs', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert 'i64_column_docs', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    if i64_column_names.len() != i64_column_docs.len() {
+        ERROR!(function: "ffi_index_multi_column_docs", "i64_column_names size doesn't match i64_column_docs size");
+        let error_msg_for_cxx: String =
+            "i64_column_names size doesn't match i64_column_docs size".to_string();
+        return BoolResult {
+            result: false,
+            error_code: -1,
+            error_msg: error_msg_for_cxx,
+        };
+    }
+
+    let f64_column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(f64_column_names) {
+        Ok(names) => names,
+        Err(e) => {
+            ERROR!(function: "ffi_index_multi_column_docs", "Can't convert 'f64_column_names', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert 'f64_column_names', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    // convert CxxVector<f64> to Vec<f64>
+    let f64_column_docs = match cxx_vector_converter::<f64>().convert(f64_column_docs) {
+        Ok(docs) => docs,
+        Err(e) => {
+            ERROR!(function: "ffi_index_multi_column_docs", "Can't convert 'f64_column_docs', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert 'f64_column_docs', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    if f64_column_names.len() != f64_column_docs.len() {
+        ERROR!(function: "ffi_index_multi_column_docs", "f64_column_names size doesn't match f64_column_docs size");
+        let error_msg_for_cxx: String =
+            "f64_column_names size doesn't match f64_column_docs size".to_string();
+        return BoolResult {
+            result: false,
+            error_code: -1,
+            error_msg: error_msg_for_cxx,
+        };
+    }
+
+    let bytes_column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER
+        .convert(bytes_column_names)
+    {
+        Ok(names) => names,
+        Err(e) => {
+            ERROR!(function: "ffi_index_multi_column_docs", "Can't convert 'bytes_column_names', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert 'bytes_column_names', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let bytes_column_docs: Vec<Vec<u8>> = match CXX_VECTOR_STRING_TO_BYTES_CONERTER
+        .convert(bytes_column_docs)
+    {
+        Ok(docs) => docs,
+        Err(e) => {
+            ERROR!(function: "ffi_index_multi_column_docs", "Can't convert 'bytes_column_docs', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert 'bytes_column_docs', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    if bytes_column_names.len() != bytes_column_docs.len() {
+        ERROR!(function: "ffi_index_multi_column_docs", "bytes_column_names size doesn't match bytes_column_docs size");
+        let error_msg_for_cxx: String =
+            "bytes_column_names size doesn't match bytes_column_docs size".to_string();
+        return BoolResult {
+            result: false,
+            error_code: -1,
+            error_msg: error_msg_for_cxx,
+        };
+    }
+
+    if (text_column_names.len()
+        + i64_column_names.len()
+        + f64_column_names.len()
+        + bytes_column_names.len())
+        == 0
+        || (text_column_docs.len()
+            + i64_column_docs.len()
+            + f64_column_docs.len()
+            + bytes_column_docs.len())
+            == 0
+    {
+        ERROR!(function: "ffi_index_multi_column_docs", "column_names and column_docs can't be empty");
+        let error_msg_for_cxx: String = "column_names and column_docs can't be empty".to_string();
+        return BoolResult {
+            result: false,
+            error_code: -1,
+            error_msg: error_msg_for_cxx,
+        };
+    }
+
+    match index_multi_type_column_docs(
+        &index_path,
+        row_id,
+        &text_column_names,
+        &text_column_docs,
+        &i64_column_names,
+        &i64_column_docs,
+        &f64_column_names,
+        &f64_column_docs,
+        &bytes_column_names,
+        &bytes_column_docs,
+    ) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_index_multi_column_docs", "Error indexing multi-column docs: {}", e);
+            let error_msg_for_cxx: String = format!("Error indexing multi-column docs: {}", e);
+            let error_code = match e {
+                TantivySearchError::Backpressure(_) => BACKPRESSURE_ERROR_CODE,
+                _ => -1,
+            };
+            return BoolResult {
+                result: false,
+                error_code,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_delete_row_ids(index_path: &CxxString, row_ids: &CxxVector<u64>) -> BoolResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_delete_row_ids", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let row_ids: Vec<u64> = match cxx_vector_converter::<u64>().convert(row_ids) {
+        Ok(ids) => ids,
+        Err(e) => {
             ERROR!(function: "ffi_delete_row_ids", "Can't convert 'row_ids', message: {}", e);
             let error_msg_for_cxx: String = format!("Can't convert 'row_ids', message: {}", e);
             return BoolResult {
@@ -464,29 +900,1184 @@ pub fn ffi_delete_row_ids(index_path: &CxxString, row_ids: &CxxVector<u64>) -> B
         }
     };
 
-    match delete_row_ids(&index_path, &row_ids) {
+    match delete_row_ids(&index_path, &row_ids) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_delete_row_ids", "Error deleting row ids: {}", e);
+            let error_msg_for_cxx: String = format!("Error deleting row ids: {}", e);
+            let error_code = match e {
+                TantivySearchError::OutOfDiskSpace(_) => OUT_OF_DISK_SPACE_ERROR_CODE,
+                _ => -1,
+            };
+            return BoolResult {
+                result: false,
+                error_code,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_index_writer_commit(index_path: &CxxString) -> BoolResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_index_writer_commit", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match commit_index(&index_path) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_index_writer_commit", "Error committing index: {}", e);
+            let error_msg_for_cxx: String = format!("Error committing index: {}", e);
+            let error_code = match e {
+                TantivySearchError::OutOfDiskSpace(_) => OUT_OF_DISK_SPACE_ERROR_CODE,
+                _ => -1,
+            };
+            return BoolResult {
+                result: false,
+                error_code,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_commit_async(index_path: &CxxString) -> U64Result {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_commit_async", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return U64Result {
+                result: 0,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match commit_async(&index_path) {
+        Ok(ticket) => U64Result {
+            result: ticket,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_commit_async", "Error scheduling commit: {}", e);
+            let error_msg_for_cxx: String = format!("Error scheduling commit: {}", e);
+            U64Result {
+                result: 0,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_wait_commit(ticket: u64, timeout_millis: u64) -> BoolResult {
+    match wait_commit(ticket, timeout_millis) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_wait_commit", "Error waiting commit: {}", e);
+            let error_msg_for_cxx: String = format!("Error waiting commit: {}", e);
+            BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_commit_nowait(index_path: &CxxString) -> BoolResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_commit_nowait", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match commit_nowait(&index_path) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_commit_nowait", "Error scheduling commit: {}", e);
+            let error_msg_for_cxx: String = format!("Error scheduling commit: {}", e);
+            BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_free_index_writer(index_path: &CxxString) -> BoolResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_free_index_writer", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match free_index_writer(&index_path) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_free_index_writer", "Error freeing index writer: {}", e);
+            let error_msg_for_cxx: String = format!("Error freeing index writer: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_load_index_writer(index_path: &CxxString) -> BoolResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_load_index_writer", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match load_index_writer(&index_path) {
+        Ok(status) => {
+            return BoolResult {
+                result: status,
+                error_code: 0,
+                error_msg: String::new(),
+            };
+        }
+        Err(e) => {
+            ERROR!(function: "ffi_load_index_writer", "Error loading index reader: {}", e);
+            let error_msg_for_cxx: String = format!("Error loading index reader: {}", e);
+            let error_code = match e {
+                TantivySearchError::IncompatibleIndexVersion(_) => {
+                    INCOMPATIBLE_INDEX_VERSION_ERROR_CODE
+                }
+                _ => -1,
+            };
+            return BoolResult {
+                result: false,
+                error_code,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_load_index_writer_with_lock_recovery(
+    index_path: &CxxString,
+    break_stale_lock: bool,
+) -> BoolResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_load_index_writer_with_lock_recovery", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match load_index_writer_with_lock_recovery(&index_path, break_stale_lock) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_load_index_writer_with_lock_recovery", "Error loading index writer: {}", e);
+            let error_msg_for_cxx: String = format!("Error loading index writer: {}", e);
+            let error_code = match e {
+                TantivySearchError::IncompatibleIndexVersion(_) => {
+                    INCOMPATIBLE_INDEX_VERSION_ERROR_CODE
+                }
+                _ => -1,
+            };
+            return BoolResult {
+                result: false,
+                error_code,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_enable_auto_commit(
+    index_path: &CxxString,
+    interval_secs: u64,
+    max_docs: u64,
+) -> BoolResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_enable_auto_commit", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match enable_auto_commit(&index_path, interval_secs, max_docs) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_enable_auto_commit", "Error enabling auto commit: {}", e);
+            let error_msg_for_cxx: String = format!("Error enabling auto commit: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_disable_auto_commit(index_path: &CxxString) -> BoolResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_disable_auto_commit", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match disable_auto_commit(&index_path) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_disable_auto_commit", "Error disabling auto commit: {}", e);
+            let error_msg_for_cxx: String = format!("Error disabling auto commit: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_register_index_namespace(
+    index_path: &CxxString,
+    namespace_name: &CxxString,
+) -> BoolResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_register_index_namespace", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let namespace: String = match CXX_STRING_CONERTER.convert(namespace_name) {
+        Ok(namespace) => namespace,
+        Err(e) => {
+            ERROR!(function: "ffi_register_index_namespace", "Can't convert 'namespace_name', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert 'namespace_name', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match register_index_namespace(&index_path, &namespace) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_register_index_namespace", "Error registering index namespace: {}", e);
+            let error_msg_for_cxx: String = format!("Error registering index namespace: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_list_namespaces() -> Vec<String> {
+    INDEX_NAMESPACES.all_namespaces()
+}
+
+pub fn ffi_list_namespace_indexes(namespace_name: &CxxString) -> Vec<String> {
+    let namespace: String = match CXX_STRING_CONERTER.convert(namespace_name) {
+        Ok(namespace) => namespace,
+        Err(e) => {
+            ERROR!(function: "ffi_list_namespace_indexes", "Can't convert 'namespace_name', message: {}", e);
+            return Vec::new();
+        }
+    };
+    INDEX_NAMESPACES.keys_of(&namespace)
+}
+
+pub fn ffi_free_namespace(namespace_name: &CxxString) -> U64Result {
+    let namespace: String = match CXX_STRING_CONERTER.convert(namespace_name) {
+        Ok(namespace) => namespace,
+        Err(e) => {
+            ERROR!(function: "ffi_free_namespace", "Can't convert 'namespace_name', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert 'namespace_name', message: {}", e);
+            return U64Result {
+                result: 0,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match free_namespace(&namespace) {
+        Ok(freed) => U64Result {
+            result: freed,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_free_namespace", "Error freeing namespace: {}", e);
+            let error_msg_for_cxx: String = format!("Error freeing namespace: {}", e);
+            return U64Result {
+                result: 0,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_enable_idle_index_unload(
+    idle_timeout_secs: u64,
+    check_interval_secs: u64,
+) -> BoolResult {
+    match enable_idle_index_unload(idle_timeout_secs, check_interval_secs) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_enable_idle_index_unload", "Error enabling idle index unload: {}", e);
+            let error_msg_for_cxx: String = format!("Error enabling idle index unload: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_disable_idle_index_unload() -> BoolResult {
+    match disable_idle_index_unload() {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_disable_idle_index_unload", "Error disabling idle index unload: {}", e);
+            let error_msg_for_cxx: String = format!("Error disabling idle index unload: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_shutdown(policy: &CxxString, timeout_millis: u64) -> U64Result {
+    let policy: String = match CXX_STRING_CONERTER.convert(policy) {
+        Ok(policy) => policy,
+        Err(e) => {
+            ERROR!(function: "ffi_shutdown", "Can't convert 'policy', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'policy', message: {}", e);
+            return U64Result {
+                result: 0,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match shutdown(&policy, timeout_millis) {
+        Ok(freed) => U64Result {
+            result: freed,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_shutdown", "Error shutting down: {}", e);
+            let error_msg_for_cxx: String = format!("Error shutting down: {}", e);
+            U64Result {
+                result: 0,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_update_index_settings(index_path: &CxxString, settings_json: &CxxString) -> BoolResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_update_index_settings", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let settings_json: String = match CXX_STRING_CONERTER.convert(settings_json) {
+        Ok(json) => json,
+        Err(e) => {
+            ERROR!(function: "ffi_update_index_settings", "Can't convert 'settings_json', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert 'settings_json', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match update_index_settings(&index_path, &settings_json) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_update_index_settings", "Error updating index settings: {}", e);
+            let error_msg_for_cxx: String = format!("Error updating index settings: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_set_writer_backpressure(
+    index_path: &CxxString,
+    max_uncommitted_bytes: u64,
+    max_segments: u64,
+    block_timeout_millis: u64,
+) -> BoolResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_set_writer_backpressure", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match set_writer_backpressure(
+        &index_path,
+        max_uncommitted_bytes,
+        max_segments,
+        block_timeout_millis,
+    ) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_set_writer_backpressure", "Error setting writer backpressure: {}", e);
+            let error_msg_for_cxx: String = format!("Error setting writer backpressure: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_set_row_id_check(index_path: &CxxString, mode: &CxxString, reject: bool) -> BoolResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_set_row_id_check", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let mode: String = match CXX_STRING_CONERTER.convert(mode) {
+        Ok(mode) => mode,
+        Err(e) => {
+            ERROR!(function: "ffi_set_row_id_check", "Can't convert 'mode', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'mode', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match set_row_id_check(&index_path, &mode, reject) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_set_row_id_check", "Error setting row_id check: {}", e);
+            let error_msg_for_cxx: String = format!("Error setting row_id check: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_get_index_health(index_path: &CxxString) -> IndexHealthResult {
+    let empty_health = IndexHealth {
+        canonical_path: String::new(),
+        writer_alive: false,
+        writer_busy: false,
+        reader_loaded: false,
+        reader_generation: 0,
+        last_commit_opstamp: 0,
+        lock_contention_count: 0,
+        last_error: String::new(),
+        row_id_mapping_bytes: 0,
+    };
+
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_get_index_health", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return IndexHealthResult {
+                result: empty_health,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match get_index_health(&index_path) {
+        Ok(health) => IndexHealthResult {
+            result: health,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_get_index_health", "Error getting index health: {}", e);
+            let error_msg_for_cxx: String = format!("Error getting index health: {}", e);
+            return IndexHealthResult {
+                result: empty_health,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_get_liveness(index_path: &CxxString) -> LivenessResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_get_liveness", "Can't convert 'index_path', message: {}", e);
+            return LivenessResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: format!("Can't convert 'index_path', message: {}", e),
+            };
+        }
+    };
+
+    match get_liveness(&index_path) {
+        Ok(liveness) => LivenessResult {
+            result: liveness,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_get_liveness", "Error getting liveness: {}", e);
+            LivenessResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: format!("Error getting liveness: {}", e),
+            }
+        }
+    }
+}
+
+pub fn ffi_probe_liveness(index_path: &CxxString, max_heartbeat_age_millis: u64) -> BoolResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_probe_liveness", "Can't convert 'index_path', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: format!("Can't convert 'index_path', message: {}", e),
+            };
+        }
+    };
+
+    match probe_liveness(&index_path, max_heartbeat_age_millis) {
+        Ok(live) => BoolResult {
+            result: live,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_probe_liveness", "Error probing liveness: {}", e);
+            BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: format!("Error probing liveness: {}", e),
+            }
+        }
+    }
+}
+
+pub fn ffi_probe_readiness(index_path: &CxxString) -> BoolResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_probe_readiness", "Can't convert 'index_path', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: format!("Can't convert 'index_path', message: {}", e),
+            };
+        }
+    };
+
+    match probe_readiness(&index_path) {
+        Ok(ready) => BoolResult {
+            result: ready,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_probe_readiness", "Error probing readiness: {}", e);
+            BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: format!("Error probing readiness: {}", e),
+            }
+        }
+    }
+}
+
+pub fn ffi_list_indexes() -> StringResult {
+    match list_loaded_indexes() {
+        Ok(indexes_json) => StringResult {
+            result: indexes_json,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_list_indexes", "Error listing loaded indexes: {}", e);
+            let error_msg_for_cxx: String = format!("Error listing loaded indexes: {}", e);
+            StringResult {
+                result: String::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_set_max_open_files(max_open_files: u64) -> BoolResult {
+    match set_max_open_files(max_open_files) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_set_max_open_files", "Error setting max open files: {}", e);
+            let error_msg_for_cxx: String = format!("Error setting max open files: {}", e);
+            BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_get_open_files() -> U64Result {
+    match get_open_files() {
+        Ok(open_files) => U64Result {
+            result: open_files,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_get_open_files", "Error getting open files: {}", e);
+            let error_msg_for_cxx: String = format!("Error getting open files: {}", e);
+            U64Result {
+                result: 0,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_set_merge_throttle(max_mb_per_sec: u64) -> BoolResult {
+    match set_merge_throttle(max_mb_per_sec) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_set_merge_throttle", "Error setting merge throttle: {}", e);
+            let error_msg_for_cxx: String = format!("Error setting merge throttle: {}", e);
+            BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_set_disk_space_reserve(min_free_mb: u64) -> BoolResult {
+    match set_disk_space_reserve(min_free_mb) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_set_disk_space_reserve", "Error setting disk space reserve: {}", e);
+            let error_msg_for_cxx: String = format!("Error setting disk space reserve: {}", e);
+            BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_set_bitmap_union_threads(num_threads: u64) -> BoolResult {
+    match set_bitmap_union_threads(num_threads) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_set_bitmap_union_threads", "Error setting bitmap union threads: {}", e);
+            let error_msg_for_cxx: String = format!("Error setting bitmap union threads: {}", e);
+            BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_set_thread_cpu_set(cpus: &CxxVector<u32>) -> BoolResult {
+    let cpus: Vec<u32> = match cxx_vector_converter::<u32>().convert(cpus) {
+        Ok(cpus) => cpus,
+        Err(e) => {
+            ERROR!(function: "ffi_set_thread_cpu_set", "Can't convert 'cpus', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'cpus', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+    match set_thread_cpu_set(&cpus) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_set_thread_cpu_set", "Error setting thread CPU set: {}", e);
+            let error_msg_for_cxx: String = format!("Error setting thread CPU set: {}", e);
+            BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_get_allocator_stats() -> AllocatorStatsResult {
+    match get_allocator_stats() {
+        Ok(stats) => AllocatorStatsResult {
+            result: stats,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_get_allocator_stats", "Error getting allocator stats: {}", e);
+            let error_msg_for_cxx: String = format!("Error getting allocator stats: {}", e);
+            AllocatorStatsResult {
+                result: AllocatorStats {
+                    allocator: String::new(),
+                    allocated_bytes: 0,
+                    active_bytes: 0,
+                    resident_bytes: 0,
+                    retained_bytes: 0,
+                },
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_purge_allocator() -> BoolResult {
+    match purge_allocator() {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_purge_allocator", "Error purging allocator: {}", e);
+            let error_msg_for_cxx: String = format!("Error purging allocator: {}", e);
+            BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_get_segment_delete_stats(index_path: &CxxString) -> SegmentDeleteStatsResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_get_segment_delete_stats", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return SegmentDeleteStatsResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match get_segment_delete_stats(&index_path) {
+        Ok(stats) => SegmentDeleteStatsResult {
+            result: stats,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_get_segment_delete_stats", "Error getting segment delete stats: {}", e);
+            let error_msg_for_cxx: String = format!("Error getting segment delete stats: {}", e);
+            return SegmentDeleteStatsResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_vacuum_index(index_path: &CxxString, min_deleted_ratio: f32) -> BoolResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_vacuum_index", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match vacuum_index(&index_path, min_deleted_ratio) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_vacuum_index", "Error vacuuming index: {}", e);
+            let error_msg_for_cxx: String = format!("Error vacuuming index: {}", e);
+            let error_code = match e {
+                TantivySearchError::OutOfDiskSpace(_) => OUT_OF_DISK_SPACE_ERROR_CODE,
+                _ => -1,
+            };
+            return BoolResult {
+                result: false,
+                error_code,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_estimate_index_size(
+    column_names: &CxxVector<CxxString>,
+    index_json_parameter: &CxxString,
+    sample_docs: &CxxVector<CxxString>,
+    total_docs: u64,
+) -> IndexSizeEstimateResult {
+    let empty_estimate = IndexSizeEstimate {
+        sample_docs: 0,
+        sample_index_bytes: 0,
+        estimated_index_bytes: 0,
+        estimated_num_tokens: 0,
+        estimated_reader_memory_bytes: 0,
+        estimated_writer_memory_bytes: 0,
+    };
+
+    let column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(column_names) {
+        Ok(names) => names,
+        Err(e) => {
+            ERROR!(function: "ffi_estimate_index_size", "Can't convert vector 'column_names', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'column_names', message: {}", e);
+            return IndexSizeEstimateResult {
+                result: empty_estimate,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let index_json_parameter: String = match CXX_STRING_CONERTER.convert(index_json_parameter) {
+        Ok(json) => json,
+        Err(e) => {
+            ERROR!(function: "ffi_estimate_index_size", "Can't convert 'index_json_parameter', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert 'index_json_parameter', message: {}", e);
+            return IndexSizeEstimateResult {
+                result: empty_estimate,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let sample_docs: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(sample_docs) {
+        Ok(docs) => docs,
+        Err(e) => {
+            ERROR!(function: "ffi_estimate_index_size", "Can't convert vector 'sample_docs', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'sample_docs', message: {}", e);
+            return IndexSizeEstimateResult {
+                result: empty_estimate,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match estimate_index_size(
+        &column_names,
+        &index_json_parameter,
+        &sample_docs,
+        total_docs,
+    ) {
+        Ok(estimate) => IndexSizeEstimateResult {
+            result: estimate,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_estimate_index_size", "Error estimating index size: {}", e);
+            let error_msg_for_cxx: String = format!("Error estimating index size: {}", e);
+            IndexSizeEstimateResult {
+                result: empty_estimate,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_start_build_progress(
+    index_path: &CxxString,
+    total_docs: u64,
+    report_interval_millis: u64,
+) -> BoolResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_start_build_progress", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match start_build_progress(&index_path, total_docs, report_interval_millis) {
         Ok(status) => BoolResult {
             result: status,
             error_code: 0,
             error_msg: String::new(),
         },
         Err(e) => {
-            ERROR!(function: "ffi_delete_row_ids", "Error deleting row ids: {}", e);
-            let error_msg_for_cxx: String = format!("Error deleting row ids: {}", e);
-            return BoolResult {
+            ERROR!(function: "ffi_start_build_progress", "Error starting build progress: {}", e);
+            let error_msg_for_cxx: String = format!("Error starting build progress: {}", e);
+            BoolResult {
                 result: false,
                 error_code: -1,
                 error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_get_build_progress(index_path: &CxxString) -> BuildProgressResult {
+    let empty_progress = BuildProgress {
+        total_docs: 0,
+        docs_indexed: 0,
+        bytes_indexed: 0,
+        elapsed_millis: 0,
+        docs_per_sec: 0.0,
+        eta_millis: -1,
+    };
+
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_get_build_progress", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BuildProgressResult {
+                result: empty_progress,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
             };
         }
+    };
+
+    match get_build_progress(&index_path) {
+        Ok(progress) => BuildProgressResult {
+            result: progress,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_get_build_progress", "Error getting build progress: {}", e);
+            let error_msg_for_cxx: String = format!("Error getting build progress: {}", e);
+            BuildProgressResult {
+                result: empty_progress,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
     }
 }
 
-pub fn ffi_index_writer_commit(index_path: &CxxString) -> BoolResult {
+pub fn ffi_stop_build_progress(index_path: &CxxString) -> BoolResult {
     let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
         Ok(path) => path,
         Err(e) => {
-            ERROR!(function: "ffi_index_writer_commit", "Can't convert 'index_path', message: {}", e);
+            ERROR!(function: "ffi_stop_build_progress", "Can't convert 'index_path', message: {}", e);
             let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
             return BoolResult {
                 result: false,
@@ -496,29 +2087,185 @@ pub fn ffi_index_writer_commit(index_path: &CxxString) -> BoolResult {
         }
     };
 
-    match commit_index(&index_path) {
+    match stop_build_progress(&index_path) {
         Ok(status) => BoolResult {
             result: status,
             error_code: 0,
             error_msg: String::new(),
         },
         Err(e) => {
-            ERROR!(function: "ffi_index_writer_commit", "Error committing index: {}", e);
-            let error_msg_for_cxx: String = format!("Error committing index: {}", e);
-            return BoolResult {
+            ERROR!(function: "ffi_stop_build_progress", "Error stopping build progress: {}", e);
+            let error_msg_for_cxx: String = format!("Error stopping build progress: {}", e);
+            BoolResult {
                 result: false,
                 error_code: -1,
                 error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_get_build_checkpoint(index_path: &CxxString) -> U64Result {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_get_build_checkpoint", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return U64Result {
+                result: 0,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match get_build_checkpoint(&index_path) {
+        Ok(row_id) => U64Result {
+            result: row_id,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_get_build_checkpoint", "Error getting build checkpoint: {}", e);
+            let error_msg_for_cxx: String = format!("Error getting build checkpoint: {}", e);
+            U64Result {
+                result: 0,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_create_multi_part_build(
+    index_paths: &CxxVector<CxxString>,
+    column_names: &CxxVector<CxxString>,
+    index_json_parameter: &CxxString,
+    memory_budget_bytes: u64,
+    num_threads: u64,
+) -> U64Result {
+    let index_paths: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(index_paths) {
+        Ok(value) => value,
+        Err(e) => {
+            ERROR!(function: "ffi_create_multi_part_build", "Can't convert 'index_paths', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_paths', message: {}", e);
+            return U64Result {
+                result: 0,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(column_names) {
+        Ok(value) => value,
+        Err(e) => {
+            ERROR!(function: "ffi_create_multi_part_build", "Can't convert 'column_names', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'column_names', message: {}", e);
+            return U64Result {
+                result: 0,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let index_json_parameter: String = match CXX_STRING_CONERTER.convert(index_json_parameter) {
+        Ok(value) => value,
+        Err(e) => {
+            ERROR!(function: "ffi_create_multi_part_build", "Can't convert 'index_json_parameter', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert 'index_json_parameter', message: {}", e);
+            return U64Result {
+                result: 0,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
             };
         }
+    };
+
+    match create_multi_part_build(
+        &index_paths,
+        &column_names,
+        &index_json_parameter,
+        memory_budget_bytes,
+        num_threads,
+    ) {
+        Ok(build_id) => U64Result {
+            result: build_id,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_create_multi_part_build", "Error creating multi-part build: {}", e);
+            let error_msg_for_cxx: String = format!("Error creating multi-part build: {}", e);
+            U64Result {
+                result: 0,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
     }
 }
 
-pub fn ffi_free_index_writer(index_path: &CxxString) -> BoolResult {
+pub fn ffi_multi_part_build_index_batch(
+    build_id: u64,
+    part_ids: &[u64],
+    row_ids: &[u64],
+    column_data: &[u8],
+    column_offsets: &[u64],
+) -> BoolResult {
+    match multi_part_build_index_batch(build_id, part_ids, row_ids, column_data, column_offsets) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_multi_part_build_index_batch", "Error indexing multi-part batch: {}", e);
+            let error_msg_for_cxx: String = format!("Error indexing multi-part batch: {}", e);
+            let error_code = match e {
+                TantivySearchError::Backpressure(_) => BACKPRESSURE_ERROR_CODE,
+                _ => -1,
+            };
+            BoolResult {
+                result: false,
+                error_code,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_finish_multi_part_build(build_id: u64) -> BoolResult {
+    match finish_multi_part_build(build_id) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_finish_multi_part_build", "Error finishing multi-part build: {}", e);
+            let error_msg_for_cxx: String = format!("Error finishing multi-part build: {}", e);
+            BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_start_sorted_build(
+    index_path: &CxxString,
+    column_names: &CxxVector<CxxString>,
+    memory_limit_bytes: u64,
+    spill_directory: &CxxString,
+) -> BoolResult {
     let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
         Ok(path) => path,
         Err(e) => {
-            ERROR!(function: "ffi_free_index_writer", "Can't convert 'index_path', message: {}", e);
+            ERROR!(function: "ffi_start_sorted_build", "Can't convert 'index_path', message: {}", e);
             let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
             return BoolResult {
                 result: false,
@@ -528,29 +2275,66 @@ pub fn ffi_free_index_writer(index_path: &CxxString) -> BoolResult {
         }
     };
 
-    match free_index_writer(&index_path) {
+    let column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(column_names) {
+        Ok(names) => names,
+        Err(e) => {
+            ERROR!(function: "ffi_start_sorted_build", "Can't convert 'column_names', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'column_names', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let spill_directory: String = match CXX_STRING_CONERTER.convert(spill_directory) {
+        Ok(directory) => directory,
+        Err(e) => {
+            ERROR!(function: "ffi_start_sorted_build", "Can't convert 'spill_directory', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert 'spill_directory', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match start_sorted_build(
+        &index_path,
+        &column_names,
+        memory_limit_bytes,
+        &spill_directory,
+    ) {
         Ok(status) => BoolResult {
             result: status,
             error_code: 0,
             error_msg: String::new(),
         },
         Err(e) => {
-            ERROR!(function: "ffi_free_index_writer", "Error freeing index writer: {}", e);
-            let error_msg_for_cxx: String = format!("Error freeing index writer: {}", e);
-            return BoolResult {
+            ERROR!(function: "ffi_start_sorted_build", "Error starting sorted build: {}", e);
+            let error_msg_for_cxx: String = format!("Error starting sorted build: {}", e);
+            BoolResult {
                 result: false,
                 error_code: -1,
                 error_msg: error_msg_for_cxx,
-            };
+            }
         }
     }
 }
 
-pub fn ffi_load_index_writer(index_path: &CxxString) -> BoolResult {
+pub fn ffi_sorted_build_add_batch(
+    index_path: &CxxString,
+    row_ids: &[u64],
+    column_data: &[u8],
+    column_offsets: &[u64],
+) -> BoolResult {
     let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
         Ok(path) => path,
         Err(e) => {
-            ERROR!(function: "ffi_load_index_writer", "Can't convert 'index_path', message: {}", e);
+            ERROR!(function: "ffi_sorted_build_add_batch", "Can't convert 'index_path', message: {}", e);
             let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
             return BoolResult {
                 result: false,
@@ -560,22 +2344,146 @@ pub fn ffi_load_index_writer(index_path: &CxxString) -> BoolResult {
         }
     };
 
-    match load_index_writer(&index_path) {
-        Ok(status) => {
-            return BoolResult {
-                result: status,
-                error_code: 0,
-                error_msg: String::new(),
+    match sorted_build_add_batch(&index_path, row_ids, column_data, column_offsets) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_sorted_build_add_batch", "Error adding sorted build batch: {}", e);
+            let error_msg_for_cxx: String = format!("Error adding sorted build batch: {}", e);
+            BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_finish_sorted_build(index_path: &CxxString) -> U64Result {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_finish_sorted_build", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return U64Result {
+                result: 0,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
             };
         }
+    };
+
+    match finish_sorted_build(&index_path) {
+        Ok(rows_indexed) => U64Result {
+            result: rows_indexed,
+            error_code: 0,
+            error_msg: String::new(),
+        },
         Err(e) => {
-            ERROR!(function: "ffi_load_index_writer", "Error loading index reader: {}", e);
-            let error_msg_for_cxx: String = format!("Error loading index reader: {}", e);
+            ERROR!(function: "ffi_finish_sorted_build", "Error finishing sorted build: {}", e);
+            let error_msg_for_cxx: String = format!("Error finishing sorted build: {}", e);
+            let error_code = match e {
+                TantivySearchError::Backpressure(_) => BACKPRESSURE_ERROR_CODE,
+                TantivySearchError::OutOfDiskSpace(_) => OUT_OF_DISK_SPACE_ERROR_CODE,
+                _ => -1,
+            };
+            U64Result {
+                result: 0,
+                error_code,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_reindex_index(index_path: &CxxString, index_json_parameter: &CxxString) -> U64Result {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_reindex_index", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return U64Result {
+                result: 0,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let index_json_parameter: String = match CXX_STRING_CONERTER.convert(index_json_parameter) {
+        Ok(parameter) => parameter,
+        Err(e) => {
+            ERROR!(function: "ffi_reindex_index", "Can't convert 'index_json_parameter', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert 'index_json_parameter', message: {}", e);
+            return U64Result {
+                result: 0,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match reindex_index(&index_path, &index_json_parameter, None) {
+        Ok(rows_indexed) => U64Result {
+            result: rows_indexed,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_reindex_index", "Error re-indexing index: {}", e);
+            let error_msg_for_cxx: String = format!("Error re-indexing index: {}", e);
+            let error_code = match e {
+                TantivySearchError::OutOfDiskSpace(_) => OUT_OF_DISK_SPACE_ERROR_CODE,
+                _ => -1,
+            };
+            U64Result {
+                result: 0,
+                error_code,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_upgrade_index(index_path: &CxxString) -> BoolResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_upgrade_index", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
             return BoolResult {
                 result: false,
                 error_code: -1,
                 error_msg: error_msg_for_cxx,
             };
         }
+    };
+
+    match upgrade_index(&index_path) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_upgrade_index", "Error upgrading index: {}", e);
+            let error_msg_for_cxx: String = format!("Error upgrading index: {}", e);
+            let error_code = match e {
+                TantivySearchError::OutOfDiskSpace(_) => OUT_OF_DISK_SPACE_ERROR_CODE,
+                TantivySearchError::IncompatibleIndexVersion(_) => {
+                    INCOMPATIBLE_INDEX_VERSION_ERROR_CODE
+                }
+                _ => -1,
+            };
+            BoolResult {
+                result: false,
+                error_code,
+                error_msg: error_msg_for_cxx,
+            }
+        }
     }
 }
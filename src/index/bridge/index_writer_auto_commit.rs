@@ -0,0 +1,212 @@
+use super::index_writer_bridge::IndexWriterBridge;
+use crate::common::heartbeat::Heartbeat;
+use crate::common::thread_affinity::{spawn_thread, ThreadKind};
+use crate::logger::logger_bridge::TantivySearchLogger;
+use crate::FFI_INDEX_SEARCHER_CACHE;
+use crate::{common::constants::LOG_CALLBACK, DEBUG, ERROR, INFO};
+use rand::Rng;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Weak};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+// How often the background task checks whether a commit is needed.
+static AUTO_COMMIT_TICK_MILLIS: u64 = 200;
+
+/// Auto commit will be triggered every `interval_secs` seconds or every `max_docs` operations
+/// since last commit, whichever comes first. Zero disables the corresponding condition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AutoCommitConfig {
+    pub interval_secs: u64,
+    pub max_docs: u64,
+}
+
+impl AutoCommitConfig {
+    pub fn is_valid(&self) -> bool {
+        self.interval_secs > 0 || self.max_docs > 0
+    }
+
+    // Add up to 10% random jitter to interval, avoid many writers commit at the same time.
+    fn next_deadline(&self) -> Option<Instant> {
+        if self.interval_secs == 0 {
+            return None;
+        }
+        let interval_millis = self.interval_secs.saturating_mul(1000);
+        let jitter_millis = rand::thread_rng().gen_range(0..=interval_millis / 10);
+        Some(Instant::now() + Duration::from_millis(interval_millis + jitter_millis))
+    }
+}
+
+/// Handle of a background auto commit task.
+pub struct AutoCommitHandle {
+    pub config: AutoCommitConfig,
+    // Beats every tick, it goes stale if a commit gets stuck.
+    pub heartbeat: Arc<Heartbeat>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl AutoCommitHandle {
+    /// Spawn a background task which commits the given index writer bridge.
+    /// The task only holds a weak reference, it will exit after the bridge has been dropped.
+    pub fn spawn(
+        bridge: Weak<IndexWriterBridge>,
+        config: AutoCommitConfig,
+    ) -> Result<Self, String> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = stop.clone();
+        let heartbeat = Arc::new(Heartbeat::new());
+        let task_heartbeat = heartbeat.clone();
+        let thread = spawn_thread(ThreadKind::Ingest, "auto-commit", move || {
+            Self::run(bridge, config, stop_flag, task_heartbeat)
+        })
+        .map_err(|e| format!("Failed to spawn auto commit thread: {}", e))?;
+        Ok(AutoCommitHandle {
+            config,
+            heartbeat,
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    fn run(
+        bridge: Weak<IndexWriterBridge>,
+        config: AutoCommitConfig,
+        stop: Arc<AtomicBool>,
+        heartbeat: Arc<Heartbeat>,
+    ) {
+        let mut deadline = config.next_deadline();
+        loop {
+            thread::sleep(Duration::from_millis(AUTO_COMMIT_TICK_MILLIS));
+            if stop.load(Ordering::Acquire) {
+                break;
+            }
+            let bridge = match bridge.upgrade() {
+                Some(bridge) => bridge,
+                None => break,
+            };
+            heartbeat.beat();
+
+            let uncommitted = bridge.uncommitted_operations();
+            let time_due = deadline.map_or(false, |deadline| Instant::now() >= deadline);
+            let docs_due = config.max_docs > 0 && uncommitted >= config.max_docs;
+
+            if uncommitted > 0 && (time_due || docs_due) {
+                match bridge.commit() {
+                    Ok(opstamp) => {
+                        DEBUG!(function:"auto_commit", "Auto committed {} operations, opstamp:{}, index_path:[{}]", uncommitted, opstamp, bridge.path);
+                        if let Ok(index_reader_bridge) =
+                            FFI_INDEX_SEARCHER_CACHE.get_index_reader_bridge(bridge.path.clone())
+                        {
+                            let _ = index_reader_bridge.reload();
+                        }
+                    }
+                    Err(e) => {
+                        ERROR!(function:"auto_commit", "Failed to auto commit index_path:[{}], {}", bridge.path, e);
+                    }
+                }
+                deadline = config.next_deadline();
+            } else if time_due {
+                deadline = config.next_deadline();
+            }
+        }
+        INFO!(function:"auto_commit", "Auto commit task has been stopped.");
+    }
+
+    /// Whether the background thread is still running, it exits after the bridge is dropped.
+    pub fn is_alive(&self) -> bool {
+        self.thread
+            .as_ref()
+            .map_or(false, |thread| !thread.is_finished())
+    }
+
+    /// Stop the background task and wait it finished.
+    pub fn stop(mut self) {
+        self.stop.store(true, Ordering::Release);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tantivy::{
+        schema::{Schema, FAST, INDEXED, TEXT},
+        Index, TantivyDocument,
+    };
+    use tempfile::TempDir;
+
+    fn create_index_writer_bridge(index_directory_str: &str) -> Arc<IndexWriterBridge> {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_u64_field("row_id", FAST | INDEXED);
+        schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_dir(index_directory_str, schema_builder.build())
+            .expect("Can't create index");
+        let writer = index
+            .writer_with_num_threads(2, 1024 * 1024 * 64)
+            .expect("Can't create index writer");
+        Arc::new(IndexWriterBridge::new(
+            index_directory_str.to_string(),
+            index,
+            writer,
+        ))
+    }
+
+    #[test]
+    fn test_auto_commit_config_is_valid() {
+        assert!(!AutoCommitConfig {
+            interval_secs: 0,
+            max_docs: 0
+        }
+        .is_valid());
+        assert!(AutoCommitConfig {
+            interval_secs: 1,
+            max_docs: 0
+        }
+        .is_valid());
+        assert!(AutoCommitConfig {
+            interval_secs: 0,
+            max_docs: 10
+        }
+        .is_valid());
+    }
+
+    #[test]
+    fn test_auto_commit_with_max_docs() {
+        let temp_directory = TempDir::new().expect("Can't create temp directory");
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let bridge = create_index_writer_bridge(temp_directory_str);
+
+        assert!(bridge
+            .start_auto_commit(AutoCommitConfig {
+                interval_secs: 0,
+                max_docs: 2,
+            })
+            .is_ok());
+
+        let row_id_field = bridge.index.schema().get_field("row_id").unwrap();
+        for row_id in 0..2u64 {
+            let mut doc = TantivyDocument::default();
+            doc.add_u64(row_id_field, row_id);
+            assert!(bridge.add_document(doc).is_ok());
+        }
+
+        // Wait background task commit.
+        let mut committed = false;
+        for _ in 0..50 {
+            thread::sleep(Duration::from_millis(AUTO_COMMIT_TICK_MILLIS));
+            if bridge.uncommitted_operations() == 0 {
+                committed = true;
+                break;
+            }
+        }
+        assert!(committed);
+        bridge.stop_auto_commit();
+
+        let searcher = bridge.index.reader().unwrap().searcher();
+        assert_eq!(searcher.num_docs(), 2);
+    }
+}
@@ -1,74 +1,840 @@
+use super::index_writer_auto_commit::{AutoCommitConfig, AutoCommitHandle};
+use super::index_writer_build_progress::BuildProgressTracker;
+use super::index_writer_checkpoint::{checkpoint_payload, committed_checkpoint};
+use super::index_writer_durability::IndexWriteOptions;
+use super::index_writer_external_sort::ExternalRowSorter;
+use super::index_writer_language_router::LanguageRouter;
+use super::index_writer_merge_events::{segment_bytes, MergeEvent, MergeProgress};
+use super::index_writer_row_id_check::{RowIdCheckMode, RowIdChecker};
+use super::index_writer_sort::DocumentSorter;
+use crate::common::constants::{
+    BUILD_PROGRESS_CALLBACK, COMMIT_CALLBACK, DISK_SPACE_GUARD, MERGE_CALLBACK, MERGE_THROTTLE,
+    METRICS,
+};
+use crate::common::geo::GeoPoint;
+use crate::ffi::BuildProgress;
 use crate::logger::logger_bridge::TantivySearchLogger;
-use crate::{common::constants::LOG_CALLBACK, INFO};
-use std::sync::Mutex;
-use tantivy::{Index, IndexWriter, Opstamp, TantivyDocument, Term};
+use crate::tokenizer::date_format::DateFormat;
+use crate::tokenizer::sparse_vector_format::SparseVectorFormat;
+use crate::tokenizer::vector_format::VectorFormat;
+use crate::utils::time_utils::TimeUtils;
+use crate::{common::constants::LOG_CALLBACK, INFO, TRACE_SPAN, WARNING};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, TryLockError};
+use std::thread;
+use std::time::{Duration, Instant};
+use tantivy::schema::Field;
+use tantivy::{Index, IndexWriter, Opstamp, SegmentId, TantivyDocument, Term};
+
+/// Runtime statistics of `IndexWriterBridge`, used for index health check.
+#[derive(Default)]
+pub struct IndexWriterBridgeStats {
+    pub last_commit_opstamp: AtomicU64,
+    pub lock_contention_count: AtomicU64,
+    pub uncommitted_operations: AtomicU64,
+    pub uncommitted_bytes: AtomicU64,
+    pub searchable_segments: AtomicU64,
+    // Unix timestamp in millis of last access through writer cache.
+    pub last_access_millis: AtomicU64,
+    pub last_error: Mutex<String>,
+}
+
+/// Backpressure thresholds of `IndexWriterBridge`, zero disables the corresponding check.
+/// - `max_uncommitted_bytes`: estimated bytes indexed since last commit, should be less than writer memory budget.
+/// - `max_segments`: searchable segments count, a large value means merges are far behind.
+/// - `block_timeout_millis`: how long `add_document` waits for backpressure to be relieved, 0 means fail fast.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BackpressureConfig {
+    pub max_uncommitted_bytes: u64,
+    pub max_segments: u64,
+    pub block_timeout_millis: u64,
+}
+
+// Interval for polling backpressure status when blocking.
+static BACKPRESSURE_POLL_MILLIS: u64 = 10;
 
 pub struct IndexWriterBridge {
     pub path: String,
     pub index: Index,
     pub writer: Mutex<Option<IndexWriter>>,
+    pub stats: IndexWriterBridgeStats,
+    pub auto_commit: Mutex<Option<AutoCommitHandle>>,
+    pub backpressure: Mutex<BackpressureConfig>,
+    pub row_id_checker: Mutex<RowIdChecker>,
+    pub auto_row_id: Mutex<Option<u64>>,
+    pub build_progress: Mutex<Option<BuildProgressTracker>>,
+    // Rows of a sorted build waiting to be indexed in row_id order, `None` if it's not started.
+    pub external_sort: Mutex<Option<ExternalRowSorter>>,
+    // Max row_id added, persisted in commit payload so interrupted builds can resume.
+    pub checkpoint_row_id: Mutex<Option<u64>>,
+    // Sorts documents of each batch by index sort field, `None` if index sort isn't configured.
+    pub document_sorter: Option<DocumentSorter>,
+    // Routes text to language sub-fields, `None` if language routing isn't configured.
+    pub language_router: Option<LanguageRouter>,
+    // Formats of date columns, their text is parsed as date.
+    pub date_formats: HashMap<Field, DateFormat>,
+    // Geo point columns, their text `lat,lon` is stored as Morton code.
+    pub geo_point_fields: HashSet<Field>,
+    // Formats of vector columns, their text is stored as little endian f32 bytes.
+    pub vector_formats: HashMap<Field, VectorFormat>,
+    // Quantization of sparse vector columns, their JSON term weights are pre-tokenized.
+    pub sparse_vector_formats: HashMap<Field, SparseVectorFormat>,
+    // Background merges observed by merge policy, `None` if they are disabled.
+    pub merge_progress: Option<Arc<MergeProgress>>,
+    // Durability and direct I/O of files written through index directory, shared with
+    // `DurableDirectory`. Durability takes effect on next commit, direct I/O on next file.
+    pub write_options: Arc<IndexWriteOptions>,
 }
 
 impl IndexWriterBridge {
+    pub fn new(path: String, index: Index, writer: IndexWriter) -> Self {
+        // Keep committed checkpoint, commits without new rows shouldn't drop it.
+        let checkpoint_row_id = committed_checkpoint(&index).unwrap_or_else(|e| {
+            WARNING!("Failed to read build checkpoint of [{}]: {}", path, e);
+            None
+        });
+        let bridge = IndexWriterBridge {
+            path,
+            index,
+            writer: Mutex::new(Some(writer)),
+            stats: IndexWriterBridgeStats::default(),
+            auto_commit: Mutex::new(None),
+            backpressure: Mutex::new(BackpressureConfig::default()),
+            row_id_checker: Mutex::new(RowIdChecker::default()),
+            auto_row_id: Mutex::new(None),
+            build_progress: Mutex::new(None),
+            external_sort: Mutex::new(None),
+            checkpoint_row_id: Mutex::new(checkpoint_row_id),
+            document_sorter: None,
+            language_router: None,
+            date_formats: HashMap::new(),
+            geo_point_fields: HashSet::new(),
+            vector_formats: HashMap::new(),
+            sparse_vector_formats: HashMap::new(),
+            merge_progress: None,
+            write_options: Arc::new(IndexWriteOptions::new()),
+        };
+        bridge.touch();
+        bridge
+    }
+
+    pub fn with_document_sorter(mut self, document_sorter: Option<DocumentSorter>) -> Self {
+        self.document_sorter = document_sorter;
+        self
+    }
+
+    pub fn with_language_router(mut self, language_router: Option<LanguageRouter>) -> Self {
+        self.language_router = language_router;
+        self
+    }
+
+    pub fn with_date_formats(mut self, date_formats: HashMap<Field, DateFormat>) -> Self {
+        self.date_formats = date_formats;
+        self
+    }
+
+    pub fn with_geo_point_fields(mut self, geo_point_fields: HashSet<Field>) -> Self {
+        self.geo_point_fields = geo_point_fields;
+        self
+    }
+
+    pub fn with_vector_formats(mut self, vector_formats: HashMap<Field, VectorFormat>) -> Self {
+        self.vector_formats = vector_formats;
+        self
+    }
+
+    pub fn with_sparse_vector_formats(
+        mut self,
+        sparse_vector_formats: HashMap<Field, SparseVectorFormat>,
+    ) -> Self {
+        self.sparse_vector_formats = sparse_vector_formats;
+        self
+    }
+
+    pub fn with_merge_progress(mut self, merge_progress: Option<Arc<MergeProgress>>) -> Self {
+        self.merge_progress = merge_progress;
+        self
+    }
+
+    pub fn with_write_options(mut self, write_options: Arc<IndexWriteOptions>) -> Self {
+        self.write_options = write_options;
+        self
+    }
+
+    // Add text of a host column to `document`, text of a date, geo point, vector or sparse vector
+    // column is parsed.
+    pub fn add_column_text(
+        &self,
+        document: &mut TantivyDocument,
+        field: Field,
+        text: &str,
+    ) -> Result<(), String> {
+        match self.date_formats.get(&field) {
+            Some(date_format) => {
+                document.add_date(field, date_format.parse(text)?);
+                Ok(())
+            }
+            None if self.geo_point_fields.contains(&field) => {
+                document.add_u64(field, GeoPoint::parse(text)?.encode());
+                Ok(())
+            }
+            None if self.vector_formats.contains_key(&field) => {
+                let vector: Vec<f32> = self.vector_formats[&field].parse(text)?;
+                document.add_bytes(field, VectorFormat::encode(&vector));
+                Ok(())
+            }
+            None if self.sparse_vector_formats.contains_key(&field) => {
+                document.add_pre_tokenized_text(
+                    field,
+                    self.sparse_vector_formats[&field].tokenize(text)?,
+                );
+                Ok(())
+            }
+            None => {
+                self.add_text(document, field, text);
+                Ok(())
+            }
+        }
+    }
+
+    // Add text of `field` to `document`, it's also added to the language sub-field detected.
+    pub fn add_text(&self, document: &mut TantivyDocument, field: Field, text: &str) {
+        if let Some(language_field) = self
+            .language_router
+            .as_ref()
+            .and_then(|language_router| language_router.language_field(field, text))
+        {
+            document.add_text(language_field, text);
+        }
+        document.add_text(field, text);
+    }
+
+    // Record an access of this writer.
+    pub fn touch(&self) {
+        self.stats
+            .last_access_millis
+            .store(TimeUtils::now_millis(), Ordering::Relaxed);
+    }
+
+    // Acquire writer lock, record lock contention when lock is held by others.
+    fn lock_writer(&self) -> Result<MutexGuard<'_, Option<IndexWriter>>, String> {
+        match self.writer.try_lock() {
+            Ok(writer) => Ok(writer),
+            Err(TryLockError::WouldBlock) => {
+                self.stats
+                    .lock_contention_count
+                    .fetch_add(1, Ordering::Relaxed);
+                self.writer
+                    .lock()
+                    .map_err(|e| self.record_error(format!("Lock error: {}", e)))
+            }
+            Err(TryLockError::Poisoned(e)) => Err(self.record_error(format!("Lock error: {}", e))),
+        }
+    }
+
+    // Record the last error happened in this writer.
+    fn record_error(&self, error: String) -> String {
+        if let Ok(mut last_error) = self.stats.last_error.lock() {
+            *last_error = error.clone();
+        }
+        error
+    }
+
+    pub fn last_error(&self) -> String {
+        match self.stats.last_error.lock() {
+            Ok(last_error) => last_error.clone(),
+            Err(e) => format!("Lock error: {}", e),
+        }
+    }
+
+    // Whether inner `IndexWriter` is available, never waits for the writer lock. A writer held
+    // by a commit or merge is alive, see `is_writer_busy`.
+    pub fn is_writer_alive(&self) -> bool {
+        match self.writer.try_lock() {
+            Ok(writer) => writer.is_some(),
+            Err(TryLockError::WouldBlock) => true,
+            Err(TryLockError::Poisoned(_)) => false,
+        }
+    }
+
+    // Whether inner `IndexWriter` is held by a commit, merge or document operation right now.
+    pub fn is_writer_busy(&self) -> bool {
+        matches!(self.writer.try_lock(), Err(TryLockError::WouldBlock))
+    }
+
+    // Number of add/delete operations since last commit.
+    pub fn uncommitted_operations(&self) -> u64 {
+        self.stats.uncommitted_operations.load(Ordering::Relaxed)
+    }
+
+    // Start a background task to commit automatically, replace the previous one if exists.
+    pub fn start_auto_commit(self: &Arc<Self>, config: AutoCommitConfig) -> Result<(), String> {
+        if !config.is_valid() {
+            return Err(
+                "Auto commit requires `interval_secs` or `max_docs` to be positive".to_string(),
+            );
+        }
+        self.stop_auto_commit();
+        let handle = AutoCommitHandle::spawn(Arc::downgrade(self), config)?;
+        let mut auto_commit = self
+            .auto_commit
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        *auto_commit = Some(handle);
+        Ok(())
+    }
+
+    // Config of the running auto commit task, `None` if it's not running.
+    pub fn auto_commit_config(&self) -> Option<AutoCommitConfig> {
+        match self.auto_commit.lock() {
+            Ok(auto_commit) => auto_commit.as_ref().map(|handle| handle.config),
+            Err(_) => None,
+        }
+    }
+
+    // Whether auto commit thread is alive and its last heartbeat, `None` if it's not running.
+    pub fn auto_commit_liveness(&self) -> Option<(bool, u64)> {
+        match self.auto_commit.lock() {
+            Ok(auto_commit) => auto_commit
+                .as_ref()
+                .map(|handle| (handle.is_alive(), handle.heartbeat.last_millis())),
+            Err(_) => None,
+        }
+    }
+
+    // Stop the background auto commit task, do nothing if it's not running.
+    pub fn stop_auto_commit(&self) {
+        let handle = match self.auto_commit.lock() {
+            Ok(mut auto_commit) => auto_commit.take(),
+            Err(_) => None,
+        };
+        if let Some(handle) = handle {
+            handle.stop();
+        }
+    }
+
+    pub fn set_backpressure_config(&self, config: BackpressureConfig) -> Result<(), String> {
+        let mut backpressure = self
+            .backpressure
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        *backpressure = config;
+        Ok(())
+    }
+
+    pub fn backpressure_config(&self) -> BackpressureConfig {
+        match self.backpressure.lock() {
+            Ok(config) => *config,
+            Err(_) => BackpressureConfig::default(),
+        }
+    }
+
+    /// Enable row_id verification, checker is seeded with row_ids already committed.
+    pub fn set_row_id_check(&self, mode: RowIdCheckMode, reject: bool) -> Result<(), String> {
+        let checker = RowIdChecker::seeded(&self.index, mode, reject)?;
+        let mut row_id_checker = self
+            .row_id_checker
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        *row_id_checker = checker;
+        Ok(())
+    }
+
+    /// Verify row_ids before they are added, violations are logged and counted.
+    /// Return `Err` if checker rejects violations.
+    pub fn check_row_ids(&self, row_ids: &[u64]) -> Result<(), String> {
+        let mut row_id_checker = self
+            .row_id_checker
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        match row_id_checker.check(row_ids) {
+            Ok(violations) => {
+                METRICS
+                    .row_id_violations
+                    .fetch_add(violations.len() as u64, Ordering::Relaxed);
+                for violation in violations {
+                    WARNING!(
+                        "Row_id violation in index_path:[{}], {}",
+                        self.path,
+                        violation
+                    );
+                }
+                Ok(())
+            }
+            Err(violation) => {
+                METRICS.row_id_violations.fetch_add(1, Ordering::Relaxed);
+                Err(self.record_error(violation))
+            }
+        }
+    }
+
+    // Max row_id in committed segments, deleted documents are included.
+    fn max_committed_row_id(&self) -> Result<Option<u64>, String> {
+        let searcher = self
+            .index
+            .reader()
+            .map_err(|e| format!("Failed to open reader for auto row_id: {}", e))?
+            .searcher();
+        let mut max_row_id: Option<u64> = None;
+        for segment_reader in searcher.segment_readers() {
+            if segment_reader.max_doc() == 0 {
+                continue;
+            }
+            let row_id_column = segment_reader
+                .fast_fields()
+                .u64("row_id")
+                .map_err(|e| format!("Failed to open row_id fast field: {}", e))?;
+            let segment_max = row_id_column.max_value();
+            max_row_id = Some(max_row_id.map_or(segment_max, |max| max.max(segment_max)));
+        }
+        Ok(max_row_id)
+    }
+
+    /// Enable automatic row_id assignment, return the next row_id to be assigned.
+    /// Row_ids continue from max committed row_id, do nothing if it's already enabled.
+    pub fn enable_auto_row_id(&self) -> Result<u64, String> {
+        let mut auto_row_id = self.lock_auto_row_id()?;
+        if let Some(next_row_id) = *auto_row_id {
+            return Ok(next_row_id);
+        }
+        let next_row_id = self.max_committed_row_id()?.map_or(0, |max| max + 1);
+        *auto_row_id = Some(next_row_id);
+        Ok(next_row_id)
+    }
+
+    /// Acquire next row_id to be assigned, `None` means automatic row_id is disabled.
+    /// Keep the guard until documents are added, so row_ids are assigned in order.
+    pub fn lock_auto_row_id(&self) -> Result<MutexGuard<'_, Option<u64>>, String> {
+        self.auto_row_id
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))
+    }
+
+    // Deleted row_ids are no longer duplicates once they are added again.
+    pub fn forget_row_ids(&self, row_ids: &[u64]) {
+        if let Ok(mut row_id_checker) = self.row_id_checker.lock() {
+            row_id_checker.forget(row_ids);
+        }
+    }
+
+    // Refresh searchable segments count, merged segments will be reflected after merge finished.
+    fn refresh_searchable_segments(&self) {
+        if let Ok(segment_ids) = self.index.searchable_segment_ids() {
+            self.stats
+                .searchable_segments
+                .store(segment_ids.len() as u64, Ordering::Relaxed);
+        }
+    }
+
+    // Return the reason if writer is under backpressure.
+    fn backpressure_reason(&self, config: &BackpressureConfig) -> Option<String> {
+        let uncommitted_bytes = self.stats.uncommitted_bytes.load(Ordering::Relaxed);
+        if config.max_uncommitted_bytes > 0 && uncommitted_bytes >= config.max_uncommitted_bytes {
+            return Some(format!(
+                "uncommitted bytes {} exceeds {}",
+                uncommitted_bytes, config.max_uncommitted_bytes
+            ));
+        }
+        let searchable_segments = self.stats.searchable_segments.load(Ordering::Relaxed);
+        if config.max_segments > 0 && searchable_segments >= config.max_segments {
+            return Some(format!(
+                "searchable segments {} exceeds {}",
+                searchable_segments, config.max_segments
+            ));
+        }
+        None
+    }
+
+    /// Check whether writer can accept more documents, blocks at most `block_timeout_millis`.
+    /// Return `Err` with the backpressure reason if it's still under pressure.
+    pub fn check_backpressure(&self) -> Result<(), String> {
+        let config = self.backpressure_config();
+        let mut reason = match self.backpressure_reason(&config) {
+            Some(reason) => reason,
+            None => return Ok(()),
+        };
+        let deadline = Instant::now() + Duration::from_millis(config.block_timeout_millis);
+        while Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(BACKPRESSURE_POLL_MILLIS));
+            if config.max_segments > 0 {
+                self.refresh_searchable_segments();
+            }
+            reason = match self.backpressure_reason(&config) {
+                Some(reason) => reason,
+                None => return Ok(()),
+            };
+        }
+        Err(reason)
+    }
+
+    /// Check that segments of uncommitted documents fit in free disk space.
+    /// Uncommitted bytes are used as estimated footprint, index files are rarely larger than raw docs.
+    pub fn check_disk_space_for_commit(&self) -> Result<(), String> {
+        let uncommitted_bytes = self.stats.uncommitted_bytes.load(Ordering::Relaxed);
+        DISK_SPACE_GUARD.check(&self.path, uncommitted_bytes)
+    }
+
+    // Bytes and docs of merge input segments, merged segment takes about the bytes of its inputs.
+    fn merge_footprint(&self, segment_ids: &[SegmentId]) -> (u64, u64) {
+        match self.index.searchable_segment_metas() {
+            Ok(segment_metas) => segment_metas
+                .iter()
+                .filter(|segment_meta| segment_ids.contains(&segment_meta.id()))
+                .fold((0u64, 0u64), |(bytes, num_docs), segment_meta| {
+                    (
+                        bytes + segment_bytes(&self.path, segment_meta),
+                        num_docs + segment_meta.num_docs() as u64,
+                    )
+                }),
+            Err(_) => (0, 0),
+        }
+    }
+
     // wrapper for IndexWriter.commit()
+    // Commit is refused before anything is written if it may run out of disk space.
     pub fn commit(&self) -> Result<Opstamp, String> {
-        match self.writer.lock() {
-            Ok(mut writer) => {
-                if let Some(writer) = writer.as_mut() {
-                    writer.commit().map_err(|e| e.to_string())
-                } else {
-                    Err("IndexWriterBridge is not available".to_string())
+        TRACE_SPAN!("commit", index_path = %self.path);
+        // Host disk space callback may call back into this writer, so it's checked without writer lock.
+        self.check_disk_space_for_commit()
+            .map_err(|e| self.record_error(e))?;
+        let mut writer = self.lock_writer()?;
+        if let Some(writer_ref) = writer.as_mut() {
+            let start = Instant::now();
+            // Checkpoint is read under writer lock, so it covers exactly the committed rows.
+            let commit_result = match self.checkpoint_row_id() {
+                Some(checkpoint_row_id) => {
+                    writer_ref.prepare_commit().and_then(|mut prepared_commit| {
+                        prepared_commit.set_payload(&checkpoint_payload(checkpoint_row_id));
+                        prepared_commit.commit()
+                    })
                 }
+                None => writer_ref.commit(),
+            };
+            METRICS.record_commit(start.elapsed(), commit_result.is_ok());
+            let opstamp = commit_result.map_err(|e| self.record_error(e.to_string()))?;
+            self.stats
+                .last_commit_opstamp
+                .store(opstamp, Ordering::Relaxed);
+            self.stats
+                .uncommitted_operations
+                .store(0, Ordering::Relaxed);
+            self.stats.uncommitted_bytes.store(0, Ordering::Relaxed);
+            self.refresh_searchable_segments();
+            // Host callback may call back into this writer, so it's invoked after lock released.
+            drop(writer);
+            self.notify_commit(opstamp);
+            Ok(opstamp)
+        } else {
+            Err(self.record_error("IndexWriterBridge is not available".to_string()))
+        }
+    }
+
+    // Notify host commit callback with committed docs and segments.
+    fn notify_commit(&self, opstamp: Opstamp) {
+        if COMMIT_CALLBACK.get().is_none() {
+            return;
+        }
+        match self.index.searchable_segment_metas() {
+            Ok(segment_metas) => {
+                let num_docs: u64 = segment_metas
+                    .iter()
+                    .map(|segment_meta| segment_meta.num_docs() as u64)
+                    .sum();
+                COMMIT_CALLBACK.notify(&self.path, opstamp, num_docs, segment_metas.len() as u64);
+            }
+            Err(e) => {
+                WARNING!("Failed to read segments for commit callback: {}", e);
             }
-            Err(e) => Err(format!("Lock error: {}", e)),
+        }
+    }
+
+    // Max row_id added, `None` if no row is added since index created.
+    pub fn checkpoint_row_id(&self) -> Option<u64> {
+        match self.checkpoint_row_id.lock() {
+            Ok(checkpoint_row_id) => *checkpoint_row_id,
+            Err(_) => None,
+        }
+    }
+
+    // Advance checkpoint, it's called under writer lock right after rows are added.
+    fn advance_checkpoint(&self, row_id: Option<u64>) {
+        if let (Some(row_id), Ok(mut checkpoint_row_id)) = (row_id, self.checkpoint_row_id.lock()) {
+            *checkpoint_row_id = Some(checkpoint_row_id.map_or(row_id, |max| max.max(row_id)));
         }
     }
 
     // wrapper for IndexWriter.add_document()
     pub fn add_document(&self, document: TantivyDocument) -> Result<Opstamp, String> {
-        match self.writer.lock() {
-            Ok(mut writer) => {
-                if let Some(writer) = writer.as_mut() {
-                    writer.add_document(document).map_err(|e| e.to_string())
-                } else {
-                    Err("IndexWriterBridge is not available".to_string())
-                }
+        self.add_document_with_size(document, 0, None)
+    }
+
+    // Add document and account its estimated size for backpressure,
+    // `checkpoint_row_id` is row_id of the document to be recorded in build checkpoint.
+    pub fn add_document_with_size(
+        &self,
+        document: TantivyDocument,
+        estimated_bytes: u64,
+        checkpoint_row_id: Option<u64>,
+    ) -> Result<Opstamp, String> {
+        TRACE_SPAN!("add_document");
+        let mut writer = self.lock_writer()?;
+        if let Some(writer) = writer.as_mut() {
+            let opstamp = writer
+                .add_document(document)
+                .map_err(|e| self.record_error(e.to_string()))?;
+            self.advance_checkpoint(checkpoint_row_id);
+            self.stats
+                .uncommitted_operations
+                .fetch_add(1, Ordering::Relaxed);
+            self.stats
+                .uncommitted_bytes
+                .fetch_add(estimated_bytes, Ordering::Relaxed);
+            METRICS.documents_indexed.fetch_add(1, Ordering::Relaxed);
+            METRICS
+                .bytes_indexed
+                .fetch_add(estimated_bytes, Ordering::Relaxed);
+            drop(writer);
+            self.record_build_progress(1, estimated_bytes);
+            Ok(opstamp)
+        } else {
+            Err(self.record_error("IndexWriterBridge is not available".to_string()))
+        }
+    }
+
+    // Add a batch of documents under one writer lock, `estimated_bytes` is size of whole batch,
+    // `checkpoint_row_id` is max row_id of the batch, rows skipped by caller included.
+    pub fn add_documents_with_size(
+        &self,
+        mut documents: Vec<TantivyDocument>,
+        estimated_bytes: u64,
+        checkpoint_row_id: Option<u64>,
+    ) -> Result<Opstamp, String> {
+        TRACE_SPAN!("add_document");
+        // Sort batch before writer lock acquired.
+        if let Some(document_sorter) = &self.document_sorter {
+            document_sorter.sort(&mut documents);
+        }
+        let mut writer = self.lock_writer()?;
+        if let Some(writer) = writer.as_mut() {
+            let documents_count = documents.len() as u64;
+            let mut opstamp: Opstamp = 0;
+            for document in documents {
+                opstamp = writer
+                    .add_document(document)
+                    .map_err(|e| self.record_error(e.to_string()))?;
             }
-            Err(e) => Err(format!("Lock error: {}", e)),
+            self.advance_checkpoint(checkpoint_row_id);
+            self.stats
+                .uncommitted_operations
+                .fetch_add(documents_count, Ordering::Relaxed);
+            self.stats
+                .uncommitted_bytes
+                .fetch_add(estimated_bytes, Ordering::Relaxed);
+            METRICS
+                .documents_indexed
+                .fetch_add(documents_count, Ordering::Relaxed);
+            METRICS
+                .bytes_indexed
+                .fetch_add(estimated_bytes, Ordering::Relaxed);
+            drop(writer);
+            self.record_build_progress(documents_count, estimated_bytes);
+            Ok(opstamp)
+        } else {
+            Err(self.record_error("IndexWriterBridge is not available".to_string()))
+        }
+    }
+
+    // Start tracking bulk build progress, previous progress is discarded.
+    pub fn start_build_progress(
+        &self,
+        total_docs: u64,
+        report_interval_millis: u64,
+    ) -> Result<(), String> {
+        let mut build_progress = self
+            .build_progress
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        *build_progress = Some(BuildProgressTracker::new(
+            total_docs,
+            report_interval_millis,
+        ));
+        Ok(())
+    }
+
+    // Stop tracking bulk build progress, return last progress if it's tracked.
+    pub fn stop_build_progress(&self) -> Result<Option<BuildProgress>, String> {
+        let mut build_progress = self
+            .build_progress
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        Ok(build_progress.take().map(|tracker| tracker.progress()))
+    }
+
+    // Start buffering rows of a sorted build, a running one should be finished first.
+    pub fn start_external_sort(&self, sorter: ExternalRowSorter) -> Result<(), String> {
+        let mut external_sort = self
+            .external_sort
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        if external_sort.is_some() {
+            return Err(format!(
+                "Sorted build of [{}] is already started",
+                self.path
+            ));
+        }
+        *external_sort = Some(sorter);
+        Ok(())
+    }
+
+    pub fn add_external_sort_batch(
+        &self,
+        row_ids: &[u64],
+        column_data: &[u8],
+        column_offsets: &[u64],
+    ) -> Result<(), String> {
+        let mut external_sort = self
+            .external_sort
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        match external_sort.as_mut() {
+            Some(sorter) => sorter.add_batch(row_ids, column_data, column_offsets),
+            None => Err(format!("Sorted build of [{}] isn't started", self.path)),
+        }
+    }
+
+    // Take rows of the sorted build to index them, `None` if it's not started.
+    pub fn take_external_sort(&self) -> Result<Option<ExternalRowSorter>, String> {
+        let mut external_sort = self
+            .external_sort
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        Ok(external_sort.take())
+    }
+
+    pub fn build_progress(&self) -> Result<Option<BuildProgress>, String> {
+        let build_progress = self
+            .build_progress
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        Ok(build_progress.as_ref().map(|tracker| tracker.progress()))
+    }
+
+    // Account docs to build progress, host callback is invoked without writer lock.
+    fn record_build_progress(&self, docs: u64, bytes: u64) {
+        let progress = match self.build_progress.lock() {
+            Ok(mut build_progress) => build_progress
+                .as_mut()
+                .and_then(|tracker| tracker.record(docs, bytes)),
+            Err(_) => None,
+        };
+        if let Some(progress) = progress {
+            BUILD_PROGRESS_CALLBACK.notify(&self.path, &progress);
         }
     }
 
     // wrapper for IndexWriter.delete_term()
     #[allow(dead_code)]
     pub fn delete_term(&self, term: Term) -> Result<Opstamp, String> {
-        match self.writer.lock() {
-            Ok(mut writer) => {
-                if let Some(writer) = writer.as_mut() {
-                    Ok(writer.delete_term(term))
-                } else {
-                    Err("IndexWriterBridge is not available for delete_term".to_string())
-                }
-            }
-            Err(e) => Err(format!("Lock error: {}", e)),
+        let mut writer = self.lock_writer()?;
+        if let Some(writer) = writer.as_mut() {
+            let opstamp = writer.delete_term(term);
+            self.stats
+                .uncommitted_operations
+                .fetch_add(1, Ordering::Relaxed);
+            METRICS.delete_operations.fetch_add(1, Ordering::Relaxed);
+            Ok(opstamp)
+        } else {
+            Err(self.record_error("IndexWriterBridge is not available for delete_term".to_string()))
         }
     }
 
     // Delete a group of terms.
     pub fn delete_terms(&self, terms: Vec<Term>) -> Result<Opstamp, String> {
-        match self.writer.lock() {
-            Ok(mut writer) => {
-                if let Some(writer) = writer.as_mut() {
-                    let mut opstamp: Opstamp = 0;
-                    for term in terms {
-                        opstamp = writer.delete_term(term)
-                    }
-                    Ok(opstamp)
-                } else {
-                    Err("IndexWriterBridge is not available for delete_term".to_string())
-                }
+        let mut writer = self.lock_writer()?;
+        if let Some(writer) = writer.as_mut() {
+            let mut opstamp: Opstamp = 0;
+            for term in terms {
+                opstamp = writer.delete_term(term);
+                self.stats
+                    .uncommitted_operations
+                    .fetch_add(1, Ordering::Relaxed);
+                METRICS.delete_operations.fetch_add(1, Ordering::Relaxed);
+            }
+            Ok(opstamp)
+        } else {
+            Err(self.record_error("IndexWriterBridge is not available for delete_term".to_string()))
+        }
+    }
+
+    // Wrapper for IndexWriter.merge(), blocks until merge finished without holding writer lock.
+    // Merge waits for `MERGE_THROTTLE` budget before writer is locked, and is reported to host.
+    // Merge is refused before budget is taken if its output may run out of disk space.
+    pub fn merge_segments(&self, segment_ids: &[SegmentId]) -> Result<(), String> {
+        let (bytes, num_docs) = self.merge_footprint(segment_ids);
+        DISK_SPACE_GUARD
+            .check(&self.path, bytes)
+            .map_err(|e| self.record_error(e))?;
+        MERGE_THROTTLE.acquire(bytes);
+
+        // Host callback may call back into this writer, so it's invoked without writer lock.
+        let segment_count = segment_ids.len() as u64;
+        MERGE_CALLBACK.notify(&self.path, MergeEvent::Start, segment_count, num_docs);
+        let merge_result = self.merge_segments_locked(segment_ids);
+        let event = if merge_result.is_ok() {
+            MergeEvent::Finish
+        } else {
+            MergeEvent::Failed
+        };
+        MERGE_CALLBACK.notify(&self.path, event, segment_count, num_docs);
+        merge_result
+    }
+
+    // Run background merges deferred by `MERGE_THROTTLE`, blocks until they finished.
+    // Merges whose segments are gone meanwhile, e.g. merged by others, are dropped.
+    // Return count of merges run.
+    pub fn merge_deferred(&self) -> Result<usize, String> {
+        let merge_progress = match self.merge_progress.as_ref() {
+            Some(merge_progress) => merge_progress,
+            None => return Ok(0),
+        };
+        let deferred_merges: Vec<Vec<SegmentId>> = match merge_progress.deferred_merges.lock() {
+            Ok(mut deferred_merges) => std::mem::take(&mut *deferred_merges),
+            Err(e) => return Err(self.record_error(format!("Lock error: {}", e))),
+        };
+        let committed: HashSet<SegmentId> = self
+            .index
+            .searchable_segment_ids()
+            .map_err(|e| self.record_error(e.to_string()))?
+            .into_iter()
+            .collect();
+        let mut merged: usize = 0;
+        for segment_ids in deferred_merges {
+            if segment_ids.iter().all(|id| committed.contains(id)) {
+                self.merge_segments(&segment_ids)?;
+                merged += 1;
             }
-            Err(e) => Err(format!("Lock error: {}", e)),
         }
+        Ok(merged)
+    }
+
+    // Writer lock is only held to start the merge, inserts, deletes and commits aren't blocked
+    // while segments are merged.
+    fn merge_segments_locked(&self, segment_ids: &[SegmentId]) -> Result<(), String> {
+        let mut writer = self.lock_writer()?;
+        let merge_future = match writer.as_mut() {
+            Some(writer) => writer.merge(segment_ids),
+            None => {
+                return Err(
+                    self.record_error("IndexWriterBridge is not available for merge".to_string())
+                )
+            }
+        };
+        drop(writer);
+        merge_future
+            .wait()
+            .map(|_| METRICS.record_merge(segment_ids.len()))
+            .map_err(|e| self.record_error(e.to_string()))
     }
 
     // Wrapper for IndexWriter.wait_merging_threads().
@@ -94,14 +860,13 @@ impl Drop for IndexWriterBridge {
 
 #[cfg(test)]
 mod tests {
-    use crate::index::bridge::index_writer_bridge::IndexWriterBridge;
+    use crate::index::bridge::index_writer_bridge::{BackpressureConfig, IndexWriterBridge};
     use crate::search::collector::row_id_bitmap_collector::RowIdRoaringCollector;
-    use std::sync::Mutex;
     use tantivy::{
         merge_policy::LogMergePolicy,
         query::QueryParser,
         schema::{Schema, FAST, INDEXED, STORED, TEXT},
-        Index, TantivyDocument, Term,
+        Index, IndexReader, ReloadPolicy, TantivyDocument, Term,
     };
     use tempfile::TempDir;
 
@@ -121,11 +886,8 @@ mod tests {
         // Configure default merge policy
         writer.set_merge_policy(Box::new(LogMergePolicy::default()));
         // Generate ffiIndexWriter.
-        let index_writer_bridge = IndexWriterBridge {
-            index,
-            path: index_directory_str.to_string(),
-            writer: Mutex::new(Some(writer)),
-        };
+        let index_writer_bridge =
+            IndexWriterBridge::new(index_directory_str.to_string(), index, writer);
         index_writer_bridge
     }
 
@@ -160,6 +922,15 @@ mod tests {
         QueryParser::for_index(&index_writer_bridge.index, vec![text_field])
     }
 
+    fn manual_reload_reader(index_writer_bridge: &IndexWriterBridge) -> IndexReader {
+        index_writer_bridge
+            .index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()
+            .expect("Can't get reader from index")
+    }
+
     #[test]
     pub fn test_add_document_and_commit() {
         // Create a temp directory for test.
@@ -178,12 +949,11 @@ mod tests {
             .expect("Can't parse query");
         let row_id_collector = RowIdRoaringCollector::with_field("row_id".to_string());
 
+        // One reader is reused by all queries, it's reloaded after each commit.
+        let reader = manual_reload_reader(&index_writer_bridge);
+
         // Execute a query before commit.
-        let searcher_1 = index_writer_bridge
-            .index
-            .reader()
-            .expect("Can't get reader from index")
-            .searcher();
+        let searcher_1 = reader.searcher();
         let searched_bitmap_1 = searcher_1
             .search(&text_query, &row_id_collector)
             .expect("Can't execute search.");
@@ -193,11 +963,8 @@ mod tests {
         assert!(index_writer_bridge.commit().is_ok());
 
         // Execute a qeury after commit.
-        let searcher_2 = index_writer_bridge
-            .index
-            .reader()
-            .expect("Can't get reader from index")
-            .searcher();
+        assert!(reader.reload().is_ok());
+        let searcher_2 = reader.searcher();
         let searched_bitmap_2 = searcher_2
             .search(&text_query, &row_id_collector)
             .expect("Can't execute search.");
@@ -255,12 +1022,11 @@ mod tests {
             .expect("Can't parse query");
         let row_id_collector = RowIdRoaringCollector::with_field("row_id".to_string());
 
+        // One reader is reused by all queries, it's reloaded after each commit.
+        let reader = manual_reload_reader(&index_writer_bridge);
+
         // Execute a query before delete a group of terms.
-        let searcher_1 = index_writer_bridge
-            .index
-            .reader()
-            .expect("Can't get reader from index")
-            .searcher();
+        let searcher_1 = reader.searcher();
         let searched_bitmap_1 = searcher_1
             .search(&text_query, &row_id_collector)
             .expect("Can't execute search.");
@@ -276,11 +1042,8 @@ mod tests {
         assert!(index_writer_bridge.commit().is_ok());
 
         // Execute a query after delete a group of terms.
-        let searcher_2 = index_writer_bridge
-            .index
-            .reader()
-            .expect("Can't get reader from index")
-            .searcher();
+        assert!(reader.reload().is_ok());
+        let searcher_2 = reader.searcher();
         let searched_bitmap_2 = searcher_2
             .search(&text_query, &row_id_collector)
             .expect("Can't execute search.");
@@ -292,14 +1055,47 @@ mod tests {
         assert!(index_writer_bridge.commit().is_ok());
 
         // Execute a query after delete the specific term.
-        let searcher_3 = index_writer_bridge
-            .index
-            .reader()
-            .expect("Can't get reader from index")
-            .searcher();
+        assert!(reader.reload().is_ok());
+        let searcher_3 = reader.searcher();
         let searched_bitmap_3 = searcher_3
             .search(&text_query, &row_id_collector)
             .expect("Can't execute search.");
         assert_eq!(searched_bitmap_3.len(), 0);
     }
+
+    #[test]
+    pub fn test_check_backpressure() {
+        // Create a temp directory for test.
+        let directory = TempDir::new().expect("Can't create temp directory");
+        let directory_str = directory.path().to_str().unwrap();
+
+        // Initialize a temp `IndexWriterBridge` for test.
+        let index_writer_bridge = create_index_in_temp_directory(directory_str);
+        let row_id_field = index_writer_bridge
+            .index
+            .schema()
+            .get_field("row_id")
+            .expect("Can't get row_id filed");
+
+        // Backpressure is disabled by default.
+        assert!(index_writer_bridge.check_backpressure().is_ok());
+
+        assert!(index_writer_bridge
+            .set_backpressure_config(BackpressureConfig {
+                max_uncommitted_bytes: 100,
+                max_segments: 0,
+                block_timeout_millis: 20,
+            })
+            .is_ok());
+        let mut doc = TantivyDocument::default();
+        doc.add_u64(row_id_field, 0);
+        assert!(index_writer_bridge
+            .add_document_with_size(doc, 100, None)
+            .is_ok());
+
+        // Writer is under backpressure until commit.
+        assert!(index_writer_bridge.check_backpressure().is_err());
+        assert!(index_writer_bridge.commit().is_ok());
+        assert!(index_writer_bridge.check_backpressure().is_ok());
+    }
 }
@@ -1,4 +1,5 @@
 use super::index_writer_bridge::IndexWriterBridge;
+use crate::common::constants::CANONICAL_INDEX_PATHS;
 use crate::logger::logger_bridge::TantivySearchLogger;
 use crate::{common::constants::LOG_CALLBACK, DEBUG, WARNING};
 use flurry::HashMap;
@@ -17,12 +18,15 @@ impl IndexWriterBridgeCache {
 
     pub fn get_index_writer_bridge(&self, key: String) -> Result<Arc<IndexWriterBridge>, String> {
         let pinned = self.cache.pin();
-        let trimmed_key: String = key.trim_end_matches('/').to_string();
-        match pinned.get(&trimmed_key) {
-            Some(result) => Ok(result.clone()),
+        let canonical_key: String = CANONICAL_INDEX_PATHS.canonical_of(&key);
+        match pinned.get(&canonical_key) {
+            Some(result) => {
+                result.touch();
+                Ok(result.clone())
+            }
             None => Err(format!(
                 "Index Writer doesn't exist with given key: [{}]",
-                trimmed_key
+                canonical_key
             )),
         }
     }
@@ -33,30 +37,46 @@ impl IndexWriterBridgeCache {
         value: Arc<IndexWriterBridge>,
     ) -> Result<(), String> {
         let pinned = self.cache.pin();
-        let trimmed_key: String = key.trim_end_matches('/').to_string();
-        if pinned.contains_key(&trimmed_key) {
-            pinned.insert(trimmed_key.clone(), value.clone());
+        let canonical_key: String = CANONICAL_INDEX_PATHS.register(&key);
+        if pinned.contains_key(&canonical_key) {
+            pinned.insert(canonical_key.clone(), value.clone());
             WARNING!(
                 "{}",
                 format!(
                     "Index writer already exists with given key: [{}], it has been overwritten.",
-                    trimmed_key
+                    canonical_key
                 )
             )
         } else {
-            pinned.insert(trimmed_key, value.clone());
+            pinned.insert(canonical_key, value.clone());
         }
         Ok(())
     }
+    // Number of cached bridges.
+    pub fn bridge_count(&self) -> usize {
+        self.cache.pin().len()
+    }
+
+    // Snapshot of all cached index writers with their keys.
+    pub fn all_index_writer_bridges(&self) -> Vec<(String, Arc<IndexWriterBridge>)> {
+        self.cache
+            .pin()
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+
     pub fn remove_index_writer_bridge(&self, key: String) -> Result<(), String> {
         let pinned = self.cache.pin();
-        let trimmed_key: String = key.trim_end_matches('/').to_string();
-        if pinned.contains_key(&trimmed_key) {
-            pinned.remove(&trimmed_key);
+        let canonical_key: String = CANONICAL_INDEX_PATHS.canonical_of(&key);
+        if pinned.contains_key(&canonical_key) {
+            pinned.remove(&canonical_key);
+            // Memoized resolution is dropped with the index, the other cache resolves it again.
+            CANONICAL_INDEX_PATHS.forget(&canonical_key);
         } else {
             let message = format!(
                 "IndexWriterBridge doesn't exist, can't remove it with given key: [{}]",
-                trimmed_key
+                canonical_key
             );
             DEBUG!("{}", message)
         }
@@ -68,7 +88,7 @@ impl IndexWriterBridgeCache {
 mod tests {
     use crate::index::bridge::index_writer_bridge::IndexWriterBridge;
     use crate::index::bridge::index_writer_bridge_cache::IndexWriterBridgeCache;
-    use std::sync::{Arc, Mutex};
+    use std::sync::Arc;
     use tantivy::{
         merge_policy::LogMergePolicy,
         schema::{Schema, FAST, INDEXED, STORED, TEXT},
@@ -92,11 +112,7 @@ mod tests {
         // Configure default merge policy
         writer.set_merge_policy(Box::new(LogMergePolicy::default()));
         // Generate indexW.
-        let index_w = IndexWriterBridge {
-            index,
-            path: index_directory_str.to_string(),
-            writer: Mutex::new(Some(writer)),
-        };
+        let index_w = IndexWriterBridge::new(index_directory_str.to_string(), index, writer);
         index_w
     }
 
@@ -183,4 +199,27 @@ mod tests {
         let second_removed = test_cache.remove_index_writer_bridge(path_str.to_string());
         assert!(second_removed.is_ok());
     }
+
+    #[test]
+    fn test_index_writer_bridge_with_path_spellings() {
+        let test_cache = IndexWriterBridgeCache::new();
+        let path = TempDir::new().expect("Can't create temp directory");
+        let path_str = path.path().to_str().unwrap();
+
+        let index_value = Arc::new(create_index_in_temp_directory(path_str));
+        assert!(test_cache
+            .set_index_writer_bridge(format!("{}/", path_str), index_value)
+            .is_ok());
+        // Different spellings of the same directory share one writer.
+        assert!(test_cache
+            .get_index_writer_bridge(path_str.to_string())
+            .is_ok());
+        assert!(test_cache
+            .get_index_writer_bridge(format!("{}//.", path_str))
+            .is_ok());
+        assert!(test_cache
+            .remove_index_writer_bridge(format!("{}/./", path_str))
+            .is_ok());
+        assert_eq!(test_cache.bridge_count(), 0);
+    }
 }
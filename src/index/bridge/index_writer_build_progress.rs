@@ -0,0 +1,138 @@
+use crate::common::callback_cell::CallbackCell;
+use crate::common::constants::BuildProgressCallback;
+use crate::ffi::BuildProgress;
+use std::ffi::CString;
+use std::time::{Duration, Instant};
+
+/// Progress of a bulk build, docs added through the writer after build started are counted.
+/// `total_docs` is given by host, 0 means unknown and ETA won't be estimated.
+pub struct BuildProgressTracker {
+    total_docs: u64,
+    report_interval: Duration,
+    started: Instant,
+    last_report: Instant,
+    docs_indexed: u64,
+    bytes_indexed: u64,
+}
+
+impl BuildProgressTracker {
+    pub fn new(total_docs: u64, report_interval_millis: u64) -> Self {
+        let now = Instant::now();
+        BuildProgressTracker {
+            total_docs,
+            report_interval: Duration::from_millis(report_interval_millis),
+            started: now,
+            last_report: now,
+            docs_indexed: 0,
+            bytes_indexed: 0,
+        }
+    }
+
+    // Account indexed docs, return progress if it should be reported, 0 interval never reports.
+    pub fn record(&mut self, docs: u64, bytes: u64) -> Option<BuildProgress> {
+        self.record_at(docs, bytes, Instant::now())
+    }
+
+    fn record_at(&mut self, docs: u64, bytes: u64, now: Instant) -> Option<BuildProgress> {
+        self.docs_indexed += docs;
+        self.bytes_indexed += bytes;
+        if self.report_interval.is_zero()
+            || now.saturating_duration_since(self.last_report) < self.report_interval
+        {
+            return None;
+        }
+        self.last_report = now;
+        Some(self.progress_at(now))
+    }
+
+    pub fn progress(&self) -> BuildProgress {
+        self.progress_at(Instant::now())
+    }
+
+    // Throughput is averaged since build started, ETA is -1 if it can't be estimated yet.
+    fn progress_at(&self, now: Instant) -> BuildProgress {
+        let elapsed = now.saturating_duration_since(self.started);
+        let docs_per_sec = if elapsed.is_zero() {
+            0.0
+        } else {
+            self.docs_indexed as f64 / elapsed.as_secs_f64()
+        };
+        let eta_millis: i64 = if self.total_docs == 0 {
+            -1
+        } else if self.docs_indexed >= self.total_docs {
+            0
+        } else if docs_per_sec > 0.0 {
+            ((self.total_docs - self.docs_indexed) as f64 / docs_per_sec * 1000.0) as i64
+        } else {
+            -1
+        };
+        BuildProgress {
+            total_docs: self.total_docs,
+            docs_indexed: self.docs_indexed,
+            bytes_indexed: self.bytes_indexed,
+            elapsed_millis: elapsed.as_millis() as u64,
+            docs_per_sec,
+            eta_millis,
+        }
+    }
+}
+
+/// Holds the host build progress callback.
+pub type BuildProgressCallbackCell = CallbackCell<BuildProgressCallback>;
+
+impl BuildProgressCallbackCell {
+    // Invoke callback with progress, `index_path` containing nul byte is passed as empty string.
+    pub fn notify(&self, index_path: &str, progress: &BuildProgress) {
+        if let Some(callback) = self.get() {
+            let index_path = CString::new(index_path).unwrap_or_default();
+            callback(
+                index_path.as_ptr(),
+                progress.docs_indexed,
+                progress.total_docs,
+                progress.bytes_indexed,
+                progress.eta_millis,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_progress_tracker() {
+        let mut tracker = BuildProgressTracker::new(1000, 1000);
+        let started = tracker.started;
+        let progress = tracker.progress_at(started);
+        assert_eq!(progress.docs_indexed, 0);
+        assert_eq!(progress.eta_millis, -1);
+
+        // Not reported before interval elapsed.
+        assert!(tracker
+            .record_at(100, 4096, started + Duration::from_millis(500))
+            .is_none());
+        let progress = tracker
+            .record_at(100, 4096, started + Duration::from_secs(2))
+            .unwrap();
+        assert_eq!(progress.docs_indexed, 200);
+        assert_eq!(progress.bytes_indexed, 8192);
+        assert_eq!(progress.elapsed_millis, 2000);
+        assert_eq!(progress.docs_per_sec, 100.0);
+        assert_eq!(progress.eta_millis, 8000);
+        // Interval restarts after each report.
+        assert!(tracker
+            .record_at(800, 0, started + Duration::from_millis(2500))
+            .is_none());
+        assert_eq!(
+            tracker
+                .progress_at(started + Duration::from_secs(3))
+                .eta_millis,
+            0
+        );
+
+        // Unknown total docs.
+        let tracker = BuildProgressTracker::new(0, 0);
+        assert_eq!(tracker.progress().eta_millis, -1);
+    }
+}
@@ -0,0 +1,59 @@
+use serde_json::{json, Value};
+use tantivy::Index;
+
+// Key of the build checkpoint in commit payload.
+static CHECKPOINT_ROW_ID_KEY: &str = "checkpoint_row_id";
+
+/// Commit payload recording the build checkpoint, it's the max row_id added before the commit.
+/// Bulk builds add rows in row_id order, so rows up to the checkpoint are durable once committed.
+pub fn checkpoint_payload(checkpoint_row_id: u64) -> String {
+    json!({ CHECKPOINT_ROW_ID_KEY: checkpoint_row_id }).to_string()
+}
+
+/// Parse build checkpoint from commit payload, `None` if payload doesn't carry one.
+pub fn parse_checkpoint_payload(payload: &str) -> Option<u64> {
+    serde_json::from_str::<Value>(payload)
+        .ok()?
+        .get(CHECKPOINT_ROW_ID_KEY)?
+        .as_u64()
+}
+
+/// Build checkpoint of the last commit of `index`, `None` if it's never committed with one.
+pub fn committed_checkpoint(index: &Index) -> Result<Option<u64>, String> {
+    let index_meta = index
+        .load_metas()
+        .map_err(|e| format!("Failed to load index metas: {}", e))?;
+    Ok(index_meta
+        .payload
+        .as_deref()
+        .and_then(parse_checkpoint_payload))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tantivy::schema::{Schema, TEXT};
+
+    #[test]
+    fn test_checkpoint_payload() {
+        assert_eq!(parse_checkpoint_payload(&checkpoint_payload(42)), Some(42));
+        assert_eq!(
+            parse_checkpoint_payload(&checkpoint_payload(u64::MAX)),
+            Some(u64::MAX)
+        );
+        // Payload written by others isn't a checkpoint.
+        assert_eq!(parse_checkpoint_payload("custom payload"), None);
+        assert_eq!(parse_checkpoint_payload(r#"{"other":1}"#), None);
+
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        assert_eq!(committed_checkpoint(&index).unwrap(), None);
+
+        let mut writer = index.writer_with_num_threads(1, 1024 * 1024 * 64).unwrap();
+        let mut prepared_commit = writer.prepare_commit().unwrap();
+        prepared_commit.set_payload(&checkpoint_payload(7));
+        assert!(prepared_commit.commit().is_ok());
+        assert_eq!(committed_checkpoint(&index).unwrap(), Some(7));
+    }
+}
@@ -0,0 +1,57 @@
+use crate::common::callback_cell::CallbackCell;
+use crate::common::constants::CommitCallback;
+use std::ffi::CString;
+
+/// Holds the host commit callback, invoked after every successful commit of any index writer.
+pub type CommitCallbackCell = CallbackCell<CommitCallback>;
+
+impl CommitCallbackCell {
+    // Invoke callback with commit result, `index_path` containing nul byte is passed as empty string.
+    pub fn notify(&self, index_path: &str, opstamp: u64, num_docs: u64, segment_count: u64) {
+        if let Some(callback) = self.get() {
+            let index_path = CString::new(index_path).unwrap_or_default();
+            callback(index_path.as_ptr(), opstamp, num_docs, segment_count);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libc::c_char;
+    use std::ffi::CStr;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static NOTIFIED_OPSTAMP: AtomicU64 = AtomicU64::new(0);
+    static NOTIFIED_DOCS: AtomicU64 = AtomicU64::new(0);
+
+    extern "C" fn commit_callback(
+        index_path: *const c_char,
+        opstamp: u64,
+        num_docs: u64,
+        _segment_count: u64,
+    ) {
+        let index_path = unsafe { CStr::from_ptr(index_path) };
+        assert_eq!(index_path.to_str().unwrap(), "/data/index");
+        NOTIFIED_OPSTAMP.store(opstamp, Ordering::SeqCst);
+        NOTIFIED_DOCS.store(num_docs, Ordering::SeqCst);
+    }
+
+    #[test]
+    fn test_notify_commit_callback() {
+        let cell = CommitCallbackCell::new();
+        // Nothing happens without callback.
+        cell.notify("/data/index", 1, 1, 1);
+        assert_eq!(NOTIFIED_OPSTAMP.load(Ordering::SeqCst), 0);
+
+        assert!(cell.set(commit_callback).is_none());
+        cell.notify("/data/index", 7, 100, 2);
+        assert_eq!(NOTIFIED_OPSTAMP.load(Ordering::SeqCst), 7);
+        assert_eq!(NOTIFIED_DOCS.load(Ordering::SeqCst), 100);
+
+        assert!(cell.clear().is_some());
+        cell.notify("/data/index", 8, 101, 2);
+        assert_eq!(NOTIFIED_OPSTAMP.load(Ordering::SeqCst), 7);
+        assert!(cell.clear().is_none());
+    }
+}
@@ -0,0 +1,419 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::Arc;
+
+use tantivy::directory::error::{
+    DeleteError, LockError, OpenDirectoryError, OpenReadError, OpenWriteError,
+};
+use tantivy::directory::{
+    AntiCallToken, DirectoryLock, FileHandle, FileSlice, Lock, MmapDirectory, TerminatingWrite,
+    WatchCallback, WatchHandle, WritePtr,
+};
+use tantivy::Directory;
+
+use crate::logger::logger_bridge::TantivySearchLogger;
+use crate::{common::constants::LOG_CALLBACK, WARNING};
+
+// O_DIRECT requires file offsets, lengths and buffers aligned to logical block size.
+const DIRECT_IO_ALIGNMENT: usize = 4096;
+const DIRECT_IO_BUFFER_BYTES: usize = 1024 * 1024;
+
+/// How index files are flushed to disk when they are committed.
+/// Bulk rebuilds which can be run again after a crash may skip syncs for speed,
+/// a crash may then leave torn segments or `meta.json` behind.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommitDurability {
+    // fsync files and index directory, file metadata included.
+    Fsync = 0,
+    // fdatasync files and fsync index directory, like tantivy itself.
+    Fdatasync = 1,
+    // Leave flushing to OS.
+    None = 2,
+}
+
+impl CommitDurability {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name {
+            "fsync" => Ok(CommitDurability::Fsync),
+            "fdatasync" => Ok(CommitDurability::Fdatasync),
+            "none" => Ok(CommitDurability::None),
+            _ => Err(format!(
+                "Unknown commit durability `{}`, should be one of `fsync`, `fdatasync` and `none`",
+                name
+            )),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CommitDurability::Fsync => "fsync",
+            CommitDurability::Fdatasync => "fdatasync",
+            CommitDurability::None => "none",
+        }
+    }
+
+    fn sync_file(&self, file: &File) -> io::Result<()> {
+        match self {
+            CommitDurability::Fsync => file.sync_all(),
+            CommitDurability::Fdatasync => file.sync_data(),
+            CommitDurability::None => Ok(()),
+        }
+    }
+}
+
+/// Write options shared by an index directory and its writer bridge, so they can be changed
+/// at runtime. Files are written with options current when they are opened and synced with
+/// durability current when they are finished.
+#[derive(Debug)]
+pub struct IndexWriteOptions {
+    durability: AtomicU8,
+    direct_io: AtomicBool,
+}
+
+impl IndexWriteOptions {
+    pub fn new() -> Self {
+        IndexWriteOptions {
+            durability: AtomicU8::new(CommitDurability::Fsync as u8),
+            direct_io: AtomicBool::new(false),
+        }
+    }
+
+    pub fn durability(&self) -> CommitDurability {
+        match self.durability.load(Ordering::Relaxed) {
+            0 => CommitDurability::Fsync,
+            1 => CommitDurability::Fdatasync,
+            _ => CommitDurability::None,
+        }
+    }
+
+    pub fn set_durability(&self, durability: CommitDurability) {
+        self.durability.store(durability as u8, Ordering::Relaxed);
+    }
+
+    pub fn direct_io(&self) -> bool {
+        self.direct_io.load(Ordering::Relaxed)
+    }
+
+    pub fn set_direct_io(&self, direct_io: bool) {
+        self.direct_io.store(direct_io, Ordering::Relaxed);
+    }
+}
+
+impl Default for IndexWriteOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `MmapDirectory` whose written files are synced by `CommitDurability` and optionally bypass
+/// page cache with direct I/O. Reads, locks and watches are served by the mmap directory.
+#[derive(Debug, Clone)]
+pub struct DurableDirectory {
+    inner: MmapDirectory,
+    root_path: PathBuf,
+    write_options: Arc<IndexWriteOptions>,
+}
+
+impl DurableDirectory {
+    pub fn open(
+        directory_path: &Path,
+        write_options: Arc<IndexWriteOptions>,
+    ) -> Result<Self, OpenDirectoryError> {
+        let inner: MmapDirectory = MmapDirectory::open(directory_path)?;
+        Ok(DurableDirectory {
+            inner,
+            root_path: directory_path.to_path_buf(),
+            write_options,
+        })
+    }
+}
+
+// File written by `DurableDirectory`, synced when tantivy finishes it.
+struct DurableFileWriter {
+    file: File,
+    write_options: Arc<IndexWriteOptions>,
+}
+
+impl Write for DurableFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+impl TerminatingWrite for DurableFileWriter {
+    fn terminate_ref(&mut self, _: AntiCallToken) -> io::Result<()> {
+        self.file.flush()?;
+        self.write_options.durability().sync_file(&self.file)
+    }
+}
+
+// File written with O_DIRECT, so segments of bulk builds don't evict page cache of queries.
+// Data is written in whole aligned blocks, `flush` can't write a partial block, the last one
+// is padded when file is finished and padding is truncated.
+struct DirectFileWriter {
+    file: File,
+    // Aligned buffer is `storage[offset..offset + DIRECT_IO_BUFFER_BYTES]`.
+    storage: Vec<u8>,
+    offset: usize,
+    buffered: usize,
+    written: u64,
+    write_options: Arc<IndexWriteOptions>,
+}
+
+impl DirectFileWriter {
+    fn new(file: File, write_options: Arc<IndexWriteOptions>) -> Self {
+        let storage: Vec<u8> = vec![0u8; DIRECT_IO_BUFFER_BYTES + DIRECT_IO_ALIGNMENT];
+        let offset: usize = storage.as_ptr().align_offset(DIRECT_IO_ALIGNMENT);
+        DirectFileWriter {
+            file,
+            storage,
+            offset,
+            buffered: 0,
+            written: 0,
+            write_options,
+        }
+    }
+
+    fn write_blocks(&mut self, len: usize) -> io::Result<()> {
+        self.file
+            .write_all(&self.storage[self.offset..self.offset + len])?;
+        self.written += len as u64;
+        self.buffered = 0;
+        Ok(())
+    }
+}
+
+impl Write for DirectFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len: usize = buf.len().min(DIRECT_IO_BUFFER_BYTES - self.buffered);
+        let start: usize = self.offset + self.buffered;
+        self.storage[start..start + len].copy_from_slice(&buf[..len]);
+        self.buffered += len;
+        if self.buffered == DIRECT_IO_BUFFER_BYTES {
+            self.write_blocks(DIRECT_IO_BUFFER_BYTES)?;
+        }
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl TerminatingWrite for DirectFileWriter {
+    fn terminate_ref(&mut self, _: AntiCallToken) -> io::Result<()> {
+        if self.buffered > 0 {
+            let tail: usize = self.buffered;
+            let padded: usize = tail.div_ceil(DIRECT_IO_ALIGNMENT) * DIRECT_IO_ALIGNMENT;
+            let start: usize = self.offset + tail;
+            self.storage[start..self.offset + padded].fill(0);
+            self.write_blocks(padded)?;
+            self.written -= (padded - tail) as u64;
+            self.file.set_len(self.written)?;
+        }
+        self.write_options.durability().sync_file(&self.file)
+    }
+}
+
+// Switch `file` to direct I/O, fails on file systems without O_DIRECT like tmpfs.
+#[cfg(target_os = "linux")]
+fn enable_direct_io(file: &File) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+    // Safety: `file` owns a valid descriptor during both calls.
+    let flags: libc::c_int = unsafe { libc::fcntl(file.as_raw_fd(), libc::F_GETFL) };
+    if flags < 0
+        || unsafe { libc::fcntl(file.as_raw_fd(), libc::F_SETFL, flags | libc::O_DIRECT) } < 0
+    {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn enable_direct_io(_file: &File) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "direct I/O is only supported on linux",
+    ))
+}
+
+impl Directory for DurableDirectory {
+    fn get_file_handle(&self, path: &Path) -> Result<Arc<dyn FileHandle>, OpenReadError> {
+        self.inner.get_file_handle(path)
+    }
+
+    fn open_read(&self, path: &Path) -> Result<FileSlice, OpenReadError> {
+        self.inner.open_read(path)
+    }
+
+    fn delete(&self, path: &Path) -> Result<(), DeleteError> {
+        self.inner.delete(path)
+    }
+
+    fn exists(&self, path: &Path) -> Result<bool, OpenReadError> {
+        self.inner.exists(path)
+    }
+
+    fn open_write(&self, path: &Path) -> Result<WritePtr, OpenWriteError> {
+        let full_path: PathBuf = self.root_path.join(path);
+        let file: File = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&full_path)
+            .map_err(|e| {
+                if e.kind() == io::ErrorKind::AlreadyExists {
+                    OpenWriteError::FileAlreadyExists(path.to_path_buf())
+                } else {
+                    OpenWriteError::IoError {
+                        io_error: Arc::new(e),
+                        filepath: path.to_path_buf(),
+                    }
+                }
+            })?;
+        // New directory entry is persisted by `sync_directory` at commit.
+        if self.write_options.direct_io() {
+            match enable_direct_io(&file) {
+                Ok(()) => {
+                    return Ok(BufWriter::new(Box::new(DirectFileWriter::new(
+                        file,
+                        self.write_options.clone(),
+                    ))));
+                }
+                Err(e) => {
+                    WARNING!(
+                        "{}",
+                        format!(
+                            "Direct I/O is unavailable for {:?}, written through page cache: {}",
+                            full_path, e
+                        )
+                    );
+                }
+            }
+        }
+        Ok(BufWriter::new(Box::new(DurableFileWriter {
+            file,
+            write_options: self.write_options.clone(),
+        })))
+    }
+
+    fn atomic_read(&self, path: &Path) -> Result<Vec<u8>, OpenReadError> {
+        self.inner.atomic_read(path)
+    }
+
+    // Content is written aside then renamed, readers never see a partial `meta.json`.
+    fn atomic_write(&self, path: &Path, data: &[u8]) -> io::Result<()> {
+        let durability: CommitDurability = self.write_options.durability();
+        let mut temp_file = tempfile::Builder::new()
+            .prefix(".tmp")
+            .tempfile_in(&self.root_path)?;
+        temp_file.write_all(data)?;
+        temp_file.flush()?;
+        durability.sync_file(temp_file.as_file())?;
+        temp_file
+            .persist(self.root_path.join(path))
+            .map_err(|e| e.error)?;
+        self.sync_directory()
+    }
+
+    fn acquire_lock(&self, lock: &Lock) -> Result<DirectoryLock, LockError> {
+        self.inner.acquire_lock(lock)
+    }
+
+    fn watch(&self, watch_callback: WatchCallback) -> tantivy::Result<WatchHandle> {
+        self.inner.watch(watch_callback)
+    }
+
+    fn sync_directory(&self) -> io::Result<()> {
+        match self.write_options.durability() {
+            CommitDurability::None => Ok(()),
+            _ => self.inner.sync_directory(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tantivy::schema::{Schema, STORED, TEXT};
+    use tantivy::{doc, Index};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_commit_durability_from_name() {
+        for durability in [
+            CommitDurability::Fsync,
+            CommitDurability::Fdatasync,
+            CommitDurability::None,
+        ] {
+            assert_eq!(
+                CommitDurability::from_name(durability.name()),
+                Ok(durability)
+            );
+        }
+        assert!(CommitDurability::from_name("fast").is_err());
+    }
+
+    #[test]
+    fn test_commit_with_each_write_option() {
+        let temp_directory = TempDir::new().unwrap();
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT | STORED);
+        let write_options = Arc::new(IndexWriteOptions::new());
+        let directory =
+            DurableDirectory::open(temp_directory.path(), write_options.clone()).unwrap();
+        let index = Index::create(directory, schema_builder.build(), Default::default()).unwrap();
+        let mut writer = index.writer_with_num_threads(1, 15_000_000).unwrap();
+
+        let mut num_docs: u64 = 0;
+        for direct_io in [false, true] {
+            for durability in [
+                CommitDurability::Fsync,
+                CommitDurability::Fdatasync,
+                CommitDurability::None,
+            ] {
+                write_options.set_direct_io(direct_io);
+                write_options.set_durability(durability);
+                assert_eq!(write_options.direct_io(), direct_io);
+                assert_eq!(write_options.durability(), durability);
+                writer.add_document(doc!(text => "durable")).unwrap();
+                assert!(writer.commit().is_ok());
+                num_docs += 1;
+                let reader = index.reader().unwrap();
+                assert_eq!(reader.searcher().num_docs(), num_docs);
+            }
+        }
+
+        // Committed index is opened by plain mmap directory.
+        let reopened = Index::open_in_dir(temp_directory.path()).unwrap();
+        assert_eq!(reopened.reader().unwrap().searcher().num_docs(), num_docs);
+    }
+
+    #[test]
+    fn test_direct_file_writer() {
+        let temp_directory = TempDir::new().unwrap();
+        let path = temp_directory.path().join("direct");
+        let file = File::create(&path).unwrap();
+        // Content must be the same whether file system accepts O_DIRECT or not.
+        let _ = enable_direct_io(&file);
+        let content: Vec<u8> = (0..DIRECT_IO_BUFFER_BYTES * 2 + 12345)
+            .map(|i| (i % 251) as u8)
+            .collect();
+        let mut writer = DirectFileWriter::new(file, Arc::new(IndexWriteOptions::new()));
+        assert_eq!(
+            writer.storage[writer.offset..].as_ptr() as usize % DIRECT_IO_ALIGNMENT,
+            0
+        );
+        for chunk in content.chunks(7777) {
+            writer.write_all(chunk).unwrap();
+        }
+        writer.terminate().unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), content);
+    }
+}
@@ -0,0 +1,393 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use tempfile::TempDir;
+
+// Rows of a batch given to `index_column_batch` when sorted rows are indexed.
+pub const SORTED_BATCH_ROWS: usize = 4096;
+
+// Bookkeeping bytes of a buffered row besides its texts.
+const ROW_OVERHEAD_BYTES: u64 = 64;
+
+// Texts of all columns of a row, `lengths[i]` bytes of `texts` belong to column i.
+struct SortRow {
+    row_id: u64,
+    texts: Vec<u8>,
+    lengths: Vec<u32>,
+}
+
+impl SortRow {
+    fn memory_bytes(&self) -> u64 {
+        self.texts.len() as u64 + self.lengths.len() as u64 * 4 + ROW_OVERHEAD_BYTES
+    }
+
+    // Run file layout: row_id, then length and bytes of each column text, little endian.
+    fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(&self.row_id.to_le_bytes())?;
+        let mut start: usize = 0;
+        for length in self.lengths.iter() {
+            writer.write_all(&length.to_le_bytes())?;
+            writer.write_all(&self.texts[start..start + *length as usize])?;
+            start += *length as usize;
+        }
+        Ok(())
+    }
+
+    // `None` at the end of run file.
+    fn read_from(reader: &mut impl Read, columns: usize) -> io::Result<Option<SortRow>> {
+        let mut row_id_bytes = [0u8; 8];
+        match reader.read_exact(&mut row_id_bytes) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        }
+        let mut texts: Vec<u8> = Vec::new();
+        let mut lengths: Vec<u32> = Vec::with_capacity(columns);
+        for _ in 0..columns {
+            let mut length_bytes = [0u8; 4];
+            reader.read_exact(&mut length_bytes)?;
+            let length: u32 = u32::from_le_bytes(length_bytes);
+            let start: usize = texts.len();
+            texts.resize(start + length as usize, 0);
+            reader.read_exact(&mut texts[start..])?;
+            lengths.push(length);
+        }
+        Ok(Some(SortRow {
+            row_id: u64::from_le_bytes(row_id_bytes),
+            texts,
+            lengths,
+        }))
+    }
+}
+
+/// Rows in the layout of `index_column_batch`, the string of column c of row r is
+/// `column_data[column_offsets[c * rows + r]..column_offsets[c * rows + r + 1]]`.
+#[derive(Debug, Default, PartialEq)]
+pub struct RowBatch {
+    pub row_ids: Vec<u64>,
+    pub column_data: Vec<u8>,
+    pub column_offsets: Vec<u64>,
+}
+
+/// Sorts rows fed in any order by row_id before they are indexed, for hosts feeding rows from
+/// parallel readers into an index which expects increasing row_ids. Rows are buffered until
+/// `memory_limit_bytes`, then sorted and spilled to a run file in a temporary directory under
+/// `spill_directory`. Runs are merged when the build finishes, rows of equal row_id keep the
+/// order they were fed in. Run files are removed when the sorter is dropped.
+pub struct ExternalRowSorter {
+    pub column_names: Vec<String>,
+    memory_limit_bytes: u64,
+    spill_directory: TempDir,
+    rows: Vec<SortRow>,
+    buffered_bytes: u64,
+    runs: Vec<PathBuf>,
+    rows_added: u64,
+}
+
+impl ExternalRowSorter {
+    /// `spill_directory` empty means system temporary directory.
+    pub fn new(
+        column_names: Vec<String>,
+        memory_limit_bytes: u64,
+        spill_directory: &str,
+    ) -> Result<Self, String> {
+        if column_names.is_empty() {
+            return Err("External sort requires at least one column".to_string());
+        }
+        let parent: PathBuf = if spill_directory.is_empty() {
+            std::env::temp_dir()
+        } else {
+            PathBuf::from(spill_directory)
+        };
+        let spill_directory: TempDir = tempfile::Builder::new()
+            .prefix("tantivy_external_sort")
+            .tempdir_in(&parent)
+            .map_err(|e| format!("Failed to create spill directory in {:?}: {}", parent, e))?;
+        Ok(ExternalRowSorter {
+            column_names,
+            memory_limit_bytes,
+            spill_directory,
+            rows: Vec::new(),
+            buffered_bytes: 0,
+            runs: Vec::new(),
+            rows_added: 0,
+        })
+    }
+
+    pub fn rows_added(&self) -> u64 {
+        self.rows_added
+    }
+
+    pub fn spilled_runs(&self) -> usize {
+        self.runs.len()
+    }
+
+    pub fn spill_path(&self) -> &Path {
+        self.spill_directory.path()
+    }
+
+    /// Buffer a batch laid out same as `index_column_batch`, rows are spilled once buffered
+    /// rows exceed memory limit. A batch with invalid layout is rejected as a whole.
+    pub fn add_batch(
+        &mut self,
+        row_ids: &[u64],
+        column_data: &[u8],
+        column_offsets: &[u64],
+    ) -> Result<(), String> {
+        let rows: usize = row_ids.len();
+        let columns: usize = self.column_names.len();
+        if column_offsets.len() != columns * rows + 1
+            || column_offsets.windows(2).any(|w| w[0] > w[1])
+            || column_offsets[column_offsets.len() - 1] > column_data.len() as u64
+        {
+            return Err(format!(
+                "column_offsets should be {} ascending offsets within column_data size {}",
+                columns * rows + 1,
+                column_data.len()
+            ));
+        }
+        for (row, row_id) in row_ids.iter().enumerate() {
+            let mut texts: Vec<u8> = Vec::new();
+            let mut lengths: Vec<u32> = Vec::with_capacity(columns);
+            for column in 0..columns {
+                let offset_idx: usize = column * rows + row;
+                let text: &[u8] = &column_data
+                    [column_offsets[offset_idx] as usize..column_offsets[offset_idx + 1] as usize];
+                texts.extend_from_slice(text);
+                lengths.push(text.len() as u32);
+            }
+            let sort_row = SortRow {
+                row_id: *row_id,
+                texts,
+                lengths,
+            };
+            self.buffered_bytes += sort_row.memory_bytes();
+            self.rows.push(sort_row);
+        }
+        self.rows_added += rows as u64;
+        if self.buffered_bytes >= self.memory_limit_bytes {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    // Sort buffered rows and write them to a new run file.
+    fn spill(&mut self) -> Result<(), String> {
+        if self.rows.is_empty() {
+            return Ok(());
+        }
+        // Stable sort keeps feed order of equal row_ids.
+        self.rows.sort_by_key(|row| row.row_id);
+        let path: PathBuf = self
+            .spill_directory
+            .path()
+            .join(format!("run-{}", self.runs.len()));
+        let write = || -> io::Result<()> {
+            let mut writer = BufWriter::new(File::create(&path)?);
+            for row in self.rows.iter() {
+                row.write_to(&mut writer)?;
+            }
+            writer.flush()
+        };
+        write().map_err(|e| format!("Failed to spill sorted rows to {:?}: {}", path, e))?;
+        self.runs.push(path);
+        self.rows.clear();
+        self.buffered_bytes = 0;
+        Ok(())
+    }
+
+    /// Merge spilled runs and buffered rows into batches of at most `batch_rows` rows in
+    /// row_id order.
+    pub fn into_sorted_batches(mut self, batch_rows: usize) -> Result<SortedRowBatches, String> {
+        self.rows.sort_by_key(|row| row.row_id);
+        let columns: usize = self.column_names.len();
+        // Runs come first, so equal row_ids of earlier runs and buffered rows keep feed order.
+        let mut sources: Vec<RowSource> = Vec::with_capacity(self.runs.len() + 1);
+        for path in self.runs.iter() {
+            let file: File = File::open(path)
+                .map_err(|e| format!("Failed to open sorted run {:?}: {}", path, e))?;
+            sources.push(RowSource::Run(BufReader::new(file)));
+        }
+        sources.push(RowSource::Memory(
+            std::mem::take(&mut self.rows).into_iter(),
+        ));
+
+        let mut batches = SortedRowBatches {
+            columns,
+            batch_rows: batch_rows.max(1),
+            sources,
+            heads: Vec::new(),
+            heap: BinaryHeap::new(),
+            _spill_directory: self.spill_directory,
+        };
+        for source in 0..batches.sources.len() {
+            let head: Option<SortRow> = batches.next_of(source)?;
+            if let Some(row) = &head {
+                batches.heap.push(Reverse((row.row_id, source)));
+            }
+            batches.heads.push(head);
+        }
+        Ok(batches)
+    }
+}
+
+enum RowSource {
+    Run(BufReader<File>),
+    Memory(std::vec::IntoIter<SortRow>),
+}
+
+/// Sorted rows of an `ExternalRowSorter` read batch by batch, run files are kept until it's dropped.
+pub struct SortedRowBatches {
+    columns: usize,
+    batch_rows: usize,
+    sources: Vec<RowSource>,
+    // Next row of each source, the heap orders sources by it.
+    heads: Vec<Option<SortRow>>,
+    heap: BinaryHeap<Reverse<(u64, usize)>>,
+    _spill_directory: TempDir,
+}
+
+impl SortedRowBatches {
+    fn next_of(&mut self, source: usize) -> Result<Option<SortRow>, String> {
+        let columns: usize = self.columns;
+        match &mut self.sources[source] {
+            RowSource::Run(reader) => SortRow::read_from(reader, columns)
+                .map_err(|e| format!("Failed to read sorted run {}: {}", source, e)),
+            RowSource::Memory(rows) => Ok(rows.next()),
+        }
+    }
+
+    /// Next batch in row_id order, `None` once all rows are read.
+    pub fn next_batch(&mut self) -> Result<Option<RowBatch>, String> {
+        let mut rows: Vec<SortRow> = Vec::with_capacity(self.batch_rows);
+        while rows.len() < self.batch_rows {
+            let Some(Reverse((_, source))) = self.heap.pop() else {
+                break;
+            };
+            let next: Option<SortRow> = self.next_of(source)?;
+            if let Some(row) = &next {
+                self.heap.push(Reverse((row.row_id, source)));
+            }
+            if let Some(row) = std::mem::replace(&mut self.heads[source], next) {
+                rows.push(row);
+            }
+        }
+        if rows.is_empty() {
+            return Ok(None);
+        }
+
+        let mut batch = RowBatch {
+            row_ids: rows.iter().map(|row| row.row_id).collect(),
+            column_data: Vec::new(),
+            column_offsets: Vec::with_capacity(self.columns * rows.len() + 1),
+        };
+        batch.column_offsets.push(0);
+        // Start of each row's next column text in its `texts`.
+        let mut starts: Vec<usize> = vec![0; rows.len()];
+        for column in 0..self.columns {
+            for (row, start) in rows.iter().zip(starts.iter_mut()) {
+                let length: usize = row.lengths[column] as usize;
+                batch
+                    .column_data
+                    .extend_from_slice(&row.texts[*start..*start + length]);
+                batch.column_offsets.push(batch.column_data.len() as u64);
+                *start += length;
+            }
+        }
+        Ok(Some(batch))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Two columns of each row are "a{row_id}" and "b{row_id}{suffix}".
+    fn column_batch(row_ids: &[u64], suffix: &str) -> (Vec<u8>, Vec<u64>) {
+        let mut column_data: Vec<u8> = Vec::new();
+        let mut column_offsets: Vec<u64> = vec![0];
+        for column in ["a", "b"] {
+            for row_id in row_ids {
+                let text: String = if column == "a" {
+                    format!("a{}", row_id)
+                } else {
+                    format!("b{}{}", row_id, suffix)
+                };
+                column_data.extend_from_slice(text.as_bytes());
+                column_offsets.push(column_data.len() as u64);
+            }
+        }
+        (column_data, column_offsets)
+    }
+
+    fn collect_rows(mut batches: SortedRowBatches) -> Vec<(u64, String, String)> {
+        let mut rows: Vec<(u64, String, String)> = Vec::new();
+        while let Some(batch) = batches.next_batch().unwrap() {
+            let count: usize = batch.row_ids.len();
+            assert!(count <= 3);
+            let text = |idx: usize| {
+                String::from_utf8(
+                    batch.column_data[batch.column_offsets[idx] as usize
+                        ..batch.column_offsets[idx + 1] as usize]
+                        .to_vec(),
+                )
+                .unwrap()
+            };
+            for row in 0..count {
+                rows.push((batch.row_ids[row], text(row), text(count + row)));
+            }
+        }
+        rows
+    }
+
+    #[test]
+    fn test_external_row_sorter() {
+        let column_names: Vec<String> = vec!["a".to_string(), "b".to_string()];
+        let temp_directory = TempDir::new().unwrap();
+        let spill_directory: &str = temp_directory.path().to_str().unwrap();
+
+        // Every batch is spilled with 1 byte memory limit.
+        let mut sorter = ExternalRowSorter::new(column_names.clone(), 1, spill_directory).unwrap();
+        let spill_path: PathBuf = sorter.spill_path().to_path_buf();
+        for (row_ids, suffix) in [
+            (vec![7u64, 2, 9], ""),
+            (vec![5, 2], "-dup"),
+            (vec![1, 8, 3, 6, 4], ""),
+        ] {
+            let (column_data, column_offsets) = column_batch(&row_ids, suffix);
+            sorter
+                .add_batch(&row_ids, &column_data, &column_offsets)
+                .unwrap();
+        }
+        assert!(sorter.add_batch(&[10], b"a10", &[0, 3]).is_err());
+        assert_eq!(sorter.rows_added(), 10);
+        assert_eq!(sorter.spilled_runs(), 3);
+
+        let rows = collect_rows(sorter.into_sorted_batches(3).unwrap());
+        let row_ids: Vec<u64> = rows.iter().map(|row| row.0).collect();
+        assert_eq!(row_ids, vec![1, 2, 2, 3, 4, 5, 6, 7, 8, 9]);
+        // Equal row_ids keep feed order, texts follow their rows.
+        assert_eq!(rows[1], (2, "a2".to_string(), "b2".to_string()));
+        assert_eq!(rows[2], (2, "a2".to_string(), "b2-dup".to_string()));
+        assert_eq!(rows[9], (9, "a9".to_string(), "b9".to_string()));
+        // Run files are removed with the sorter.
+        assert!(!spill_path.exists());
+
+        // Rows within memory limit are never spilled.
+        let mut sorter =
+            ExternalRowSorter::new(column_names, 1024 * 1024, spill_directory).unwrap();
+        let (column_data, column_offsets) = column_batch(&[3, 1, 2], "");
+        sorter
+            .add_batch(&[3, 1, 2], &column_data, &column_offsets)
+            .unwrap();
+        assert_eq!(sorter.spilled_runs(), 0);
+        let rows = collect_rows(sorter.into_sorted_batches(3).unwrap());
+        assert_eq!(
+            rows.iter().map(|row| row.0).collect::<Vec<u64>>(),
+            vec![1, 2, 3]
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use crate::tokenizer::language_routing::{detect_language, language_field_name};
+use crate::tokenizer::vo::language::SupportRoutingLanguage;
+use crate::tokenizer::vo::tokenizers_vo::TokenizerConfig;
+use std::collections::HashMap;
+use tantivy::schema::{Field, Schema};
+
+// Language sub-fields of a routed column.
+#[derive(Debug, Clone)]
+struct LanguageRoute {
+    languages: Vec<(SupportRoutingLanguage, Field)>,
+    min_confidence: f64,
+}
+
+/// Routes text of columns configured by `language_routing` to their language sub-fields.
+///
+/// Text is always indexed in its column, so stored values and queries of languages not
+/// routed keep working, text of a detected language is also indexed in the sub-field.
+#[derive(Debug, Clone)]
+pub struct LanguageRouter {
+    routes: HashMap<Field, LanguageRoute>,
+}
+
+impl LanguageRouter {
+    /// Resolve language sub-fields in `schema`, `None` if no column in schema is routed.
+    pub fn new(
+        schema: &Schema,
+        col_tokenizer_map: &HashMap<String, TokenizerConfig>,
+    ) -> Result<Option<Self>, String> {
+        let mut routes: HashMap<Field, LanguageRoute> = HashMap::new();
+        for (column_name, tokenizer_config) in col_tokenizer_map {
+            let language_routing = match &tokenizer_config.language_routing {
+                Some(language_routing) => language_routing,
+                None => continue,
+            };
+            // Column configured but not created by host.
+            let field = match schema.get_field(column_name) {
+                Ok(field) => field,
+                Err(_) => continue,
+            };
+            let mut languages: Vec<(SupportRoutingLanguage, Field)> = Vec::new();
+            for (language, _) in &language_routing.languages {
+                let subfield_name = language_field_name(column_name, *language);
+                let subfield = schema.get_field(&subfield_name).map_err(|e| {
+                    format!("Language sub-field `{}` not exists: {}", subfield_name, e)
+                })?;
+                languages.push((*language, subfield));
+            }
+            routes.insert(
+                field,
+                LanguageRoute {
+                    languages,
+                    min_confidence: language_routing.min_confidence,
+                },
+            );
+        }
+        match routes.is_empty() {
+            true => Ok(None),
+            false => Ok(Some(LanguageRouter { routes })),
+        }
+    }
+
+    /// Language sub-field `text` of `field` should also be indexed in, `None` if not routed.
+    pub fn language_field(&self, field: Field, text: &str) -> Option<Field> {
+        let route = self.routes.get(&field)?;
+        let languages: Vec<SupportRoutingLanguage> = route
+            .languages
+            .iter()
+            .map(|(language, _)| *language)
+            .collect();
+        let language = detect_language(text, &languages, route.min_confidence)?;
+        route
+            .languages
+            .iter()
+            .find(|(routed, _)| *routed == language)
+            .map(|(_, subfield)| *subfield)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::tokenizer_utils::TokenizerUtils;
+    use tantivy::schema::TEXT;
+
+    #[test]
+    fn test_language_router() {
+        let col_tokenizer_map = TokenizerUtils::parse_tokenizer_json_to_config_map(
+            r#"{"text": {"tokenizer": {"type": "default"}, "language_routing": {"languages": ["en", "zh"]}}}"#,
+        )
+        .unwrap();
+        let mut schema_builder = Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let text_en_field = schema_builder.add_text_field("text_en", TEXT);
+        let text_zh_field = schema_builder.add_text_field("text_zh", TEXT);
+        let other_field = schema_builder.add_text_field("other", TEXT);
+        let schema = schema_builder.build();
+
+        let router = LanguageRouter::new(&schema, &col_tokenizer_map)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            router.language_field(
+                text_field,
+                "Strategic military campaigns alter the balance of power."
+            ),
+            Some(text_en_field)
+        );
+        assert_eq!(
+            router.language_field(text_field, "战略军事行动改变了力量的平衡。"),
+            Some(text_zh_field)
+        );
+        assert_eq!(
+            router.language_field(
+                other_field,
+                "Strategic military campaigns alter the balance of power."
+            ),
+            None
+        );
+
+        // Sub-fields should be declared in schema.
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("text", TEXT);
+        assert!(LanguageRouter::new(&schema_builder.build(), &col_tokenizer_map).is_err());
+    }
+}
@@ -0,0 +1,375 @@
+use super::index_writer_merge_policy::host_merge_candidates;
+use crate::common::callback_cell::CallbackCell;
+use crate::common::constants::{
+    MergeCallback, DISK_SPACE_GUARD, MERGE_CALLBACK, MERGE_POLICY_CALLBACK, MERGE_THROTTLE,
+};
+use crate::common::heartbeat::Heartbeat;
+use crate::common::thread_affinity::{spawn_thread, ThreadKind};
+use crate::logger::logger_bridge::TantivySearchLogger;
+use crate::FFI_INDEX_WRITER_CACHE;
+use crate::{common::constants::LOG_CALLBACK, DEBUG, WARNING};
+use std::collections::HashSet;
+use std::ffi::CString;
+use std::fmt;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tantivy::merge_policy::{MergeCandidate, MergePolicy};
+use tantivy::{Index, SegmentId, SegmentMeta};
+
+/// Merge events reported to host merge callback.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeEvent {
+    Start = 0,
+    Finish = 1,
+    Failed = 2,
+}
+
+/// Holds the host merge callback.
+pub type MergeCallbackCell = CallbackCell<MergeCallback>;
+
+impl MergeCallbackCell {
+    // Invoke callback with merge event, `index_path` containing nul byte is passed as empty string.
+    pub fn notify(&self, index_path: &str, event: MergeEvent, segment_count: u64, num_docs: u64) {
+        if let Some(callback) = self.get() {
+            let index_path = CString::new(index_path).unwrap_or_default();
+            callback(index_path.as_ptr(), event as i32, segment_count, num_docs);
+        }
+    }
+}
+
+struct MergeThrottleState {
+    bytes_per_sec: u64,
+    // Negative means merges started recently read more than budget, later merges wait it paid back.
+    available_bytes: f64,
+    last_refill: Instant,
+}
+
+/// Process wide merge I/O budget in MB/s shared by all index writers, 0 means unlimited.
+/// Tantivy doesn't expose merge I/O, so the budget is applied when merges start: a merge
+/// takes bytes of its input segments, merges started over budget are deferred, so average
+/// merge throughput stays within budget while a single merge still runs at full speed.
+/// Background merges over budget are deferred and retried once budget is paid back, explicit
+/// merges (`IndexWriterBridge::merge_segments`) wait for budget with `acquire`.
+pub struct MergeThrottle {
+    state: Mutex<MergeThrottleState>,
+}
+
+impl MergeThrottle {
+    pub fn new() -> Self {
+        MergeThrottle {
+            state: Mutex::new(MergeThrottleState {
+                bytes_per_sec: 0,
+                available_bytes: 0.0,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    pub fn max_mb_per_sec(&self) -> u64 {
+        match self.state.lock() {
+            Ok(state) => state.bytes_per_sec / (1024 * 1024),
+            Err(_) => 0,
+        }
+    }
+
+    pub fn set_max_mb_per_sec(&self, max_mb_per_sec: u64) -> Result<(), String> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        state.bytes_per_sec = max_mb_per_sec.saturating_mul(1024 * 1024);
+        // Allow one second burst after budget changed.
+        state.available_bytes = state.bytes_per_sec as f64;
+        state.last_refill = Instant::now();
+        Ok(())
+    }
+
+    // Take `bytes` from budget at `now`, return how long to wait if budget is in debt.
+    fn try_acquire_at(&self, bytes: u64, now: Instant) -> Result<(), Duration> {
+        let mut state = match self.state.lock() {
+            Ok(state) => state,
+            Err(_) => return Ok(()),
+        };
+        if state.bytes_per_sec == 0 {
+            return Ok(());
+        }
+        let rate = state.bytes_per_sec as f64;
+        let elapsed = now
+            .saturating_duration_since(state.last_refill)
+            .as_secs_f64();
+        state.available_bytes = (state.available_bytes + elapsed * rate).min(rate);
+        state.last_refill = now;
+        if state.available_bytes < 0.0 {
+            return Err(Duration::from_secs_f64(-state.available_bytes / rate));
+        }
+        state.available_bytes -= bytes as f64;
+        Ok(())
+    }
+
+    // Take `bytes` from budget, return how long merge should be deferred if budget is in debt.
+    pub fn try_acquire(&self, bytes: u64) -> Result<(), Duration> {
+        self.try_acquire_at(bytes, Instant::now())
+    }
+
+    // Take `bytes` from budget, wait until budget isn't in debt.
+    pub fn acquire(&self, bytes: u64) {
+        while let Err(wait) = self.try_acquire_at(bytes, Instant::now()) {
+            thread::sleep(wait);
+        }
+    }
+}
+
+impl Default for MergeThrottle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Bytes of existing component files of a segment.
+pub fn segment_bytes(index_path: &str, segment_meta: &SegmentMeta) -> u64 {
+    segment_meta
+        .list_files()
+        .iter()
+        .filter_map(|file| std::fs::metadata(Path::new(index_path).join(file)).ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+/// Background merges of committed segments observed by `ObservedMergePolicy`.
+/// Heartbeat beats when a merge starts or ends, merges running long after it are wedged.
+#[derive(Debug, Default)]
+pub struct MergeProgress {
+    pub running_merges: AtomicU64,
+    pub heartbeat: Heartbeat,
+    // Input segments of background merges deferred by `MERGE_THROTTLE`.
+    pub deferred_merges: Mutex<Vec<Vec<SegmentId>>>,
+    // Whether a retry of deferred merges is scheduled.
+    retry_scheduled: AtomicBool,
+}
+
+/// Merge policy reporting background merges to host merge callback and applying `MERGE_THROTTLE`.
+/// Merges are proposed by `inner` policy, or by host merge policy callback if it's installed.
+///
+/// Merges are reported when they are handed over to tantivy. Tantivy doesn't report merge
+/// completion, a merge of committed segments is reported finished once its input segments are
+/// gone from committed segments, which is checked every time merge candidates are computed,
+/// including right after a merge ended. Merges of uncommitted segments only report start.
+/// Merges deferred by disk space are reconsidered on next segment update, e.g. next commit.
+/// Merges of committed segments deferred by throttle are also retried once budget is paid
+/// back, so an index which stops receiving commits still merges them.
+pub struct ObservedMergePolicy {
+    index_path: String,
+    index: Index,
+    inner: Box<dyn MergePolicy>,
+    // Running merges of committed segments: (input segment ids, num_docs).
+    running_merges: Mutex<Vec<(Vec<SegmentId>, u64)>>,
+    progress: Arc<MergeProgress>,
+}
+
+impl ObservedMergePolicy {
+    pub fn new(index_path: String, index: Index, inner: Box<dyn MergePolicy>) -> Self {
+        ObservedMergePolicy {
+            index_path,
+            index,
+            inner,
+            running_merges: Mutex::new(Vec::new()),
+            progress: Arc::new(MergeProgress::default()),
+        }
+    }
+
+    pub fn progress(&self) -> Arc<MergeProgress> {
+        self.progress.clone()
+    }
+
+    // Report running merges whose input segments are gone, a merge sharing inputs with
+    // `scheduled` has failed, otherwise its segments wouldn't be merged again.
+    fn report_ended(&self, committed: &HashSet<SegmentId>, scheduled: &[SegmentId]) {
+        let mut running_merges = match self.running_merges.lock() {
+            Ok(running_merges) => running_merges,
+            Err(_) => return,
+        };
+        running_merges.retain(|(segment_ids, num_docs)| {
+            let event = if segment_ids.iter().all(|id| !committed.contains(id)) {
+                MergeEvent::Finish
+            } else if segment_ids.iter().any(|id| scheduled.contains(id)) {
+                MergeEvent::Failed
+            } else {
+                return true;
+            };
+            MERGE_CALLBACK.notify(&self.index_path, event, segment_ids.len() as u64, *num_docs);
+            self.progress.heartbeat.beat();
+            false
+        });
+        self.progress
+            .running_merges
+            .store(running_merges.len() as u64, Ordering::Relaxed);
+    }
+
+    // Keep merges deferred by throttle, replacing ones of `segments` which were reconsidered.
+    // A retry is scheduled after `wait` unless one is already scheduled.
+    fn defer_merges(
+        &self,
+        segments: &[SegmentMeta],
+        deferred: Vec<Vec<SegmentId>>,
+        wait: Duration,
+    ) {
+        if let Ok(mut deferred_merges) = self.progress.deferred_merges.lock() {
+            deferred_merges.retain(|segment_ids| {
+                !segments
+                    .iter()
+                    .any(|segment_meta| segment_ids.contains(&segment_meta.id()))
+            });
+            deferred_merges.extend(deferred);
+            if deferred_merges.is_empty() {
+                return;
+            }
+        }
+        if self.progress.retry_scheduled.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        // Writer is looked up by path when retrying, the thread doesn't keep it alive.
+        let index_path: String = self.index_path.clone();
+        let progress = Arc::downgrade(&self.progress);
+        let spawned = spawn_thread(ThreadKind::Merge, "merge-retry", move || {
+            thread::sleep(wait);
+            match progress.upgrade() {
+                Some(progress) => progress.retry_scheduled.store(false, Ordering::Release),
+                None => return,
+            }
+            if let Ok(bridge) = FFI_INDEX_WRITER_CACHE.get_index_writer_bridge(index_path.clone()) {
+                match bridge.merge_deferred() {
+                    Ok(merged) => {
+                        DEBUG!(function: "ObservedMergePolicy", "Retried {} deferred merges, index_path:[{}]", merged, index_path)
+                    }
+                    Err(e) => {
+                        WARNING!(function: "ObservedMergePolicy", "Failed to retry deferred merges, index_path:[{}], {}", index_path, e)
+                    }
+                }
+            }
+        });
+        if let Err(e) = spawned {
+            self.progress
+                .retry_scheduled
+                .store(false, Ordering::Release);
+            WARNING!(function: "ObservedMergePolicy", "Failed to schedule deferred merges, index_path:[{}], {}", self.index_path, e);
+        }
+    }
+}
+
+impl fmt::Debug for ObservedMergePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "ObservedMergePolicy(index_path:{}, inner:{:?})",
+            self.index_path, self.inner
+        )
+    }
+}
+
+impl MergePolicy for ObservedMergePolicy {
+    fn compute_merge_candidates(&self, segments: &[SegmentMeta]) -> Vec<MergeCandidate> {
+        let committed: HashSet<SegmentId> = self
+            .index
+            .searchable_segment_ids()
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        self.report_ended(&committed, &[]);
+
+        // Host merge policy takes over builtin one once it's installed.
+        let proposed: Vec<MergeCandidate> = match MERGE_POLICY_CALLBACK.get() {
+            Some(callback) => host_merge_candidates(callback, &self.index_path, segments),
+            None => self.inner.compute_merge_candidates(segments),
+        };
+        let mut candidates: Vec<MergeCandidate> = Vec::new();
+        let mut deferred: Vec<Vec<SegmentId>> = Vec::new();
+        let mut retry_wait: Option<Duration> = None;
+        for candidate in proposed {
+            let segment_metas: Vec<&SegmentMeta> = segments
+                .iter()
+                .filter(|segment_meta| candidate.0.contains(&segment_meta.id()))
+                .collect();
+            let bytes: u64 = segment_metas
+                .iter()
+                .map(|segment_meta| segment_bytes(&self.index_path, segment_meta))
+                .sum();
+            if let Err(e) = DISK_SPACE_GUARD.check(&self.index_path, bytes) {
+                WARNING!(function: "ObservedMergePolicy", "Merge of {} segments deferred, {}", candidate.0.len(), e);
+                continue;
+            }
+            if let Err(wait) = MERGE_THROTTLE.try_acquire(bytes) {
+                DEBUG!(function: "ObservedMergePolicy", "Merge of {} segments deferred by throttle for {:?}, index_path:[{}]", candidate.0.len(), wait, self.index_path);
+                // Merges of uncommitted segments are reconsidered on commit.
+                if candidate.0.iter().all(|id| committed.contains(id)) {
+                    deferred.push(candidate.0);
+                    retry_wait = Some(wait);
+                }
+                continue;
+            }
+            let num_docs: u64 = segment_metas
+                .iter()
+                .map(|segment_meta| segment_meta.num_docs() as u64)
+                .sum();
+            self.report_ended(&committed, &candidate.0);
+            MERGE_CALLBACK.notify(
+                &self.index_path,
+                MergeEvent::Start,
+                candidate.0.len() as u64,
+                num_docs,
+            );
+            if candidate.0.iter().all(|id| committed.contains(id)) {
+                if let Ok(mut running_merges) = self.running_merges.lock() {
+                    running_merges.push((candidate.0.clone(), num_docs));
+                    self.progress.heartbeat.beat();
+                    self.progress
+                        .running_merges
+                        .store(running_merges.len() as u64, Ordering::Relaxed);
+                }
+            }
+            candidates.push(candidate);
+        }
+        self.defer_merges(segments, deferred, retry_wait.unwrap_or_default());
+        candidates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_throttle() {
+        let throttle = MergeThrottle::new();
+        // Unlimited by default.
+        assert!(throttle.try_acquire(u64::MAX).is_ok());
+
+        assert!(throttle.set_max_mb_per_sec(1).is_ok());
+        assert_eq!(throttle.max_mb_per_sec(), 1);
+        let start = Instant::now();
+        // One second burst is allowed, a merge larger than budget still starts.
+        assert!(throttle.try_acquire_at(3 * 1024 * 1024, start).is_ok());
+        // Budget is in debt for 2 seconds.
+        let wait = throttle.try_acquire_at(1, start).unwrap_err();
+        assert_eq!(wait.as_secs(), 2);
+        assert!(throttle
+            .try_acquire_at(1, start + Duration::from_secs(1))
+            .is_err());
+        assert!(throttle
+            .try_acquire_at(1, start + Duration::from_secs(2))
+            .is_ok());
+
+        // Explicit merges wait until budget isn't in debt.
+        assert!(throttle.set_max_mb_per_sec(10).is_ok());
+        assert!(throttle.try_acquire(12 * 1024 * 1024).is_ok());
+        let start = Instant::now();
+        throttle.acquire(1);
+        assert!(start.elapsed() >= Duration::from_millis(150));
+
+        assert!(throttle.set_max_mb_per_sec(0).is_ok());
+        assert!(throttle.try_acquire(u64::MAX).is_ok());
+    }
+}
@@ -0,0 +1,137 @@
+use super::index_writer_merge_events::segment_bytes;
+use crate::common::callback_cell::CallbackCell;
+use crate::common::constants::MergePolicyCallback;
+use crate::logger::logger_bridge::TantivySearchLogger;
+use crate::{common::constants::LOG_CALLBACK, DEBUG};
+use libc::c_char;
+use std::collections::BTreeMap;
+use std::ffi::CString;
+use tantivy::merge_policy::MergeCandidate;
+use tantivy::SegmentMeta;
+
+/// Mergeable segment passed to host merge policy callback.
+/// `segment_id` is the uuid hex string of segment, it's only valid during the callback.
+#[repr(C)]
+pub struct MergeSegmentInfo {
+    pub segment_id: *const c_char,
+    pub num_docs: u64,
+    pub num_deleted_docs: u64,
+    pub bytes: u64,
+}
+
+/// Holds the host merge policy callback.
+/// When it's installed, background merges of all index writers are decided by host.
+pub type MergePolicyCallbackCell = CallbackCell<MergePolicyCallback>;
+
+/// Ask host `callback` which of `segments` should be merged.
+/// Host writes a merge group into `merge_groups[i]` for `segments[i]`, segments sharing a
+/// non-negative group are merged together, negative group means segment isn't merged.
+/// Groups with less than two segments are ignored.
+pub fn host_merge_candidates(
+    callback: MergePolicyCallback,
+    index_path: &str,
+    segments: &[SegmentMeta],
+) -> Vec<MergeCandidate> {
+    if segments.len() < 2 {
+        return Vec::new();
+    }
+    let segment_ids: Vec<CString> = segments
+        .iter()
+        .map(|segment_meta| CString::new(segment_meta.id().uuid_string()).unwrap_or_default())
+        .collect();
+    let segment_infos: Vec<MergeSegmentInfo> = segments
+        .iter()
+        .zip(segment_ids.iter())
+        .map(|(segment_meta, segment_id)| MergeSegmentInfo {
+            segment_id: segment_id.as_ptr(),
+            num_docs: segment_meta.num_docs() as u64,
+            num_deleted_docs: segment_meta.num_deleted_docs() as u64,
+            bytes: segment_bytes(index_path, segment_meta),
+        })
+        .collect();
+    let mut merge_groups: Vec<i64> = vec![-1; segments.len()];
+    let index_path_cstr = CString::new(index_path).unwrap_or_default();
+    callback(
+        index_path_cstr.as_ptr(),
+        segment_infos.as_ptr(),
+        segment_infos.len(),
+        merge_groups.as_mut_ptr(),
+    );
+
+    let mut groups: BTreeMap<i64, MergeCandidate> = BTreeMap::new();
+    for (segment_meta, merge_group) in segments.iter().zip(merge_groups) {
+        if merge_group >= 0 {
+            groups
+                .entry(merge_group)
+                .or_insert_with(|| MergeCandidate(Vec::new()))
+                .0
+                .push(segment_meta.id());
+        }
+    }
+    groups
+        .into_values()
+        .filter(|candidate| {
+            if candidate.0.len() < 2 {
+                DEBUG!(function: "host_merge_candidates", "Merge group with single segment is ignored, index_path:[{}]", index_path);
+                return false;
+            }
+            true
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+    use tantivy::schema::{Schema, TEXT};
+    use tantivy::{Index, SegmentId};
+
+    // Merge segments with docs less than 100 into group 0, others into their own group.
+    extern "C" fn merge_small_segments(
+        index_path: *const c_char,
+        segments: *const MergeSegmentInfo,
+        segment_count: usize,
+        merge_groups: *mut i64,
+    ) {
+        assert_eq!(
+            unsafe { CStr::from_ptr(index_path) }.to_str().unwrap(),
+            "/data/index"
+        );
+        let segments = unsafe { std::slice::from_raw_parts(segments, segment_count) };
+        let merge_groups = unsafe { std::slice::from_raw_parts_mut(merge_groups, segment_count) };
+        for (i, segment) in segments.iter().enumerate() {
+            assert_eq!(
+                unsafe { CStr::from_ptr(segment.segment_id) }
+                    .to_str()
+                    .unwrap()
+                    .len(),
+                32
+            );
+            merge_groups[i] = if segment.num_docs < 100 { 0 } else { i as i64 };
+        }
+    }
+
+    #[test]
+    fn test_host_merge_candidates() {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let segments: Vec<SegmentMeta> = [10u32, 20, 500, 30]
+            .iter()
+            .map(|max_doc| index.new_segment_meta(SegmentId::generate_random(), *max_doc))
+            .collect();
+
+        let candidates = host_merge_candidates(merge_small_segments, "/data/index", &segments);
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(
+            candidates[0].0,
+            vec![segments[0].id(), segments[1].id(), segments[3].id()]
+        );
+
+        // Single segment can't be merged.
+        assert!(
+            host_merge_candidates(merge_small_segments, "/data/index", &segments[..1]).is_empty()
+        );
+    }
+}
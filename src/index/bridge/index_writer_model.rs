@@ -0,0 +1,181 @@
+use super::index_writer_bridge::IndexWriterBridge;
+use crate::search::collector::row_id_bitmap_collector::RowIdRoaringCollector;
+use std::collections::BTreeSet;
+use tantivy::merge_policy::LogMergePolicy;
+use tantivy::query::AllQuery;
+use tantivy::schema::{Field, Schema, FAST, INDEXED, TEXT};
+use tantivy::{Index, IndexReader, ReloadPolicy, TantivyDocument, Term};
+use tempfile::TempDir;
+
+/// Operation applied on both `IndexWriterBridge` and `IndexWriterModel`.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriterOp {
+    Add(u64),
+    DeleteTerm(u64),
+    Commit,
+    Reload,
+}
+
+/// Expected row_ids of a writer bridge searched by a reader with `ReloadPolicy::Manual`.
+///
+/// - A delete removes all docs of the row_id added before it, docs added after it are kept.
+/// - Commit publishes pending row_ids, reload makes the last commit searchable.
+#[derive(Debug, Default, Clone)]
+pub struct IndexWriterModel {
+    pending: BTreeSet<u64>,
+    committed: BTreeSet<u64>,
+    searchable: BTreeSet<u64>,
+}
+
+#[allow(dead_code)]
+impl IndexWriterModel {
+    pub fn apply(&mut self, op: WriterOp) {
+        match op {
+            WriterOp::Add(row_id) => {
+                self.pending.insert(row_id);
+            }
+            WriterOp::DeleteTerm(row_id) => {
+                self.pending.remove(&row_id);
+            }
+            WriterOp::Commit => self.committed = self.pending.clone(),
+            WriterOp::Reload => self.searchable = self.committed.clone(),
+        }
+    }
+
+    pub fn searchable_row_ids(&self) -> Vec<u64> {
+        self.searchable.iter().cloned().collect()
+    }
+}
+
+/// Writer bridge on a temp directory index and a manual reload reader.
+#[allow(dead_code)]
+pub struct IndexWriterHarness {
+    pub bridge: IndexWriterBridge,
+    pub reader: IndexReader,
+    row_id_field: Field,
+    text_field: Field,
+    // Index directory is removed when harness dropped.
+    _directory: TempDir,
+}
+
+#[allow(dead_code)]
+impl IndexWriterHarness {
+    pub fn new() -> Result<Self, String> {
+        let mut schema_builder = Schema::builder();
+        let row_id_field = schema_builder.add_u64_field("row_id", FAST | INDEXED);
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let directory = TempDir::new().map_err(|e| e.to_string())?;
+        let index_path = directory.path().to_string_lossy().to_string();
+        let index =
+            Index::create_in_dir(&index_path, schema_builder.build()).map_err(|e| e.to_string())?;
+        let writer = index
+            .writer_with_num_threads(1, 1024 * 1024 * 64)
+            .map_err(|e| e.to_string())?;
+        writer.set_merge_policy(Box::new(LogMergePolicy::default()));
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()
+            .map_err(|e: tantivy::TantivyError| e.to_string())?;
+        let bridge = IndexWriterBridge::new(index_path, index, writer);
+        Ok(IndexWriterHarness {
+            bridge,
+            reader,
+            row_id_field,
+            text_field,
+            _directory: directory,
+        })
+    }
+
+    pub fn apply(&self, op: WriterOp) -> Result<(), String> {
+        match op {
+            WriterOp::Add(row_id) => {
+                let mut doc = TantivyDocument::default();
+                doc.add_u64(self.row_id_field, row_id);
+                doc.add_text(self.text_field, format!("row {}", row_id));
+                self.bridge.add_document(doc).map(|_| ())
+            }
+            WriterOp::DeleteTerm(row_id) => self
+                .bridge
+                .delete_term(Term::from_field_u64(self.row_id_field, row_id))
+                .map(|_| ()),
+            WriterOp::Commit => self.bridge.commit().map(|_| ()),
+            WriterOp::Reload => self.reader.reload().map_err(|e| e.to_string()),
+        }
+    }
+
+    // Distinct row_ids searchable by current reader generation.
+    pub fn searchable_row_ids(&self) -> Result<Vec<u64>, String> {
+        let row_id_collector = RowIdRoaringCollector::with_field("row_id".to_string());
+        let bitmap = self
+            .reader
+            .searcher()
+            .search(&AllQuery, &row_id_collector)
+            .map_err(|e| e.to_string())?;
+        Ok(bitmap.iter().map(|row_id| row_id as u64).collect())
+    }
+
+    /// Apply `ops` on bridge and model, return the first op after which they disagree.
+    pub fn check_against_model(&self, ops: &[WriterOp]) -> Result<(), String> {
+        let mut model = IndexWriterModel::default();
+        for (step, op) in ops.iter().enumerate() {
+            self.apply(*op)?;
+            model.apply(*op);
+            let searchable = self.searchable_row_ids()?;
+            if searchable != model.searchable_row_ids() {
+                return Err(format!(
+                    "Step {} {:?}: searchable {:?}, expected {:?}",
+                    step,
+                    op,
+                    searchable,
+                    model.searchable_row_ids()
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn writer_op() -> impl Strategy<Value = WriterOp> {
+        // Few row_ids, so adds and deletes hit the same rows often.
+        prop_oneof![
+            4 => (0..16u64).prop_map(WriterOp::Add),
+            2 => (0..16u64).prop_map(WriterOp::DeleteTerm),
+            1 => Just(WriterOp::Commit),
+            1 => Just(WriterOp::Reload),
+        ]
+    }
+
+    #[test]
+    fn test_delete_before_add_keeps_new_doc() {
+        let harness = IndexWriterHarness::new().unwrap();
+        let ops = [
+            WriterOp::Add(1),
+            WriterOp::Add(2),
+            WriterOp::DeleteTerm(1),
+            WriterOp::Add(1),
+            WriterOp::DeleteTerm(2),
+            WriterOp::Commit,
+            WriterOp::Reload,
+        ];
+        assert!(harness.check_against_model(&ops).is_ok());
+        assert_eq!(harness.searchable_row_ids().unwrap(), vec![1]);
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn test_writer_ops_match_model(ops in prop::collection::vec(writer_op(), 1..48)) {
+            let harness = IndexWriterHarness::new().unwrap();
+            let result = harness.check_against_model(&ops);
+            prop_assert!(result.is_ok(), "{}", result.unwrap_err());
+        }
+    }
+}
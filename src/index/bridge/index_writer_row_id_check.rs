@@ -0,0 +1,168 @@
+use roaring::RoaringTreemap;
+use tantivy::Index;
+
+/// How row_ids of ingested documents are verified.
+/// - `Disabled`: no verification, it's the default.
+/// - `Increasing`: row_ids should arrive strictly increasing.
+/// - `Unique`: row_ids can arrive in any order, but never twice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RowIdCheckMode {
+    #[default]
+    Disabled,
+    Increasing,
+    Unique,
+}
+
+impl RowIdCheckMode {
+    pub fn from_name(name: &str) -> Option<RowIdCheckMode> {
+        match name.to_lowercase().as_str() {
+            "disabled" | "" => Some(RowIdCheckMode::Disabled),
+            "increasing" => Some(RowIdCheckMode::Increasing),
+            "unique" => Some(RowIdCheckMode::Unique),
+            _ => None,
+        }
+    }
+}
+
+/// Track row_ids seen by an index writer, duplicated row_ids break bitmap semantics of host.
+/// Violations are rejected when `reject` is true, otherwise they are only counted.
+#[derive(Debug, Default)]
+pub struct RowIdChecker {
+    pub mode: RowIdCheckMode,
+    pub reject: bool,
+    pub max_row_id: Option<u64>,
+    pub seen_row_ids: RoaringTreemap,
+    pub violations: u64,
+}
+
+impl RowIdChecker {
+    /// Create checker seeded with row_ids already committed in `index`.
+    pub fn seeded(index: &Index, mode: RowIdCheckMode, reject: bool) -> Result<Self, String> {
+        let mut checker = RowIdChecker {
+            mode,
+            reject,
+            ..Default::default()
+        };
+        if mode == RowIdCheckMode::Disabled {
+            return Ok(checker);
+        }
+        let searcher = index
+            .reader()
+            .map_err(|e| format!("Failed to open reader for row_id check: {}", e))?
+            .searcher();
+        for segment_reader in searcher.segment_readers() {
+            let row_id_column = segment_reader
+                .fast_fields()
+                .u64("row_id")
+                .map_err(|e| format!("Failed to open row_id fast field: {}", e))?;
+            for doc_id in segment_reader.doc_ids_alive() {
+                if let Some(row_id) = row_id_column.first(doc_id) {
+                    checker.record(row_id);
+                }
+            }
+        }
+        Ok(checker)
+    }
+
+    fn record(&mut self, row_id: u64) {
+        self.max_row_id = Some(self.max_row_id.map_or(row_id, |max| max.max(row_id)));
+        if self.mode == RowIdCheckMode::Unique {
+            self.seen_row_ids.insert(row_id);
+        }
+    }
+
+    /// Deleted row_ids can be added again in `Unique` mode.
+    pub fn forget(&mut self, row_ids: &[u64]) {
+        for row_id in row_ids.iter() {
+            self.seen_row_ids.remove(*row_id);
+        }
+    }
+
+    fn violation(&self, row_id: u64, batch: &RoaringTreemap, last: Option<u64>) -> Option<String> {
+        match self.mode {
+            RowIdCheckMode::Disabled => None,
+            RowIdCheckMode::Increasing => match last.or(self.max_row_id) {
+                Some(max) if row_id <= max => Some(format!(
+                    "row_id {} isn't greater than max seen row_id {}",
+                    row_id, max
+                )),
+                _ => None,
+            },
+            RowIdCheckMode::Unique => {
+                if self.seen_row_ids.contains(row_id) || batch.contains(row_id) {
+                    Some(format!("row_id {} is duplicated", row_id))
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// Verify a batch of row_ids, returns violation messages.
+    /// A rejected batch is not recorded, so the caller can retry with fixed row_ids.
+    pub fn check(&mut self, row_ids: &[u64]) -> Result<Vec<String>, String> {
+        if self.mode == RowIdCheckMode::Disabled {
+            return Ok(vec![]);
+        }
+        let mut violations: Vec<String> = vec![];
+        let mut batch = RoaringTreemap::new();
+        let mut last: Option<u64> = None;
+        for row_id in row_ids.iter() {
+            if let Some(violation) = self.violation(*row_id, &batch, last) {
+                if self.reject {
+                    self.violations += 1;
+                    return Err(violation);
+                }
+                violations.push(violation);
+            }
+            batch.insert(*row_id);
+            last = Some(
+                last.map_or(*row_id, |last| last.max(*row_id))
+                    .max(self.max_row_id.unwrap_or(0)),
+            );
+        }
+        self.violations += violations.len() as u64;
+        for row_id in row_ids.iter() {
+            self.record(*row_id);
+        }
+        Ok(violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_row_id_checker() {
+        let mut checker = RowIdChecker {
+            mode: RowIdCheckMode::Increasing,
+            reject: true,
+            ..Default::default()
+        };
+        assert!(checker.check(&[1, 2, 5]).unwrap().is_empty());
+        assert!(checker.check(&[6, 6]).is_err());
+        // Rejected batch isn't recorded.
+        assert_eq!(checker.max_row_id, Some(5));
+        assert!(checker.check(&[3]).is_err());
+        assert_eq!(checker.violations, 2);
+
+        let mut checker = RowIdChecker {
+            mode: RowIdCheckMode::Unique,
+            reject: false,
+            ..Default::default()
+        };
+        assert!(checker.check(&[5, 1, 3]).unwrap().is_empty());
+        assert_eq!(checker.check(&[2, 3, 2]).unwrap().len(), 2);
+        assert_eq!(checker.violations, 2);
+        assert_eq!(checker.seen_row_ids.len(), 4);
+        checker.forget(&[3]);
+        assert!(checker.check(&[3]).unwrap().is_empty());
+
+        assert_eq!(
+            RowIdCheckMode::from_name("Unique"),
+            Some(RowIdCheckMode::Unique)
+        );
+        assert_eq!(RowIdCheckMode::from_name("unknown"), None);
+    }
+}
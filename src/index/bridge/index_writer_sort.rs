@@ -0,0 +1,118 @@
+use crate::tokenizer::vo::tokenizer_json_vo::{IndexSort, IndexSortOrder};
+use std::cmp::Ordering;
+use tantivy::schema::{Field, Schema, Type, Value};
+use tantivy::TantivyDocument;
+
+/// Sorts documents added to index writer by a fast field configured by `IndexSort`.
+///
+/// Tantivy no longer sorts segments by itself, so documents of each batch are sorted before
+/// being handed to the writer. Indexing threads keep the order documents arrive in, segments
+/// are sorted as long as batches arrive in sort order, e.g. time ordered data.
+/// Merged segments only keep sort order within each merged input segment.
+#[derive(Debug, Clone)]
+pub struct DocumentSorter {
+    field: Field,
+    value_type: Type,
+    order: IndexSortOrder,
+}
+
+impl DocumentSorter {
+    /// Resolve sort field in `schema`, it should be a u64, i64 or f64 fast field.
+    pub fn new(schema: &Schema, index_sort: &IndexSort) -> Result<Self, String> {
+        let field = schema
+            .get_field(&index_sort.field)
+            .map_err(|e| format!("Invalid index sort field: {}", e))?;
+        let field_entry = schema.get_field_entry(field);
+        let value_type = field_entry.field_type().value_type();
+        if !field_entry.is_fast() || !matches!(value_type, Type::U64 | Type::I64 | Type::F64) {
+            return Err(format!(
+                "Index sort field `{}` should be row_id or a numeric column",
+                index_sort.field
+            ));
+        }
+        Ok(DocumentSorter {
+            field,
+            value_type,
+            order: index_sort.order,
+        })
+    }
+
+    // Documents without sort field are placed first in ascending order.
+    fn compare(&self, left: &TantivyDocument, right: &TantivyDocument) -> Ordering {
+        let left = left.get_first(self.field);
+        let right = right.get_first(self.field);
+        let ordering = match self.value_type {
+            Type::U64 => left
+                .and_then(|value| value.as_u64())
+                .cmp(&right.and_then(|value| value.as_u64())),
+            Type::I64 => left
+                .and_then(|value| value.as_i64())
+                .cmp(&right.and_then(|value| value.as_i64())),
+            _ => match (
+                left.and_then(|value| value.as_f64()),
+                right.and_then(|value| value.as_f64()),
+            ) {
+                (Some(left), Some(right)) => left.total_cmp(&right),
+                (left, right) => left.is_some().cmp(&right.is_some()),
+            },
+        };
+        match self.order {
+            IndexSortOrder::Asc => ordering,
+            IndexSortOrder::Desc => ordering.reverse(),
+        }
+    }
+
+    /// Sort documents, documents with equal sort value keep their order.
+    pub fn sort(&self, documents: &mut [TantivyDocument]) {
+        documents.sort_by(|left, right| self.compare(left, right));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tantivy::schema::{FAST, INDEXED, TEXT};
+
+    #[test]
+    fn test_document_sorter() {
+        let mut schema_builder = Schema::builder();
+        let row_id_field = schema_builder.add_u64_field("row_id", FAST | INDEXED);
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let ts_field = schema_builder.add_i64_field("ts", INDEXED);
+        let schema = schema_builder.build();
+
+        let index_sort = |field: &str, order: IndexSortOrder| IndexSort {
+            field: field.to_string(),
+            order,
+        };
+        // Only numeric fast fields can be sorted.
+        assert!(DocumentSorter::new(&schema, &index_sort("text", IndexSortOrder::Asc)).is_err());
+        assert!(DocumentSorter::new(&schema, &index_sort("ts", IndexSortOrder::Asc)).is_err());
+        assert!(DocumentSorter::new(&schema, &index_sort("missing", IndexSortOrder::Asc)).is_err());
+
+        let mut documents: Vec<TantivyDocument> = [3u64, 1, 2]
+            .iter()
+            .map(|row_id| {
+                let mut doc = TantivyDocument::default();
+                doc.add_u64(row_id_field, *row_id);
+                doc.add_text(text_field, format!("row {}", row_id));
+                doc
+            })
+            .collect();
+        let row_ids = |documents: &[TantivyDocument]| -> Vec<u64> {
+            documents
+                .iter()
+                .map(|doc| doc.get_first(row_id_field).unwrap().as_u64().unwrap())
+                .collect()
+        };
+        let sorter =
+            DocumentSorter::new(&schema, &index_sort("row_id", IndexSortOrder::Asc)).unwrap();
+        sorter.sort(&mut documents);
+        assert_eq!(row_ids(&documents), vec![1, 2, 3]);
+
+        let sorter =
+            DocumentSorter::new(&schema, &index_sort("row_id", IndexSortOrder::Desc)).unwrap();
+        sorter.sort(&mut documents);
+        assert_eq!(row_ids(&documents), vec![3, 2, 1]);
+    }
+}
@@ -1,2 +1,14 @@
+pub mod index_writer_auto_commit;
 pub mod index_writer_bridge;
 pub mod index_writer_bridge_cache;
+pub mod index_writer_build_progress;
+pub mod index_writer_checkpoint;
+pub mod index_writer_commit_hook;
+pub mod index_writer_durability;
+pub mod index_writer_external_sort;
+pub mod index_writer_language_router;
+pub mod index_writer_merge_events;
+pub mod index_writer_merge_policy;
+pub mod index_writer_model;
+pub mod index_writer_row_id_check;
+pub mod index_writer_sort;
@@ -1,71 +1,190 @@
-use std::collections::HashMap;
-use std::sync::Mutex;
+use rayon::prelude::*;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::atomic::Ordering;
 use std::{path::Path, sync::Arc};
 
-use tantivy::schema::IndexRecordOption;
+use tantivy::merge_policy::{LogMergePolicy, NoMergePolicy};
+use tantivy::schema::DateOptions;
+use tantivy::schema::Field;
+use tantivy::schema::FieldType;
+use tantivy::schema::NumericOptions;
 use tantivy::schema::TextFieldIndexing;
 use tantivy::schema::TextOptions;
 use tantivy::schema::FAST;
 use tantivy::schema::{Schema, TEXT};
 use tantivy::schema::{INDEXED, STORED};
 
+use crate::common::allocator::AllocatorMemory;
+use crate::common::constants::{
+    BITMAP_UNION_POOL, CACHE_FOR_SKIP_INDEX, CANONICAL_INDEX_PATHS, DISK_SPACE_GUARD,
+    IDLE_INDEX_JANITOR, MERGE_THROTTLE, METRICS, MULTI_PART_BUILDS, OPEN_FILES_BUDGET,
+    SEARCHER_HANDLES, THREAD_AFFINITY, UTF8_POLICY,
+};
+use crate::common::disk_space_guard::DiskSpaceGuard;
 use crate::common::errors::TantivySearchError;
-use crate::index::bridge::index_writer_bridge::IndexWriterBridge;
+use crate::common::geo::GeoPoint;
+use crate::common::idle_index_janitor::{IdleIndexJanitor, IdleUnloadConfig};
+use crate::common::open_files_budget::IndexBridgeKind;
+use crate::common::utf8_policy::Sanitized;
+use crate::ffi::{
+    AllocatorStats, BuildProgress, IndexHealth, IndexSizeEstimate, SegmentDeleteStat,
+    SubsystemLiveness,
+};
+use crate::index::bridge::index_writer_auto_commit::AutoCommitConfig;
+use crate::index::bridge::index_writer_bridge::{BackpressureConfig, IndexWriterBridge};
+use crate::index::bridge::index_writer_checkpoint::committed_checkpoint;
+use crate::index::bridge::index_writer_durability::{
+    CommitDurability, DurableDirectory, IndexWriteOptions,
+};
+use crate::index::bridge::index_writer_external_sort::{
+    ExternalRowSorter, SortedRowBatches, SORTED_BATCH_ROWS,
+};
+use crate::index::bridge::index_writer_language_router::LanguageRouter;
+use crate::index::bridge::index_writer_merge_events::{MergeProgress, ObservedMergePolicy};
+use crate::index::bridge::index_writer_row_id_check::RowIdCheckMode;
+use crate::index::bridge::index_writer_sort::DocumentSorter;
+use crate::index::implements::commit_tickets::drain_commits;
+use crate::index::implements::multi_part_builder::MultiPartBuilder;
 use crate::logger::logger_bridge::TantivySearchLogger;
+use crate::logger::trace_profiler::TraceProfiler;
+use crate::search::bridge::index_reader_bridge::IndexReaderBridge;
+use crate::search::bridge::index_reader_query_cache::QueryResultCache;
 use crate::search::implements::api_common_impl::free_index_reader;
+use crate::search::implements::strategy::similarity_query::{Bm25Params, Similarity};
+use crate::tokenizer::date_format::DateFormat;
 use crate::tokenizer::dto::index_parameter_dto::IndexParameterDTO;
+use crate::tokenizer::language_routing::{language_field_name, language_tokenizer_name};
+use crate::tokenizer::sparse_vector_format::{SparseVectorFormat, SPARSE_VECTOR_TOKENIZER};
 use crate::tokenizer::tokenizer_utils::TokenizerUtils;
+use crate::tokenizer::vector_format::VectorFormat;
+use crate::tokenizer::vo::tokenizer_json_vo::IndexSort;
 use crate::tokenizer::vo::tokenizer_types::TokenizerType;
 use crate::tokenizer::vo::tokenizers_vo::TokenizerConfig;
 use crate::utils::index_utils::IndexUtils;
-use crate::{common::constants::LOG_CALLBACK, DEBUG, ERROR, INFO, WARNING};
-use crate::{FFI_INDEX_SEARCHER_CACHE, FFI_INDEX_WRITER_CACHE};
+use crate::utils::index_version_utils::IndexVersionUtils;
+use crate::utils::orphan_file_utils::OrphanFileUtils;
+use crate::utils::path_utils::PathUtils;
+use crate::utils::segment_id_utils::SegmentIdUtils;
+use crate::utils::time_utils::TimeUtils;
+use crate::utils::writer_lock_utils::WriterLockUtils;
+use crate::{common::constants::LOG_CALLBACK, DEBUG, ERROR, INFO, TRACE_SPAN, WARNING};
+use crate::{FFI_INDEX_SEARCHER_CACHE, FFI_INDEX_WRITER_CACHE, INDEX_NAMESPACES};
 
-use tantivy::{Index, TantivyDocument, Term};
+use tantivy::{Index, IndexSettings, IndexWriter, TantivyDocument, TantivyError, Term};
 
-pub fn create_index_with_parameter(
-    index_path: &str,
-    column_names: &Vec<String>,
-    index_json_parameter: &str,
-) -> Result<bool, TantivySearchError> {
-    // If the `index_path` already exists, it will be recreated,
-    // it's necessary to free any `index_reader` associated with this directory.
-    free_index_reader(index_path).map_err(|e| {
-        ERROR!("{}", e);
-        e
-    })?;
+// Memory budget of index writer, shared by all indexing threads.
+pub(crate) const INDEX_WRITER_MEMORY_BUDGET: usize = 1024 * 1024 * 64;
 
-    // If the `index_path` already exists, it will be recreated,
-    // it's necessary to free any `index_writer` associated with this directory.
-    free_index_writer(index_path).map_err(|e| {
-        ERROR!("{}", e);
-        e
-    })?;
+// Tantivy requires at least 15MB memory budget per indexing thread.
+pub(crate) const MIN_WRITER_MEMORY_PER_THREAD: usize = 15_000_000;
 
-    // Initialize the index directory, it will store tantivy index files.
-    let index_files_directory: &Path = Path::new(index_path);
-    IndexUtils::initialize_index_directory(index_files_directory)?;
+// Same as `Index::create_in_dir`, files written by writer follow `write_options`.
+fn create_durable_index(
+    directory_path: &Path,
+    schema: Schema,
+    write_options: &Arc<IndexWriteOptions>,
+) -> tantivy::Result<Index> {
+    let directory = DurableDirectory::open(directory_path, write_options.clone())?;
+    if Index::exists(&directory)? {
+        return Err(TantivyError::IndexAlreadyExists);
+    }
+    Index::create(directory, schema, IndexSettings::default())
+}
 
-    // Save custom index json parameter DTO to index directory.
-    let index_parameter_dto = IndexParameterDTO {
-        tokenizers_json_parameter: index_json_parameter.to_string(),
-    };
+// Same as `Index::open_in_dir`, files written by writer follow `write_options`.
+fn open_durable_index(
+    directory_path: &Path,
+    write_options: &Arc<IndexWriteOptions>,
+) -> tantivy::Result<Index> {
+    Index::open(DurableDirectory::open(
+        directory_path,
+        write_options.clone(),
+    )?)
+}
 
-    DEBUG!(function:"create_index_with_parameter", "parameter DTO:{:?}", index_parameter_dto);
+// Create the writer with a specified buffer size (e.g., 64 MB).
+// Deterministic build uses a single indexing thread to keep doc order stable,
+// and disables background merges, segments are only merged explicitly.
+// Writer with memory less than two threads required uses a single indexing thread.
+// Progress of background merges is returned if they are enabled.
+fn create_index_writer(
+    index_path: &str,
+    index: &Index,
+    deterministic_build: bool,
+    writer_memory_bytes: usize,
+) -> tantivy::Result<(IndexWriter, Option<Arc<MergeProgress>>)> {
+    let num_threads: usize =
+        if deterministic_build || writer_memory_bytes < 2 * MIN_WRITER_MEMORY_PER_THREAD {
+            1
+        } else {
+            2
+        };
+    let writer: IndexWriter = index.writer_with_num_threads(num_threads, writer_memory_bytes)?;
+    // Indexing and merge threads spawned by tantivy are named and pinned as threads of this library.
+    if let Err(e) = THREAD_AFFINITY.adopt_threads() {
+        WARNING!(function: "create_index_writer", "Failed to adopt writer threads of {}: {}", index_path, e);
+    }
+    if deterministic_build {
+        writer.set_merge_policy(Box::new(NoMergePolicy));
+        return Ok((writer, None));
+    }
+    // Configure and set the merge policy.
+    let mut merge_policy = LogMergePolicy::default();
+    merge_policy.set_min_num_segments(5);
+    // Background merges are reported to host and throttled.
+    let observed_merge_policy = ObservedMergePolicy::new(
+        PathUtils::index_key(index_path),
+        index.clone(),
+        Box::new(merge_policy),
+    );
+    let merge_progress: Arc<MergeProgress> = observed_merge_policy.progress();
+    writer.set_merge_policy(Box::new(observed_merge_policy));
+    Ok((writer, Some(merge_progress)))
+}
 
-    IndexUtils::save_custom_index_setting(index_files_directory, &index_parameter_dto)?;
+// Options of a numeric column, sort column of index sort is also a fast field.
+fn numeric_column_options(
+    tokenizer_config: &TokenizerConfig,
+    is_sort_field: bool,
+) -> NumericOptions {
+    let mut options = NumericOptions::default();
+    if tokenizer_config.doc_index {
+        options = options.set_indexed();
+    }
+    // Column neither stored nor indexed is kept in doc store.
+    if tokenizer_config.doc_store || !tokenizer_config.doc_index {
+        options = options.set_stored();
+    }
+    if is_sort_field {
+        options = options.set_fast();
+    }
+    options
+}
 
-    // Parse tokenizer map from local index parameter DTO.
-    let col_tokenizer_map: HashMap<String, TokenizerConfig> =
-        TokenizerUtils::parse_tokenizer_json_to_config_map(
-            &index_parameter_dto.tokenizers_json_parameter,
-        )
-        .map_err(|e| {
-            ERROR!("{}", e.to_string());
-            TantivySearchError::TokenizerUtilsError(e)
-        })?;
+// Options of a date column, it's a fast field so results can be ordered by it.
+fn date_column_options(tokenizer_config: &TokenizerConfig) -> DateOptions {
+    let mut options = DateOptions::default().set_fast();
+    if let Some(date_format) = &tokenizer_config.date_format {
+        options = options.set_precision(date_format.precision.to_tantivy());
+    }
+    if tokenizer_config.doc_index {
+        options = options.set_indexed();
+    }
+    if tokenizer_config.doc_store {
+        options = options.set_stored();
+    }
+    options
+}
 
-    // Construct the schema for the index.
+// Build index schema, columns are configured by `col_tokenizer_map`, others are default TEXT fields.
+// Numeric column named by `sort_field` is stored as fast field.
+fn build_index_schema(
+    column_names: &Vec<String>,
+    col_tokenizer_map: &HashMap<String, TokenizerConfig>,
+    sort_field: Option<&String>,
+) -> Schema {
     let mut schema_builder = Schema::builder();
     schema_builder.add_u64_field("row_id", FAST | INDEXED);
 
@@ -74,31 +193,57 @@ pub fn create_index_with_parameter(
             if !tokenizer_config.is_text_field {
                 match tokenizer_config.tokenizer_type {
                     TokenizerType::I64(_) => {
-                        if tokenizer_config.doc_store && tokenizer_config.doc_index {
-                            schema_builder.add_i64_field(&column_name, STORED | INDEXED);
-                        } else {
-                            if !tokenizer_config.doc_store && tokenizer_config.doc_index {
-                                schema_builder.add_i64_field(&column_name, INDEXED);
-                            } else {
-                                schema_builder.add_i64_field(&column_name, STORED);
-                            }
-                        }
+                        schema_builder.add_i64_field(
+                            &column_name,
+                            numeric_column_options(
+                                tokenizer_config,
+                                sort_field == Some(column_name),
+                            ),
+                        );
                         INFO!(function:"create_index_with_parameter", "column_name:{}, field_options name: {}", column_name, "I64");
                         continue;
                     }
                     TokenizerType::F64(_) => {
-                        if tokenizer_config.doc_store && tokenizer_config.doc_index {
-                            schema_builder.add_f64_field(&column_name, STORED | INDEXED);
-                        } else {
-                            if !tokenizer_config.doc_store && tokenizer_config.doc_index {
-                                schema_builder.add_f64_field(&column_name, INDEXED);
-                            } else {
-                                schema_builder.add_f64_field(&column_name, STORED);
-                            }
-                        }
+                        schema_builder.add_f64_field(
+                            &column_name,
+                            numeric_column_options(
+                                tokenizer_config,
+                                sort_field == Some(column_name),
+                            ),
+                        );
                         INFO!(function:"create_index_with_parameter", "column_name:{}, field_options name: {}", column_name, "F64");
                         continue;
                     }
+                    TokenizerType::GeoPoint(_) => {
+                        schema_builder.add_u64_field(&column_name, FAST);
+                        INFO!(function:"create_index_with_parameter", "column_name:{}, field_options name: {}", column_name, "GeoPoint");
+                        continue;
+                    }
+                    TokenizerType::Vector(_) => {
+                        schema_builder.add_bytes_field(&column_name, FAST);
+                        INFO!(function:"create_index_with_parameter", "column_name:{}, field_options name: {}", column_name, "Vector");
+                        continue;
+                    }
+                    TokenizerType::SparseVector(_) => {
+                        // Weights are term frequencies, lengths aren't used by dot product.
+                        schema_builder.add_text_field(
+                            &column_name,
+                            TextOptions::default().set_indexing_options(
+                                TextFieldIndexing::default()
+                                    .set_tokenizer(SPARSE_VECTOR_TOKENIZER)
+                                    .set_index_option(tokenizer_config.index_record_option)
+                                    .set_fieldnorms(false),
+                            ),
+                        );
+                        INFO!(function:"create_index_with_parameter", "column_name:{}, field_options name: {}", column_name, "SparseVector");
+                        continue;
+                    }
+                    TokenizerType::Date(_) => {
+                        schema_builder
+                            .add_date_field(&column_name, date_column_options(tokenizer_config));
+                        INFO!(function:"create_index_with_parameter", "column_name:{}, field_options name: {}", column_name, "Date");
+                        continue;
+                    }
                     TokenizerType::Bytes(_) => {
                         if tokenizer_config.doc_store && tokenizer_config.doc_index {
                             schema_builder.add_bytes_field(&column_name, STORED | INDEXED);
@@ -122,11 +267,15 @@ pub fn create_index_with_parameter(
 
             let tokenizer_name =
                 format!("{}_{}", column_name, tokenizer_config.tokenizer_type.name());
-            let mut text_options = TextOptions::default().set_indexing_options(
-                TextFieldIndexing::default()
-                    .set_tokenizer(&tokenizer_name)
-                    .set_index_option(IndexRecordOption::WithFreqsAndPositions),
-            );
+            // Text column not indexed is only kept in doc store.
+            let mut text_options = TextOptions::default();
+            if tokenizer_config.doc_index {
+                text_options = text_options.set_indexing_options(
+                    TextFieldIndexing::default()
+                        .set_tokenizer(&tokenizer_name)
+                        .set_index_option(tokenizer_config.index_record_option),
+                );
+            }
 
             if tokenizer_config.doc_store {
                 text_options = text_options.set_stored();
@@ -134,36 +283,38 @@ pub fn create_index_with_parameter(
 
             INFO!(function:"create_index_with_parameter", "column_name:{}, field_options name: {}", column_name, tokenizer_name);
             schema_builder.add_text_field(&column_name, text_options);
+
+            // Language sub-fields are only indexed, stored text is kept in the column.
+            if let Some(language_routing) = &tokenizer_config.language_routing {
+                for (language, _) in &language_routing.languages {
+                    let subfield_options = TextOptions::default().set_indexing_options(
+                        TextFieldIndexing::default()
+                            .set_tokenizer(&language_tokenizer_name(column_name, *language))
+                            .set_index_option(tokenizer_config.index_record_option),
+                    );
+                    schema_builder.add_text_field(
+                        &language_field_name(column_name, *language),
+                        subfield_options,
+                    );
+                }
+            }
         } else {
             WARNING!(function:"create_index_with_parameter with no_json_schema", "column_name:{}, field_options name: {}", column_name, "TEXT");
             schema_builder.add_text_field(&column_name, TEXT);
         }
     }
 
-    let schema = schema_builder.build();
-
-    INFO!(function:"create_index_with_parameter",
-        "index_path:{}, index_json_parameter:{}, col_tokenizer_map size:{}",
-        index_path,
-        index_json_parameter,
-        col_tokenizer_map.len()
-    );
-
-    // Create the index in the specified directory.
-    let mut index = Index::create_in_dir(index_files_directory, schema).map_err(|e| {
-        let error_info = format!(
-            "Failed to create index in directory:{}; exception:{}",
-            index_path,
-            e.to_string()
-        );
-        ERROR!(function:"create_index_with_parameter", "{}", error_info);
-        TantivySearchError::TantivyError(e)
-    })?;
+    schema_builder.build()
+}
 
-    // Register the tokenizer with the index.
+// Register tokenizers configured by `col_tokenizer_map` to index.
+fn register_index_tokenizers(
+    index: &mut Index,
+    col_tokenizer_map: &HashMap<String, TokenizerConfig>,
+) -> Result<(), TantivySearchError> {
     for (col_name, tokenizer_config) in col_tokenizer_map.iter() {
         TokenizerUtils::register_tokenizer_to_index(
-            &mut index,
+            index,
             tokenizer_config.tokenizer_type.clone(),
             &col_name,
             tokenizer_config.text_analyzer.clone(),
@@ -172,28 +323,290 @@ pub fn create_index_with_parameter(
             ERROR!(function:"create_index_with_parameter", "{}", e.to_string());
             TantivySearchError::TokenizerUtilsError(e)
         })?;
+        TokenizerUtils::register_language_tokenizers(index, col_name, tokenizer_config);
+    }
+    Ok(())
+}
+
+// Language sub-fields are declared by index, host columns can't use their names.
+fn check_language_subfields(
+    function: &str,
+    column_names: &Vec<String>,
+    col_tokenizer_map: &HashMap<String, TokenizerConfig>,
+) -> Result<(), TantivySearchError> {
+    for column_name in column_names {
+        let language_routing = match col_tokenizer_map
+            .get(column_name)
+            .and_then(|tokenizer_config| tokenizer_config.language_routing.as_ref())
+        {
+            Some(language_routing) => language_routing,
+            None => continue,
+        };
+        for (language, _) in &language_routing.languages {
+            let subfield_name = language_field_name(column_name, *language);
+            if column_names.contains(&subfield_name) {
+                let error_info = format!(
+                    "column `{}` conflicts with language sub-field of column `{}`",
+                    subfield_name, column_name
+                );
+                ERROR!(function: function, "{}", error_info);
+                return Err(TantivySearchError::InvalidArgument(error_info));
+            }
+        }
+    }
+    Ok(())
+}
+
+// Resolve language router of index schema, `None` if language routing isn't configured.
+fn build_language_router(
+    function: &str,
+    schema: &Schema,
+    col_tokenizer_map: &HashMap<String, TokenizerConfig>,
+) -> Result<Option<LanguageRouter>, TantivySearchError> {
+    LanguageRouter::new(schema, col_tokenizer_map).map_err(|e| {
+        ERROR!(function: function, "{}", e);
+        TantivySearchError::InvalidArgument(e)
+    })
+}
+
+// Formats of date columns configured by `col_tokenizer_map` and created in `schema`.
+fn build_date_formats(
+    schema: &Schema,
+    col_tokenizer_map: &HashMap<String, TokenizerConfig>,
+) -> HashMap<Field, DateFormat> {
+    col_tokenizer_map
+        .iter()
+        .filter_map(|(column_name, tokenizer_config)| {
+            let date_format: &DateFormat = tokenizer_config.date_format.as_ref()?;
+            let field: Field = schema.get_field(column_name).ok()?;
+            Some((field, date_format.clone()))
+        })
+        .collect()
+}
+
+// Geo point columns configured by `col_tokenizer_map` and created in `schema`.
+fn build_geo_point_fields(
+    schema: &Schema,
+    col_tokenizer_map: &HashMap<String, TokenizerConfig>,
+) -> HashSet<Field> {
+    col_tokenizer_map
+        .iter()
+        .filter(|(_, tokenizer_config)| {
+            matches!(tokenizer_config.tokenizer_type, TokenizerType::GeoPoint(_))
+        })
+        .filter_map(|(column_name, _)| schema.get_field(column_name).ok())
+        .collect()
+}
+
+// Formats of vector columns configured by `col_tokenizer_map` and created in `schema`.
+fn build_vector_formats(
+    schema: &Schema,
+    col_tokenizer_map: &HashMap<String, TokenizerConfig>,
+) -> HashMap<Field, VectorFormat> {
+    col_tokenizer_map
+        .iter()
+        .filter_map(|(column_name, tokenizer_config)| {
+            let vector_format: &VectorFormat = tokenizer_config.vector_format.as_ref()?;
+            let field: Field = schema.get_field(column_name).ok()?;
+            Some((field, vector_format.clone()))
+        })
+        .collect()
+}
+
+// Quantization of sparse vector columns configured by `col_tokenizer_map` and created in `schema`.
+fn build_sparse_vector_formats(
+    schema: &Schema,
+    col_tokenizer_map: &HashMap<String, TokenizerConfig>,
+) -> HashMap<Field, SparseVectorFormat> {
+    col_tokenizer_map
+        .iter()
+        .filter_map(|(column_name, tokenizer_config)| {
+            let sparse_vector_format: SparseVectorFormat = tokenizer_config.sparse_vector_format?;
+            let field: Field = schema.get_field(column_name).ok()?;
+            Some((field, sparse_vector_format))
+        })
+        .collect()
+}
+
+// Parse index sort option of `index_json_parameter`, `None` if it isn't configured.
+fn parse_index_sort(
+    function: &str,
+    index_json_parameter: &str,
+) -> Result<Option<IndexSort>, TantivySearchError> {
+    TokenizerUtils::parse_index_sort(index_json_parameter).map_err(|e| {
+        ERROR!(function: function, "{}", e.to_string());
+        TantivySearchError::TokenizerUtilsError(e)
+    })
+}
+
+// Resolve document sorter of `index_sort` in index schema.
+fn build_document_sorter(
+    function: &str,
+    schema: &Schema,
+    index_sort: Option<&IndexSort>,
+) -> Result<Option<DocumentSorter>, TantivySearchError> {
+    match index_sort {
+        Some(index_sort) => DocumentSorter::new(schema, index_sort)
+            .map(Some)
+            .map_err(|e| {
+                ERROR!(function: function, "{}", e);
+                TantivySearchError::InvalidArgument(e)
+            }),
+        None => Ok(None),
     }
+}
+
+pub fn create_index_with_parameter(
+    index_path: &str,
+    column_names: &Vec<String>,
+    index_json_parameter: &str,
+) -> Result<bool, TantivySearchError> {
+    create_index_with_build_mode(index_path, column_names, index_json_parameter, false)
+}
+
+/// Create index, `deterministic_build` makes replicas indexing the same input
+/// produce byte-comparable index files:
+/// - docs are indexed by a single thread in insertion order, background merges are disabled.
+/// - segment ids are derived from segment content when the index writer is freed.
+pub fn create_index_with_build_mode(
+    index_path: &str,
+    column_names: &Vec<String>,
+    index_json_parameter: &str,
+    deterministic_build: bool,
+) -> Result<bool, TantivySearchError> {
+    create_index_with_writer_memory(
+        index_path,
+        column_names,
+        index_json_parameter,
+        deterministic_build,
+        INDEX_WRITER_MEMORY_BUDGET,
+    )
+}
+
+/// Create index whose writer claims `writer_memory_bytes` memory budget instead of the default.
+/// Writer reloaded later by `load_index_writer` uses the default memory budget.
+pub(crate) fn create_index_with_writer_memory(
+    index_path: &str,
+    column_names: &Vec<String>,
+    index_json_parameter: &str,
+    deterministic_build: bool,
+    writer_memory_bytes: usize,
+) -> Result<bool, TantivySearchError> {
+    TRACE_SPAN!("create_index", index_path);
+    // If the `index_path` already exists, it will be recreated,
+    // it's necessary to free any `index_reader` associated with this directory.
+    free_index_reader(index_path).map_err(|e| {
+        ERROR!("{}", e);
+        e
+    })?;
+
+    // If the `index_path` already exists, it will be recreated,
+    // it's necessary to free any `index_writer` associated with this directory.
+    free_index_writer(index_path).map_err(|e| {
+        ERROR!("{}", e);
+        e
+    })?;
+
+    // Initialize the index directory, it will store tantivy index files.
+    let index_files_directory: &Path = &PathUtils::index_directory(index_path);
+    IndexUtils::initialize_index_directory(index_files_directory)?;
+
+    // Save custom index json parameter DTO to index directory.
+    let mut index_parameter_dto = IndexParameterDTO {
+        tokenizers_json_parameter: index_json_parameter.to_string(),
+        deterministic_build,
+        ..IndexParameterDTO::default()
+    };
+    IndexVersionUtils::stamp(&mut index_parameter_dto);
+
+    DEBUG!(function:"create_index_with_parameter", "parameter DTO:{:?}", index_parameter_dto);
 
-    // Create the writer with a specified buffer size (e.g., 64 MB).
-    let writer = index
-        .writer_with_num_threads(2, 1024 * 1024 * 64)
+    IndexUtils::save_custom_index_setting(index_files_directory, &index_parameter_dto)?;
+
+    // Parse tokenizer map from local index parameter DTO.
+    let col_tokenizer_map: HashMap<String, TokenizerConfig> =
+        TokenizerUtils::parse_tokenizer_json_to_config_map(
+            &index_parameter_dto.tokenizers_json_parameter,
+        )
         .map_err(|e| {
-            let error_info = format!("Failed to create tantivy writer: {}", e);
+            ERROR!("{}", e.to_string());
+            TantivySearchError::TokenizerUtilsError(e)
+        })?;
+
+    let index_sort: Option<IndexSort> =
+        parse_index_sort("create_index_with_parameter", index_json_parameter)?;
+    check_language_subfields(
+        "create_index_with_parameter",
+        column_names,
+        &col_tokenizer_map,
+    )?;
+
+    // Construct the schema for the index.
+    let schema: Schema = build_index_schema(
+        column_names,
+        &col_tokenizer_map,
+        index_sort.as_ref().map(|index_sort| &index_sort.field),
+    );
+    let document_sorter: Option<DocumentSorter> =
+        build_document_sorter("create_index_with_parameter", &schema, index_sort.as_ref())?;
+    let language_router: Option<LanguageRouter> =
+        build_language_router("create_index_with_parameter", &schema, &col_tokenizer_map)?;
+    let date_formats: HashMap<Field, DateFormat> = build_date_formats(&schema, &col_tokenizer_map);
+    let geo_point_fields: HashSet<Field> = build_geo_point_fields(&schema, &col_tokenizer_map);
+    let vector_formats: HashMap<Field, VectorFormat> =
+        build_vector_formats(&schema, &col_tokenizer_map);
+    let sparse_vector_formats: HashMap<Field, SparseVectorFormat> =
+        build_sparse_vector_formats(&schema, &col_tokenizer_map);
+
+    INFO!(function:"create_index_with_parameter",
+        "index_path:{}, index_json_parameter:{}, col_tokenizer_map size:{}",
+        index_path,
+        index_json_parameter,
+        col_tokenizer_map.len()
+    );
+
+    // Create the index in the specified directory.
+    let write_options = Arc::new(IndexWriteOptions::new());
+    let mut index =
+        create_durable_index(index_files_directory, schema, &write_options).map_err(|e| {
+            let error_info = format!(
+                "Failed to create index in directory:{}; exception:{}",
+                index_path,
+                e.to_string()
+            );
             ERROR!(function:"create_index_with_parameter", "{}", error_info);
             TantivySearchError::TantivyError(e)
         })?;
 
-    // Configure and set the merge policy.
-    let mut merge_policy = tantivy::merge_policy::LogMergePolicy::default();
-    merge_policy.set_min_num_segments(5);
-    writer.set_merge_policy(Box::new(merge_policy));
+    // Register the tokenizer with the index.
+    register_index_tokenizers(&mut index, &col_tokenizer_map)?;
+
+    let (writer, merge_progress) =
+        create_index_writer(index_path, &index, deterministic_build, writer_memory_bytes).map_err(
+            |e| {
+                let error_info = format!("Failed to create tantivy writer: {}", e);
+                ERROR!(function:"create_index_with_parameter", "{}", error_info);
+                TantivySearchError::TantivyError(e)
+            },
+        )?;
+
+    // Record current process as writer owner, it's used for stale lock detection.
+    WriterLockUtils::record_writer_owner(index_files_directory).map_err(|e| {
+        ERROR!(function:"create_index_with_parameter", "{}", e);
+        TantivySearchError::IndexUtilsError(e)
+    })?;
 
     // Save index_writer_bridge to cache.
-    let index_writer_bridge: IndexWriterBridge = IndexWriterBridge {
-        index,
-        path: index_path.trim_end_matches('/').to_string(),
-        writer: Mutex::new(Some(writer)),
-    };
+    let index_writer_bridge: IndexWriterBridge =
+        IndexWriterBridge::new(PathUtils::index_key(index_path), index, writer)
+            .with_document_sorter(document_sorter)
+            .with_language_router(language_router)
+            .with_date_formats(date_formats)
+            .with_geo_point_fields(geo_point_fields)
+            .with_vector_formats(vector_formats)
+            .with_sparse_vector_formats(sparse_vector_formats)
+            .with_merge_progress(merge_progress)
+            .with_write_options(write_options);
 
     FFI_INDEX_WRITER_CACHE
         .set_index_writer_bridge(index_path.to_string(), Arc::new(index_writer_bridge))
@@ -218,6 +631,28 @@ pub fn index_multi_column_docs(
     column_names: &Vec<String>,
     column_docs: &Vec<String>,
 ) -> Result<bool, TantivySearchError> {
+    let column_docs: Vec<&str> = column_docs.iter().map(|doc| doc.as_str()).collect();
+    index_multi_column_borrowed_docs(index_path, row_id, column_names, &column_docs)
+}
+
+/// Index multi column docs borrowed from caller's buffers,
+/// text is copied only once, into tantivy document.
+pub fn index_multi_column_borrowed_docs(
+    index_path: &str,
+    row_id: u64,
+    column_names: &Vec<String>,
+    column_docs: &[&str],
+) -> Result<bool, TantivySearchError> {
+    if column_names.len() != column_docs.len() {
+        let error_info = format!(
+            "column_names size {} doesn't match column_docs size {}",
+            column_names.len(),
+            column_docs.len()
+        );
+        ERROR!(function: "index_multi_column_docs", "{}", error_info);
+        return Err(TantivySearchError::InvalidArgument(error_info));
+    }
+
     // Get index writer from CACHE
     let index_writer_bridge = FFI_INDEX_WRITER_CACHE
         .get_index_writer_bridge(index_path.to_string())
@@ -226,6 +661,18 @@ pub fn index_multi_column_docs(
             TantivySearchError::InternalError(e)
         })?;
 
+    // Reject document early if writer is under backpressure.
+    index_writer_bridge.check_backpressure().map_err(|e| {
+        WARNING!(function: "index_multi_column_docs", "Index writer is under backpressure: {}", e);
+        TantivySearchError::Backpressure(e)
+    })?;
+
+    // Verify row_id if row_id check is enabled.
+    index_writer_bridge.check_row_ids(&[row_id]).map_err(|e| {
+        ERROR!(function: "index_multi_column_docs", "Row_id check failed: {}", e);
+        TantivySearchError::InvalidArgument(e)
+    })?;
+
     // Get schema from index writer.
     let schema = index_writer_bridge.index.schema();
     let row_id_field = schema.get_field("row_id").map_err(|e| {
@@ -236,17 +683,21 @@ pub fn index_multi_column_docs(
     let mut doc = TantivyDocument::default();
     doc.add_u64(row_id_field, row_id);
 
-    let mut column_idx = 0;
-    for column_name in column_names {
+    for (column_name, column_doc) in column_names.iter().zip(column_docs.iter()) {
         let column_field = schema.get_field(column_name).map_err(|e| {
             ERROR!(function: "index_multi_column_docs", "Failed to get {} field in schema: {}", column_name, e.to_string());
             TantivySearchError::TantivyError(e)
         })?;
-        doc.add_text(column_field, column_docs[column_idx].clone());
-        column_idx += 1;
+        index_writer_bridge
+            .add_column_text(&mut doc, column_field, column_doc)
+            .map_err(|e| {
+                ERROR!(function: "index_multi_column_docs", "Invalid value of column {}: {}", column_name, e);
+                TantivySearchError::InvalidArgument(e)
+            })?;
     }
 
-    match index_writer_bridge.add_document(doc) {
+    let estimated_bytes: usize = column_docs.iter().map(|doc| doc.len()).sum();
+    match index_writer_bridge.add_document_with_size(doc, estimated_bytes as u64, Some(row_id)) {
         Ok(_) => Ok(true),
         Err(e) => {
             let error_info = format!("Failed to index doc:{}", e);
@@ -276,6 +727,18 @@ pub fn index_multi_type_column_docs(
             TantivySearchError::InternalError(e)
         })?;
 
+    // Reject document early if writer is under backpressure.
+    index_writer_bridge.check_backpressure().map_err(|e| {
+        WARNING!(function: "index_multi_column_docs", "Index writer is under backpressure: {}", e);
+        TantivySearchError::Backpressure(e)
+    })?;
+
+    // Verify row_id if row_id check is enabled.
+    index_writer_bridge.check_row_ids(&[row_id]).map_err(|e| {
+        ERROR!(function: "index_multi_column_docs", "Row_id check failed: {}", e);
+        TantivySearchError::InvalidArgument(e)
+    })?;
+
     // Get schema from index writer.
     let schema = index_writer_bridge.index.schema();
     let row_id_field = schema.get_field("row_id").map_err(|e| {
@@ -293,7 +756,12 @@ pub fn index_multi_type_column_docs(
             ERROR!(function: "index_multi_column_docs", "Failed to get {} field in schema: {}", column_name, e.to_string());
             TantivySearchError::TantivyError(e)
         })?;
-        doc.add_text(column_field, text_column_docs[column_idx].clone());
+        index_writer_bridge
+            .add_column_text(&mut doc, column_field, &text_column_docs[column_idx])
+            .map_err(|e| {
+                ERROR!(function: "index_multi_column_docs", "Invalid value of column {}: {}", column_name, e);
+                TantivySearchError::InvalidArgument(e)
+            })?;
         column_idx += 1;
     }
 
@@ -330,7 +798,10 @@ pub fn index_multi_type_column_docs(
         column_idx += 1;
     }
 
-    match index_writer_bridge.add_document(doc) {
+    let estimated_bytes: usize = text_column_docs.iter().map(|doc| doc.len()).sum::<usize>()
+        + bytes_column_docs.iter().map(|doc| doc.len()).sum::<usize>()
+        + (i64_column_docs.len() + f64_column_docs.len()) * 8;
+    match index_writer_bridge.add_document_with_size(doc, estimated_bytes as u64, Some(row_id)) {
         Ok(_) => Ok(true),
         Err(e) => {
             let error_info = format!("Failed to index doc:{}", e);
@@ -340,26 +811,263 @@ pub fn index_multi_type_column_docs(
     }
 }
 
-pub fn delete_row_ids(index_path: &str, row_ids: &Vec<u64>) -> Result<bool, TantivySearchError> {
+/// Index a column batch at once, strings are stored column by column in `column_data`:
+/// string of column `c` row `r` is `column_data[column_offsets[c * rows + r]..column_offsets[c * rows + r + 1]]`,
+/// so `column_offsets` has `column_names.len() * row_ids.len() + 1` elements.
+/// Documents are built in parallel and added in row order.
+pub fn index_column_batch(
+    index_path: &str,
+    row_ids: &[u64],
+    column_names: &Vec<String>,
+    column_data: &[u8],
+    column_offsets: &[u64],
+) -> Result<bool, TantivySearchError> {
+    let rows: usize = row_ids.len();
+    if column_names.is_empty() || column_offsets.len() != column_names.len() * rows + 1 {
+        let error_info = format!(
+            "column_offsets size should be {}, but got {}",
+            column_names.len() * rows + 1,
+            column_offsets.len()
+        );
+        ERROR!(function: "index_column_batch", "{}", error_info);
+        return Err(TantivySearchError::InvalidArgument(error_info));
+    }
+    if column_offsets.windows(2).any(|w| w[0] > w[1])
+        || column_offsets[column_offsets.len() - 1] > column_data.len() as u64
+    {
+        let error_info = format!(
+            "column_offsets should be ascending and within column_data size {}",
+            column_data.len()
+        );
+        ERROR!(function: "index_column_batch", "{}", error_info);
+        return Err(TantivySearchError::InvalidArgument(error_info));
+    }
+
     // Get index writer from CACHE
-    let index_writer_bridge =
-        match FFI_INDEX_WRITER_CACHE.get_index_writer_bridge(index_path.to_string()) {
-            Ok(content) => content,
-            Err(e) => {
-                ERROR!(function: "delete_row_ids", "{}", e);
-                return Err(TantivySearchError::InternalError(e));
-            }
-        };
+    let index_writer_bridge = FFI_INDEX_WRITER_CACHE
+        .get_index_writer_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function: "index_column_batch", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+
+    // Reject batch early if writer is under backpressure.
+    index_writer_bridge.check_backpressure().map_err(|e| {
+        WARNING!(function: "index_column_batch", "Index writer is under backpressure: {}", e);
+        TantivySearchError::Backpressure(e)
+    })?;
 
+    // Get schema from index writer.
     let schema = index_writer_bridge.index.schema();
     let row_id_field = schema.get_field("row_id").map_err(|e| {
-        ERROR!(function: "delete_row_ids", "Failed to get row_id field: {}", e.to_string());
+        ERROR!(function: "index_column_batch", "Failed to get row_id field: {}", e.to_string());
         TantivySearchError::TantivyError(e)
     })?;
+    let mut column_fields = Vec::with_capacity(column_names.len());
+    for column_name in column_names {
+        column_fields.push(schema.get_field(column_name).map_err(|e| {
+            ERROR!(function: "index_column_batch", "Failed to get {} field in schema: {}", column_name, e.to_string());
+            TantivySearchError::TantivyError(e)
+        })?);
+    }
 
-    let terms = row_ids
-        .iter()
-        .map(|row_id| Term::from_field_u64(row_id_field, *row_id))
+    // Invalid utf-8 is handled by `UTF8_POLICY`, skipped rows are `None`.
+    let documents: Vec<Option<TantivyDocument>> = (0..rows)
+        .into_par_iter()
+        .map(|row| {
+            let mut doc = TantivyDocument::default();
+            doc.add_u64(row_id_field, row_ids[row]);
+            for (column_idx, column_field) in column_fields.iter().enumerate() {
+                let offset_idx = column_idx * rows + row;
+                let bytes = &column_data
+                    [column_offsets[offset_idx] as usize..column_offsets[offset_idx + 1] as usize];
+                match UTF8_POLICY.sanitize(bytes) {
+                    Ok(Sanitized::Text(text)) => index_writer_bridge
+                        .add_column_text(&mut doc, *column_field, &text)
+                        .map_err(|e| {
+                            TantivySearchError::InvalidArgument(format!(
+                                "Invalid value in row_id {}, column {}: {}",
+                                row_ids[row], column_names[column_idx], e
+                            ))
+                        })?,
+                    Ok(Sanitized::SkipRow(_)) => return Ok(None),
+                    Err(e) => {
+                        return Err(TantivySearchError::InvalidArgument(format!(
+                            "Invalid utf-8 text in row_id {}, column {}: {}",
+                            row_ids[row], column_names[column_idx], e
+                        )))
+                    }
+                }
+            }
+            Ok(Some(doc))
+        })
+        .collect::<Result<Vec<Option<TantivyDocument>>, TantivySearchError>>()
+        .map_err(|e| {
+            ERROR!(function: "index_column_batch", "{}", e);
+            e
+        })?;
+    // Only row_ids of rows not skipped are verified.
+    let indexed_row_ids: Vec<u64> = row_ids
+        .iter()
+        .zip(documents.iter())
+        .filter(|(_, doc)| doc.is_some())
+        .map(|(row_id, _)| *row_id)
+        .collect();
+    index_writer_bridge
+        .check_row_ids(&indexed_row_ids)
+        .map_err(|e| {
+            ERROR!(function: "index_column_batch", "Row_id check failed: {}", e);
+            TantivySearchError::InvalidArgument(e)
+        })?;
+    let documents: Vec<TantivyDocument> = documents.into_iter().flatten().collect();
+    if documents.len() < rows {
+        WARNING!(function: "index_column_batch", "Skipped {} rows for invalid utf-8", rows - documents.len());
+    }
+
+    let estimated_bytes: u64 = column_offsets[column_offsets.len() - 1] - column_offsets[0];
+    match index_writer_bridge.add_documents_with_size(
+        documents,
+        estimated_bytes,
+        row_ids.iter().max().copied(),
+    ) {
+        Ok(_) => Ok(true),
+        Err(e) => {
+            let error_info = format!("Failed to index column batch:{}", e);
+            ERROR!(function: "index_column_batch", "{}", error_info);
+            Err(TantivySearchError::InternalError(e))
+        }
+    }
+}
+
+// Assign `rows` consecutive row_ids and index them by `index_rows`, return the first row_id.
+// Row_ids are consumed only if `index_rows` succeeded.
+fn index_with_auto_row_ids<F>(
+    index_path: &str,
+    function: &str,
+    rows: u64,
+    index_rows: F,
+) -> Result<u64, TantivySearchError>
+where
+    F: FnOnce(u64) -> Result<bool, TantivySearchError>,
+{
+    let index_writer_bridge = FFI_INDEX_WRITER_CACHE
+        .get_index_writer_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function: function, "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+    let mut auto_row_id = index_writer_bridge.lock_auto_row_id().map_err(|e| {
+        ERROR!(function: function, "{}", e);
+        TantivySearchError::InternalError(e)
+    })?;
+    let first_row_id: u64 = auto_row_id.ok_or_else(|| {
+        let error_info = format!("Auto row_id isn't enabled, index_path:[{}]", index_path);
+        ERROR!(function: function, "{}", error_info);
+        TantivySearchError::InvalidArgument(error_info)
+    })?;
+    index_rows(first_row_id)?;
+    *auto_row_id = Some(first_row_id + rows);
+    Ok(first_row_id)
+}
+
+/// Enable automatic row_id assignment for an index writer, return the next row_id.
+pub fn enable_auto_row_id(index_path: &str) -> Result<u64, TantivySearchError> {
+    let index_writer_bridge: Arc<IndexWriterBridge> = FFI_INDEX_WRITER_CACHE
+        .get_index_writer_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function: "enable_auto_row_id", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+    let next_row_id = index_writer_bridge.enable_auto_row_id().map_err(|e| {
+        ERROR!(function: "enable_auto_row_id", "{}", e);
+        TantivySearchError::InternalError(e)
+    })?;
+    INFO!(function: "enable_auto_row_id", "Auto row_id enabled, next_row_id:{}, index_path:[{}]", next_row_id, index_path);
+    Ok(next_row_id)
+}
+
+/// Index multi column docs with a row_id assigned by index writer, return the assigned row_id.
+pub fn index_multi_column_docs_auto_row_id(
+    index_path: &str,
+    column_names: &Vec<String>,
+    column_docs: &Vec<String>,
+) -> Result<u64, TantivySearchError> {
+    index_with_auto_row_ids(
+        index_path,
+        "index_multi_column_docs_auto_row_id",
+        1,
+        |row_id| index_multi_column_docs(index_path, row_id, column_names, column_docs),
+    )
+}
+
+/// Index a column batch with consecutive row_ids assigned by index writer,
+/// return the first assigned row_id. Rows skipped by `UTF8_POLICY` still consume their row_ids.
+pub fn index_column_batch_auto_row_id(
+    index_path: &str,
+    column_names: &Vec<String>,
+    column_data: &[u8],
+    column_offsets: &[u64],
+) -> Result<u64, TantivySearchError> {
+    if column_names.is_empty()
+        || column_offsets.is_empty()
+        || (column_offsets.len() - 1) % column_names.len() != 0
+    {
+        let error_info = format!(
+            "column_offsets size {} doesn't match column_names size {}",
+            column_offsets.len(),
+            column_names.len()
+        );
+        ERROR!(function: "index_column_batch_auto_row_id", "{}", error_info);
+        return Err(TantivySearchError::InvalidArgument(error_info));
+    }
+    let rows: u64 = ((column_offsets.len() - 1) / column_names.len()) as u64;
+    index_with_auto_row_ids(
+        index_path,
+        "index_column_batch_auto_row_id",
+        rows,
+        |first_row_id| {
+            let row_ids: Vec<u64> = (first_row_id..first_row_id + rows).collect();
+            index_column_batch(
+                index_path,
+                &row_ids,
+                column_names,
+                column_data,
+                column_offsets,
+            )
+        },
+    )
+}
+
+// Commit or merge refused by `DiskSpaceGuard` fails with `OutOfDiskSpace`, other failures of
+// writer bridge with `InternalError` carrying `error_info`.
+fn writer_failure(error: String, error_info: String) -> TantivySearchError {
+    if DiskSpaceGuard::is_out_of_disk_space(&error) {
+        TantivySearchError::OutOfDiskSpace(error)
+    } else {
+        TantivySearchError::InternalError(error_info)
+    }
+}
+
+pub fn delete_row_ids(index_path: &str, row_ids: &Vec<u64>) -> Result<bool, TantivySearchError> {
+    // Get index writer from CACHE
+    let index_writer_bridge =
+        match FFI_INDEX_WRITER_CACHE.get_index_writer_bridge(index_path.to_string()) {
+            Ok(content) => content,
+            Err(e) => {
+                ERROR!(function: "delete_row_ids", "{}", e);
+                return Err(TantivySearchError::InternalError(e));
+            }
+        };
+
+    let schema = index_writer_bridge.index.schema();
+    let row_id_field = schema.get_field("row_id").map_err(|e| {
+        ERROR!(function: "delete_row_ids", "Failed to get row_id field: {}", e.to_string());
+        TantivySearchError::TantivyError(e)
+    })?;
+
+    let terms = row_ids
+        .iter()
+        .map(|row_id| Term::from_field_u64(row_id_field, *row_id))
         .collect();
 
     // Delete row_id terms.
@@ -367,11 +1075,12 @@ pub fn delete_row_ids(index_path: &str, row_ids: &Vec<u64>) -> Result<bool, Tant
         ERROR!(function: "delete_row_ids", "{}", e);
         TantivySearchError::InternalError(e)
     })?;
+    index_writer_bridge.forget_row_ids(row_ids);
     // After delete_term, need commit index writer.
     index_writer_bridge.commit().map_err(|e| {
-        let error_info = format!("Failed to commit index writer: {}", e.to_string());
+        let error_info = format!("Failed to commit index writer: {}", e);
         ERROR!(function: "delete_row_ids", "{}", error_info);
-        TantivySearchError::InternalError(error_info)
+        writer_failure(e, error_info)
     })?;
     // Try reload index reader from CACHE
     let reload_status = match FFI_INDEX_SEARCHER_CACHE
@@ -402,9 +1111,9 @@ pub fn commit_index(index_path: &str) -> Result<bool, TantivySearchError> {
         })?;
 
     index_writer_bridge.commit().map_err(|e| {
-        let error_info = format!("Failed to commit index writer: {}", e.to_string());
+        let error_info = format!("Failed to commit index writer: {}", e);
         ERROR!(function: "commit_index", "{}", error_info);
-        TantivySearchError::InternalError(e)
+        writer_failure(e, error_info)
     })?;
 
     // get index writer bridge from CACHE
@@ -418,6 +1127,277 @@ pub fn commit_index(index_path: &str) -> Result<bool, TantivySearchError> {
     Ok(true)
 }
 
+pub fn enable_auto_commit(
+    index_path: &str,
+    interval_secs: u64,
+    max_docs: u64,
+) -> Result<bool, TantivySearchError> {
+    let config = AutoCommitConfig {
+        interval_secs,
+        max_docs,
+    };
+    if !config.is_valid() {
+        let error_info = "`interval_secs` and `max_docs` can't be both zero".to_string();
+        ERROR!(function: "enable_auto_commit", "{}", error_info);
+        return Err(TantivySearchError::InvalidArgument(error_info));
+    }
+    let index_writer_bridge: Arc<IndexWriterBridge> = FFI_INDEX_WRITER_CACHE
+        .get_index_writer_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function: "enable_auto_commit", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+    index_writer_bridge.start_auto_commit(config).map_err(|e| {
+        ERROR!(function: "enable_auto_commit", "{}", e);
+        TantivySearchError::InternalError(e)
+    })?;
+    INFO!(function: "enable_auto_commit", "Auto commit enabled, interval_secs:{}, max_docs:{}, index_path:[{}]", interval_secs, max_docs, index_path);
+    Ok(true)
+}
+
+pub fn disable_auto_commit(index_path: &str) -> Result<bool, TantivySearchError> {
+    let index_writer_bridge: Arc<IndexWriterBridge> = FFI_INDEX_WRITER_CACHE
+        .get_index_writer_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function: "disable_auto_commit", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+    index_writer_bridge.stop_auto_commit();
+    Ok(true)
+}
+
+/// Settings which can be changed on a loaded index without reopening it,
+/// fields not given are kept unchanged.
+#[derive(Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+struct IndexRuntimeSettings {
+    // Queries slower than it are logged by index reader, 0 disables it.
+    slow_query_threshold_ms: Option<u64>,
+    // Interval of index writer auto commit, `max_docs` of running auto commit is kept.
+    // 0 disables interval commit, auto commit stops if `max_docs` is 0 too.
+    auto_commit_interval_secs: Option<u64>,
+    // How committed files are synced, one of `fsync`, `fdatasync` and `none`.
+    commit_durability: Option<String>,
+    // Write new segment files with O_DIRECT bypassing page cache, for bulk builds.
+    // Falls back to buffered writes where O_DIRECT isn't supported.
+    direct_io: Option<bool>,
+    // Capacity of bitmap query results persisted next to index, 0 disables it.
+    persistent_query_cache_mb: Option<u64>,
+    // Similarity of ranked search, one of `bm25`, `tf_only` and `constant`.
+    similarity: Option<String>,
+    // BM25 parameters of ranked search, a missing one keeps its current value.
+    // They can't be given if similarity is not BM25.
+    bm25_k1: Option<f32>,
+    bm25_b: Option<f32>,
+}
+
+/// Apply runtime settings in json to loaded index writer and reader, e.g.
+/// `{"slow_query_threshold_ms": 200, "auto_commit_interval_secs": 5, "similarity": "bm25", "bm25_k1": 0.9}`.
+/// All settings are verified before any of them is applied.
+pub fn update_index_settings(
+    index_path: &str,
+    settings_json: &str,
+) -> Result<bool, TantivySearchError> {
+    let settings: IndexRuntimeSettings = serde_json::from_str(settings_json).map_err(|e| {
+        let error_info = format!("Invalid settings json: {}", e);
+        ERROR!(function: "update_index_settings", "{}", error_info);
+        TantivySearchError::InvalidArgument(error_info)
+    })?;
+    DEBUG!(function: "update_index_settings", "index_path:[{}], settings:{:?}", index_path, settings);
+
+    let index_reader_bridge = FFI_INDEX_SEARCHER_CACHE
+        .get_index_reader_bridge(index_path.to_string())
+        .ok();
+    let index_writer_bridge = FFI_INDEX_WRITER_CACHE
+        .get_index_writer_bridge(index_path.to_string())
+        .ok();
+    let updates_reader: bool = settings.slow_query_threshold_ms.is_some()
+        || settings.persistent_query_cache_mb.is_some()
+        || settings.similarity.is_some()
+        || settings.bm25_k1.is_some()
+        || settings.bm25_b.is_some();
+    if updates_reader && index_reader_bridge.is_none() {
+        let error_info = format!("Index reader is not loaded: {}", index_path);
+        ERROR!(function: "update_index_settings", "{}", error_info);
+        return Err(TantivySearchError::IndexNotExists(error_info));
+    }
+    let updates_writer: bool = settings.auto_commit_interval_secs.is_some()
+        || settings.commit_durability.is_some()
+        || settings.direct_io.is_some();
+    if updates_writer && index_writer_bridge.is_none() {
+        let error_info = format!("Index writer is not loaded: {}", index_path);
+        ERROR!(function: "update_index_settings", "{}", error_info);
+        return Err(TantivySearchError::IndexNotExists(error_info));
+    }
+
+    let commit_durability: Option<CommitDurability> = match &settings.commit_durability {
+        Some(name) => Some(CommitDurability::from_name(name).map_err(|e| {
+            ERROR!(function: "update_index_settings", "{}", e);
+            TantivySearchError::InvalidArgument(e)
+        })?),
+        None => None,
+    };
+
+    let similarity: Option<Similarity> = match &index_reader_bridge {
+        Some(index_reader_bridge)
+            if settings.similarity.is_some()
+                || settings.bm25_k1.is_some()
+                || settings.bm25_b.is_some() =>
+        {
+            let current: Similarity = index_reader_bridge
+                .similarity()
+                .unwrap_or(Similarity::Bm25(Bm25Params::default()));
+            let name: &str = settings.similarity.as_deref().unwrap_or(current.name());
+            // BM25 parameters not given are kept, switching from another similarity uses defaults.
+            let current_params: Option<Bm25Params> = match current {
+                Similarity::Bm25(bm25_params) => Some(bm25_params),
+                _ => None,
+            };
+            let bm25_params: Option<Bm25Params> = match (settings.bm25_k1, settings.bm25_b) {
+                (None, None) => current_params.filter(|_| name == "bm25"),
+                (k1, b) => {
+                    let current_params: Bm25Params = current_params.unwrap_or_default();
+                    let bm25_params = Bm25Params::new(
+                        k1.unwrap_or(current_params.k1),
+                        b.unwrap_or(current_params.b),
+                    )
+                    .map_err(|e| {
+                        ERROR!(function: "update_index_settings", "{}", e);
+                        TantivySearchError::InvalidArgument(e)
+                    })?;
+                    Some(bm25_params)
+                }
+            };
+            let similarity: Similarity = Similarity::from_name(name, bm25_params).map_err(|e| {
+                ERROR!(function: "update_index_settings", "{}", e);
+                TantivySearchError::InvalidArgument(e)
+            })?;
+            Some(similarity)
+        }
+        _ => None,
+    };
+
+    // Cache directory is created before any setting is applied.
+    let query_result_cache: Option<Option<Arc<QueryResultCache>>> =
+        match settings.persistent_query_cache_mb {
+            Some(0) => Some(None),
+            Some(capacity_mb) => {
+                let query_result_cache =
+                    QueryResultCache::open(index_path, capacity_mb.saturating_mul(1024 * 1024))
+                        .map_err(|e| {
+                            ERROR!(function: "update_index_settings", "{}", e);
+                            TantivySearchError::InternalError(e)
+                        })?;
+                Some(Some(Arc::new(query_result_cache)))
+            }
+            None => None,
+        };
+
+    if let (Some(threshold_ms), Some(index_reader_bridge)) =
+        (settings.slow_query_threshold_ms, &index_reader_bridge)
+    {
+        index_reader_bridge.set_slow_query_threshold(threshold_ms);
+    }
+    if let (Some(similarity), Some(index_reader_bridge)) = (similarity, &index_reader_bridge) {
+        index_reader_bridge.set_similarity(similarity);
+    }
+    if let (Some(query_result_cache), Some(index_reader_bridge)) =
+        (query_result_cache, &index_reader_bridge)
+    {
+        index_reader_bridge.set_query_result_cache(query_result_cache);
+    }
+
+    if let (Some(commit_durability), Some(index_writer_bridge)) =
+        (commit_durability, &index_writer_bridge)
+    {
+        index_writer_bridge
+            .write_options
+            .set_durability(commit_durability);
+    }
+    if let (Some(direct_io), Some(index_writer_bridge)) = (settings.direct_io, &index_writer_bridge)
+    {
+        index_writer_bridge.write_options.set_direct_io(direct_io);
+    }
+    if let (Some(interval_secs), Some(index_writer_bridge)) =
+        (settings.auto_commit_interval_secs, &index_writer_bridge)
+    {
+        let max_docs: u64 = index_writer_bridge
+            .auto_commit_config()
+            .map_or(0, |config| config.max_docs);
+        let config = AutoCommitConfig {
+            interval_secs,
+            max_docs,
+        };
+        if config.is_valid() {
+            index_writer_bridge.start_auto_commit(config).map_err(|e| {
+                ERROR!(function: "update_index_settings", "{}", e);
+                TantivySearchError::InternalError(e)
+            })?;
+        } else {
+            index_writer_bridge.stop_auto_commit();
+        }
+    }
+
+    INFO!(function: "update_index_settings", "Settings updated, index_path:[{}], settings:{:?}", index_path, settings);
+    Ok(true)
+}
+
+pub fn set_writer_backpressure(
+    index_path: &str,
+    max_uncommitted_bytes: u64,
+    max_segments: u64,
+    block_timeout_millis: u64,
+) -> Result<bool, TantivySearchError> {
+    let index_writer_bridge: Arc<IndexWriterBridge> = FFI_INDEX_WRITER_CACHE
+        .get_index_writer_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function: "set_writer_backpressure", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+    index_writer_bridge
+        .set_backpressure_config(BackpressureConfig {
+            max_uncommitted_bytes,
+            max_segments,
+            block_timeout_millis,
+        })
+        .map_err(|e| {
+            ERROR!(function: "set_writer_backpressure", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+    Ok(true)
+}
+
+/// Enable row_id verification for an index writer, `mode` is one of
+/// `disabled`, `increasing` or `unique`, violations are rejected if `reject` is true.
+pub fn set_row_id_check(
+    index_path: &str,
+    mode: &str,
+    reject: bool,
+) -> Result<bool, TantivySearchError> {
+    let mode: RowIdCheckMode = RowIdCheckMode::from_name(mode).ok_or_else(|| {
+        let error_info = format!(
+            "Unknown row_id check mode `{}`, expect `disabled`, `increasing` or `unique`",
+            mode
+        );
+        ERROR!(function: "set_row_id_check", "{}", error_info);
+        TantivySearchError::InvalidArgument(error_info)
+    })?;
+    let index_writer_bridge: Arc<IndexWriterBridge> = FFI_INDEX_WRITER_CACHE
+        .get_index_writer_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function: "set_row_id_check", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+    index_writer_bridge
+        .set_row_id_check(mode, reject)
+        .map_err(|e| {
+            ERROR!(function: "set_row_id_check", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+    INFO!(function: "set_row_id_check", "Row_id check mode:{:?}, reject:{}, index_path:[{}]", mode, reject, index_path);
+    Ok(true)
+}
+
 pub fn free_index_writer(index_path: &str) -> Result<bool, TantivySearchError> {
     // get index writer bridge from CACHE
     let index_writer_bridge: Arc<IndexWriterBridge> =
@@ -428,6 +1408,14 @@ pub fn free_index_writer(index_path: &str) -> Result<bool, TantivySearchError> {
                 return Ok(false);
             }
         };
+    // Settings are read before the writer is touched, a failure leaves the writer loaded.
+    let index_parameter_dto: IndexParameterDTO =
+        IndexUtils::load_custom_index_setting(Path::new(index_path)).map_err(|e| {
+            ERROR!(function: "free_index_writer", "{}", e);
+            TantivySearchError::IndexUtilsError(e)
+        })?;
+    // Background auto commit task must be stopped before writer been taken.
+    index_writer_bridge.stop_auto_commit();
     index_writer_bridge.wait_merging_threads().map_err(|e| {
         let error_info = format!("Can't wait merging threads, exception: {}", e);
         ERROR!(function: "free_index_writer", "{}", error_info);
@@ -442,106 +1430,484 @@ pub fn free_index_writer(index_path: &str) -> Result<bool, TantivySearchError> {
             TantivySearchError::InternalError(e)
         })?;
 
-    DEBUG!(function: "free_index_writer", "Index writer has been freed:[{}]", index_path);
-    Ok(true)
-}
-
-pub fn load_index_writer(index_path: &str) -> Result<bool, TantivySearchError> {
-    // Verify index files directory.
-    let index_files_directory = Path::new(index_path);
-    if !index_files_directory.exists() || !index_files_directory.is_dir() {
-        let error_info: String = format!("index_path not exists: {:?}", index_path);
-        let error: TantivySearchError = TantivySearchError::IndexNotExists(error_info);
-        ERROR!(function:"load_index_writer", "{}", error.to_string());
-        return Err(error);
+    // Writer lock has been released, owner file is useless now.
+    if let Err(e) = WriterLockUtils::remove_writer_owner(Path::new(index_path)) {
+        WARNING!(function: "free_index_writer", "Failed to remove writer owner file: {}", e);
     }
 
-    // Load tantivy index with given directory.
-    let mut index: Index = Index::open_in_dir(index_files_directory).map_err(|e| {
-        let error: TantivySearchError = TantivySearchError::TantivyError(e);
-        ERROR!(function:"load_index_writer", "{}", error.to_string());
-        error
-    })?;
-
-    // Load index parameter DTO from local index files.
-    let index_parameter_dto: IndexParameterDTO =
-        IndexUtils::load_custom_index_setting(index_files_directory).map_err(|e| {
-            ERROR!(function:"load_index_writer", "{}", e);
+    // Segment ids of deterministic build are derived after all segments are flushed.
+    if index_parameter_dto.deterministic_build {
+        let renamed = SegmentIdUtils::derive_segment_ids(Path::new(index_path)).map_err(|e| {
+            ERROR!(function: "free_index_writer", "Failed to derive segment ids: {}", e);
             TantivySearchError::IndexUtilsError(e)
         })?;
+        DEBUG!(function: "free_index_writer", "Derived {} segment ids, index_path:[{}]", renamed, index_path);
+    }
 
-    DEBUG!(function:"load_index_writer", "parameter DTO is {:?}", index_parameter_dto);
-
-    // Parse tokenizer map from local index parameter DTO.
-    let col_tokenizer_map: HashMap<String, TokenizerConfig> =
-        TokenizerUtils::parse_tokenizer_json_to_config_map(
-            &index_parameter_dto.tokenizers_json_parameter,
-        )
-        .map_err(|e| {
-            ERROR!(function:"load_index_writer", "{}", e);
-            TantivySearchError::TokenizerUtilsError(e)
-        })?;
+    DEBUG!(function: "free_index_writer", "Index writer has been freed:[{}]", index_path);
+    Ok(true)
+}
 
-    // Register tokenizer config into `index`.
-    for (column_name, tokenizer_config) in col_tokenizer_map.iter() {
-        TokenizerUtils::register_tokenizer_to_index(
-            &mut index,
-            tokenizer_config.tokenizer_type.clone(),
-            &column_name,
-            tokenizer_config.text_analyzer.clone(),
-        )
-        .map_err(|e| {
-            ERROR!(function:"load_index_writer", "{}", e);
-            TantivySearchError::TokenizerUtilsError(e)
-        })?;
+/// Assign an index path to `namespace`, so it can be listed and freed with its namespace.
+/// Index doesn't need to be loaded yet, the namespace is kept until `free_namespace`.
+pub fn register_index_namespace(
+    index_path: &str,
+    namespace: &str,
+) -> Result<bool, TantivySearchError> {
+    if namespace.is_empty() {
+        let error_info = "namespace can't be empty".to_string();
+        ERROR!(function: "register_index_namespace", "{}", error_info);
+        return Err(TantivySearchError::InvalidArgument(error_info));
     }
+    INDEX_NAMESPACES.register(namespace, index_path);
+    DEBUG!(function: "register_index_namespace", "namespace:{}, index_path:[{}]", namespace, index_path);
+    Ok(true)
+}
 
-    #[cfg(feature = "use-shared-search-pool")]
-    {
-        // Set the multithreaded executor for search.
-        match FFI_INDEX_SEARCHER_CACHE.get_shared_multithread_executor(2) {
-            Ok(shared_thread_pool) => {
-                index.set_executor(shared_thread_pool.as_ref().clone());
-                DEBUG!(function:"load_index_writer", "Using shared multithread with index_path: [{}]", index_path);
-            }
+/// Free index writers and readers of all indexes in `namespace`, return number of indexes freed.
+/// Indexes failed to be freed are kept in namespace, and the first error is returned.
+pub fn free_namespace(namespace: &str) -> Result<u64, TantivySearchError> {
+    let mut freed: u64 = 0;
+    let mut first_error: Option<TantivySearchError> = None;
+    for index_path in INDEX_NAMESPACES.keys_of(namespace) {
+        let writer_freed = match free_index_writer(&index_path) {
+            Ok(writer_freed) => writer_freed,
             Err(e) => {
-                ERROR!(function:"load_index_writer", "Failed to use shared multithread executor, due to: {}", e);
-                index.set_default_multithread_executor().map_err(|e| {
-                    ERROR!(function:"load_index_writer", "Failed fall back to default multithread executor, due to: {}", e);
-                    TantivySearchError::TantivyError(e)
-                })?;
+                ERROR!(function: "free_namespace", "Failed to free index writer [{}], {}", index_path, e);
+                first_error.get_or_insert(e);
+                continue;
             }
+        };
+        let reader_freed = free_index_reader(&index_path)?;
+        INDEX_NAMESPACES.unregister(&index_path);
+        if writer_freed || reader_freed {
+            freed += 1;
         }
     }
-    #[cfg(not(feature = "use-shared-search-pool"))]
-    {
-        index.set_default_multithread_executor().map_err(|e| {
-            ERROR!(function:"load_index_writer", "Failed to set default multithread executor, due to: {}", e);
-            TantivySearchError::TantivyError(e)
-        })?;
+    INFO!(function: "free_namespace", "Freed {} indexes of namespace:{}", freed, namespace);
+    match first_error {
+        Some(error) => Err(error),
+        None => Ok(freed),
     }
+}
 
-    // Create the writer with a specified buffer size (e.g., 64 MB).
-    let writer = index
-        .writer_with_num_threads(2, 1024 * 1024 * 64)
-        .map_err(|e| {
-            let error_info = format!("Failed to create tantivy writer: {}", e);
-            ERROR!(function:"load_index_writer", "{}", error_info);
-            TantivySearchError::TantivyError(e)
-        })?;
-
-    // Configure and set the merge policy.
-    let mut merge_policy = tantivy::merge_policy::LogMergePolicy::default();
-    merge_policy.set_min_num_segments(5);
-    writer.set_merge_policy(Box::new(merge_policy));
+/// Unload index writers and readers not accessed within `idle_timeout_millis`, return number of
+/// bridges unloaded. Pending writes are committed before writer is freed. Readers with pinned
+/// searchers or lightweight deleted row_ids are kept, because these states only live in memory.
+pub fn unload_idle_indexes(idle_timeout_millis: u64) -> u64 {
+    let deadline_millis: u64 = TimeUtils::now_millis().saturating_sub(idle_timeout_millis);
+    let mut unloaded: u64 = 0;
 
-    // Save index_writer_bridge to cache.
-    let index_writer_bridge: IndexWriterBridge = IndexWriterBridge {
-        index,
-        path: index_path.trim_end_matches('/').to_string(),
-        writer: Mutex::new(Some(writer)),
+    for (index_path, index_writer_bridge) in FFI_INDEX_WRITER_CACHE.all_index_writer_bridges() {
+        if index_writer_bridge
+            .stats
+            .last_access_millis
+            .load(Ordering::Relaxed)
+            > deadline_millis
+        {
+            continue;
+        }
+        if index_writer_bridge.uncommitted_operations() > 0 {
+            if let Err(e) = index_writer_bridge.commit() {
+                ERROR!(function: "unload_idle_indexes", "Failed to commit idle index writer [{}], {}", index_path, e);
+                continue;
+            }
+            // Cached reader is reloaded manually, make the commit visible to it.
+            if let Ok(index_reader_bridge) =
+                FFI_INDEX_SEARCHER_CACHE.get_index_reader_bridge(index_path.clone())
+            {
+                let _ = index_reader_bridge.reload();
+            }
+        }
+        drop(index_writer_bridge);
+        match free_index_writer(&index_path) {
+            Ok(_) => {
+                INFO!(function: "unload_idle_indexes", "Idle index writer has been unloaded:[{}]", index_path);
+                unloaded += 1;
+            }
+            Err(e) => {
+                ERROR!(function: "unload_idle_indexes", "Failed to free idle index writer [{}], {}", index_path, e);
+            }
+        }
+    }
+
+    for (index_path, index_reader_bridge) in FFI_INDEX_SEARCHER_CACHE.all_index_reader_bridges() {
+        if index_reader_bridge
+            .last_access_millis
+            .load(Ordering::Relaxed)
+            > deadline_millis
+            || index_reader_bridge.has_pinned_searchers()
+            || index_reader_bridge
+                .deleted_row_ids()
+                .map_or(false, |deleted_row_ids| !deleted_row_ids.is_empty())
+        {
+            continue;
+        }
+        drop(index_reader_bridge);
+        if let Ok(true) = free_index_reader(&index_path) {
+            INFO!(function: "unload_idle_indexes", "Idle index reader has been unloaded:[{}]", index_path);
+            unloaded += 1;
+        }
+    }
+    unloaded
+}
+
+/// Start background janitor unloading indexes idle for `idle_timeout_secs`,
+/// it checks every `check_interval_secs` seconds and replaces the previous janitor if exists.
+pub fn enable_idle_index_unload(
+    idle_timeout_secs: u64,
+    check_interval_secs: u64,
+) -> Result<bool, TantivySearchError> {
+    let config = IdleUnloadConfig {
+        idle_timeout_secs,
+        check_interval_secs,
+    };
+    if !config.is_valid() {
+        let error_info =
+            "`idle_timeout_secs` and `check_interval_secs` should be positive".to_string();
+        ERROR!(function: "enable_idle_index_unload", "{}", error_info);
+        return Err(TantivySearchError::InvalidArgument(error_info));
+    }
+    disable_idle_index_unload()?;
+    let janitor = IdleIndexJanitor::spawn(config).map_err(|e| {
+        ERROR!(function: "enable_idle_index_unload", "{}", e);
+        TantivySearchError::InternalError(e)
+    })?;
+    let mut idle_index_janitor = IDLE_INDEX_JANITOR.lock().map_err(|e| {
+        let error_info = format!("Lock error: {}", e);
+        ERROR!(function: "enable_idle_index_unload", "{}", error_info);
+        TantivySearchError::InternalError(error_info)
+    })?;
+    *idle_index_janitor = Some(janitor);
+    INFO!(function: "enable_idle_index_unload", "Idle index unload enabled, idle_timeout_secs:{}, check_interval_secs:{}", idle_timeout_secs, check_interval_secs);
+    Ok(true)
+}
+
+/// Stop background janitor, return `false` if it's not running.
+pub fn disable_idle_index_unload() -> Result<bool, TantivySearchError> {
+    let janitor = IDLE_INDEX_JANITOR
+        .lock()
+        .map_err(|e| {
+            let error_info = format!("Lock error: {}", e);
+            ERROR!(function: "disable_idle_index_unload", "{}", error_info);
+            TantivySearchError::InternalError(error_info)
+        })?
+        .take();
+    match janitor {
+        Some(janitor) => {
+            janitor.stop();
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// What `shutdown` does with pending writes of loaded index writers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownPolicy {
+    /// Commit pending writes, writers failed to commit are kept loaded.
+    Commit,
+    /// Discard pending writes.
+    Abort,
+}
+
+impl ShutdownPolicy {
+    pub fn from_name(name: &str) -> Option<ShutdownPolicy> {
+        match name.to_lowercase().as_str() {
+            "commit" | "" => Some(ShutdownPolicy::Commit),
+            "abort" => Some(ShutdownPolicy::Abort),
+            _ => None,
+        }
+    }
+}
+
+/// Shut the library down on clean exit of host, so no internal thread outlives it.
+/// Background janitor and trace profiler are stopped, commits scheduled on commit pool are
+/// waited for `timeout_millis` (0 waits forever), then all index writers are freed with their
+/// pending writes committed or discarded by `policy` (`commit` or `abort`), searcher handles
+/// are released, readers are freed and caches are cleared. Nothing is freed if commits are
+/// still running after timeout. Return number of bridges freed. Writers failed to commit are
+/// kept loaded and the first error is returned, shutdown can be retried, e.g. with `abort`.
+pub fn shutdown(policy: &str, timeout_millis: u64) -> Result<u64, TantivySearchError> {
+    let policy: ShutdownPolicy = ShutdownPolicy::from_name(policy).ok_or_else(|| {
+        let error_info = format!(
+            "Unknown shutdown policy `{}`, expect `commit` or `abort`",
+            policy
+        );
+        ERROR!(function: "shutdown", "{}", error_info);
+        TantivySearchError::InvalidArgument(error_info)
+    })?;
+    disable_idle_index_unload()?;
+    if let Err(e) = TraceProfiler::stop() {
+        WARNING!(function: "shutdown", "Failed to stop trace profiler: {}", e);
+    }
+    if !drain_commits(timeout_millis)? {
+        let error_info = format!("Commits are still running after {}ms", timeout_millis);
+        ERROR!(function: "shutdown", "{}", error_info);
+        return Err(TantivySearchError::InternalError(error_info));
+    }
+    // Part writers of multi-part builds are freed with other writers.
+    let aborted_builds: usize = MULTI_PART_BUILDS
+        .remove_all()
+        .map_err(|e| {
+            ERROR!(function: "shutdown", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?
+        .len();
+
+    let mut freed: u64 = 0;
+    let mut first_error: Option<TantivySearchError> = None;
+    for (index_path, index_writer_bridge) in FFI_INDEX_WRITER_CACHE.all_index_writer_bridges() {
+        if policy == ShutdownPolicy::Commit && index_writer_bridge.uncommitted_operations() > 0 {
+            if let Err(e) = index_writer_bridge.commit() {
+                ERROR!(function: "shutdown", "Failed to commit index writer [{}], {}", index_path, e);
+                first_error.get_or_insert(TantivySearchError::InternalError(e));
+                continue;
+            }
+        }
+        drop(index_writer_bridge);
+        match free_index_writer(&index_path) {
+            Ok(writer_freed) => freed += writer_freed as u64,
+            Err(e) => {
+                ERROR!(function: "shutdown", "Failed to free index writer [{}], {}", index_path, e);
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+
+    let released_handles: usize = SEARCHER_HANDLES.release_all().map_err(|e| {
+        ERROR!(function: "shutdown", "{}", e);
+        TantivySearchError::InternalError(e)
+    })?;
+    for (index_path, _) in FFI_INDEX_SEARCHER_CACHE.all_index_reader_bridges() {
+        if free_index_reader(&index_path)? {
+            freed += 1;
+        }
+    }
+    CACHE_FOR_SKIP_INDEX.clear();
+
+    INFO!(function: "shutdown", "Shutdown with policy:{:?}, freed {} bridges, released {} searcher handles, aborted {} multi-part builds", policy, freed, released_handles, aborted_builds);
+    match first_error {
+        Some(error) => Err(error),
+        None => Ok(freed),
+    }
+}
+
+pub fn load_index_writer(index_path: &str) -> Result<bool, TantivySearchError> {
+    load_index_writer_with_lock_recovery(index_path, false)
+}
+
+/// Try to recover a writer lock left by a crashed process.
+/// The lock will be broken only when it's owner process has died, return `true` if lock has been broken.
+pub fn recover_stale_writer_lock(index_path: &str) -> Result<bool, TantivySearchError> {
+    let index_files_directory: &Path = &PathUtils::index_directory(index_path);
+    let owner = WriterLockUtils::load_writer_owner(index_files_directory).map_err(|e| {
+        ERROR!(function:"recover_stale_writer_lock", "{}", e);
+        TantivySearchError::IndexUtilsError(e)
+    })?;
+
+    let owner = match owner {
+        Some(owner) => owner,
+        None => {
+            WARNING!(function:"recover_stale_writer_lock", "Writer owner is unknown, won't break lock for index_path: [{}]", index_path);
+            return Ok(false);
+        }
+    };
+
+    // Only an owner whose process has died is stale, including the current process, writers
+    // opened outside writer cache (reindex, multi-part build, upgrade) hold the lock too. The OS
+    // releases the file lock of a dead process, so breaking only cleans up its leftover files.
+    if !WriterLockUtils::is_owner_stale(&owner) {
+        WARNING!(function:"recover_stale_writer_lock", "Writer lock is held by an active owner (pid: {}), index_path: [{}]", owner.pid, index_path);
+        return Ok(false);
+    }
+
+    WARNING!(function:"recover_stale_writer_lock", "Breaking stale writer lock, owner pid: {}, locked at: {}ms, index_path: [{}]", owner.pid, owner.recorded_millis, index_path);
+    WriterLockUtils::break_writer_lock(index_files_directory).map_err(|e| {
+        ERROR!(function:"recover_stale_writer_lock", "{}", e);
+        TantivySearchError::IndexUtilsError(e)
+    })?;
+    Ok(true)
+}
+
+/// Load index writer, if `break_stale_lock` is true, a writer lock left by a crashed process will be broken.
+pub fn load_index_writer_with_lock_recovery(
+    index_path: &str,
+    break_stale_lock: bool,
+) -> Result<bool, TantivySearchError> {
+    // Verify index files directory.
+    let index_files_directory: &Path = &PathUtils::index_directory(index_path);
+    if !index_files_directory.exists() || !index_files_directory.is_dir() {
+        let error_info: String = format!("index_path not exists: {:?}", index_path);
+        let error: TantivySearchError = TantivySearchError::IndexNotExists(error_info);
+        ERROR!(function:"load_index_writer", "{}", error.to_string());
+        return Err(error);
+    }
+
+    // Refuse index written by a newer version before tantivy reads its files.
+    IndexVersionUtils::check_index(index_files_directory).map_err(|e| {
+        ERROR!(function:"load_index_writer", "{}", e);
+        e
+    })?;
+
+    // Load tantivy index with given directory.
+    let write_options = Arc::new(IndexWriteOptions::new());
+    let mut index: Index =
+        open_durable_index(index_files_directory, &write_options).map_err(|e| {
+            let error: TantivySearchError = TantivySearchError::TantivyError(e);
+            ERROR!(function:"load_index_writer", "{}", error.to_string());
+            error
+        })?;
+
+    // Refuse loading before its files are opened by writer.
+    OPEN_FILES_BUDGET
+        .check_load(index_path, IndexBridgeKind::Writer)
+        .map_err(|e| {
+            ERROR!(function:"load_index_writer", "{}", e);
+            TantivySearchError::OpenFilesBudgetExceeded(e)
+        })?;
+
+    // Load index parameter DTO from local index files.
+    let index_parameter_dto: IndexParameterDTO =
+        IndexUtils::load_custom_index_setting(index_files_directory).map_err(|e| {
+            ERROR!(function:"load_index_writer", "{}", e);
+            TantivySearchError::IndexUtilsError(e)
+        })?;
+
+    DEBUG!(function:"load_index_writer", "parameter DTO is {:?}", index_parameter_dto);
+
+    // Parse tokenizer map from local index parameter DTO.
+    let col_tokenizer_map: HashMap<String, TokenizerConfig> =
+        TokenizerUtils::parse_tokenizer_json_to_config_map(
+            &index_parameter_dto.tokenizers_json_parameter,
+        )
+        .map_err(|e| {
+            ERROR!(function:"load_index_writer", "{}", e);
+            TantivySearchError::TokenizerUtilsError(e)
+        })?;
+
+    // Register tokenizer config into `index`.
+    for (column_name, tokenizer_config) in col_tokenizer_map.iter() {
+        TokenizerUtils::register_tokenizer_to_index(
+            &mut index,
+            tokenizer_config.tokenizer_type.clone(),
+            &column_name,
+            tokenizer_config.text_analyzer.clone(),
+        )
+        .map_err(|e| {
+            ERROR!(function:"load_index_writer", "{}", e);
+            TantivySearchError::TokenizerUtilsError(e)
+        })?;
+        TokenizerUtils::register_language_tokenizers(&mut index, column_name, tokenizer_config);
+    }
+
+    #[cfg(feature = "use-shared-search-pool")]
+    {
+        // Set the multithreaded executor for search.
+        match FFI_INDEX_SEARCHER_CACHE.get_shared_multithread_executor(2) {
+            Ok(shared_thread_pool) => {
+                index.set_executor(shared_thread_pool.as_ref().clone());
+                DEBUG!(function:"load_index_writer", "Using shared multithread with index_path: [{}]", index_path);
+            }
+            Err(e) => {
+                ERROR!(function:"load_index_writer", "Failed to use shared multithread executor, due to: {}", e);
+                index.set_default_multithread_executor().map_err(|e| {
+                    ERROR!(function:"load_index_writer", "Failed fall back to default multithread executor, due to: {}", e);
+                    TantivySearchError::TantivyError(e)
+                })?;
+            }
+        }
+    }
+    #[cfg(not(feature = "use-shared-search-pool"))]
+    {
+        index.set_default_multithread_executor().map_err(|e| {
+            ERROR!(function:"load_index_writer", "Failed to set default multithread executor, due to: {}", e);
+            TantivySearchError::TantivyError(e)
+        })?;
+    }
+
+    let deterministic_build: bool = index_parameter_dto.deterministic_build;
+    let index_sort: Option<IndexSort> = parse_index_sort(
+        "load_index_writer",
+        &index_parameter_dto.tokenizers_json_parameter,
+    )?;
+    let document_sorter: Option<DocumentSorter> =
+        build_document_sorter("load_index_writer", &index.schema(), index_sort.as_ref())?;
+    let language_router: Option<LanguageRouter> =
+        build_language_router("load_index_writer", &index.schema(), &col_tokenizer_map)?;
+    let date_formats: HashMap<Field, DateFormat> =
+        build_date_formats(&index.schema(), &col_tokenizer_map);
+    let geo_point_fields: HashSet<Field> =
+        build_geo_point_fields(&index.schema(), &col_tokenizer_map);
+    let vector_formats: HashMap<Field, VectorFormat> =
+        build_vector_formats(&index.schema(), &col_tokenizer_map);
+    let sparse_vector_formats: HashMap<Field, SparseVectorFormat> =
+        build_sparse_vector_formats(&index.schema(), &col_tokenizer_map);
+    let (writer, merge_progress) = match create_index_writer(
+        index_path,
+        &index,
+        deterministic_build,
+        INDEX_WRITER_MEMORY_BUDGET,
+    ) {
+        Ok(created) => created,
+        Err(TantivyError::LockFailure(lock_error, _)) if break_stale_lock => {
+            WARNING!(function:"load_index_writer", "Writer lock is busy: {:?}, try recover it.", lock_error);
+            if !recover_stale_writer_lock(index_path)? {
+                let error_info = format!("Writer lock is not stale, index_path: {}", index_path);
+                ERROR!(function:"load_index_writer", "{}", error_info);
+                return Err(TantivySearchError::InternalError(error_info));
+            }
+            create_index_writer(
+                index_path,
+                &index,
+                deterministic_build,
+                INDEX_WRITER_MEMORY_BUDGET,
+            )
+            .map_err(|e| {
+                let error_info = format!("Failed to create tantivy writer: {}", e);
+                ERROR!(function:"load_index_writer", "{}", error_info);
+                TantivySearchError::TantivyError(e)
+            })?
+        }
+        Err(e) => {
+            let error_info = format!("Failed to create tantivy writer: {}", e);
+            ERROR!(function:"load_index_writer", "{}", error_info);
+            return Err(TantivySearchError::TantivyError(e));
+        }
     };
 
+    // Record current process as writer owner, it's used for stale lock detection.
+    WriterLockUtils::record_writer_owner(index_files_directory).map_err(|e| {
+        ERROR!(function:"load_index_writer", "{}", e);
+        TantivySearchError::IndexUtilsError(e)
+    })?;
+
+    // Writer lock is held and nothing is indexed yet, files left by a crashed writer are orphans.
+    match OrphanFileUtils::clean_orphan_files(index_files_directory) {
+        Ok(reclaimed) if reclaimed.files > 0 => {
+            METRICS
+                .orphan_bytes_reclaimed
+                .fetch_add(reclaimed.bytes, Ordering::Relaxed);
+            INFO!(function:"load_index_writer", "Removed {} orphaned files, reclaimed {} bytes, index_path:[{}]", reclaimed.files, reclaimed.bytes, index_path);
+        }
+        Ok(_) => {}
+        Err(e) => {
+            WARNING!(function:"load_index_writer", "Failed to clean orphaned files: {}", e);
+        }
+    }
+
+    // Save index_writer_bridge to cache.
+    let index_writer_bridge: IndexWriterBridge =
+        IndexWriterBridge::new(PathUtils::index_key(index_path), index, writer)
+            .with_document_sorter(document_sorter)
+            .with_language_router(language_router)
+            .with_date_formats(date_formats)
+            .with_geo_point_fields(geo_point_fields)
+            .with_vector_formats(vector_formats)
+            .with_sparse_vector_formats(sparse_vector_formats)
+            .with_merge_progress(merge_progress)
+            .with_write_options(write_options);
+
     FFI_INDEX_WRITER_CACHE
         .set_index_writer_bridge(index_path.to_string(), Arc::new(index_writer_bridge))
         .map_err(|e| {
@@ -551,3 +1917,926 @@ pub fn load_index_writer(index_path: &str) -> Result<bool, TantivySearchError> {
 
     Ok(true)
 }
+
+pub fn get_index_health(index_path: &str) -> Result<IndexHealth, TantivySearchError> {
+    let index_writer_bridge = FFI_INDEX_WRITER_CACHE
+        .get_index_writer_bridge(index_path.to_string())
+        .ok();
+    let index_reader_bridge = FFI_INDEX_SEARCHER_CACHE
+        .get_index_reader_bridge(index_path.to_string())
+        .ok();
+
+    if index_writer_bridge.is_none() && index_reader_bridge.is_none() {
+        let error_info = format!("Neither index writer nor reader is loaded: {}", index_path);
+        ERROR!(function: "get_index_health", "{}", error_info);
+        return Err(TantivySearchError::IndexNotExists(error_info));
+    }
+
+    let mut index_health = IndexHealth {
+        canonical_path: CANONICAL_INDEX_PATHS.canonical_of(index_path),
+        writer_alive: false,
+        writer_busy: false,
+        reader_loaded: index_reader_bridge.is_some(),
+        reader_generation: 0,
+        last_commit_opstamp: 0,
+        lock_contention_count: 0,
+        last_error: String::new(),
+        row_id_mapping_bytes: 0,
+    };
+
+    if let Some(index_writer_bridge) = index_writer_bridge {
+        index_health.writer_alive = index_writer_bridge.is_writer_alive();
+        index_health.writer_busy = index_writer_bridge.is_writer_busy();
+        index_health.last_commit_opstamp = index_writer_bridge
+            .stats
+            .last_commit_opstamp
+            .load(Ordering::Relaxed);
+        index_health.lock_contention_count = index_writer_bridge
+            .stats
+            .lock_contention_count
+            .load(Ordering::Relaxed);
+        index_health.last_error = index_writer_bridge.last_error();
+    }
+
+    if let Some(index_reader_bridge) = index_reader_bridge {
+        index_health.reader_generation = index_reader_bridge.generation();
+        index_health.row_id_mapping_bytes = index_reader_bridge.row_id_mapping_bytes();
+    }
+
+    DEBUG!(function: "get_index_health", "index_path:[{}], health:{:?}", index_path, index_health);
+    Ok(index_health)
+}
+
+/// Liveness of background subsystems, `idle_janitor` is process-wide, `auto_commit` and
+/// `merges` belong to writer of `index_path`, only `idle_janitor` is reported if it's empty.
+/// Tantivy merge threads can't be observed, merges are alive while the writer is and beat
+/// when a merge of committed segments starts or ends.
+pub fn get_liveness(index_path: &str) -> Result<Vec<SubsystemLiveness>, TantivySearchError> {
+    let mut liveness: Vec<SubsystemLiveness> = Vec::new();
+    {
+        let idle_index_janitor = IDLE_INDEX_JANITOR.lock().map_err(|e| {
+            let error_info = format!("Lock error: {}", e);
+            ERROR!(function: "get_liveness", "{}", error_info);
+            TantivySearchError::InternalError(error_info)
+        })?;
+        liveness.push(SubsystemLiveness {
+            name: "idle_janitor".to_string(),
+            running: idle_index_janitor.is_some(),
+            alive: idle_index_janitor
+                .as_ref()
+                .map_or(false, |janitor| janitor.is_alive()),
+            last_heartbeat_millis: idle_index_janitor
+                .as_ref()
+                .map_or(0, |janitor| janitor.heartbeat.last_millis()),
+        });
+    }
+    if index_path.is_empty() {
+        return Ok(liveness);
+    }
+
+    let index_writer_bridge = FFI_INDEX_WRITER_CACHE
+        .get_index_writer_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function: "get_liveness", "{}", e);
+            TantivySearchError::IndexNotExists(e)
+        })?;
+    let auto_commit: Option<(bool, u64)> = index_writer_bridge.auto_commit_liveness();
+    liveness.push(SubsystemLiveness {
+        name: "auto_commit".to_string(),
+        running: auto_commit.is_some(),
+        alive: auto_commit.map_or(false, |(alive, _)| alive),
+        last_heartbeat_millis: auto_commit.map_or(0, |(_, heartbeat_millis)| heartbeat_millis),
+    });
+    let merge_progress = index_writer_bridge.merge_progress.as_ref();
+    liveness.push(SubsystemLiveness {
+        name: "merges".to_string(),
+        running: merge_progress.map_or(false, |merge_progress| {
+            merge_progress.running_merges.load(Ordering::Relaxed) > 0
+        }),
+        alive: merge_progress.is_some() && index_writer_bridge.is_writer_alive(),
+        last_heartbeat_millis: merge_progress
+            .map_or(0, |merge_progress| merge_progress.heartbeat.last_millis()),
+    });
+    Ok(liveness)
+}
+
+/// Liveness probe, `false` if a running subsystem is dead or its heartbeat is older than
+/// `max_heartbeat_age_millis`, host may restart the index worker then.
+pub fn probe_liveness(
+    index_path: &str,
+    max_heartbeat_age_millis: u64,
+) -> Result<bool, TantivySearchError> {
+    let now_millis: u64 = TimeUtils::now_millis();
+    let stale: Vec<SubsystemLiveness> = get_liveness(index_path)?
+        .into_iter()
+        .filter(|subsystem| {
+            subsystem.running
+                && (!subsystem.alive
+                    || now_millis.saturating_sub(subsystem.last_heartbeat_millis)
+                        > max_heartbeat_age_millis)
+        })
+        .collect();
+    if !stale.is_empty() {
+        WARNING!(function: "probe_liveness", "Subsystems aren't live, index_path:[{}], subsystems:{:?}", index_path, stale);
+    }
+    Ok(stale.is_empty())
+}
+
+/// Readiness probe, `true` if writer or reader of `index_path` is loaded
+/// and the writer, if it's loaded, still accepts documents.
+pub fn probe_readiness(index_path: &str) -> Result<bool, TantivySearchError> {
+    let index_writer_bridge = FFI_INDEX_WRITER_CACHE
+        .get_index_writer_bridge(index_path.to_string())
+        .ok();
+    let reader_loaded: bool = FFI_INDEX_SEARCHER_CACHE
+        .get_index_reader_bridge(index_path.to_string())
+        .is_ok();
+    Ok(match index_writer_bridge {
+        Some(index_writer_bridge) => index_writer_bridge.is_writer_alive(),
+        None => reader_loaded,
+    })
+}
+
+/// Describe every index held in writer and reader caches as json array, sorted by index path:
+/// `[{"index_path":"/data/a","namespace":"tenant_a","writer_loaded":true,"reader_loaded":true,
+/// "num_docs":100,"writer_uncommitted_bytes":0,"reader_memory_bytes":1600,"open_files":1,
+/// "last_access_millis":1700000000000}]`.
+/// `num_docs` is read from reader if it's loaded, otherwise from committed segments of writer.
+/// `open_files` is descriptors held by writer and reader, memory mapped segment files hold none.
+pub fn list_loaded_indexes() -> Result<String, TantivySearchError> {
+    let mut indexes: BTreeMap<
+        String,
+        (
+            Option<Arc<IndexWriterBridge>>,
+            Option<Arc<IndexReaderBridge>>,
+        ),
+    > = BTreeMap::new();
+    for (index_path, index_writer_bridge) in FFI_INDEX_WRITER_CACHE.all_index_writer_bridges() {
+        indexes.entry(index_path).or_default().0 = Some(index_writer_bridge);
+    }
+    for (index_path, index_reader_bridge) in FFI_INDEX_SEARCHER_CACHE.all_index_reader_bridges() {
+        indexes.entry(index_path).or_default().1 = Some(index_reader_bridge);
+    }
+
+    let mut loaded_indexes: Vec<serde_json::Value> = Vec::with_capacity(indexes.len());
+    for (canonical_path, (index_writer_bridge, index_reader_bridge)) in indexes {
+        // Path spelled by host when index was loaded, cache keys are canonical paths.
+        let index_path: &str = match (&index_writer_bridge, &index_reader_bridge) {
+            (Some(index_writer_bridge), _) => &index_writer_bridge.path,
+            (None, Some(index_reader_bridge)) => &index_reader_bridge.path,
+            (None, None) => &canonical_path,
+        };
+        let mut num_docs: u64 = 0;
+        let mut writer_uncommitted_bytes: u64 = 0;
+        let mut reader_memory_bytes: u64 = 0;
+        let mut open_files: u64 = 0;
+        let mut last_access_millis: u64 = 0;
+        if let Some(index_writer_bridge) = &index_writer_bridge {
+            num_docs = index_writer_bridge
+                .index
+                .searchable_segment_metas()
+                .map_err(|e| {
+                    ERROR!(function: "list_loaded_indexes", "{}", e);
+                    TantivySearchError::TantivyError(e)
+                })?
+                .iter()
+                .map(|segment_meta| segment_meta.num_docs() as u64)
+                .sum();
+            writer_uncommitted_bytes = index_writer_bridge
+                .stats
+                .uncommitted_bytes
+                .load(Ordering::Relaxed);
+            open_files += IndexBridgeKind::Writer.held_descriptors();
+            last_access_millis = index_writer_bridge
+                .stats
+                .last_access_millis
+                .load(Ordering::Relaxed);
+        }
+        if let Some(index_reader_bridge) = &index_reader_bridge {
+            num_docs = index_reader_bridge.reader.searcher().num_docs();
+            reader_memory_bytes = index_reader_bridge.row_id_mapping_bytes();
+            open_files += IndexBridgeKind::Reader.held_descriptors();
+            last_access_millis = last_access_millis.max(
+                index_reader_bridge
+                    .last_access_millis
+                    .load(Ordering::Relaxed),
+            );
+        }
+        loaded_indexes.push(json!({
+            "index_path": index_path,
+            "canonical_path": canonical_path,
+            "namespace": INDEX_NAMESPACES.namespace_of(&canonical_path),
+            "writer_loaded": index_writer_bridge.is_some(),
+            "reader_loaded": index_reader_bridge.is_some(),
+            "num_docs": num_docs,
+            "writer_uncommitted_bytes": writer_uncommitted_bytes,
+            "reader_memory_bytes": reader_memory_bytes,
+            "open_files": open_files,
+            "last_access_millis": last_access_millis,
+        }));
+    }
+
+    serde_json::to_string(&loaded_indexes).map_err(|e| {
+        let error_info = format!("Failed to serialize loaded indexes: {}", e);
+        ERROR!(function: "list_loaded_indexes", "{}", error_info);
+        TantivySearchError::InternalError(error_info)
+    })
+}
+
+/// Limit file descriptors held open by the process, 0 disables the limit.
+/// Loaded indexes are kept, later loads past the budget fail with `OpenFilesBudgetExceeded`.
+pub fn set_max_open_files(max_open_files: u64) -> Result<bool, TantivySearchError> {
+    OPEN_FILES_BUDGET.set_max_open_files(max_open_files);
+    let open_files = OPEN_FILES_BUDGET.open_files();
+    if max_open_files != 0 && open_files > max_open_files {
+        WARNING!(function: "set_max_open_files", "{} files already held open, exceed budget {}", open_files, max_open_files);
+    }
+    INFO!(function: "set_max_open_files", "Max open files:{}", max_open_files);
+    Ok(true)
+}
+
+/// File descriptors held open by the process.
+pub fn get_open_files() -> Result<u64, TantivySearchError> {
+    Ok(OPEN_FILES_BUDGET.open_files())
+}
+
+/// Limit merge I/O of all index writers in MB/s, 0 disables the limit.
+/// Budget is taken by input segments when merges start, merges over budget are deferred.
+pub fn set_merge_throttle(max_mb_per_sec: u64) -> Result<bool, TantivySearchError> {
+    MERGE_THROTTLE
+        .set_max_mb_per_sec(max_mb_per_sec)
+        .map_err(|e| {
+            ERROR!(function: "set_merge_throttle", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+    INFO!(function: "set_merge_throttle", "Merge throttle:{}MB/s", max_mb_per_sec);
+    Ok(true)
+}
+
+/// Free disk space in MB kept by commits and merges of all index writers.
+pub fn set_disk_space_reserve(min_free_mb: u64) -> Result<bool, TantivySearchError> {
+    DISK_SPACE_GUARD.set_min_free_bytes(min_free_mb.saturating_mul(1024 * 1024));
+    INFO!(function: "set_disk_space_reserve", "Disk space reserve:{}MB", min_free_mb);
+    Ok(true)
+}
+
+/// Threads merging per-segment row_id bitmaps of bitmap queries, 0 merges on calling thread.
+pub fn set_bitmap_union_threads(num_threads: u64) -> Result<bool, TantivySearchError> {
+    BITMAP_UNION_POOL
+        .set_num_threads(num_threads as usize)
+        .map_err(|e| {
+            ERROR!(function: "set_bitmap_union_threads", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+    INFO!(function: "set_bitmap_union_threads", "Bitmap union threads:{}", num_threads);
+    Ok(true)
+}
+
+/// Pin internal threads to `cpus`, empty `cpus` unpins them, see `ThreadAffinity`.
+pub fn set_thread_cpu_set(cpus: &[u32]) -> Result<bool, TantivySearchError> {
+    let cpus: Vec<usize> = cpus.iter().map(|cpu| *cpu as usize).collect();
+    THREAD_AFFINITY.set_cpus(&cpus).map_err(|e| {
+        ERROR!(function: "set_thread_cpu_set", "{}", e);
+        TantivySearchError::InvalidArgument(e)
+    })?;
+    INFO!(function: "set_thread_cpu_set", "Thread CPU set:{:?}", cpus);
+    Ok(true)
+}
+
+/// Memory held by the allocator of this library, see `AllocatorMemory`.
+pub fn get_allocator_stats() -> Result<AllocatorStats, TantivySearchError> {
+    AllocatorMemory::stats().map_err(|e| {
+        ERROR!(function: "get_allocator_stats", "{}", e);
+        TantivySearchError::InternalError(e)
+    })
+}
+
+/// Return memory the allocator of this library retains but doesn't use to the OS, e.g. after
+/// large merges.
+pub fn purge_allocator() -> Result<bool, TantivySearchError> {
+    AllocatorMemory::purge().map_err(|e| {
+        ERROR!(function: "purge_allocator", "{}", e);
+        TantivySearchError::InternalError(e)
+    })?;
+    INFO!(function: "purge_allocator", "Purged {} retained memory", AllocatorMemory::name());
+    Ok(true)
+}
+
+pub fn get_segment_delete_stats(
+    index_path: &str,
+) -> Result<Vec<SegmentDeleteStat>, TantivySearchError> {
+    // Prefer index in writer, it can see the latest committed segments.
+    let index: Index = match FFI_INDEX_WRITER_CACHE.get_index_writer_bridge(index_path.to_string())
+    {
+        Ok(index_writer_bridge) => index_writer_bridge.index.clone(),
+        Err(_) => match FFI_INDEX_SEARCHER_CACHE.get_index_reader_bridge(index_path.to_string()) {
+            Ok(index_reader_bridge) => index_reader_bridge.index.clone(),
+            Err(e) => {
+                ERROR!(function: "get_segment_delete_stats", "{}", e);
+                return Err(TantivySearchError::IndexNotExists(e));
+            }
+        },
+    };
+
+    let segment_metas = index.searchable_segment_metas().map_err(|e| {
+        ERROR!(function: "get_segment_delete_stats", "Failed to load segment metas: {}", e);
+        TantivySearchError::TantivyError(e)
+    })?;
+
+    let segment_delete_stats = segment_metas
+        .iter()
+        .map(|segment_meta| {
+            let max_doc = segment_meta.max_doc();
+            let num_deleted_docs = segment_meta.num_deleted_docs();
+            SegmentDeleteStat {
+                segment_id: segment_meta.id().uuid_string(),
+                max_doc,
+                num_deleted_docs,
+                deleted_ratio: if max_doc == 0 {
+                    0.0
+                } else {
+                    num_deleted_docs as f32 / max_doc as f32
+                },
+            }
+        })
+        .collect();
+    Ok(segment_delete_stats)
+}
+
+pub fn vacuum_index(index_path: &str, min_deleted_ratio: f32) -> Result<bool, TantivySearchError> {
+    if !(min_deleted_ratio > 0.0 && min_deleted_ratio <= 1.0) {
+        let error_info = format!(
+            "`min_deleted_ratio` should be in (0, 1], but got {}",
+            min_deleted_ratio
+        );
+        ERROR!(function: "vacuum_index", "{}", error_info);
+        return Err(TantivySearchError::InvalidArgument(error_info));
+    }
+
+    let index_writer_bridge: Arc<IndexWriterBridge> = FFI_INDEX_WRITER_CACHE
+        .get_index_writer_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function: "vacuum_index", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+
+    let segment_metas = index_writer_bridge
+        .index
+        .searchable_segment_metas()
+        .map_err(|e| {
+            ERROR!(function: "vacuum_index", "Failed to load segment metas: {}", e);
+            TantivySearchError::TantivyError(e)
+        })?;
+
+    // Only segments exceeding the deleted ratio need to be merged.
+    let segment_ids: Vec<_> = segment_metas
+        .iter()
+        .filter(|segment_meta| {
+            segment_meta.max_doc() > 0
+                && segment_meta.num_deleted_docs() as f32 / segment_meta.max_doc() as f32
+                    >= min_deleted_ratio
+        })
+        .map(|segment_meta| segment_meta.id())
+        .collect();
+
+    if segment_ids.is_empty() {
+        DEBUG!(function: "vacuum_index", "No segment needs vacuum, index_path:[{}]", index_path);
+        return Ok(false);
+    }
+
+    index_writer_bridge
+        .merge_segments(&segment_ids)
+        .map_err(|e| {
+            let error_info = format!("Failed to merge segments: {}", e);
+            ERROR!(function: "vacuum_index", "{}", error_info);
+            writer_failure(e, error_info)
+        })?;
+    INFO!(function: "vacuum_index", "Vacuumed {} segments, index_path:[{}]", segment_ids.len(), index_path);
+
+    if let Ok(index_reader_bridge) =
+        FFI_INDEX_SEARCHER_CACHE.get_index_reader_bridge(index_path.to_string())
+    {
+        let _ = index_reader_bridge.reload();
+    }
+    Ok(true)
+}
+
+/// Rewrite segments of an index written by an older version, or before versions were recorded,
+/// in current format by merging them into one, then record current versions. Loaded writer is
+/// used as is, otherwise a writer is loaded for the upgrade. Return false if it's up to date.
+pub fn upgrade_index(index_path: &str) -> Result<bool, TantivySearchError> {
+    let index_files_directory: &Path = &PathUtils::index_directory(index_path);
+    if !index_files_directory.is_dir() {
+        let error_info = format!("index_path not exists: {:?}", index_path);
+        ERROR!(function: "upgrade_index", "{}", error_info);
+        return Err(TantivySearchError::IndexNotExists(error_info));
+    }
+    let mut index_parameter_dto: IndexParameterDTO =
+        IndexVersionUtils::check_index(index_files_directory).map_err(|e| {
+            ERROR!(function: "upgrade_index", "{}", e);
+            e
+        })?;
+    if !IndexVersionUtils::is_outdated(&index_parameter_dto) {
+        DEBUG!(function: "upgrade_index", "Index is up to date, index_path:[{}]", index_path);
+        return Ok(false);
+    }
+
+    let writer_loaded: bool = FFI_INDEX_WRITER_CACHE
+        .get_index_writer_bridge(index_path.to_string())
+        .is_ok();
+    if !writer_loaded {
+        load_index_writer(index_path)?;
+    }
+    let rewritten = rewrite_segments(index_path);
+    if !writer_loaded {
+        free_index_writer(index_path)?;
+    }
+    let segment_count: usize = rewritten?;
+
+    IndexVersionUtils::stamp(&mut index_parameter_dto);
+    IndexUtils::save_custom_index_setting(index_files_directory, &index_parameter_dto).map_err(
+        |e| {
+            ERROR!(function: "upgrade_index", "{}", e);
+            TantivySearchError::IndexUtilsError(e)
+        },
+    )?;
+    INFO!(function: "upgrade_index", "Rewrote {} segments, index_path:[{}], parameter DTO:{:?}", segment_count, index_path, index_parameter_dto);
+
+    if let Ok(index_reader_bridge) =
+        FFI_INDEX_SEARCHER_CACHE.get_index_reader_bridge(index_path.to_string())
+    {
+        let _ = index_reader_bridge.reload();
+    }
+    Ok(true)
+}
+
+// Merge all segments of loaded writer into one, even a single segment is rewritten.
+fn rewrite_segments(index_path: &str) -> Result<usize, TantivySearchError> {
+    let index_writer_bridge: Arc<IndexWriterBridge> = FFI_INDEX_WRITER_CACHE
+        .get_index_writer_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function: "upgrade_index", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+    let segment_ids = index_writer_bridge
+        .index
+        .searchable_segment_ids()
+        .map_err(|e| {
+            ERROR!(function: "upgrade_index", "Failed to load segment ids: {}", e);
+            TantivySearchError::TantivyError(e)
+        })?;
+    if segment_ids.is_empty() {
+        return Ok(0);
+    }
+    index_writer_bridge
+        .merge_segments(&segment_ids)
+        .map_err(|e| {
+            let error_info = format!("Failed to rewrite segments: {}", e);
+            ERROR!(function: "upgrade_index", "{}", error_info);
+            writer_failure(e, error_info)
+        })?;
+    Ok(segment_ids.len())
+}
+
+/// Start tracking bulk build progress of a loaded index writer, previous progress is discarded.
+/// Progress is reported to host build progress callback every `report_interval_millis`,
+/// 0 disables reports, progress can still be queried by `get_build_progress`.
+pub fn start_build_progress(
+    index_path: &str,
+    total_docs: u64,
+    report_interval_millis: u64,
+) -> Result<bool, TantivySearchError> {
+    let index_writer_bridge: Arc<IndexWriterBridge> = FFI_INDEX_WRITER_CACHE
+        .get_index_writer_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function: "start_build_progress", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+    index_writer_bridge
+        .start_build_progress(total_docs, report_interval_millis)
+        .map_err(|e| {
+            ERROR!(function: "start_build_progress", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+    DEBUG!(function: "start_build_progress", "total_docs:{}, report_interval_millis:{}, index_path:[{}]", total_docs, report_interval_millis, index_path);
+    Ok(true)
+}
+
+/// Get bulk build progress, it should be started by `start_build_progress`.
+pub fn get_build_progress(index_path: &str) -> Result<BuildProgress, TantivySearchError> {
+    let index_writer_bridge: Arc<IndexWriterBridge> = FFI_INDEX_WRITER_CACHE
+        .get_index_writer_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function: "get_build_progress", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+    let build_progress = index_writer_bridge.build_progress().map_err(|e| {
+        ERROR!(function: "get_build_progress", "{}", e);
+        TantivySearchError::InternalError(e)
+    })?;
+    build_progress.ok_or_else(|| {
+        let error_info = format!("Build progress isn't started, index_path:[{}]", index_path);
+        ERROR!(function: "get_build_progress", "{}", error_info);
+        TantivySearchError::InvalidArgument(error_info)
+    })
+}
+
+/// Stop tracking bulk build progress, return `false` if it's not started.
+pub fn stop_build_progress(index_path: &str) -> Result<bool, TantivySearchError> {
+    let index_writer_bridge: Arc<IndexWriterBridge> = FFI_INDEX_WRITER_CACHE
+        .get_index_writer_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function: "stop_build_progress", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+    let build_progress = index_writer_bridge.stop_build_progress().map_err(|e| {
+        ERROR!(function: "stop_build_progress", "{}", e);
+        TantivySearchError::InternalError(e)
+    })?;
+    if let Some(build_progress) = &build_progress {
+        INFO!(function: "stop_build_progress", "Build finished with {} docs in {}ms, index_path:[{}]", build_progress.docs_indexed, build_progress.elapsed_millis, index_path);
+    }
+    Ok(build_progress.is_some())
+}
+
+/// Start a sorted build of a loaded index writer, rows added by `sorted_build_add_batch` in any
+/// order are indexed in row_id order by `finish_sorted_build`. Rows beyond `memory_limit_bytes`
+/// are sorted and spilled to `spill_directory`, empty means system temporary directory.
+pub fn start_sorted_build(
+    index_path: &str,
+    column_names: &Vec<String>,
+    memory_limit_bytes: u64,
+    spill_directory: &str,
+) -> Result<bool, TantivySearchError> {
+    let index_writer_bridge: Arc<IndexWriterBridge> = FFI_INDEX_WRITER_CACHE
+        .get_index_writer_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function: "start_sorted_build", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+    // Unknown columns are rejected before any row is buffered.
+    let schema = index_writer_bridge.index.schema();
+    for column_name in column_names {
+        schema.get_field(column_name).map_err(|e| {
+            ERROR!(function: "start_sorted_build", "Failed to get {} field in schema: {}", column_name, e.to_string());
+            TantivySearchError::TantivyError(e)
+        })?;
+    }
+    let sorter = ExternalRowSorter::new(column_names.clone(), memory_limit_bytes, spill_directory)
+        .map_err(|e| {
+            ERROR!(function: "start_sorted_build", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+    index_writer_bridge
+        .start_external_sort(sorter)
+        .map_err(|e| {
+            ERROR!(function: "start_sorted_build", "{}", e);
+            TantivySearchError::InvalidArgument(e)
+        })?;
+    INFO!(function: "start_sorted_build", "memory_limit_bytes:{}, spill_directory:[{}], index_path:[{}]", memory_limit_bytes, spill_directory, index_path);
+    Ok(true)
+}
+
+/// Buffer rows of a sorted build, laid out same as `index_column_batch`.
+/// Batches can be added concurrently in any row_id order.
+pub fn sorted_build_add_batch(
+    index_path: &str,
+    row_ids: &[u64],
+    column_data: &[u8],
+    column_offsets: &[u64],
+) -> Result<bool, TantivySearchError> {
+    let index_writer_bridge: Arc<IndexWriterBridge> = FFI_INDEX_WRITER_CACHE
+        .get_index_writer_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function: "sorted_build_add_batch", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+    index_writer_bridge
+        .add_external_sort_batch(row_ids, column_data, column_offsets)
+        .map_err(|e| {
+            ERROR!(function: "sorted_build_add_batch", "{}", e);
+            TantivySearchError::InvalidArgument(e)
+        })?;
+    Ok(true)
+}
+
+/// Index rows of a sorted build in row_id order and commit them, return rows indexed.
+/// The build is over afterwards even if indexing failed, spilled rows are removed.
+pub fn finish_sorted_build(index_path: &str) -> Result<u64, TantivySearchError> {
+    let index_writer_bridge: Arc<IndexWriterBridge> = FFI_INDEX_WRITER_CACHE
+        .get_index_writer_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function: "finish_sorted_build", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+    let sorter: ExternalRowSorter = index_writer_bridge
+        .take_external_sort()
+        .map_err(|e| {
+            ERROR!(function: "finish_sorted_build", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?
+        .ok_or_else(|| {
+            let error_info = format!("Sorted build isn't started, index_path:[{}]", index_path);
+            ERROR!(function: "finish_sorted_build", "{}", error_info);
+            TantivySearchError::InvalidArgument(error_info)
+        })?;
+    let column_names: Vec<String> = sorter.column_names.clone();
+    let spilled_runs: usize = sorter.spilled_runs();
+    let mut batches: SortedRowBatches =
+        sorter.into_sorted_batches(SORTED_BATCH_ROWS).map_err(|e| {
+            ERROR!(function: "finish_sorted_build", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+    let mut rows_indexed: u64 = 0;
+    while let Some(batch) = batches.next_batch().map_err(|e| {
+        ERROR!(function: "finish_sorted_build", "{}", e);
+        TantivySearchError::InternalError(e)
+    })? {
+        index_column_batch(
+            index_path,
+            &batch.row_ids,
+            &column_names,
+            &batch.column_data,
+            &batch.column_offsets,
+        )?;
+        rows_indexed += batch.row_ids.len() as u64;
+    }
+    commit_index(index_path)?;
+    INFO!(function: "finish_sorted_build", "Indexed {} rows merged from {} spilled runs, index_path:[{}]", rows_indexed, spilled_runs, index_path);
+    Ok(rows_indexed)
+}
+
+/// Create a multi-part build indexing `index_paths` concurrently on a pool of `num_threads`,
+/// writers of all parts share `memory_budget_bytes`. Return the build id.
+pub fn create_multi_part_build(
+    index_paths: &Vec<String>,
+    column_names: &Vec<String>,
+    index_json_parameter: &str,
+    memory_budget_bytes: u64,
+    num_threads: u64,
+) -> Result<u64, TantivySearchError> {
+    let builder = MultiPartBuilder::create(
+        index_paths.clone(),
+        column_names.clone(),
+        index_json_parameter,
+        memory_budget_bytes,
+        num_threads as usize,
+    )
+    .map_err(|e| {
+        ERROR!(function: "create_multi_part_build", "{}", e);
+        e
+    })?;
+    let build_id = MULTI_PART_BUILDS.insert(builder).map_err(|e| {
+        ERROR!(function: "create_multi_part_build", "{}", e);
+        TantivySearchError::InternalError(e)
+    })?;
+    INFO!(function: "create_multi_part_build", "build_id:{}, parts:{:?}", build_id, index_paths);
+    Ok(build_id)
+}
+
+/// Index a batch holding rows of several parts of a multi-part build,
+/// `part_ids[i]` is the position in `index_paths` of the part `row_ids[i]` belongs to.
+pub fn multi_part_build_index_batch(
+    build_id: u64,
+    part_ids: &[u64],
+    row_ids: &[u64],
+    column_data: &[u8],
+    column_offsets: &[u64],
+) -> Result<bool, TantivySearchError> {
+    let builder = MULTI_PART_BUILDS.get(build_id).map_err(|e| {
+        ERROR!(function: "multi_part_build_index_batch", "{}", e);
+        TantivySearchError::InvalidArgument(e)
+    })?;
+    builder.index_batch(part_ids, row_ids, column_data, column_offsets)?;
+    Ok(true)
+}
+
+/// Commit and free writers of all parts, the build id is invalid afterwards.
+pub fn finish_multi_part_build(build_id: u64) -> Result<bool, TantivySearchError> {
+    let builder = MULTI_PART_BUILDS.remove(build_id).map_err(|e| {
+        ERROR!(function: "finish_multi_part_build", "{}", e);
+        TantivySearchError::InvalidArgument(e)
+    })?;
+    builder.finish().map_err(|e| {
+        ERROR!(function: "finish_multi_part_build", "{}", e);
+        e
+    })?;
+    INFO!(function: "finish_multi_part_build", "build_id:{} finished", build_id);
+    Ok(true)
+}
+
+/// Get the row_id an interrupted bulk build should resume from, it's the build checkpoint
+/// (max row_id added before last commit) plus one, 0 if index is never committed with a checkpoint.
+/// Checkpoint is read from committed index files, so it works without loading index writer.
+pub fn get_build_checkpoint(index_path: &str) -> Result<u64, TantivySearchError> {
+    // Verify index files directory.
+    let index_files_directory: &Path = &PathUtils::index_directory(index_path);
+    if !index_files_directory.exists() || !index_files_directory.is_dir() {
+        let error_info: String = format!("index_path not exists: {:?}", index_path);
+        let error: TantivySearchError = TantivySearchError::IndexNotExists(error_info);
+        ERROR!(function: "get_build_checkpoint", "{}", error.to_string());
+        return Err(error);
+    }
+
+    let index: Index = Index::open_in_dir(index_files_directory).map_err(|e| {
+        let error: TantivySearchError = TantivySearchError::TantivyError(e);
+        ERROR!(function: "get_build_checkpoint", "{}", error.to_string());
+        error
+    })?;
+    let checkpoint_row_id = committed_checkpoint(&index).map_err(|e| {
+        ERROR!(function: "get_build_checkpoint", "{}", e);
+        TantivySearchError::InternalError(e)
+    })?;
+    DEBUG!(function: "get_build_checkpoint", "checkpoint_row_id:{:?}, index_path:[{}]", checkpoint_row_id, index_path);
+    Ok(checkpoint_row_id.map_or(0, |row_id| row_id.saturating_add(1)))
+}
+
+/// Estimate index size and memory requirements of `total_docs` documents by indexing a sample in RAM.
+/// `sample_docs` stores documents row by row, each row has `column_names.len()` values.
+/// Sizes are projected linearly from the sample, term dictionaries grow sublinearly,
+/// so the projection tends to be an upper bound for large tables.
+pub fn estimate_index_size(
+    column_names: &Vec<String>,
+    index_json_parameter: &str,
+    sample_docs: &Vec<String>,
+    total_docs: u64,
+) -> Result<IndexSizeEstimate, TantivySearchError> {
+    if column_names.is_empty()
+        || sample_docs.is_empty()
+        || sample_docs.len() % column_names.len() != 0
+    {
+        let error_info = format!(
+            "sample_docs size {} should be a positive multiple of column_names size {}",
+            sample_docs.len(),
+            column_names.len()
+        );
+        ERROR!(function: "estimate_index_size", "{}", error_info);
+        return Err(TantivySearchError::InvalidArgument(error_info));
+    }
+    let num_sample_docs: usize = sample_docs.len() / column_names.len();
+
+    let col_tokenizer_map: HashMap<String, TokenizerConfig> =
+        TokenizerUtils::parse_tokenizer_json_to_config_map(index_json_parameter).map_err(|e| {
+            ERROR!(function: "estimate_index_size", "{}", e.to_string());
+            TantivySearchError::TokenizerUtilsError(e)
+        })?;
+
+    let index_sort: Option<IndexSort> =
+        parse_index_sort("estimate_index_size", index_json_parameter)?;
+    check_language_subfields("estimate_index_size", column_names, &col_tokenizer_map)?;
+
+    // Build sample index in RAM with the same schema and tokenizers.
+    let schema: Schema = build_index_schema(
+        column_names,
+        &col_tokenizer_map,
+        index_sort.as_ref().map(|index_sort| &index_sort.field),
+    );
+    let language_router: Option<LanguageRouter> =
+        build_language_router("estimate_index_size", &schema, &col_tokenizer_map)?;
+    let date_formats: HashMap<Field, DateFormat> = build_date_formats(&schema, &col_tokenizer_map);
+    let geo_point_fields: HashSet<Field> = build_geo_point_fields(&schema, &col_tokenizer_map);
+    let vector_formats: HashMap<Field, VectorFormat> =
+        build_vector_formats(&schema, &col_tokenizer_map);
+    let sparse_vector_formats: HashMap<Field, SparseVectorFormat> =
+        build_sparse_vector_formats(&schema, &col_tokenizer_map);
+    let mut index: Index = Index::create_in_ram(schema.clone());
+    register_index_tokenizers(&mut index, &col_tokenizer_map)?;
+
+    let mut writer: IndexWriter = index
+        .writer_with_num_threads(1, INDEX_WRITER_MEMORY_BUDGET)
+        .map_err(|e| {
+            ERROR!(function: "estimate_index_size", "Failed to create tantivy writer: {}", e);
+            TantivySearchError::TantivyError(e)
+        })?;
+    let row_id_field = schema.get_field("row_id").map_err(|e| {
+        ERROR!(function: "estimate_index_size", "Failed to get row_id field: {}", e);
+        TantivySearchError::TantivyError(e)
+    })?;
+    let mut column_fields = Vec::with_capacity(column_names.len());
+    for column_name in column_names {
+        column_fields.push(schema.get_field(column_name).map_err(|e| {
+            ERROR!(function: "estimate_index_size", "Failed to get {} field in schema: {}", column_name, e);
+            TantivySearchError::TantivyError(e)
+        })?);
+    }
+
+    for (row, row_docs) in sample_docs.chunks(column_names.len()).enumerate() {
+        let mut doc = TantivyDocument::default();
+        doc.add_u64(row_id_field, row as u64);
+        for (column_field, column_doc) in column_fields.iter().zip(row_docs) {
+            match schema.get_field_entry(*column_field).field_type() {
+                FieldType::I64(_) => doc.add_i64(
+                    *column_field,
+                    column_doc.parse::<i64>().map_err(|e| {
+                        let error_info = format!("Can't parse `{}` as i64: {}", column_doc, e);
+                        ERROR!(function: "estimate_index_size", "{}", error_info);
+                        TantivySearchError::InvalidArgument(error_info)
+                    })?,
+                ),
+                FieldType::F64(_) => doc.add_f64(
+                    *column_field,
+                    column_doc.parse::<f64>().map_err(|e| {
+                        let error_info = format!("Can't parse `{}` as f64: {}", column_doc, e);
+                        ERROR!(function: "estimate_index_size", "{}", error_info);
+                        TantivySearchError::InvalidArgument(error_info)
+                    })?,
+                ),
+                FieldType::Bytes(_) if vector_formats.contains_key(column_field) => doc.add_bytes(
+                    *column_field,
+                    VectorFormat::encode(&vector_formats[column_field].parse(column_doc).map_err(
+                        |e| {
+                            ERROR!(function: "estimate_index_size", "{}", e);
+                            TantivySearchError::InvalidArgument(e)
+                        },
+                    )?),
+                ),
+                FieldType::Bytes(_) => doc.add_bytes(*column_field, column_doc.as_bytes()),
+                FieldType::U64(_) if geo_point_fields.contains(column_field) => doc.add_u64(
+                    *column_field,
+                    GeoPoint::parse(column_doc)
+                        .map_err(|e| {
+                            ERROR!(function: "estimate_index_size", "{}", e);
+                            TantivySearchError::InvalidArgument(e)
+                        })?
+                        .encode(),
+                ),
+                FieldType::Str(_) if sparse_vector_formats.contains_key(column_field) => doc
+                    .add_pre_tokenized_text(
+                        *column_field,
+                        sparse_vector_formats[column_field]
+                            .tokenize(column_doc)
+                            .map_err(|e| {
+                                ERROR!(function: "estimate_index_size", "{}", e);
+                                TantivySearchError::InvalidArgument(e)
+                            })?,
+                    ),
+                FieldType::Date(_) => doc.add_date(
+                    *column_field,
+                    date_formats[column_field].parse(column_doc).map_err(|e| {
+                        ERROR!(function: "estimate_index_size", "{}", e);
+                        TantivySearchError::InvalidArgument(e)
+                    })?,
+                ),
+                _ => {
+                    if let Some(language_field) = language_router
+                        .as_ref()
+                        .and_then(|router| router.language_field(*column_field, column_doc))
+                    {
+                        doc.add_text(language_field, column_doc);
+                    }
+                    doc.add_text(*column_field, column_doc)
+                }
+            }
+        }
+        writer.add_document(doc).map_err(|e| {
+            ERROR!(function: "estimate_index_size", "Failed to index sample doc: {}", e);
+            TantivySearchError::TantivyError(e)
+        })?;
+    }
+    writer.commit().map_err(|e| {
+        ERROR!(function: "estimate_index_size", "Failed to commit sample index: {}", e);
+        TantivySearchError::TantivyError(e)
+    })?;
+
+    let searcher = index
+        .reader()
+        .map_err(|e| {
+            ERROR!(function: "estimate_index_size", "Failed to load sample index: {}", e);
+            TantivySearchError::TantivyError(e)
+        })?
+        .searcher();
+    let sample_index_bytes: u64 = searcher
+        .space_usage()
+        .map_err(|e| {
+            ERROR!(function: "estimate_index_size", "Failed to compute space usage: {}", e);
+            TantivySearchError::InternalError(e.to_string())
+        })?
+        .total()
+        .get_bytes();
+    let mut sample_num_tokens: u64 = 0;
+    for segment_reader in searcher.segment_readers() {
+        for column_field in &column_fields {
+            if let FieldType::Str(_) = schema.get_field_entry(*column_field).field_type() {
+                let inverted_index = segment_reader
+                    .inverted_index(*column_field)
+                    .map_err(TantivySearchError::TantivyError)?;
+                sample_num_tokens += inverted_index.total_num_tokens();
+            }
+        }
+    }
+
+    let projection: f64 = total_docs as f64 / num_sample_docs as f64;
+    // Row_id mapping cached by each index reader, see `SegmentRowIdMapping`.
+    let row_id_mapping_bytes_per_doc: u64 =
+        (std::mem::size_of::<u64>() + std::mem::size_of::<(u64, u32)>()) as u64;
+    let estimate = IndexSizeEstimate {
+        sample_docs: num_sample_docs as u64,
+        sample_index_bytes,
+        estimated_index_bytes: (sample_index_bytes as f64 * projection) as u64,
+        estimated_num_tokens: (sample_num_tokens as f64 * projection) as u64,
+        estimated_reader_memory_bytes: total_docs * row_id_mapping_bytes_per_doc,
+        estimated_writer_memory_bytes: INDEX_WRITER_MEMORY_BUDGET as u64,
+    };
+    INFO!(function: "estimate_index_size", "{:?}", estimate);
+    Ok(estimate)
+}
@@ -0,0 +1,287 @@
+use crate::common::errors::TantivySearchError;
+use crate::common::thread_affinity::{thread_pool_builder, ThreadKind};
+use crate::index::implements::api_index_impl::commit_index;
+use crate::logger::logger_bridge::TantivySearchLogger;
+use crate::FFI_INDEX_WRITER_CACHE;
+use crate::{common::constants::LOG_CALLBACK, ERROR};
+use once_cell::sync::{Lazy, OnceCell};
+use rayon::ThreadPool;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+// Threads of commit pool, commits are heavy but not frequent.
+static COMMIT_THREADS: usize = 2;
+
+static COMMIT_POOL: OnceCell<ThreadPool> = OnceCell::new();
+
+// Commit tasks spawned on commit pool and not ended yet, so they can be drained.
+struct CommitsInFlight {
+    count: Mutex<usize>,
+    drained: Condvar,
+}
+
+static COMMITS_IN_FLIGHT: CommitsInFlight = CommitsInFlight {
+    count: Mutex::new(0),
+    drained: Condvar::new(),
+};
+
+impl CommitsInFlight {
+    fn add(&self, delta: isize) {
+        if let Ok(mut count) = self.count.lock() {
+            *count = count.saturating_add_signed(delta);
+            if *count == 0 {
+                self.drained.notify_all();
+            }
+        }
+    }
+
+    // Wait all commits ended, `false` if some are still running after `timeout_millis`,
+    // 0 waits forever.
+    fn wait_drained(&self, timeout_millis: u64) -> Result<bool, TantivySearchError> {
+        let deadline = Instant::now() + Duration::from_millis(timeout_millis);
+        let mut count = self
+            .count
+            .lock()
+            .map_err(|e| TantivySearchError::InternalError(format!("Lock error: {}", e)))?;
+        while *count > 0 {
+            count = if timeout_millis == 0 {
+                self.drained.wait(count)
+            } else {
+                let now = Instant::now();
+                if now >= deadline {
+                    return Ok(false);
+                }
+                self.drained
+                    .wait_timeout(count, deadline - now)
+                    .map(|(count, _)| count)
+            }
+            .map_err(|e| TantivySearchError::InternalError(format!("Lock error: {}", e)))?;
+        }
+        Ok(true)
+    }
+}
+
+// Run commit task on commit pool without waiting its result.
+fn spawn_commit_task<F>(task: F) -> Result<(), TantivySearchError>
+where
+    F: FnOnce() + Send + 'static,
+{
+    let pool = COMMIT_POOL
+        .get_or_try_init(|| {
+            thread_pool_builder(ThreadKind::Ingest, "commit")
+                .num_threads(COMMIT_THREADS)
+                .build()
+        })
+        .map_err(|e| {
+            TantivySearchError::InternalError(format!("Failed to build commit pool: {}", e))
+        })?;
+    COMMITS_IN_FLIGHT.add(1);
+    pool.spawn(move || {
+        task();
+        COMMITS_IN_FLIGHT.add(-1);
+    });
+    Ok(())
+}
+
+// Commit running on commit pool, `result` is set once commit ended.
+#[derive(Default)]
+struct CommitTicket {
+    result: Mutex<Option<Result<bool, TantivySearchError>>>,
+    done: Condvar,
+}
+
+impl CommitTicket {
+    fn complete(&self, result: Result<bool, TantivySearchError>) {
+        if let Ok(mut ticket_result) = self.result.lock() {
+            *ticket_result = Some(result);
+        }
+        self.done.notify_all();
+    }
+
+    // Wait commit ended, `None` if it's still running after `timeout_millis`, 0 waits forever.
+    fn wait(&self, timeout_millis: u64) -> Option<Result<bool, TantivySearchError>> {
+        let deadline = Instant::now() + Duration::from_millis(timeout_millis);
+        let mut result = match self.result.lock() {
+            Ok(result) => result,
+            Err(e) => return Some(Err(TantivySearchError::InternalError(e.to_string()))),
+        };
+        while result.is_none() {
+            result = if timeout_millis == 0 {
+                match self.done.wait(result) {
+                    Ok(result) => result,
+                    Err(e) => return Some(Err(TantivySearchError::InternalError(e.to_string()))),
+                }
+            } else {
+                let now = Instant::now();
+                if now >= deadline {
+                    return None;
+                }
+                match self.done.wait_timeout(result, deadline - now) {
+                    Ok((result, _)) => result,
+                    Err(e) => return Some(Err(TantivySearchError::InternalError(e.to_string()))),
+                }
+            };
+        }
+        result.clone()
+    }
+}
+
+/// Commits submitted by `commit_async`, a ticket is released once a wait observed its result.
+struct CommitTickets {
+    next_ticket: AtomicU64,
+    tickets: Mutex<HashMap<u64, Arc<CommitTicket>>>,
+}
+
+static COMMIT_TICKETS: Lazy<CommitTickets> = Lazy::new(|| CommitTickets {
+    next_ticket: AtomicU64::new(1),
+    tickets: Mutex::new(HashMap::new()),
+});
+
+impl CommitTickets {
+    fn lock_tickets(
+        &self,
+    ) -> Result<std::sync::MutexGuard<'_, HashMap<u64, Arc<CommitTicket>>>, TantivySearchError>
+    {
+        self.tickets
+            .lock()
+            .map_err(|e| TantivySearchError::InternalError(format!("Lock error: {}", e)))
+    }
+
+    fn submit(&self, index_path: &str) -> Result<u64, TantivySearchError> {
+        let ticket = self.next_ticket.fetch_add(1, Ordering::Relaxed);
+        let commit_ticket = Arc::new(CommitTicket::default());
+        self.lock_tickets()?.insert(ticket, commit_ticket.clone());
+        let index_path = index_path.to_string();
+        let spawned = spawn_commit_task(move || {
+            commit_ticket.complete(commit_index(&index_path));
+        });
+        if let Err(e) = spawned {
+            self.lock_tickets()?.remove(&ticket);
+            return Err(e);
+        }
+        Ok(ticket)
+    }
+
+    fn wait(&self, ticket: u64, timeout_millis: u64) -> Result<bool, TantivySearchError> {
+        let commit_ticket = self.lock_tickets()?.get(&ticket).cloned().ok_or_else(|| {
+            TantivySearchError::InvalidArgument(format!(
+                "Commit ticket {} doesn't exist or has been waited",
+                ticket
+            ))
+        })?;
+        match commit_ticket.wait(timeout_millis) {
+            Some(result) => {
+                self.lock_tickets()?.remove(&ticket);
+                result
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+// Index writer should be loaded before a commit is scheduled, so misuse is reported at once.
+fn check_index_writer(function: &str, index_path: &str) -> Result<(), TantivySearchError> {
+    FFI_INDEX_WRITER_CACHE
+        .get_index_writer_bridge(index_path.to_string())
+        .map(|_| ())
+        .map_err(|e| {
+            ERROR!(function: function, "{}", e);
+            TantivySearchError::InternalError(e)
+        })
+}
+
+/// Schedule a commit on commit pool and return a ticket for `wait_commit`,
+/// so host can overlap the fsync heavy commit with other work.
+pub fn commit_async(index_path: &str) -> Result<u64, TantivySearchError> {
+    check_index_writer("commit_async", index_path)?;
+    COMMIT_TICKETS.submit(index_path).map_err(|e| {
+        ERROR!(function: "commit_async", "{}", e);
+        e
+    })
+}
+
+/// Wait a commit scheduled by `commit_async`, `timeout_millis` 0 waits until commit ended.
+/// Return `false` if commit is still running after timeout, the ticket can be waited again.
+/// Once commit ended its result is returned and the ticket is released.
+pub fn wait_commit(ticket: u64, timeout_millis: u64) -> Result<bool, TantivySearchError> {
+    COMMIT_TICKETS.wait(ticket, timeout_millis).map_err(|e| {
+        ERROR!(function: "wait_commit", "{}", e);
+        e
+    })
+}
+
+/// Schedule a commit on commit pool without waiting it, commit failure is only logged.
+pub fn commit_nowait(index_path: &str) -> Result<bool, TantivySearchError> {
+    check_index_writer("commit_nowait", index_path)?;
+    let index_path = index_path.to_string();
+    spawn_commit_task(move || {
+        if let Err(e) = commit_index(&index_path) {
+            ERROR!(function: "commit_nowait", "Failed to commit [{}]: {}", index_path, e);
+        }
+    })?;
+    Ok(true)
+}
+
+/// Wait commits scheduled by `commit_async` and `commit_nowait` ended, `timeout_millis` 0
+/// waits forever. Return `false` if some commits are still running after timeout.
+pub fn drain_commits(timeout_millis: u64) -> Result<bool, TantivySearchError> {
+    COMMITS_IN_FLIGHT.wait_drained(timeout_millis).map_err(|e| {
+        ERROR!(function: "drain_commits", "{}", e);
+        e
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::tests::{create_3column_names, index_3column_docs_with_index_writer_bridge};
+    use crate::index::implements::api_index_impl::{
+        create_index, free_index_writer, index_multi_column_docs,
+    };
+    use crate::TEST_MUTEX;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_commit_ticket_wait() {
+        let commit_ticket = CommitTicket::default();
+        assert!(commit_ticket.wait(10).is_none());
+        commit_ticket.complete(Ok(true));
+        assert!(commit_ticket.wait(0).unwrap().unwrap());
+        // Result is kept for other waiters.
+        assert!(commit_ticket.wait(10).unwrap().unwrap());
+    }
+
+    #[test]
+    fn test_commit_async() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        assert!(commit_async(temp_directory_str).is_err());
+        assert!(commit_nowait(temp_directory_str).is_err());
+
+        assert!(create_index(temp_directory_str, &create_3column_names()).is_ok());
+        let index_writer_bridge =
+            index_3column_docs_with_index_writer_bridge(temp_directory_str, false);
+        assert!(index_multi_column_docs(
+            temp_directory_str,
+            100,
+            &create_3column_names(),
+            &vec!["col1".to_string(), "col2".to_string(), "col3".to_string()]
+        )
+        .is_ok());
+        assert_eq!(index_writer_bridge.uncommitted_operations(), 1);
+
+        let ticket = commit_async(temp_directory_str).unwrap();
+        assert!(wait_commit(ticket, 0).unwrap());
+        assert_eq!(index_writer_bridge.uncommitted_operations(), 0);
+        // Ticket is released after its result is observed.
+        assert!(wait_commit(ticket, 0).is_err());
+
+        assert!(commit_nowait(temp_directory_str).is_ok());
+        assert!(drain_commits(0).unwrap());
+        assert!(free_index_writer(temp_directory_str).is_ok());
+    }
+}
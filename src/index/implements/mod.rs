@@ -1,2 +1,5 @@
 pub mod api_index_impl;
+pub mod commit_tickets;
+pub mod multi_part_builder;
+pub mod reindex;
 mod tests;
@@ -0,0 +1,299 @@
+use crate::common::errors::TantivySearchError;
+use crate::common::thread_affinity::{thread_pool_builder, ThreadKind};
+use crate::index::implements::api_index_impl::{
+    commit_index, create_index_with_writer_memory, free_index_writer, index_column_batch,
+    MIN_WRITER_MEMORY_PER_THREAD,
+};
+use crate::logger::logger_bridge::TantivySearchLogger;
+use crate::{common::constants::LOG_CALLBACK, ERROR, INFO};
+use rayon::prelude::*;
+use rayon::ThreadPool;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+// Rows of one part in a multi-part batch, laid out same as `index_column_batch`.
+struct PartBatch {
+    part_id: usize,
+    row_ids: Vec<u64>,
+    column_data: Vec<u8>,
+    column_offsets: Vec<u64>,
+}
+
+/// Builds several independent indexes concurrently on one thread pool.
+///
+/// Writers of all parts share `memory_budget_bytes`, each part's writer claims an equal slice,
+/// so N simultaneous part builds don't each claim the default writer memory budget.
+/// Rows of a part are indexed in the order they are given, different parts are indexed in parallel.
+pub struct MultiPartBuilder {
+    pub index_paths: Vec<String>,
+    pub column_names: Vec<String>,
+    pub writer_memory_bytes: usize,
+    pool: ThreadPool,
+}
+
+impl MultiPartBuilder {
+    /// Memory budget of each part's writer, it should satisfy at least one indexing thread.
+    pub fn writer_memory_per_part(memory_budget_bytes: u64, parts: usize) -> Result<usize, String> {
+        if parts == 0 {
+            return Err("Multi-part build requires at least one part".to_string());
+        }
+        let writer_memory_bytes = (memory_budget_bytes / parts as u64) as usize;
+        if writer_memory_bytes < MIN_WRITER_MEMORY_PER_THREAD {
+            return Err(format!(
+                "Memory budget {} is too small for {} parts, each part requires at least {} bytes",
+                memory_budget_bytes, parts, MIN_WRITER_MEMORY_PER_THREAD
+            ));
+        }
+        Ok(writer_memory_bytes)
+    }
+
+    /// Create indexes of all parts, `num_threads` 0 means available parallelism.
+    /// Parts created before a failure are freed.
+    pub fn create(
+        index_paths: Vec<String>,
+        column_names: Vec<String>,
+        index_json_parameter: &str,
+        memory_budget_bytes: u64,
+        num_threads: usize,
+    ) -> Result<Self, TantivySearchError> {
+        let writer_memory_bytes =
+            Self::writer_memory_per_part(memory_budget_bytes, index_paths.len())
+                .map_err(TantivySearchError::InvalidArgument)?;
+        let num_threads = if num_threads == 0 {
+            std::thread::available_parallelism()
+                .map(|threads| threads.get())
+                .unwrap_or(4)
+        } else {
+            num_threads
+        };
+        let pool = thread_pool_builder(ThreadKind::Ingest, "build")
+            .num_threads(num_threads)
+            .build()
+            .map_err(|e| {
+                TantivySearchError::InternalError(format!(
+                    "Failed to build multi-part build pool: {}",
+                    e
+                ))
+            })?;
+
+        for (created, index_path) in index_paths.iter().enumerate() {
+            if let Err(e) = create_index_with_writer_memory(
+                index_path,
+                &column_names,
+                index_json_parameter,
+                false,
+                writer_memory_bytes,
+            ) {
+                for index_path in &index_paths[..created] {
+                    let _ = free_index_writer(index_path);
+                }
+                return Err(e);
+            }
+        }
+        INFO!(function: "MultiPartBuilder", "Created {} parts, writer_memory_bytes:{}, num_threads:{}", index_paths.len(), writer_memory_bytes, num_threads);
+        Ok(MultiPartBuilder {
+            index_paths,
+            column_names,
+            writer_memory_bytes,
+            pool,
+        })
+    }
+
+    // Split a batch into per-part batches, rows keep their order within a part.
+    fn split_batch(
+        &self,
+        part_ids: &[u64],
+        row_ids: &[u64],
+        column_data: &[u8],
+        column_offsets: &[u64],
+    ) -> Result<Vec<PartBatch>, String> {
+        let rows = row_ids.len();
+        let columns = self.column_names.len();
+        if part_ids.len() != rows {
+            return Err(format!(
+                "part_ids size {} doesn't match row_ids size {}",
+                part_ids.len(),
+                rows
+            ));
+        }
+        if column_offsets.len() != columns * rows + 1
+            || column_offsets.windows(2).any(|w| w[0] > w[1])
+            || column_offsets[column_offsets.len() - 1] > column_data.len() as u64
+        {
+            return Err(format!(
+                "column_offsets should be {} ascending offsets within column_data size {}",
+                columns * rows + 1,
+                column_data.len()
+            ));
+        }
+
+        let mut part_rows: Vec<Vec<usize>> = vec![Vec::new(); self.index_paths.len()];
+        for (row, part_id) in part_ids.iter().enumerate() {
+            match part_rows.get_mut(*part_id as usize) {
+                Some(rows) => rows.push(row),
+                None => {
+                    return Err(format!(
+                        "part_id {} is out of {} parts",
+                        part_id,
+                        self.index_paths.len()
+                    ))
+                }
+            }
+        }
+
+        Ok(part_rows
+            .into_iter()
+            .enumerate()
+            .filter(|(_, selected)| !selected.is_empty())
+            .map(|(part_id, selected)| {
+                let mut column_data_of_part: Vec<u8> = Vec::new();
+                let mut column_offsets_of_part: Vec<u64> =
+                    Vec::with_capacity(columns * selected.len() + 1);
+                column_offsets_of_part.push(0);
+                for column in 0..columns {
+                    for row in selected.iter() {
+                        let offset_idx = column * rows + row;
+                        column_data_of_part.extend_from_slice(
+                            &column_data[column_offsets[offset_idx] as usize
+                                ..column_offsets[offset_idx + 1] as usize],
+                        );
+                        column_offsets_of_part.push(column_data_of_part.len() as u64);
+                    }
+                }
+                PartBatch {
+                    part_id,
+                    row_ids: selected.iter().map(|row| row_ids[*row]).collect(),
+                    column_data: column_data_of_part,
+                    column_offsets: column_offsets_of_part,
+                }
+            })
+            .collect())
+    }
+
+    /// Index a batch holding rows of several parts, `part_ids[i]` is the part of `row_ids[i]`.
+    /// Column strings are laid out same as `index_column_batch`. Parts are indexed in parallel.
+    pub fn index_batch(
+        &self,
+        part_ids: &[u64],
+        row_ids: &[u64],
+        column_data: &[u8],
+        column_offsets: &[u64],
+    ) -> Result<(), TantivySearchError> {
+        let part_batches = self
+            .split_batch(part_ids, row_ids, column_data, column_offsets)
+            .map_err(|e| {
+                ERROR!(function: "MultiPartBuilder", "{}", e);
+                TantivySearchError::InvalidArgument(e)
+            })?;
+        // Documents are built on the same pool, `index_column_batch` runs inside `install`.
+        self.pool.install(|| {
+            part_batches.par_iter().try_for_each(|part_batch| {
+                index_column_batch(
+                    &self.index_paths[part_batch.part_id],
+                    &part_batch.row_ids,
+                    &self.column_names,
+                    &part_batch.column_data,
+                    &part_batch.column_offsets,
+                )
+                .map(|_| ())
+            })
+        })
+    }
+
+    /// Commit all parts in parallel.
+    pub fn commit(&self) -> Result<(), TantivySearchError> {
+        self.pool.install(|| {
+            self.index_paths
+                .par_iter()
+                .try_for_each(|index_path| commit_index(index_path).map(|_| ()))
+        })
+    }
+
+    /// Commit and free writers of all parts, writers are freed even if some commits failed.
+    pub fn finish(&self) -> Result<(), TantivySearchError> {
+        let committed = self.commit();
+        self.pool.install(|| {
+            self.index_paths
+                .par_iter()
+                .try_for_each(|index_path| free_index_writer(index_path).map(|_| ()))
+        })?;
+        committed
+    }
+}
+
+/// Multi-part builds in progress, keyed by build id.
+pub struct MultiPartBuilds {
+    next_build_id: AtomicU64,
+    builds: Mutex<HashMap<u64, Arc<MultiPartBuilder>>>,
+}
+
+impl MultiPartBuilds {
+    pub fn new() -> Self {
+        MultiPartBuilds {
+            next_build_id: AtomicU64::new(1),
+            builds: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn insert(&self, builder: MultiPartBuilder) -> Result<u64, String> {
+        let build_id = self.next_build_id.fetch_add(1, Ordering::Relaxed);
+        let mut builds = self
+            .builds
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        builds.insert(build_id, Arc::new(builder));
+        Ok(build_id)
+    }
+
+    pub fn get(&self, build_id: u64) -> Result<Arc<MultiPartBuilder>, String> {
+        let builds = self
+            .builds
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        builds
+            .get(&build_id)
+            .cloned()
+            .ok_or_else(|| format!("Multi-part build {} doesn't exist", build_id))
+    }
+
+    pub fn remove(&self, build_id: u64) -> Result<Arc<MultiPartBuilder>, String> {
+        let mut builds = self
+            .builds
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        builds
+            .remove(&build_id)
+            .ok_or_else(|| format!("Multi-part build {} doesn't exist", build_id))
+    }
+
+    /// Remove all builds, their part writers are left loaded.
+    pub fn remove_all(&self) -> Result<Vec<Arc<MultiPartBuilder>>, String> {
+        let mut builds = self
+            .builds
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        Ok(builds.drain().map(|(_, builder)| builder).collect())
+    }
+}
+
+impl Default for MultiPartBuilds {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_writer_memory_per_part() {
+        assert!(MultiPartBuilder::writer_memory_per_part(1024 * 1024 * 64, 0).is_err());
+        assert_eq!(
+            MultiPartBuilder::writer_memory_per_part(1024 * 1024 * 64, 2).unwrap(),
+            1024 * 1024 * 32
+        );
+        assert!(MultiPartBuilder::writer_memory_per_part(1024 * 1024 * 64, 5).is_err());
+    }
+}
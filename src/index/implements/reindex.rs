@@ -0,0 +1,326 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tantivy::schema::{Field, Schema};
+use tantivy::{DocAddress, Index, IndexReader, ReloadPolicy, Searcher, TantivyDocument};
+
+use crate::common::errors::TantivySearchError;
+use crate::index::implements::api_index_impl::{
+    commit_index, create_index_with_build_mode, free_index_writer, index_column_batch,
+    load_index_writer,
+};
+use crate::logger::logger_bridge::TantivySearchLogger;
+use crate::search::implements::api_common_impl::{free_index_reader, load_index_reader};
+use crate::tokenizer::dto::index_parameter_dto::IndexParameterDTO;
+use crate::tokenizer::language_routing::language_field_name;
+use crate::tokenizer::tokenizer_utils::TokenizerUtils;
+use crate::tokenizer::vo::tokenizers_vo::TokenizerConfig;
+use crate::utils::index_utils::IndexUtils;
+use crate::utils::index_version_utils::IndexVersionUtils;
+use crate::utils::path_utils::PathUtils;
+use crate::{common::constants::LOG_CALLBACK, ERROR, INFO, WARNING};
+use crate::{FFI_INDEX_SEARCHER_CACHE, FFI_INDEX_WRITER_CACHE};
+
+// Rows given to `index_column_batch` at once while re-indexing.
+const REINDEX_BATCH_ROWS: usize = 4096;
+
+/// Fetch source texts of `column_names` of a row by its row_id, `None` skips the row,
+/// e.g. it's gone from the source.
+pub type FetchColumns<'a> = dyn Fn(u64, &[String]) -> Result<Option<Vec<String>>, String> + 'a;
+
+// Sibling directory the new index is built in, e.g. `/data/index.reindex`.
+fn reindex_directory(index_directory: &Path) -> Option<PathBuf> {
+    let mut name = index_directory.file_name()?.to_owned();
+    name.push(".reindex");
+    Some(index_directory.with_file_name(name))
+}
+
+// Host columns of `schema` in creation order, row_id and language sub-fields are left out.
+fn index_column_names(schema: &Schema, index_parameter_dto: &IndexParameterDTO) -> Vec<String> {
+    let col_tokenizer_map: HashMap<String, TokenizerConfig> =
+        TokenizerUtils::parse_tokenizer_json_to_config_map(
+            &index_parameter_dto.tokenizers_json_parameter,
+        )
+        .unwrap_or_default();
+    let mut subfields: HashSet<String> = HashSet::new();
+    for (column_name, tokenizer_config) in col_tokenizer_map.iter() {
+        if let Some(language_routing) = &tokenizer_config.language_routing {
+            for (language, _) in &language_routing.languages {
+                subfields.insert(language_field_name(column_name, *language));
+            }
+        }
+    }
+    schema
+        .fields()
+        .map(|(_, field_entry)| field_entry.name().to_string())
+        .filter(|name| name != "row_id" && !subfields.contains(name))
+        .collect()
+}
+
+// Text of a stored value as it was given to index, `None` if it can't be told from it.
+fn stored_text(document: &TantivyDocument, field: Field) -> Option<String> {
+    let value = match document.get_first(field) {
+        Some(value) => value,
+        None => return Some(String::new()),
+    };
+    if let Some(text) = value.as_str() {
+        Some(text.to_string())
+    } else if let Some(number) = value.as_i64() {
+        Some(number.to_string())
+    } else if let Some(number) = value.as_f64() {
+        Some(number.to_string())
+    } else if let Some(number) = value.as_u64() {
+        Some(number.to_string())
+    } else if let Some(bytes) = value.as_bytes() {
+        String::from_utf8(bytes.to_vec()).ok()
+    } else {
+        None
+    }
+}
+
+// Texts of all columns of a row, `None` if the row is skipped.
+fn row_texts(
+    searcher: &Searcher,
+    doc_address: DocAddress,
+    row_id: u64,
+    column_names: &[String],
+    column_fields: &[Field],
+    fetch_columns: Option<&FetchColumns>,
+) -> Result<Option<Vec<String>>, TantivySearchError> {
+    if let Some(fetch_columns) = fetch_columns {
+        let texts: Option<Vec<String>> = fetch_columns(row_id, column_names).map_err(|e| {
+            TantivySearchError::InternalError(format!(
+                "Failed to fetch columns of row_id {}: {}",
+                row_id, e
+            ))
+        })?;
+        if let Some(texts) = &texts {
+            if texts.len() != column_names.len() {
+                return Err(TantivySearchError::InvalidArgument(format!(
+                    "{} columns fetched for row_id {}, but {} columns are indexed",
+                    texts.len(),
+                    row_id,
+                    column_names.len()
+                )));
+            }
+        }
+        return Ok(texts);
+    }
+    let document: TantivyDocument = searcher
+        .doc(doc_address)
+        .map_err(TantivySearchError::TantivyError)?;
+    column_names
+        .iter()
+        .zip(column_fields.iter())
+        .map(|(column_name, field)| {
+            stored_text(&document, *field).ok_or_else(|| {
+                TantivySearchError::InvalidArgument(format!(
+                    "Stored value of column `{}` of row_id {} can't be re-indexed, fetch it from source instead",
+                    column_name, row_id
+                ))
+            })
+        })
+        .collect::<Result<Vec<String>, TantivySearchError>>()
+        .map(Some)
+}
+
+// Build the new index at `sibling_path` from alive rows of `old_index` in row_id order.
+fn build_reindexed(
+    old_index: &Index,
+    sibling_path: &str,
+    column_names: &[String],
+    index_json_parameter: &str,
+    deterministic_build: bool,
+    fetch_columns: Option<&FetchColumns>,
+) -> Result<u64, TantivySearchError> {
+    let schema: Schema = old_index.schema();
+    let mut column_fields: Vec<Field> = Vec::with_capacity(column_names.len());
+    for column_name in column_names {
+        let field: Field = schema
+            .get_field(column_name)
+            .map_err(TantivySearchError::TantivyError)?;
+        if fetch_columns.is_none() && !schema.get_field_entry(field).is_stored() {
+            return Err(TantivySearchError::InvalidArgument(format!(
+                "Column `{}` isn't stored, fetch it from source to re-index",
+                column_name
+            )));
+        }
+        column_fields.push(field);
+    }
+
+    let reader: IndexReader = old_index
+        .reader_builder()
+        .reload_policy(ReloadPolicy::Manual)
+        .try_into()
+        .map_err(TantivySearchError::TantivyError)?;
+    let searcher: Searcher = reader.searcher();
+    let mut rows: Vec<(u64, DocAddress)> = Vec::with_capacity(searcher.num_docs() as usize);
+    for (segment_ord, segment_reader) in searcher.segment_readers().iter().enumerate() {
+        let row_id_column = segment_reader
+            .fast_fields()
+            .u64("row_id")
+            .map_err(TantivySearchError::TantivyError)?;
+        for doc_id in segment_reader.doc_ids_alive() {
+            if let Some(row_id) = row_id_column.first(doc_id) {
+                rows.push((row_id, DocAddress::new(segment_ord as u32, doc_id)));
+            }
+        }
+    }
+    rows.sort_by_key(|(row_id, _)| *row_id);
+
+    let column_names: Vec<String> = column_names.to_vec();
+    create_index_with_build_mode(
+        sibling_path,
+        &column_names,
+        index_json_parameter,
+        deterministic_build,
+    )?;
+    let mut rows_indexed: u64 = 0;
+    for chunk in rows.chunks(REINDEX_BATCH_ROWS) {
+        let mut row_ids: Vec<u64> = Vec::with_capacity(chunk.len());
+        let mut texts: Vec<Vec<String>> = Vec::with_capacity(chunk.len());
+        for (row_id, doc_address) in chunk {
+            if let Some(row) = row_texts(
+                &searcher,
+                *doc_address,
+                *row_id,
+                &column_names,
+                &column_fields,
+                fetch_columns,
+            )? {
+                row_ids.push(*row_id);
+                texts.push(row);
+            }
+        }
+        let mut column_data: Vec<u8> = Vec::new();
+        let mut column_offsets: Vec<u64> = vec![0];
+        for column in 0..column_names.len() {
+            for row in texts.iter() {
+                column_data.extend_from_slice(row[column].as_bytes());
+                column_offsets.push(column_data.len() as u64);
+            }
+        }
+        if !row_ids.is_empty() {
+            index_column_batch(
+                sibling_path,
+                &row_ids,
+                &column_names,
+                &column_data,
+                &column_offsets,
+            )?;
+        }
+        rows_indexed += row_ids.len() as u64;
+    }
+    commit_index(sibling_path)?;
+    free_index_writer(sibling_path)?;
+    Ok(rows_indexed)
+}
+
+/// Re-index an index in place with `index_json_parameter` as its new tokenizer settings, e.g.
+/// after users change analyzers of a table. Alive rows are indexed in row_id order into a
+/// sibling directory, from stored columns or texts fetched from source by `fetch_columns`,
+/// then the sibling is swapped with the index. Return rows indexed.
+/// Loaded writer is committed first and reloaded afterwards, rows it receives meanwhile fail
+/// with index not exists. Loaded reader keeps serving the old index until the swap.
+/// The old index is kept untouched if re-indexing fails.
+pub fn reindex_index(
+    index_path: &str,
+    index_json_parameter: &str,
+    fetch_columns: Option<&FetchColumns>,
+) -> Result<u64, TantivySearchError> {
+    let index_files_directory: PathBuf = PathUtils::index_directory(index_path);
+    if !index_files_directory.is_dir() {
+        let error_info = format!("Index directory doesn't exist: {}", index_path);
+        ERROR!(function: "reindex_index", "{}", error_info);
+        return Err(TantivySearchError::IndexNotExists(error_info));
+    }
+    let writer_loaded: bool = FFI_INDEX_WRITER_CACHE
+        .get_index_writer_bridge(index_path.to_string())
+        .is_ok();
+    let reader_loaded: bool = FFI_INDEX_SEARCHER_CACHE
+        .get_index_reader_bridge(index_path.to_string())
+        .is_ok();
+    // Loaded writer and reader come back on the index in place, re-indexed or not.
+    let reload = || -> Result<(), TantivySearchError> {
+        if writer_loaded {
+            load_index_writer(index_path)?;
+        }
+        if reader_loaded {
+            load_index_reader(index_path)?;
+        }
+        Ok(())
+    };
+
+    let sibling_directory: PathBuf =
+        reindex_directory(&index_files_directory).ok_or_else(|| {
+            let error_info = format!("Index directory has no name: {}", index_path);
+            ERROR!(function: "reindex_index", "{}", error_info);
+            TantivySearchError::InvalidArgument(error_info)
+        })?;
+    let sibling_path: String = sibling_directory.to_string_lossy().to_string();
+    if writer_loaded {
+        commit_index(index_path)?;
+        free_index_writer(index_path)?;
+    }
+    let built = (|| -> Result<u64, TantivySearchError> {
+        let index_parameter_dto: IndexParameterDTO =
+            IndexVersionUtils::check_index(&index_files_directory)?;
+        let old_index: Index =
+            Index::open_in_dir(&index_files_directory).map_err(TantivySearchError::TantivyError)?;
+        let column_names: Vec<String> =
+            index_column_names(&old_index.schema(), &index_parameter_dto);
+        build_reindexed(
+            &old_index,
+            &sibling_path,
+            &column_names,
+            index_json_parameter,
+            index_parameter_dto.deterministic_build,
+            fetch_columns,
+        )
+    })();
+    // Readers keep index files open, they are released before the swap.
+    let swapped = built.and_then(|rows_indexed| {
+        if reader_loaded {
+            free_index_reader(index_path)?;
+        }
+        IndexUtils::swap_directories(&index_files_directory, &sibling_directory)?;
+        Ok(rows_indexed)
+    });
+    let rows_indexed: u64 = match swapped {
+        Ok(rows_indexed) => rows_indexed,
+        Err(e) => {
+            ERROR!(function: "reindex_index", "Failed to re-index [{}]: {}", index_path, e);
+            let _ = free_index_writer(&sibling_path);
+            if let Err(e) = fs::remove_dir_all(&sibling_directory) {
+                WARNING!(function: "reindex_index", "Failed to remove {:?}: {}", sibling_directory, e);
+            }
+            reload()?;
+            return Err(e);
+        }
+    };
+    if let Err(e) = fs::remove_dir_all(&sibling_directory) {
+        WARNING!(function: "reindex_index", "Failed to remove old index {:?}: {}", sibling_directory, e);
+    }
+    reload()?;
+    INFO!(function: "reindex_index", "Re-indexed {} rows, index_path:[{}], index_json_parameter:{}", rows_indexed, index_path, index_json_parameter);
+    Ok(rows_indexed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reindex_directory() {
+        assert_eq!(
+            reindex_directory(Path::new("/data/index")),
+            Some(PathBuf::from("/data/index.reindex"))
+        );
+        assert_eq!(
+            reindex_directory(Path::new("/data/index/")),
+            Some(PathBuf::from("/data/index.reindex"))
+        );
+        assert_eq!(reindex_directory(Path::new("/")), None);
+    }
+}
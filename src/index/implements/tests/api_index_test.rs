@@ -1,19 +1,48 @@
 #[cfg(test)]
 mod tests {
     use std::cmp::min;
+    use std::sync::atomic::Ordering;
     use tantivy::collector::Count;
-    use tantivy::query::QueryParser;
+    use tantivy::query::{QueryParser, TermQuery};
+    use tantivy::schema::{FieldType, IndexRecordOption};
+    use tantivy::Term;
     use tempfile::TempDir;
 
+    use crate::common::constants::{
+        INDEX_FORMAT_VERSION, MERGE_THROTTLE, METRICS, QUERY_RESULT_CACHE_DIR_NAME, UTF8_POLICY,
+        WRITER_OWNER_FILE_NAME,
+    };
+    use crate::common::errors::TantivySearchError;
     use crate::common::tests::{
         get_mocked_docs, index_3column_docs_with_index_writer_bridge,
         search_with_index_writer_bridge,
     };
+    use crate::common::utf8_policy::Utf8Policy;
+    use crate::index::bridge::index_writer_durability::CommitDurability;
     use crate::index::implements::api_index_impl::{
-        commit_index, create_index, create_index_with_parameter, delete_row_ids, free_index_writer,
-        index_multi_column_docs,
+        commit_index, create_index, create_index_with_parameter, create_multi_part_build,
+        delete_row_ids, disable_auto_commit, disable_idle_index_unload, enable_auto_commit,
+        enable_auto_row_id, enable_idle_index_unload, estimate_index_size, finish_multi_part_build,
+        finish_sorted_build, free_index_writer, free_namespace, get_build_checkpoint,
+        get_build_progress, get_index_health, get_liveness, get_segment_delete_stats,
+        index_column_batch, index_column_batch_auto_row_id, index_multi_column_borrowed_docs,
+        index_multi_column_docs, index_multi_column_docs_auto_row_id, list_loaded_indexes,
+        load_index_writer, multi_part_build_index_batch, probe_liveness, probe_readiness,
+        recover_stale_writer_lock, register_index_namespace, set_merge_throttle, set_row_id_check,
+        set_writer_backpressure, shutdown, sorted_build_add_batch, start_build_progress,
+        start_sorted_build, stop_build_progress, unload_idle_indexes, update_index_settings,
+        upgrade_index, vacuum_index, ShutdownPolicy,
+    };
+    use crate::index::implements::reindex::reindex_index;
+    use crate::search::implements::api_common_impl::{free_index_reader, load_index_reader};
+    use crate::search::implements::api_dingo_impl::{
+        bm25_search_with_column_names, get_stored_fields,
     };
-    use crate::{FFI_INDEX_WRITER_CACHE, TEST_MUTEX};
+    use crate::search::implements::strategy::similarity_query::{Bm25Params, Similarity};
+    use crate::tokenizer::dto::index_parameter_dto::IndexParameterDTO;
+    use crate::utils::index_utils::IndexUtils;
+    use crate::utils::writer_lock_utils::{WriterLockUtils, WriterOwner};
+    use crate::{FFI_INDEX_SEARCHER_CACHE, FFI_INDEX_WRITER_CACHE, INDEX_NAMESPACES, TEST_MUTEX};
 
     #[test]
     pub fn test_create_index_with_valid_tokenizer() {
@@ -239,4 +268,1577 @@ mod tests {
             .as_mut()
             .is_none());
     }
+
+    #[test]
+    pub fn test_recover_stale_writer_lock() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec!["col1".to_string(), "col2".to_string(), "col3".to_string()];
+
+        assert!(create_index(temp_directory_str, &column_names).is_ok());
+
+        // Writer lock held by current process is not stale.
+        assert_eq!(
+            recover_stale_writer_lock(temp_directory_str).unwrap(),
+            false
+        );
+
+        // Owner file will be removed after free index writer, unknown owner won't be broken.
+        assert!(free_index_writer(temp_directory_str).is_ok());
+        assert_eq!(
+            recover_stale_writer_lock(temp_directory_str).unwrap(),
+            false
+        );
+
+        // Owner recorded by current process is never broken, even without cached writer.
+        assert!(WriterLockUtils::record_writer_owner(temp_directory.path()).is_ok());
+        assert_eq!(
+            recover_stale_writer_lock(temp_directory_str).unwrap(),
+            false
+        );
+
+        // Owner recorded by an exited process is stale.
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let exited_owner = WriterOwner {
+            pid: child.id(),
+            recorded_millis: 0,
+        };
+        child.wait().unwrap();
+        std::fs::write(
+            temp_directory.path().join(WRITER_OWNER_FILE_NAME),
+            serde_json::to_string(&exited_owner).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(recover_stale_writer_lock(temp_directory_str).unwrap(), true);
+    }
+
+    #[test]
+    pub fn test_load_index_writer_cleans_orphan_files() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec!["col1".to_string(), "col2".to_string(), "col3".to_string()];
+
+        assert!(create_index(temp_directory_str, &column_names).is_ok());
+        let _ = index_3column_docs_with_index_writer_bridge(temp_directory_str, false);
+        assert!(free_index_writer(temp_directory_str).is_ok());
+
+        // Files left by a writer crashed while merging.
+        let orphan_paths = [
+            temp_directory.path().join(".tmpXyZ123"),
+            temp_directory
+                .path()
+                .join(format!("{}.store", "0f".repeat(16))),
+        ];
+        for orphan_path in orphan_paths.iter() {
+            std::fs::write(orphan_path, b"orphan").unwrap();
+        }
+        let reclaimed: u64 = METRICS.orphan_bytes_reclaimed.load(Ordering::Relaxed);
+        assert!(load_index_writer(temp_directory_str).is_ok());
+        assert!(orphan_paths.iter().all(|orphan_path| !orphan_path.exists()));
+        assert!(METRICS.orphan_bytes_reclaimed.load(Ordering::Relaxed) >= reclaimed + 12);
+        assert!(load_index_reader(temp_directory_str).is_ok());
+        assert!(free_index_reader(temp_directory_str).is_ok());
+        assert!(free_index_writer(temp_directory_str).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    pub fn test_index_opened_through_symlink() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let index_path = temp_directory.path().join("index");
+        let link_path = temp_directory.path().join("link");
+        std::fs::create_dir(&index_path).unwrap();
+        std::os::unix::fs::symlink(&index_path, &link_path).unwrap();
+        let index_path_str = index_path.to_str().unwrap();
+        let link_path_str = link_path.to_str().unwrap();
+        let canonical_path: String = std::fs::canonicalize(&index_path)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+        let column_names = vec!["col1".to_string(), "col2".to_string(), "col3".to_string()];
+        let docs = vec![
+            "symlink".to_string(),
+            "safe".to_string(),
+            "index".to_string(),
+        ];
+
+        // Symlink, relative components and target spell the same index.
+        assert!(create_index(link_path_str, &column_names).is_ok());
+        assert!(index_multi_column_docs(index_path_str, 0, &column_names, &docs).is_ok());
+        assert!(index_multi_column_docs(
+            &format!("{}/../link/", link_path_str),
+            1,
+            &column_names,
+            &docs
+        )
+        .is_ok());
+        assert!(commit_index(link_path_str).is_ok());
+
+        let health = get_index_health(index_path_str).unwrap();
+        assert_eq!(health.canonical_path, canonical_path);
+        let indexes: Vec<serde_json::Value> =
+            serde_json::from_str(&list_loaded_indexes().unwrap()).unwrap();
+        let indexes: Vec<&serde_json::Value> = indexes
+            .iter()
+            .filter(|index| index["canonical_path"] == canonical_path.as_str())
+            .collect();
+        assert_eq!(indexes.len(), 1);
+        assert_eq!(indexes[0]["index_path"], link_path_str);
+        assert_eq!(indexes[0]["num_docs"], 2);
+
+        assert!(free_index_writer(index_path_str).is_ok());
+        assert!(get_index_health(link_path_str).is_err());
+    }
+
+    #[test]
+    pub fn test_get_index_health() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec!["col1".to_string(), "col2".to_string(), "col3".to_string()];
+
+        // Index not loaded can't get health status.
+        assert!(get_index_health(temp_directory_str).is_err());
+
+        assert!(create_index(temp_directory_str, &column_names).is_ok());
+        let _ = index_3column_docs_with_index_writer_bridge(temp_directory_str, false);
+
+        let health = get_index_health(temp_directory_str).unwrap();
+        assert!(health.writer_alive);
+        assert!(!health.writer_busy);
+        assert!(!health.reader_loaded);
+        assert!(health.last_commit_opstamp > 0);
+        assert!(health.last_error.is_empty());
+        assert_eq!(health.row_id_mapping_bytes, 0);
+
+        // Loaded reader warms DocId and row_id mapping.
+        assert!(load_index_reader(temp_directory_str).is_ok());
+        let health = get_index_health(temp_directory_str).unwrap();
+        assert!(health.reader_loaded);
+        assert!(health.row_id_mapping_bytes > 0);
+        assert!(free_index_reader(temp_directory_str).is_ok());
+
+        // Writer held by a commit or merge is reported busy instead of blocking health check.
+        let index_writer_bridge = FFI_INDEX_WRITER_CACHE
+            .get_index_writer_bridge(temp_directory_str.to_string())
+            .unwrap();
+        {
+            let _writer = index_writer_bridge.writer.lock().unwrap();
+            let health = get_index_health(temp_directory_str).unwrap();
+            assert!(health.writer_alive);
+            assert!(health.writer_busy);
+        }
+        drop(index_writer_bridge);
+
+        // Writer is not alive after free.
+        assert!(free_index_writer(temp_directory_str).is_ok());
+        assert!(get_index_health(temp_directory_str).is_err());
+    }
+
+    #[test]
+    pub fn test_liveness_and_readiness_probes() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec!["col1".to_string(), "col2".to_string(), "col3".to_string()];
+        let subsystem = |name: &str| {
+            get_liveness(temp_directory_str)
+                .unwrap()
+                .into_iter()
+                .find(|subsystem| subsystem.name == name)
+                .unwrap()
+        };
+
+        assert!(!probe_readiness(temp_directory_str).unwrap());
+        assert!(get_liveness(temp_directory_str).is_err());
+        // Process subsystems are reported without index.
+        assert_eq!(get_liveness("").unwrap()[0].name, "idle_janitor");
+
+        assert!(create_index(temp_directory_str, &column_names).is_ok());
+        assert!(probe_readiness(temp_directory_str).unwrap());
+        let auto_commit = subsystem("auto_commit");
+        assert!(!auto_commit.running);
+        let merges = subsystem("merges");
+        assert!(merges.alive);
+        assert!(!merges.running);
+        assert!(probe_liveness(temp_directory_str, 0).unwrap());
+
+        // Background threads beat while they are running.
+        assert!(enable_auto_commit(temp_directory_str, 1, 0).is_ok());
+        assert!(enable_idle_index_unload(3600, 3600).is_ok());
+        std::thread::sleep(std::time::Duration::from_millis(500));
+        let auto_commit = subsystem("auto_commit");
+        assert!(auto_commit.running && auto_commit.alive);
+        assert!(auto_commit.last_heartbeat_millis > 0);
+        let idle_janitor = subsystem("idle_janitor");
+        assert!(idle_janitor.running && idle_janitor.alive);
+        assert!(probe_liveness(temp_directory_str, 60_000).unwrap());
+        assert!(disable_idle_index_unload().unwrap());
+        assert!(!subsystem("idle_janitor").running);
+
+        assert!(free_index_writer(temp_directory_str).is_ok());
+        assert!(!probe_readiness(temp_directory_str).unwrap());
+    }
+
+    #[test]
+    pub fn test_list_loaded_indexes() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec!["col1".to_string(), "col2".to_string(), "col3".to_string()];
+        let find_index = |index_path: &str| -> Option<serde_json::Value> {
+            let indexes: Vec<serde_json::Value> =
+                serde_json::from_str(&list_loaded_indexes().unwrap()).unwrap();
+            indexes
+                .into_iter()
+                .find(|index| index["index_path"] == index_path)
+        };
+        assert!(find_index(temp_directory_str).is_none());
+
+        assert!(create_index(temp_directory_str, &column_names).is_ok());
+        let docs = vec![
+            "list".to_string(),
+            "loaded".to_string(),
+            "index".to_string(),
+        ];
+        for row_id in 0..3u64 {
+            assert!(
+                index_multi_column_docs(temp_directory_str, row_id, &column_names, &docs).is_ok()
+            );
+        }
+        let index = find_index(temp_directory_str).unwrap();
+        assert_eq!(index["writer_loaded"], true);
+        assert_eq!(index["reader_loaded"], false);
+        assert_eq!(index["num_docs"], 0);
+        assert!(index["writer_uncommitted_bytes"].as_u64().unwrap() > 0);
+        assert!(index["open_files"].as_u64().unwrap() > 0);
+        assert!(index["last_access_millis"].as_u64().unwrap() > 0);
+
+        assert!(commit_index(temp_directory_str).is_ok());
+        assert!(load_index_reader(temp_directory_str).is_ok());
+        let index = find_index(temp_directory_str).unwrap();
+        assert_eq!(index["reader_loaded"], true);
+        assert_eq!(index["num_docs"], 3);
+        assert!(index["reader_memory_bytes"].as_u64().unwrap() > 0);
+
+        assert!(free_index_reader(temp_directory_str).is_ok());
+        assert!(free_index_writer(temp_directory_str).is_ok());
+        assert!(find_index(temp_directory_str).is_none());
+    }
+
+    #[test]
+    pub fn test_enable_and_disable_auto_commit() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec!["col1".to_string(), "col2".to_string(), "col3".to_string()];
+
+        // Index writer not loaded.
+        assert!(enable_auto_commit(temp_directory_str, 1, 0).is_err());
+
+        assert!(create_index(temp_directory_str, &column_names).is_ok());
+        // Both conditions are disabled.
+        assert!(enable_auto_commit(temp_directory_str, 0, 0).is_err());
+
+        assert!(enable_auto_commit(temp_directory_str, 0, 3).is_ok());
+        let docs = vec!["auto".to_string(), "commit".to_string(), "test".to_string()];
+        for row_id in 0..3u64 {
+            assert!(
+                index_multi_column_docs(temp_directory_str, row_id, &column_names, &docs).is_ok()
+            );
+        }
+
+        let index_writer_bridge = FFI_INDEX_WRITER_CACHE
+            .get_index_writer_bridge(temp_directory_str.to_string())
+            .unwrap();
+        let mut committed = false;
+        for _ in 0..50 {
+            std::thread::sleep(std::time::Duration::from_millis(200));
+            if index_writer_bridge.uncommitted_operations() == 0 {
+                committed = true;
+                break;
+            }
+        }
+        assert!(committed);
+
+        assert!(disable_auto_commit(temp_directory_str).is_ok());
+        assert!(free_index_writer(temp_directory_str).is_ok());
+    }
+
+    #[test]
+    pub fn test_free_namespace() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let column_names = vec!["col1".to_string()];
+        let index_paths: Vec<String> = (0..3)
+            .map(|i| {
+                temp_directory
+                    .path()
+                    .join(format!("index_{}", i))
+                    .to_str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect();
+        for index_path in &index_paths {
+            assert!(create_index(index_path, &column_names).is_ok());
+        }
+        assert!(register_index_namespace(&index_paths[0], "").is_err());
+        assert!(register_index_namespace(&index_paths[0], "tenant_a").is_ok());
+        assert!(register_index_namespace(&index_paths[1], "tenant_a").is_ok());
+        assert!(register_index_namespace(&index_paths[2], "tenant_b").is_ok());
+        assert!(commit_index(&index_paths[0]).is_ok());
+        assert!(load_index_reader(&index_paths[0]).is_ok());
+
+        assert_eq!(
+            INDEX_NAMESPACES.keys_of("tenant_a"),
+            vec![index_paths[0].clone(), index_paths[1].clone()]
+        );
+
+        // Writers and readers of `tenant_a` are freed, `tenant_b` is untouched.
+        assert_eq!(free_namespace("tenant_a").unwrap(), 2);
+        assert!(FFI_INDEX_WRITER_CACHE
+            .get_index_writer_bridge(index_paths[0].clone())
+            .is_err());
+        assert!(FFI_INDEX_SEARCHER_CACHE
+            .get_index_reader_bridge(index_paths[0].clone())
+            .is_err());
+        assert!(FFI_INDEX_WRITER_CACHE
+            .get_index_writer_bridge(index_paths[1].clone())
+            .is_err());
+        assert!(FFI_INDEX_WRITER_CACHE
+            .get_index_writer_bridge(index_paths[2].clone())
+            .is_ok());
+        assert!(INDEX_NAMESPACES.keys_of("tenant_a").is_empty());
+        assert_eq!(free_namespace("tenant_a").unwrap(), 0);
+
+        assert_eq!(free_namespace("tenant_b").unwrap(), 1);
+    }
+
+    #[test]
+    pub fn test_unload_idle_indexes() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let column_names = vec!["col1".to_string()];
+        let index_paths: Vec<String> = (0..2)
+            .map(|i| {
+                temp_directory
+                    .path()
+                    .join(format!("index_{}", i))
+                    .to_str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect();
+        for index_path in &index_paths {
+            assert!(create_index(index_path, &column_names).is_ok());
+            assert!(index_multi_column_docs(
+                index_path,
+                0,
+                &column_names,
+                &vec!["idle".to_string()]
+            )
+            .is_ok());
+            assert!(commit_index(index_path).is_ok());
+            assert!(load_index_reader(index_path).is_ok());
+        }
+        // Pending write of `index_0` should be committed when it's unloaded.
+        assert!(index_multi_column_docs(
+            &index_paths[0],
+            1,
+            &column_names,
+            &vec!["pending".to_string()]
+        )
+        .is_ok());
+
+        // Mark `index_0` as accessed long ago.
+        FFI_INDEX_WRITER_CACHE
+            .get_index_writer_bridge(index_paths[0].clone())
+            .unwrap()
+            .stats
+            .last_access_millis
+            .store(0, Ordering::Relaxed);
+        FFI_INDEX_SEARCHER_CACHE
+            .get_index_reader_bridge(index_paths[0].clone())
+            .unwrap()
+            .last_access_millis
+            .store(0, Ordering::Relaxed);
+
+        assert_eq!(unload_idle_indexes(3600 * 1000), 2);
+        assert!(FFI_INDEX_WRITER_CACHE
+            .get_index_writer_bridge(index_paths[0].clone())
+            .is_err());
+        assert!(FFI_INDEX_SEARCHER_CACHE
+            .get_index_reader_bridge(index_paths[0].clone())
+            .is_err());
+        assert!(FFI_INDEX_WRITER_CACHE
+            .get_index_writer_bridge(index_paths[1].clone())
+            .is_ok());
+        assert!(FFI_INDEX_SEARCHER_CACHE
+            .get_index_reader_bridge(index_paths[1].clone())
+            .is_ok());
+
+        assert!(load_index_reader(&index_paths[0]).is_ok());
+        assert_eq!(
+            FFI_INDEX_SEARCHER_CACHE
+                .get_index_reader_bridge(index_paths[0].clone())
+                .unwrap()
+                .reader
+                .searcher()
+                .num_docs(),
+            2
+        );
+
+        assert!(enable_idle_index_unload(0, 1).is_err());
+        assert!(enable_idle_index_unload(3600, 1).is_ok());
+        assert!(disable_idle_index_unload().unwrap());
+        assert!(!disable_idle_index_unload().unwrap());
+
+        for index_path in &index_paths {
+            assert!(free_index_reader(index_path).is_ok());
+            assert!(free_index_writer(index_path).is_ok());
+        }
+    }
+
+    #[test]
+    pub fn test_shutdown_policy() {
+        assert_eq!(
+            ShutdownPolicy::from_name("Commit"),
+            Some(ShutdownPolicy::Commit)
+        );
+        assert_eq!(ShutdownPolicy::from_name(""), Some(ShutdownPolicy::Commit));
+        assert_eq!(
+            ShutdownPolicy::from_name("abort"),
+            Some(ShutdownPolicy::Abort)
+        );
+        // Unknown policy is refused before anything is stopped.
+        assert!(matches!(
+            shutdown("flush", 0),
+            Err(TantivySearchError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    pub fn test_update_index_settings() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec!["col1".to_string(), "col2".to_string(), "col3".to_string()];
+
+        // Index not loaded.
+        assert!(
+            update_index_settings(temp_directory_str, r#"{"auto_commit_interval_secs": 1}"#)
+                .is_err()
+        );
+
+        assert!(create_index(temp_directory_str, &column_names).is_ok());
+        assert!(enable_auto_commit(temp_directory_str, 0, 100).is_ok());
+
+        // Interval is updated and `max_docs` of running auto commit is kept.
+        assert!(
+            update_index_settings(temp_directory_str, r#"{"auto_commit_interval_secs": 5}"#)
+                .is_ok()
+        );
+        let index_writer_bridge = FFI_INDEX_WRITER_CACHE
+            .get_index_writer_bridge(temp_directory_str.to_string())
+            .unwrap();
+        let config = index_writer_bridge.auto_commit_config().unwrap();
+        assert_eq!(config.interval_secs, 5);
+        assert_eq!(config.max_docs, 100);
+
+        // Commit durability is applied to files committed afterwards.
+        assert!(
+            update_index_settings(temp_directory_str, r#"{"commit_durability": "none"}"#).is_ok()
+        );
+        assert_eq!(
+            index_writer_bridge.write_options.durability(),
+            CommitDurability::None
+        );
+        assert!(
+            update_index_settings(temp_directory_str, r#"{"commit_durability": "async"}"#).is_err()
+        );
+        assert_eq!(
+            index_writer_bridge.write_options.durability(),
+            CommitDurability::None
+        );
+        assert!(update_index_settings(temp_directory_str, r#"{"direct_io": true}"#).is_ok());
+        assert!(index_writer_bridge.write_options.direct_io());
+        assert!(update_index_settings(temp_directory_str, r#"{"direct_io": false}"#).is_ok());
+        assert!(!index_writer_bridge.write_options.direct_io());
+
+        // Slow query threshold requires index reader.
+        assert!(
+            update_index_settings(temp_directory_str, r#"{"slow_query_threshold_ms": 200}"#)
+                .is_err()
+        );
+        assert!(commit_index(temp_directory_str).is_ok());
+        assert!(load_index_reader(temp_directory_str).is_ok());
+        assert!(
+            update_index_settings(temp_directory_str, r#"{"slow_query_threshold_ms": 200}"#)
+                .is_ok()
+        );
+        let index_reader_bridge = FFI_INDEX_SEARCHER_CACHE
+            .get_index_reader_bridge(temp_directory_str.to_string())
+            .unwrap();
+        assert_eq!(
+            index_reader_bridge.slow_query_threshold(),
+            Some(std::time::Duration::from_millis(200))
+        );
+
+        // Invalid or unknown settings are rejected, nothing is applied.
+        assert!(update_index_settings(
+            temp_directory_str,
+            r#"{"slow_query_threshold_ms": 0, "similarity": "unknown"}"#
+        )
+        .is_err());
+        assert!(update_index_settings(temp_directory_str, r#"{"unknown": 1}"#).is_err());
+        assert!(index_reader_bridge.slow_query_threshold().is_some());
+
+        // A missing BM25 parameter keeps its current value.
+        assert!(update_index_settings(temp_directory_str, r#"{"bm25_b": 0.3}"#).is_ok());
+        assert_eq!(
+            index_reader_bridge.similarity(),
+            Some(Similarity::Bm25(Bm25Params { k1: 1.2, b: 0.3 }))
+        );
+        assert!(update_index_settings(temp_directory_str, r#"{"bm25_k1": -1.0}"#).is_err());
+        assert_eq!(
+            index_reader_bridge.similarity(),
+            Some(Similarity::Bm25(Bm25Params { k1: 1.2, b: 0.3 }))
+        );
+
+        // BM25 parameters are only allowed for BM25 similarity.
+        assert!(update_index_settings(temp_directory_str, r#"{"similarity": "tf_only"}"#).is_ok());
+        assert_eq!(index_reader_bridge.similarity(), Some(Similarity::TfOnly));
+        assert!(update_index_settings(temp_directory_str, r#"{"bm25_b": 0.5}"#).is_err());
+        assert!(update_index_settings(
+            temp_directory_str,
+            r#"{"similarity": "constant", "bm25_k1": 1.0}"#
+        )
+        .is_err());
+        assert!(update_index_settings(temp_directory_str, r#"{"similarity": "dfr"}"#).is_err());
+        assert_eq!(index_reader_bridge.similarity(), Some(Similarity::TfOnly));
+        assert!(update_index_settings(
+            temp_directory_str,
+            r#"{"similarity": "bm25", "bm25_k1": 2.0}"#
+        )
+        .is_ok());
+        assert_eq!(
+            index_reader_bridge.similarity(),
+            Some(Similarity::Bm25(Bm25Params { k1: 2.0, b: 0.75 }))
+        );
+
+        // Zero disables slow query log and interval commit, `max_docs` commit keeps running.
+        assert!(update_index_settings(
+            temp_directory_str,
+            r#"{"slow_query_threshold_ms": 0, "auto_commit_interval_secs": 0}"#
+        )
+        .is_ok());
+        assert!(index_reader_bridge.slow_query_threshold().is_none());
+        let config = index_writer_bridge.auto_commit_config().unwrap();
+        assert_eq!(config.interval_secs, 0);
+        assert_eq!(config.max_docs, 100);
+
+        // Persistent query result cache is created next to index, zero disables it.
+        assert!(
+            update_index_settings(temp_directory_str, r#"{"persistent_query_cache_mb": 4}"#)
+                .is_ok()
+        );
+        assert_eq!(
+            index_reader_bridge
+                .query_result_cache()
+                .map(|query_result_cache| query_result_cache.capacity_bytes()),
+            Some(4 * 1024 * 1024)
+        );
+        assert!(temp_directory
+            .path()
+            .join(QUERY_RESULT_CACHE_DIR_NAME)
+            .is_dir());
+        assert!(
+            update_index_settings(temp_directory_str, r#"{"persistent_query_cache_mb": 0}"#)
+                .is_ok()
+        );
+        assert!(index_reader_bridge.query_result_cache().is_none());
+
+        assert!(free_index_reader(temp_directory_str).is_ok());
+        assert!(free_index_writer(temp_directory_str).is_ok());
+    }
+
+    #[test]
+    pub fn test_index_docs_with_backpressure() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec!["col1".to_string(), "col2".to_string(), "col3".to_string()];
+        let docs = vec![
+            "back".to_string(),
+            "pressure".to_string(),
+            "test".to_string(),
+        ];
+
+        assert!(create_index(temp_directory_str, &column_names).is_ok());
+        assert!(set_writer_backpressure(temp_directory_str, 16, 0, 0).is_ok());
+
+        // First doc fills the uncommitted bytes budget.
+        assert!(index_multi_column_docs(temp_directory_str, 0, &column_names, &docs).is_ok());
+        let result = index_multi_column_docs(temp_directory_str, 1, &column_names, &docs);
+        assert!(matches!(result, Err(TantivySearchError::Backpressure(_))));
+
+        // Commit relieves backpressure.
+        assert!(commit_index(temp_directory_str).is_ok());
+        assert!(index_multi_column_docs(temp_directory_str, 1, &column_names, &docs).is_ok());
+        assert!(free_index_writer(temp_directory_str).is_ok());
+    }
+
+    #[test]
+    pub fn test_index_docs_with_row_id_check() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec!["col1".to_string(), "col2".to_string(), "col3".to_string()];
+        let docs = vec!["row".to_string(), "id".to_string(), "check".to_string()];
+
+        assert!(create_index(temp_directory_str, &column_names).is_ok());
+        assert!(index_multi_column_docs(temp_directory_str, 3, &column_names, &docs).is_ok());
+        assert!(commit_index(temp_directory_str).is_ok());
+        assert!(set_row_id_check(temp_directory_str, "unknown", true).is_err());
+
+        // Committed row_ids are seeded into checker.
+        assert!(set_row_id_check(temp_directory_str, "unique", true).is_ok());
+        let result = index_multi_column_docs(temp_directory_str, 3, &column_names, &docs);
+        assert!(matches!(
+            result,
+            Err(TantivySearchError::InvalidArgument(_))
+        ));
+        assert!(index_multi_column_docs(temp_directory_str, 1, &column_names, &docs).is_ok());
+
+        // Deleted row_id can be indexed again.
+        assert!(delete_row_ids(temp_directory_str, &vec![1]).is_ok());
+        assert!(index_multi_column_docs(temp_directory_str, 1, &column_names, &docs).is_ok());
+
+        assert!(set_row_id_check(temp_directory_str, "increasing", true).is_ok());
+        let result = index_multi_column_docs(temp_directory_str, 2, &column_names, &docs);
+        assert!(matches!(
+            result,
+            Err(TantivySearchError::InvalidArgument(_))
+        ));
+        assert!(index_multi_column_docs(temp_directory_str, 4, &column_names, &docs).is_ok());
+
+        // Violations are only counted when not rejected.
+        assert!(set_row_id_check(temp_directory_str, "increasing", false).is_ok());
+        assert!(index_multi_column_docs(temp_directory_str, 0, &column_names, &docs).is_ok());
+        assert!(free_index_writer(temp_directory_str).is_ok());
+    }
+
+    #[test]
+    pub fn test_segment_delete_stats_and_vacuum() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec!["col1".to_string(), "col2".to_string(), "col3".to_string()];
+        let docs = vec![
+            "vacuum".to_string(),
+            "segment".to_string(),
+            "test".to_string(),
+        ];
+
+        assert!(get_segment_delete_stats(temp_directory_str).is_err());
+        assert!(create_index(temp_directory_str, &column_names).is_ok());
+        for row_id in 0..4u64 {
+            assert!(
+                index_multi_column_docs(temp_directory_str, row_id, &column_names, &docs).is_ok()
+            );
+        }
+        assert!(commit_index(temp_directory_str).is_ok());
+        assert!(delete_row_ids(temp_directory_str, &vec![0, 1, 2]).is_ok());
+
+        let stats = get_segment_delete_stats(temp_directory_str).unwrap();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].max_doc, 4);
+        assert_eq!(stats[0].num_deleted_docs, 3);
+        assert!((stats[0].deleted_ratio - 0.75).abs() < f32::EPSILON);
+
+        // Invalid ratio.
+        assert!(vacuum_index(temp_directory_str, 0.0).is_err());
+        // No segment exceeds the ratio.
+        assert_eq!(vacuum_index(temp_directory_str, 0.8).unwrap(), false);
+        assert_eq!(vacuum_index(temp_directory_str, 0.5).unwrap(), true);
+
+        let stats = get_segment_delete_stats(temp_directory_str).unwrap();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].max_doc, 1);
+        assert_eq!(stats[0].num_deleted_docs, 0);
+        assert!(free_index_writer(temp_directory_str).is_ok());
+    }
+
+    #[test]
+    pub fn test_merges_deferred_by_throttle_are_retried() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec!["col1".to_string(), "col2".to_string(), "col3".to_string()];
+        let docs = vec![
+            "deferred".to_string(),
+            "merge".to_string(),
+            "retry".to_string(),
+        ];
+        assert!(create_index(temp_directory_str, &column_names).is_ok());
+        let searchable_segments = || {
+            FFI_INDEX_WRITER_CACHE
+                .get_index_writer_bridge(temp_directory_str.to_string())
+                .unwrap()
+                .index
+                .searchable_segment_ids()
+                .unwrap()
+                .len()
+        };
+
+        // Merge budget is in debt for 3 seconds, background merges are deferred meanwhile.
+        assert!(set_merge_throttle(1).unwrap());
+        assert!(MERGE_THROTTLE.try_acquire(4 * 1024 * 1024).is_ok());
+        // Builtin merge policy merges 5 segments of the same level.
+        for row_id in 0..5u64 {
+            assert!(
+                index_multi_column_docs(temp_directory_str, row_id, &column_names, &docs).is_ok()
+            );
+            assert!(commit_index(temp_directory_str).is_ok());
+        }
+        assert_eq!(searchable_segments(), 5);
+
+        // Deferred merge runs once budget is paid back, though nothing is committed anymore.
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(15);
+        while searchable_segments() > 1 && std::time::Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+        assert_eq!(searchable_segments(), 1);
+
+        assert!(set_merge_throttle(0).unwrap());
+        assert!(free_index_writer(temp_directory_str).is_ok());
+    }
+
+    #[test]
+    pub fn test_index_column_batch() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec!["col1".to_string(), "col2".to_string(), "col3".to_string()];
+
+        assert!(create_index(temp_directory_str, &column_names).is_ok());
+
+        // Concatenate mocked docs column by column.
+        let (col1_docs, col2_docs, col3_docs) = get_mocked_docs();
+        let row_ids: Vec<u64> = (0..col1_docs.len() as u64).collect();
+        let mut column_data: Vec<u8> = vec![];
+        let mut column_offsets: Vec<u64> = vec![0];
+        for docs in [&col1_docs, &col2_docs, &col3_docs] {
+            for doc in docs.iter() {
+                column_data.extend_from_slice(doc.as_bytes());
+                column_offsets.push(column_data.len() as u64);
+            }
+        }
+
+        // Offsets size doesn't match rows and columns.
+        let result = index_column_batch(
+            temp_directory_str,
+            &row_ids,
+            &column_names,
+            &column_data,
+            &column_offsets[1..],
+        );
+        assert!(matches!(
+            result,
+            Err(TantivySearchError::InvalidArgument(_))
+        ));
+
+        assert!(index_column_batch(
+            temp_directory_str,
+            &row_ids,
+            &column_names,
+            &column_data,
+            &column_offsets
+        )
+        .unwrap());
+        assert!(commit_index(temp_directory_str).is_ok());
+
+        // get index writer from CACHE
+        let index_writer_bridge = FFI_INDEX_WRITER_CACHE
+            .get_index_writer_bridge(temp_directory_str.to_string())
+            .unwrap();
+
+        search_with_index_writer_bridge(index_writer_bridge);
+        assert!(free_index_writer(temp_directory_str).is_ok());
+    }
+
+    #[test]
+    pub fn test_index_docs_with_auto_row_id() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec!["col1".to_string(), "col2".to_string(), "col3".to_string()];
+        let docs = vec!["auto".to_string(), "row".to_string(), "id".to_string()];
+
+        assert!(create_index(temp_directory_str, &column_names).is_ok());
+        assert!(index_multi_column_docs(temp_directory_str, 5, &column_names, &docs).is_ok());
+        assert!(commit_index(temp_directory_str).is_ok());
+
+        // Auto row_id must be enabled first.
+        let result = index_multi_column_docs_auto_row_id(temp_directory_str, &column_names, &docs);
+        assert!(matches!(
+            result,
+            Err(TantivySearchError::InvalidArgument(_))
+        ));
+
+        // Row_ids continue from max committed row_id.
+        assert_eq!(enable_auto_row_id(temp_directory_str).unwrap(), 6);
+        assert_eq!(
+            index_multi_column_docs_auto_row_id(temp_directory_str, &column_names, &docs).unwrap(),
+            6
+        );
+
+        let (col1_docs, col2_docs, col3_docs) = get_mocked_docs();
+        let mut column_data: Vec<u8> = vec![];
+        let mut column_offsets: Vec<u64> = vec![0];
+        for docs in [&col1_docs, &col2_docs, &col3_docs] {
+            for doc in docs.iter() {
+                column_data.extend_from_slice(doc.as_bytes());
+                column_offsets.push(column_data.len() as u64);
+            }
+        }
+        assert_eq!(
+            index_column_batch_auto_row_id(
+                temp_directory_str,
+                &column_names,
+                &column_data,
+                &column_offsets
+            )
+            .unwrap(),
+            7
+        );
+        // Failed batch doesn't consume row_ids.
+        assert!(index_column_batch_auto_row_id(
+            temp_directory_str,
+            &column_names,
+            &column_data,
+            &column_offsets[1..]
+        )
+        .is_err());
+        assert_eq!(
+            enable_auto_row_id(temp_directory_str).unwrap(),
+            7 + col1_docs.len() as u64
+        );
+        assert!(free_index_writer(temp_directory_str).is_ok());
+    }
+
+    #[test]
+    pub fn test_index_multi_column_borrowed_docs() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec!["col1".to_string(), "col2".to_string(), "col3".to_string()];
+
+        assert!(create_index(temp_directory_str, &column_names).is_ok());
+
+        // Docs of each row are borrowed from one shared buffer.
+        let (col1_docs, col2_docs, col3_docs) = get_mocked_docs();
+        for row_id in 0..col1_docs.len() {
+            let buffer = format!(
+                "{}{}{}",
+                col1_docs[row_id], col2_docs[row_id], col3_docs[row_id]
+            );
+            let (col1, rest) = buffer.split_at(col1_docs[row_id].len());
+            let (col2, col3) = rest.split_at(col2_docs[row_id].len());
+            assert!(index_multi_column_borrowed_docs(
+                temp_directory_str,
+                row_id as u64,
+                &column_names,
+                &[col1, col2, col3]
+            )
+            .unwrap());
+        }
+        // Columns size mismatch.
+        assert!(matches!(
+            index_multi_column_borrowed_docs(temp_directory_str, 5, &column_names, &["only one"]),
+            Err(TantivySearchError::InvalidArgument(_))
+        ));
+        assert!(commit_index(temp_directory_str).is_ok());
+
+        // get index writer from CACHE
+        let index_writer_bridge = FFI_INDEX_WRITER_CACHE
+            .get_index_writer_bridge(temp_directory_str.to_string())
+            .unwrap();
+
+        search_with_index_writer_bridge(index_writer_bridge);
+        assert!(free_index_writer(temp_directory_str).is_ok());
+    }
+
+    #[test]
+    pub fn test_index_column_batch_with_utf8_policy() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec!["col1".to_string()];
+        assert!(create_index(temp_directory_str, &column_names).is_ok());
+
+        // Second row contains invalid utf-8.
+        let row_ids: Vec<u64> = vec![0, 1, 2];
+        let column_data: Vec<u8> = b"first\xffsecondthird".to_vec();
+        let column_offsets: Vec<u64> = vec![0, 5, 12, 17];
+
+        let result = index_column_batch(
+            temp_directory_str,
+            &row_ids,
+            &column_names,
+            &column_data,
+            &column_offsets,
+        );
+        assert!(matches!(
+            result,
+            Err(TantivySearchError::InvalidArgument(_))
+        ));
+
+        UTF8_POLICY.set(Utf8Policy::SkipRow);
+        assert!(index_column_batch(
+            temp_directory_str,
+            &row_ids,
+            &column_names,
+            &column_data,
+            &column_offsets
+        )
+        .unwrap());
+        UTF8_POLICY.set(Utf8Policy::Reject);
+        assert!(commit_index(temp_directory_str).is_ok());
+
+        let index_writer_bridge = FFI_INDEX_WRITER_CACHE
+            .get_index_writer_bridge(temp_directory_str.to_string())
+            .unwrap();
+        let searcher = index_writer_bridge.index.reader().unwrap().searcher();
+        assert_eq!(searcher.num_docs(), 2);
+        assert!(free_index_writer(temp_directory_str).is_ok());
+    }
+
+    #[test]
+    pub fn test_estimate_index_size() {
+        let column_names = vec!["col1".to_string(), "col2".to_string()];
+        let sample_docs: Vec<String> = (0..20)
+            .flat_map(|row| {
+                vec![
+                    format!("Ancient empires rise and fall {}", row),
+                    format!("Social movements transform societies {}", row),
+                ]
+            })
+            .collect();
+
+        let estimate = estimate_index_size(&column_names, "{}", &sample_docs, 2000).unwrap();
+        assert_eq!(estimate.sample_docs, 20);
+        assert!(estimate.sample_index_bytes > 0);
+        assert!(estimate.estimated_index_bytes >= estimate.sample_index_bytes * 99);
+        // Each sample doc has 11 tokens.
+        assert_eq!(estimate.estimated_num_tokens, 2000 * 11);
+        assert!(estimate.estimated_reader_memory_bytes > 0);
+
+        // Sample docs should be full rows.
+        let result = estimate_index_size(&column_names, "{}", &sample_docs[1..].to_vec(), 2000);
+        assert!(matches!(
+            result,
+            Err(TantivySearchError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    pub fn test_build_progress() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec!["col1".to_string()];
+        assert!(create_index(temp_directory_str, &column_names).is_ok());
+        assert!(matches!(
+            get_build_progress(temp_directory_str),
+            Err(TantivySearchError::InvalidArgument(_))
+        ));
+
+        assert!(start_build_progress(temp_directory_str, 20, 0).is_ok());
+        for row_id in 0..10 {
+            assert!(index_multi_column_docs(
+                temp_directory_str,
+                row_id,
+                &column_names,
+                &vec![format!("progress {}", row_id)]
+            )
+            .is_ok());
+        }
+        let progress = get_build_progress(temp_directory_str).unwrap();
+        assert_eq!(progress.total_docs, 20);
+        assert_eq!(progress.docs_indexed, 10);
+        assert!(progress.bytes_indexed > 0);
+        assert!(stop_build_progress(temp_directory_str).unwrap());
+        assert!(!stop_build_progress(temp_directory_str).unwrap());
+
+        assert!(free_index_writer(temp_directory_str).is_ok());
+        assert!(start_build_progress(temp_directory_str, 20, 0).is_err());
+    }
+
+    #[test]
+    pub fn test_build_checkpoint() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec!["col1".to_string()];
+        assert!(create_index(temp_directory_str, &column_names).is_ok());
+        assert_eq!(get_build_checkpoint(temp_directory_str).unwrap(), 0);
+
+        for row_id in 0..5 {
+            assert!(index_multi_column_docs(
+                temp_directory_str,
+                row_id,
+                &column_names,
+                &vec![format!("checkpoint {}", row_id)]
+            )
+            .is_ok());
+        }
+        assert!(commit_index(temp_directory_str).is_ok());
+        assert_eq!(get_build_checkpoint(temp_directory_str).unwrap(), 5);
+
+        // Rows not committed aren't durable.
+        let column_data = b"checkpoint 5checkpoint 6".to_vec();
+        assert!(index_column_batch(
+            temp_directory_str,
+            &[5, 6],
+            &column_names,
+            &column_data,
+            &[0, 12, 24]
+        )
+        .is_ok());
+        assert_eq!(get_build_checkpoint(temp_directory_str).unwrap(), 5);
+
+        // Uncommitted rows are lost when writer is freed, checkpoint isn't dropped by
+        // reloaded writer committing without new rows.
+        assert!(free_index_writer(temp_directory_str).is_ok());
+        assert!(load_index_writer(temp_directory_str).is_ok());
+        assert!(commit_index(temp_directory_str).is_ok());
+        assert_eq!(get_build_checkpoint(temp_directory_str).unwrap(), 5);
+
+        // Resume from checkpoint.
+        assert!(index_column_batch(
+            temp_directory_str,
+            &[5, 6],
+            &column_names,
+            &column_data,
+            &[0, 12, 24]
+        )
+        .is_ok());
+        assert!(commit_index(temp_directory_str).is_ok());
+        assert_eq!(get_build_checkpoint(temp_directory_str).unwrap(), 7);
+        assert!(free_index_writer(temp_directory_str).is_ok());
+
+        assert!(
+            get_build_checkpoint(temp_directory.path().join("missing").to_str().unwrap()).is_err()
+        );
+    }
+
+    #[test]
+    pub fn test_multi_part_build() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let index_paths: Vec<String> = (0..2)
+            .map(|i| {
+                temp_directory
+                    .path()
+                    .join(format!("part_{}", i))
+                    .to_str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect();
+        let column_names = vec!["col1".to_string()];
+        // Memory budget can't satisfy writers of all parts.
+        assert!(matches!(
+            create_multi_part_build(&index_paths, &column_names, "{}", 1024 * 1024 * 16, 2),
+            Err(TantivySearchError::InvalidArgument(_))
+        ));
+
+        let build_id =
+            create_multi_part_build(&index_paths, &column_names, "{}", 1024 * 1024 * 64, 2)
+                .unwrap();
+        for index_path in &index_paths {
+            assert!(FFI_INDEX_WRITER_CACHE
+                .get_index_writer_bridge(index_path.clone())
+                .is_ok());
+        }
+        // Rows 0, 2, 3 belong to part 0, row 1 belongs to part 1.
+        let column_data = b"row0row1row2row3".to_vec();
+        assert!(multi_part_build_index_batch(
+            build_id,
+            &[0, 1, 0, 0],
+            &[0, 1, 2, 3],
+            &column_data,
+            &[0, 4, 8, 12, 16]
+        )
+        .is_ok());
+        // Part id out of range.
+        assert!(multi_part_build_index_batch(build_id, &[2], &[4], b"row4", &[0, 4]).is_err());
+
+        assert!(finish_multi_part_build(build_id).is_ok());
+        assert!(finish_multi_part_build(build_id).is_err());
+        for (index_path, expected_docs) in index_paths.iter().zip([3u64, 1]) {
+            assert!(FFI_INDEX_WRITER_CACHE
+                .get_index_writer_bridge(index_path.clone())
+                .is_err());
+            let index = tantivy::Index::open_in_dir(index_path).unwrap();
+            let searcher = index.reader().unwrap().searcher();
+            assert_eq!(searcher.num_docs(), expected_docs);
+        }
+    }
+
+    #[test]
+    pub fn test_sorted_build() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let spill_directory = TempDir::new().unwrap();
+        let spill_directory_str = spill_directory.path().to_str().unwrap();
+        let column_names = vec!["col1".to_string()];
+        assert!(create_index(temp_directory_str, &column_names).is_ok());
+        // Rows out of order would be rejected if they reached the writer unsorted.
+        assert!(set_row_id_check(temp_directory_str, "increasing", true).is_ok());
+
+        assert!(finish_sorted_build(temp_directory_str).is_err());
+        assert!(sorted_build_add_batch(temp_directory_str, &[0], b"row0", &[0, 4]).is_err());
+        assert!(start_sorted_build(
+            temp_directory_str,
+            &vec!["missing".to_string()],
+            1,
+            spill_directory_str
+        )
+        .is_err());
+        // Every batch is spilled with 1 byte memory limit.
+        assert!(
+            start_sorted_build(temp_directory_str, &column_names, 1, spill_directory_str).is_ok()
+        );
+        assert!(
+            start_sorted_build(temp_directory_str, &column_names, 1, spill_directory_str).is_err()
+        );
+        assert!(
+            sorted_build_add_batch(temp_directory_str, &[5, 1], b"row5row1", &[0, 4, 8]).is_ok()
+        );
+        assert!(sorted_build_add_batch(temp_directory_str, &[3], b"row3", &[0, 4]).is_ok());
+        assert!(sorted_build_add_batch(
+            temp_directory_str,
+            &[4, 0, 2],
+            b"row4row0row2",
+            &[0, 4, 8, 12]
+        )
+        .is_ok());
+        assert!(sorted_build_add_batch(temp_directory_str, &[6], b"row6", &[0]).is_err());
+        assert_eq!(
+            std::fs::read_dir(spill_directory.path()).unwrap().count(),
+            1
+        );
+
+        assert_eq!(finish_sorted_build(temp_directory_str).unwrap(), 6);
+        assert!(finish_sorted_build(temp_directory_str).is_err());
+        assert_eq!(
+            std::fs::read_dir(spill_directory.path()).unwrap().count(),
+            0
+        );
+        assert_eq!(get_build_checkpoint(temp_directory_str).unwrap(), 6);
+        let index_writer_bridge = FFI_INDEX_WRITER_CACHE
+            .get_index_writer_bridge(temp_directory_str.to_string())
+            .unwrap();
+        let searcher = index_writer_bridge.index.reader().unwrap().searcher();
+        assert_eq!(searcher.num_docs(), 6);
+        assert!(free_index_writer(temp_directory_str).is_ok());
+    }
+
+    #[test]
+    pub fn test_reindex_index() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec!["col1".to_string()];
+        let case_sensitive = r#"{"col1": {"tokenizer": {"type": "whitespace", "case_sensitive": true, "store_doc": true}}}"#;
+        let lowercase = r#"{"col1": {"tokenizer": {"type": "whitespace", "case_sensitive": false, "store_doc": true}}}"#;
+        assert!(
+            create_index_with_parameter(temp_directory_str, &column_names, case_sensitive).is_ok()
+        );
+        assert!(index_column_batch(
+            temp_directory_str,
+            &[3, 1, 2],
+            &column_names,
+            b"Hello WorldHello ThereBye",
+            &[0, 11, 22, 25]
+        )
+        .is_ok());
+        assert!(delete_row_ids(temp_directory_str, &[2]).is_ok());
+        assert!(load_index_reader(temp_directory_str).is_ok());
+
+        // Docs of term in the index currently loaded by writer.
+        let count_term = |text: &str| {
+            let index_writer_bridge = FFI_INDEX_WRITER_CACHE
+                .get_index_writer_bridge(temp_directory_str.to_string())
+                .unwrap();
+            let index = &index_writer_bridge.index;
+            let col1 = index.schema().get_field("col1").unwrap();
+            let query = TermQuery::new(Term::from_field_text(col1, text), IndexRecordOption::Basic);
+            index
+                .reader()
+                .unwrap()
+                .searcher()
+                .search(&query, &Count)
+                .unwrap()
+        };
+        assert_eq!(count_term("Hello"), 2);
+        assert_eq!(count_term("hello"), 0);
+
+        // Rows are re-indexed from stored texts, deleted rows are dropped.
+        assert_eq!(
+            reindex_index(temp_directory_str, lowercase, None).unwrap(),
+            2
+        );
+        assert_eq!(count_term("Hello"), 0);
+        assert_eq!(count_term("hello"), 2);
+        assert!(FFI_INDEX_SEARCHER_CACHE
+            .get_index_reader_bridge(temp_directory_str.to_string())
+            .is_ok());
+        assert!(!temp_directory.path().with_extension("reindex").exists());
+        assert_eq!(get_build_checkpoint(temp_directory_str).unwrap(), 4);
+
+        // Rows fetched from source, skipped rows are left out.
+        let fetch = |row_id: u64, column_names: &[String]| -> Result<Option<Vec<String>>, String> {
+            assert_eq!(column_names, &["col1".to_string()]);
+            if row_id == 1 {
+                Ok(None)
+            } else {
+                Ok(Some(vec![format!("Fetched {}", row_id)]))
+            }
+        };
+        assert_eq!(
+            reindex_index(temp_directory_str, case_sensitive, Some(&fetch)).unwrap(),
+            1
+        );
+        assert_eq!(count_term("Fetched"), 1);
+        assert_eq!(count_term("hello"), 0);
+
+        // Columns not stored can't be re-indexed from index, the index is kept.
+        assert!(create_index(temp_directory_str, &column_names).is_ok());
+        assert!(
+            index_column_batch(temp_directory_str, &[0], &column_names, b"hello", &[0, 5]).is_ok()
+        );
+        assert!(matches!(
+            reindex_index(temp_directory_str, lowercase, None),
+            Err(TantivySearchError::InvalidArgument(_))
+        ));
+        assert_eq!(count_term("hello"), 1);
+        assert!(!temp_directory.path().with_extension("reindex").exists());
+
+        assert!(free_index_reader(temp_directory_str).is_ok());
+        assert!(free_index_writer(temp_directory_str).is_ok());
+    }
+
+    #[test]
+    pub fn test_upgrade_index() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec!["col1".to_string()];
+        assert!(create_index(temp_directory_str, &column_names).is_ok());
+        for row_id in 0..2 {
+            assert!(index_column_batch(
+                temp_directory_str,
+                &[row_id],
+                &column_names,
+                b"hello",
+                &[0, 5]
+            )
+            .is_ok());
+            assert!(commit_index(temp_directory_str).is_ok());
+        }
+        assert!(free_index_writer(temp_directory_str).is_ok());
+
+        // Created indexes record current versions.
+        let mut index_parameter_dto: IndexParameterDTO =
+            IndexUtils::load_custom_index_setting(temp_directory.path()).unwrap();
+        assert_eq!(index_parameter_dto.format_version, INDEX_FORMAT_VERSION);
+        assert!(!upgrade_index(temp_directory_str).unwrap());
+
+        // Index written before versions were recorded, segments are rewritten into one.
+        let old_dto = IndexParameterDTO {
+            tokenizers_json_parameter: index_parameter_dto.tokenizers_json_parameter.clone(),
+            ..IndexParameterDTO::default()
+        };
+        IndexUtils::save_custom_index_setting(temp_directory.path(), &old_dto).unwrap();
+        assert!(upgrade_index(temp_directory_str).unwrap());
+        assert!(FFI_INDEX_WRITER_CACHE
+            .get_index_writer_bridge(temp_directory_str.to_string())
+            .is_err());
+        assert_eq!(
+            IndexUtils::load_custom_index_setting(temp_directory.path()).unwrap(),
+            index_parameter_dto
+        );
+        assert!(!upgrade_index(temp_directory_str).unwrap());
+        assert!(load_index_writer(temp_directory_str).is_ok());
+        let index_writer_bridge = FFI_INDEX_WRITER_CACHE
+            .get_index_writer_bridge(temp_directory_str.to_string())
+            .unwrap();
+        assert_eq!(
+            index_writer_bridge
+                .index
+                .searchable_segment_ids()
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            index_writer_bridge
+                .index
+                .reader()
+                .unwrap()
+                .searcher()
+                .num_docs(),
+            2
+        );
+        assert!(free_index_writer(temp_directory_str).is_ok());
+
+        // Index written by a newer version is refused before it's opened.
+        index_parameter_dto.format_version = INDEX_FORMAT_VERSION + 1;
+        IndexUtils::save_custom_index_setting(temp_directory.path(), &index_parameter_dto).unwrap();
+        assert!(matches!(
+            load_index_writer(temp_directory_str),
+            Err(TantivySearchError::IncompatibleIndexVersion(_))
+        ));
+        assert!(matches!(
+            load_index_reader(temp_directory_str),
+            Err(TantivySearchError::IncompatibleIndexVersion(_))
+        ));
+        assert!(matches!(
+            upgrade_index(temp_directory_str),
+            Err(TantivySearchError::IncompatibleIndexVersion(_))
+        ));
+    }
+
+    #[test]
+    pub fn test_create_index_with_index_sort() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec!["col1".to_string()];
+        // Text column can't be sort field.
+        assert!(matches!(
+            create_index_with_parameter(
+                temp_directory_str,
+                &column_names,
+                r#"{"index_sort": {"field": "col1"}}"#,
+            ),
+            Err(TantivySearchError::InvalidArgument(_))
+        ));
+
+        assert!(create_index_with_parameter(
+            temp_directory_str,
+            &column_names,
+            r#"{"index_sort": {"field": "row_id", "order": "desc"},
+                "col1": {"tokenizer": {"type": "default"}}}"#,
+        )
+        .is_ok());
+        let column_data = b"row1row3row2".to_vec();
+        assert!(index_column_batch(
+            temp_directory_str,
+            &[1, 3, 2],
+            &column_names,
+            &column_data,
+            &[0, 4, 8, 12]
+        )
+        .is_ok());
+        assert!(commit_index(temp_directory_str).is_ok());
+
+        let index_writer_bridge = FFI_INDEX_WRITER_CACHE
+            .get_index_writer_bridge(temp_directory_str.to_string())
+            .unwrap();
+        // Docs may be spread over segments of indexing threads, each segment keeps sort order.
+        let searcher = index_writer_bridge.index.reader().unwrap().searcher();
+        let mut num_docs = 0;
+        for segment_reader in searcher.segment_readers() {
+            let row_id_column = segment_reader.fast_fields().u64("row_id").unwrap();
+            let row_ids: Vec<u64> = (0..segment_reader.max_doc())
+                .map(|doc_id| row_id_column.first(doc_id).unwrap())
+                .collect();
+            assert!(row_ids.windows(2).all(|w| w[0] > w[1]));
+            num_docs += row_ids.len();
+        }
+        assert_eq!(num_docs, 3);
+        assert!(free_index_writer(temp_directory_str).is_ok());
+    }
+
+    #[test]
+    pub fn test_create_index_with_index_record_option() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+
+        let result = create_index_with_parameter(
+            temp_directory_str,
+            &vec!["col1".to_string(), "col2".to_string()],
+            r#"{"col1": {"tokenizer": {"type": "default"}, "index_record_option": "basic"},
+                "col2": {"tokenizer": {"type": "default"}}}"#,
+        );
+        assert!(result.is_ok());
+
+        let index_writer_bridge = FFI_INDEX_WRITER_CACHE
+            .get_index_writer_bridge(temp_directory_str.to_string())
+            .unwrap();
+        let schema = index_writer_bridge.index.schema();
+        let record_option_of = |column_name: &str| match schema
+            .get_field_entry(schema.get_field(column_name).unwrap())
+            .field_type()
+        {
+            FieldType::Str(text_options) => text_options
+                .get_indexing_options()
+                .map(|indexing| indexing.index_option()),
+            _ => None,
+        };
+        assert_eq!(record_option_of("col1"), Some(IndexRecordOption::Basic));
+        assert_eq!(
+            record_option_of("col2"),
+            Some(IndexRecordOption::WithFreqsAndPositions)
+        );
+        assert!(free_index_writer(temp_directory_str).is_ok());
+
+        // Record option of non-text column is rejected.
+        let result = create_index_with_parameter(
+            temp_directory_str,
+            &vec!["col1".to_string()],
+            r#"{"col1": {"tokenizer": {"type": "i64"}, "index_record_option": "freqs"}}"#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn test_get_stored_fields_with_column_stored_option() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec![
+            "title".to_string(),
+            "body".to_string(),
+            "source".to_string(),
+        ];
+
+        // `body` is indexed only, `source` is stored only.
+        let result = create_index_with_parameter(
+            temp_directory_str,
+            &column_names,
+            r#"{"title": {"tokenizer": {"type": "default"}, "stored": true},
+                "body": {"tokenizer": {"type": "default", "store_doc": true}, "stored": false},
+                "source": {"tokenizer": {"type": "raw"}, "stored": true, "indexed": false}}"#,
+        );
+        assert!(result.is_ok());
+
+        let index_writer_bridge = FFI_INDEX_WRITER_CACHE
+            .get_index_writer_bridge(temp_directory_str.to_string())
+            .unwrap();
+        let schema = index_writer_bridge.index.schema();
+        let entry_of =
+            |column_name: &str| schema.get_field_entry(schema.get_field(column_name).unwrap());
+        assert!(entry_of("title").is_stored() && entry_of("title").is_indexed());
+        assert!(!entry_of("body").is_stored() && entry_of("body").is_indexed());
+        assert!(entry_of("source").is_stored() && !entry_of("source").is_indexed());
+
+        let docs = vec![
+            vec![
+                "apple pie".to_string(),
+                "sweet apple".to_string(),
+                "s3://a".to_string(),
+            ],
+            vec![
+                "banana".to_string(),
+                "yellow banana".to_string(),
+                "s3://b".to_string(),
+            ],
+        ];
+        for (row_id, column_docs) in docs.iter().enumerate() {
+            assert!(index_multi_column_docs(
+                temp_directory_str,
+                row_id as u64,
+                &column_names,
+                column_docs
+            )
+            .is_ok());
+        }
+        assert!(commit_index(temp_directory_str).is_ok());
+        assert!(load_index_reader(temp_directory_str).is_ok());
+
+        // Only requested fields are returned, missing row_ids are skipped.
+        let stored_docs = get_stored_fields(
+            temp_directory_str,
+            &vec![1, 0, 9],
+            &vec!["source".to_string(), "title".to_string()],
+        )
+        .unwrap();
+        assert_eq!(stored_docs.len(), 2);
+        assert_eq!(stored_docs[0].row_id, 1);
+        assert_eq!(stored_docs[0].values, vec!["s3://b", "banana"]);
+        assert_eq!(stored_docs[1].row_id, 0);
+        assert_eq!(stored_docs[1].values, vec!["s3://a", "apple pie"]);
+
+        // Fields not stored are rejected.
+        assert!(
+            get_stored_fields(temp_directory_str, &vec![0], &vec!["body".to_string()]).is_err()
+        );
+
+        // Stored only column isn't searched by BM25.
+        let result = bm25_search_with_column_names(
+            temp_directory_str,
+            "apple",
+            10,
+            &vec![],
+            false,
+            false,
+            0,
+            0,
+            false,
+            &vec![],
+        )
+        .unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].row_id, 0);
+
+        assert!(free_index_reader(temp_directory_str).is_ok());
+        assert!(free_index_writer(temp_directory_str).is_ok());
+
+        // Column neither stored nor indexed is rejected.
+        let result = create_index_with_parameter(
+            temp_directory_str,
+            &vec!["col1".to_string()],
+            r#"{"col1": {"tokenizer": {"type": "default"}, "indexed": false}}"#,
+        );
+        assert!(result.is_err());
+    }
 }
@@ -0,0 +1,119 @@
+//! JNI bindings of index APIs, native methods of `io.dingodb.tantivy.TantivySearch`.
+
+use super::jni_utils::*;
+use crate::index::implements::api_index_impl::*;
+use jni::objects::{JClass, JLongArray, JObjectArray, JString};
+use jni::sys::{jboolean, jlong, JNI_FALSE};
+use jni::JNIEnv;
+
+#[no_mangle]
+pub extern "system" fn Java_io_dingodb_tantivy_TantivySearch_createIndex<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    index_path: JString<'local>,
+    column_names: JObjectArray<'local>,
+) -> jboolean {
+    jni_call(&mut env, "createIndex", JNI_FALSE, |env| {
+        let index_path: String = get_string(env, &index_path, "index_path")?;
+        let column_names: Vec<String> = get_string_array(env, &column_names, "column_names")?;
+        create_index(&index_path, &column_names)
+            .map(|result| result as jboolean)
+            .map_err(|e| e.to_string())
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_io_dingodb_tantivy_TantivySearch_createIndexWithParameter<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    index_path: JString<'local>,
+    column_names: JObjectArray<'local>,
+    index_json_parameter: JString<'local>,
+) -> jboolean {
+    jni_call(&mut env, "createIndexWithParameter", JNI_FALSE, |env| {
+        let index_path: String = get_string(env, &index_path, "index_path")?;
+        let column_names: Vec<String> = get_string_array(env, &column_names, "column_names")?;
+        let index_json_parameter: String =
+            get_string(env, &index_json_parameter, "index_json_parameter")?;
+        create_index_with_parameter(&index_path, &column_names, &index_json_parameter)
+            .map(|result| result as jboolean)
+            .map_err(|e| e.to_string())
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_io_dingodb_tantivy_TantivySearch_indexMultiColumnDocs<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    index_path: JString<'local>,
+    row_id: jlong,
+    column_names: JObjectArray<'local>,
+    column_docs: JObjectArray<'local>,
+) -> jboolean {
+    jni_call(&mut env, "indexMultiColumnDocs", JNI_FALSE, |env| {
+        let index_path: String = get_string(env, &index_path, "index_path")?;
+        let column_names: Vec<String> = get_string_array(env, &column_names, "column_names")?;
+        let column_docs: Vec<String> = get_string_array(env, &column_docs, "column_docs")?;
+        index_multi_column_docs(&index_path, row_id as u64, &column_names, &column_docs)
+            .map(|result| result as jboolean)
+            .map_err(|e| e.to_string())
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_io_dingodb_tantivy_TantivySearch_deleteRowIds<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    index_path: JString<'local>,
+    row_ids: JLongArray<'local>,
+) -> jboolean {
+    jni_call(&mut env, "deleteRowIds", JNI_FALSE, |env| {
+        let index_path: String = get_string(env, &index_path, "index_path")?;
+        let row_ids: Vec<u64> = get_u64_array(env, &row_ids, "row_ids")?;
+        delete_row_ids(&index_path, &row_ids)
+            .map(|result| result as jboolean)
+            .map_err(|e| e.to_string())
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_io_dingodb_tantivy_TantivySearch_commitIndex<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    index_path: JString<'local>,
+) -> jboolean {
+    jni_call(&mut env, "commitIndex", JNI_FALSE, |env| {
+        let index_path: String = get_string(env, &index_path, "index_path")?;
+        commit_index(&index_path)
+            .map(|result| result as jboolean)
+            .map_err(|e| e.to_string())
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_io_dingodb_tantivy_TantivySearch_loadIndexWriter<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    index_path: JString<'local>,
+) -> jboolean {
+    jni_call(&mut env, "loadIndexWriter", JNI_FALSE, |env| {
+        let index_path: String = get_string(env, &index_path, "index_path")?;
+        load_index_writer(&index_path)
+            .map(|result| result as jboolean)
+            .map_err(|e| e.to_string())
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_io_dingodb_tantivy_TantivySearch_freeIndexWriter<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    index_path: JString<'local>,
+) -> jboolean {
+    jni_call(&mut env, "freeIndexWriter", JNI_FALSE, |env| {
+        let index_path: String = get_string(env, &index_path, "index_path")?;
+        free_index_writer(&index_path)
+            .map(|result| result as jboolean)
+            .map_err(|e| e.to_string())
+    })
+}
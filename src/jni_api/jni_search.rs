@@ -0,0 +1,151 @@
+//! JNI bindings of search APIs, native methods of `io.dingodb.tantivy.TantivySearch`.
+
+use super::jni_utils::*;
+use crate::search::implements::api_clickhouse_impl::query_term_bitmap;
+use crate::search::implements::api_common_impl::{
+    free_index_reader, get_indexed_doc_counts, load_index_reader,
+};
+use crate::search::implements::api_dingo_impl::{
+    bm25_search_with_column_names, index_reader_reload,
+};
+use jni::objects::{JClass, JObject, JObjectArray, JString, JValue};
+use jni::sys::{jboolean, jbyteArray, jint, jlong, jobjectArray, JNI_FALSE};
+use jni::JNIEnv;
+
+// Java class of BM25 search result, constructed by `(long rowId, float score)`.
+static ROW_ID_WITH_SCORE_CLASS: &str = "io/dingodb/tantivy/RowIdWithScore";
+
+#[no_mangle]
+pub extern "system" fn Java_io_dingodb_tantivy_TantivySearch_loadIndexReader<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    index_path: JString<'local>,
+) -> jboolean {
+    jni_call(&mut env, "loadIndexReader", JNI_FALSE, |env| {
+        let index_path: String = get_string(env, &index_path, "index_path")?;
+        load_index_reader(&index_path)
+            .map(|result| result as jboolean)
+            .map_err(|e| e.to_string())
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_io_dingodb_tantivy_TantivySearch_freeIndexReader<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    index_path: JString<'local>,
+) -> jboolean {
+    jni_call(&mut env, "freeIndexReader", JNI_FALSE, |env| {
+        let index_path: String = get_string(env, &index_path, "index_path")?;
+        free_index_reader(&index_path)
+            .map(|result| result as jboolean)
+            .map_err(|e| e.to_string())
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_io_dingodb_tantivy_TantivySearch_indexReaderReload<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    index_path: JString<'local>,
+) -> jboolean {
+    jni_call(&mut env, "indexReaderReload", JNI_FALSE, |env| {
+        let index_path: String = get_string(env, &index_path, "index_path")?;
+        index_reader_reload(&index_path)
+            .map(|result| result as jboolean)
+            .map_err(|e| e.to_string())
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_io_dingodb_tantivy_TantivySearch_getIndexedDocCounts<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    index_path: JString<'local>,
+) -> jlong {
+    jni_call(&mut env, "getIndexedDocCounts", 0, |env| {
+        let index_path: String = get_string(env, &index_path, "index_path")?;
+        get_indexed_doc_counts(&index_path)
+            .map(|result| result as jlong)
+            .map_err(|e| e.to_string())
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_io_dingodb_tantivy_TantivySearch_queryTermBitmap<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    index_path: JString<'local>,
+    column_name: JString<'local>,
+    term: JString<'local>,
+) -> jbyteArray {
+    jni_call(&mut env, "queryTermBitmap", std::ptr::null_mut(), |env| {
+        let index_path: String = get_string(env, &index_path, "index_path")?;
+        let column_name: String = get_string(env, &column_name, "column_name")?;
+        let term: String = get_string(env, &term, "term")?;
+        let bitmap: Vec<u8> =
+            query_term_bitmap(&index_path, &column_name, &term).map_err(|e| e.to_string())?;
+        env.byte_array_from_slice(&bitmap)
+            .map(|array| array.into_raw())
+            .map_err(|e| format!("Failed to create byte array: {}", e))
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_io_dingodb_tantivy_TantivySearch_bm25Search<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    index_path: JString<'local>,
+    sentence: JString<'local>,
+    topk: jint,
+    column_names: JObjectArray<'local>,
+) -> jobjectArray {
+    jni_call(&mut env, "bm25Search", std::ptr::null_mut(), |env| {
+        let index_path: String = get_string(env, &index_path, "index_path")?;
+        let sentence: String = get_string(env, &sentence, "sentence")?;
+        if topk < 0 {
+            return Err(format!("`topk` can't be negative: {}", topk));
+        }
+        // Empty `column_names` means search all columns.
+        let column_names: Vec<String> = if column_names.is_null() {
+            vec![]
+        } else {
+            get_string_array(env, &column_names, "column_names")?
+        };
+        let results = bm25_search_with_column_names(
+            &index_path,
+            &sentence,
+            topk as u32,
+            &vec![],
+            false,
+            false,
+            0,
+            0,
+            false,
+            &column_names,
+        )
+        .map_err(|e| e.to_string())?;
+
+        let jni_error = |e: jni::errors::Error| format!("Failed to build search results: {}", e);
+        let result_class = env.find_class(ROW_ID_WITH_SCORE_CLASS).map_err(jni_error)?;
+        let result_array = env
+            .new_object_array(results.len() as i32, &result_class, JObject::null())
+            .map_err(jni_error)?;
+        for (index, result) in results.iter().enumerate() {
+            let element = env
+                .new_object(
+                    &result_class,
+                    "(JF)V",
+                    &[
+                        JValue::Long(result.row_id as jlong),
+                        JValue::Float(result.score),
+                    ],
+                )
+                .map_err(jni_error)?;
+            env.set_object_array_element(&result_array, index as i32, &element)
+                .map_err(jni_error)?;
+            let _ = env.delete_local_ref(element);
+        }
+        Ok(result_array.into_raw())
+    })
+}
@@ -0,0 +1,80 @@
+use crate::logger::logger_bridge::TantivySearchLogger;
+use crate::{common::constants::LOG_CALLBACK, ERROR};
+use jni::objects::{JLongArray, JObjectArray, JString};
+use jni::JNIEnv;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+// Exception thrown to JVM when a native call failed.
+static JAVA_EXCEPTION_CLASS: &str = "java/lang/RuntimeException";
+
+// Run JNI function body, errors and panics are thrown as java exception with `default` returned.
+pub(crate) fn jni_call<'local, T, F>(
+    env: &mut JNIEnv<'local>,
+    function: &str,
+    default: T,
+    body: F,
+) -> T
+where
+    F: FnOnce(&mut JNIEnv<'local>) -> Result<T, String>,
+{
+    let error: String = match catch_unwind(AssertUnwindSafe(|| body(env))) {
+        Ok(Ok(result)) => return result,
+        Ok(Err(e)) => e,
+        Err(_) => format!("Unexpected panic in `{}`", function),
+    };
+    ERROR!(function: function, "{}", error);
+    // A pending exception may exist if error comes from JNI itself.
+    if !env.exception_check().unwrap_or(false) {
+        let _ = env.throw_new(JAVA_EXCEPTION_CLASS, error);
+    }
+    default
+}
+
+pub(crate) fn get_string(env: &mut JNIEnv, value: &JString, name: &str) -> Result<String, String> {
+    if value.is_null() {
+        return Err(format!("`{}` can't be null", name));
+    }
+    env.get_string(value)
+        .map(|value| value.into())
+        .map_err(|e| format!("Failed to convert `{}`: {}", name, e))
+}
+
+pub(crate) fn get_string_array(
+    env: &mut JNIEnv,
+    values: &JObjectArray,
+    name: &str,
+) -> Result<Vec<String>, String> {
+    if values.is_null() {
+        return Err(format!("`{}` can't be null", name));
+    }
+    let length = env
+        .get_array_length(values)
+        .map_err(|e| format!("Failed to get length of `{}`: {}", name, e))?;
+    let mut result: Vec<String> = Vec::with_capacity(length as usize);
+    for index in 0..length {
+        let element: JString = env
+            .get_object_array_element(values, index)
+            .map_err(|e| format!("Failed to get element of `{}`: {}", name, e))?
+            .into();
+        result.push(get_string(env, &element, name)?);
+        let _ = env.delete_local_ref(element);
+    }
+    Ok(result)
+}
+
+pub(crate) fn get_u64_array(
+    env: &mut JNIEnv,
+    values: &JLongArray,
+    name: &str,
+) -> Result<Vec<u64>, String> {
+    if values.is_null() {
+        return Err(format!("`{}` can't be null", name));
+    }
+    let length = env
+        .get_array_length(values)
+        .map_err(|e| format!("Failed to get length of `{}`: {}", name, e))?;
+    let mut buffer: Vec<i64> = vec![0; length as usize];
+    env.get_long_array_region(values, 0, &mut buffer)
+        .map_err(|e| format!("Failed to read `{}`: {}", name, e))?;
+    Ok(buffer.into_iter().map(|value| value as u64).collect())
+}
@@ -0,0 +1,3 @@
+pub mod jni_index;
+pub mod jni_search;
+mod jni_utils;
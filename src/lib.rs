@@ -1,14 +1,44 @@
+use ffi::AdaptiveBitmapResult;
+use ffi::AllocatorStatsResult;
+use ffi::AnalyzedTokensResult;
 use ffi::BM25Result;
 use ffi::BoolResult;
+use ffi::BuildProgressResult;
+use ffi::CardinalityResult;
 use ffi::DocWithFreq;
 use ffi::FieldTokenNums;
+use ffi::GroupTopDocsResult;
+use ffi::IndexHealthResult;
+use ffi::IndexSizeEstimateResult;
+use ffi::LivenessResult;
+use ffi::MatchedOffsetsResult;
+use ffi::MatchedTermsTopDocsResult;
+use ffi::PercentilesResult;
+use ffi::QueryIdsResult;
+use ffi::RowIdRange;
 use ffi::RowIdWithScore;
+use ffi::SegmentDeleteStatsResult;
 use ffi::Statistics;
+use ffi::StoredDocsResult;
 use ffi::StringResult;
+use ffi::TopDocsWithTotalHits;
+use ffi::TopDocsWithTotalHitsResult;
+use ffi::U64Result;
 use std::cmp::Ordering;
 
+#[cfg(feature = "tokio")]
+pub mod async_api;
+mod capi;
+#[cfg(feature = "cli")]
+pub mod cli;
 mod common;
+#[cfg(feature = "fuzzing")]
+pub mod fuzzing;
+#[cfg(feature = "grpc-server")]
+pub mod grpc_server;
 mod index;
+#[cfg(feature = "jni")]
+mod jni_api;
 mod logger;
 mod search;
 mod tokenizer;
@@ -19,6 +49,13 @@ use search::api::api_clickhouse::*;
 use search::api::api_common::*;
 use search::api::api_dingo::*;
 use utils::ffi_utils::*;
+// re-export plain C api.
+pub use capi::capi_index::*;
+pub use capi::capi_search::*;
+pub use capi::capi_types::*;
+// re-export error type used by async api.
+#[cfg(feature = "tokio")]
+pub use common::errors::TantivySearchError;
 // re-export log ffi function.
 pub use logger::ffi_logger::*;
 
@@ -55,6 +92,13 @@ pub mod ffi {
         error_msg: String,
     }
 
+    #[derive(Debug, Clone)]
+    pub struct U64Result {
+        result: u64,
+        error_code: i32,
+        error_msg: String,
+    }
+
     #[derive(Debug, Clone, PartialEq)]
     pub struct DocWithFreq {
         pub term_str: String,
@@ -68,6 +112,238 @@ pub mod ffi {
         pub field_total_tokens: u64,
     }
 
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct AnalyzedToken {
+        pub token: String,
+        pub position: u64,
+        pub offset_from: u64,
+        pub offset_to: u64,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct AnalyzedTokensResult {
+        result: Vec<AnalyzedToken>,
+        error_code: i32,
+        error_msg: String,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct IndexHealth {
+        pub canonical_path: String,
+        pub writer_alive: bool,
+        pub writer_busy: bool,
+        pub reader_loaded: bool,
+        pub reader_generation: u64,
+        pub last_commit_opstamp: u64,
+        pub lock_contention_count: u64,
+        pub last_error: String,
+        pub row_id_mapping_bytes: u64,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct IndexHealthResult {
+        result: IndexHealth,
+        error_code: i32,
+        error_msg: String,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct AllocatorStats {
+        pub allocator: String,
+        pub allocated_bytes: u64,
+        pub active_bytes: u64,
+        pub resident_bytes: u64,
+        pub retained_bytes: u64,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct AllocatorStatsResult {
+        result: AllocatorStats,
+        error_code: i32,
+        error_msg: String,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct SubsystemLiveness {
+        pub name: String,
+        pub running: bool,
+        pub alive: bool,
+        pub last_heartbeat_millis: u64,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct LivenessResult {
+        result: Vec<SubsystemLiveness>,
+        error_code: i32,
+        error_msg: String,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct IndexSizeEstimate {
+        pub sample_docs: u64,
+        pub sample_index_bytes: u64,
+        pub estimated_index_bytes: u64,
+        pub estimated_num_tokens: u64,
+        pub estimated_reader_memory_bytes: u64,
+        pub estimated_writer_memory_bytes: u64,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct IndexSizeEstimateResult {
+        result: IndexSizeEstimate,
+        error_code: i32,
+        error_msg: String,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct BuildProgress {
+        pub total_docs: u64,
+        pub docs_indexed: u64,
+        pub bytes_indexed: u64,
+        pub elapsed_millis: u64,
+        pub docs_per_sec: f64,
+        pub eta_millis: i64,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct BuildProgressResult {
+        result: BuildProgress,
+        error_code: i32,
+        error_msg: String,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct SegmentDeleteStat {
+        pub segment_id: String,
+        pub max_doc: u32,
+        pub num_deleted_docs: u32,
+        pub deleted_ratio: f32,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct SegmentDeleteStatsResult {
+        result: Vec<SegmentDeleteStat>,
+        error_code: i32,
+        error_msg: String,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct GroupTopDoc {
+        pub group_key: u64,
+        pub hit_count: u64,
+        pub top_doc: RowIdWithScore,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct GroupTopDocsResult {
+        result: Vec<GroupTopDoc>,
+        error_code: i32,
+        error_msg: String,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct MatchedOffset {
+        pub field: String,
+        pub term: String,
+        pub start: u64,
+        pub end: u64,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct MatchedOffsetsResult {
+        result: Vec<MatchedOffset>,
+        error_code: i32,
+        error_msg: String,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct MatchedTerm {
+        pub field: String,
+        pub term: String,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct MatchedTermsTopDoc {
+        pub top_doc: RowIdWithScore,
+        pub matched_terms: Vec<MatchedTerm>,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct MatchedTermsTopDocsResult {
+        result: Vec<MatchedTermsTopDoc>,
+        error_code: i32,
+        error_msg: String,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct TopDocsWithTotalHits {
+        pub top_docs: Vec<RowIdWithScore>,
+        pub total_hits: u64,
+        pub total_hits_exact: bool,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct TopDocsWithTotalHitsResult {
+        result: TopDocsWithTotalHits,
+        error_code: i32,
+        error_msg: String,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct StoredDoc {
+        pub row_id: u64,
+        pub values: Vec<String>,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct StoredDocsResult {
+        result: Vec<StoredDoc>,
+        error_code: i32,
+        error_msg: String,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct QueryIdsResult {
+        result: Vec<u64>,
+        error_code: i32,
+        error_msg: String,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct CardinalityResult {
+        estimate: u64,
+        sketch: Vec<u8>,
+        error_code: i32,
+        error_msg: String,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct PercentilesResult {
+        values: Vec<f64>,
+        digest: Vec<u8>,
+        error_code: i32,
+        error_msg: String,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct RowIdRange {
+        pub start: u64,
+        pub end: u64,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct AdaptiveBitmapResult {
+        strategy: String,
+        count: u64,
+        row_ids: Vec<u8>,
+        range_start: u64,
+        range_end: u64,
+        excluded_row_ids: Vec<u32>,
+        ranges: Vec<RowIdRange>,
+        error_code: i32,
+        error_msg: String,
+    }
+
     #[derive(Debug, Clone)]
     pub struct Statistics {
         pub docs_freq: Vec<DocWithFreq>,
@@ -79,6 +355,32 @@ pub mod ffi {
     extern "Rust" {
         pub fn ffi_varify_index_parameter(index_json_parameter: &CxxString) -> BoolResult;
 
+        /// Set how invalid utf-8 in ingested text and query strings is handled.
+        /// arguments:
+        /// - `policy`: `reject` (default), `lossy` replaces invalid sequences with U+FFFD,
+        ///   `skip_row` skips ingested rows with `result` false and `error_code` 0.
+        pub fn ffi_set_utf8_policy(policy: &CxxString) -> BoolResult;
+
+        /// Record tracing spans into a chrome-trace or folded-stack file for a window of time.
+        /// Requires `tracing-spans` feature, otherwise an error is returned.
+        /// arguments:
+        /// - `output_path`: trace output file path.
+        /// - `format`: `chrome` or `folded`.
+        /// - `duration_secs`: profiling window in seconds.
+        pub fn ffi_start_trace_profiling(
+            output_path: &CxxString,
+            format: &CxxString,
+            duration_secs: u64,
+        ) -> BoolResult;
+
+        /// Stop trace profiling window in advance and flush output file.
+        /// Returns false if no profiling window is running.
+        pub fn ffi_stop_trace_profiling() -> BoolResult;
+
+        /// Render internal metrics (cache sizes, query latencies, ingest rates, merge activity)
+        /// in prometheus text exposition format.
+        pub fn ffi_metrics_prometheus() -> String;
+
         /// Create tantivy index.
         /// arguments:
         /// - `index_path`: index directory.
@@ -90,6 +392,20 @@ pub mod ffi {
             index_json_parameter: &CxxString,
         ) -> BoolResult;
 
+        /// Create tantivy index with build mode.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `column_names`: which columns will be used to build index.
+        /// - `index_json_parameter`: config index with json.
+        /// - `deterministic_build`: build byte-comparable index for the same input,
+        ///   segment ids are derived when index writer is freed.
+        fn ffi_create_index_with_build_mode(
+            index_path: &CxxString,
+            column_names: &CxxVector<CxxString>,
+            index_json_parameter: &CxxString,
+            deterministic_build: bool,
+        ) -> BoolResult;
+
         /// Create tantivy index by default.
         /// argements:
         /// - `index_path`: index directory.
@@ -99,6 +415,137 @@ pub mod ffi {
             column_names: &CxxVector<CxxString>,
         ) -> BoolResult;
 
+        /// Estimate index size and memory requirements without a full build.
+        /// A sample is indexed in RAM with the given config and projected to `total_docs`.
+        /// arguments:
+        /// - `column_names`: which columns will be used to build index.
+        /// - `index_json_parameter`: config index with json.
+        /// - `sample_docs`: sample documents row by row, each row has `column_names.size()` values.
+        /// - `total_docs`: number of documents the index will be built with.
+        fn ffi_estimate_index_size(
+            column_names: &CxxVector<CxxString>,
+            index_json_parameter: &CxxString,
+            sample_docs: &CxxVector<CxxString>,
+            total_docs: u64,
+        ) -> IndexSizeEstimateResult;
+
+        /// Start tracking bulk build progress of a loaded index writer, previous progress is discarded.
+        /// Docs added after it are counted, ETA is estimated by average throughput.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `total_docs`: docs the build will add, 0 means unknown and ETA is -1.
+        /// - `report_interval_millis`: interval of reporting to build progress callback, 0 disables reports.
+        fn ffi_start_build_progress(
+            index_path: &CxxString,
+            total_docs: u64,
+            report_interval_millis: u64,
+        ) -> BoolResult;
+
+        /// Get bulk build progress, it should be started by `ffi_start_build_progress`.
+        /// arguments:
+        /// - `index_path`: index directory.
+        fn ffi_get_build_progress(index_path: &CxxString) -> BuildProgressResult;
+
+        /// Stop tracking bulk build progress, `false` if it's not started.
+        /// arguments:
+        /// - `index_path`: index directory.
+        fn ffi_stop_build_progress(index_path: &CxxString) -> BoolResult;
+
+        /// Get the row_id an interrupted bulk build should resume from.
+        /// Every commit records max row_id added before it as build checkpoint, rows are expected
+        /// to be added in row_id order, so rows before the returned row_id are durable.
+        /// It's read from committed index files, index writer doesn't need to be loaded.
+        /// arguments:
+        /// - `index_path`: index directory.
+        fn ffi_get_build_checkpoint(index_path: &CxxString) -> U64Result;
+
+        /// Create a multi-part build, it indexes several independent indexes concurrently on one
+        /// thread pool. Writers of all parts share the memory budget, each claims an equal slice.
+        /// Existing indexes in `index_paths` are recreated. Return the build id.
+        /// arguments:
+        /// - `index_paths`: index directory of each part.
+        /// - `column_names`: columns of all parts.
+        /// - `index_json_parameter`: tokenizer parameter of all parts.
+        /// - `memory_budget_bytes`: memory shared by writers, each part requires at least 15MB.
+        /// - `num_threads`: threads of the build pool, 0 means available parallelism.
+        fn ffi_create_multi_part_build(
+            index_paths: &CxxVector<CxxString>,
+            column_names: &CxxVector<CxxString>,
+            index_json_parameter: &CxxString,
+            memory_budget_bytes: u64,
+            num_threads: u64,
+        ) -> U64Result;
+
+        /// Index a batch holding rows of several parts, parts are indexed in parallel and rows
+        /// of a part keep their order. Column strings are laid out same as `ffi_index_column_batch`.
+        /// arguments:
+        /// - `build_id`: multi-part build id.
+        /// - `part_ids`: position in `index_paths` of the part each row belongs to, align with row_ids.
+        /// - `row_ids`: row_id of each row.
+        /// - `column_data`: concatenated utf-8 strings of all columns.
+        /// - `column_offsets`: offsets of strings in `column_data`, `column_names.len() * row_ids.len() + 1` elements.
+        fn ffi_multi_part_build_index_batch(
+            build_id: u64,
+            part_ids: &[u64],
+            row_ids: &[u64],
+            column_data: &[u8],
+            column_offsets: &[u64],
+        ) -> BoolResult;
+
+        /// Commit and free writers of all parts, the build id is invalid afterwards.
+        /// arguments:
+        /// - `build_id`: multi-part build id.
+        fn ffi_finish_multi_part_build(build_id: u64) -> BoolResult;
+
+        /// Start a sorted build of a loaded index writer, for hosts feeding rows out of row_id order
+        /// e.g. from parallel readers. Rows added by `ffi_sorted_build_add_batch` are buffered, sorted
+        /// and spilled to disk beyond the memory limit, then indexed in row_id order by
+        /// `ffi_finish_sorted_build`, so sorted index guarantees hold. Rows of equal row_id keep the
+        /// order they were added in.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `column_names`: columns of added rows.
+        /// - `memory_limit_bytes`: bytes of rows buffered in memory before they are spilled.
+        /// - `spill_directory`: directory of spilled rows, empty means system temporary directory.
+        fn ffi_start_sorted_build(
+            index_path: &CxxString,
+            column_names: &CxxVector<CxxString>,
+            memory_limit_bytes: u64,
+            spill_directory: &CxxString,
+        ) -> BoolResult;
+
+        /// Buffer rows of a sorted build, batches can be added concurrently in any row_id order.
+        /// Column strings are laid out same as `ffi_index_column_batch`.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `row_ids`: row_id of each row.
+        /// - `column_data`: concatenated utf-8 strings of all columns.
+        /// - `column_offsets`: offsets of strings in `column_data`, `column_names.len() * row_ids.len() + 1` elements.
+        fn ffi_sorted_build_add_batch(
+            index_path: &CxxString,
+            row_ids: &[u64],
+            column_data: &[u8],
+            column_offsets: &[u64],
+        ) -> BoolResult;
+
+        /// Index rows of a sorted build in row_id order and commit them, return rows indexed.
+        /// The build is over afterwards even if it failed, spilled rows are removed.
+        /// `error_code` is 1 under writer backpressure and 2 when commit doesn't fit in disk space.
+        /// arguments:
+        /// - `index_path`: index directory.
+        fn ffi_finish_sorted_build(index_path: &CxxString) -> U64Result;
+
+        /// Re-index an index in place with new tokenizer settings, e.g. after users change analyzers
+        /// of a table. Alive rows are indexed from stored columns in row_id order into a sibling
+        /// directory which is then swapped with the index, loaded writer and reader are reloaded.
+        /// Columns not stored can be re-indexed by `tantivy_search_index_reindex` of C API, which
+        /// fetches them from source. The old index is kept if re-indexing fails. Return rows indexed.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `index_json_parameter`: new tokenizer parameter of the index.
+        fn ffi_reindex_index(index_path: &CxxString, index_json_parameter: &CxxString)
+            -> U64Result;
+
         /// Index multi column docs with given rowId.
         /// arguments:
         /// - `index_path`: index directory.
@@ -112,6 +559,71 @@ pub mod ffi {
             column_docs: &CxxVector<CxxString>,
         ) -> BoolResult;
 
+        /// Index multi column docs borrowed from caller's buffer, avoid copying each doc
+        /// into intermediate strings.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `row_id`: row_id given by ClickHouse.
+        /// - `column_names`: align with column_lengths.
+        /// - `column_data`: utf-8 docs of all columns, stored consecutively.
+        /// - `column_lengths`: byte length of each column doc in `column_data`.
+        fn ffi_index_multi_column_borrowed_docs(
+            index_path: &CxxString,
+            row_id: u64,
+            column_names: &CxxVector<CxxString>,
+            column_data: &[u8],
+            column_lengths: &[u64],
+        ) -> BoolResult;
+
+        /// Index a column batch at once, documents are built in parallel.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `row_ids`: row_ids of the batch.
+        /// - `column_names`: columns of the batch.
+        /// - `column_data`: utf-8 strings of all columns, column by column.
+        /// - `column_offsets`: `column_names.size() * row_ids.size() + 1` offsets in `column_data`,
+        ///   string of column `c` row `r` is `[offsets[c * rows + r], offsets[c * rows + r + 1])`.
+        fn ffi_index_column_batch(
+            index_path: &CxxString,
+            row_ids: &[u64],
+            column_names: &CxxVector<CxxString>,
+            column_data: &[u8],
+            column_offsets: &[u64],
+        ) -> BoolResult;
+
+        /// Enable automatic row_id assignment, row_ids continue from max committed row_id.
+        /// Explicitly given row_ids aren't coordinated with assigned ones, don't mix them in an index.
+        /// Return the next row_id to be assigned.
+        /// arguments:
+        /// - `index_path`: index directory.
+        fn ffi_enable_auto_row_id(index_path: &CxxString) -> U64Result;
+
+        /// Index multi column docs with a row_id assigned by index writer, return the assigned row_id.
+        /// Row skipped by utf-8 policy doesn't consume a row_id, `error_code` is 0 and `error_msg` isn't empty.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `column_names`: align with column_docs.
+        /// - `column_docs`: align with column_names.
+        fn ffi_index_multi_column_docs_auto_row_id(
+            index_path: &CxxString,
+            column_names: &CxxVector<CxxString>,
+            column_docs: &CxxVector<CxxString>,
+        ) -> U64Result;
+
+        /// Index a column batch with consecutive row_ids assigned by index writer,
+        /// return the first assigned row_id, rows skipped by utf-8 policy still consume their row_ids.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `column_names`: columns of the batch.
+        /// - `column_data`: utf-8 strings of all columns, column by column.
+        /// - `column_offsets`: `column_names.size() * rows + 1` offsets in `column_data`.
+        fn ffi_index_column_batch_auto_row_id(
+            index_path: &CxxString,
+            column_names: &CxxVector<CxxString>,
+            column_data: &[u8],
+            column_offsets: &[u64],
+        ) -> U64Result;
+
         /// Index multi column docs with given rowId.
         /// arguments:
         /// - `index_path`: index directory.
@@ -138,67 +650,302 @@ pub mod ffi {
         ) -> BoolResult;
 
         /// Delete a group of rowIds.
+        /// Returns `error_code` 2 if the deletion commit doesn't fit in free disk space.
         /// arguments:
         /// - `index_path`: index directory.
         /// - `row_ids`: a group of rowIds need be deleted.
         fn ffi_delete_row_ids(index_path: &CxxString, row_ids: &CxxVector<u64>) -> BoolResult;
 
         /// Commit index writer
+        /// Returns `error_code` 2 without writing anything if the commit doesn't fit in free disk space.
         /// arguments:
         /// - `index_path`: index directory.
         fn ffi_index_writer_commit(index_path: &CxxString) -> BoolResult;
 
+        /// Schedule a commit on background commit pool, return a ticket for `ffi_wait_commit`.
+        /// Host can overlap the fsync heavy commit with other work.
+        /// arguments:
+        /// - `index_path`: index directory.
+        fn ffi_commit_async(index_path: &CxxString) -> U64Result;
+
+        /// Wait a commit scheduled by `ffi_commit_async`, `false` if it's still running after timeout
+        /// and the ticket can be waited again. Once commit ended, its result is returned and ticket is released.
+        /// arguments:
+        /// - `ticket`: ticket returned by `ffi_commit_async`.
+        /// - `timeout_millis`: max time to wait, 0 waits until commit ended.
+        fn ffi_wait_commit(ticket: u64, timeout_millis: u64) -> BoolResult;
+
+        /// Schedule a commit on background commit pool without waiting it, failure is only logged.
+        /// arguments:
+        /// - `index_path`: index directory.
+        fn ffi_commit_nowait(index_path: &CxxString) -> BoolResult;
+
         /// Free index writer
         /// arguments:
         /// - `index_path`: index directory.
         fn ffi_free_index_writer(index_path: &CxxString) -> BoolResult;
 
         /// Load index writer
+        /// Returns `error_code` 3 if the index was written by a newer incompatible version.
         /// arguments:
         /// - `index_path`: index directory.
         fn ffi_load_index_writer(index_path: &CxxString) -> BoolResult;
 
-        /// Do index reader reload
+        /// Load index writer, try to break a stale writer lock left by a crashed process.
         /// arguments:
         /// - `index_path`: index directory.
-        pub fn ffi_index_reader_reload(index_path: &CxxString) -> BoolResult;
+        /// - `break_stale_lock`: whether break writer lock when it's owner process has died.
+        fn ffi_load_index_writer_with_lock_recovery(
+            index_path: &CxxString,
+            break_stale_lock: bool,
+        ) -> BoolResult;
 
-        /// Load index reader
+        /// Enable background auto commit for an index writer.
         /// arguments:
         /// - `index_path`: index directory.
-        fn ffi_load_index_reader(index_path: &CxxString) -> BoolResult;
+        /// - `interval_secs`: commit every `interval_secs` seconds, 0 means disable it.
+        /// - `max_docs`: commit every `max_docs` operations since last commit, 0 means disable it.
+        fn ffi_enable_auto_commit(
+            index_path: &CxxString,
+            interval_secs: u64,
+            max_docs: u64,
+        ) -> BoolResult;
 
-        /// Free index reader
+        /// Disable background auto commit for an index writer.
         /// arguments:
         /// - `index_path`: index directory.
-        fn ffi_free_index_reader(index_path: &CxxString) -> BoolResult;
+        fn ffi_disable_auto_commit(index_path: &CxxString) -> BoolResult;
 
-        /// Get indexed docs numbers.
+        /// Update runtime settings of a loaded index without reopening it.
+        /// Supported settings: `slow_query_threshold_ms` (0 disables slow query log, requires reader),
+        /// `auto_commit_interval_secs` (0 disables interval commit, requires writer),
+        /// `commit_durability` of committed files (`fsync` by default, `fdatasync` or `none`
+        /// for rebuilds which can be run again after a crash, requires writer),
+        /// `direct_io` writing new segment files with O_DIRECT to keep bulk builds out of page
+        /// cache (`false` by default, buffered writes where it's unsupported, requires writer),
+        /// `similarity` of ranked search (`bm25`, `tf_only` or `constant`, requires reader)
+        /// `bm25_k1`, `bm25_b` of `bm25` similarity (requires reader)
+        /// and `persistent_query_cache_mb` of bitmap query results persisted next to index
+        /// (0 disables it, requires reader).
         /// arguments:
         /// - `index_path`: index directory.
-        fn ffi_get_indexed_doc_counts(index_path: &CxxString) -> u64;
+        /// - `settings_json`: settings to update, e.g. `{"slow_query_threshold_ms": 200}`.
+        fn ffi_update_index_settings(
+            index_path: &CxxString,
+            settings_json: &CxxString,
+        ) -> BoolResult;
 
-        /// Execute single term query with given rowId range.
+        /// Configure index writer backpressure, zero disables the corresponding check.
+        /// When writer is under backpressure, `ffi_index_multi_column_docs` and
+        /// `ffi_index_multi_type_column_docs` return `error_code` 1 instead of -1.
         /// arguments:
         /// - `index_path`: index directory.
-        /// - `column_name`: which column will execute search.
-        /// - `term`: term needs to be searched.
-        /// - `lrange`: rowId left begin.
-        /// - `rrange`: rowId right end.
-        fn ffi_query_term_with_range(
+        /// - `max_uncommitted_bytes`: max estimated bytes indexed since last commit.
+        /// - `max_segments`: max searchable segments, exceeding it means merges are far behind.
+        /// - `block_timeout_millis`: how long to wait for backpressure relieved before fail.
+        fn ffi_set_writer_backpressure(
             index_path: &CxxString,
-            column_name: &CxxString,
-            term: &CxxString,
-            lrange: u64,
-            rrange: u64,
+            max_uncommitted_bytes: u64,
+            max_segments: u64,
+            block_timeout_millis: u64,
         ) -> BoolResult;
 
-        /// Execute a group of terms query with given rowId range.
+        /// Verify row_ids of ingested documents, duplicated row_ids break row_id bitmap semantics.
+        /// Checker is seeded with row_ids already committed in the index.
         /// arguments:
         /// - `index_path`: index directory.
-        /// - `column_name`: which column will execute search.
-        /// - `terms`: a group of terms need to be searched.
-        /// - `lrange`: rowId left begin.
+        /// - `mode`: `disabled` (default), `increasing` requires strictly increasing row_ids,
+        ///   `unique` allows any order but rejects duplicates.
+        /// - `reject`: reject violating documents if true, otherwise only log and count them.
+        fn ffi_set_row_id_check(
+            index_path: &CxxString,
+            mode: &CxxString,
+            reject: bool,
+        ) -> BoolResult;
+
+        /// Do index reader reload
+        /// Commits made through this library reload the reader, call it for commits of other processes.
+        /// arguments:
+        /// - `index_path`: index directory.
+        pub fn ffi_index_reader_reload(index_path: &CxxString) -> BoolResult;
+
+        /// Load index reader
+        /// Returns `error_code` 3 if the index was written by a newer incompatible version.
+        /// arguments:
+        /// - `index_path`: index directory.
+        fn ffi_load_index_reader(index_path: &CxxString) -> BoolResult;
+
+        /// Free index reader
+        /// arguments:
+        /// - `index_path`: index directory.
+        fn ffi_free_index_reader(index_path: &CxxString) -> BoolResult;
+
+        /// Load readers of all indexes under `root_dir` after a restart, so first queries
+        /// don't pay for loading them. Indexes failing validation are skipped and reported.
+        /// Returns a json summary with `discovered`, `loaded`, `warmed`, `warmed_bytes` and `failed`.
+        /// arguments:
+        /// - `root_dir`: directory index directories are discovered under.
+        /// - `hot_within_secs`: warm files of indexes committed within it, 0 warms nothing.
+        /// - `num_threads`: threads loading indexes, 0 means available parallelism.
+        fn ffi_bootstrap_indexes(
+            root_dir: &CxxString,
+            hot_within_secs: u64,
+            num_threads: u64,
+        ) -> StringResult;
+
+        /// Assign an index to a namespace, e.g. all indexes of one database on a multi-tenant host.
+        /// An index belongs to one namespace at most, registering again moves it.
+        /// arguments:
+        /// - `index_path`: index directory, it doesn't need to be loaded yet.
+        /// - `namespace_name`: namespace name, can't be empty.
+        fn ffi_register_index_namespace(
+            index_path: &CxxString,
+            namespace_name: &CxxString,
+        ) -> BoolResult;
+
+        /// List namespaces with at least one registered index.
+        fn ffi_list_namespaces() -> Vec<String>;
+
+        /// List index directories registered in a namespace.
+        /// arguments:
+        /// - `namespace_name`: namespace name.
+        fn ffi_list_namespace_indexes(namespace_name: &CxxString) -> Vec<String>;
+
+        /// Free index writers and readers of all indexes in a namespace, return number of indexes freed.
+        /// arguments:
+        /// - `namespace_name`: namespace name.
+        fn ffi_free_namespace(namespace_name: &CxxString) -> U64Result;
+
+        /// Start a background janitor unloading index writers and readers not accessed for a while,
+        /// pending writes are committed before writer is unloaded. Previous janitor is replaced.
+        /// Readers holding pinned searchers or lightweight deleted row_ids are kept.
+        /// arguments:
+        /// - `idle_timeout_secs`: unload indexes not accessed for this many seconds, should be positive.
+        /// - `check_interval_secs`: how often caches are checked, should be positive.
+        fn ffi_enable_idle_index_unload(
+            idle_timeout_secs: u64,
+            check_interval_secs: u64,
+        ) -> BoolResult;
+
+        /// Stop the idle index janitor, `false` if it's not running.
+        fn ffi_disable_idle_index_unload() -> BoolResult;
+
+        /// Shut the library down on clean exit of host, so no internal thread outlives it and no
+        /// destructor runs against a loaded index. Background janitor is stopped, commits scheduled by
+        /// `ffi_commit_async` and `ffi_commit_nowait` are waited, then all index writers and readers
+        /// are freed and caches are cleared. Nothing is freed if commits are still running after
+        /// timeout. Writers failed to commit are kept loaded and the error is returned, shutdown can
+        /// be retried with `abort`. Return number of index writers and readers freed.
+        /// arguments:
+        /// - `policy`: `commit` commits pending writes of writers, `abort` discards them.
+        /// - `timeout_millis`: how long running commits are waited, 0 waits forever.
+        fn ffi_shutdown(policy: &CxxString, timeout_millis: u64) -> U64Result;
+
+        /// Get indexed docs numbers.
+        /// arguments:
+        /// - `index_path`: index directory.
+        fn ffi_get_indexed_doc_counts(index_path: &CxxString) -> u64;
+
+        /// Register lightweight deleted row ids, they will be excluded from all search results.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `row_ids`: row ids deleted by host.
+        fn ffi_register_deleted_row_ids(
+            index_path: &CxxString,
+            row_ids: &CxxVector<u64>,
+        ) -> BoolResult;
+
+        /// Clear all registered lightweight deleted row ids.
+        /// arguments:
+        /// - `index_path`: index directory.
+        fn ffi_clear_deleted_row_ids(index_path: &CxxString) -> BoolResult;
+
+        /// Restrict searches of `principal` to rows matching `sentence`, it's ANDed into every
+        /// search the principal issues on this index. A previous filter of it is replaced.
+        /// Filters are kept by loaded reader, they survive reloads but not `ffi_free_index_reader`.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `principal`: principal id bound by `ffi_set_search_principal`.
+        /// - `sentence`: stored query, parsed as `ffi_register_percolator_query` sentences.
+        fn ffi_register_security_filter_query(
+            index_path: &CxxString,
+            principal: &CxxString,
+            sentence: &CxxString,
+        ) -> BoolResult;
+
+        /// Restrict searches of `principal` to rows of a bitmap, as `ffi_register_security_filter_query`.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `principal`: principal id bound by `ffi_set_search_principal`.
+        /// - `u8_bitmap`: rowIds the principal may see given by u8 bitmap.
+        fn ffi_register_security_filter_bitmap(
+            index_path: &CxxString,
+            principal: &CxxString,
+            u8_bitmap: &CxxVector<u8>,
+        ) -> BoolResult;
+
+        /// Remove security filter of `principal`, result is false if it has none.
+        /// Searches of the principal see nothing until a filter is registered again.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `principal`: principal id.
+        fn ffi_unregister_security_filter(
+            index_path: &CxxString,
+            principal: &CxxString,
+        ) -> BoolResult;
+
+        /// Bind `principal` to searches of the calling thread until it's changed, its security
+        /// filter is ANDed into every search and stored fields read of the thread. A principal
+        /// without filter on the searched index sees nothing.
+        /// arguments:
+        /// - `principal`: principal id, empty unbinds the principal and searches are unfiltered.
+        fn ffi_set_search_principal(principal: &CxxString) -> BoolResult;
+
+        /// Record a sampled fraction of searches of all indexes into query audit log, each record
+        /// is (timestamp, index, principal, query, hit count, latency). Records are passed to the
+        /// audit callback installed by `tantivy_search_set_query_audit_callback` and appended to
+        /// `file_path` as json lines.
+        /// arguments:
+        /// - `file_path`: audit log file, empty keeps no file.
+        /// - `sample_rate`: fraction of searches recorded in [0, 1], 0 disables auditing.
+        fn ffi_configure_query_audit(file_path: &CxxString, sample_rate: f64) -> BoolResult;
+
+        /// Limit cost of queries of all indexes, a query over any limit isn't executed, it fails
+        /// with `QueryTooExpensive` error. Limits protect a shared server from pathological queries.
+        /// arguments:
+        /// - `max_clause_count`: terms of a term set or parsed query, 0 means unlimited.
+        /// - `max_regex_states`: automaton states of a LIKE/regex pattern, 0 means unlimited.
+        /// - `max_expanded_terms`: terms a LIKE/regex pattern, prefix ones included, expands to
+        ///   in a segment, 0 means unlimited.
+        /// - `max_bitmap_size`: row ids of a bitmap query result, 0 means unlimited.
+        fn ffi_set_query_cost_limits(
+            max_clause_count: u64,
+            max_regex_states: u64,
+            max_expanded_terms: u64,
+            max_bitmap_size: u64,
+        ) -> BoolResult;
+
+        /// Execute single term query with given rowId range.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `column_name`: which column will execute search.
+        /// - `term`: term needs to be searched.
+        /// - `lrange`: rowId left begin.
+        /// - `rrange`: rowId right end.
+        fn ffi_query_term_with_range(
+            index_path: &CxxString,
+            column_name: &CxxString,
+            term: &CxxString,
+            lrange: u64,
+            rrange: u64,
+        ) -> BoolResult;
+
+        /// Execute a group of terms query with given rowId range.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `column_name`: which column will execute search.
+        /// - `terms`: a group of terms need to be searched.
+        /// - `lrange`: rowId left begin.
         /// - `rrange`: rowId right end.
         fn ffi_query_terms_with_range(
             index_path: &CxxString,
@@ -260,6 +1007,32 @@ pub mod ffi {
             terms: &CxxVector<CxxString>,
         ) -> Vec<u8>;
 
+        /// Execute a group of terms query, matched rowIds are returned in a representation
+        /// picked by `mode`, `strategy` of result tells which one:
+        /// - `empty`: short-circuited, no row has any term.
+        /// - `count`: only `count` is set.
+        /// - `bitmap`: `row_ids` is rowIds u8 bitmap.
+        /// - `dense_range`: rowIds in [`range_start`, `range_end`) except `excluded_row_ids`.
+        /// - `ranges`: rowIds are runs in `ranges`, each of them is [`start`, `end`).
+        /// `count` is always set.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `column_name`: which column will execute search.
+        /// - `terms`: terms need to be searched.
+        /// - `mode`: `bitmap`, `dense_range`, `count` or `auto`. `auto` estimates selectivity by
+        ///   term doc frequencies before execution, more than half of rows matched is a dense range.
+        /// - `need_row_ids`: whether `auto` mode returns rowIds, otherwise rows are only counted.
+        /// - `accept_ranges`: whether rowIds can be returned as `ranges`, they're returned so
+        ///   when smaller, e.g. matches are contiguous in index sorted by row_id.
+        pub fn ffi_query_terms_adaptive(
+            index_path: &CxxString,
+            column_name: &CxxString,
+            terms: &CxxVector<CxxString>,
+            mode: &CxxString,
+            need_row_ids: bool,
+            accept_ranges: bool,
+        ) -> AdaptiveBitmapResult;
+
         /// Execute a sentence query and return rowIds u8 bitmap.
         /// arguments:
         /// - `index_path`: index directory.
@@ -282,6 +1055,54 @@ pub mod ffi {
             pattern: &CxxString,
         ) -> Vec<u8>;
 
+        /// Execute a date range query and return rowIds u8 bitmap.
+        /// Bounds are inclusive and truncated to precision of the date column.
+        /// A bound can also be a local date `2024-03-01` or date time `2024-03-01 08:30:00`
+        /// in host timezone, a local date upper bound covers the whole day.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `column_name`: date column will execute search.
+        /// - `lower`: lower bound, empty means unbounded.
+        /// - `upper`: upper bound, empty means unbounded.
+        /// - `utc_offset_seconds`: offset of host timezone, e.g. 28800 for UTC+8.
+        pub fn ffi_query_date_range_bitmap(
+            index_path: &CxxString,
+            column_name: &CxxString,
+            lower: &CxxString,
+            upper: &CxxString,
+            utc_offset_seconds: i32,
+        ) -> Vec<u8>;
+
+        /// Execute a geo bounding box query and return rowIds u8 bitmap.
+        /// The box crosses the antimeridian if `min_lon` is larger than `max_lon`.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `column_name`: geo point column will execute search.
+        /// - `min_lat`, `min_lon`: south west corner in degrees.
+        /// - `max_lat`, `max_lon`: north east corner in degrees.
+        pub fn ffi_query_geo_bounding_box_bitmap(
+            index_path: &CxxString,
+            column_name: &CxxString,
+            min_lat: f64,
+            min_lon: f64,
+            max_lat: f64,
+            max_lon: f64,
+        ) -> Vec<u8>;
+
+        /// Execute a geo distance query and return rowIds u8 bitmap.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `column_name`: geo point column will execute search.
+        /// - `lat`, `lon`: center in degrees.
+        /// - `radius_meters`: rows within this distance of center are matched.
+        pub fn ffi_query_geo_distance_bitmap(
+            index_path: &CxxString,
+            column_name: &CxxString,
+            lat: f64,
+            lon: f64,
+            radius_meters: f64,
+        ) -> Vec<u8>;
+
         /// Execute a regex query and return rowIds u8 bitmap.
         /// arguments:
         /// - `index_path`: index directory.
@@ -341,6 +1162,541 @@ pub mod ffi {
             column_names: &CxxVector<CxxString>,
         ) -> BM25Result;
 
+        /// Execute BM25 search in filter mode, BM25 score is not computed and all results score 1.0.
+        /// Faster when only matched rowIds are needed, e.g. high frequency terms.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `sentence`: from DingoDB TextSearch function.
+        /// - `topk`: only return top k matched results, in no particular order.
+        /// - `alived_ids`: alived rowIds given by u64 vector.
+        /// - `query_with_filter`: whether use alived_ids or not.
+        /// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+        /// - `start_id`: id range start.
+        /// - `end_id`: id range end.
+        /// - `colunm_names`: for multi column search.
+        pub fn ffi_bm25_filter_search(
+            index_path: &CxxString,
+            sentence: &CxxString,
+            topk: u32,
+            alived_ids: &CxxVector<u64>,
+            query_with_filter: bool,
+            query_with_id_range: bool,
+            start_id: u64,
+            end_id: u64,
+            column_names: &CxxVector<CxxString>,
+        ) -> BM25Result;
+
+        /// Execute BM25 search scored with given BM25 parameters instead of the index default ones.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `sentence`: from DingoDB TextSearch function.
+        /// - `topk`: only return top k related results.
+        /// - `alived_ids`: alived rowIds given by u64 vector.
+        /// - `query_with_filter`: whether use alived_ids or not.
+        /// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+        /// - `start_id`: id range start.
+        /// - `end_id`: id range end.
+        /// - `colunm_names`: for multi column search.
+        /// - `k1`: term frequency saturation, non-negative, tantivy uses 1.2.
+        /// - `b`: document length normalization in [0, 1], tantivy uses 0.75.
+        pub fn ffi_bm25_search_with_bm25_params(
+            index_path: &CxxString,
+            sentence: &CxxString,
+            topk: u32,
+            alived_ids: &CxxVector<u64>,
+            query_with_filter: bool,
+            query_with_id_range: bool,
+            start_id: u64,
+            end_id: u64,
+            column_names: &CxxVector<CxxString>,
+            k1: f32,
+            b: f32,
+        ) -> BM25Result;
+
+        /// Execute BM25 search with a pinned searcher, results are consistent with that generation.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `sentence`: from DingoDB TextSearch function.
+        /// - `topk`: only return top k related results.
+        /// - `alived_ids`: alived rowIds given by u64 vector.
+        /// - `query_with_filter`: whether use alived_ids or not.
+        /// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+        /// - `start_id`: id range start.
+        /// - `end_id`: id range end.
+        /// - `colunm_names`: for multi column search.
+        /// - `generation`: searcher generation returned by `ffi_pin_searcher`.
+        pub fn ffi_bm25_search_at_generation(
+            index_path: &CxxString,
+            sentence: &CxxString,
+            topk: u32,
+            alived_ids: &CxxVector<u64>,
+            query_with_filter: bool,
+            query_with_id_range: bool,
+            start_id: u64,
+            end_id: u64,
+            column_names: &CxxVector<CxxString>,
+            generation: u64,
+        ) -> BM25Result;
+
+        /// Execute BM25 search over `column_names`, scores of a column are multiplied by its boost.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `sentence`: from DingoDB TextSearch function.
+        /// - `topk`: only return top k related results.
+        /// - `alived_ids`: alived rowIds given by u64 vector.
+        /// - `query_with_filter`: whether use alived_ids or not.
+        /// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+        /// - `start_id`: id range start.
+        /// - `end_id`: id range end.
+        /// - `column_names`: text columns to search.
+        /// - `boosts`: positive boost of each column in `column_names`, e.g. 3.0 for title and 1.0 for body.
+        pub fn ffi_bm25_search_with_field_boosts(
+            index_path: &CxxString,
+            sentence: &CxxString,
+            topk: u32,
+            alived_ids: &CxxVector<u64>,
+            query_with_filter: bool,
+            query_with_id_range: bool,
+            start_id: u64,
+            end_id: u64,
+            column_names: &CxxVector<CxxString>,
+            boosts: &CxxVector<f32>,
+        ) -> BM25Result;
+
+        /// Execute BM25 search for one page, results are ranked by score desc and row_id asc.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `sentence`: from DingoDB TextSearch function.
+        /// - `topk`: page size.
+        /// - `offset`: number of ranked results to skip.
+        /// - `has_search_after`: whether use (`after_score`, `after_row_id`) cursor or not.
+        /// - `after_score`: score of the last result in previous page.
+        /// - `after_row_id`: row_id of the last result in previous page.
+        /// - `alived_ids`: alived rowIds given by u64 vector.
+        /// - `query_with_filter`: whether use alived_ids or not.
+        /// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+        /// - `start_id`: id range start.
+        /// - `end_id`: id range end.
+        /// - `colunm_names`: for multi column search.
+        pub fn ffi_bm25_search_page(
+            index_path: &CxxString,
+            sentence: &CxxString,
+            topk: u32,
+            offset: u32,
+            has_search_after: bool,
+            after_score: f32,
+            after_row_id: u64,
+            alived_ids: &CxxVector<u64>,
+            query_with_filter: bool,
+            query_with_id_range: bool,
+            start_id: u64,
+            end_id: u64,
+            column_names: &CxxVector<CxxString>,
+        ) -> BM25Result;
+
+        /// Execute ranked search with a query of the JSON query DSL.
+        /// e.g. `{"type": "dis_max", "tie_breaker": 0.2, "queries": [
+        ///     {"type": "match", "field": "title", "query": "rust"},
+        ///     {"type": "match", "field": "body", "query": "rust"}]}`
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `query_json`: query DSL json.
+        /// - `topk`: only return top k related results.
+        /// - `alived_ids`: alived rowIds given by u64 vector.
+        /// - `query_with_filter`: whether use alived_ids or not.
+        /// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+        /// - `start_id`: id range start.
+        /// - `end_id`: id range end.
+        pub fn ffi_bm25_search_with_query_dsl(
+            index_path: &CxxString,
+            query_json: &CxxString,
+            topk: u32,
+            alived_ids: &CxxVector<u64>,
+            query_with_filter: bool,
+            query_with_id_range: bool,
+            start_id: u64,
+            end_id: u64,
+        ) -> BM25Result;
+
+        /// Search rows whose vectors are nearest to `vector`, scored by metric of the vector column.
+        /// Only rows matching `filter_sentence` are searched, so text filter and vector similarity
+        /// are combined in one search.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `column_name`: vector column will execute search.
+        /// - `vector`: query vector, it should have dims of the column.
+        /// - `topk`: only return top k nearest results.
+        /// - `filter_sentence`: sentence rows should match, empty means all rows are searched.
+        /// - `alived_ids`: alived rowIds given by u64 vector.
+        /// - `query_with_filter`: whether use alived_ids or not.
+        /// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+        /// - `start_id`: id range start.
+        /// - `end_id`: id range end.
+        pub fn ffi_knn_search(
+            index_path: &CxxString,
+            column_name: &CxxString,
+            vector: &CxxVector<f32>,
+            topk: u32,
+            filter_sentence: &CxxString,
+            alived_ids: &CxxVector<u64>,
+            query_with_filter: bool,
+            query_with_id_range: bool,
+            start_id: u64,
+            end_id: u64,
+        ) -> BM25Result;
+
+        /// Search rows by dot product of query term weights and term weights of a sparse vector
+        /// column, e.g. weights of a SPLADE model. Only rows matching `filter_sentence` are searched.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `column_name`: sparse vector column will execute search.
+        /// - `query_json`: query term weights as a JSON object, e.g. `{"apple": 1.2, "fruit": 0.4}`.
+        /// - `topk`: only return top k results.
+        /// - `filter_sentence`: sentence rows should match, empty means all rows are searched.
+        /// - `alived_ids`: alived rowIds given by u64 vector.
+        /// - `query_with_filter`: whether use alived_ids or not.
+        /// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+        /// - `start_id`: id range start.
+        /// - `end_id`: id range end.
+        pub fn ffi_sparse_vector_search(
+            index_path: &CxxString,
+            column_name: &CxxString,
+            query_json: &CxxString,
+            topk: u32,
+            filter_sentence: &CxxString,
+            alived_ids: &CxxVector<u64>,
+            query_with_filter: bool,
+            query_with_id_range: bool,
+            start_id: u64,
+            end_id: u64,
+        ) -> BM25Result;
+
+        /// Execute BM25 search and order results by a numeric or date fast field.
+        /// BM25 score is the tiebreak, docs missing `order_field` are ranked last.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `sentence`: from DingoDB TextSearch function.
+        /// - `topk`: only return top k results.
+        /// - `order_field`: numeric or date fast field used to order results.
+        /// - `ascending`: order by ascending field value or not.
+        /// - `alived_ids`: alived rowIds given by u64 vector.
+        /// - `query_with_filter`: whether use alived_ids or not.
+        /// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+        /// - `start_id`: id range start.
+        /// - `end_id`: id range end.
+        /// - `colunm_names`: for multi column search.
+        pub fn ffi_bm25_search_order_by_field(
+            index_path: &CxxString,
+            sentence: &CxxString,
+            topk: u32,
+            order_field: &CxxString,
+            ascending: bool,
+            alived_ids: &CxxVector<u64>,
+            query_with_filter: bool,
+            query_with_id_range: bool,
+            start_id: u64,
+            end_id: u64,
+            column_names: &CxxVector<CxxString>,
+        ) -> BM25Result;
+
+        /// Execute BM25 search keeping at most `max_per_key` results per value of a fast field,
+        /// e.g. at most 2 results per domain. Docs missing the field share one key.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `sentence`: from DingoDB TextSearch function.
+        /// - `topk`: only return top k results.
+        /// - `diversify_field`: numeric or date fast field used to diversify results.
+        /// - `max_per_key`: max number of results for each value of `diversify_field`.
+        /// - `alived_ids`: alived rowIds given by u64 vector.
+        /// - `query_with_filter`: whether use alived_ids or not.
+        /// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+        /// - `start_id`: id range start.
+        /// - `end_id`: id range end.
+        /// - `colunm_names`: for multi column search.
+        pub fn ffi_bm25_search_diversified(
+            index_path: &CxxString,
+            sentence: &CxxString,
+            topk: u32,
+            diversify_field: &CxxString,
+            max_per_key: u32,
+            alived_ids: &CxxVector<u64>,
+            query_with_filter: bool,
+            query_with_id_range: bool,
+            start_id: u64,
+            end_id: u64,
+            column_names: &CxxVector<CxxString>,
+        ) -> BM25Result;
+
+        /// Execute BM25 search and collapse results by a u64 fast field, e.g. best hit per user_id.
+        /// Each group returns its best hit and hit count, groups are ranked by score of best hit.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `sentence`: from DingoDB TextSearch function.
+        /// - `topk`: only return top k groups.
+        /// - `group_field`: u64 fast field used to group results.
+        /// - `alived_ids`: alived rowIds given by u64 vector.
+        /// - `query_with_filter`: whether use alived_ids or not.
+        /// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+        /// - `start_id`: id range start.
+        /// - `end_id`: id range end.
+        /// - `colunm_names`: for multi column search.
+        pub fn ffi_bm25_search_grouped(
+            index_path: &CxxString,
+            sentence: &CxxString,
+            topk: u32,
+            group_field: &CxxString,
+            alived_ids: &CxxVector<u64>,
+            query_with_filter: bool,
+            query_with_id_range: bool,
+            start_id: u64,
+            end_id: u64,
+            column_names: &CxxVector<CxxString>,
+        ) -> GroupTopDocsResult;
+
+        /// Execute BM25 search and report query terms matched in each result, e.g. to render
+        /// "matched on: title(foo), body(bar)" without analyzing docs again.
+        /// Terms matched in a language sub-field are reported in its column.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `sentence`: from DingoDB TextSearch function.
+        /// - `topk`: only return top k related results.
+        /// - `alived_ids`: alived rowIds given by u64 vector.
+        /// - `query_with_filter`: whether use alived_ids or not.
+        /// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+        /// - `start_id`: id range start.
+        /// - `end_id`: id range end.
+        /// - `colunm_names`: for multi column search.
+        pub fn ffi_bm25_search_with_matched_terms(
+            index_path: &CxxString,
+            sentence: &CxxString,
+            topk: u32,
+            alived_ids: &CxxVector<u64>,
+            query_with_filter: bool,
+            query_with_id_range: bool,
+            start_id: u64,
+            end_id: u64,
+            column_names: &CxxVector<CxxString>,
+        ) -> MatchedTermsTopDocsResult;
+
+        /// Execute BM25 search and count total hits in the same pass, e.g. to show
+        /// "1-10 of ~12,400" without running another count query.
+        /// Total hits are distinct row_ids matching the query and filters. Unless
+        /// `exact_total_hits`, docs which can't rank in topk are skipped and total hits may be
+        /// a lower bound, `total_hits_exact` tells whether they are exact.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `sentence`: from DingoDB TextSearch function.
+        /// - `topk`: only return top k related results.
+        /// - `alived_ids`: alived rowIds given by u64 vector.
+        /// - `query_with_filter`: whether use alived_ids or not.
+        /// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+        /// - `start_id`: id range start.
+        /// - `end_id`: id range end.
+        /// - `colunm_names`: for multi column search.
+        /// - `exact_total_hits`: score every matched doc to count total hits exactly.
+        pub fn ffi_bm25_search_with_total_hits(
+            index_path: &CxxString,
+            sentence: &CxxString,
+            topk: u32,
+            alived_ids: &CxxVector<u64>,
+            query_with_filter: bool,
+            query_with_id_range: bool,
+            start_id: u64,
+            end_id: u64,
+            column_names: &CxxVector<CxxString>,
+            exact_total_hits: bool,
+        ) -> TopDocsWithTotalHitsResult;
+
+        /// Read stored fields of given row_ids, only requested fields are loaded.
+        /// Values of each doc keep the order of `field_names`, missing values are empty strings.
+        /// Row_ids not found or deleted are skipped.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `row_ids`: row_ids to read.
+        /// - `field_names`: stored fields to read, fields not stored are rejected.
+        pub fn ffi_get_stored_fields(
+            index_path: &CxxString,
+            row_ids: &CxxVector<u64>,
+            field_names: &CxxVector<CxxString>,
+        ) -> StoredDocsResult;
+
+        /// Byte offsets of query terms matched in stored text of a doc, e.g. to highlight the
+        /// original text kept by host. Offsets are ordered by column and position.
+        /// Columns should be stored, a row_id not found or deleted has no offsets.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `row_id`: doc to read.
+        /// - `sentence`: query sentence, parsed like sentences of BM25 search.
+        /// - `colunm_names`: columns to highlight, all text columns if it's empty.
+        pub fn ffi_get_matched_offsets(
+            index_path: &CxxString,
+            row_id: u64,
+            sentence: &CxxString,
+            column_names: &CxxVector<CxxString>,
+        ) -> MatchedOffsetsResult;
+
+        /// Store a query for reverse search, it's persisted in the index directory.
+        /// A query with the same id is replaced.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `query_id`: id returned by `ffi_percolate` when the query matches.
+        /// - `sentence`: query sentence, parsed like sentences of BM25 search.
+        pub fn ffi_register_percolator_query(
+            index_path: &CxxString,
+            query_id: u64,
+            sentence: &CxxString,
+        ) -> BoolResult;
+
+        /// Remove a stored query, `false` if it isn't stored.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `query_id`: id of the query.
+        pub fn ffi_unregister_percolator_query(index_path: &CxxString, query_id: u64)
+            -> BoolResult;
+
+        /// Return ids of stored queries matching a doc in ascending order, the doc isn't indexed.
+        /// Text and numeric columns are matched, other columns are ignored.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `column_names`: columns of the doc.
+        /// - `column_docs`: values of `column_names`.
+        pub fn ffi_percolate(
+            index_path: &CxxString,
+            column_names: &CxxVector<CxxString>,
+            column_docs: &CxxVector<CxxString>,
+        ) -> QueryIdsResult;
+
+        /// Estimate the number of distinct values of a fast field among docs matching sentence.
+        /// The serialized HyperLogLog sketch is returned to merge estimates of index parts.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `sentence`: from DingoDB TextSearch function.
+        /// - `field_name`: numeric or date fast field whose distinct values are counted.
+        /// - `alived_ids`: alived rowIds given by u64 vector.
+        /// - `query_with_filter`: whether use alived_ids or not.
+        /// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+        /// - `start_id`: id range start.
+        /// - `end_id`: id range end.
+        /// - `colunm_names`: for multi column search.
+        pub fn ffi_estimate_cardinality(
+            index_path: &CxxString,
+            sentence: &CxxString,
+            field_name: &CxxString,
+            alived_ids: &CxxVector<u64>,
+            query_with_filter: bool,
+            query_with_id_range: bool,
+            start_id: u64,
+            end_id: u64,
+            column_names: &CxxVector<CxxString>,
+        ) -> CardinalityResult;
+
+        /// Merge two sketches returned by `ffi_estimate_cardinality`, values in both are counted once.
+        /// arguments:
+        /// - `left`: serialized sketch.
+        /// - `right`: serialized sketch of the same precision.
+        pub fn ffi_merge_cardinality_sketches(
+            left: &CxxVector<u8>,
+            right: &CxxVector<u8>,
+        ) -> CardinalityResult;
+
+        /// Estimate percentiles of a numeric fast field among docs matching sentence.
+        /// Values are NaN if no doc has the field, the serialized t-digest is returned
+        /// to merge percentiles of index parts.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `sentence`: from DingoDB TextSearch function.
+        /// - `field_name`: u64, i64 or f64 fast field.
+        /// - `percents`: percentiles to estimate in [0, 100], e.g. 50, 95 and 99.
+        /// - `alived_ids`: alived rowIds given by u64 vector.
+        /// - `query_with_filter`: whether use alived_ids or not.
+        /// - `query_with_id_range`: whether use id range or not, [start_id, end_id).
+        /// - `start_id`: id range start.
+        /// - `end_id`: id range end.
+        /// - `colunm_names`: for multi column search.
+        pub fn ffi_estimate_percentiles(
+            index_path: &CxxString,
+            sentence: &CxxString,
+            field_name: &CxxString,
+            percents: &CxxVector<f64>,
+            alived_ids: &CxxVector<u64>,
+            query_with_filter: bool,
+            query_with_id_range: bool,
+            start_id: u64,
+            end_id: u64,
+            column_names: &CxxVector<CxxString>,
+        ) -> PercentilesResult;
+
+        /// Merge two digests returned by `ffi_estimate_percentiles` and estimate percentiles of both.
+        /// arguments:
+        /// - `left`: serialized digest.
+        /// - `right`: serialized digest of the same compression.
+        /// - `percents`: percentiles to estimate in [0, 100].
+        pub fn ffi_merge_percentiles_digests(
+            left: &CxxVector<u8>,
+            right: &CxxVector<u8>,
+            percents: &CxxVector<f64>,
+        ) -> PercentilesResult;
+
+        /// Pin current searcher of index reader, return its generation.
+        /// Pinned searcher is kept until `ffi_unpin_searcher`, even if reader reloaded.
+        /// arguments:
+        /// - `index_path`: index directory.
+        pub fn ffi_pin_searcher(index_path: &CxxString) -> U64Result;
+
+        /// Unpin a searcher generation.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `generation`: searcher generation returned by `ffi_pin_searcher`.
+        pub fn ffi_unpin_searcher(index_path: &CxxString, generation: u64) -> BoolResult;
+
+        /// Acquire a handle pinning current searcher of index reader, return an opaque handle id.
+        /// Steps of a multi-step operation (count, search, fetch) using the handle see one
+        /// consistent searcher, even if reader reloaded meanwhile. Release it by `ffi_release_searcher_handle`.
+        /// arguments:
+        /// - `index_path`: index directory.
+        pub fn ffi_acquire_searcher_handle(index_path: &CxxString) -> U64Result;
+
+        /// Release a searcher handle, `false` if it doesn't exist or has been released.
+        /// arguments:
+        /// - `handle`: handle returned by `ffi_acquire_searcher_handle`.
+        pub fn ffi_release_searcher_handle(handle: u64) -> BoolResult;
+
+        /// Count docs visible to the searcher of a handle.
+        /// arguments:
+        /// - `handle`: handle returned by `ffi_acquire_searcher_handle`.
+        pub fn ffi_searcher_handle_num_docs(handle: u64) -> U64Result;
+
+        /// Execute BM25 search for one page of results with the searcher of a handle.
+        /// Arguments are same as `ffi_bm25_search_page` except `handle`.
+        /// arguments:
+        /// - `handle`: handle returned by `ffi_acquire_searcher_handle`.
+        pub fn ffi_bm25_search_with_handle(
+            handle: u64,
+            sentence: &CxxString,
+            topk: u32,
+            offset: u32,
+            has_search_after: bool,
+            after_score: f32,
+            after_row_id: u64,
+            alived_ids: &CxxVector<u64>,
+            query_with_filter: bool,
+            query_with_id_range: bool,
+            start_id: u64,
+            end_id: u64,
+            column_names: &CxxVector<CxxString>,
+        ) -> BM25Result;
+
+        /// Read stored fields of given row_ids with the searcher of a handle, same as `ffi_get_stored_fields`.
+        /// arguments:
+        /// - `handle`: handle returned by `ffi_acquire_searcher_handle`.
+        /// - `row_ids`: row_ids to read.
+        /// - `field_names`: stored fields to read, fields not stored are rejected.
+        pub fn ffi_get_stored_fields_with_handle(
+            handle: u64,
+            row_ids: &CxxVector<u64>,
+            field_names: &CxxVector<CxxString>,
+        ) -> StoredDocsResult;
+
         /// Get doc freq for current part.
         /// arguments:
         /// - `index_path`: index directory.
@@ -366,6 +1722,137 @@ pub mod ffi {
         /// arguments:
         /// - `index_path`: index directory.
         pub fn ffi_get_index_json_parameter(index_path: &CxxString) -> StringResult;
+
+        /// Dry-run analyzer of a field, return tokens with position and byte offsets.
+        /// It's used to debug why a query doesn't match, nothing is indexed.
+        /// arguments:
+        /// - `index_path_or_config`: index directory, or index json parameter.
+        /// - `field`: column name, columns not configured use default analyzer.
+        /// - `text`: text to be analyzed.
+        pub fn ffi_analyze(
+            index_path_or_config: &CxxString,
+            field: &CxxString,
+            text: &CxxString,
+        ) -> AnalyzedTokensResult;
+
+        /// Normalize a raw query value the same way a field is indexed, e.g. lowercase it
+        /// for a `keyword_lowercase` field, so exact match filters hit the indexed term.
+        /// Empty string is returned if analyzer drops the value, error if it's split into terms.
+        /// arguments:
+        /// - `index_path_or_config`: index directory, or index json parameter.
+        /// - `field`: column name, columns not configured use default analyzer.
+        /// - `value`: raw value of an exact match filter.
+        pub fn ffi_normalize_term(
+            index_path_or_config: &CxxString,
+            field: &CxxString,
+            value: &CxxString,
+        ) -> StringResult;
+
+        /// Get health status of a cached index, include writer and reader.
+        /// `writer_busy` means writer is held by a commit or merge, health check doesn't wait for it.
+        /// `canonical_path` is the index directory with relative paths and symlinks resolved,
+        /// every spelling of it refers to the same cached writer and reader.
+        /// arguments:
+        /// - `index_path`: index directory.
+        pub fn ffi_get_index_health(index_path: &CxxString) -> IndexHealthResult;
+
+        /// Get liveness of background subsystems: `idle_janitor` of process, `auto_commit` and
+        /// `merges` of index writer. `running` is false if a subsystem is disabled or idle,
+        /// `alive` is false if its thread has exited, `last_heartbeat_millis` is unix millis
+        /// it last made progress.
+        /// arguments:
+        /// - `index_path`: index directory, empty only reports process subsystems.
+        pub fn ffi_get_liveness(index_path: &CxxString) -> LivenessResult;
+
+        /// Liveness probe, false if a running subsystem is dead or hasn't made progress recently.
+        /// arguments:
+        /// - `index_path`: index directory, empty only probes process subsystems.
+        /// - `max_heartbeat_age_millis`: heartbeats older than it are stale.
+        pub fn ffi_probe_liveness(
+            index_path: &CxxString,
+            max_heartbeat_age_millis: u64,
+        ) -> BoolResult;
+
+        /// Readiness probe, true if index is loaded and its writer, if loaded, accepts documents.
+        /// arguments:
+        /// - `index_path`: index directory.
+        pub fn ffi_probe_readiness(index_path: &CxxString) -> BoolResult;
+
+        /// List every index held in writer and reader caches as json array, each item has
+        /// `index_path`, `canonical_path`, `namespace`, `writer_loaded`, `reader_loaded`, `num_docs`,
+        /// `writer_uncommitted_bytes`, `reader_memory_bytes`, `open_files` and `last_access_millis`.
+        pub fn ffi_list_indexes() -> StringResult;
+
+        /// Limit file descriptors held open by the process, loading an index writer or reader
+        /// past the budget fails instead of hitting the process file descriptor limit.
+        /// Loaded indexes are kept when the budget is lowered.
+        /// arguments:
+        /// - `max_open_files`: files budget, 0 means unlimited.
+        pub fn ffi_set_max_open_files(max_open_files: u64) -> BoolResult;
+
+        /// Get file descriptors held open by the process.
+        pub fn ffi_get_open_files() -> U64Result;
+
+        /// Limit merge I/O of all index writers so merges don't starve host disk bandwidth.
+        /// Budget is taken by input segments when a merge starts, merges over budget are deferred
+        /// to next segment update, explicit merges wait for the budget.
+        /// arguments:
+        /// - `max_mb_per_sec`: merge I/O budget in MB/s, 0 means unlimited.
+        pub fn ffi_set_merge_throttle(max_mb_per_sec: u64) -> BoolResult;
+
+        /// Keep free disk space for host, commits and merges of all index writers whose estimated
+        /// footprint doesn't fit in free disk space minus the reserve are refused before writing.
+        /// arguments:
+        /// - `min_free_mb`: free disk space to keep in MB, 0 only keeps room for the footprint.
+        pub fn ffi_set_disk_space_reserve(min_free_mb: u64) -> BoolResult;
+
+        /// Set threads merging per-segment row_id bitmaps of bitmap queries in parallel,
+        /// merging dominates bitmap queries over indexes of many segments.
+        /// arguments:
+        /// - `num_threads`: merge threads, 0 merges bitmaps on calling thread.
+        pub fn ffi_set_bitmap_union_threads(num_threads: u64) -> BoolResult;
+
+        /// Pin internal threads to a CPU set, so cgroup management of host can constrain CPU usage
+        /// of the library. Threads are named `tantivy-search-*`, `tantivy-ingest-*` and
+        /// `tantivy-merge-*` by what they do, writer and merge threads spawned by tantivy are
+        /// renamed on linux. Pinning is supported on linux only.
+        /// arguments:
+        /// - `cpus`: CPU numbers threads may run on, empty unpins threads.
+        pub fn ffi_set_thread_cpu_set(cpus: &CxxVector<u32>) -> BoolResult;
+
+        /// Get memory held by the allocator of this library in bytes. Allocations are served by
+        /// jemalloc or mimalloc if the library is built with feature `jemalloc` or `mimalloc`.
+        /// - `allocator`: `jemalloc`, `mimalloc` or `system`.
+        /// - `allocated_bytes`: allocated by the library, 0 for mimalloc.
+        /// - `active_bytes`: pages holding allocations, committed pages for mimalloc.
+        /// - `resident_bytes`: resident pages of allocator, resident set of process for mimalloc.
+        /// - `retained_bytes`: kept for reuse instead of returned to the OS, 0 for mimalloc.
+        /// The system allocator doesn't report statistics, all of them are 0.
+        pub fn ffi_get_allocator_stats() -> AllocatorStatsResult;
+
+        /// Return memory retained by the allocator of this library but not in use to the OS,
+        /// e.g. after large merges. The system allocator is trimmed on linux with glibc.
+        pub fn ffi_purge_allocator() -> BoolResult;
+
+        /// Get deleted docs statistics of each searchable segment.
+        /// arguments:
+        /// - `index_path`: index directory.
+        pub fn ffi_get_segment_delete_stats(index_path: &CxxString) -> SegmentDeleteStatsResult;
+
+        /// Merge segments whose deleted docs ratio exceeds `min_deleted_ratio` to reclaim space.
+        /// Returns `error_code` 2 if the merged segment doesn't fit in free disk space.
+        /// arguments:
+        /// - `index_path`: index directory.
+        /// - `min_deleted_ratio`: segments with deleted ratio >= it will be merged, range (0, 1].
+        pub fn ffi_vacuum_index(index_path: &CxxString, min_deleted_ratio: f32) -> BoolResult;
+
+        /// Rewrite segments of an index written by an older version in current format and record
+        /// current versions, returns false if it's up to date. A writer is loaded meanwhile if
+        /// none is. Returns `error_code` 2 if rewritten segments don't fit in free disk space,
+        /// 3 if the index was written by a newer incompatible version.
+        /// arguments:
+        /// - `index_path`: index directory.
+        pub fn ffi_upgrade_index(index_path: &CxxString) -> BoolResult;
     }
 }
 
@@ -4,7 +4,7 @@ use crate::ERROR;
 use libc::*;
 use std::ffi::CStr;
 
-use super::logger_config::LoggerConfig;
+use super::logger_config::{FallbackLoggerConfig, LoggerConfig};
 
 #[no_mangle]
 pub extern "C" fn tantivy_search_log4rs_initialize(
@@ -14,13 +14,15 @@ pub extern "C" fn tantivy_search_log4rs_initialize(
     console_dispaly: bool,
     only_record_tantivy_search: bool,
 ) -> bool {
+    // Re-initialize logger config only, keep the installed log callback.
+    let callback: LogCallback = LOG_CALLBACK.get_or_init(|| empty_log_callback);
     tantivy_search_log4rs_initialize_with_callback(
         log_directory,
         log_level,
         log_in_file,
         console_dispaly,
         only_record_tantivy_search,
-        empty_log_callback,
+        callback,
     )
 }
 /// Initializes the logger configuration for the tantivy search library.
@@ -33,6 +35,7 @@ pub extern "C" fn tantivy_search_log4rs_initialize(
 /// - `console_dispaly`: Enables logging to the console if set to true.
 /// - `only_tantivy_search`: Only record `target=tantivy_search` log content.
 /// - `callback`: A callback function, typically provided by ClickHouse.
+///   It replaces the previous callback, so this function can be called again to reload config.
 ///
 /// Returns:
 /// - `true` if the logger is successfully initialized, `false` otherwise.
@@ -98,6 +101,96 @@ pub extern "C" fn tantivy_search_log4rs_initialize_with_callback(
     true
 }
 
+/// Installs or replaces the log callback without touching logger config.
+///
+/// Returns:
+/// - `true` if the callback is installed, `false` otherwise.
+#[no_mangle]
+pub extern "C" fn tantivy_search_update_log_callback(callback: LogCallback) -> bool {
+    match TantivySearchLogger::update_log_callback(&LOG_CALLBACK, callback) {
+        Ok(_) => true,
+        Err(e) => {
+            ERROR!("{:?}", e);
+            false
+        }
+    }
+}
+
+/// Removes the log callback, logs are still recorded by log4rs.
+/// If a fallback file logger is configured, it takes effect after removal.
+///
+/// Returns:
+/// - `true` if the callback is removed, `false` otherwise.
+#[no_mangle]
+pub extern "C" fn tantivy_search_remove_log_callback() -> bool {
+    match TantivySearchLogger::remove_log_callback(&LOG_CALLBACK) {
+        Ok(_) => {}
+        Err(e) => {
+            ERROR!("{:?}", e);
+            return false;
+        }
+    };
+    match TantivySearchLogger::apply_fallback_logger() {
+        Ok(_) => true,
+        Err(e) => {
+            ERROR!("{:?}", e);
+            false
+        }
+    }
+}
+
+/// Initializes the built-in file logger, which is used when no log callback is registered.
+///
+/// Arguments:
+/// - `log_file_path`: The log file path, rotated files are named `{log_file_path}.{index}`.
+/// - `log_level`: The logging level to use. Supported levels: info, debug, trace, error, warning.
+/// - `max_file_size`: Rotate log file after it exceeds this size in bytes.
+/// - `rotation_count`: The number of rotated log files to keep.
+///
+/// Returns:
+/// - `true` if the config is valid and saved, `false` otherwise.
+#[no_mangle]
+pub extern "C" fn tantivy_search_fallback_logger_initialize(
+    log_file_path: *const c_char,
+    log_level: *const c_char,
+    max_file_size: u64,
+    rotation_count: u32,
+) -> bool {
+    if log_file_path.is_null() || log_level.is_null() {
+        ERROR!("`log_file_path` or `log_level` can't be nullptr");
+        return false;
+    }
+    let log_file_path: String = match unsafe { CStr::from_ptr(log_file_path) }.to_str() {
+        Ok(path) => path.to_owned(),
+        Err(_) => {
+            ERROR!("`log_file_path` (string) is invalid");
+            return false;
+        }
+    };
+    let log_level: String = match unsafe { CStr::from_ptr(log_level) }.to_str() {
+        Ok(level) => level.to_owned(),
+        Err(_) => {
+            ERROR!("`log_level` (string) is invalid");
+            return false;
+        }
+    };
+    let config =
+        match FallbackLoggerConfig::new(log_file_path, log_level, max_file_size, rotation_count) {
+            Ok(config) => config,
+            Err(e) => {
+                ERROR!("{:?}", e);
+                return false;
+            }
+        };
+    match TantivySearchLogger::install_fallback_logger(config) {
+        Ok(_) => true,
+        Err(e) => {
+            ERROR!("{:?}", e);
+            false
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::INFO;
@@ -147,4 +240,54 @@ mod tests {
             true
         );
     }
+
+    #[test]
+    fn test_tantivy_search_update_and_remove_log_callback() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+        assert!(tantivy_search_update_log_callback(empty_log_callback));
+        assert!(LOG_CALLBACK.get().is_some());
+        assert!(tantivy_search_remove_log_callback());
+        assert!(LOG_CALLBACK.get().is_none());
+        // Logging without callback is still fine.
+        INFO!("Log without callback");
+        assert!(tantivy_search_update_log_callback(empty_log_callback));
+    }
+
+    #[test]
+    fn test_tantivy_search_fallback_logger_initialize() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+        let log_directory = tempfile::TempDir::new().unwrap();
+        let log_file_path = format!("{}/fallback.log", log_directory.path().to_str().unwrap());
+
+        let log_level = to_c_str("info");
+        assert!(!tantivy_search_fallback_logger_initialize(
+            std::ptr::null(),
+            log_level,
+            1024,
+            2
+        ));
+        assert!(!tantivy_search_fallback_logger_initialize(
+            to_c_str(&log_file_path),
+            log_level,
+            0,
+            2
+        ));
+
+        // A registered callback takes priority over fallback logger.
+        assert!(tantivy_search_update_log_callback(empty_log_callback));
+        assert!(tantivy_search_fallback_logger_initialize(
+            to_c_str(&log_file_path),
+            log_level,
+            1024,
+            2
+        ));
+        assert!(!std::path::Path::new(&log_file_path).exists());
+
+        // Fallback logger takes effect after callback removed.
+        assert!(tantivy_search_remove_log_callback());
+        assert!(std::path::Path::new(&log_file_path).exists());
+
+        *FALLBACK_LOGGER_CONFIG.lock().unwrap() = None;
+        assert!(tantivy_search_update_log_callback(empty_log_callback));
+    }
 }
@@ -0,0 +1,5 @@
+use crate::common::callback_cell::CallbackCell;
+use crate::common::constants::LogCallback;
+
+/// Holds the host log callback, it can be installed, replaced and removed at runtime.
+pub type LogCallbackCell = CallbackCell<LogCallback>;
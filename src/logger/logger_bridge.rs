@@ -1,4 +1,7 @@
 use crate::common::constants::{LogCallback, LOG_CALLBACK};
+use crate::common::constants::{FALLBACK_LOGGER_CONFIG, LOG4RS_HANDLE};
+use crate::logger::log_callback::LogCallbackCell;
+use crate::logger::logger_config::FallbackLoggerConfig;
 use crate::INFO;
 use once_cell::sync::OnceCell;
 use std::ffi::{c_int, CString};
@@ -17,11 +20,18 @@ fn to_cstring(s: String) -> CString {
 pub struct TantivySearchLogger;
 
 impl TantivySearchLogger {
+    // Install or replace log callback, logs in flight keep using the callback they loaded.
     pub fn update_log_callback(
-        cell: &OnceCell<LogCallback>,
+        cell: &LogCallbackCell,
         callback: LogCallback,
     ) -> Result<(), String> {
-        let _ = cell.get_or_init(|| callback);
+        let _ = cell.set(callback);
+        Ok(())
+    }
+
+    // Remove log callback, later logs won't trigger any callback.
+    pub fn remove_log_callback(cell: &LogCallbackCell) -> Result<(), String> {
+        let _ = cell.clear();
         Ok(())
     }
 
@@ -45,6 +55,35 @@ impl TantivySearchLogger {
         }
     }
 
+    // Save fallback file logger config, and apply it if no log callback is registered.
+    // Returns whether the fallback logger is applied.
+    pub fn install_fallback_logger(config: FallbackLoggerConfig) -> Result<bool, String> {
+        let mut fallback_config = FALLBACK_LOGGER_CONFIG
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        *fallback_config = Some(config);
+        drop(fallback_config);
+        Self::apply_fallback_logger()
+    }
+
+    // Apply saved fallback file logger config when no log callback is registered.
+    // Returns whether the fallback logger is applied.
+    pub fn apply_fallback_logger() -> Result<bool, String> {
+        if LOG_CALLBACK.get().is_some() {
+            return Ok(false);
+        }
+        let fallback_config = FALLBACK_LOGGER_CONFIG
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        match fallback_config.as_ref() {
+            Some(config) => {
+                Self::update_log4rs_handler(&LOG4RS_HANDLE, config.build_logger_config()?)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
     fn get_thread_id() -> String {
         let thread_id: String = format!("{:?}", thread::current().id());
         thread_id
@@ -75,7 +114,7 @@ impl TantivySearchLogger {
 
 #[cfg(test)]
 mod tests {
-    use crate::{LOG4RS_HANDLE, TEST_MUTEX};
+    use crate::{empty_log_callback, LOG4RS_HANDLE, TEST_MUTEX};
 
     use super::*;
     use libc::*;
@@ -85,7 +124,6 @@ mod tests {
         config::{Appender, Config, Root},
         encode::pattern::PatternEncoder,
     };
-    use once_cell::sync::OnceCell;
 
     extern "C" fn log_callback_for_test(level: i32, _info: *const c_char, _message: *const c_char) {
         assert_eq!(level, 1);
@@ -93,12 +131,26 @@ mod tests {
 
     #[test]
     fn test_update_log_callback() {
-        let callback_cell: OnceCell<LogCallback> = OnceCell::new();
+        let callback_cell = LogCallbackCell::new();
         assert!(callback_cell.get().is_none());
         let result =
             TantivySearchLogger::update_log_callback(&callback_cell, log_callback_for_test);
         assert!(callback_cell.get().is_some());
         assert!(result.is_ok());
+        // Replace the installed callback.
+        let result = TantivySearchLogger::update_log_callback(&callback_cell, empty_log_callback);
+        assert!(result.is_ok());
+        assert!(callback_cell.get().is_some());
+    }
+
+    #[test]
+    fn test_remove_log_callback() {
+        let callback_cell = LogCallbackCell::new();
+        assert!(
+            TantivySearchLogger::update_log_callback(&callback_cell, log_callback_for_test).is_ok()
+        );
+        assert!(TantivySearchLogger::remove_log_callback(&callback_cell).is_ok());
+        assert!(callback_cell.get().is_none());
     }
 
     #[test]
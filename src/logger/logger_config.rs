@@ -29,6 +29,26 @@ impl Filter for LoggerFilter {
     }
 }
 
+// Build a size based rolling file appender, `rotation_count` old log files are kept.
+fn build_rolling_file_appender(
+    log_file_path: &str,
+    log_rolling_pattern: &str,
+    max_file_size: u64,
+    rotation_count: u32,
+) -> Result<RollingFileAppender, String> {
+    let roller = FixedWindowRoller::builder()
+        .build(log_rolling_pattern, rotation_count)
+        .map_err(|e| e.to_string())?;
+
+    let size_trigger = SizeTrigger::new(max_file_size);
+    let policy = CompoundPolicy::new(Box::new(size_trigger), Box::new(roller));
+
+    RollingFileAppender::builder()
+        .encoder(Box::new(PatternEncoder::new("{d} - {l} - {t} - {m}{n}")))
+        .build(log_file_path, Box::new(policy))
+        .map_err(|e| e.to_string())
+}
+
 pub struct LoggerConfig {
     log_directory: String,            // log file stored path.
     log_level: LevelFilter,           // log level.
@@ -73,17 +93,13 @@ impl LoggerConfig {
             let log_file_path = format!("{}/tantivy-search.log", log_path_trimmed);
             let log_rolling_pattern = format!("{}/tantivy-search.{{}}.log", log_path_trimmed);
 
-            let roller = FixedWindowRoller::builder()
-                .build(&log_rolling_pattern, 15)
-                .map_err(|e| e.to_string())?;
-
-            let size_trigger = SizeTrigger::new(200 * 1024 * 1024); // log file trigger size: 100MB
-            let policy = CompoundPolicy::new(Box::new(size_trigger), Box::new(roller));
-
-            let file = RollingFileAppender::builder()
-                .encoder(Box::new(PatternEncoder::new("{d} - {l} - {t} - {m}{n}")))
-                .build(log_file_path, Box::new(policy))
-                .map_err(|e| e.to_string())?;
+            // log file trigger size: 200MB
+            let file = build_rolling_file_appender(
+                &log_file_path,
+                &log_rolling_pattern,
+                200 * 1024 * 1024,
+                15,
+            )?;
 
             let file_appender = Appender::builder()
                 .filter(Box::new(ThresholdFilter::new(self.log_level)))
@@ -119,6 +135,65 @@ impl LoggerConfig {
     }
 }
 
+/// Built-in file logger, used when host doesn't register a log callback,
+/// such as standalone tools and tests of this crate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FallbackLoggerConfig {
+    log_file_path: String, // log file path, rotated files are `{log_file_path}.{index}`.
+    log_level: LevelFilter, // log level.
+    max_file_size: u64,    // rotate log file after it exceeds this size in bytes.
+    rotation_count: u32,   // number of rotated log files to keep.
+}
+
+impl FallbackLoggerConfig {
+    pub fn new(
+        log_file_path: String,
+        log_level: String,
+        max_file_size: u64,
+        rotation_count: u32,
+    ) -> Result<Self, String> {
+        if log_file_path.is_empty() || log_file_path.ends_with('/') {
+            return Err(format!("Invalid log file path: `{}`", log_file_path));
+        }
+        if max_file_size == 0 || rotation_count == 0 {
+            return Err(format!(
+                "`max_file_size`({}) and `rotation_count`({}) should be greater than 0",
+                max_file_size, rotation_count
+            ));
+        }
+        // Reuse log level parsing of `LoggerConfig`.
+        let log_level = LoggerConfig::new(String::new(), log_level, false, false, false).log_level;
+        Ok(FallbackLoggerConfig {
+            log_file_path,
+            log_level,
+            max_file_size,
+            rotation_count,
+        })
+    }
+
+    pub fn build_logger_config(&self) -> Result<Config, String> {
+        let log_rolling_pattern = format!("{}.{{}}", self.log_file_path);
+        let file = build_rolling_file_appender(
+            &self.log_file_path,
+            &log_rolling_pattern,
+            self.max_file_size,
+            self.rotation_count,
+        )?;
+        let file_appender = Appender::builder()
+            .filter(Box::new(ThresholdFilter::new(self.log_level)))
+            .build("fallback_file", Box::new(file));
+
+        Config::builder()
+            .appender(file_appender)
+            .build(
+                Root::builder()
+                    .appender("fallback_file")
+                    .build(self.log_level),
+            )
+            .map_err(|e| e.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -156,4 +231,26 @@ mod tests {
         let config_result = logger_config.build_logger_config();
         assert!(config_result.is_ok());
     }
+
+    #[test]
+    fn test_fallback_logger_config() {
+        let log_directory = TempDir::new().unwrap();
+        let log_file_path = format!("{}/fallback.log", log_directory.path().to_str().unwrap());
+
+        assert!(FallbackLoggerConfig::new(String::new(), "info".to_string(), 1024, 2).is_err());
+        assert!(
+            FallbackLoggerConfig::new("/tmp/".to_string(), "info".to_string(), 1024, 2).is_err()
+        );
+        assert!(
+            FallbackLoggerConfig::new(log_file_path.clone(), "info".to_string(), 0, 2).is_err()
+        );
+        assert!(
+            FallbackLoggerConfig::new(log_file_path.clone(), "info".to_string(), 1024, 0).is_err()
+        );
+
+        let fallback_config =
+            FallbackLoggerConfig::new(log_file_path, "warning".to_string(), 1024, 2).unwrap();
+        assert_eq!(fallback_config.log_level, LevelFilter::Warn);
+        assert!(fallback_config.build_logger_config().is_ok());
+    }
 }
@@ -1,5 +1,7 @@
 // pub mod configure;
 pub mod ffi_logger;
+pub mod log_callback;
 pub mod logger_bridge;
 pub mod logger_config;
 pub mod macros;
+pub mod trace_profiler;
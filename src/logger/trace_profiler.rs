@@ -0,0 +1,247 @@
+/// Collects `tracing` spans into a chrome-trace or folded-stack file for a window of time.
+///
+/// Spans are only recorded when crate is built with `tracing-spans` feature,
+/// otherwise starting a profiling window returns an error.
+pub struct TraceProfiler;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TraceOutputFormat {
+    // Json file which can be loaded by `chrome://tracing` or perfetto.
+    Chrome,
+    // Folded stack file which can be rendered by `inferno-flamegraph`.
+    Folded,
+}
+
+impl TraceOutputFormat {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "chrome" | "chrome-trace" | "json" => Ok(TraceOutputFormat::Chrome),
+            "folded" | "folded-stack" | "flamegraph" => Ok(TraceOutputFormat::Folded),
+            _ => Err(format!(
+                "Unsupported trace output format: `{}`, expect `chrome` or `folded`",
+                name
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "tracing-spans")]
+mod profiler {
+    use super::TraceOutputFormat;
+    use crate::logger::logger_bridge::TantivySearchLogger;
+    use crate::{common::constants::LOG_CALLBACK, INFO, WARNING};
+    use once_cell::sync::Lazy;
+    use std::fs::File;
+    use std::io::BufWriter;
+    use std::sync::mpsc::{self, Sender};
+    use std::sync::Mutex;
+    use std::thread::{self, JoinHandle};
+    use std::time::Duration;
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_subscriber::{reload, Layer, Registry};
+
+    type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+    enum FlushGuard {
+        Chrome(tracing_chrome::FlushGuard),
+        Folded(tracing_flame::FlushGuard<BufWriter<File>>),
+    }
+
+    impl FlushGuard {
+        fn flush(self) {
+            match self {
+                FlushGuard::Chrome(guard) => guard.flush(),
+                FlushGuard::Folded(guard) => {
+                    if let Err(e) = guard.flush() {
+                        WARNING!("Failed to flush folded stack trace: {}", e);
+                    }
+                }
+            }
+        }
+    }
+
+    struct ProfilingWindow {
+        stop_sender: Sender<()>,
+        worker: JoinHandle<()>,
+    }
+
+    // Global subscriber can be set only once, profiling layer is swapped in and out by reload.
+    static RELOAD_HANDLE: Lazy<Result<reload::Handle<Option<BoxedLayer>, Registry>, String>> =
+        Lazy::new(|| {
+            let (layer, handle) = reload::Layer::new(None::<BoxedLayer>);
+            tracing::subscriber::set_global_default(Registry::default().with(layer))
+                .map_err(|e| format!("Failed to set global tracing subscriber: {}", e))?;
+            Ok(handle)
+        });
+
+    static PROFILING_WINDOW: Lazy<Mutex<Option<ProfilingWindow>>> = Lazy::new(|| Mutex::new(None));
+
+    pub fn start(
+        output_path: &str,
+        format: TraceOutputFormat,
+        duration: Duration,
+    ) -> Result<(), String> {
+        let handle = RELOAD_HANDLE.as_ref().map_err(|e| e.clone())?;
+        let mut window = PROFILING_WINDOW
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        if let Some(running) = window.as_ref() {
+            if !running.worker.is_finished() {
+                return Err("Trace profiling is already running".to_string());
+            }
+        }
+
+        let (layer, guard): (BoxedLayer, FlushGuard) = match format {
+            TraceOutputFormat::Chrome => {
+                let (layer, guard) = tracing_chrome::ChromeLayerBuilder::new()
+                    .file(output_path)
+                    .include_args(true)
+                    .build();
+                (Box::new(layer), FlushGuard::Chrome(guard))
+            }
+            TraceOutputFormat::Folded => {
+                let (layer, guard) = tracing_flame::FlameLayer::with_file(output_path)
+                    .map_err(|e| format!("Failed to create folded stack file: {}", e))?;
+                (Box::new(layer), FlushGuard::Folded(guard))
+            }
+        };
+        handle
+            .reload(Some(layer))
+            .map_err(|e| format!("Failed to enable trace profiling: {}", e))?;
+        INFO!(
+            "Trace profiling started, format:{:?}, output:[{}], duration:{:?}",
+            format,
+            output_path,
+            duration
+        );
+
+        let (stop_sender, stop_receiver) = mpsc::channel::<()>();
+        let worker_handle = handle.clone();
+        let worker = thread::Builder::new()
+            .name("trace_profiler".to_string())
+            .spawn(move || {
+                // Wait until window elapsed or stopped manually.
+                let _ = stop_receiver.recv_timeout(duration);
+                if let Err(e) = worker_handle.reload(None) {
+                    WARNING!("Failed to disable trace profiling: {}", e);
+                }
+                guard.flush();
+                INFO!("Trace profiling finished.");
+            })
+            .map_err(|e| format!("Failed to spawn trace profiler thread: {}", e))?;
+
+        *window = Some(ProfilingWindow {
+            stop_sender,
+            worker,
+        });
+        Ok(())
+    }
+
+    pub fn stop() -> Result<bool, String> {
+        let running = PROFILING_WINDOW
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?
+            .take();
+        match running {
+            Some(window) => {
+                let _ = window.stop_sender.send(());
+                window
+                    .worker
+                    .join()
+                    .map_err(|_| "Trace profiler thread panicked".to_string())?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+impl TraceProfiler {
+    // Record spans into `output_path` for `duration_secs`, output file is flushed when window ends.
+    pub fn start(output_path: &str, format: &str, duration_secs: u64) -> Result<(), String> {
+        if output_path.is_empty() {
+            return Err("Trace output path can't be empty".to_string());
+        }
+        if duration_secs == 0 {
+            return Err("Trace duration should be greater than 0".to_string());
+        }
+        let format = TraceOutputFormat::from_name(format)?;
+        #[cfg(feature = "tracing-spans")]
+        {
+            profiler::start(
+                output_path,
+                format,
+                std::time::Duration::from_secs(duration_secs),
+            )
+        }
+        #[cfg(not(feature = "tracing-spans"))]
+        {
+            let _ = format;
+            Err("Trace profiling requires `tracing-spans` feature".to_string())
+        }
+    }
+
+    // Stop profiling window in advance, returns false if no window is running.
+    pub fn stop() -> Result<bool, String> {
+        #[cfg(feature = "tracing-spans")]
+        {
+            profiler::stop()
+        }
+        #[cfg(not(feature = "tracing-spans"))]
+        {
+            Ok(false)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_trace_output_format() {
+        assert_eq!(
+            TraceOutputFormat::from_name("Chrome").unwrap(),
+            TraceOutputFormat::Chrome
+        );
+        assert_eq!(
+            TraceOutputFormat::from_name("folded").unwrap(),
+            TraceOutputFormat::Folded
+        );
+        assert!(TraceOutputFormat::from_name("svg").is_err());
+    }
+
+    #[test]
+    fn test_trace_profiler_invalid_arguments() {
+        assert!(TraceProfiler::start("", "chrome", 1).is_err());
+        assert!(TraceProfiler::start("/tmp/trace.json", "chrome", 0).is_err());
+        assert!(TraceProfiler::start("/tmp/trace.json", "svg", 1).is_err());
+    }
+
+    #[cfg(feature = "tracing-spans")]
+    #[test]
+    fn test_trace_profiler_window() {
+        let temp_directory = TempDir::new().unwrap();
+        let output_path = format!("{}/trace.json", temp_directory.path().to_str().unwrap());
+
+        assert!(TraceProfiler::start(&output_path, "chrome", 60).is_ok());
+        // Only one window can run at the same time.
+        assert!(TraceProfiler::start(&output_path, "folded", 60).is_err());
+        {
+            TRACE_SPAN!("test_trace_profiler_window");
+        }
+        assert!(TraceProfiler::stop().unwrap());
+        assert!(!TraceProfiler::stop().unwrap());
+        assert!(std::path::Path::new(&output_path).exists());
+    }
+
+    #[cfg(not(feature = "tracing-spans"))]
+    #[test]
+    fn test_trace_profiler_without_feature() {
+        let temp_directory = TempDir::new().unwrap();
+        let output_path = format!("{}/trace.json", temp_directory.path().to_str().unwrap());
+        assert!(TraceProfiler::start(&output_path, "chrome", 1).is_err());
+        assert!(!TraceProfiler::stop().unwrap());
+    }
+}
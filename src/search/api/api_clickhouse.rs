@@ -1,13 +1,21 @@
 use crate::logger::logger_bridge::TantivySearchLogger;
+use crate::search::implements::api_clickhouse_impl::query_date_range_bitmap;
+use crate::search::implements::api_clickhouse_impl::query_geo_bounding_box_bitmap;
+use crate::search::implements::api_clickhouse_impl::query_geo_distance_bitmap;
 use crate::search::implements::api_clickhouse_impl::query_sentence_bitmap;
 use crate::search::implements::api_clickhouse_impl::query_sentence_with_range;
 use crate::search::implements::api_clickhouse_impl::query_term_bitmap;
 use crate::search::implements::api_clickhouse_impl::query_term_with_range;
+use crate::search::implements::api_clickhouse_impl::query_terms_adaptive;
 use crate::search::implements::api_clickhouse_impl::query_terms_bitmap;
 use crate::search::implements::api_clickhouse_impl::query_terms_with_range;
 use crate::search::implements::api_clickhouse_impl::regex_term_bitmap;
 use crate::search::implements::api_clickhouse_impl::regex_term_with_range;
+use crate::search::implements::strategy::adaptive_collect::AdaptiveRows;
+use crate::search::utils::convert_utils::ConvertUtils;
+use crate::AdaptiveBitmapResult;
 use crate::BoolResult;
+use crate::RowIdRange;
 use crate::CXX_STRING_CONERTER;
 use crate::CXX_VECTOR_STRING_CONERTER;
 use crate::{common::constants::LOG_CALLBACK, ERROR};
@@ -370,6 +378,105 @@ pub fn ffi_query_terms_bitmap(
     }
 }
 
+pub fn ffi_query_terms_adaptive(
+    index_path: &CxxString,
+    column_name: &CxxString,
+    terms: &CxxVector<CxxString>,
+    mode: &CxxString,
+    need_row_ids: bool,
+    accept_ranges: bool,
+) -> AdaptiveBitmapResult {
+    let error_result = |error_msg: String| AdaptiveBitmapResult {
+        strategy: String::new(),
+        count: 0,
+        row_ids: Vec::new(),
+        range_start: 0,
+        range_end: 0,
+        excluded_row_ids: Vec::new(),
+        ranges: Vec::new(),
+        error_code: -1,
+        error_msg,
+    };
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_query_terms_adaptive", "Can't convert 'index_path', message: {}", e);
+            return error_result(format!("Can't convert 'index_path', message: {}", e));
+        }
+    };
+    let column_name: String = match CXX_STRING_CONERTER.convert(column_name) {
+        Ok(name) => name,
+        Err(e) => {
+            ERROR!(function: "ffi_query_terms_adaptive", "Can't convert 'column_name', message: {}", e);
+            return error_result(format!("Can't convert 'column_name', message: {}", e));
+        }
+    };
+    let terms: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(terms) {
+        Ok(ts) => ts,
+        Err(e) => {
+            ERROR!(function: "ffi_query_terms_adaptive", "Can't convert 'terms', message: {}", e);
+            return error_result(format!("Can't convert 'terms', message: {}", e));
+        }
+    };
+    let mode: String = match CXX_STRING_CONERTER.convert(mode) {
+        Ok(m) => m,
+        Err(e) => {
+            ERROR!(function: "ffi_query_terms_adaptive", "Can't convert 'mode', message: {}", e);
+            return error_result(format!("Can't convert 'mode', message: {}", e));
+        }
+    };
+
+    match query_terms_adaptive(
+        &index_path,
+        &column_name,
+        &terms,
+        &mode,
+        need_row_ids,
+        accept_ranges,
+    ) {
+        Ok(rows) => {
+            let mut result = AdaptiveBitmapResult {
+                strategy: rows.strategy_name().to_string(),
+                count: rows.count(),
+                row_ids: Vec::new(),
+                range_start: 0,
+                range_end: 0,
+                excluded_row_ids: Vec::new(),
+                ranges: Vec::new(),
+                error_code: 0,
+                error_msg: String::new(),
+            };
+            match rows {
+                AdaptiveRows::Bitmap(bitmap) => {
+                    let row_ids_number: Vec<u32> = bitmap.iter().collect();
+                    result.row_ids = ConvertUtils::row_ids_to_u8_bitmap(&row_ids_number);
+                }
+                AdaptiveRows::DenseRange {
+                    start,
+                    end,
+                    excluded,
+                } => {
+                    result.range_start = start;
+                    result.range_end = end;
+                    result.excluded_row_ids = excluded.iter().collect();
+                }
+                AdaptiveRows::Ranges(ranges) => {
+                    result.ranges = ranges
+                        .into_iter()
+                        .map(|(start, end)| RowIdRange { start, end })
+                        .collect();
+                }
+                AdaptiveRows::Empty | AdaptiveRows::Count(_) => {}
+            }
+            result
+        }
+        Err(e) => {
+            ERROR!(function: "ffi_query_terms_adaptive", "Error happend. {}", e);
+            error_result(format!("Error happend. {}", e))
+        }
+    }
+}
+
 pub fn ffi_query_sentence_bitmap(
     index_path: &CxxString,
     column_name: &CxxString,
@@ -441,3 +548,124 @@ pub fn ffi_regex_term_bitmap(
         }
     }
 }
+
+pub fn ffi_query_date_range_bitmap(
+    index_path: &CxxString,
+    column_name: &CxxString,
+    lower: &CxxString,
+    upper: &CxxString,
+    utc_offset_seconds: i32,
+) -> Vec<u8> {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_query_date_range_bitmap", "Can't convert 'index_path', message: {}", e);
+            return Vec::new();
+        }
+    };
+    let column_name: String = match CXX_STRING_CONERTER.convert(column_name) {
+        Ok(name) => name,
+        Err(e) => {
+            ERROR!(function: "ffi_query_date_range_bitmap", "Can't convert 'column_name', message: {}", e);
+            return Vec::new();
+        }
+    };
+    let lower: String = match CXX_STRING_CONERTER.convert(lower) {
+        Ok(bound) => bound,
+        Err(e) => {
+            ERROR!(function: "ffi_query_date_range_bitmap", "Can't convert 'lower', message: {}", e);
+            return Vec::new();
+        }
+    };
+    let upper: String = match CXX_STRING_CONERTER.convert(upper) {
+        Ok(bound) => bound,
+        Err(e) => {
+            ERROR!(function: "ffi_query_date_range_bitmap", "Can't convert 'upper', message: {}", e);
+            return Vec::new();
+        }
+    };
+
+    match query_date_range_bitmap(
+        &index_path,
+        &column_name,
+        &lower,
+        &upper,
+        utc_offset_seconds,
+    ) {
+        Ok(status) => status,
+        Err(e) => {
+            ERROR!(function: "ffi_query_date_range_bitmap", "Error happend. {}", e);
+            Vec::new()
+        }
+    }
+}
+
+pub fn ffi_query_geo_bounding_box_bitmap(
+    index_path: &CxxString,
+    column_name: &CxxString,
+    min_lat: f64,
+    min_lon: f64,
+    max_lat: f64,
+    max_lon: f64,
+) -> Vec<u8> {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_query_geo_bounding_box_bitmap", "Can't convert 'index_path', message: {}", e);
+            return Vec::new();
+        }
+    };
+    let column_name: String = match CXX_STRING_CONERTER.convert(column_name) {
+        Ok(name) => name,
+        Err(e) => {
+            ERROR!(function: "ffi_query_geo_bounding_box_bitmap", "Can't convert 'column_name', message: {}", e);
+            return Vec::new();
+        }
+    };
+
+    match query_geo_bounding_box_bitmap(
+        &index_path,
+        &column_name,
+        min_lat,
+        min_lon,
+        max_lat,
+        max_lon,
+    ) {
+        Ok(status) => status,
+        Err(e) => {
+            ERROR!(function: "ffi_query_geo_bounding_box_bitmap", "Error happend. {}", e);
+            Vec::new()
+        }
+    }
+}
+
+pub fn ffi_query_geo_distance_bitmap(
+    index_path: &CxxString,
+    column_name: &CxxString,
+    lat: f64,
+    lon: f64,
+    radius_meters: f64,
+) -> Vec<u8> {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_query_geo_distance_bitmap", "Can't convert 'index_path', message: {}", e);
+            return Vec::new();
+        }
+    };
+    let column_name: String = match CXX_STRING_CONERTER.convert(column_name) {
+        Ok(name) => name,
+        Err(e) => {
+            ERROR!(function: "ffi_query_geo_distance_bitmap", "Can't convert 'column_name', message: {}", e);
+            return Vec::new();
+        }
+    };
+
+    match query_geo_distance_bitmap(&index_path, &column_name, lat, lon, radius_meters) {
+        Ok(status) => status,
+        Err(e) => {
+            ERROR!(function: "ffi_query_geo_distance_bitmap", "Error happend. {}", e);
+            Vec::new()
+        }
+    }
+}
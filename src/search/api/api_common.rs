@@ -1,13 +1,28 @@
+use crate::common::constants::INCOMPATIBLE_INDEX_VERSION_ERROR_CODE;
+use crate::common::errors::TantivySearchError;
+use crate::common::query_cost_guard::QueryCostLimits;
 use crate::logger::logger_bridge::TantivySearchLogger;
+use crate::search::implements::api_common_impl::analyze_text;
+use crate::search::implements::api_common_impl::bootstrap_indexes;
+use crate::search::implements::api_common_impl::clear_deleted_row_ids;
+use crate::search::implements::api_common_impl::configure_query_audit;
 use crate::search::implements::api_common_impl::free_index_reader;
 use crate::search::implements::api_common_impl::get_index_json_parameter;
 use crate::search::implements::api_common_impl::get_index_meta_json;
 use crate::search::implements::api_common_impl::get_indexed_doc_counts;
 use crate::search::implements::api_common_impl::load_index_reader;
-use crate::CXX_STRING_CONERTER;
+use crate::search::implements::api_common_impl::normalize_term;
+use crate::search::implements::api_common_impl::register_deleted_row_ids;
+use crate::search::implements::api_common_impl::set_query_cost_limits;
+use crate::search::implements::api_common_impl::{
+    register_security_filter_query, register_security_filter_row_ids, set_search_principal,
+    unregister_security_filter,
+};
+use crate::search::utils::convert_utils::ConvertUtils;
 use crate::{common::constants::LOG_CALLBACK, ERROR};
-use crate::{BoolResult, StringResult};
-use cxx::CxxString;
+use crate::{cxx_vector_converter, CXX_STRING_CONERTER};
+use crate::{AnalyzedTokensResult, BoolResult, StringResult};
+use cxx::{CxxString, CxxVector};
 
 pub fn ffi_get_index_json_parameter(index_path: &CxxString) -> StringResult {
     let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
@@ -41,6 +56,132 @@ pub fn ffi_get_index_json_parameter(index_path: &CxxString) -> StringResult {
     }
 }
 
+pub fn ffi_analyze(
+    index_path_or_config: &CxxString,
+    field: &CxxString,
+    text: &CxxString,
+) -> AnalyzedTokensResult {
+    let index_path_or_config: String = match CXX_STRING_CONERTER.convert(index_path_or_config) {
+        Ok(value) => value,
+        Err(e) => {
+            ERROR!(function: "ffi_analyze", "Can't convert 'index_path_or_config', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert 'index_path_or_config', message: {}", e);
+            return AnalyzedTokensResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let field: String = match CXX_STRING_CONERTER.convert(field) {
+        Ok(value) => value,
+        Err(e) => {
+            ERROR!(function: "ffi_analyze", "Can't convert 'field', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'field', message: {}", e);
+            return AnalyzedTokensResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let text: String = match CXX_STRING_CONERTER.convert(text) {
+        Ok(value) => value,
+        Err(e) => {
+            ERROR!(function: "ffi_analyze", "Can't convert 'text', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'text', message: {}", e);
+            return AnalyzedTokensResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match analyze_text(&index_path_or_config, &field, &text) {
+        Ok(tokens) => AnalyzedTokensResult {
+            result: tokens,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_analyze", "Error analyze text: {}", e);
+            let error_msg_for_cxx: String = format!("Error analyze text: {}", e);
+            AnalyzedTokensResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_normalize_term(
+    index_path_or_config: &CxxString,
+    field: &CxxString,
+    value: &CxxString,
+) -> StringResult {
+    let index_path_or_config: String = match CXX_STRING_CONERTER.convert(index_path_or_config) {
+        Ok(value) => value,
+        Err(e) => {
+            ERROR!(function: "ffi_normalize_term", "Can't convert 'index_path_or_config', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert 'index_path_or_config', message: {}", e);
+            return StringResult {
+                result: String::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let field: String = match CXX_STRING_CONERTER.convert(field) {
+        Ok(value) => value,
+        Err(e) => {
+            ERROR!(function: "ffi_normalize_term", "Can't convert 'field', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'field', message: {}", e);
+            return StringResult {
+                result: String::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let value: String = match CXX_STRING_CONERTER.convert(value) {
+        Ok(value) => value,
+        Err(e) => {
+            ERROR!(function: "ffi_normalize_term", "Can't convert 'value', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'value', message: {}", e);
+            return StringResult {
+                result: String::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match normalize_term(&index_path_or_config, &field, &value) {
+        Ok(term) => StringResult {
+            result: term,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_normalize_term", "Error normalize term: {}", e);
+            let error_msg_for_cxx: String = format!("Error normalize term: {}", e);
+            StringResult {
+                result: String::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
 pub fn ffi_get_index_meta_json(index_path: &CxxString) -> StringResult {
     let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
         Ok(path) => path,
@@ -96,9 +237,15 @@ pub fn ffi_load_index_reader(index_path: &CxxString) -> BoolResult {
         Err(e) => {
             ERROR!(function: "ffi_load_index_reader", "Error loading index reader: {}", e);
             let error_msg_for_cxx: String = format!("Error loading index reader: {}", e);
+            let error_code = match e {
+                TantivySearchError::IncompatibleIndexVersion(_) => {
+                    INCOMPATIBLE_INDEX_VERSION_ERROR_CODE
+                }
+                _ => 1,
+            };
             return BoolResult {
                 result: false,
-                error_code: 1,
+                error_code,
                 error_msg: error_msg_for_cxx,
             };
         }
@@ -137,6 +284,42 @@ pub fn ffi_free_index_reader(index_path: &CxxString) -> BoolResult {
     }
 }
 
+pub fn ffi_bootstrap_indexes(
+    root_dir: &CxxString,
+    hot_within_secs: u64,
+    num_threads: u64,
+) -> StringResult {
+    let root_dir: String = match CXX_STRING_CONERTER.convert(root_dir) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_bootstrap_indexes", "Can't convert 'root_dir', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'root_dir', message: {}", e);
+            return StringResult {
+                result: String::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match bootstrap_indexes(&root_dir, hot_within_secs, num_threads as usize) {
+        Ok(summary) => StringResult {
+            result: summary,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_bootstrap_indexes", "Error bootstrap indexes: {}", e);
+            let error_msg_for_cxx: String = format!("Error bootstrap indexes: {}", e);
+            StringResult {
+                result: String::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
 pub fn ffi_get_indexed_doc_counts(index_path: &CxxString) -> u64 {
     let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
         Ok(path) => path,
@@ -154,3 +337,343 @@ pub fn ffi_get_indexed_doc_counts(index_path: &CxxString) -> u64 {
         }
     }
 }
+
+pub fn ffi_register_deleted_row_ids(
+    index_path: &CxxString,
+    row_ids: &CxxVector<u64>,
+) -> BoolResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_register_deleted_row_ids", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: 1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let row_ids: Vec<u64> = match cxx_vector_converter::<u64>().convert(row_ids) {
+        Ok(ids) => ids,
+        Err(e) => {
+            ERROR!(function: "ffi_register_deleted_row_ids", "Can't convert 'row_ids', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'row_ids', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: 1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match register_deleted_row_ids(&index_path, &row_ids) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_register_deleted_row_ids", "Error registering deleted row ids: {}", e);
+            let error_msg_for_cxx: String = format!("Error registering deleted row ids: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: 1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_clear_deleted_row_ids(index_path: &CxxString) -> BoolResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_clear_deleted_row_ids", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: 1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match clear_deleted_row_ids(&index_path) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_clear_deleted_row_ids", "Error clearing deleted row ids: {}", e);
+            let error_msg_for_cxx: String = format!("Error clearing deleted row ids: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: 1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_register_security_filter_query(
+    index_path: &CxxString,
+    principal: &CxxString,
+    sentence: &CxxString,
+) -> BoolResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(value) => value,
+        Err(e) => {
+            ERROR!(function: "ffi_register_security_filter_query", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: 1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+    let principal: String = match CXX_STRING_CONERTER.convert(principal) {
+        Ok(value) => value,
+        Err(e) => {
+            ERROR!(function: "ffi_register_security_filter_query", "Can't convert 'principal', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'principal', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: 1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+    let sentence: String = match CXX_STRING_CONERTER.convert(sentence) {
+        Ok(value) => value,
+        Err(e) => {
+            ERROR!(function: "ffi_register_security_filter_query", "Can't convert 'sentence', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'sentence', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: 1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match register_security_filter_query(&index_path, &principal, &sentence) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_register_security_filter_query", "Error registering security filter: {}", e);
+            let error_msg_for_cxx: String = format!("Error registering security filter: {}", e);
+            BoolResult {
+                result: false,
+                error_code: 1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_register_security_filter_bitmap(
+    index_path: &CxxString,
+    principal: &CxxString,
+    u8_bitmap: &CxxVector<u8>,
+) -> BoolResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(value) => value,
+        Err(e) => {
+            ERROR!(function: "ffi_register_security_filter_bitmap", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: 1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+    let principal: String = match CXX_STRING_CONERTER.convert(principal) {
+        Ok(value) => value,
+        Err(e) => {
+            ERROR!(function: "ffi_register_security_filter_bitmap", "Can't convert 'principal', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'principal', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: 1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+    let u8_bitmap: Vec<u8> = match cxx_vector_converter::<u8>().convert(u8_bitmap) {
+        Ok(value) => value,
+        Err(e) => {
+            ERROR!(function: "ffi_register_security_filter_bitmap", "Can't convert vector 'u8_bitmap', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'u8_bitmap', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: 1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+    let row_ids: Vec<u64> = ConvertUtils::u8_bitmap_to_row_ids64(&u8_bitmap);
+
+    match register_security_filter_row_ids(&index_path, &principal, &row_ids) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_register_security_filter_bitmap", "Error registering security filter: {}", e);
+            let error_msg_for_cxx: String = format!("Error registering security filter: {}", e);
+            BoolResult {
+                result: false,
+                error_code: 1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_unregister_security_filter(index_path: &CxxString, principal: &CxxString) -> BoolResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(value) => value,
+        Err(e) => {
+            ERROR!(function: "ffi_unregister_security_filter", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: 1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+    let principal: String = match CXX_STRING_CONERTER.convert(principal) {
+        Ok(value) => value,
+        Err(e) => {
+            ERROR!(function: "ffi_unregister_security_filter", "Can't convert 'principal', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'principal', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: 1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match unregister_security_filter(&index_path, &principal) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_unregister_security_filter", "Error unregistering security filter: {}", e);
+            let error_msg_for_cxx: String = format!("Error unregistering security filter: {}", e);
+            BoolResult {
+                result: false,
+                error_code: 1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_set_search_principal(principal: &CxxString) -> BoolResult {
+    let principal: String = match CXX_STRING_CONERTER.convert(principal) {
+        Ok(value) => value,
+        Err(e) => {
+            ERROR!(function: "ffi_set_search_principal", "Can't convert 'principal', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'principal', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: 1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match set_search_principal(&principal) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_set_search_principal", "Error setting search principal: {}", e);
+            let error_msg_for_cxx: String = format!("Error setting search principal: {}", e);
+            BoolResult {
+                result: false,
+                error_code: 1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_configure_query_audit(file_path: &CxxString, sample_rate: f64) -> BoolResult {
+    let file_path: String = match CXX_STRING_CONERTER.convert(file_path) {
+        Ok(value) => value,
+        Err(e) => {
+            ERROR!(function: "ffi_configure_query_audit", "Can't convert 'file_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'file_path', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: 1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match configure_query_audit(&file_path, sample_rate) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_configure_query_audit", "Error configuring query audit: {}", e);
+            let error_msg_for_cxx: String = format!("Error configuring query audit: {}", e);
+            BoolResult {
+                result: false,
+                error_code: 1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_set_query_cost_limits(
+    max_clause_count: u64,
+    max_regex_states: u64,
+    max_expanded_terms: u64,
+    max_bitmap_size: u64,
+) -> BoolResult {
+    let limits: QueryCostLimits = QueryCostLimits {
+        max_clause_count,
+        max_regex_states,
+        max_expanded_terms,
+        max_bitmap_size,
+    };
+    match set_query_cost_limits(limits) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_set_query_cost_limits", "Error setting query cost limits: {}", e);
+            let error_msg_for_cxx: String = format!("Error setting query cost limits: {}", e);
+            BoolResult {
+                result: false,
+                error_code: 1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
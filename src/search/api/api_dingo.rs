@@ -1,17 +1,36 @@
 use crate::cxx_vector_converter;
 use crate::logger::logger_bridge::TantivySearchLogger;
 use crate::search::implements::api_dingo_impl::{
-    bm25_search_with_column_names, get_doc_freq, get_total_num_docs, get_total_num_tokens,
-    index_reader_reload,
+    acquire_searcher_handle, bm25_filter_search, bm25_search_at_generation,
+    bm25_search_diversified, bm25_search_grouped, bm25_search_order_by_field, bm25_search_page,
+    bm25_search_with_bm25_params, bm25_search_with_column_names, bm25_search_with_field_boosts,
+    bm25_search_with_handle, bm25_search_with_matched_terms, bm25_search_with_query_dsl,
+    bm25_search_with_total_hits, digest_percentiles, estimate_cardinality, estimate_percentiles,
+    get_doc_freq, get_matched_offsets, get_stored_fields, get_stored_fields_with_handle,
+    get_total_num_docs, get_total_num_tokens, index_reader_reload, knn_search,
+    merge_cardinality_sketches, merge_percentiles_digests, percolate, pin_searcher,
+    register_percolator_query, release_searcher_handle, searcher_handle_num_docs,
+    sparse_vector_search, unpin_searcher, unregister_percolator_query,
 };
 use crate::search::utils::convert_utils::ConvertUtils;
 use crate::BM25Result;
 use crate::BoolResult;
+use crate::CardinalityResult;
 use crate::DocWithFreq;
+use crate::GroupTopDocsResult;
+use crate::MatchedOffsetsResult;
+use crate::MatchedTermsTopDocsResult;
+use crate::PercentilesResult;
+use crate::QueryIdsResult;
+use crate::StoredDocsResult;
+use crate::TopDocsWithTotalHits;
+use crate::TopDocsWithTotalHitsResult;
+use crate::U64Result;
 use crate::{common::constants::LOG_CALLBACK, ERROR};
 use crate::{CXX_STRING_CONERTER, CXX_VECTOR_STRING_CONERTER};
 use cxx::CxxString;
 use cxx::CxxVector;
+use std::collections::HashMap;
 
 pub fn ffi_bm25_search(
     index_path: &CxxString,
@@ -275,6 +294,2336 @@ pub fn ffi_bm25_search_with_column_names(
     }
 }
 
+pub fn ffi_bm25_filter_search(
+    index_path: &CxxString,
+    sentence: &CxxString,
+    topk: u32,
+    alived_ids: &CxxVector<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    column_names: &CxxVector<CxxString>,
+) -> BM25Result {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_filter_search", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let sentence: String = match CXX_STRING_CONERTER.convert(sentence) {
+        Ok(q) => q,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_filter_search", "Can't convert 'sentence', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'sentence', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let alived_ids: Vec<u64> = match cxx_vector_converter::<u64>().convert(alived_ids) {
+        Ok(bitmap) => bitmap,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_filter_search", "Can't convert vector 'alived_ids', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'alived_ids', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(column_names) {
+        Ok(names) => names,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_filter_search", "Can't convert vector 'column_names', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'column_names', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match bm25_filter_search(
+        &index_path,
+        &sentence,
+        topk,
+        &alived_ids,
+        query_with_filter,
+        query_with_id_range,
+        start_id,
+        end_id,
+        &column_names,
+    ) {
+        Ok(results) => {
+            return BM25Result {
+                result: results,
+                error_code: 0,
+                error_msg: String::new(),
+            };
+        }
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_filter_search", "Error performing BM25 search in filter mode: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Error performing BM25 search in filter mode: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_bm25_search_with_bm25_params(
+    index_path: &CxxString,
+    sentence: &CxxString,
+    topk: u32,
+    alived_ids: &CxxVector<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    column_names: &CxxVector<CxxString>,
+    k1: f32,
+    b: f32,
+) -> BM25Result {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_with_bm25_params", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let sentence: String = match CXX_STRING_CONERTER.convert(sentence) {
+        Ok(q) => q,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_with_bm25_params", "Can't convert 'sentence', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'sentence', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let alived_ids: Vec<u64> = match cxx_vector_converter::<u64>().convert(alived_ids) {
+        Ok(bitmap) => bitmap,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_with_bm25_params", "Can't convert vector 'alived_ids', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'alived_ids', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(column_names) {
+        Ok(names) => names,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_with_bm25_params", "Can't convert vector 'column_names', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'column_names', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match bm25_search_with_bm25_params(
+        &index_path,
+        &sentence,
+        topk,
+        &alived_ids,
+        query_with_filter,
+        query_with_id_range,
+        start_id,
+        end_id,
+        false,
+        &column_names,
+        k1,
+        b,
+    ) {
+        Ok(results) => {
+            return BM25Result {
+                result: results,
+                error_code: 0,
+                error_msg: String::new(),
+            };
+        }
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_with_bm25_params", "Error performing BM25 search with bm25 params: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Error performing BM25 search with bm25 params: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_bm25_search_at_generation(
+    index_path: &CxxString,
+    sentence: &CxxString,
+    topk: u32,
+    alived_ids: &CxxVector<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    column_names: &CxxVector<CxxString>,
+    generation: u64,
+) -> BM25Result {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_at_generation", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let sentence: String = match CXX_STRING_CONERTER.convert(sentence) {
+        Ok(q) => q,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_at_generation", "Can't convert 'sentence', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'sentence', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let alived_ids: Vec<u64> = match cxx_vector_converter::<u64>().convert(alived_ids) {
+        Ok(bitmap) => bitmap,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_at_generation", "Can't convert vector 'alived_ids', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'alived_ids', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(column_names) {
+        Ok(names) => names,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_at_generation", "Can't convert vector 'column_names', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'column_names', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match bm25_search_at_generation(
+        &index_path,
+        &sentence,
+        topk,
+        &alived_ids,
+        query_with_filter,
+        query_with_id_range,
+        start_id,
+        end_id,
+        false,
+        &column_names,
+        Some(generation),
+    ) {
+        Ok(results) => {
+            return BM25Result {
+                result: results,
+                error_code: 0,
+                error_msg: String::new(),
+            };
+        }
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_at_generation", "Error performing BM25 search with statistics: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Error performing BM25 search with statistics: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_bm25_search_with_field_boosts(
+    index_path: &CxxString,
+    sentence: &CxxString,
+    topk: u32,
+    alived_ids: &CxxVector<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    column_names: &CxxVector<CxxString>,
+    boosts: &CxxVector<f32>,
+) -> BM25Result {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_with_field_boosts", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let sentence: String = match CXX_STRING_CONERTER.convert(sentence) {
+        Ok(q) => q,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_with_field_boosts", "Can't convert 'sentence', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'sentence', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let alived_ids: Vec<u64> = match cxx_vector_converter::<u64>().convert(alived_ids) {
+        Ok(bitmap) => bitmap,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_with_field_boosts", "Can't convert vector 'alived_ids', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'alived_ids', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(column_names) {
+        Ok(names) => names,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_with_field_boosts", "Can't convert vector 'column_names', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'column_names', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let boosts: Vec<f32> = match cxx_vector_converter::<f32>().convert(boosts) {
+        Ok(boosts) => boosts,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_with_field_boosts", "Can't convert vector 'boosts', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'boosts', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    if column_names.len() != boosts.len() {
+        let error_msg_for_cxx: String = format!(
+            "Size of 'column_names' {} and 'boosts' {} should be same",
+            column_names.len(),
+            boosts.len()
+        );
+        ERROR!(function: "ffi_bm25_search_with_field_boosts", "{}", error_msg_for_cxx);
+        return BM25Result {
+            result: Vec::new(),
+            error_code: -1,
+            error_msg: error_msg_for_cxx,
+        };
+    }
+    let field_boosts: HashMap<String, f32> = column_names.into_iter().zip(boosts).collect();
+
+    match bm25_search_with_field_boosts(
+        &index_path,
+        &sentence,
+        topk,
+        &alived_ids,
+        query_with_filter,
+        query_with_id_range,
+        start_id,
+        end_id,
+        false,
+        &field_boosts,
+    ) {
+        Ok(results) => {
+            return BM25Result {
+                result: results,
+                error_code: 0,
+                error_msg: String::new(),
+            };
+        }
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_with_field_boosts", "Error performing BM25 search with field boosts: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Error performing BM25 search with field boosts: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_bm25_search_page(
+    index_path: &CxxString,
+    sentence: &CxxString,
+    topk: u32,
+    offset: u32,
+    has_search_after: bool,
+    after_score: f32,
+    after_row_id: u64,
+    alived_ids: &CxxVector<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    column_names: &CxxVector<CxxString>,
+) -> BM25Result {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_page", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let sentence: String = match CXX_STRING_CONERTER.convert(sentence) {
+        Ok(q) => q,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_page", "Can't convert 'sentence', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'sentence', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let alived_ids: Vec<u64> = match cxx_vector_converter::<u64>().convert(alived_ids) {
+        Ok(bitmap) => bitmap,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_page", "Can't convert vector 'alived_ids', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'alived_ids', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(column_names) {
+        Ok(names) => names,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_page", "Can't convert vector 'column_names', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'column_names', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let search_after: Option<(f32, u64)> = match has_search_after {
+        true => Some((after_score, after_row_id)),
+        false => None,
+    };
+
+    match bm25_search_page(
+        &index_path,
+        &sentence,
+        topk,
+        offset,
+        search_after,
+        &alived_ids,
+        query_with_filter,
+        query_with_id_range,
+        start_id,
+        end_id,
+        false,
+        &column_names,
+        &HashMap::new(),
+        None,
+    ) {
+        Ok(results) => {
+            return BM25Result {
+                result: results,
+                error_code: 0,
+                error_msg: String::new(),
+            };
+        }
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_page", "Error performing BM25 search with statistics: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Error performing BM25 search with statistics: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_bm25_search_with_query_dsl(
+    index_path: &CxxString,
+    query_json: &CxxString,
+    topk: u32,
+    alived_ids: &CxxVector<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+) -> BM25Result {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_with_query_dsl", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let query_json: String = match CXX_STRING_CONERTER.convert(query_json) {
+        Ok(json) => json,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_with_query_dsl", "Can't convert 'query_json', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'query_json', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let alived_ids: Vec<u64> = match cxx_vector_converter::<u64>().convert(alived_ids) {
+        Ok(bitmap) => bitmap,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_with_query_dsl", "Can't convert vector 'alived_ids', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'alived_ids', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match bm25_search_with_query_dsl(
+        &index_path,
+        &query_json,
+        topk,
+        &alived_ids,
+        query_with_filter,
+        query_with_id_range,
+        start_id,
+        end_id,
+        false,
+    ) {
+        Ok(results) => {
+            return BM25Result {
+                result: results,
+                error_code: 0,
+                error_msg: String::new(),
+            };
+        }
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_with_query_dsl", "Error performing BM25 search with query dsl: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Error performing BM25 search with query dsl: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_knn_search(
+    index_path: &CxxString,
+    column_name: &CxxString,
+    vector: &CxxVector<f32>,
+    topk: u32,
+    filter_sentence: &CxxString,
+    alived_ids: &CxxVector<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+) -> BM25Result {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_knn_search", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let column_name: String = match CXX_STRING_CONERTER.convert(column_name) {
+        Ok(name) => name,
+        Err(e) => {
+            ERROR!(function: "ffi_knn_search", "Can't convert 'column_name', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'column_name', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let vector: Vec<f32> = match cxx_vector_converter::<f32>().convert(vector) {
+        Ok(vector) => vector,
+        Err(e) => {
+            ERROR!(function: "ffi_knn_search", "Can't convert vector 'vector', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'vector', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let filter_sentence: String = match CXX_STRING_CONERTER.convert(filter_sentence) {
+        Ok(sentence) => sentence,
+        Err(e) => {
+            ERROR!(function: "ffi_knn_search", "Can't convert 'filter_sentence', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert 'filter_sentence', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let alived_ids: Vec<u64> = match cxx_vector_converter::<u64>().convert(alived_ids) {
+        Ok(bitmap) => bitmap,
+        Err(e) => {
+            ERROR!(function: "ffi_knn_search", "Can't convert vector 'alived_ids', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'alived_ids', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match knn_search(
+        &index_path,
+        &column_name,
+        &vector,
+        topk,
+        &filter_sentence,
+        &alived_ids,
+        query_with_filter,
+        query_with_id_range,
+        start_id,
+        end_id,
+        false,
+    ) {
+        Ok(results) => {
+            return BM25Result {
+                result: results,
+                error_code: 0,
+                error_msg: String::new(),
+            };
+        }
+        Err(e) => {
+            ERROR!(function: "ffi_knn_search", "Error performing knn search: {}", e);
+            let error_msg_for_cxx: String = format!("Error performing knn search: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_sparse_vector_search(
+    index_path: &CxxString,
+    column_name: &CxxString,
+    query_json: &CxxString,
+    topk: u32,
+    filter_sentence: &CxxString,
+    alived_ids: &CxxVector<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+) -> BM25Result {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_sparse_vector_search", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let column_name: String = match CXX_STRING_CONERTER.convert(column_name) {
+        Ok(name) => name,
+        Err(e) => {
+            ERROR!(function: "ffi_sparse_vector_search", "Can't convert 'column_name', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'column_name', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let query_json: String = match CXX_STRING_CONERTER.convert(query_json) {
+        Ok(json) => json,
+        Err(e) => {
+            ERROR!(function: "ffi_sparse_vector_search", "Can't convert 'query_json', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'query_json', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let filter_sentence: String = match CXX_STRING_CONERTER.convert(filter_sentence) {
+        Ok(sentence) => sentence,
+        Err(e) => {
+            ERROR!(function: "ffi_sparse_vector_search", "Can't convert 'filter_sentence', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert 'filter_sentence', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let alived_ids: Vec<u64> = match cxx_vector_converter::<u64>().convert(alived_ids) {
+        Ok(bitmap) => bitmap,
+        Err(e) => {
+            ERROR!(function: "ffi_sparse_vector_search", "Can't convert vector 'alived_ids', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'alived_ids', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match sparse_vector_search(
+        &index_path,
+        &column_name,
+        &query_json,
+        topk,
+        &filter_sentence,
+        &alived_ids,
+        query_with_filter,
+        query_with_id_range,
+        start_id,
+        end_id,
+        false,
+    ) {
+        Ok(results) => {
+            return BM25Result {
+                result: results,
+                error_code: 0,
+                error_msg: String::new(),
+            };
+        }
+        Err(e) => {
+            ERROR!(function: "ffi_sparse_vector_search", "Error performing sparse vector search: {}", e);
+            let error_msg_for_cxx: String = format!("Error performing sparse vector search: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_bm25_search_order_by_field(
+    index_path: &CxxString,
+    sentence: &CxxString,
+    topk: u32,
+    order_field: &CxxString,
+    ascending: bool,
+    alived_ids: &CxxVector<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    column_names: &CxxVector<CxxString>,
+) -> BM25Result {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_order_by_field", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let sentence: String = match CXX_STRING_CONERTER.convert(sentence) {
+        Ok(q) => q,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_order_by_field", "Can't convert 'sentence', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'sentence', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let alived_ids: Vec<u64> = match cxx_vector_converter::<u64>().convert(alived_ids) {
+        Ok(bitmap) => bitmap,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_order_by_field", "Can't convert vector 'alived_ids', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'alived_ids', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(column_names) {
+        Ok(names) => names,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_order_by_field", "Can't convert vector 'column_names', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'column_names', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let order_field: String = match CXX_STRING_CONERTER.convert(order_field) {
+        Ok(field) => field,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_order_by_field", "Can't convert 'order_field', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'order_field', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match bm25_search_order_by_field(
+        &index_path,
+        &sentence,
+        topk,
+        &order_field,
+        ascending,
+        &alived_ids,
+        query_with_filter,
+        query_with_id_range,
+        start_id,
+        end_id,
+        false,
+        &column_names,
+    ) {
+        Ok(results) => {
+            return BM25Result {
+                result: results,
+                error_code: 0,
+                error_msg: String::new(),
+            };
+        }
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_order_by_field", "Error performing BM25 search with statistics: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Error performing BM25 search with statistics: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_bm25_search_diversified(
+    index_path: &CxxString,
+    sentence: &CxxString,
+    topk: u32,
+    diversify_field: &CxxString,
+    max_per_key: u32,
+    alived_ids: &CxxVector<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    column_names: &CxxVector<CxxString>,
+) -> BM25Result {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_diversified", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let sentence: String = match CXX_STRING_CONERTER.convert(sentence) {
+        Ok(q) => q,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_diversified", "Can't convert 'sentence', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'sentence', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let alived_ids: Vec<u64> = match cxx_vector_converter::<u64>().convert(alived_ids) {
+        Ok(bitmap) => bitmap,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_diversified", "Can't convert vector 'alived_ids', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'alived_ids', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(column_names) {
+        Ok(names) => names,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_diversified", "Can't convert vector 'column_names', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'column_names', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let diversify_field: String = match CXX_STRING_CONERTER.convert(diversify_field) {
+        Ok(field) => field,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_diversified", "Can't convert 'diversify_field', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert 'diversify_field', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match bm25_search_diversified(
+        &index_path,
+        &sentence,
+        topk,
+        &diversify_field,
+        max_per_key,
+        &alived_ids,
+        query_with_filter,
+        query_with_id_range,
+        start_id,
+        end_id,
+        false,
+        &column_names,
+    ) {
+        Ok(results) => {
+            return BM25Result {
+                result: results,
+                error_code: 0,
+                error_msg: String::new(),
+            };
+        }
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_diversified", "Error performing BM25 search with statistics: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Error performing BM25 search with statistics: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_bm25_search_grouped(
+    index_path: &CxxString,
+    sentence: &CxxString,
+    topk: u32,
+    group_field: &CxxString,
+    alived_ids: &CxxVector<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    column_names: &CxxVector<CxxString>,
+) -> GroupTopDocsResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_grouped", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return GroupTopDocsResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let sentence: String = match CXX_STRING_CONERTER.convert(sentence) {
+        Ok(q) => q,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_grouped", "Can't convert 'sentence', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'sentence', message: {}", e);
+            return GroupTopDocsResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let alived_ids: Vec<u64> = match cxx_vector_converter::<u64>().convert(alived_ids) {
+        Ok(bitmap) => bitmap,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_grouped", "Can't convert vector 'alived_ids', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'alived_ids', message: {}", e);
+            return GroupTopDocsResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(column_names) {
+        Ok(names) => names,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_grouped", "Can't convert vector 'column_names', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'column_names', message: {}", e);
+            return GroupTopDocsResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let group_field: String = match CXX_STRING_CONERTER.convert(group_field) {
+        Ok(field) => field,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_grouped", "Can't convert 'group_field', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'group_field', message: {}", e);
+            return GroupTopDocsResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match bm25_search_grouped(
+        &index_path,
+        &sentence,
+        topk,
+        &group_field,
+        &alived_ids,
+        query_with_filter,
+        query_with_id_range,
+        start_id,
+        end_id,
+        false,
+        &column_names,
+    ) {
+        Ok(results) => {
+            return GroupTopDocsResult {
+                result: results,
+                error_code: 0,
+                error_msg: String::new(),
+            };
+        }
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_grouped", "Error performing grouped BM25 search: {}", e);
+            let error_msg_for_cxx: String = format!("Error performing grouped BM25 search: {}", e);
+            return GroupTopDocsResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_bm25_search_with_matched_terms(
+    index_path: &CxxString,
+    sentence: &CxxString,
+    topk: u32,
+    alived_ids: &CxxVector<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    column_names: &CxxVector<CxxString>,
+) -> MatchedTermsTopDocsResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_with_matched_terms", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return MatchedTermsTopDocsResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let sentence: String = match CXX_STRING_CONERTER.convert(sentence) {
+        Ok(q) => q,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_with_matched_terms", "Can't convert 'sentence', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'sentence', message: {}", e);
+            return MatchedTermsTopDocsResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let alived_ids: Vec<u64> = match cxx_vector_converter::<u64>().convert(alived_ids) {
+        Ok(bitmap) => bitmap,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_with_matched_terms", "Can't convert vector 'alived_ids', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'alived_ids', message: {}", e);
+            return MatchedTermsTopDocsResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(column_names) {
+        Ok(names) => names,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_with_matched_terms", "Can't convert vector 'column_names', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'column_names', message: {}", e);
+            return MatchedTermsTopDocsResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match bm25_search_with_matched_terms(
+        &index_path,
+        &sentence,
+        topk,
+        &alived_ids,
+        query_with_filter,
+        query_with_id_range,
+        start_id,
+        end_id,
+        false,
+        &column_names,
+    ) {
+        Ok(results) => {
+            return MatchedTermsTopDocsResult {
+                result: results,
+                error_code: 0,
+                error_msg: String::new(),
+            };
+        }
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_with_matched_terms", "Error performing BM25 search with matched terms: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Error performing BM25 search with matched terms: {}", e);
+            return MatchedTermsTopDocsResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_bm25_search_with_total_hits(
+    index_path: &CxxString,
+    sentence: &CxxString,
+    topk: u32,
+    alived_ids: &CxxVector<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    column_names: &CxxVector<CxxString>,
+    exact_total_hits: bool,
+) -> TopDocsWithTotalHitsResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_with_total_hits", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return TopDocsWithTotalHitsResult {
+                result: TopDocsWithTotalHits {
+                    top_docs: Vec::new(),
+                    total_hits: 0,
+                    total_hits_exact: false,
+                },
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let sentence: String = match CXX_STRING_CONERTER.convert(sentence) {
+        Ok(q) => q,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_with_total_hits", "Can't convert 'sentence', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'sentence', message: {}", e);
+            return TopDocsWithTotalHitsResult {
+                result: TopDocsWithTotalHits {
+                    top_docs: Vec::new(),
+                    total_hits: 0,
+                    total_hits_exact: false,
+                },
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let alived_ids: Vec<u64> = match cxx_vector_converter::<u64>().convert(alived_ids) {
+        Ok(bitmap) => bitmap,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_with_total_hits", "Can't convert vector 'alived_ids', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'alived_ids', message: {}", e);
+            return TopDocsWithTotalHitsResult {
+                result: TopDocsWithTotalHits {
+                    top_docs: Vec::new(),
+                    total_hits: 0,
+                    total_hits_exact: false,
+                },
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(column_names) {
+        Ok(names) => names,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_with_total_hits", "Can't convert vector 'column_names', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'column_names', message: {}", e);
+            return TopDocsWithTotalHitsResult {
+                result: TopDocsWithTotalHits {
+                    top_docs: Vec::new(),
+                    total_hits: 0,
+                    total_hits_exact: false,
+                },
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match bm25_search_with_total_hits(
+        &index_path,
+        &sentence,
+        topk,
+        &alived_ids,
+        query_with_filter,
+        query_with_id_range,
+        start_id,
+        end_id,
+        false,
+        &column_names,
+        exact_total_hits,
+    ) {
+        Ok(results) => {
+            return TopDocsWithTotalHitsResult {
+                result: results,
+                error_code: 0,
+                error_msg: String::new(),
+            };
+        }
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_with_total_hits", "Error performing BM25 search with total hits: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Error performing BM25 search with total hits: {}", e);
+            return TopDocsWithTotalHitsResult {
+                result: TopDocsWithTotalHits {
+                    top_docs: Vec::new(),
+                    total_hits: 0,
+                    total_hits_exact: false,
+                },
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_get_stored_fields(
+    index_path: &CxxString,
+    row_ids: &CxxVector<u64>,
+    field_names: &CxxVector<CxxString>,
+) -> StoredDocsResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_get_stored_fields", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return StoredDocsResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let row_ids: Vec<u64> = match cxx_vector_converter::<u64>().convert(row_ids) {
+        Ok(ids) => ids,
+        Err(e) => {
+            ERROR!(function: "ffi_get_stored_fields", "Can't convert vector 'row_ids', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'row_ids', message: {}", e);
+            return StoredDocsResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let field_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(field_names) {
+        Ok(names) => names,
+        Err(e) => {
+            ERROR!(function: "ffi_get_stored_fields", "Can't convert vector 'field_names', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'field_names', message: {}", e);
+            return StoredDocsResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match get_stored_fields(&index_path, &row_ids, &field_names) {
+        Ok(results) => StoredDocsResult {
+            result: results,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_get_stored_fields", "Error reading stored fields: {}", e);
+            let error_msg_for_cxx: String = format!("Error reading stored fields: {}", e);
+            StoredDocsResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_get_matched_offsets(
+    index_path: &CxxString,
+    row_id: u64,
+    sentence: &CxxString,
+    column_names: &CxxVector<CxxString>,
+) -> MatchedOffsetsResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_get_matched_offsets", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return MatchedOffsetsResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let sentence: String = match CXX_STRING_CONERTER.convert(sentence) {
+        Ok(q) => q,
+        Err(e) => {
+            ERROR!(function: "ffi_get_matched_offsets", "Can't convert 'sentence', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'sentence', message: {}", e);
+            return MatchedOffsetsResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(column_names) {
+        Ok(names) => names,
+        Err(e) => {
+            ERROR!(function: "ffi_get_matched_offsets", "Can't convert vector 'column_names', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'column_names', message: {}", e);
+            return MatchedOffsetsResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match get_matched_offsets(&index_path, row_id, &sentence, &column_names) {
+        Ok(results) => MatchedOffsetsResult {
+            result: results,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_get_matched_offsets", "Error getting matched offsets: {}", e);
+            let error_msg_for_cxx: String = format!("Error getting matched offsets: {}", e);
+            MatchedOffsetsResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_register_percolator_query(
+    index_path: &CxxString,
+    query_id: u64,
+    sentence: &CxxString,
+) -> BoolResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(index_path) => index_path,
+        Err(e) => {
+            ERROR!(function: "ffi_register_percolator_query", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let sentence: String = match CXX_STRING_CONERTER.convert(sentence) {
+        Ok(sentence) => sentence,
+        Err(e) => {
+            ERROR!(function: "ffi_register_percolator_query", "Can't convert 'sentence', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'sentence', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match register_percolator_query(&index_path, query_id, &sentence) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_register_percolator_query", "Error registering percolator query: {}", e);
+            let error_msg_for_cxx: String = format!("Error registering percolator query: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_unregister_percolator_query(index_path: &CxxString, query_id: u64) -> BoolResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(index_path) => index_path,
+        Err(e) => {
+            ERROR!(function: "ffi_unregister_percolator_query", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match unregister_percolator_query(&index_path, query_id) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_unregister_percolator_query", "Error unregistering percolator query: {}", e);
+            let error_msg_for_cxx: String = format!("Error unregistering percolator query: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_percolate(
+    index_path: &CxxString,
+    column_names: &CxxVector<CxxString>,
+    column_docs: &CxxVector<CxxString>,
+) -> QueryIdsResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(index_path) => index_path,
+        Err(e) => {
+            ERROR!(function: "ffi_percolate", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return QueryIdsResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(column_names) {
+        Ok(names) => names,
+        Err(e) => {
+            ERROR!(function: "ffi_percolate", "Can't convert vector 'column_names', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'column_names', message: {}", e);
+            return QueryIdsResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let column_docs: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(column_docs) {
+        Ok(docs) => docs,
+        Err(e) => {
+            ERROR!(function: "ffi_percolate", "Can't convert vector 'column_docs', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'column_docs', message: {}", e);
+            return QueryIdsResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match percolate(&index_path, &column_names, &column_docs) {
+        Ok(query_ids) => QueryIdsResult {
+            result: query_ids,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_percolate", "Error percolating doc: {}", e);
+            let error_msg_for_cxx: String = format!("Error percolating doc: {}", e);
+            return QueryIdsResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_estimate_cardinality(
+    index_path: &CxxString,
+    sentence: &CxxString,
+    field_name: &CxxString,
+    alived_ids: &CxxVector<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    column_names: &CxxVector<CxxString>,
+) -> CardinalityResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_estimate_cardinality", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return CardinalityResult {
+                estimate: 0,
+                sketch: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let sentence: String = match CXX_STRING_CONERTER.convert(sentence) {
+        Ok(q) => q,
+        Err(e) => {
+            ERROR!(function: "ffi_estimate_cardinality", "Can't convert 'sentence', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'sentence', message: {}", e);
+            return CardinalityResult {
+                estimate: 0,
+                sketch: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let field_name: String = match CXX_STRING_CONERTER.convert(field_name) {
+        Ok(field) => field,
+        Err(e) => {
+            ERROR!(function: "ffi_estimate_cardinality", "Can't convert 'field_name', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'field_name', message: {}", e);
+            return CardinalityResult {
+                estimate: 0,
+                sketch: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let alived_ids: Vec<u64> = match cxx_vector_converter::<u64>().convert(alived_ids) {
+        Ok(ids) => ids,
+        Err(e) => {
+            ERROR!(function: "ffi_estimate_cardinality", "Can't convert vector 'alived_ids', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'alived_ids', message: {}", e);
+            return CardinalityResult {
+                estimate: 0,
+                sketch: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(column_names) {
+        Ok(names) => names,
+        Err(e) => {
+            ERROR!(function: "ffi_estimate_cardinality", "Can't convert vector 'column_names', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'column_names', message: {}", e);
+            return CardinalityResult {
+                estimate: 0,
+                sketch: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match estimate_cardinality(
+        &index_path,
+        &sentence,
+        &field_name,
+        &alived_ids,
+        query_with_filter,
+        query_with_id_range,
+        start_id,
+        end_id,
+        &column_names,
+    ) {
+        Ok(sketch) => CardinalityResult {
+            estimate: sketch.estimate(),
+            sketch: sketch.to_bytes(),
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_estimate_cardinality", "Error estimating cardinality: {}", e);
+            let error_msg_for_cxx: String = format!("Error estimating cardinality: {}", e);
+            return CardinalityResult {
+                estimate: 0,
+                sketch: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_merge_cardinality_sketches(
+    left: &CxxVector<u8>,
+    right: &CxxVector<u8>,
+) -> CardinalityResult {
+    let left: Vec<u8> = match cxx_vector_converter::<u8>().convert(left) {
+        Ok(sketch) => sketch,
+        Err(e) => {
+            ERROR!(function: "ffi_merge_cardinality_sketches", "Can't convert vector 'left', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert vector 'left', message: {}", e);
+            return CardinalityResult {
+                estimate: 0,
+                sketch: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let right: Vec<u8> = match cxx_vector_converter::<u8>().convert(right) {
+        Ok(sketch) => sketch,
+        Err(e) => {
+            ERROR!(function: "ffi_merge_cardinality_sketches", "Can't convert vector 'right', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert vector 'right', message: {}", e);
+            return CardinalityResult {
+                estimate: 0,
+                sketch: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match merge_cardinality_sketches(&left, &right) {
+        Ok(sketch) => CardinalityResult {
+            estimate: sketch.estimate(),
+            sketch: sketch.to_bytes(),
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_merge_cardinality_sketches", "Error merging cardinality sketches: {}", e);
+            let error_msg_for_cxx: String = format!("Error merging cardinality sketches: {}", e);
+            return CardinalityResult {
+                estimate: 0,
+                sketch: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_estimate_percentiles(
+    index_path: &CxxString,
+    sentence: &CxxString,
+    field_name: &CxxString,
+    percents: &CxxVector<f64>,
+    alived_ids: &CxxVector<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    column_names: &CxxVector<CxxString>,
+) -> PercentilesResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_estimate_percentiles", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return PercentilesResult {
+                values: Vec::new(),
+                digest: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let sentence: String = match CXX_STRING_CONERTER.convert(sentence) {
+        Ok(q) => q,
+        Err(e) => {
+            ERROR!(function: "ffi_estimate_percentiles", "Can't convert 'sentence', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'sentence', message: {}", e);
+            return PercentilesResult {
+                values: Vec::new(),
+                digest: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let field_name: String = match CXX_STRING_CONERTER.convert(field_name) {
+        Ok(field) => field,
+        Err(e) => {
+            ERROR!(function: "ffi_estimate_percentiles", "Can't convert 'field_name', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'field_name', message: {}", e);
+            return PercentilesResult {
+                values: Vec::new(),
+                digest: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let percents: Vec<f64> = match cxx_vector_converter::<f64>().convert(percents) {
+        Ok(percents) => percents,
+        Err(e) => {
+            ERROR!(function: "ffi_estimate_percentiles", "Can't convert vector 'percents', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'percents', message: {}", e);
+            return PercentilesResult {
+                values: Vec::new(),
+                digest: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let alived_ids: Vec<u64> = match cxx_vector_converter::<u64>().convert(alived_ids) {
+        Ok(ids) => ids,
+        Err(e) => {
+            ERROR!(function: "ffi_estimate_percentiles", "Can't convert vector 'alived_ids', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'alived_ids', message: {}", e);
+            return PercentilesResult {
+                values: Vec::new(),
+                digest: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(column_names) {
+        Ok(names) => names,
+        Err(e) => {
+            ERROR!(function: "ffi_estimate_percentiles", "Can't convert vector 'column_names', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'column_names', message: {}", e);
+            return PercentilesResult {
+                values: Vec::new(),
+                digest: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match estimate_percentiles(
+        &index_path,
+        &sentence,
+        &field_name,
+        &alived_ids,
+        query_with_filter,
+        query_with_id_range,
+        start_id,
+        end_id,
+        &column_names,
+    ) {
+        Ok(mut digest) => match digest_percentiles(&mut digest, &percents) {
+            Ok(values) => PercentilesResult {
+                values,
+                digest: digest.to_bytes(),
+                error_code: 0,
+                error_msg: String::new(),
+            },
+            Err(e) => {
+                ERROR!(function: "ffi_estimate_percentiles", "Error estimating percentiles: {}", e);
+                let error_msg_for_cxx: String = format!("Error estimating percentiles: {}", e);
+                PercentilesResult {
+                    values: Vec::new(),
+                    digest: Vec::new(),
+                    error_code: -1,
+                    error_msg: error_msg_for_cxx,
+                }
+            }
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_estimate_percentiles", "Error estimating percentiles: {}", e);
+            let error_msg_for_cxx: String = format!("Error estimating percentiles: {}", e);
+            return PercentilesResult {
+                values: Vec::new(),
+                digest: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_merge_percentiles_digests(
+    left: &CxxVector<u8>,
+    right: &CxxVector<u8>,
+    percents: &CxxVector<f64>,
+) -> PercentilesResult {
+    let left: Vec<u8> = match cxx_vector_converter::<u8>().convert(left) {
+        Ok(digest) => digest,
+        Err(e) => {
+            ERROR!(function: "ffi_merge_percentiles_digests", "Can't convert vector 'left', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert vector 'left', message: {}", e);
+            return PercentilesResult {
+                values: Vec::new(),
+                digest: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let right: Vec<u8> = match cxx_vector_converter::<u8>().convert(right) {
+        Ok(digest) => digest,
+        Err(e) => {
+            ERROR!(function: "ffi_merge_percentiles_digests", "Can't convert vector 'right', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert vector 'right', message: {}", e);
+            return PercentilesResult {
+                values: Vec::new(),
+                digest: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let percents: Vec<f64> = match cxx_vector_converter::<f64>().convert(percents) {
+        Ok(percents) => percents,
+        Err(e) => {
+            ERROR!(function: "ffi_merge_percentiles_digests", "Can't convert vector 'percents', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'percents', message: {}", e);
+            return PercentilesResult {
+                values: Vec::new(),
+                digest: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match merge_percentiles_digests(&left, &right) {
+        Ok(mut digest) => match digest_percentiles(&mut digest, &percents) {
+            Ok(values) => PercentilesResult {
+                values,
+                digest: digest.to_bytes(),
+                error_code: 0,
+                error_msg: String::new(),
+            },
+            Err(e) => {
+                ERROR!(function: "ffi_merge_percentiles_digests", "Error estimating percentiles: {}", e);
+                let error_msg_for_cxx: String = format!("Error estimating percentiles: {}", e);
+                PercentilesResult {
+                    values: Vec::new(),
+                    digest: Vec::new(),
+                    error_code: -1,
+                    error_msg: error_msg_for_cxx,
+                }
+            }
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_merge_percentiles_digests", "Error merging percentiles digests: {}", e);
+            let error_msg_for_cxx: String = format!("Error merging percentiles digests: {}", e);
+            return PercentilesResult {
+                values: Vec::new(),
+                digest: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_pin_searcher(index_path: &CxxString) -> U64Result {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_pin_searcher", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return U64Result {
+                result: 0,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match pin_searcher(&index_path) {
+        Ok(generation) => U64Result {
+            result: generation,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_pin_searcher", "Error pinning searcher: {}", e);
+            let error_msg_for_cxx: String = format!("Error pinning searcher: {}", e);
+            return U64Result {
+                result: 0,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_unpin_searcher(index_path: &CxxString, generation: u64) -> BoolResult {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_unpin_searcher", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match unpin_searcher(&index_path, generation) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_unpin_searcher", "Error unpinning searcher: {}", e);
+            let error_msg_for_cxx: String = format!("Error unpinning searcher: {}", e);
+            return BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    }
+}
+
+pub fn ffi_acquire_searcher_handle(index_path: &CxxString) -> U64Result {
+    let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_acquire_searcher_handle", "Can't convert 'index_path', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'index_path', message: {}", e);
+            return U64Result {
+                result: 0,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match acquire_searcher_handle(&index_path) {
+        Ok(handle) => U64Result {
+            result: handle,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_acquire_searcher_handle", "Error acquiring searcher handle: {}", e);
+            let error_msg_for_cxx: String = format!("Error acquiring searcher handle: {}", e);
+            U64Result {
+                result: 0,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_release_searcher_handle(handle: u64) -> BoolResult {
+    match release_searcher_handle(handle) {
+        Ok(status) => BoolResult {
+            result: status,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_release_searcher_handle", "Error releasing searcher handle: {}", e);
+            let error_msg_for_cxx: String = format!("Error releasing searcher handle: {}", e);
+            BoolResult {
+                result: false,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_searcher_handle_num_docs(handle: u64) -> U64Result {
+    match searcher_handle_num_docs(handle) {
+        Ok(num_docs) => U64Result {
+            result: num_docs,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_searcher_handle_num_docs", "Error counting docs: {}", e);
+            let error_msg_for_cxx: String = format!("Error counting docs: {}", e);
+            U64Result {
+                result: 0,
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_bm25_search_with_handle(
+    handle: u64,
+    sentence: &CxxString,
+    topk: u32,
+    offset: u32,
+    has_search_after: bool,
+    after_score: f32,
+    after_row_id: u64,
+    alived_ids: &CxxVector<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    column_names: &CxxVector<CxxString>,
+) -> BM25Result {
+    let sentence: String = match CXX_STRING_CONERTER.convert(sentence) {
+        Ok(q) => q,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_with_handle", "Can't convert 'sentence', message: {}", e);
+            let error_msg_for_cxx: String = format!("Can't convert 'sentence', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let alived_ids: Vec<u64> = match cxx_vector_converter::<u64>().convert(alived_ids) {
+        Ok(bitmap) => bitmap,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_with_handle", "Can't convert vector 'alived_ids', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'alived_ids', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let column_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(column_names) {
+        Ok(names) => names,
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_with_handle", "Can't convert vector 'column_names', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'column_names', message: {}", e);
+            return BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let search_after: Option<(f32, u64)> = match has_search_after {
+        true => Some((after_score, after_row_id)),
+        false => None,
+    };
+
+    match bm25_search_with_handle(
+        handle,
+        &sentence,
+        topk,
+        offset,
+        search_after,
+        &alived_ids,
+        query_with_filter,
+        query_with_id_range,
+        start_id,
+        end_id,
+        false,
+        &column_names,
+    ) {
+        Ok(results) => BM25Result {
+            result: results,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_bm25_search_with_handle", "Error performing BM25 search with statistics: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Error performing BM25 search with statistics: {}", e);
+            BM25Result {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
+pub fn ffi_get_stored_fields_with_handle(
+    handle: u64,
+    row_ids: &CxxVector<u64>,
+    field_names: &CxxVector<CxxString>,
+) -> StoredDocsResult {
+    let row_ids: Vec<u64> = match cxx_vector_converter::<u64>().convert(row_ids) {
+        Ok(ids) => ids,
+        Err(e) => {
+            ERROR!(function: "ffi_get_stored_fields_with_handle", "Can't convert vector 'row_ids', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'row_ids', message: {}", e);
+            return StoredDocsResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    let field_names: Vec<String> = match CXX_VECTOR_STRING_CONERTER.convert(field_names) {
+        Ok(names) => names,
+        Err(e) => {
+            ERROR!(function: "ffi_get_stored_fields_with_handle", "Can't convert vector 'field_names', message: {}", e);
+            let error_msg_for_cxx: String =
+                format!("Can't convert vector 'field_names', message: {}", e);
+            return StoredDocsResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            };
+        }
+    };
+
+    match get_stored_fields_with_handle(handle, &row_ids, &field_names) {
+        Ok(results) => StoredDocsResult {
+            result: results,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_get_stored_fields_with_handle", "Error reading stored fields: {}", e);
+            let error_msg_for_cxx: String = format!("Error reading stored fields: {}", e);
+            StoredDocsResult {
+                result: Vec::new(),
+                error_code: -1,
+                error_msg: error_msg_for_cxx,
+            }
+        }
+    }
+}
+
 pub fn ffi_index_reader_reload(index_path: &CxxString) -> BoolResult {
     let index_path: String = match CXX_STRING_CONERTER.convert(index_path) {
         Ok(path) => path,
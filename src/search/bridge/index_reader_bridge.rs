@@ -1,11 +1,46 @@
+use super::index_reader_percolator::Percolator;
+use super::index_reader_query_cache::QueryResultCache;
+use super::index_reader_row_id_mapping::RowIdMappingCache;
+use super::index_reader_security_filter::{PrincipalFilter, SearchPrincipal, SecurityFilters};
+use super::index_reader_vector_cache::VectorCache;
 use crate::logger::logger_bridge::TantivySearchLogger;
+use crate::search::implements::strategy::similarity_query::Similarity;
+use crate::utils::time_utils::TimeUtils;
 use crate::{common::constants::LOG_CALLBACK, INFO};
-use tantivy::{Index, IndexReader};
+use roaring::RoaringTreemap;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use tantivy::tokenizer::TokenizerManager;
+use tantivy::{Index, IndexReader, Searcher};
 
 pub struct IndexReaderBridge {
     pub path: String,
     pub index: Index,
     pub reader: IndexReader,
+    // Row ids deleted by host (lightweight delete), excluded from all search results.
+    pub deleted_row_ids: RwLock<Option<Arc<RoaringTreemap>>>,
+    // Searchers pinned by generation id, with their pin reference count.
+    pub pinned_searchers: Mutex<HashMap<u64, (Searcher, usize)>>,
+    // DocId and row_id mapping warmed by `reader`, `None` if it's not registered as warmer.
+    pub row_id_mapping: Option<Arc<RowIdMappingCache>>,
+    // Vectors and HNSW graphs warmed by `reader`, `None` if index has no vector column.
+    pub vector_cache: Option<Arc<VectorCache>>,
+    // Queries registered for reverse search, `None` if they aren't loaded.
+    pub percolator: Option<Arc<Percolator>>,
+    // Analyzers of indexed text, `index` only registers query analyzers.
+    pub index_tokenizers: TokenizerManager,
+    // Queries slower than it are logged, 0 disables slow query log.
+    pub slow_query_threshold_millis: AtomicU64,
+    // Similarity of ranked search, `None` uses tantivy BM25.
+    pub similarity: RwLock<Option<Similarity>>,
+    // Bitmap query results persisted next to index, `None` if it's disabled.
+    pub query_result_cache: RwLock<Option<Arc<QueryResultCache>>>,
+    // Unix timestamp in millis of last access through reader cache.
+    pub last_access_millis: AtomicU64,
+    // Row level security filters ANDed into searches of their principal.
+    pub security_filters: Arc<SecurityFilters>,
 }
 
 impl Drop for IndexReaderBridge {
@@ -18,6 +53,97 @@ impl Drop for IndexReaderBridge {
 }
 
 impl IndexReaderBridge {
+    pub fn new(path: String, index: Index, reader: IndexReader) -> Self {
+        let index_tokenizers: TokenizerManager = index.tokenizers().clone();
+        IndexReaderBridge {
+            path,
+            index,
+            reader,
+            deleted_row_ids: RwLock::new(None),
+            pinned_searchers: Mutex::new(HashMap::new()),
+            row_id_mapping: None,
+            vector_cache: None,
+            percolator: None,
+            index_tokenizers,
+            slow_query_threshold_millis: AtomicU64::new(0),
+            similarity: RwLock::new(None),
+            query_result_cache: RwLock::new(None),
+            last_access_millis: AtomicU64::new(TimeUtils::now_millis()),
+            security_filters: Arc::new(SecurityFilters::new()),
+        }
+    }
+
+    // Record an access of this reader.
+    pub fn touch(&self) {
+        self.last_access_millis
+            .store(TimeUtils::now_millis(), Ordering::Relaxed);
+    }
+
+    // `row_id_mapping` should be registered as a warmer of `reader`.
+    pub fn with_row_id_mapping(mut self, row_id_mapping: Arc<RowIdMappingCache>) -> Self {
+        self.row_id_mapping = Some(row_id_mapping);
+        self
+    }
+
+    // `vector_cache` should be registered as a warmer of `reader`.
+    pub fn with_vector_cache(mut self, vector_cache: Arc<VectorCache>) -> Self {
+        self.vector_cache = Some(vector_cache);
+        self
+    }
+
+    pub fn with_percolator(mut self, percolator: Arc<Percolator>) -> Self {
+        self.percolator = Some(percolator);
+        self
+    }
+
+    pub fn with_security_filters(mut self, security_filters: Arc<SecurityFilters>) -> Self {
+        self.security_filters = security_filters;
+        self
+    }
+
+    pub fn with_index_tokenizers(mut self, index_tokenizers: TokenizerManager) -> Self {
+        self.index_tokenizers = index_tokenizers;
+        self
+    }
+
+    // Bytes held by DocId and row_id mapping cache.
+    pub fn row_id_mapping_bytes(&self) -> u64 {
+        match &self.row_id_mapping {
+            Some(row_id_mapping) => row_id_mapping.memory_usage(),
+            None => 0,
+        }
+    }
+    // Update slow query threshold at runtime, 0 disables slow query log.
+    pub fn set_slow_query_threshold(&self, threshold_millis: u64) {
+        self.slow_query_threshold_millis
+            .store(threshold_millis, Ordering::Relaxed);
+    }
+
+    pub fn slow_query_threshold(&self) -> Option<Duration> {
+        match self.slow_query_threshold_millis.load(Ordering::Relaxed) {
+            0 => None,
+            threshold_millis => Some(Duration::from_millis(threshold_millis)),
+        }
+    }
+
+    // Update similarity of ranked search at runtime.
+    pub fn set_similarity(&self, similarity: Similarity) {
+        *self.similarity.write().unwrap() = Some(similarity);
+    }
+
+    pub fn similarity(&self) -> Option<Similarity> {
+        *self.similarity.read().unwrap()
+    }
+
+    // Replace persisted query result cache at runtime, `None` disables it and keeps its entries.
+    pub fn set_query_result_cache(&self, query_result_cache: Option<Arc<QueryResultCache>>) {
+        *self.query_result_cache.write().unwrap() = query_result_cache;
+    }
+
+    pub fn query_result_cache(&self) -> Option<Arc<QueryResultCache>> {
+        self.query_result_cache.read().unwrap().clone()
+    }
+
     #[allow(dead_code)]
     pub fn reader_address(&self) -> usize {
         &self.reader as *const IndexReader as usize
@@ -25,6 +151,133 @@ impl IndexReaderBridge {
     pub fn reload(&self) -> Result<(), String> {
         self.reader.reload().map_err(|e| e.to_string())
     }
+    // Generation id of current searcher, it will be changed after reader reload.
+    pub fn generation(&self) -> u64 {
+        self.reader.searcher().generation().generation_id()
+    }
+
+    // Pin current searcher, it can be used by later queries even after reader reloaded.
+    pub fn pin_searcher(&self) -> Result<u64, String> {
+        let searcher = self.reader.searcher();
+        let generation = searcher.generation().generation_id();
+        let mut pinned_searchers = self
+            .pinned_searchers
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        pinned_searchers
+            .entry(generation)
+            .or_insert((searcher, 0))
+            .1 += 1;
+        Ok(generation)
+    }
+
+    // Unpin a searcher, it will be released after all pins are removed.
+    pub fn unpin_searcher(&self, generation: u64) -> Result<bool, String> {
+        let mut pinned_searchers = self
+            .pinned_searchers
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        match pinned_searchers.get_mut(&generation) {
+            Some((_, pin_count)) => {
+                *pin_count -= 1;
+                if *pin_count == 0 {
+                    pinned_searchers.remove(&generation);
+                }
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    // Whether any searcher is pinned, pinned searchers are still used by host.
+    pub fn has_pinned_searchers(&self) -> bool {
+        match self.pinned_searchers.lock() {
+            Ok(pinned_searchers) => !pinned_searchers.is_empty(),
+            Err(_) => true,
+        }
+    }
+
+    // Get searcher of given generation, `None` means the latest searcher.
+    pub fn searcher_of(&self, generation: Option<u64>) -> Result<Searcher, String> {
+        let searcher = self.reader.searcher();
+        let generation = match generation {
+            Some(generation) if generation != searcher.generation().generation_id() => generation,
+            _ => return Ok(searcher),
+        };
+        let pinned_searchers = self
+            .pinned_searchers
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        match pinned_searchers.get(&generation) {
+            Some((searcher, _)) => Ok(searcher.clone()),
+            None => Err(format!(
+                "Searcher generation {} is not pinned, index_path:[{}]",
+                generation, self.path
+            )),
+        }
+    }
+
+    // Snapshot of lightweight deleted row ids, `None` means nothing deleted.
+    pub fn deleted_row_ids(&self) -> Option<Arc<RoaringTreemap>> {
+        match self.deleted_row_ids.read() {
+            Ok(deleted_row_ids) => deleted_row_ids.clone(),
+            Err(_) => None,
+        }
+    }
+
+    // Security filter of the principal bound to current thread, `None` if no principal is bound.
+    pub fn principal_filter(&self) -> Option<PrincipalFilter> {
+        SearchPrincipal::current().map(|principal| PrincipalFilter {
+            filters: self.security_filters.clone(),
+            principal,
+        })
+    }
+
+    // Row ids excluded from searches of current thread on `searcher`, lightweight deleted
+    // ones and ones hidden from its principal, `None` means nothing excluded.
+    pub fn excluded_row_ids(
+        &self,
+        searcher: &Searcher,
+    ) -> Result<Option<Arc<RoaringTreemap>>, String> {
+        let deleted_row_ids: Option<Arc<RoaringTreemap>> = self.deleted_row_ids();
+        match self.principal_filter() {
+            Some(principal_filter) => principal_filter
+                .excluded_row_ids(searcher, deleted_row_ids.as_ref())
+                .map(Some),
+            None => Ok(deleted_row_ids),
+        }
+    }
+
+    // Replace lightweight deleted row ids, an empty bitmap clears them.
+    pub fn set_deleted_row_ids(&self, row_ids: RoaringTreemap) -> Result<(), String> {
+        let mut deleted_row_ids = self
+            .deleted_row_ids
+            .write()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        *deleted_row_ids = if row_ids.is_empty() {
+            None
+        } else {
+            Some(Arc::new(row_ids))
+        };
+        Ok(())
+    }
+
+    // Add row ids into lightweight deleted row ids, searches in flight keep their own snapshot.
+    pub fn add_deleted_row_ids(&self, row_ids: &[u64]) -> Result<(), String> {
+        let mut deleted_row_ids = self
+            .deleted_row_ids
+            .write()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        let mut updated: RoaringTreemap = match deleted_row_ids.as_ref() {
+            Some(current) => current.as_ref().clone(),
+            None => RoaringTreemap::new(),
+        };
+        updated.extend(row_ids.iter().copied());
+        if !updated.is_empty() {
+            *deleted_row_ids = Some(Arc::new(updated));
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -82,11 +335,11 @@ mod tests {
             .expect("Can't set reload policy");
 
         (
-            IndexReaderBridge {
-                path: index_directory_str.to_string(),
-                index: index.clone(),
-                reader: reader.clone(),
-            },
+            IndexReaderBridge::new(
+                index_directory_str.to_string(),
+                index.clone(),
+                reader.clone(),
+            ),
             writer,
         )
     }
@@ -169,4 +422,56 @@ mod tests {
             .expect("Can't execute search.");
         assert_eq!(searched_bitmap_3.len(), 1);
     }
+
+    #[test]
+    fn test_pin_searcher() {
+        let temp_directory = TempDir::new().expect("Can't create temp directory");
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let (index_reader_bridge, mut index_writer) =
+            index_some_docs_in_temp_directory(temp_directory_str);
+
+        let text_field = index_reader_bridge
+            .index
+            .schema()
+            .get_field("text")
+            .unwrap();
+        let row_id_field = index_reader_bridge
+            .index
+            .schema()
+            .get_field("row_id")
+            .unwrap();
+        let query_parser = QueryParser::for_index(&index_reader_bridge.index, vec![text_field]);
+        let text_query = query_parser
+            .parse_query("Ancient")
+            .expect("Can't parse query");
+        let row_id_collector = RowIdRoaringCollector::with_field("row_id".to_string());
+
+        let generation = index_reader_bridge.pin_searcher().unwrap();
+
+        // Delete a row and reload reader.
+        index_writer.delete_term(Term::from_field_u64(row_id_field, 0));
+        assert!(index_writer.commit().is_ok());
+        assert!(index_reader_bridge.reload().is_ok());
+        assert_ne!(index_reader_bridge.generation(), generation);
+
+        // Latest searcher can't see the deleted row.
+        let latest_bitmap = index_reader_bridge
+            .searcher_of(None)
+            .unwrap()
+            .search(&text_query, &row_id_collector)
+            .expect("Can't execute search.");
+        assert_eq!(latest_bitmap.len(), 1);
+
+        // Pinned searcher still serves the old generation.
+        let pinned_bitmap = index_reader_bridge
+            .searcher_of(Some(generation))
+            .unwrap()
+            .search(&text_query, &row_id_collector)
+            .expect("Can't execute search.");
+        assert_eq!(pinned_bitmap.len(), 2);
+
+        assert!(index_reader_bridge.unpin_searcher(generation).unwrap());
+        assert!(!index_reader_bridge.unpin_searcher(generation).unwrap());
+        assert!(index_reader_bridge.searcher_of(Some(generation)).is_err());
+    }
 }
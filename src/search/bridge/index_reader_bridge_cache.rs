@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use crate::common::constants::CANONICAL_INDEX_PATHS;
 use crate::logger::logger_bridge::TantivySearchLogger;
 use crate::{common::constants::LOG_CALLBACK, DEBUG, WARNING};
 use flurry::HashMap;
@@ -27,43 +28,62 @@ impl IndexReaderBridgeCache {
         value: Arc<IndexReaderBridge>,
     ) -> Result<(), String> {
         let pinned = self.cache.pin();
-        let trimmed_key: String = key.trim_end_matches('/').to_string();
-        if pinned.contains_key(&trimmed_key) {
-            pinned.insert(trimmed_key.clone(), value.clone());
+        let canonical_key: String = CANONICAL_INDEX_PATHS.register(&key);
+        if pinned.contains_key(&canonical_key) {
+            pinned.insert(canonical_key.clone(), value.clone());
             WARNING!(
                 "{}",
                 format!(
                     "IndexReaderBridge already exists with given key: [{}], it has been overwritten.",
-                    trimmed_key
+                    canonical_key
                 )
             )
         } else {
-            pinned.insert(trimmed_key, value.clone());
+            pinned.insert(canonical_key, value.clone());
         }
         Ok(())
     }
 
     pub fn get_index_reader_bridge(&self, key: String) -> Result<Arc<IndexReaderBridge>, String> {
         let pinned = self.cache.pin();
-        let trimmed_key: String = key.trim_end_matches('/').to_string();
-        match pinned.get(&trimmed_key) {
-            Some(result) => Ok(result.clone()),
+        let canonical_key: String = CANONICAL_INDEX_PATHS.canonical_of(&key);
+        match pinned.get(&canonical_key) {
+            Some(result) => {
+                result.touch();
+                Ok(result.clone())
+            }
             None => Err(format!(
                 "IndexReaderBridge doesn't exist with given key: [{}]",
-                trimmed_key
+                canonical_key
             )),
         }
     }
 
+    // Number of cached bridges.
+    pub fn bridge_count(&self) -> usize {
+        self.cache.pin().len()
+    }
+
+    // Snapshot of all cached index readers with their keys.
+    pub fn all_index_reader_bridges(&self) -> Vec<(String, Arc<IndexReaderBridge>)> {
+        self.cache
+            .pin()
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+
     pub fn remove_index_reader_bridge(&self, key: String) -> Result<(), String> {
         let pinned = self.cache.pin();
-        let trimmed_key: String = key.trim_end_matches('/').to_string();
-        if pinned.contains_key(&trimmed_key) {
-            pinned.remove(&trimmed_key);
+        let canonical_key: String = CANONICAL_INDEX_PATHS.canonical_of(&key);
+        if pinned.contains_key(&canonical_key) {
+            pinned.remove(&canonical_key);
+            // Memoized resolution is dropped with the index, the other cache resolves it again.
+            CANONICAL_INDEX_PATHS.forget(&canonical_key);
         } else {
             let message: String = format!(
                 "IndexReaderBridge doesn't exist, can't remove it with given key [{}]",
-                trimmed_key
+                canonical_key
             );
             DEBUG!("{}", message);
             return Err(message);
@@ -135,11 +155,11 @@ mod tests {
             let result = writer.add_document(doc);
             assert!(result.is_ok());
         }
-        IndexReaderBridge {
-            path: index_directory_str.to_string(),
-            index: index.clone(),
-            reader: index.reader().expect("Can't get reader from index"),
-        }
+        IndexReaderBridge::new(
+            index_directory_str.to_string(),
+            index.clone(),
+            index.reader().expect("Can't get reader from index"),
+        )
     }
 
     #[test]
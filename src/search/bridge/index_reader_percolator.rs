@@ -0,0 +1,261 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use tantivy::collector::Count;
+use tantivy::query::{Query, QueryParser};
+use tantivy::schema::{Field, FieldType, Schema};
+use tantivy::tokenizer::TokenizerManager;
+use tantivy::{Index, IndexWriter, Searcher, TantivyDocument};
+
+use crate::common::constants::PERCOLATOR_QUERIES_FILE_NAME;
+use crate::logger::logger_bridge::TantivySearchLogger;
+use crate::tokenizer::sparse_vector_format::SPARSE_VECTOR_TOKENIZER;
+use crate::{common::constants::LOG_CALLBACK, WARNING};
+
+// Memory budget of the single doc index built by each percolation, tantivy minimum.
+const PERCOLATE_WRITER_MEMORY_BUDGET: usize = 15_000_000;
+
+struct StoredQuery {
+    sentence: String,
+    query: Box<dyn Query>,
+}
+
+/// Queries registered for reverse search of an index, persisted in the index directory.
+/// A percolated doc is indexed alone in RAM with the index analyzers, and every stored query
+/// is run against it, so docs are matched exactly as if they were indexed.
+pub struct Percolator {
+    file_path: PathBuf,
+    schema: Schema,
+    // Analyzers of indexed text, reader index only registers query analyzers.
+    index_tokenizers: TokenizerManager,
+    query_parser: QueryParser,
+    queries: RwLock<BTreeMap<u64, StoredQuery>>,
+}
+
+impl Percolator {
+    /// Load queries stored in `index_directory`, queries can't be parsed anymore are skipped.
+    pub fn load(
+        index_directory: &Path,
+        schema: Schema,
+        index_tokenizers: TokenizerManager,
+        query_parser: QueryParser,
+    ) -> Result<Self, String> {
+        let file_path: PathBuf = index_directory.join(PERCOLATOR_QUERIES_FILE_NAME);
+        let sentences: BTreeMap<u64, String> = match file_path.exists() {
+            true => {
+                let contents: String = fs::read_to_string(&file_path)
+                    .map_err(|e| format!("file: {:?}, message: {}", file_path, e))?;
+                serde_json::from_str(&contents)
+                    .map_err(|e| format!("file: {:?}, message: {}", file_path, e))?
+            }
+            false => BTreeMap::new(),
+        };
+        let mut queries: BTreeMap<u64, StoredQuery> = BTreeMap::new();
+        for (query_id, sentence) in sentences {
+            match query_parser.parse_query(&sentence) {
+                Ok(query) => {
+                    queries.insert(query_id, StoredQuery { sentence, query });
+                }
+                Err(e) => {
+                    WARNING!(function: "Percolator", "Skip stored query {}: {}. {}", query_id, sentence, e);
+                }
+            }
+        }
+        Ok(Percolator {
+            file_path,
+            schema,
+            index_tokenizers,
+            query_parser,
+            queries: RwLock::new(queries),
+        })
+    }
+
+    /// Store `sentence` as query `query_id`, a query with the same id is replaced.
+    pub fn register(&self, query_id: u64, sentence: &str) -> Result<(), String> {
+        let query: Box<dyn Query> = self
+            .query_parser
+            .parse_query(sentence)
+            .map_err(|e| format!("Error when parse: {}. {}", sentence, e))?;
+        let mut queries = self
+            .queries
+            .write()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        let previous: Option<StoredQuery> = queries.insert(
+            query_id,
+            StoredQuery {
+                sentence: sentence.to_string(),
+                query,
+            },
+        );
+        if let Err(e) = self.save(&queries) {
+            // Keep memory consistent with the file.
+            match previous {
+                Some(previous) => queries.insert(query_id, previous),
+                None => queries.remove(&query_id),
+            };
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Remove query `query_id`, `false` if it isn't stored.
+    pub fn unregister(&self, query_id: u64) -> Result<bool, String> {
+        let mut queries = self
+            .queries
+            .write()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        let removed: StoredQuery = match queries.remove(&query_id) {
+            Some(removed) => removed,
+            None => return Ok(false),
+        };
+        if let Err(e) = self.save(&queries) {
+            queries.insert(query_id, removed);
+            return Err(e);
+        }
+        Ok(true)
+    }
+
+    pub fn num_queries(&self) -> usize {
+        match self.queries.read() {
+            Ok(queries) => queries.len(),
+            Err(_) => 0,
+        }
+    }
+
+    /// Ids of stored queries matching a doc of `column_names` and `column_docs`, ascending.
+    /// Text and numeric columns are matched, other columns can't be queried by sentences.
+    pub fn percolate(
+        &self,
+        column_names: &[String],
+        column_docs: &[String],
+    ) -> Result<Vec<u64>, String> {
+        if column_names.len() != column_docs.len() {
+            return Err(format!(
+                "column_names size {} doesn't match column_docs size {}",
+                column_names.len(),
+                column_docs.len()
+            ));
+        }
+        let mut document: TantivyDocument = TantivyDocument::default();
+        for (column_name, column_doc) in column_names.iter().zip(column_docs) {
+            let field: Field = self
+                .schema
+                .get_field(column_name)
+                .map_err(|e| e.to_string())?;
+            match self.schema.get_field_entry(field).field_type() {
+                FieldType::Str(text_options) => match text_options.get_indexing_options() {
+                    Some(indexing) if indexing.tokenizer() != SPARSE_VECTOR_TOKENIZER => {
+                        document.add_text(field, column_doc)
+                    }
+                    _ => {}
+                },
+                FieldType::I64(_) => document.add_i64(
+                    field,
+                    column_doc
+                        .parse::<i64>()
+                        .map_err(|e| format!("Can't parse `{}` as i64: {}", column_doc, e))?,
+                ),
+                FieldType::F64(_) => document.add_f64(
+                    field,
+                    column_doc
+                        .parse::<f64>()
+                        .map_err(|e| format!("Can't parse `{}` as f64: {}", column_doc, e))?,
+                ),
+                _ => {}
+            }
+        }
+
+        let mut index: Index = Index::create_in_ram(self.schema.clone());
+        index.set_tokenizers(self.index_tokenizers.clone());
+        let mut writer: IndexWriter = index
+            .writer_with_num_threads(1, PERCOLATE_WRITER_MEMORY_BUDGET)
+            .map_err(|e| e.to_string())?;
+        writer.add_document(document).map_err(|e| e.to_string())?;
+        writer.commit().map_err(|e| e.to_string())?;
+        let searcher: Searcher = index.reader().map_err(|e| e.to_string())?.searcher();
+
+        let queries = self
+            .queries
+            .read()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        let mut query_ids: Vec<u64> = Vec::new();
+        for (query_id, stored_query) in queries.iter() {
+            if searcher
+                .search(stored_query.query.as_ref(), &Count)
+                .map_err(|e| e.to_string())?
+                > 0
+            {
+                query_ids.push(*query_id);
+            }
+        }
+        Ok(query_ids)
+    }
+
+    // Write sentences to a temporary file and rename it, so the file is never half written.
+    fn save(&self, queries: &BTreeMap<u64, StoredQuery>) -> Result<(), String> {
+        let sentences: BTreeMap<u64, &String> = queries
+            .iter()
+            .map(|(query_id, stored_query)| (*query_id, &stored_query.sentence))
+            .collect();
+        let contents: String = serde_json::to_string(&sentences).map_err(|e| e.to_string())?;
+        let temp_path: PathBuf = self.file_path.with_extension("json.tmp");
+        fs::write(&temp_path, contents)
+            .map_err(|e| format!("file: {:?}, message: {}", temp_path, e))?;
+        fs::rename(&temp_path, &self.file_path)
+            .map_err(|e| format!("file: {:?}, message: {}", self.file_path, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tantivy::schema::{FAST, INDEXED, TEXT};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_percolator() {
+        let temp_directory = TempDir::new().unwrap();
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        schema_builder.add_i64_field("price", FAST | INDEXED);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema.clone());
+        let load = || {
+            Percolator::load(
+                temp_directory.path(),
+                schema.clone(),
+                index.tokenizers().clone(),
+                QueryParser::for_index(&index, vec![title]),
+            )
+            .unwrap()
+        };
+        let percolator = load();
+        assert!(percolator.register(1, "red AND apple").is_ok());
+        assert!(percolator.register(2, "banana").is_ok());
+        assert!(percolator.register(3, "price:[10 TO 20]").is_ok());
+        assert!(percolator.register(4, "title:(").is_err());
+        assert_eq!(percolator.num_queries(), 3);
+
+        let column_names = vec!["title".to_string(), "price".to_string()];
+        let percolate = |percolator: &Percolator, title: &str, price: &str| {
+            percolator
+                .percolate(&column_names, &[title.to_string(), price.to_string()])
+                .unwrap()
+        };
+        assert_eq!(percolate(&percolator, "Red Apple pie", "15"), vec![1, 3]);
+        assert_eq!(percolate(&percolator, "red banana", "30"), vec![2]);
+        assert!(percolator
+            .percolate(&column_names, &["red".to_string(), "cheap".to_string()])
+            .is_err());
+
+        // Queries are persisted.
+        assert!(percolator.unregister(2).unwrap());
+        assert!(!percolator.unregister(2).unwrap());
+        assert!(percolator.register(1, "green").is_ok());
+        let reloaded = load();
+        assert_eq!(reloaded.num_queries(), 2);
+        assert_eq!(percolate(&reloaded, "green apple", "15"), vec![1, 3]);
+    }
+}
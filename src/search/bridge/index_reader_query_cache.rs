@@ -0,0 +1,274 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use roaring::RoaringBitmap;
+use tantivy::{Opstamp, Searcher, SegmentId};
+
+use crate::common::constants::QUERY_RESULT_CACHE_DIR_NAME;
+use crate::search::implements::strategy::query_key::QueryKey;
+
+// Leading bytes of cache entries, version is bumped when entry layout changes.
+static ENTRY_MAGIC: &[u8; 4] = b"TSQC";
+static ENTRY_VERSION: u8 = 1;
+static ENTRY_EXTENSION: &str = "bitmap";
+
+/// Bitmap query results persisted in a directory next to the index, they survive process
+/// restarts. Entries are keyed by segments and delete opstamps searched and the canonical query,
+/// entries of other segments are removed once results of new segments are written.
+/// Oldest written entries are evicted when entries exceed `capacity_bytes`.
+pub struct QueryResultCache {
+    directory: PathBuf,
+    capacity_bytes: u64,
+    // Writes and evictions of this process don't interleave.
+    write_lock: Mutex<()>,
+}
+
+struct CacheEntry {
+    path: PathBuf,
+    bytes: u64,
+    modified: SystemTime,
+}
+
+impl QueryResultCache {
+    pub fn open(index_path: &str, capacity_bytes: u64) -> Result<Self, String> {
+        let directory: PathBuf = Path::new(index_path).join(QUERY_RESULT_CACHE_DIR_NAME);
+        fs::create_dir_all(&directory).map_err(|e| {
+            format!(
+                "Failed to create query result cache directory {:?}: {}",
+                directory, e
+            )
+        })?;
+        Ok(QueryResultCache {
+            directory,
+            capacity_bytes,
+            write_lock: Mutex::new(()),
+        })
+    }
+
+    pub fn capacity_bytes(&self) -> u64 {
+        self.capacity_bytes
+    }
+
+    /// Cached result of `query_key` over segments of `searcher`, `None` if it isn't cached.
+    pub fn get(&self, searcher: &Searcher, query_key: &QueryKey) -> Option<RoaringBitmap> {
+        let segments: Vec<u8> = segments_key(searcher);
+        let query: Vec<u8> = query_key.to_bytes();
+        let path: PathBuf = self.entry_path(&segments, &query);
+        let content: Vec<u8> = fs::read(&path).ok()?;
+        let header: Vec<u8> = entry_header(&segments, &query);
+        // Hash collisions of entry names are told apart by the whole key in header.
+        if content.len() < header.len() || content[..header.len()] != header[..] {
+            return None;
+        }
+        match RoaringBitmap::deserialize_from(&content[header.len()..]) {
+            Ok(bitmap) => Some(bitmap),
+            Err(_) => {
+                // Corrupted entry is removed, the query is executed again.
+                let _ = fs::remove_file(&path);
+                None
+            }
+        }
+    }
+
+    /// Persist result of `query_key` over segments of `searcher`.
+    pub fn put(
+        &self,
+        searcher: &Searcher,
+        query_key: &QueryKey,
+        bitmap: &RoaringBitmap,
+    ) -> Result<(), String> {
+        let segments: Vec<u8> = segments_key(searcher);
+        let query: Vec<u8> = query_key.to_bytes();
+        let mut content: Vec<u8> = entry_header(&segments, &query);
+        bitmap
+            .serialize_into(&mut content)
+            .map_err(|e| format!("Failed to serialize query result: {}", e))?;
+        if content.len() as u64 > self.capacity_bytes {
+            return Ok(());
+        }
+
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let segments_prefix: String = format!("{:016x}-", fnv1a(&segments));
+        let mut entries: Vec<CacheEntry> = Vec::new();
+        for entry in self.entries()? {
+            let file_name = entry.path.file_name().and_then(|name| name.to_str());
+            if file_name.map_or(false, |name| name.starts_with(&segments_prefix)) {
+                entries.push(entry);
+            } else {
+                // Segments have changed since it was written, nobody reads it anymore.
+                let _ = fs::remove_file(&entry.path);
+            }
+        }
+        entries.sort_by_key(|entry| entry.modified);
+        let mut used_bytes: u64 = entries.iter().map(|entry| entry.bytes).sum();
+        for entry in entries {
+            if used_bytes + content.len() as u64 <= self.capacity_bytes {
+                break;
+            }
+            if fs::remove_file(&entry.path).is_ok() {
+                used_bytes -= entry.bytes;
+            }
+        }
+
+        // Entry is written aside then renamed, readers never see a partial entry.
+        let path: PathBuf = self.entry_path(&segments, &query);
+        let temp_path: PathBuf = path.with_extension(format!("{}.tmp", std::process::id()));
+        let write = || -> std::io::Result<()> {
+            let mut file: File = File::create(&temp_path)?;
+            file.write_all(&content)?;
+            file.sync_data()?;
+            fs::rename(&temp_path, &path)
+        };
+        write().map_err(|e| {
+            let _ = fs::remove_file(&temp_path);
+            format!("Failed to write query result cache entry {:?}: {}", path, e)
+        })
+    }
+
+    /// Bytes of all cache entries.
+    pub fn used_bytes(&self) -> u64 {
+        self.entries()
+            .map_or(0, |entries| entries.iter().map(|entry| entry.bytes).sum())
+    }
+
+    fn entry_path(&self, segments: &[u8], query: &[u8]) -> PathBuf {
+        self.directory.join(format!(
+            "{:016x}-{:016x}.{}",
+            fnv1a(segments),
+            fnv1a(query),
+            ENTRY_EXTENSION
+        ))
+    }
+
+    fn entries(&self) -> Result<Vec<CacheEntry>, String> {
+        let read_dir = fs::read_dir(&self.directory).map_err(|e| {
+            format!(
+                "Failed to list query result cache directory {:?}: {}",
+                self.directory, e
+            )
+        })?;
+        Ok(read_dir
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                entry.path().extension().and_then(|ext| ext.to_str()) == Some(ENTRY_EXTENSION)
+            })
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                Some(CacheEntry {
+                    path: entry.path(),
+                    bytes: metadata.len(),
+                    modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                })
+            })
+            .collect())
+    }
+}
+
+// Segment ids are unique across indexes, delete opstamps change with deletes.
+fn segments_key(searcher: &Searcher) -> Vec<u8> {
+    let mut segments: Vec<(SegmentId, Option<Opstamp>)> = searcher
+        .segment_readers()
+        .iter()
+        .map(|segment_reader| (segment_reader.segment_id(), segment_reader.delete_opstamp()))
+        .collect();
+    segments.sort();
+    let mut bytes: Vec<u8> = Vec::with_capacity(segments.len() * 25);
+    for (segment_id, delete_opstamp) in segments {
+        bytes.extend_from_slice(segment_id.uuid_bytes());
+        match delete_opstamp {
+            Some(opstamp) => {
+                bytes.push(1);
+                bytes.extend_from_slice(&opstamp.to_le_bytes());
+            }
+            None => bytes.push(0),
+        }
+    }
+    bytes
+}
+
+fn entry_header(segments: &[u8], query: &[u8]) -> Vec<u8> {
+    let mut header: Vec<u8> = Vec::with_capacity(13 + segments.len() + query.len());
+    header.extend_from_slice(ENTRY_MAGIC);
+    header.push(ENTRY_VERSION);
+    header.extend_from_slice(&(segments.len() as u32).to_le_bytes());
+    header.extend_from_slice(segments);
+    header.extend_from_slice(&(query.len() as u32).to_le_bytes());
+    header.extend_from_slice(query);
+    header
+}
+
+// Entry names must be the same across processes, std hashers don't promise it.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::tests::index_3column_docs_without_threads_merge;
+    use tantivy::Term;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_query_result_cache() {
+        let temp_directory: TempDir = TempDir::new().unwrap();
+        let temp_directory_str: &str = temp_directory.path().to_str().unwrap();
+        let (mut index_writer, index_reader, _) =
+            index_3column_docs_without_threads_merge(temp_directory_str);
+        let searcher = index_reader.searcher();
+        let col1 = searcher.index().schema().get_field("col1").unwrap();
+        let query_key = |text: &str| QueryKey::term_set(vec![Term::from_field_text(col1, text)]);
+        let bitmap = RoaringBitmap::from_iter([1u32, 3, 5]);
+
+        let cache = QueryResultCache::open(temp_directory_str, 1024 * 1024).unwrap();
+        assert_eq!(cache.get(&searcher, &query_key("ancient")), None);
+        cache
+            .put(&searcher, &query_key("ancient"), &bitmap)
+            .unwrap();
+        assert_eq!(
+            cache.get(&searcher, &query_key("ancient")),
+            Some(bitmap.clone())
+        );
+        assert_eq!(cache.get(&searcher, &query_key("balance")), None);
+
+        // Entries are read by a cache opened later, like after process restart.
+        let reopened = QueryResultCache::open(temp_directory_str, 1024 * 1024).unwrap();
+        assert_eq!(
+            reopened.get(&searcher, &query_key("ancient")),
+            Some(bitmap.clone())
+        );
+
+        // Entries of old segments are invalidated once segments change.
+        let row_id = searcher.index().schema().get_field("row_id").unwrap();
+        index_writer.delete_term(Term::from_field_u64(row_id, 0));
+        index_writer.commit().unwrap();
+        index_reader.reload().unwrap();
+        let new_searcher = index_reader.searcher();
+        assert_eq!(reopened.get(&new_searcher, &query_key("ancient")), None);
+        reopened
+            .put(&new_searcher, &query_key("balance"), &bitmap)
+            .unwrap();
+        assert_eq!(reopened.get(&searcher, &query_key("ancient")), None);
+        assert_eq!(
+            reopened.get(&new_searcher, &query_key("balance")),
+            Some(bitmap.clone())
+        );
+
+        // Oldest entries are evicted beyond capacity.
+        let entry_bytes: u64 = reopened.used_bytes();
+        let small = QueryResultCache::open(temp_directory_str, entry_bytes * 3 / 2).unwrap();
+        small
+            .put(&new_searcher, &query_key("ancient"), &bitmap)
+            .unwrap();
+        assert!(small.used_bytes() <= entry_bytes * 3 / 2);
+        assert_eq!(
+            small.get(&new_searcher, &query_key("ancient")),
+            Some(bitmap)
+        );
+    }
+}
@@ -0,0 +1,222 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+
+use tantivy::columnar::Column;
+use tantivy::{DocAddress, DocId, Searcher, SearcherGeneration, SegmentId, SegmentReader, Warmer};
+
+/// Mapping between `DocId` and row_id of one segment, deleted docs are included.
+pub struct SegmentRowIdMapping {
+    // row_id of each doc, indexed by `DocId`.
+    row_ids: Vec<u64>,
+    // (row_id, doc_id) pairs sorted by row_id.
+    doc_ids: Vec<(u64, DocId)>,
+}
+
+impl SegmentRowIdMapping {
+    pub fn build(segment_reader: &SegmentReader) -> tantivy::Result<Self> {
+        let row_id_column = segment_reader
+            .fast_fields()
+            .u64("row_id")?
+            .first_or_default_col(0);
+        let row_ids: Vec<u64> = (0..segment_reader.max_doc())
+            .map(|doc_id| row_id_column.get_val(doc_id))
+            .collect();
+        let mut doc_ids: Vec<(u64, DocId)> = row_ids
+            .iter()
+            .enumerate()
+            .map(|(doc_id, row_id)| (*row_id, doc_id as DocId))
+            .collect();
+        doc_ids.sort_unstable();
+        Ok(SegmentRowIdMapping { row_ids, doc_ids })
+    }
+
+    #[inline]
+    pub fn row_id(&self, doc_id: DocId) -> u64 {
+        self.row_ids[doc_id as usize]
+    }
+
+    pub fn doc_id(&self, row_id: u64) -> Option<DocId> {
+        self.doc_ids
+            .binary_search_by_key(&row_id, |(row_id, _)| *row_id)
+            .ok()
+            .map(|idx| self.doc_ids[idx].1)
+    }
+
+    pub fn memory_usage(&self) -> usize {
+        self.row_ids.capacity() * std::mem::size_of::<u64>()
+            + self.doc_ids.capacity() * std::mem::size_of::<(u64, DocId)>()
+    }
+}
+
+/// Cache of `SegmentRowIdMapping`, registered as warmer of an index reader,
+/// so mappings are built when a new searcher generation is loaded.
+#[derive(Default)]
+pub struct RowIdMappingCache {
+    segments: RwLock<HashMap<SegmentId, Arc<SegmentRowIdMapping>>>,
+}
+
+impl RowIdMappingCache {
+    pub fn segment(&self, segment_id: &SegmentId) -> Option<Arc<SegmentRowIdMapping>> {
+        match self.segments.read() {
+            Ok(segments) => segments.get(segment_id).cloned(),
+            Err(_) => None,
+        }
+    }
+
+    // Locate doc of a row_id in `searcher`, segments not warmed yet are skipped.
+    pub fn doc_address(&self, searcher: &Searcher, row_id: u64) -> Option<DocAddress> {
+        searcher
+            .segment_readers()
+            .iter()
+            .enumerate()
+            .find_map(|(segment_ord, segment_reader)| {
+                let doc_id = self.segment(&segment_reader.segment_id())?.doc_id(row_id)?;
+                if segment_reader.is_deleted(doc_id) {
+                    return None;
+                }
+                Some(DocAddress::new(segment_ord as u32, doc_id))
+            })
+    }
+
+    // Total bytes held by cached mappings.
+    pub fn memory_usage(&self) -> u64 {
+        match self.segments.read() {
+            Ok(segments) => segments
+                .values()
+                .map(|mapping| mapping.memory_usage() as u64)
+                .sum(),
+            Err(_) => 0,
+        }
+    }
+}
+
+impl Warmer for RowIdMappingCache {
+    fn warm(&self, searcher: &Searcher) -> tantivy::Result<()> {
+        for segment_reader in searcher.segment_readers() {
+            let segment_id = segment_reader.segment_id();
+            if self.segment(&segment_id).is_some() {
+                continue;
+            }
+            let mapping = Arc::new(SegmentRowIdMapping::build(segment_reader)?);
+            if let Ok(mut segments) = self.segments.write() {
+                segments.insert(segment_id, mapping);
+            }
+        }
+        Ok(())
+    }
+
+    fn garbage_collect(&self, live_generations: &[&SearcherGeneration]) {
+        let live_segments: HashSet<SegmentId> = live_generations
+            .iter()
+            .flat_map(|generation| generation.segments().keys().copied())
+            .collect();
+        if let Ok(mut segments) = self.segments.write() {
+            segments.retain(|segment_id, _| live_segments.contains(segment_id));
+        }
+    }
+}
+
+/// Row_id reader of a segment, cached mapping is preferred over opening `row_id` fast field.
+pub enum SegmentRowIdReader {
+    Mapping(Arc<SegmentRowIdMapping>),
+    Column(Column<u64>),
+}
+
+impl SegmentRowIdReader {
+    // Mapping is only built for `row_id` field, other fields are always read from column.
+    pub fn open(
+        segment_reader: &SegmentReader,
+        row_id_field: &str,
+        row_id_mapping: Option<&RowIdMappingCache>,
+    ) -> tantivy::Result<Self> {
+        if row_id_field == "row_id" {
+            if let Some(mapping) =
+                row_id_mapping.and_then(|cache| cache.segment(&segment_reader.segment_id()))
+            {
+                return Ok(SegmentRowIdReader::Mapping(mapping));
+            }
+        }
+        Ok(SegmentRowIdReader::Column(
+            segment_reader.fast_fields().u64(row_id_field)?,
+        ))
+    }
+
+    // First row_id of doc, 0 if it has no row_id.
+    #[inline]
+    pub fn row_id(&self, doc_id: DocId) -> u64 {
+        match self {
+            SegmentRowIdReader::Mapping(mapping) => mapping.row_id(doc_id),
+            SegmentRowIdReader::Column(column) => column.first(doc_id).unwrap_or(0),
+        }
+    }
+
+    // First row_ids of a block of docs, `None` if doc has no row_id.
+    pub fn row_ids_for_block(&self, docs: &[DocId], output: &mut Vec<Option<u64>>) {
+        output.clear();
+        output.resize(docs.len(), None);
+        match self {
+            SegmentRowIdReader::Mapping(mapping) => {
+                for (doc_id, row_id) in docs.iter().zip(output.iter_mut()) {
+                    *row_id = Some(mapping.row_id(*doc_id));
+                }
+            }
+            SegmentRowIdReader::Column(column) => column.first_vals(docs, output),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tantivy::schema::{Schema, FAST, INDEXED, TEXT};
+    use tantivy::{Index, IndexReader, ReloadPolicy, TantivyDocument};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_row_id_mapping_cache() {
+        let temp_directory = TempDir::new().expect("Can't create temp directory");
+        let mut schema_builder = Schema::builder();
+        let row_id_field = schema_builder.add_u64_field("row_id", FAST | INDEXED);
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_dir(temp_directory.path(), schema_builder.build()).unwrap();
+        let mut writer = index.writer_with_num_threads(1, 1024 * 1024 * 64).unwrap();
+        for row_id in [7u64, 3, 5] {
+            let mut doc = TantivyDocument::default();
+            doc.add_u64(row_id_field, row_id);
+            doc.add_text(text_field, "mapping");
+            assert!(writer.add_document(doc).is_ok());
+        }
+        assert!(writer.commit().is_ok());
+
+        let cache = Arc::new(RowIdMappingCache::default());
+        let warmer: Arc<dyn Warmer> = cache.clone();
+        let reader: IndexReader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .warmers(vec![Arc::downgrade(&warmer)])
+            .try_into()
+            .unwrap();
+
+        let searcher = reader.searcher();
+        let segment_id = searcher.segment_readers()[0].segment_id();
+        let mapping = cache
+            .segment(&segment_id)
+            .expect("Mapping should be warmed");
+        assert_eq!(mapping.row_id(0), 7);
+        assert_eq!(mapping.row_id(1), 3);
+        assert_eq!(mapping.doc_id(5), Some(2));
+        assert_eq!(mapping.doc_id(4), None);
+        assert!(cache.memory_usage() > 0);
+        assert_eq!(cache.doc_address(&searcher, 3), Some(DocAddress::new(0, 1)));
+
+        // Mapping is preferred over fast field column.
+        let row_id_reader = SegmentRowIdReader::open(
+            &searcher.segment_readers()[0],
+            "row_id",
+            Some(cache.as_ref()),
+        )
+        .unwrap();
+        assert!(matches!(row_id_reader, SegmentRowIdReader::Mapping(_)));
+        assert_eq!(row_id_reader.row_id(2), 5);
+    }
+}
@@ -0,0 +1,182 @@
+use super::index_reader_bridge::IndexReaderBridge;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tantivy::Searcher;
+
+/// Searcher generation pinned for a multi-step host operation.
+/// It keeps its reader bridge, so the handle stays usable even if the reader is freed or reloaded.
+pub struct SearcherHandle {
+    pub bridge: Arc<IndexReaderBridge>,
+    pub generation: u64,
+}
+
+impl SearcherHandle {
+    pub fn searcher(&self) -> Result<Searcher, String> {
+        self.bridge.searcher_of(Some(self.generation))
+    }
+}
+
+/// Searcher handles acquired by host, keyed by opaque handle id.
+/// Each handle holds its own pin, so releasing a handle never drops a pin held by another one.
+pub struct SearcherHandles {
+    next_handle: AtomicU64,
+    handles: Mutex<HashMap<u64, Arc<SearcherHandle>>>,
+}
+
+impl SearcherHandles {
+    pub fn new() -> Self {
+        SearcherHandles {
+            next_handle: AtomicU64::new(1),
+            handles: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Pin current searcher of `bridge` and return a new handle id.
+    pub fn acquire(&self, bridge: Arc<IndexReaderBridge>) -> Result<u64, String> {
+        let generation = bridge.pin_searcher()?;
+        let mut handles = match self.handles.lock() {
+            Ok(handles) => handles,
+            Err(e) => {
+                let _ = bridge.unpin_searcher(generation);
+                return Err(format!("Lock error: {}", e));
+            }
+        };
+        let handle = self.next_handle.fetch_add(1, Ordering::Relaxed);
+        handles.insert(handle, Arc::new(SearcherHandle { bridge, generation }));
+        Ok(handle)
+    }
+
+    pub fn get(&self, handle: u64) -> Result<Arc<SearcherHandle>, String> {
+        let handles = self
+            .handles
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        handles
+            .get(&handle)
+            .cloned()
+            .ok_or_else(|| format!("Searcher handle {} doesn't exist", handle))
+    }
+
+    // Release a handle and unpin its searcher, `false` if handle doesn't exist.
+    pub fn release(&self, handle: u64) -> Result<bool, String> {
+        let searcher_handle = {
+            let mut handles = self
+                .handles
+                .lock()
+                .map_err(|e| format!("Lock error: {}", e))?;
+            handles.remove(&handle)
+        };
+        match searcher_handle {
+            Some(searcher_handle) => searcher_handle
+                .bridge
+                .unpin_searcher(searcher_handle.generation),
+            None => Ok(false),
+        }
+    }
+
+    // Release all handles and unpin their searchers, returns number of handles released.
+    pub fn release_all(&self) -> Result<usize, String> {
+        let searcher_handles: Vec<Arc<SearcherHandle>> = self
+            .handles
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?
+            .drain()
+            .map(|(_, searcher_handle)| searcher_handle)
+            .collect();
+        for searcher_handle in &searcher_handles {
+            searcher_handle
+                .bridge
+                .unpin_searcher(searcher_handle.generation)?;
+        }
+        Ok(searcher_handles.len())
+    }
+
+    // Number of handles not released yet.
+    pub fn handle_count(&self) -> usize {
+        match self.handles.lock() {
+            Ok(handles) => handles.len(),
+            Err(_) => 0,
+        }
+    }
+}
+
+impl Default for SearcherHandles {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tantivy::schema::{Schema, FAST, INDEXED};
+    use tantivy::{Index, ReloadPolicy, TantivyDocument, Term};
+
+    #[test]
+    fn test_searcher_handles() {
+        let mut schema_builder = Schema::builder();
+        let row_id_field = schema_builder.add_u64_field("row_id", FAST | INDEXED);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut writer = index.writer_with_num_threads(1, 1024 * 1024 * 64).unwrap();
+        for row_id in 0..3u64 {
+            let mut doc = TantivyDocument::default();
+            doc.add_u64(row_id_field, row_id);
+            assert!(writer.add_document(doc).is_ok());
+        }
+        assert!(writer.commit().is_ok());
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()
+            .unwrap();
+        let bridge = Arc::new(IndexReaderBridge::new(
+            "searcher_handles".to_string(),
+            index.clone(),
+            reader,
+        ));
+
+        let handles = SearcherHandles::new();
+        let handle_a = handles.acquire(bridge.clone()).unwrap();
+        let handle_b = handles.acquire(bridge.clone()).unwrap();
+        assert_ne!(handle_a, handle_b);
+        assert_eq!(handles.handle_count(), 2);
+
+        // Handles keep serving the generation they acquired after reader reloaded.
+        writer.delete_term(Term::from_field_u64(row_id_field, 0));
+        assert!(writer.commit().is_ok());
+        assert!(bridge.reload().is_ok());
+        assert_eq!(bridge.searcher_of(None).unwrap().num_docs(), 2);
+        assert_eq!(
+            handles
+                .get(handle_a)
+                .unwrap()
+                .searcher()
+                .unwrap()
+                .num_docs(),
+            3
+        );
+
+        // Releasing one handle keeps the pin of the other one.
+        assert!(handles.release(handle_a).unwrap());
+        assert!(!handles.release(handle_a).unwrap());
+        assert!(handles.get(handle_a).is_err());
+        assert_eq!(
+            handles
+                .get(handle_b)
+                .unwrap()
+                .searcher()
+                .unwrap()
+                .num_docs(),
+            3
+        );
+        assert!(handles.release(handle_b).unwrap());
+        assert!(!bridge.has_pinned_searchers());
+
+        handles.acquire(bridge.clone()).unwrap();
+        handles.acquire(bridge.clone()).unwrap();
+        assert_eq!(handles.release_all().unwrap(), 2);
+        assert_eq!(handles.handle_count(), 0);
+        assert!(!bridge.has_pinned_searchers());
+    }
+}
@@ -0,0 +1,323 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+
+use roaring::{RoaringBitmap, RoaringTreemap};
+use tantivy::query::{EnableScoring, Query, Scorer, Weight};
+use tantivy::{DocSet, Searcher, TERMINATED};
+
+thread_local! {
+    // Principal of searches issued by current thread, `None` searches unfiltered.
+    static SEARCH_PRINCIPAL: RefCell<Option<String>> = RefCell::new(None);
+}
+
+/// Principal bound to searches of the calling thread, set by host before it searches on behalf
+/// of a user. Its security filter is ANDed into every search of the thread.
+pub struct SearchPrincipal;
+
+impl SearchPrincipal {
+    pub fn current() -> Option<String> {
+        SEARCH_PRINCIPAL.with(|principal| principal.borrow().clone())
+    }
+
+    // Bind `principal` to current thread, `None` unbinds it. Returns the previous one.
+    pub fn set(principal: Option<String>) -> Option<String> {
+        SEARCH_PRINCIPAL.with(|current| current.replace(principal))
+    }
+}
+
+/// Rows a principal may see.
+pub enum SecurityFilter {
+    /// Rows matching a stored query.
+    Query {
+        sentence: String,
+        query: Box<dyn Query>,
+    },
+    /// Rows in a bitmap of row ids.
+    RowIds(RoaringTreemap),
+}
+
+/// Security filters of an index reader keyed by principal. A filter is resolved into row ids
+/// the principal can't see once per searcher generation, they are excluded from results
+/// together with lightweight deleted row ids. A principal without filter sees nothing.
+pub struct SecurityFilters {
+    filters: RwLock<HashMap<String, Arc<SecurityFilter>>>,
+    // Excluded row ids of each principal, with the searcher generation they are resolved for.
+    excluded: Mutex<HashMap<String, (u64, Arc<RoaringTreemap>)>>,
+}
+
+impl SecurityFilters {
+    pub fn new() -> Self {
+        SecurityFilters {
+            filters: RwLock::new(HashMap::new()),
+            excluded: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Filters of `other`, e.g. a reader being replaced, they are resolved again.
+    pub fn with_filters_of(other: &SecurityFilters) -> Self {
+        let filters: HashMap<String, Arc<SecurityFilter>> = match other.filters.read() {
+            Ok(filters) => filters.clone(),
+            Err(_) => HashMap::new(),
+        };
+        SecurityFilters {
+            filters: RwLock::new(filters),
+            excluded: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Set filter of `principal`, a previous one is replaced.
+    pub fn register(&self, principal: &str, filter: SecurityFilter) -> Result<(), String> {
+        let mut filters = self
+            .filters
+            .write()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        filters.insert(principal.to_string(), Arc::new(filter));
+        self.forget_excluded(principal)
+    }
+
+    /// Remove filter of `principal`, `false` if it has none.
+    pub fn unregister(&self, principal: &str) -> Result<bool, String> {
+        let mut filters = self
+            .filters
+            .write()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        let removed: bool = filters.remove(principal).is_some();
+        self.forget_excluded(principal)?;
+        Ok(removed)
+    }
+
+    pub fn num_principals(&self) -> usize {
+        match self.filters.read() {
+            Ok(filters) => filters.len(),
+            Err(_) => 0,
+        }
+    }
+
+    fn forget_excluded(&self, principal: &str) -> Result<(), String> {
+        self.excluded
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?
+            .remove(principal);
+        Ok(())
+    }
+
+    /// Row ids of `searcher` which `principal` can't see.
+    pub fn excluded_row_ids(
+        &self,
+        principal: &str,
+        searcher: &Searcher,
+    ) -> Result<Arc<RoaringTreemap>, String> {
+        let generation: u64 = searcher.generation().generation_id();
+        if let Some((resolved, excluded)) = self
+            .excluded
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?
+            .get(principal)
+        {
+            if *resolved == generation {
+                return Ok(excluded.clone());
+            }
+        }
+
+        let filter: Option<Arc<SecurityFilter>> = self
+            .filters
+            .read()
+            .map_err(|e| format!("Lock error: {}", e))?
+            .get(principal)
+            .cloned();
+        let excluded: Arc<RoaringTreemap> =
+            Arc::new(resolve_excluded(filter.as_deref(), searcher)?);
+
+        // Pinned older generations are resolved again, only the newest one is kept.
+        let mut resolved = self
+            .excluded
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        let newer: bool = resolved
+            .get(principal)
+            .map_or(true, |(resolved, _)| *resolved < generation);
+        if newer {
+            resolved.insert(principal.to_string(), (generation, excluded.clone()));
+        }
+        Ok(excluded)
+    }
+}
+
+impl Default for SecurityFilters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Row ids of alive docs `filter` doesn't allow, every row of `searcher` without filter.
+fn resolve_excluded(
+    filter: Option<&SecurityFilter>,
+    searcher: &Searcher,
+) -> Result<RoaringTreemap, String> {
+    let weight: Option<Box<dyn Weight>> = match filter {
+        Some(SecurityFilter::Query { sentence, query }) => Some(
+            query
+                .weight(EnableScoring::disabled_from_searcher(searcher))
+                .map_err(|e| format!("Error when resolve filter: {}. {}", sentence, e))?,
+        ),
+        _ => None,
+    };
+    let mut excluded: RoaringTreemap = RoaringTreemap::new();
+    for segment_reader in searcher.segment_readers() {
+        let row_id_column = segment_reader
+            .fast_fields()
+            .u64("row_id")
+            .map_err(|e| format!("Failed to read row_id column: {}", e))?;
+        let mut matched_docs: RoaringBitmap = RoaringBitmap::new();
+        if let Some(weight) = &weight {
+            let mut scorer: Box<dyn Scorer> = weight
+                .scorer(segment_reader, 1.0)
+                .map_err(|e| format!("Error when resolve filter: {}", e))?;
+            let mut doc = scorer.doc();
+            while doc != TERMINATED {
+                matched_docs.insert(doc);
+                doc = scorer.advance();
+            }
+        }
+        for doc_id in segment_reader.doc_ids_alive() {
+            let row_id: u64 = match row_id_column.first(doc_id) {
+                Some(row_id) => row_id,
+                None => continue,
+            };
+            let allowed: bool = match filter {
+                Some(SecurityFilter::Query { .. }) => matched_docs.contains(doc_id),
+                Some(SecurityFilter::RowIds(row_ids)) => row_ids.contains(row_id),
+                None => false,
+            };
+            if !allowed {
+                excluded.insert(row_id);
+            }
+        }
+    }
+    Ok(excluded)
+}
+
+/// Security filter of the principal bound to a search, resolved against its searcher.
+#[derive(Clone)]
+pub struct PrincipalFilter {
+    pub filters: Arc<SecurityFilters>,
+    pub principal: String,
+}
+
+impl PrincipalFilter {
+    /// Row ids excluded from a search of `searcher`, lightweight deleted ones included.
+    pub fn excluded_row_ids(
+        &self,
+        searcher: &Searcher,
+        deleted_row_ids: Option<&Arc<RoaringTreemap>>,
+    ) -> Result<Arc<RoaringTreemap>, String> {
+        let excluded: Arc<RoaringTreemap> =
+            self.filters.excluded_row_ids(&self.principal, searcher)?;
+        Ok(match deleted_row_ids {
+            Some(deleted_row_ids) if !deleted_row_ids.is_empty() => {
+                Arc::new(excluded.as_ref() | deleted_row_ids.as_ref())
+            }
+            _ => excluded,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tantivy::query::QueryParser;
+    use tantivy::schema::{Schema, FAST, INDEXED, TEXT};
+    use tantivy::{doc, Index, IndexWriter, Term};
+
+    #[test]
+    fn test_security_filters() {
+        let mut schema_builder = Schema::builder();
+        let row_id = schema_builder.add_u64_field("row_id", FAST | INDEXED);
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut writer: IndexWriter = index.writer(15_000_000).unwrap();
+        for (id, body) in [
+            (0u64, "public"),
+            (1, "secret"),
+            (2, "public"),
+            (3, "secret"),
+        ] {
+            writer
+                .add_document(doc!(row_id => id, text => body))
+                .unwrap();
+        }
+        writer.commit().unwrap();
+        let reader = index.reader().unwrap();
+        let searcher: Searcher = reader.searcher();
+
+        let filters: Arc<SecurityFilters> = Arc::new(SecurityFilters::new());
+        let query: Box<dyn Query> = QueryParser::for_index(&index, vec![text])
+            .parse_query("public")
+            .unwrap();
+        filters
+            .register(
+                "alice",
+                SecurityFilter::Query {
+                    sentence: "public".to_string(),
+                    query,
+                },
+            )
+            .unwrap();
+        filters
+            .register(
+                "bob",
+                SecurityFilter::RowIds([1u64, 2].into_iter().collect()),
+            )
+            .unwrap();
+        let filter_of = |principal: &str| PrincipalFilter {
+            filters: filters.clone(),
+            principal: principal.to_string(),
+        };
+        let excluded = |principal: &str, deleted: Option<&Arc<RoaringTreemap>>| {
+            filter_of(principal)
+                .excluded_row_ids(&searcher, deleted)
+                .unwrap()
+                .iter()
+                .collect::<Vec<u64>>()
+        };
+
+        assert_eq!(excluded("alice", None), vec![1, 3]);
+        assert_eq!(excluded("bob", None), vec![0, 3]);
+        let deleted: Arc<RoaringTreemap> = Arc::new([0u64].into_iter().collect());
+        assert_eq!(excluded("alice", Some(&deleted)), vec![0, 1, 3]);
+        // Principal without filter sees nothing.
+        assert_eq!(excluded("eve", None), vec![0, 1, 2, 3]);
+
+        // Resolved filters are refreshed after they change.
+        assert!(filters.unregister("bob").unwrap());
+        assert!(!filters.unregister("bob").unwrap());
+        assert_eq!(excluded("bob", None), vec![0, 1, 2, 3]);
+
+        // Deleted docs aren't resolved.
+        writer.delete_term(Term::from_field_u64(row_id, 1));
+        writer.commit().unwrap();
+        reader.reload().unwrap();
+        let searcher: Searcher = reader.searcher();
+        let carried = SecurityFilters::with_filters_of(&filters);
+        assert_eq!(carried.num_principals(), 1);
+        assert_eq!(
+            carried
+                .excluded_row_ids("alice", &searcher)
+                .unwrap()
+                .iter()
+                .collect::<Vec<u64>>(),
+            vec![3]
+        );
+    }
+
+    #[test]
+    fn test_search_principal() {
+        assert_eq!(SearchPrincipal::current(), None);
+        assert_eq!(SearchPrincipal::set(Some("alice".to_string())), None);
+        assert_eq!(SearchPrincipal::current().as_deref(), Some("alice"));
+        let other_thread = std::thread::spawn(SearchPrincipal::current);
+        assert_eq!(other_thread.join().unwrap(), None);
+        assert_eq!(SearchPrincipal::set(None).as_deref(), Some("alice"));
+    }
+}
@@ -0,0 +1,233 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+
+use tantivy::columnar::BytesColumn;
+use tantivy::{DocId, Searcher, SearcherGeneration, SegmentId, SegmentReader, Warmer};
+
+use crate::common::hnsw::HnswGraph;
+use crate::tokenizer::vector_format::{VectorFormat, VectorIndexType};
+
+/// Vectors of a vector column in one segment, deleted docs are included.
+/// HNSW graph is built over them if the column is configured with `hnsw` index.
+pub struct SegmentVectors {
+    dims: usize,
+    // Docs having a vector in ascending order, position of a doc is its node in graph.
+    doc_ids: Vec<DocId>,
+    // Vectors of `doc_ids`, concatenated.
+    values: Vec<f32>,
+    graph: Option<HnswGraph>,
+}
+
+impl SegmentVectors {
+    pub fn build(
+        segment_reader: &SegmentReader,
+        column_name: &str,
+        vector_format: &VectorFormat,
+    ) -> tantivy::Result<Self> {
+        let mut vectors: SegmentVectors = SegmentVectors {
+            dims: vector_format.dims,
+            doc_ids: Vec::new(),
+            values: Vec::new(),
+            graph: None,
+        };
+        let column: BytesColumn = match segment_reader.fast_fields().bytes(column_name)? {
+            Some(column) => column,
+            None => return Ok(vectors),
+        };
+        let mut bytes: Vec<u8> = Vec::new();
+        for doc_id in 0..segment_reader.max_doc() {
+            let ord: u64 = match column.term_ords(doc_id).next() {
+                Some(ord) => ord,
+                None => continue,
+            };
+            bytes.clear();
+            if column.ord_to_bytes(ord, &mut bytes)?
+                && vector_format.decode_into(&bytes, &mut vectors.values)
+            {
+                vectors.doc_ids.push(doc_id);
+            }
+        }
+
+        if let VectorIndexType::Hnsw {
+            m, ef_construction, ..
+        } = vector_format.index
+        {
+            let metric = vector_format.metric;
+            let graph: HnswGraph =
+                HnswGraph::build(vectors.num_vectors(), m, ef_construction, |a, b| {
+                    metric.score(vectors.vector(a), vectors.vector(b))
+                });
+            vectors.graph = Some(graph);
+        }
+        Ok(vectors)
+    }
+
+    pub fn num_vectors(&self) -> usize {
+        self.doc_ids.len()
+    }
+
+    #[inline]
+    pub fn doc_id(&self, node: u32) -> DocId {
+        self.doc_ids[node as usize]
+    }
+
+    #[inline]
+    pub fn vector(&self, node: u32) -> &[f32] {
+        let start: usize = node as usize * self.dims;
+        &self.values[start..start + self.dims]
+    }
+
+    pub fn graph(&self) -> Option<&HnswGraph> {
+        self.graph.as_ref()
+    }
+
+    pub fn memory_usage(&self) -> usize {
+        self.doc_ids.capacity() * std::mem::size_of::<DocId>()
+            + self.values.capacity() * std::mem::size_of::<f32>()
+            + self.graph.as_ref().map_or(0, HnswGraph::memory_usage)
+    }
+}
+
+/// Cache of `SegmentVectors` of vector columns, registered as warmer of an index reader,
+/// so vectors are loaded and HNSW graphs are built when a new searcher generation is loaded.
+#[derive(Default)]
+pub struct VectorCache {
+    vector_formats: HashMap<String, VectorFormat>,
+    segments: RwLock<HashMap<(SegmentId, String), Arc<SegmentVectors>>>,
+}
+
+impl VectorCache {
+    pub fn new(vector_formats: HashMap<String, VectorFormat>) -> Self {
+        VectorCache {
+            vector_formats,
+            segments: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn vector_format(&self, column_name: &str) -> Option<&VectorFormat> {
+        self.vector_formats.get(column_name)
+    }
+
+    // Vectors of `column_name` in segment, they're loaded if the segment isn't warmed yet.
+    pub fn segment(
+        &self,
+        segment_reader: &SegmentReader,
+        column_name: &str,
+    ) -> tantivy::Result<Arc<SegmentVectors>> {
+        let key: (SegmentId, String) = (segment_reader.segment_id(), column_name.to_string());
+        if let Some(vectors) = self
+            .segments
+            .read()
+            .ok()
+            .and_then(|segments| segments.get(&key).cloned())
+        {
+            return Ok(vectors);
+        }
+        let vector_format: &VectorFormat =
+            self.vector_formats.get(column_name).ok_or_else(|| {
+                tantivy::TantivyError::SchemaError(format!(
+                    "{} is not a vector column",
+                    column_name
+                ))
+            })?;
+        let vectors: Arc<SegmentVectors> = Arc::new(SegmentVectors::build(
+            segment_reader,
+            column_name,
+            vector_format,
+        )?);
+        if let Ok(mut segments) = self.segments.write() {
+            segments.insert(key, vectors.clone());
+        }
+        Ok(vectors)
+    }
+
+    // Total bytes held by cached vectors and graphs.
+    pub fn memory_usage(&self) -> u64 {
+        match self.segments.read() {
+            Ok(segments) => segments
+                .values()
+                .map(|vectors| vectors.memory_usage() as u64)
+                .sum(),
+            Err(_) => 0,
+        }
+    }
+}
+
+impl Warmer for VectorCache {
+    fn warm(&self, searcher: &Searcher) -> tantivy::Result<()> {
+        for segment_reader in searcher.segment_readers() {
+            for column_name in self.vector_formats.keys() {
+                self.segment(segment_reader, column_name)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn garbage_collect(&self, live_generations: &[&SearcherGeneration]) {
+        let live_segments: HashSet<SegmentId> = live_generations
+            .iter()
+            .flat_map(|generation| generation.segments().keys().copied())
+            .collect();
+        if let Ok(mut segments) = self.segments.write() {
+            segments.retain(|(segment_id, _), _| live_segments.contains(segment_id));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::vector_format::VectorMetric;
+    use tantivy::schema::{Schema, FAST};
+    use tantivy::{Index, IndexReader, ReloadPolicy, TantivyDocument};
+
+    #[test]
+    fn test_vector_cache() {
+        let mut schema_builder = Schema::builder();
+        let embedding_field = schema_builder.add_bytes_field("embedding", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut writer = index.writer_with_num_threads(1, 15_000_000).unwrap();
+        for vector in [Some([1.0, 0.0]), None, Some([0.0, 1.0])] {
+            let mut doc = TantivyDocument::default();
+            if let Some(vector) = vector {
+                doc.add_bytes(embedding_field, VectorFormat::encode(&vector));
+            }
+            assert!(writer.add_document(doc).is_ok());
+        }
+        assert!(writer.commit().is_ok());
+
+        let vector_format = VectorFormat {
+            dims: 2,
+            metric: VectorMetric::Cosine,
+            index: VectorIndexType::Hnsw {
+                m: 4,
+                ef_construction: 10,
+                ef_search: 10,
+            },
+        };
+        let cache = Arc::new(VectorCache::new(HashMap::from([(
+            "embedding".to_string(),
+            vector_format,
+        )])));
+        let warmer: Arc<dyn Warmer> = cache.clone();
+        let reader: IndexReader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .warmers(vec![Arc::downgrade(&warmer)])
+            .try_into()
+            .unwrap();
+
+        let searcher = reader.searcher();
+        let vectors = cache
+            .segment(&searcher.segment_readers()[0], "embedding")
+            .unwrap();
+        assert_eq!(vectors.num_vectors(), 2);
+        assert_eq!(vectors.doc_id(1), 2);
+        assert_eq!(vectors.vector(1), &[0.0, 1.0]);
+        assert!(vectors.graph().is_some());
+        assert!(cache.memory_usage() > 0);
+        assert!(cache
+            .segment(&searcher.segment_readers()[0], "title")
+            .is_err());
+    }
+}
@@ -1,2 +1,8 @@
 pub mod index_reader_bridge;
 pub mod index_reader_bridge_cache;
+pub mod index_reader_percolator;
+pub mod index_reader_query_cache;
+pub mod index_reader_row_id_mapping;
+pub mod index_reader_searcher_handles;
+pub mod index_reader_security_filter;
+pub mod index_reader_vector_cache;
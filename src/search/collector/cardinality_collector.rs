@@ -0,0 +1,243 @@
+use std::fmt;
+use std::sync::Arc;
+
+use roaring::RoaringTreemap;
+use tantivy::collector::{Collector, SegmentCollector};
+use tantivy::{DocId, Score, SegmentOrdinal, SegmentReader, TantivyError};
+
+use crate::common::hyper_log_log::{HyperLogLog, HLL_PRECISION};
+use crate::search::bridge::index_reader_row_id_mapping::{RowIdMappingCache, SegmentRowIdReader};
+use crate::search::collector::top_docs_order_by_field_collector::SortKeyColumn;
+
+// Variables in CardinalityCollector:
+// @`field_name` is a numeric or date fast field, distinct values of it are estimated.
+// @`deleted_row_ids` is an Option type, row_ids in it are lightweight deleted and won't be collected.
+// @`row_id_mapping` is an Option type, warmed segments read row_id from it instead of fast field.
+// @`row_id_treemap` is an Option type, only row_ids in it will be collected.
+// @`row_id_range` is an Option type, only row_ids in range [start, end) will be collected.
+//
+// Each segment builds a HyperLogLog sketch of field values of matched docs, sketches are merged
+// so a value shared by segments is counted once. Docs without field value are not counted.
+
+pub struct CardinalityCollector {
+    pub field_name: String,
+    pub deleted_row_ids: Option<Arc<RoaringTreemap>>,
+    pub row_id_mapping: Option<Arc<RowIdMappingCache>>,
+    pub row_id_treemap: Option<Arc<RoaringTreemap>>,
+    pub row_id_range: Option<(u64, u64)>,
+}
+
+impl CardinalityCollector {
+    pub fn with_field(field_name: String) -> CardinalityCollector {
+        Self {
+            field_name,
+            deleted_row_ids: None,
+            row_id_mapping: None,
+            row_id_treemap: None,
+            row_id_range: None,
+        }
+    }
+
+    // `row_id_bitmap` is used to mark alive row_ids.
+    pub fn with_alive(mut self, row_id_bitmap: Arc<RoaringTreemap>) -> CardinalityCollector {
+        self.row_id_treemap = Some(row_id_bitmap);
+        self
+    }
+
+    // `row_id_range` is used to mark alive row_id range, the range is [start, end)
+    pub fn with_range(mut self, row_id_range: (u64, u64)) -> CardinalityCollector {
+        self.row_id_range = Some(row_id_range);
+        self
+    }
+
+    // `deleted_row_ids` is used to exclude lightweight deleted row_ids.
+    pub fn with_deleted(mut self, deleted_row_ids: Arc<RoaringTreemap>) -> CardinalityCollector {
+        self.deleted_row_ids = Some(deleted_row_ids);
+        self
+    }
+
+    // `row_id_mapping` avoids opening `row_id` fast field for warmed segments.
+    pub fn with_row_id_mapping(
+        mut self,
+        row_id_mapping: Arc<RowIdMappingCache>,
+    ) -> CardinalityCollector {
+        self.row_id_mapping = Some(row_id_mapping);
+        self
+    }
+
+    // Row_id filters are only checked when there is one.
+    fn has_row_id_filter(&self) -> bool {
+        self.deleted_row_ids.is_some()
+            || self.row_id_treemap.is_some()
+            || self.row_id_range.is_some()
+    }
+}
+
+impl fmt::Debug for CardinalityCollector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "CardinalityCollector(field_name:{}, row_ids_size:{}, row_id_range:{:?})",
+            self.field_name,
+            self.row_id_treemap
+                .as_ref()
+                .map(|treemap| treemap.len())
+                .unwrap_or(0),
+            self.row_id_range
+        )
+    }
+}
+
+impl Collector for CardinalityCollector {
+    type Fruit = HyperLogLog;
+    type Child = CardinalitySegmentCollector;
+
+    fn for_segment(
+        &self,
+        _segment_local_id: SegmentOrdinal,
+        segment_reader: &SegmentReader,
+    ) -> tantivy::Result<Self::Child> {
+        let row_id_reader: Option<SegmentRowIdReader> = match self.has_row_id_filter() {
+            true => Some(SegmentRowIdReader::open(
+                segment_reader,
+                "row_id",
+                self.row_id_mapping.as_deref(),
+            )?),
+            false => None,
+        };
+        Ok(CardinalitySegmentCollector {
+            key_column: SortKeyColumn::open(segment_reader, &self.field_name)?,
+            row_id_reader,
+            deleted_row_ids: self.deleted_row_ids.clone(),
+            row_id_treemap: self.row_id_treemap.clone(),
+            row_id_range: self.row_id_range,
+            sketch: new_sketch()?,
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        false
+    }
+
+    fn merge_fruits(&self, segment_sketches: Vec<HyperLogLog>) -> tantivy::Result<Self::Fruit> {
+        let mut sketch: HyperLogLog = new_sketch()?;
+        for segment_sketch in &segment_sketches {
+            sketch
+                .merge(segment_sketch)
+                .map_err(TantivyError::InternalError)?;
+        }
+        Ok(sketch)
+    }
+}
+
+fn new_sketch() -> tantivy::Result<HyperLogLog> {
+    HyperLogLog::new(HLL_PRECISION).map_err(TantivyError::InternalError)
+}
+
+pub struct CardinalitySegmentCollector {
+    key_column: SortKeyColumn,
+    row_id_reader: Option<SegmentRowIdReader>,
+    deleted_row_ids: Option<Arc<RoaringTreemap>>,
+    row_id_treemap: Option<Arc<RoaringTreemap>>,
+    row_id_range: Option<(u64, u64)>,
+    sketch: HyperLogLog,
+}
+
+impl CardinalitySegmentCollector {
+    #[inline]
+    fn is_collected(&self, doc: DocId) -> bool {
+        let row_id: u64 = match &self.row_id_reader {
+            Some(row_id_reader) => row_id_reader.row_id(doc),
+            None => return true,
+        };
+        if let Some(deleted_row_ids) = &self.deleted_row_ids {
+            if deleted_row_ids.contains(row_id) {
+                return false;
+            }
+        }
+        if let Some(row_id_treemap) = &self.row_id_treemap {
+            if !row_id_treemap.contains(row_id) {
+                return false;
+            }
+        }
+        if let Some((start, end)) = self.row_id_range {
+            if !(start <= row_id && row_id < end) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl SegmentCollector for CardinalitySegmentCollector {
+    type Fruit = HyperLogLog;
+
+    fn collect(&mut self, doc: DocId, _score: Score) {
+        if !self.is_collected(doc) {
+            return;
+        }
+        if let Some(key) = self.key_column.sort_key(doc) {
+            self.sketch.insert(key);
+        }
+    }
+
+    fn harvest(self) -> HyperLogLog {
+        self.sketch
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tantivy::indexer::NoMergePolicy;
+    use tantivy::query::{AllQuery, QueryParser};
+    use tantivy::schema::{Schema, FAST, INDEXED, TEXT};
+    use tantivy::{doc, Index};
+
+    #[test]
+    fn test_cardinality_collector() {
+        let mut schema_builder = Schema::builder();
+        let row_id = schema_builder.add_u64_field("row_id", FAST | INDEXED);
+        let price = schema_builder.add_i64_field("price", FAST);
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut writer = index.writer_with_num_threads(1, 15_000_000).unwrap();
+        writer.set_merge_policy(Box::new(NoMergePolicy));
+        // Prices -10..10 in two segments, every price appears in both of them.
+        for id in 0..40u64 {
+            let mut document = doc!(row_id => id, text => if id % 2 == 0 { "even" } else { "odd" });
+            document.add_i64(price, id as i64 % 20 - 10);
+            writer.add_document(document).unwrap();
+            if id == 19 {
+                writer.commit().unwrap();
+            }
+        }
+        writer
+            .add_document(doc!(row_id => 40u64, text => "odd"))
+            .unwrap();
+        writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        assert_eq!(searcher.segment_readers().len(), 2);
+
+        let estimate = |query: &dyn tantivy::query::Query, collector: CardinalityCollector| {
+            searcher.search(query, &collector).unwrap().estimate()
+        };
+        let collector = || CardinalityCollector::with_field("price".to_string());
+        assert_eq!(estimate(&AllQuery, collector()), 20);
+        let odd_query = QueryParser::for_index(&index, vec![text])
+            .parse_query("odd")
+            .unwrap();
+        assert_eq!(estimate(odd_query.as_ref(), collector()), 10);
+        assert_eq!(estimate(&AllQuery, collector().with_range((0, 5))), 5);
+        let deleted = Arc::new(RoaringTreemap::from_iter(5..40u64));
+        assert_eq!(estimate(&AllQuery, collector().with_deleted(deleted)), 5);
+
+        // Text field can't be counted.
+        assert!(searcher
+            .search(
+                &AllQuery,
+                &CardinalityCollector::with_field("text".to_string())
+            )
+            .is_err());
+    }
+}
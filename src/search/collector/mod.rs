@@ -1,4 +1,8 @@
+pub mod cardinality_collector;
+pub mod percentiles_collector;
 pub mod row_id_bitmap_collector;
 mod test;
+pub mod top_docs_order_by_field_collector;
 pub mod top_docs_with_bitmap_collector;
 pub mod top_docs_with_treemap_collector;
+pub mod top_groups_collector;
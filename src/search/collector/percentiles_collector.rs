@@ -0,0 +1,295 @@
+use std::fmt;
+use std::sync::Arc;
+
+use roaring::RoaringTreemap;
+use tantivy::collector::{Collector, SegmentCollector};
+use tantivy::columnar::Column;
+use tantivy::schema::FieldType;
+use tantivy::{DocId, Score, SegmentOrdinal, SegmentReader, TantivyError};
+
+use crate::common::t_digest::{TDigest, TDIGEST_COMPRESSION};
+use crate::search::bridge::index_reader_row_id_mapping::{RowIdMappingCache, SegmentRowIdReader};
+
+// Variables in PercentilesCollector:
+// @`field_name` is a numeric fast field, percentiles of its values are estimated.
+// @`deleted_row_ids` is an Option type, row_ids in it are lightweight deleted and won't be collected.
+// @`row_id_mapping` is an Option type, warmed segments read row_id from it instead of fast field.
+// @`row_id_treemap` is an Option type, only row_ids in it will be collected.
+// @`row_id_range` is an Option type, only row_ids in range [start, end) will be collected.
+//
+// Each segment builds a t-digest of field values of matched docs, digests are merged into one.
+// Docs without field value are not counted, multi valued docs count their first value.
+
+pub struct PercentilesCollector {
+    pub field_name: String,
+    pub deleted_row_ids: Option<Arc<RoaringTreemap>>,
+    pub row_id_mapping: Option<Arc<RowIdMappingCache>>,
+    pub row_id_treemap: Option<Arc<RoaringTreemap>>,
+    pub row_id_range: Option<(u64, u64)>,
+}
+
+impl PercentilesCollector {
+    pub fn with_field(field_name: String) -> PercentilesCollector {
+        Self {
+            field_name,
+            deleted_row_ids: None,
+            row_id_mapping: None,
+            row_id_treemap: None,
+            row_id_range: None,
+        }
+    }
+
+    // `row_id_bitmap` is used to mark alive row_ids.
+    pub fn with_alive(mut self, row_id_bitmap: Arc<RoaringTreemap>) -> PercentilesCollector {
+        self.row_id_treemap = Some(row_id_bitmap);
+        self
+    }
+
+    // `row_id_range` is used to mark alive row_id range, the range is [start, end)
+    pub fn with_range(mut self, row_id_range: (u64, u64)) -> PercentilesCollector {
+        self.row_id_range = Some(row_id_range);
+        self
+    }
+
+    // `deleted_row_ids` is used to exclude lightweight deleted row_ids.
+    pub fn with_deleted(mut self, deleted_row_ids: Arc<RoaringTreemap>) -> PercentilesCollector {
+        self.deleted_row_ids = Some(deleted_row_ids);
+        self
+    }
+
+    // `row_id_mapping` avoids opening `row_id` fast field for warmed segments.
+    pub fn with_row_id_mapping(
+        mut self,
+        row_id_mapping: Arc<RowIdMappingCache>,
+    ) -> PercentilesCollector {
+        self.row_id_mapping = Some(row_id_mapping);
+        self
+    }
+
+    // Row_id filters are only checked when there is one.
+    fn has_row_id_filter(&self) -> bool {
+        self.deleted_row_ids.is_some()
+            || self.row_id_treemap.is_some()
+            || self.row_id_range.is_some()
+    }
+}
+
+impl fmt::Debug for PercentilesCollector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "PercentilesCollector(field_name:{}, row_ids_size:{}, row_id_range:{:?})",
+            self.field_name,
+            self.row_id_treemap
+                .as_ref()
+                .map(|treemap| treemap.len())
+                .unwrap_or(0),
+            self.row_id_range
+        )
+    }
+}
+
+impl Collector for PercentilesCollector {
+    type Fruit = TDigest;
+    type Child = PercentilesSegmentCollector;
+
+    fn for_segment(
+        &self,
+        _segment_local_id: SegmentOrdinal,
+        segment_reader: &SegmentReader,
+    ) -> tantivy::Result<Self::Child> {
+        let row_id_reader: Option<SegmentRowIdReader> = match self.has_row_id_filter() {
+            true => Some(SegmentRowIdReader::open(
+                segment_reader,
+                "row_id",
+                self.row_id_mapping.as_deref(),
+            )?),
+            false => None,
+        };
+        Ok(PercentilesSegmentCollector {
+            value_column: NumericColumn::open(segment_reader, &self.field_name)?,
+            row_id_reader,
+            deleted_row_ids: self.deleted_row_ids.clone(),
+            row_id_treemap: self.row_id_treemap.clone(),
+            row_id_range: self.row_id_range,
+            digest: new_digest()?,
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        false
+    }
+
+    fn merge_fruits(&self, segment_digests: Vec<TDigest>) -> tantivy::Result<Self::Fruit> {
+        let mut digest: TDigest = new_digest()?;
+        for segment_digest in &segment_digests {
+            digest
+                .merge(segment_digest)
+                .map_err(TantivyError::InternalError)?;
+        }
+        Ok(digest)
+    }
+}
+
+fn new_digest() -> tantivy::Result<TDigest> {
+    TDigest::new(TDIGEST_COMPRESSION).map_err(TantivyError::InternalError)
+}
+
+enum NumericColumn {
+    U64(Column<u64>),
+    I64(Column<i64>),
+    F64(Column<f64>),
+}
+
+impl NumericColumn {
+    fn open(reader: &SegmentReader, field_name: &str) -> tantivy::Result<Self> {
+        let schema = reader.schema();
+        let field = schema.get_field(field_name)?;
+        let field_entry = schema.get_field_entry(field);
+        if !field_entry.is_fast() {
+            return Err(TantivyError::SchemaError(format!(
+                "Field `{}` is not a fast field.",
+                field_name
+            )));
+        }
+        let fast_fields = reader.fast_fields();
+        match field_entry.field_type() {
+            FieldType::U64(_) => Ok(NumericColumn::U64(fast_fields.u64(field_name)?)),
+            FieldType::I64(_) => Ok(NumericColumn::I64(fast_fields.i64(field_name)?)),
+            FieldType::F64(_) => Ok(NumericColumn::F64(fast_fields.f64(field_name)?)),
+            _ => Err(TantivyError::SchemaError(format!(
+                "Field `{}` is not a numeric fast field.",
+                field_name
+            ))),
+        }
+    }
+
+    #[inline]
+    fn value(&self, doc: DocId) -> Option<f64> {
+        match self {
+            NumericColumn::U64(column) => column.first(doc).map(|value| value as f64),
+            NumericColumn::I64(column) => column.first(doc).map(|value| value as f64),
+            NumericColumn::F64(column) => column.first(doc),
+        }
+    }
+}
+
+pub struct PercentilesSegmentCollector {
+    value_column: NumericColumn,
+    row_id_reader: Option<SegmentRowIdReader>,
+    deleted_row_ids: Option<Arc<RoaringTreemap>>,
+    row_id_treemap: Option<Arc<RoaringTreemap>>,
+    row_id_range: Option<(u64, u64)>,
+    digest: TDigest,
+}
+
+impl PercentilesSegmentCollector {
+    #[inline]
+    fn is_collected(&self, doc: DocId) -> bool {
+        let row_id: u64 = match &self.row_id_reader {
+            Some(row_id_reader) => row_id_reader.row_id(doc),
+            None => return true,
+        };
+        if let Some(deleted_row_ids) = &self.deleted_row_ids {
+            if deleted_row_ids.contains(row_id) {
+                return false;
+            }
+        }
+        if let Some(row_id_treemap) = &self.row_id_treemap {
+            if !row_id_treemap.contains(row_id) {
+                return false;
+            }
+        }
+        if let Some((start, end)) = self.row_id_range {
+            if !(start <= row_id && row_id < end) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl SegmentCollector for PercentilesSegmentCollector {
+    type Fruit = TDigest;
+
+    fn collect(&mut self, doc: DocId, _score: Score) {
+        if !self.is_collected(doc) {
+            return;
+        }
+        if let Some(value) = self.value_column.value(doc) {
+            self.digest.insert(value);
+        }
+    }
+
+    fn harvest(self) -> TDigest {
+        self.digest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tantivy::indexer::NoMergePolicy;
+    use tantivy::query::{AllQuery, QueryParser};
+    use tantivy::schema::{Schema, FAST, INDEXED, TEXT};
+    use tantivy::{doc, Index};
+
+    #[test]
+    fn test_percentiles_collector() {
+        let mut schema_builder = Schema::builder();
+        let row_id = schema_builder.add_u64_field("row_id", FAST | INDEXED);
+        let latency = schema_builder.add_f64_field("latency", FAST);
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut writer = index.writer_with_num_threads(1, 15_000_000).unwrap();
+        writer.set_merge_policy(Box::new(NoMergePolicy));
+        // Latencies 1..=100 in two segments, docs of `slow` have latency above 50.
+        for id in 1..=100u64 {
+            writer
+                .add_document(doc!(
+                    row_id => id,
+                    latency => id as f64,
+                    text => if id > 50 { "slow" } else { "fast" },
+                ))
+                .unwrap();
+            if id == 30 {
+                writer.commit().unwrap();
+            }
+        }
+        writer
+            .add_document(doc!(row_id => 101u64, text => "slow"))
+            .unwrap();
+        writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        assert_eq!(searcher.segment_readers().len(), 2);
+
+        let digest = |query: &dyn tantivy::query::Query, collector: PercentilesCollector| {
+            searcher.search(query, &collector).unwrap()
+        };
+        let collector = || PercentilesCollector::with_field("latency".to_string());
+        let mut all = digest(&AllQuery, collector());
+        assert_eq!(all.count(), 100);
+        assert_eq!(all.percentile(0.0), 1.0);
+        assert!((all.percentile(50.0) - 50.5).abs() < 1.0);
+        assert_eq!(all.percentile(100.0), 100.0);
+
+        let slow_query = QueryParser::for_index(&index, vec![text])
+            .parse_query("slow")
+            .unwrap();
+        let mut slow = digest(slow_query.as_ref(), collector());
+        assert_eq!(slow.count(), 50);
+        assert_eq!(slow.percentile(0.0), 51.0);
+        let deleted = Arc::new(RoaringTreemap::from_iter(11..=100u64));
+        let mut head = digest(&AllQuery, collector().with_deleted(deleted));
+        assert_eq!(head.count(), 10);
+        assert_eq!(head.percentile(100.0), 10.0);
+
+        // Text field has no percentiles.
+        assert!(searcher
+            .search(
+                &AllQuery,
+                &PercentilesCollector::with_field("text".to_string())
+            )
+            .is_err());
+    }
+}
@@ -1,9 +1,11 @@
 use std::sync::Arc;
 
-use roaring::RoaringBitmap;
+use roaring::{RoaringBitmap, RoaringTreemap};
 use tantivy::collector::{Collector, SegmentCollector};
-use tantivy::columnar::Column;
-use tantivy::{Score, SegmentReader};
+use tantivy::{DocId, Score, SegmentReader};
+
+use crate::common::constants::BITMAP_UNION_POOL;
+use crate::search::bridge::index_reader_row_id_mapping::{RowIdMappingCache, SegmentRowIdReader};
 
 /*
     Struct visualization.
@@ -25,11 +27,32 @@ use tantivy::{Score, SegmentReader};
 
 pub struct RowIdRoaringCollector {
     pub row_id_field: String,
+    pub deleted_row_ids: Option<Arc<RoaringTreemap>>,
+    pub row_id_mapping: Option<Arc<RowIdMappingCache>>,
 }
 
 impl RowIdRoaringCollector {
     pub fn with_field(row_id_field: String) -> RowIdRoaringCollector {
-        RowIdRoaringCollector { row_id_field }
+        RowIdRoaringCollector {
+            row_id_field,
+            deleted_row_ids: None,
+            row_id_mapping: None,
+        }
+    }
+
+    // `deleted_row_ids` is used to exclude lightweight deleted row_ids.
+    pub fn with_deleted(mut self, deleted_row_ids: Arc<RoaringTreemap>) -> RowIdRoaringCollector {
+        self.deleted_row_ids = Some(deleted_row_ids);
+        self
+    }
+
+    // `row_id_mapping` avoids opening `row_id` fast field for warmed segments.
+    pub fn with_row_id_mapping(
+        mut self,
+        row_id_mapping: Arc<RowIdMappingCache>,
+    ) -> RowIdRoaringCollector {
+        self.row_id_mapping = Some(row_id_mapping);
+        self
     }
 }
 
@@ -43,8 +66,13 @@ impl Collector for RowIdRoaringCollector {
         _segment_local_id: u32,
         segment_reader: &SegmentReader,
     ) -> tantivy::Result<Self::Child> {
-        let row_id_reader_ = segment_reader.fast_fields().u64(&self.row_id_field)?;
-        Ok(RowIdRoaringSegmentCollector::new(row_id_reader_))
+        let row_id_reader_ = SegmentRowIdReader::open(
+            segment_reader,
+            &self.row_id_field,
+            self.row_id_mapping.as_deref(),
+        )?;
+        Ok(RowIdRoaringSegmentCollector::new(row_id_reader_)
+            .with_deleted(self.deleted_row_ids.clone()))
     }
 
     fn requires_scoring(&self) -> bool {
@@ -56,30 +84,71 @@ impl Collector for RowIdRoaringCollector {
         &self,
         segment_row_ids: Vec<<Self::Child as SegmentCollector>::Fruit>,
     ) -> tantivy::Result<Self::Fruit> {
-        let mut row_id_roaring_bitmap = RoaringBitmap::new();
-        for segment_row_id_roaring_bitmap in segment_row_ids {
-            match Arc::try_unwrap(segment_row_id_roaring_bitmap) {
-                Ok(bitmap) => row_id_roaring_bitmap |= bitmap,
-                Err(arc_bitmap) => {
-                    // for multi reference, need call clone()
-                    row_id_roaring_bitmap |= arc_bitmap.as_ref().clone();
-                }
-            }
-        }
-        Ok(Arc::new(row_id_roaring_bitmap))
+        let segment_bitmaps: Vec<RoaringBitmap> = segment_row_ids
+            .into_iter()
+            .map(|segment_row_id_roaring_bitmap| {
+                // for multi reference, need call clone()
+                Arc::try_unwrap(segment_row_id_roaring_bitmap)
+                    .unwrap_or_else(|arc_bitmap| arc_bitmap.as_ref().clone())
+            })
+            .collect();
+        Ok(Arc::new(BITMAP_UNION_POOL.union(segment_bitmaps)))
     }
 }
 
 pub struct RowIdRoaringSegmentCollector {
-    row_id_reader: Column,
-    row_id_roaring_bitmap: Arc<RoaringBitmap>,
+    row_id_reader: SegmentRowIdReader,
+    row_id_roaring_bitmap: RoaringBitmap,
+    deleted_row_ids: Option<Arc<RoaringTreemap>>,
+    // Buffers reused across blocks.
+    block_row_ids: Vec<Option<u64>>,
+    block_values: Vec<u32>,
 }
 
 impl RowIdRoaringSegmentCollector {
-    pub fn new(row_id_reader: Column) -> Self {
+    pub fn new(row_id_reader: SegmentRowIdReader) -> Self {
         RowIdRoaringSegmentCollector {
             row_id_reader,
-            row_id_roaring_bitmap: Arc::new(RoaringBitmap::new()),
+            row_id_roaring_bitmap: RoaringBitmap::new(),
+            deleted_row_ids: None,
+            block_row_ids: Vec::new(),
+            block_values: Vec::new(),
+        }
+    }
+
+    pub fn with_deleted(mut self, deleted_row_ids: Option<Arc<RoaringTreemap>>) -> Self {
+        self.deleted_row_ids = deleted_row_ids;
+        self
+    }
+
+    // Row_id should fit in u32 and not be lightweight deleted.
+    #[inline]
+    fn accept(&self, row_id: u64) -> Option<u32> {
+        if let Some(deleted_row_ids) = &self.deleted_row_ids {
+            if deleted_row_ids.contains(row_id) {
+                return None;
+            }
+        }
+        u32::try_from(row_id).ok()
+    }
+
+    // Insert values into bitmap, runs of consecutive values are inserted as ranges,
+    // ascending values are appended without searching containers.
+    fn insert_values(bitmap: &mut RoaringBitmap, values: &[u32]) {
+        let mut idx = 0;
+        while idx < values.len() {
+            let start = values[idx];
+            let mut end = start;
+            while idx + 1 < values.len() && end != u32::MAX && values[idx + 1] == end + 1 {
+                end += 1;
+                idx += 1;
+            }
+            if end > start {
+                bitmap.insert_range(start..=end);
+            } else if !bitmap.push(start) {
+                bitmap.insert(start);
+            }
+            idx += 1;
         }
     }
 }
@@ -88,27 +157,28 @@ impl SegmentCollector for RowIdRoaringSegmentCollector {
     type Fruit = Arc<RoaringBitmap>;
 
     fn collect(&mut self, doc: u32, _score: Score) {
-        let row_ids: Vec<u32> = self
-            .row_id_reader
-            .values_for_doc(doc)
-            .filter_map(|row_id| {
-                if row_id <= u32::MAX as u64 {
-                    Some(row_id as u32)
-                } else {
-                    None
-                }
-            })
-            .collect();
+        self.collect_block(&[doc]);
+    }
 
-        match Arc::get_mut(&mut self.row_id_roaring_bitmap) {
-            Some(bitmap) => bitmap.extend(row_ids),
-            None => println!("Failed to get mutable reference to RoaringBitmap"),
-        }
+    // Row_id is single valued, only the first value of each doc is collected.
+    fn collect_block(&mut self, docs: &[DocId]) {
+        let mut block_row_ids = std::mem::take(&mut self.block_row_ids);
+        let mut block_values = std::mem::take(&mut self.block_values);
+        self.row_id_reader
+            .row_ids_for_block(docs, &mut block_row_ids);
+        block_values.clear();
+        block_values.extend(
+            block_row_ids
+                .iter()
+                .filter_map(|row_id| row_id.and_then(|row_id| self.accept(row_id))),
+        );
+        Self::insert_values(&mut self.row_id_roaring_bitmap, &block_values);
+        self.block_row_ids = block_row_ids;
+        self.block_values = block_values;
     }
 
     fn harvest(self) -> <Self as SegmentCollector>::Fruit {
-        // Arc::clone(&self.row_id_roaring_bitmap)
-        self.row_id_roaring_bitmap
+        Arc::new(self.row_id_roaring_bitmap)
     }
 }
 
@@ -178,6 +248,17 @@ mod tests {
         assert_eq!(merged, expected_bitmap.into());
     }
 
+    #[test]
+    fn test_insert_values() {
+        let mut bitmap = RoaringBitmap::new();
+        RowIdRoaringSegmentCollector::insert_values(&mut bitmap, &[1, 2, 3, 7, 9, 10]);
+        // Values lower than max fall back to insert.
+        RowIdRoaringSegmentCollector::insert_values(&mut bitmap, &[5, 4, u32::MAX - 1, u32::MAX]);
+        let expected: RoaringBitmap =
+            RoaringBitmap::from_iter(vec![1, 2, 3, 4, 5, 7, 9, 10, u32::MAX - 1, u32::MAX]);
+        assert_eq!(bitmap, expected);
+    }
+
     #[test]
     fn test_rowid_roaring_segment_collector() {
         let temp_path = TempDir::new().expect("Can't create temp path");
@@ -204,4 +285,34 @@ mod tests {
             .expect("Can't execute search.");
         assert_eq!(searched_bitmap_1.len(), 2);
     }
+
+    #[test]
+    fn test_rowid_roaring_collector_with_deleted() {
+        let temp_path = TempDir::new().expect("Can't create temp path");
+        let temp_path_str = temp_path.path().to_str().unwrap();
+        let index_reader = get_reader_from_index_path(temp_path_str);
+
+        let text_field = index_reader
+            .searcher()
+            .index()
+            .schema()
+            .get_field("text")
+            .unwrap();
+
+        let query_parser =
+            QueryParser::for_index(&index_reader.searcher().index(), vec![text_field]);
+        let text_query = query_parser
+            .parse_query("Ancient")
+            .expect("Can't parse query");
+        // Row 0 and row 4 contain `Ancient`, row 0 is lightweight deleted.
+        let row_id_collector = RowIdRoaringCollector::with_field("row_id".to_string())
+            .with_deleted(Arc::new(RoaringTreemap::from_iter(vec![0u64])));
+
+        let searched_bitmap = index_reader
+            .searcher()
+            .search(&text_query, &row_id_collector)
+            .expect("Can't execute search.");
+        assert_eq!(searched_bitmap.len(), 1);
+        assert!(searched_bitmap.contains(4));
+    }
 }
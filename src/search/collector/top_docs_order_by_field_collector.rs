@@ -0,0 +1,356 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fmt;
+use std::sync::Arc;
+
+use roaring::RoaringTreemap;
+use tantivy::collector::{Collector, SegmentCollector};
+use tantivy::columnar::Column;
+use tantivy::query::Weight;
+use tantivy::schema::FieldType;
+use tantivy::{DocId, Score, SegmentOrdinal, SegmentReader, TantivyError};
+
+use crate::search::bridge::index_reader_row_id_mapping::{RowIdMappingCache, SegmentRowIdReader};
+use crate::RowIdWithScore;
+
+// Variables in TopDocsOrderByField:
+// @`limit` restricts the number of search results.
+// @`field_name` is a numeric or date fast field used to order results, BM25 score is the tiebreak.
+// @`ascending` orders results by ascending field value, otherwise descending.
+// @`deleted_row_ids` is an Option type, row_ids in it are lightweight deleted and won't be collected.
+// @`row_id_mapping` is an Option type, warmed segments read row_id from it instead of fast field.
+// @`row_id_treemap` is an Option type, only row_ids in it will be collected.
+// @`row_id_range` is an Option type, only row_ids in range [start, end) will be collected.
+//
+// `TopDocs::order_by_fast_field` neither breaks ties by score nor supports row_id filters,
+// so ranking is done here with the same heap strategy as `TopDocsWithFilter64`.
+
+/// One collected doc with its ordering key, docs missing the field are ranked last.
+#[derive(Debug, Clone)]
+pub struct FieldOrderedDoc {
+    // Field value mapped to u64, larger is better whatever the order is.
+    pub sort_key: Option<u64>,
+    pub doc: RowIdWithScore,
+}
+
+impl Ord for FieldOrderedDoc {
+    // Same as `RowIdWithScore`, a better ranked doc is smaller, so BinaryHeap peeks the worst one.
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .sort_key
+            .cmp(&self.sort_key)
+            .then_with(|| self.doc.cmp(&other.doc))
+    }
+}
+
+impl PartialOrd for FieldOrderedDoc {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for FieldOrderedDoc {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for FieldOrderedDoc {}
+
+// Map field values to u64 while keeping their order.
+#[inline]
+fn i64_to_sortable_u64(value: i64) -> u64 {
+    (value as u64) ^ (1u64 << 63)
+}
+
+#[inline]
+fn f64_to_sortable_u64(value: f64) -> u64 {
+    let bits = value.to_bits();
+    if bits & (1u64 << 63) == 0 {
+        bits | (1u64 << 63)
+    } else {
+        !bits
+    }
+}
+
+/// Fast field column whose values are mapped to u64 keys keeping their order.
+pub(crate) enum SortKeyColumn {
+    U64(Column<u64>),
+    I64(Column<i64>),
+    F64(Column<f64>),
+    Date(Column<tantivy::DateTime>),
+}
+
+impl SortKeyColumn {
+    pub(crate) fn open(reader: &SegmentReader, field_name: &str) -> tantivy::Result<Self> {
+        let schema = reader.schema();
+        let field = schema.get_field(field_name)?;
+        let field_entry = schema.get_field_entry(field);
+        if !field_entry.is_fast() {
+            return Err(TantivyError::SchemaError(format!(
+                "Field `{}` is not a fast field.",
+                field_name
+            )));
+        }
+        let fast_fields = reader.fast_fields();
+        match field_entry.field_type() {
+            FieldType::U64(_) => Ok(SortKeyColumn::U64(fast_fields.u64(field_name)?)),
+            FieldType::I64(_) => Ok(SortKeyColumn::I64(fast_fields.i64(field_name)?)),
+            FieldType::F64(_) => Ok(SortKeyColumn::F64(fast_fields.f64(field_name)?)),
+            FieldType::Date(_) => Ok(SortKeyColumn::Date(fast_fields.date(field_name)?)),
+            _ => Err(TantivyError::SchemaError(format!(
+                "Field `{}` is not a numeric or date fast field.",
+                field_name
+            ))),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn sort_key(&self, doc: DocId) -> Option<u64> {
+        match self {
+            SortKeyColumn::U64(column) => column.first(doc),
+            SortKeyColumn::I64(column) => column.first(doc).map(i64_to_sortable_u64),
+            SortKeyColumn::F64(column) => column.first(doc).map(f64_to_sortable_u64),
+            SortKeyColumn::Date(column) => column
+                .first(doc)
+                .map(|date| i64_to_sortable_u64(date.into_timestamp_nanos())),
+        }
+    }
+}
+
+pub struct TopDocsOrderByField {
+    pub limit: usize,
+    pub field_name: String,
+    pub ascending: bool,
+    pub deleted_row_ids: Option<Arc<RoaringTreemap>>,
+    pub row_id_mapping: Option<Arc<RowIdMappingCache>>,
+    pub row_id_treemap: Option<Arc<RoaringTreemap>>,
+    pub row_id_range: Option<(u64, u64)>,
+}
+
+impl TopDocsOrderByField {
+    pub fn with_limit(limit: usize, field_name: String, ascending: bool) -> TopDocsOrderByField {
+        Self {
+            limit,
+            field_name,
+            ascending,
+            deleted_row_ids: None,
+            row_id_mapping: None,
+            row_id_treemap: None,
+            row_id_range: None,
+        }
+    }
+
+    // `row_id_bitmap` is used to mark alive row_ids.
+    pub fn with_alive(mut self, row_id_bitmap: Arc<RoaringTreemap>) -> TopDocsOrderByField {
+        self.row_id_treemap = Some(row_id_bitmap);
+        self
+    }
+
+    // `row_id_range` is used to mark alive row_id range, the range is [start, end)
+    pub fn with_range(mut self, row_id_range: (u64, u64)) -> TopDocsOrderByField {
+        self.row_id_range = Some(row_id_range);
+        self
+    }
+
+    // `deleted_row_ids` is used to exclude lightweight deleted row_ids.
+    pub fn with_deleted(mut self, deleted_row_ids: Arc<RoaringTreemap>) -> TopDocsOrderByField {
+        self.deleted_row_ids = Some(deleted_row_ids);
+        self
+    }
+
+    // `row_id_mapping` avoids opening `row_id` fast field for warmed segments.
+    pub fn with_row_id_mapping(
+        mut self,
+        row_id_mapping: Arc<RowIdMappingCache>,
+    ) -> TopDocsOrderByField {
+        self.row_id_mapping = Some(row_id_mapping);
+        self
+    }
+
+    #[inline]
+    fn is_collected(&self, row_id: u64) -> bool {
+        if let Some(deleted_row_ids) = &self.deleted_row_ids {
+            if deleted_row_ids.contains(row_id) {
+                return false;
+            }
+        }
+        if let Some(row_id_treemap) = &self.row_id_treemap {
+            if !row_id_treemap.contains(row_id) {
+                return false;
+            }
+        }
+        if let Some((start, end)) = self.row_id_range {
+            if !(start <= row_id && row_id < end) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl fmt::Debug for TopDocsOrderByField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "TopDocsOrderByField(limit:{}, field_name:{}, ascending:{}, row_ids_size:{}, row_id_range:{:?})",
+            self.limit,
+            self.field_name,
+            self.ascending,
+            self.row_id_treemap.as_ref().map(|treemap| treemap.len()).unwrap_or(0),
+            self.row_id_range
+        )
+    }
+}
+
+impl Collector for TopDocsOrderByField {
+    type Fruit = Vec<FieldOrderedDoc>;
+
+    type Child = OrderByFieldSegmentCollector; // won't use for current design.
+
+    fn for_segment(
+        &self,
+        _segment_local_id: SegmentOrdinal,
+        _reader: &SegmentReader,
+    ) -> tantivy::Result<Self::Child> {
+        Ok(OrderByFieldSegmentCollector())
+    }
+
+    // score is the tiebreak of field ordering.
+    fn requires_scoring(&self) -> bool {
+        true
+    }
+
+    fn merge_fruits(
+        &self,
+        child_fruits: Vec<Vec<FieldOrderedDoc>>,
+    ) -> tantivy::Result<Self::Fruit> {
+        if self.limit == 0 {
+            return Ok(Vec::new());
+        }
+        let mut top_collector: BinaryHeap<FieldOrderedDoc> = BinaryHeap::new();
+        for child_fruit in child_fruits {
+            for child in child_fruit {
+                if top_collector.len() < self.limit {
+                    top_collector.push(child);
+                } else if let Some(mut head) = top_collector.peek_mut() {
+                    if child < *head {
+                        *head = child;
+                    }
+                }
+            }
+        }
+        Ok(top_collector.into_sorted_vec())
+    }
+
+    // collector for each segment.
+    fn collect_segment(
+        &self,
+        weight: &dyn Weight,
+        segment_ord: SegmentOrdinal,
+        reader: &SegmentReader,
+    ) -> tantivy::Result<<Self::Child as SegmentCollector>::Fruit> {
+        if self.limit == 0 {
+            return Ok(Vec::new());
+        }
+        let mut heap: BinaryHeap<FieldOrderedDoc> = BinaryHeap::with_capacity(self.limit);
+
+        let row_id_field_reader =
+            SegmentRowIdReader::open(reader, "row_id", self.row_id_mapping.as_deref())?;
+        let sort_key_column = SortKeyColumn::open(reader, &self.field_name)?;
+        let alive_bitset = reader.alive_bitset();
+
+        // Ordering key isn't score, so docs can't be pruned by score threshold.
+        weight.for_each(reader, &mut |doc: DocId, score: Score| {
+            if let Some(alive_bitset) = alive_bitset {
+                if alive_bitset.is_deleted(doc) {
+                    return;
+                }
+            }
+            let row_id = row_id_field_reader.row_id(doc);
+            if !self.is_collected(row_id) {
+                return;
+            }
+            let sort_key = sort_key_column.sort_key(doc).map(|sort_key| {
+                if self.ascending {
+                    !sort_key
+                } else {
+                    sort_key
+                }
+            });
+            let item = FieldOrderedDoc {
+                sort_key,
+                doc: RowIdWithScore {
+                    row_id,
+                    score,
+                    seg_id: segment_ord,
+                    doc_id: doc,
+                    docs: vec![],
+                },
+            };
+            if heap.len() < self.limit {
+                heap.push(item);
+            } else if let Some(mut head) = heap.peek_mut() {
+                if item < *head {
+                    *head = item;
+                }
+            }
+        })?;
+        Ok(heap.into_vec())
+    }
+}
+
+pub struct OrderByFieldSegmentCollector();
+
+impl SegmentCollector for OrderByFieldSegmentCollector {
+    type Fruit = Vec<FieldOrderedDoc>;
+
+    fn collect(&mut self, _doc: DocId, _score: Score) {
+        unreachable!("segment collector is unused, `collect_segment` is overridden");
+    }
+
+    fn harvest(self) -> Vec<FieldOrderedDoc> {
+        unreachable!("segment collector is unused, `collect_segment` is overridden");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ordered_doc(sort_key: Option<u64>, score: f32, row_id: u64) -> FieldOrderedDoc {
+        FieldOrderedDoc {
+            sort_key,
+            doc: RowIdWithScore {
+                row_id,
+                score,
+                seg_id: 0,
+                doc_id: 0,
+                docs: vec![],
+            },
+        }
+    }
+
+    #[test]
+    fn test_sortable_u64() {
+        assert!(i64_to_sortable_u64(-5) < i64_to_sortable_u64(0));
+        assert!(i64_to_sortable_u64(0) < i64_to_sortable_u64(7));
+        assert!(f64_to_sortable_u64(-1.5) < f64_to_sortable_u64(-0.5));
+        assert!(f64_to_sortable_u64(-0.5) < f64_to_sortable_u64(0.0));
+        assert!(f64_to_sortable_u64(0.0) < f64_to_sortable_u64(2.5));
+    }
+
+    #[test]
+    fn test_field_ordered_doc_ordering() {
+        let mut docs = vec![
+            ordered_doc(Some(1), 3.0, 0),
+            ordered_doc(None, 9.0, 1),
+            ordered_doc(Some(5), 1.0, 2),
+            ordered_doc(Some(5), 2.0, 3),
+        ];
+        docs.sort();
+        // Larger key first, score breaks the tie, missing key is ranked last.
+        let row_ids: Vec<u64> = docs.iter().map(|doc| doc.doc.row_id).collect();
+        assert_eq!(row_ids, vec![3, 2, 0, 1]);
+    }
+}
@@ -2,12 +2,13 @@ use std::collections::BinaryHeap;
 use std::sync::Arc;
 use std::{cmp, fmt};
 
-use roaring::RoaringBitmap;
+use roaring::{RoaringBitmap, RoaringTreemap};
 use tantivy::collector::{Collector, SegmentCollector};
 use tantivy::query::Weight;
 use tantivy::schema::{Field, Value};
 use tantivy::{DocAddress, DocId, Score, Searcher, SegmentOrdinal, SegmentReader, TantivyDocument};
 
+use crate::search::bridge::index_reader_row_id_mapping::{RowIdMappingCache, SegmentRowIdReader};
 use crate::RowIdWithScore;
 
 // Class Inheritance Diagram:
@@ -18,6 +19,8 @@ use crate::RowIdWithScore;
 //
 // Variables in TopDocWithFilter:
 // @`limit` restricts the number of search results.
+// @`deleted_row_ids` is an Option type, row_ids in it are lightweight deleted and won't be collected.
+// @`row_id_mapping` is an Option type, warmed segments read row_id from it instead of fast field.
 // @`searcher` is an Option type used to read the original text stored in the index.
 // @`text_fields` is an Option type from which the `searcher` reads the original text stored in the index.
 // @`need_text` indicates whether the original text needs to be read from the index. If this is true, but either `searcher` or `text_fields` is None, the original text will not be retrieved.
@@ -26,6 +29,8 @@ static INITIAL_HEAP_SIZE: usize = 1000;
 
 pub struct TopDocsWithFilter {
     pub limit: usize,
+    pub deleted_row_ids: Option<Arc<RoaringTreemap>>,
+    pub row_id_mapping: Option<Arc<RowIdMappingCache>>,
     pub row_id_bitmap: Option<Arc<RoaringBitmap>>,
     pub searcher: Option<Searcher>,
     pub text_fields: Option<Vec<Field>>,
@@ -40,6 +45,8 @@ impl TopDocsWithFilter {
         Self {
             limit,
             row_id_bitmap: None,
+            deleted_row_ids: None,
+            row_id_mapping: None,
             searcher: None,
             text_fields: None,
             need_text: false,
@@ -53,6 +60,21 @@ impl TopDocsWithFilter {
         self
     }
 
+    // `deleted_row_ids` is used to exclude lightweight deleted row_ids.
+    pub fn with_deleted(mut self, deleted_row_ids: Arc<RoaringTreemap>) -> TopDocsWithFilter {
+        self.deleted_row_ids = Some(deleted_row_ids);
+        self
+    }
+
+    // `row_id_mapping` avoids opening `row_id` fast field for warmed segments.
+    pub fn with_row_id_mapping(
+        mut self,
+        row_id_mapping: Arc<RowIdMappingCache>,
+    ) -> TopDocsWithFilter {
+        self.row_id_mapping = Some(row_id_mapping);
+        self
+    }
+
     // `searcher` is used to search origin text content.
     pub fn with_searcher(mut self, searcher: Searcher) -> TopDocsWithFilter {
         self.searcher = Some(searcher.clone());
@@ -176,16 +198,18 @@ impl Collector for TopDocsWithFilter {
         let heap_len = cmp::min(self.limit, self.initial_heap_size);
         let mut heap: BinaryHeap<RowIdWithScore> = BinaryHeap::with_capacity(heap_len);
 
-        let row_id_field_reader = reader
-            .fast_fields()
-            .u64("row_id")
-            .unwrap()
-            .first_or_default_col(0);
+        let row_id_field_reader =
+            SegmentRowIdReader::open(reader, "row_id", self.row_id_mapping.as_deref())?;
 
         if let Some(alive_bitset) = reader.alive_bitset() {
             let mut threshold = Score::MIN;
             weight.for_each_pruning(threshold, reader, &mut |doc, score| {
-                let row_id = row_id_field_reader.get_val(doc);
+                let row_id = row_id_field_reader.row_id(doc);
+                if let Some(deleted_row_ids) = &self.deleted_row_ids {
+                    if deleted_row_ids.contains(row_id) {
+                        return threshold;
+                    }
+                }
                 if self.row_id_bitmap.is_some()
                     && !self.row_id_bitmap.clone().unwrap().contains(row_id as u32)
                 {
@@ -214,7 +238,12 @@ impl Collector for TopDocsWithFilter {
             })?;
         } else {
             weight.for_each_pruning(Score::MIN, reader, &mut |doc, score| {
-                let row_id = row_id_field_reader.get_val(doc);
+                let row_id = row_id_field_reader.row_id(doc);
+                if let Some(deleted_row_ids) = &self.deleted_row_ids {
+                    if deleted_row_ids.contains(row_id) {
+                        return Score::MIN;
+                    }
+                }
                 if self.row_id_bitmap.is_some()
                     && !self.row_id_bitmap.clone().unwrap().contains(row_id as u32)
                 {
@@ -1,4 +1,5 @@
-use std::collections::BinaryHeap;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap};
 use std::sync::Arc;
 use std::{cmp, fmt};
 
@@ -6,8 +7,13 @@ use roaring::RoaringTreemap;
 use tantivy::collector::{Collector, SegmentCollector};
 use tantivy::query::Weight;
 use tantivy::schema::{Field, Value};
-use tantivy::{DocAddress, DocId, Score, Searcher, SegmentOrdinal, SegmentReader, TantivyDocument};
+use tantivy::{
+    DocAddress, DocId, Score, Searcher, SegmentOrdinal, SegmentReader, TantivyDocument,
+    TantivyError,
+};
 
+use crate::search::bridge::index_reader_row_id_mapping::{RowIdMappingCache, SegmentRowIdReader};
+use crate::search::collector::top_docs_order_by_field_collector::SortKeyColumn;
 use crate::RowIdWithScore;
 
 // Class Inheritance Diagram:
@@ -18,20 +24,44 @@ use crate::RowIdWithScore;
 //
 // Variables in TopDocWithFilter:
 // @`limit` restricts the number of search results.
+// @`offset` skips the first results of ranking, used for pagination.
+// @`search_after` is an Option type `(score, row_id)` cursor, only results ranked after it will be collected.
+// @`deleted_row_ids` is an Option type, row_ids in it are lightweight deleted and won't be collected.
+// @`row_id_mapping` is an Option type, warmed segments read row_id from it instead of fast field.
 // @`searcher` is an Option type used to read the original text stored in the index.
 // @`text_fields` is an Option type from which the `searcher` reads the original text stored in the index.
 // @`need_text` indicates whether the original text needs to be read from the index. If this is true, but either `searcher` or `text_fields` is None, the original text will not be retrieved.
+// @`diversify` is an Option type `(field_name, max_per_key)`, at most `max_per_key` results are collected for each value of numeric or date fast field `field_name`, docs missing the field share one key. It needs `searcher` to read keys while merging segments.
 
 static INITIAL_HEAP_SIZE: usize = 1000;
 
+/// Row ids matched by a query, counted while its top docs are collected.
+#[derive(Debug, Clone, Default)]
+pub struct TotalHits {
+    pub row_ids: RoaringTreemap,
+    /// Whether every matched doc is counted, otherwise `row_ids` are a lower bound.
+    pub exact: bool,
+}
+
+impl TotalHits {
+    pub fn count(&self) -> u64 {
+        self.row_ids.len()
+    }
+}
+
 pub struct TopDocsWithFilter64 {
     pub limit: usize,
+    pub offset: usize,
+    pub search_after: Option<(Score, u64)>,
+    pub deleted_row_ids: Option<Arc<RoaringTreemap>>,
+    pub row_id_mapping: Option<Arc<RowIdMappingCache>>,
     pub row_id_treemap: Option<Arc<RoaringTreemap>>,
     pub row_id_range: Option<(u64, u64)>,
     pub searcher: Option<Searcher>,
     pub text_fields: Option<Vec<Field>>,
     pub need_text: bool,
     pub initial_heap_size: usize,
+    pub diversify: Option<(String, usize)>,
 }
 
 impl TopDocsWithFilter64 {
@@ -40,15 +70,32 @@ impl TopDocsWithFilter64 {
         // assert!(limit >= 1, "Limit must be strictly greater than 0.");
         Self {
             limit,
+            offset: 0,
+            search_after: None,
             row_id_treemap: None,
+            deleted_row_ids: None,
+            row_id_mapping: None,
             row_id_range: None,
             searcher: None,
             text_fields: None,
             need_text: false,
             initial_heap_size: INITIAL_HEAP_SIZE,
+            diversify: None,
         }
     }
 
+    // number of ranked results to skip.
+    pub fn with_offset(mut self, offset: usize) -> TopDocsWithFilter64 {
+        self.offset = offset;
+        self
+    }
+
+    // `search_after` is the `(score, row_id)` of last result in previous page.
+    pub fn with_search_after(mut self, search_after: (Score, u64)) -> TopDocsWithFilter64 {
+        self.search_after = Some(search_after);
+        self
+    }
+
     // `row_id_bitmap` is used to mark aive row_ids.
     pub fn with_alive(mut self, row_id_bitmap: Arc<RoaringTreemap>) -> TopDocsWithFilter64 {
         self.row_id_treemap = Some(Arc::clone(&row_id_bitmap));
@@ -61,6 +108,21 @@ impl TopDocsWithFilter64 {
         self
     }
 
+    // `deleted_row_ids` is used to exclude lightweight deleted row_ids.
+    pub fn with_deleted(mut self, deleted_row_ids: Arc<RoaringTreemap>) -> TopDocsWithFilter64 {
+        self.deleted_row_ids = Some(deleted_row_ids);
+        self
+    }
+
+    // `row_id_mapping` avoids opening `row_id` fast field for warmed segments.
+    pub fn with_row_id_mapping(
+        mut self,
+        row_id_mapping: Arc<RowIdMappingCache>,
+    ) -> TopDocsWithFilter64 {
+        self.row_id_mapping = Some(row_id_mapping);
+        self
+    }
+
     // `searcher` is used to search origin text content.
     pub fn with_searcher(mut self, searcher: Searcher) -> TopDocsWithFilter64 {
         self.searcher = Some(searcher.clone());
@@ -86,6 +148,12 @@ impl TopDocsWithFilter64 {
         self
     }
 
+    // keep at most `max_per_key` results for each value of `field_name`.
+    pub fn with_diversify(mut self, field_name: String, max_per_key: usize) -> TopDocsWithFilter64 {
+        self.diversify = Some((field_name, max_per_key));
+        self
+    }
+
     pub fn merge_fruits(
         &self,
         children: Vec<Vec<RowIdWithScore>>,
@@ -93,10 +161,14 @@ impl TopDocsWithFilter64 {
         if self.limit == 0 {
             return Ok(Vec::new());
         }
+        if let Some((field_name, max_per_key)) = &self.diversify {
+            return self.merge_diversified_fruits(children, field_name, *max_per_key);
+        }
+        let heap_len = self.limit + self.offset;
         let mut top_collector = BinaryHeap::new();
         for child_fruit in children {
             for child in child_fruit {
-                if top_collector.len() < self.limit {
+                if top_collector.len() < heap_len {
                     top_collector.push(child);
                 } else if let Some(mut head) = top_collector.peek_mut() {
                     if head.score < child.score {
@@ -105,7 +177,235 @@ impl TopDocsWithFilter64 {
                 }
             }
         }
-        Ok(top_collector.into_sorted_vec())
+        Ok(top_collector
+            .into_sorted_vec()
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit)
+            .collect())
+    }
+
+    // Each segment keeps its best docs of every key, so ranking all of them and skipping docs
+    // whose key is full again gives the same results as diversifying all matched docs.
+    fn merge_diversified_fruits(
+        &self,
+        children: Vec<Vec<RowIdWithScore>>,
+        field_name: &str,
+        max_per_key: usize,
+    ) -> tantivy::Result<Vec<RowIdWithScore>> {
+        let searcher: &Searcher = self.searcher.as_ref().ok_or_else(|| {
+            TantivyError::InvalidArgument("Searcher is required to diversify results.".to_string())
+        })?;
+        let mut candidates: Vec<RowIdWithScore> = children.into_iter().flatten().collect();
+        candidates.sort();
+
+        let heap_len = self.limit + self.offset;
+        let mut key_columns: HashMap<SegmentOrdinal, SortKeyColumn> = HashMap::new();
+        let mut key_counts: HashMap<Option<u64>, usize> = HashMap::new();
+        let mut top_docs: Vec<RowIdWithScore> = Vec::with_capacity(heap_len);
+        for candidate in candidates {
+            if top_docs.len() == heap_len {
+                break;
+            }
+            let key_column: &SortKeyColumn = match key_columns.entry(candidate.seg_id) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(SortKeyColumn::open(
+                    searcher.segment_reader(candidate.seg_id),
+                    field_name,
+                )?),
+            };
+            let key_count: &mut usize = key_counts
+                .entry(key_column.sort_key(candidate.doc_id))
+                .or_insert(0);
+            if *key_count < max_per_key {
+                *key_count += 1;
+                top_docs.push(candidate);
+            }
+        }
+        Ok(top_docs
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit)
+            .collect())
+    }
+
+    // Collect best `max_per_key` docs of each key in a segment, and keep the best `limit + offset`
+    // of them. Docs of a full key don't raise the threshold, so docs can't be pruned by score,
+    // and `total_hits` are always exact.
+    fn collect_diversified_segment(
+        &self,
+        weight: &dyn Weight,
+        segment_ord: SegmentOrdinal,
+        reader: &SegmentReader,
+        field_name: &str,
+        max_per_key: usize,
+        mut total_hits: Option<&mut TotalHits>,
+    ) -> tantivy::Result<Vec<RowIdWithScore>> {
+        if total_hits.is_none() && (self.limit == 0 || max_per_key == 0) {
+            return Ok(Vec::new());
+        }
+        let key_column: SortKeyColumn = SortKeyColumn::open(reader, field_name)?;
+        let row_id_field_reader =
+            SegmentRowIdReader::open(reader, "row_id", self.row_id_mapping.as_deref())?;
+        let alive_bitset = reader.alive_bitset();
+
+        // The worst doc of a key is on top of its heap.
+        let mut key_docs: HashMap<Option<u64>, BinaryHeap<RowIdWithScore>> = HashMap::new();
+        weight.for_each(reader, &mut |doc: DocId, score: Score| {
+            if let Some(alive_bitset) = alive_bitset {
+                if alive_bitset.is_deleted(doc) {
+                    return;
+                }
+            }
+            let row_id = row_id_field_reader.row_id(doc);
+            if !self.is_collected(row_id) {
+                return;
+            }
+            if let Some(total_hits) = total_hits.as_mut() {
+                total_hits.row_ids.insert(row_id);
+            }
+            if !self.is_after_cursor(score, row_id) {
+                return;
+            }
+            let heap_item = RowIdWithScore {
+                row_id,
+                score,
+                seg_id: segment_ord,
+                doc_id: doc,
+                docs: vec![],
+            };
+            let docs: &mut BinaryHeap<RowIdWithScore> =
+                key_docs.entry(key_column.sort_key(doc)).or_default();
+            if docs.len() < max_per_key {
+                docs.push(heap_item);
+            } else if let Some(mut head) = docs.peek_mut() {
+                if heap_item < *head {
+                    *head = heap_item;
+                }
+            }
+        })?;
+
+        if let Some(total_hits) = total_hits {
+            total_hits.exact = true;
+        }
+        let mut top_docs: Vec<RowIdWithScore> = key_docs
+            .into_values()
+            .flat_map(|docs| docs.into_vec())
+            .collect();
+        top_docs.sort();
+        top_docs.truncate(self.limit + self.offset);
+        // Only read texts of docs kept.
+        for top_doc in top_docs.iter_mut() {
+            top_doc.docs = self.extract_doc_text(top_doc.doc_id, segment_ord);
+        }
+        Ok(top_docs)
+    }
+
+    // Collect best `limit + offset` docs of a segment, once heap is full docs not scored above
+    // its worst doc are pruned. Row ids of matched docs are counted into `total_hits` if given,
+    // nothing is pruned if an exact count is requested.
+    fn collect_top_docs(
+        &self,
+        weight: &dyn Weight,
+        segment_ord: SegmentOrdinal,
+        reader: &SegmentReader,
+        mut total_hits: Option<&mut TotalHits>,
+    ) -> tantivy::Result<Vec<RowIdWithScore>> {
+        if let Some((field_name, max_per_key)) = &self.diversify {
+            return self.collect_diversified_segment(
+                weight,
+                segment_ord,
+                reader,
+                field_name,
+                *max_per_key,
+                total_hits,
+            );
+        }
+        // REFINE: need a more efficient way to initialize binary-heap.
+        let heap_len = cmp::min(self.limit, self.initial_heap_size) + self.offset;
+        let mut heap: BinaryHeap<RowIdWithScore> = BinaryHeap::with_capacity(heap_len);
+
+        let row_id_field_reader =
+            SegmentRowIdReader::open(reader, "row_id", self.row_id_mapping.as_deref())?;
+        let alive_bitset = reader.alive_bitset();
+        let prune: bool = total_hits
+            .as_ref()
+            .map_or(true, |total_hits| !total_hits.exact);
+
+        // Score of the worst doc in a full heap, docs not scored above it can't be collected.
+        let mut threshold = Score::MIN;
+        weight.for_each_pruning(Score::MIN, reader, &mut |doc, score| {
+            if let Some(alive_bitset) = alive_bitset {
+                if alive_bitset.is_deleted(doc) {
+                    return if prune { threshold } else { Score::MIN };
+                }
+            }
+            let row_id = row_id_field_reader.row_id(doc);
+            if !self.is_collected(row_id) {
+                return if prune { threshold } else { Score::MIN };
+            }
+            if let Some(total_hits) = total_hits.as_mut() {
+                total_hits.row_ids.insert(row_id);
+            }
+            if score > threshold && self.is_after_cursor(score, row_id) {
+                let heap_item = RowIdWithScore {
+                    row_id,
+                    score,
+                    seg_id: segment_ord,
+                    doc_id: doc,
+                    docs: self.extract_doc_text(doc, segment_ord),
+                };
+                if heap.len() < heap_len {
+                    heap.push(heap_item);
+                } else if let Some(mut head) = heap.peek_mut() {
+                    *head = heap_item;
+                }
+                if heap.len() == heap_len {
+                    threshold = heap.peek().map(|el| el.score).unwrap_or(Score::MIN);
+                }
+            }
+            if prune {
+                threshold
+            } else {
+                Score::MIN
+            }
+        })?;
+        if let Some(total_hits) = total_hits {
+            // Nothing was pruned until threshold is raised.
+            total_hits.exact = !prune || threshold == Score::MIN;
+        }
+        Ok(heap.into_sorted_vec())
+    }
+
+    #[inline]
+    fn is_collected(&self, row_id: u64) -> bool {
+        if let Some(deleted_row_ids) = &self.deleted_row_ids {
+            if deleted_row_ids.contains(row_id) {
+                return false;
+            }
+        }
+        if let Some(row_id_treemap) = &self.row_id_treemap {
+            if !row_id_treemap.contains(row_id) {
+                return false;
+            }
+        }
+        if let Some((start, end)) = self.row_id_range {
+            if !(start <= row_id && row_id < end) {
+                return false;
+            }
+        }
+        true
+    }
+
+    // Results are ranked by score desc and row_id asc, check whether doc is ranked after cursor.
+    #[inline]
+    fn is_after_cursor(&self, score: Score, row_id: u64) -> bool {
+        match self.search_after {
+            Some((after_score, after_row_id)) => {
+                score < after_score || (score == after_score && row_id > after_row_id)
+            }
+            None => true,
+        }
     }
 
     #[inline]
@@ -139,15 +439,18 @@ impl fmt::Debug for TopDocsWithFilter64 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "TopDocsWithFilter64(limit:{}, row_ids_size:{}, row_id_range_start:{} row_id_range_end:{} text_fields_is_some:{}, searcher_is_some:{}, need_text:{}, initial_heap_size:{})",
+            "TopDocsWithFilter64(limit:{}, offset:{}, search_after:{:?}, row_ids_size:{}, row_id_range_start:{} row_id_range_end:{} text_fields_is_some:{}, searcher_is_some:{}, need_text:{}, initial_heap_size:{}, diversify:{:?})",
             self.limit,
+            self.offset,
+            self.search_after,
             if self.row_id_treemap.is_some() {self.row_id_treemap.clone().unwrap().len()} else {0},
             if self.row_id_range.is_some() {self.row_id_range.clone().unwrap().0} else {0},
             if self.row_id_range.is_some() {self.row_id_range.clone().unwrap().1} else {0},
             self.text_fields.is_some(),
             self.searcher.is_some(),
             self.need_text,
-            self.initial_heap_size
+            self.initial_heap_size,
+            self.diversify
         )
     }
 }
@@ -182,90 +485,77 @@ impl Collector for TopDocsWithFilter64 {
         segment_ord: SegmentOrdinal,
         reader: &SegmentReader,
     ) -> tantivy::Result<<Self::Child as SegmentCollector>::Fruit> {
-        // REFINE: need a more efficient way to initialize binary-heap.
-        let heap_len = cmp::min(self.limit, self.initial_heap_size);
-        let mut heap: BinaryHeap<RowIdWithScore> = BinaryHeap::with_capacity(heap_len);
+        self.collect_top_docs(weight, segment_ord, reader, None)
+    }
+}
 
-        let row_id_field_reader = reader
-            .fast_fields()
-            .u64("row_id")
-            .unwrap()
-            .first_or_default_col(0);
-
-        if let Some(alive_bitset) = reader.alive_bitset() {
-            let mut threshold = Score::MIN;
-            weight.for_each_pruning(threshold, reader, &mut |doc, score| {
-                let row_id = row_id_field_reader.get_val(doc);
-                if self.row_id_treemap.is_some()
-                    && !self.row_id_treemap.clone().unwrap().contains(row_id)
-                {
-                    return threshold;
-                }
-                if self.row_id_range.is_some()
-                    && !(self.row_id_range.clone().unwrap().0 <= row_id
-                        && row_id < self.row_id_range.clone().unwrap().1)
-                {
-                    return threshold;
-                }
-                if alive_bitset.is_deleted(doc) {
-                    return threshold;
-                }
-                let heap_item = RowIdWithScore {
-                    row_id,
-                    score,
-                    seg_id: segment_ord,
-                    doc_id: doc,
-                    docs: self.extract_doc_text(doc, segment_ord),
-                };
-                if heap.len() < heap_len {
-                    heap.push(heap_item);
-                    if heap.len() == heap_len {
-                        threshold = heap.peek().map(|el| el.score).unwrap_or(Score::MIN);
-                    }
-                    return threshold;
-                }
-                *heap.peek_mut().unwrap() = heap_item;
-                threshold = heap.peek().map(|el| el.score).unwrap_or(Score::MIN);
-                threshold
-            })?;
-        } else {
-            weight.for_each_pruning(Score::MIN, reader, &mut |doc, score| {
-                let row_id = row_id_field_reader.get_val(doc);
-                if self.row_id_treemap.is_some()
-                    && !self.row_id_treemap.clone().unwrap().contains(row_id)
-                {
-                    return Score::MIN;
-                }
-                if self.row_id_range.is_some()
-                    && !(self.row_id_range.clone().unwrap().0 <= row_id
-                        && row_id < self.row_id_range.clone().unwrap().1)
-                {
-                    return Score::MIN;
-                }
-                let heap_item = RowIdWithScore {
-                    row_id,
-                    score,
-                    seg_id: segment_ord,
-                    doc_id: doc,
-                    docs: self.extract_doc_text(doc, segment_ord),
-                };
-                if heap.len() < heap_len {
-                    heap.push(heap_item);
-                    // REFINE: the threshold is suboptimal for heap.len == heap_len
-                    if heap.len() == heap_len {
-                        return heap.peek().map(|el| el.score).unwrap_or(Score::MIN);
-                    }
-                    return Score::MIN;
-                }
-                if let Some(mut head) = heap.peek_mut() {
-                    *head = heap_item;
-                } else {
-                    // limit size may be equal with zero.
-                }
-                heap.peek().map(|el| el.score).unwrap_or(Score::MIN)
-            })?;
+/// `TopDocsWithFilter64` which also counts total hits in the same pass, so hosts can show
+/// "1-10 of ~12,400" without running another count query.
+/// Total hits are row ids of docs matching the query and passing filters, whatever `offset`
+/// and `search_after` are. A row id matched by docs of several segments is counted once.
+/// Docs which can't rank in top docs are pruned by score, so total hits are a lower bound once
+/// a segment fills its heap. With `exact`, no doc is pruned and every matched doc is scored.
+pub struct TopDocsWithTotalHits64 {
+    pub top_docs: TopDocsWithFilter64,
+    pub exact: bool,
+}
+
+impl TopDocsWithTotalHits64 {
+    pub fn new(top_docs: TopDocsWithFilter64, exact: bool) -> TopDocsWithTotalHits64 {
+        Self { top_docs, exact }
+    }
+}
+
+impl Collector for TopDocsWithTotalHits64 {
+    type Fruit = (Vec<RowIdWithScore>, TotalHits);
+
+    type Child = TotalHitsSegmentCollector; // won't use for current design.
+
+    // won't use for current design.
+    fn for_segment(
+        &self,
+        _segment_local_id: SegmentOrdinal,
+        _reader: &SegmentReader,
+    ) -> tantivy::Result<Self::Child> {
+        Ok(TotalHitsSegmentCollector())
+    }
+
+    // won't use for current design.
+    fn requires_scoring(&self) -> bool {
+        true
+    }
+
+    fn merge_fruits(
+        &self,
+        child_fruits: Vec<(Vec<RowIdWithScore>, TotalHits)>,
+    ) -> tantivy::Result<Self::Fruit> {
+        let mut total_hits: TotalHits = TotalHits {
+            row_ids: RoaringTreemap::new(),
+            exact: true,
+        };
+        let mut children: Vec<Vec<RowIdWithScore>> = Vec::with_capacity(child_fruits.len());
+        for (top_docs, segment_hits) in child_fruits {
+            total_hits.row_ids |= segment_hits.row_ids;
+            total_hits.exact &= segment_hits.exact;
+            children.push(top_docs);
         }
-        Ok(heap.into_sorted_vec())
+        Ok((self.top_docs.merge_fruits(children)?, total_hits))
+    }
+
+    fn collect_segment(
+        &self,
+        weight: &dyn Weight,
+        segment_ord: SegmentOrdinal,
+        reader: &SegmentReader,
+    ) -> tantivy::Result<<Self::Child as SegmentCollector>::Fruit> {
+        let mut total_hits: TotalHits = TotalHits {
+            row_ids: RoaringTreemap::new(),
+            exact: self.exact,
+        };
+        let top_docs: Vec<RowIdWithScore> =
+            self.top_docs
+                .collect_top_docs(weight, segment_ord, reader, Some(&mut total_hits))?;
+        Ok((top_docs, total_hits))
     }
 }
 
@@ -283,3 +573,176 @@ impl SegmentCollector for TopScoreSegmentCollector {
         vec![]
     }
 }
+
+pub struct TotalHitsSegmentCollector();
+
+impl SegmentCollector for TotalHitsSegmentCollector {
+    type Fruit = (Vec<RowIdWithScore>, TotalHits);
+
+    fn collect(&mut self, _doc: DocId, _score: Score) {
+        unreachable!("segment collector is unused, `collect_segment` is overridden");
+    }
+
+    fn harvest(self) -> (Vec<RowIdWithScore>, TotalHits) {
+        unreachable!("segment collector is unused, `collect_segment` is overridden");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tantivy::indexer::NoMergePolicy;
+    use tantivy::query::QueryParser;
+    use tantivy::schema::{Schema, FAST, INDEXED, TEXT};
+    use tantivy::{doc, Index};
+
+    #[test]
+    fn test_diversify() {
+        let mut schema_builder = Schema::builder();
+        let row_id = schema_builder.add_u64_field("row_id", FAST | INDEXED);
+        let domain = schema_builder.add_u64_field("domain", FAST);
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut writer = index.writer_with_num_threads(1, 15_000_000).unwrap();
+        writer.set_merge_policy(Box::new(NoMergePolicy));
+        // Docs of domain 1 are split in two segments, more `apple` ranks higher.
+        writer
+            .add_document(doc!(row_id => 0u64, domain => 1u64, text => "apple apple apple"))
+            .unwrap();
+        writer
+            .add_document(doc!(row_id => 1u64, domain => 1u64, text => "apple apple"))
+            .unwrap();
+        writer
+            .add_document(doc!(row_id => 2u64, domain => 1u64, text => "apple"))
+            .unwrap();
+        writer.commit().unwrap();
+        writer
+            .add_document(doc!(row_id => 3u64, domain => 1u64, text => "apple apple apple apple"))
+            .unwrap();
+        writer
+            .add_document(doc!(row_id => 4u64, domain => 2u64, text => "apple"))
+            .unwrap();
+        writer
+            .add_document(doc!(row_id => 5u64, text => "apple"))
+            .unwrap();
+        writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        assert_eq!(searcher.segment_readers().len(), 2);
+
+        let query = QueryParser::for_index(&index, vec![text])
+            .parse_query("apple")
+            .unwrap();
+        let search = |collector: TopDocsWithFilter64| -> Vec<u64> {
+            searcher
+                .search(&query, &collector.with_searcher(searcher.clone()))
+                .unwrap()
+                .iter()
+                .map(|doc| doc.row_id)
+                .collect()
+        };
+
+        assert_eq!(search(TopDocsWithFilter64::with_limit(10)).len(), 6);
+        let diversified =
+            search(TopDocsWithFilter64::with_limit(10).with_diversify("domain".to_string(), 2));
+        assert_eq!(diversified[..2], [3, 0]);
+        let mut row_ids = diversified.clone();
+        row_ids.sort();
+        assert_eq!(row_ids, vec![0, 3, 4, 5]);
+        assert_eq!(
+            search(TopDocsWithFilter64::with_limit(1).with_diversify("domain".to_string(), 1)),
+            vec![3]
+        );
+
+        // Keys are read with searcher while merging segments.
+        let collector = TopDocsWithFilter64::with_limit(10).with_diversify("domain".to_string(), 2);
+        assert!(collector.merge_fruits(vec![vec![]]).is_err());
+        // Text field can't be used to diversify results.
+        assert!(searcher
+            .search(
+                &query,
+                &TopDocsWithFilter64::with_limit(10).with_diversify("text".to_string(), 2)
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_total_hits() {
+        let mut schema_builder = Schema::builder();
+        let row_id = schema_builder.add_u64_field("row_id", FAST | INDEXED);
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut writer = index.writer_with_num_threads(1, 15_000_000).unwrap();
+        writer.set_merge_policy(Box::new(NoMergePolicy));
+        for (id, body) in [
+            (0u64, "apple apple apple"),
+            (1, "apple apple"),
+            (2, "apple"),
+            (3, "banana"),
+        ] {
+            writer
+                .add_document(doc!(row_id => id, text => body))
+                .unwrap();
+        }
+        writer.commit().unwrap();
+        // Row id 1 is matched in both segments.
+        for id in [1u64, 4, 5] {
+            writer
+                .add_document(doc!(row_id => id, text => "apple"))
+                .unwrap();
+        }
+        writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        assert_eq!(searcher.segment_readers().len(), 2);
+
+        let query = QueryParser::for_index(&index, vec![text])
+            .parse_query("apple")
+            .unwrap();
+        let search = |top_docs: TopDocsWithFilter64, exact: bool| {
+            searcher
+                .search(&query, &TopDocsWithTotalHits64::new(top_docs, exact))
+                .unwrap()
+        };
+
+        let (top_docs, total_hits) = search(TopDocsWithFilter64::with_limit(2), true);
+        assert_eq!(top_docs.len(), 2);
+        assert!(total_hits.exact);
+        assert_eq!(
+            total_hits.row_ids.iter().collect::<Vec<u64>>(),
+            vec![0, 1, 2, 4, 5]
+        );
+
+        // Pagination doesn't change total hits, filters do.
+        let deleted: Arc<RoaringTreemap> = Arc::new([4u64].into_iter().collect());
+        let (top_docs, total_hits) = search(
+            TopDocsWithFilter64::with_limit(2)
+                .with_offset(1)
+                .with_deleted(deleted),
+            true,
+        );
+        assert_eq!(top_docs.len(), 2);
+        assert_eq!(total_hits.count(), 4);
+        let (top_docs, total_hits) = search(
+            TopDocsWithFilter64::with_limit(10).with_search_after((Score::MAX, 0)),
+            false,
+        );
+        assert_eq!(top_docs.len(), 5);
+        assert!(total_hits.exact);
+        assert_eq!(total_hits.count(), 5);
+
+        // Docs which can't rank are pruned once heap is full, total hits are a lower bound.
+        let (top_docs, total_hits) = search(TopDocsWithFilter64::with_limit(1), false);
+        assert_eq!(top_docs[0].row_id, 0);
+        assert!(!total_hits.exact);
+        assert!((1..=5).contains(&total_hits.count()));
+
+        // Diversified docs are never pruned.
+        let (_, total_hits) = search(
+            TopDocsWithFilter64::with_limit(1)
+                .with_searcher(searcher.clone())
+                .with_diversify("row_id".to_string(), 1),
+            false,
+        );
+        assert!(total_hits.exact);
+        assert_eq!(total_hits.count(), 5);
+    }
+}
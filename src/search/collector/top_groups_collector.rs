@@ -0,0 +1,290 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use roaring::RoaringTreemap;
+use tantivy::collector::{Collector, SegmentCollector};
+use tantivy::query::Weight;
+use tantivy::schema::FieldType;
+use tantivy::{DocId, Score, SegmentOrdinal, SegmentReader, TantivyError};
+
+use crate::ffi::GroupTopDoc;
+use crate::search::bridge::index_reader_row_id_mapping::{RowIdMappingCache, SegmentRowIdReader};
+use crate::RowIdWithScore;
+
+// Variables in TopGroupsCollector:
+// @`limit` restricts the number of groups returned.
+// @`group_field` is a u64 fast field, matched docs are collapsed by its value.
+// @`deleted_row_ids` is an Option type, row_ids in it are lightweight deleted and won't be collected.
+// @`row_id_mapping` is an Option type, warmed segments read row_id from it instead of fast field.
+// @`row_id_treemap` is an Option type, only row_ids in it will be collected.
+// @`row_id_range` is an Option type, only row_ids in range [start, end) will be collected.
+//
+// Each group keeps its best hit ranked like `TopDocsWithFilter64` and the number of hits,
+// docs without `group_field` value are not collected. Hit counts need every matched doc,
+// so docs can't be pruned by score threshold.
+
+pub struct TopGroupsCollector {
+    pub limit: usize,
+    pub group_field: String,
+    pub deleted_row_ids: Option<Arc<RoaringTreemap>>,
+    pub row_id_mapping: Option<Arc<RowIdMappingCache>>,
+    pub row_id_treemap: Option<Arc<RoaringTreemap>>,
+    pub row_id_range: Option<(u64, u64)>,
+}
+
+impl TopGroupsCollector {
+    pub fn with_limit(limit: usize, group_field: String) -> TopGroupsCollector {
+        Self {
+            limit,
+            group_field,
+            deleted_row_ids: None,
+            row_id_mapping: None,
+            row_id_treemap: None,
+            row_id_range: None,
+        }
+    }
+
+    // `row_id_bitmap` is used to mark alive row_ids.
+    pub fn with_alive(mut self, row_id_bitmap: Arc<RoaringTreemap>) -> TopGroupsCollector {
+        self.row_id_treemap = Some(row_id_bitmap);
+        self
+    }
+
+    // `row_id_range` is used to mark alive row_id range, the range is [start, end)
+    pub fn with_range(mut self, row_id_range: (u64, u64)) -> TopGroupsCollector {
+        self.row_id_range = Some(row_id_range);
+        self
+    }
+
+    // `deleted_row_ids` is used to exclude lightweight deleted row_ids.
+    pub fn with_deleted(mut self, deleted_row_ids: Arc<RoaringTreemap>) -> TopGroupsCollector {
+        self.deleted_row_ids = Some(deleted_row_ids);
+        self
+    }
+
+    // `row_id_mapping` avoids opening `row_id` fast field for warmed segments.
+    pub fn with_row_id_mapping(
+        mut self,
+        row_id_mapping: Arc<RowIdMappingCache>,
+    ) -> TopGroupsCollector {
+        self.row_id_mapping = Some(row_id_mapping);
+        self
+    }
+
+    #[inline]
+    fn is_collected(&self, row_id: u64) -> bool {
+        if let Some(deleted_row_ids) = &self.deleted_row_ids {
+            if deleted_row_ids.contains(row_id) {
+                return false;
+            }
+        }
+        if let Some(row_id_treemap) = &self.row_id_treemap {
+            if !row_id_treemap.contains(row_id) {
+                return false;
+            }
+        }
+        if let Some((start, end)) = self.row_id_range {
+            if !(start <= row_id && row_id < end) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+// Add a hit into its group, a better ranked `RowIdWithScore` is smaller.
+#[inline]
+fn merge_group_hit(
+    groups: &mut HashMap<u64, GroupTopDoc>,
+    group_key: u64,
+    hit_count: u64,
+    top_doc: RowIdWithScore,
+) {
+    match groups.get_mut(&group_key) {
+        Some(group) => {
+            group.hit_count += hit_count;
+            if top_doc < group.top_doc {
+                group.top_doc = top_doc;
+            }
+        }
+        None => {
+            groups.insert(
+                group_key,
+                GroupTopDoc {
+                    group_key,
+                    hit_count,
+                    top_doc,
+                },
+            );
+        }
+    }
+}
+
+impl fmt::Debug for TopGroupsCollector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "TopGroupsCollector(limit:{}, group_field:{}, row_ids_size:{}, row_id_range:{:?})",
+            self.limit,
+            self.group_field,
+            self.row_id_treemap
+                .as_ref()
+                .map(|treemap| treemap.len())
+                .unwrap_or(0),
+            self.row_id_range
+        )
+    }
+}
+
+impl Collector for TopGroupsCollector {
+    type Fruit = Vec<GroupTopDoc>;
+
+    type Child = TopGroupsSegmentCollector; // won't use for current design.
+
+    fn for_segment(
+        &self,
+        _segment_local_id: SegmentOrdinal,
+        _reader: &SegmentReader,
+    ) -> tantivy::Result<Self::Child> {
+        Ok(TopGroupsSegmentCollector())
+    }
+
+    // groups are ranked by score of their best hit.
+    fn requires_scoring(&self) -> bool {
+        true
+    }
+
+    fn merge_fruits(&self, child_fruits: Vec<Vec<GroupTopDoc>>) -> tantivy::Result<Self::Fruit> {
+        if self.limit == 0 {
+            return Ok(Vec::new());
+        }
+        let mut groups: HashMap<u64, GroupTopDoc> = HashMap::new();
+        for child_fruit in child_fruits {
+            for group in child_fruit {
+                merge_group_hit(&mut groups, group.group_key, group.hit_count, group.top_doc);
+            }
+        }
+        let mut top_groups: Vec<GroupTopDoc> = groups.into_values().collect();
+        top_groups.sort_by(|left, right| left.top_doc.cmp(&right.top_doc));
+        top_groups.truncate(self.limit);
+        Ok(top_groups)
+    }
+
+    // collector for each segment.
+    fn collect_segment(
+        &self,
+        weight: &dyn Weight,
+        segment_ord: SegmentOrdinal,
+        reader: &SegmentReader,
+    ) -> tantivy::Result<<Self::Child as SegmentCollector>::Fruit> {
+        if self.limit == 0 {
+            return Ok(Vec::new());
+        }
+        let schema = reader.schema();
+        let field = schema.get_field(&self.group_field)?;
+        let field_entry = schema.get_field_entry(field);
+        if !field_entry.is_fast() || !matches!(field_entry.field_type(), FieldType::U64(_)) {
+            return Err(TantivyError::SchemaError(format!(
+                "Field `{}` is not a u64 fast field, can't group by it.",
+                self.group_field
+            )));
+        }
+        let group_column = reader.fast_fields().u64(&self.group_field)?;
+        let row_id_field_reader =
+            SegmentRowIdReader::open(reader, "row_id", self.row_id_mapping.as_deref())?;
+        let alive_bitset = reader.alive_bitset();
+
+        let mut groups: HashMap<u64, GroupTopDoc> = HashMap::new();
+        weight.for_each(reader, &mut |doc: DocId, score: Score| {
+            if let Some(alive_bitset) = alive_bitset {
+                if alive_bitset.is_deleted(doc) {
+                    return;
+                }
+            }
+            let row_id = row_id_field_reader.row_id(doc);
+            if !self.is_collected(row_id) {
+                return;
+            }
+            let group_key: u64 = match group_column.first(doc) {
+                Some(group_key) => group_key,
+                None => return,
+            };
+            let top_doc = RowIdWithScore {
+                row_id,
+                score,
+                seg_id: segment_ord,
+                doc_id: doc,
+                docs: vec![],
+            };
+            merge_group_hit(&mut groups, group_key, 1, top_doc);
+        })?;
+        Ok(groups.into_values().collect())
+    }
+}
+
+pub struct TopGroupsSegmentCollector();
+
+impl SegmentCollector for TopGroupsSegmentCollector {
+    type Fruit = Vec<GroupTopDoc>;
+
+    fn collect(&mut self, _doc: DocId, _score: Score) {
+        unreachable!("segment collector is unused, `collect_segment` is overridden");
+    }
+
+    fn harvest(self) -> Vec<GroupTopDoc> {
+        unreachable!("segment collector is unused, `collect_segment` is overridden");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row_id_with_score(row_id: u64, score: f32) -> RowIdWithScore {
+        RowIdWithScore {
+            row_id,
+            score,
+            seg_id: 0,
+            doc_id: 0,
+            docs: vec![],
+        }
+    }
+
+    #[test]
+    fn test_merge_fruits() {
+        let collector = TopGroupsCollector::with_limit(2, "user_id".to_string());
+        let segment0 = vec![
+            GroupTopDoc {
+                group_key: 1,
+                hit_count: 2,
+                top_doc: row_id_with_score(10, 1.0),
+            },
+            GroupTopDoc {
+                group_key: 2,
+                hit_count: 1,
+                top_doc: row_id_with_score(11, 2.0),
+            },
+        ];
+        let segment1 = vec![
+            GroupTopDoc {
+                group_key: 1,
+                hit_count: 3,
+                top_doc: row_id_with_score(20, 3.0),
+            },
+            GroupTopDoc {
+                group_key: 3,
+                hit_count: 1,
+                top_doc: row_id_with_score(21, 0.5),
+            },
+        ];
+        let groups = collector.merge_fruits(vec![segment0, segment1]).unwrap();
+        assert_eq!(groups.len(), 2);
+        // Group 1 is ranked first by its best hit from segment1.
+        assert_eq!(groups[0].group_key, 1);
+        assert_eq!(groups[0].hit_count, 5);
+        assert_eq!(groups[0].top_doc.row_id, 20);
+        assert_eq!(groups[1].group_key, 2);
+        assert_eq!(groups[1].hit_count, 1);
+    }
+}
@@ -1,15 +1,25 @@
 use crate::common::errors::TantivySearchError;
+use crate::common::geo::{GeoPoint, GeoShape};
 use crate::logger::logger_bridge::TantivySearchLogger;
 use crate::search::bridge::index_reader_bridge::IndexReaderBridge;
+use crate::search::implements::api_common_impl::column_tokenizer_config;
 use crate::search::implements::strategy::query_strategy::QueryExecutor;
 use crate::search::implements::strategy::query_strategy::SingleTermQueryStrategy;
 use crate::search::utils::convert_utils::ConvertUtils;
 use crate::search::utils::index_searcher_utils::FFiIndexSearcherUtils;
+use crate::tokenizer::date_format::DateFormat;
+use crate::tokenizer::vo::tokenizer_types::TokenizerType;
 use crate::FFI_INDEX_SEARCHER_CACHE;
 use crate::{common::constants::LOG_CALLBACK, ERROR};
 use roaring::RoaringBitmap;
 use std::sync::Arc;
+use tantivy::time::UtcOffset;
+use tantivy::DateTime;
 
+use super::strategy::adaptive_collect::{AdaptiveRows, CollectMode};
+use super::strategy::query_strategy::AdaptiveTermSetQueryStrategy;
+use super::strategy::query_strategy::DateRangeQueryStrategy;
+use super::strategy::query_strategy::GeoQueryStrategy;
 use super::strategy::query_strategy::RegexQueryStrategy;
 use super::strategy::query_strategy::SentenceQueryStrategy;
 use super::strategy::query_strategy::TermSetQueryStrategy;
@@ -32,11 +42,19 @@ pub fn query_term_with_range(
 
     // Choose query strategy to construct query executor.
     let term_query: SingleTermQueryStrategy<'_> = SingleTermQueryStrategy { column_name, term };
-    let query_executor: QueryExecutor<'_, Arc<RoaringBitmap>> = QueryExecutor::new(&term_query);
+    let query_executor: QueryExecutor<'_, Arc<RoaringBitmap>> = QueryExecutor::new(&term_query)
+        .with_deleted(index_reader_bridge.deleted_row_ids())
+        .with_principal_filter(index_reader_bridge.principal_filter())
+        .with_row_id_mapping(index_reader_bridge.row_id_mapping.clone())
+        .with_result_cache(index_reader_bridge.query_result_cache())
+        .with_slow_query_threshold(
+            &index_reader_bridge.path,
+            index_reader_bridge.slow_query_threshold(),
+        );
 
     // Compute query results.
     let result: Arc<RoaringBitmap> = query_executor
-        .execute(&index_reader_bridge.reader.searcher())
+        .execute_single_flight(&index_reader_bridge.reader.searcher())
         .map_err(|e| {
             ERROR!(function:"query_term_with_range", "{}", e);
             TantivySearchError::IndexSearcherError(e)
@@ -69,11 +87,19 @@ pub fn query_terms_with_range(
 
     // Choose query strategy to construct query executor.
     let terms_query: TermSetQueryStrategy<'_> = TermSetQueryStrategy { column_name, terms };
-    let query_executor: QueryExecutor<'_, Arc<RoaringBitmap>> = QueryExecutor::new(&terms_query);
+    let query_executor: QueryExecutor<'_, Arc<RoaringBitmap>> = QueryExecutor::new(&terms_query)
+        .with_deleted(index_reader_bridge.deleted_row_ids())
+        .with_principal_filter(index_reader_bridge.principal_filter())
+        .with_row_id_mapping(index_reader_bridge.row_id_mapping.clone())
+        .with_result_cache(index_reader_bridge.query_result_cache())
+        .with_slow_query_threshold(
+            &index_reader_bridge.path,
+            index_reader_bridge.slow_query_threshold(),
+        );
 
     // Compute query results.
     let result: Arc<RoaringBitmap> = query_executor
-        .execute(&index_reader_bridge.reader.searcher())
+        .execute_single_flight(&index_reader_bridge.reader.searcher())
         .map_err(|e| {
             ERROR!(function:"query_terms_with_range", "{}", e);
             TantivySearchError::IndexSearcherError(e)
@@ -109,11 +135,19 @@ pub fn query_sentence_with_range(
         column_name,
         sentence,
     };
-    let query_executor: QueryExecutor<'_, Arc<RoaringBitmap>> = QueryExecutor::new(&sentence_query);
+    let query_executor: QueryExecutor<'_, Arc<RoaringBitmap>> = QueryExecutor::new(&sentence_query)
+        .with_deleted(index_reader_bridge.deleted_row_ids())
+        .with_principal_filter(index_reader_bridge.principal_filter())
+        .with_row_id_mapping(index_reader_bridge.row_id_mapping.clone())
+        .with_result_cache(index_reader_bridge.query_result_cache())
+        .with_slow_query_threshold(
+            &index_reader_bridge.path,
+            index_reader_bridge.slow_query_threshold(),
+        );
 
     // Compute query results.
     let result: Arc<RoaringBitmap> = query_executor
-        .execute(&index_reader_bridge.reader.searcher())
+        .execute_single_flight(&index_reader_bridge.reader.searcher())
         .map_err(|e| {
             ERROR!(function:"query_sentence_with_range", "{}", e);
             TantivySearchError::IndexSearcherError(e)
@@ -149,11 +183,19 @@ pub fn regex_term_with_range(
         column_name,
         pattern,
     };
-    let query_executor: QueryExecutor<'_, Arc<RoaringBitmap>> = QueryExecutor::new(&sentence_query);
+    let query_executor: QueryExecutor<'_, Arc<RoaringBitmap>> = QueryExecutor::new(&sentence_query)
+        .with_deleted(index_reader_bridge.deleted_row_ids())
+        .with_principal_filter(index_reader_bridge.principal_filter())
+        .with_row_id_mapping(index_reader_bridge.row_id_mapping.clone())
+        .with_result_cache(index_reader_bridge.query_result_cache())
+        .with_slow_query_threshold(
+            &index_reader_bridge.path,
+            index_reader_bridge.slow_query_threshold(),
+        );
 
     // Compute query results.
     let result: Arc<RoaringBitmap> = query_executor
-        .execute(&index_reader_bridge.reader.searcher())
+        .execute_single_flight(&index_reader_bridge.reader.searcher())
         .map_err(|e| {
             ERROR!(function:"regex_term_with_range", "{}", e);
             TantivySearchError::IndexSearcherError(e)
@@ -184,11 +226,19 @@ pub fn query_term_bitmap(
 
     // Choose query strategy to construct query executor.
     let term_query: SingleTermQueryStrategy<'_> = SingleTermQueryStrategy { column_name, term };
-    let query_executor: QueryExecutor<'_, Arc<RoaringBitmap>> = QueryExecutor::new(&term_query);
+    let query_executor: QueryExecutor<'_, Arc<RoaringBitmap>> = QueryExecutor::new(&term_query)
+        .with_deleted(index_reader_bridge.deleted_row_ids())
+        .with_principal_filter(index_reader_bridge.principal_filter())
+        .with_row_id_mapping(index_reader_bridge.row_id_mapping.clone())
+        .with_result_cache(index_reader_bridge.query_result_cache())
+        .with_slow_query_threshold(
+            &index_reader_bridge.path,
+            index_reader_bridge.slow_query_threshold(),
+        );
 
     // Compute query results.
     let result: Arc<RoaringBitmap> = query_executor
-        .execute(&index_reader_bridge.reader.searcher())
+        .execute_single_flight(&index_reader_bridge.reader.searcher())
         .map_err(|e| {
             ERROR!(function:"query_term_bitmap", "{}", e);
             TantivySearchError::IndexSearcherError(e)
@@ -217,11 +267,19 @@ pub fn query_terms_bitmap(
 
     // Choose query strategy to construct query executor.
     let sentence_query: TermSetQueryStrategy<'_> = TermSetQueryStrategy { column_name, terms };
-    let query_executor: QueryExecutor<'_, Arc<RoaringBitmap>> = QueryExecutor::new(&sentence_query);
+    let query_executor: QueryExecutor<'_, Arc<RoaringBitmap>> = QueryExecutor::new(&sentence_query)
+        .with_deleted(index_reader_bridge.deleted_row_ids())
+        .with_principal_filter(index_reader_bridge.principal_filter())
+        .with_row_id_mapping(index_reader_bridge.row_id_mapping.clone())
+        .with_result_cache(index_reader_bridge.query_result_cache())
+        .with_slow_query_threshold(
+            &index_reader_bridge.path,
+            index_reader_bridge.slow_query_threshold(),
+        );
 
     // Compute query results.
     let result: Arc<RoaringBitmap> = query_executor
-        .execute(&index_reader_bridge.reader.searcher())
+        .execute_single_flight(&index_reader_bridge.reader.searcher())
         .map_err(|e| {
             ERROR!(function:"query_terms_bitmap", "{}", e);
             TantivySearchError::IndexSearcherError(e)
@@ -234,6 +292,59 @@ pub fn query_terms_bitmap(
     Ok(u8_bitmap)
 }
 
+/// Execute Terms Query, row_ids are collected in a representation picked by `mode`.
+/// `auto` mode estimates selectivity of terms before execution, short-circuits when no doc has
+/// any term, only counts when `need_row_ids` is false and returns a dense range when most rows match.
+/// Row_ids are returned as ranges if `accept_ranges` and ranges are smaller.
+pub fn query_terms_adaptive(
+    index_path: &str,
+    column_name: &str,
+    terms: &Vec<String>,
+    mode: &str,
+    need_row_ids: bool,
+    accept_ranges: bool,
+) -> Result<AdaptiveRows, TantivySearchError> {
+    let mode: CollectMode = CollectMode::from_name(mode).ok_or_else(|| {
+        let error_info = format!(
+            "Unknown collect mode `{}`, expect `auto`, `bitmap`, `dense_range` or `count`",
+            mode
+        );
+        ERROR!(function: "query_terms_adaptive", "{}", error_info);
+        TantivySearchError::InvalidArgument(error_info)
+    })?;
+    // Get index_reader_bridge from CACHE
+    let index_reader_bridge: Arc<IndexReaderBridge> = FFI_INDEX_SEARCHER_CACHE
+        .get_index_reader_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function:"query_terms_adaptive", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+
+    // Choose query strategy to construct query executor.
+    let terms_query: TermSetQueryStrategy<'_> = TermSetQueryStrategy { column_name, terms };
+    let adaptive_query: AdaptiveTermSetQueryStrategy<'_> = AdaptiveTermSetQueryStrategy {
+        term_set: &terms_query,
+        mode,
+        need_row_ids,
+        accept_ranges,
+    };
+    let query_executor: QueryExecutor<'_, AdaptiveRows> = QueryExecutor::new(&adaptive_query)
+        .with_deleted(index_reader_bridge.deleted_row_ids())
+        .with_principal_filter(index_reader_bridge.principal_filter())
+        .with_row_id_mapping(index_reader_bridge.row_id_mapping.clone())
+        .with_slow_query_threshold(
+            &index_reader_bridge.path,
+            index_reader_bridge.slow_query_threshold(),
+        );
+
+    query_executor
+        .execute(&index_reader_bridge.reader.searcher())
+        .map_err(|e| {
+            ERROR!(function:"query_terms_adaptive", "{}", e);
+            TantivySearchError::IndexSearcherError(e)
+        })
+}
+
 /// Execute Sentence Query.
 pub fn query_sentence_bitmap(
     index_path: &str,
@@ -253,11 +364,19 @@ pub fn query_sentence_bitmap(
         column_name,
         sentence,
     };
-    let query_executor: QueryExecutor<'_, Arc<RoaringBitmap>> = QueryExecutor::new(&sentence_query);
+    let query_executor: QueryExecutor<'_, Arc<RoaringBitmap>> = QueryExecutor::new(&sentence_query)
+        .with_deleted(index_reader_bridge.deleted_row_ids())
+        .with_principal_filter(index_reader_bridge.principal_filter())
+        .with_row_id_mapping(index_reader_bridge.row_id_mapping.clone())
+        .with_result_cache(index_reader_bridge.query_result_cache())
+        .with_slow_query_threshold(
+            &index_reader_bridge.path,
+            index_reader_bridge.slow_query_threshold(),
+        );
 
     // Compute query results.
     let result: Arc<RoaringBitmap> = query_executor
-        .execute(&index_reader_bridge.reader.searcher())
+        .execute_single_flight(&index_reader_bridge.reader.searcher())
         .map_err(|e| {
             ERROR!(function:"query_sentence_bitmap", "{}", e);
             TantivySearchError::IndexSearcherError(e)
@@ -289,11 +408,19 @@ pub fn regex_term_bitmap(
         column_name,
         pattern,
     };
-    let query_executor: QueryExecutor<'_, Arc<RoaringBitmap>> = QueryExecutor::new(&sentence_query);
+    let query_executor: QueryExecutor<'_, Arc<RoaringBitmap>> = QueryExecutor::new(&sentence_query)
+        .with_deleted(index_reader_bridge.deleted_row_ids())
+        .with_principal_filter(index_reader_bridge.principal_filter())
+        .with_row_id_mapping(index_reader_bridge.row_id_mapping.clone())
+        .with_result_cache(index_reader_bridge.query_result_cache())
+        .with_slow_query_threshold(
+            &index_reader_bridge.path,
+            index_reader_bridge.slow_query_threshold(),
+        );
 
     // Compute query results.
     let result: Arc<RoaringBitmap> = query_executor
-        .execute(&index_reader_bridge.reader.searcher())
+        .execute_single_flight(&index_reader_bridge.reader.searcher())
         .map_err(|e| {
             ERROR!(function:"regex_term_bitmap", "{}", e);
             TantivySearchError::IndexSearcherError(e)
@@ -305,3 +432,196 @@ pub fn regex_term_bitmap(
 
     Ok(u8_bitmap)
 }
+
+// Date format of `column_name` configured in index json parameter.
+fn column_date_format(
+    function: &str,
+    index_path: &str,
+    column_name: &str,
+) -> Result<DateFormat, TantivySearchError> {
+    column_tokenizer_config(function, index_path, column_name)?
+        .and_then(|tokenizer_config| tokenizer_config.date_format)
+        .ok_or_else(|| {
+            let error_info: String = format!("column:{} is not a date column", column_name);
+            ERROR!(function: function, "{}", error_info);
+            TantivySearchError::InvalidArgument(error_info)
+        })
+}
+
+// Verify `column_name` is configured as a geo point column.
+fn check_geo_point_column(
+    function: &str,
+    index_path: &str,
+    column_name: &str,
+) -> Result<(), TantivySearchError> {
+    match column_tokenizer_config(function, index_path, column_name)? {
+        Some(tokenizer_config)
+            if matches!(tokenizer_config.tokenizer_type, TokenizerType::GeoPoint(_)) =>
+        {
+            Ok(())
+        }
+        _ => {
+            let error_info: String = format!("column:{} is not a geo point column", column_name);
+            ERROR!(function: function, "{}", error_info);
+            Err(TantivySearchError::InvalidArgument(error_info))
+        }
+    }
+}
+
+/// Execute Date Range Query, `lower` and `upper` are inclusive and given in input formats
+/// of the column or as local date time in timezone of `utc_offset_seconds`,
+/// an empty bound means unbounded.
+pub fn query_date_range_bitmap(
+    index_path: &str,
+    column_name: &str,
+    lower: &str,
+    upper: &str,
+    utc_offset_seconds: i32,
+) -> Result<Vec<u8>, TantivySearchError> {
+    // Get index_reader_bridge from CACHE
+    let index_reader_bridge: Arc<IndexReaderBridge> = FFI_INDEX_SEARCHER_CACHE
+        .get_index_reader_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function:"query_date_range_bitmap", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+
+    // Parse bounds by date format of the column, local date time is in host timezone.
+    let utc_offset: UtcOffset = UtcOffset::from_whole_seconds(utc_offset_seconds).map_err(|e| {
+        let error_info: String = format!("Invalid utc offset {}: {}", utc_offset_seconds, e);
+        ERROR!(function:"query_date_range_bitmap", "{}", error_info);
+        TantivySearchError::InvalidArgument(error_info)
+    })?;
+    let date_format: DateFormat =
+        column_date_format("query_date_range_bitmap", index_path, column_name)?;
+    let parse_bound =
+        |bound: &str, is_upper: bool| -> Result<Option<DateTime>, TantivySearchError> {
+            if bound.trim().is_empty() {
+                return Ok(None);
+            }
+            date_format
+                .parse_bound(bound, utc_offset, is_upper)
+                .map(Some)
+                .map_err(|e| {
+                    ERROR!(function:"query_date_range_bitmap", "{}", e);
+                    TantivySearchError::InvalidArgument(e)
+                })
+        };
+    let lower: Option<DateTime> = parse_bound(lower, false)?;
+    let upper: Option<DateTime> = parse_bound(upper, true)?;
+
+    // Choose query strategy to construct query executor.
+    let date_range_query: DateRangeQueryStrategy<'_> = DateRangeQueryStrategy {
+        column_name,
+        lower: &lower,
+        upper: &upper,
+    };
+    let query_executor: QueryExecutor<'_, Arc<RoaringBitmap>> =
+        QueryExecutor::new(&date_range_query)
+            .with_deleted(index_reader_bridge.deleted_row_ids())
+            .with_principal_filter(index_reader_bridge.principal_filter())
+            .with_row_id_mapping(index_reader_bridge.row_id_mapping.clone())
+            .with_result_cache(index_reader_bridge.query_result_cache())
+            .with_slow_query_threshold(
+                &index_reader_bridge.path,
+                index_reader_bridge.slow_query_threshold(),
+            );
+
+    // Compute query results.
+    let result: Arc<RoaringBitmap> = query_executor
+        .execute_single_flight(&index_reader_bridge.reader.searcher())
+        .map_err(|e| {
+            ERROR!(function:"query_date_range_bitmap", "{}", e);
+            TantivySearchError::IndexSearcherError(e)
+        })?;
+
+    // Convert results to u8 bitmap.
+    let row_ids_number: Vec<u32> = result.iter().collect();
+    let u8_bitmap: Vec<u8> = ConvertUtils::row_ids_to_u8_bitmap(&row_ids_number);
+
+    Ok(u8_bitmap)
+}
+
+/// Execute Geo Query, collect rows whose point is in the box between
+/// `(min_lat, min_lon)` and `(max_lat, max_lon)`. The box crosses the antimeridian
+/// if `min_lon` is larger than `max_lon`.
+pub fn query_geo_bounding_box_bitmap(
+    index_path: &str,
+    column_name: &str,
+    min_lat: f64,
+    min_lon: f64,
+    max_lat: f64,
+    max_lon: f64,
+) -> Result<Vec<u8>, TantivySearchError> {
+    let shape: GeoShape = GeoPoint::new(min_lat, min_lon)
+        .and_then(|min| GeoShape::bounding_box(min, GeoPoint::new(max_lat, max_lon)?))
+        .map_err(|e| {
+            ERROR!(function:"query_geo_bounding_box_bitmap", "{}", e);
+            TantivySearchError::InvalidArgument(e)
+        })?;
+    query_geo_bitmap(
+        "query_geo_bounding_box_bitmap",
+        index_path,
+        column_name,
+        &shape,
+    )
+}
+
+/// Execute Geo Query, collect rows whose point is within `radius_meters` of `(lat, lon)`.
+pub fn query_geo_distance_bitmap(
+    index_path: &str,
+    column_name: &str,
+    lat: f64,
+    lon: f64,
+    radius_meters: f64,
+) -> Result<Vec<u8>, TantivySearchError> {
+    let shape: GeoShape = GeoPoint::new(lat, lon)
+        .and_then(|center| GeoShape::distance(center, radius_meters))
+        .map_err(|e| {
+            ERROR!(function:"query_geo_distance_bitmap", "{}", e);
+            TantivySearchError::InvalidArgument(e)
+        })?;
+    query_geo_bitmap("query_geo_distance_bitmap", index_path, column_name, &shape)
+}
+
+fn query_geo_bitmap(
+    function: &str,
+    index_path: &str,
+    column_name: &str,
+    shape: &GeoShape,
+) -> Result<Vec<u8>, TantivySearchError> {
+    // Get index_reader_bridge from CACHE
+    let index_reader_bridge: Arc<IndexReaderBridge> = FFI_INDEX_SEARCHER_CACHE
+        .get_index_reader_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function: function, "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+    check_geo_point_column(function, index_path, column_name)?;
+
+    // Choose query strategy to construct query executor.
+    let geo_query: GeoQueryStrategy<'_> = GeoQueryStrategy { column_name, shape };
+    let query_executor: QueryExecutor<'_, Arc<RoaringBitmap>> = QueryExecutor::new(&geo_query)
+        .with_deleted(index_reader_bridge.deleted_row_ids())
+        .with_principal_filter(index_reader_bridge.principal_filter())
+        .with_row_id_mapping(index_reader_bridge.row_id_mapping.clone())
+        .with_result_cache(index_reader_bridge.query_result_cache())
+        .with_slow_query_threshold(
+            &index_reader_bridge.path,
+            index_reader_bridge.slow_query_threshold(),
+        );
+
+    // Compute query results.
+    let result: Arc<RoaringBitmap> = query_executor
+        .execute_single_flight(&index_reader_bridge.reader.searcher())
+        .map_err(|e| {
+            ERROR!(function: function, "{}", e);
+            TantivySearchError::IndexSearcherError(e)
+        })?;
+
+    // Convert results to u8 bitmap.
+    let row_ids_number: Vec<u32> = result.iter().collect();
+    let u8_bitmap: Vec<u8> = ConvertUtils::row_ids_to_u8_bitmap(&row_ids_number);
+
+    Ok(u8_bitmap)
+}
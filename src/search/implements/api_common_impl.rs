@@ -1,24 +1,48 @@
+use crate::common::constants::{OPEN_FILES_BUDGET, QUERY_AUDIT, QUERY_COST_GUARD, THREAD_AFFINITY};
 use crate::common::errors::TantivySearchError;
+use crate::common::open_files_budget::IndexBridgeKind;
+use crate::common::query_cost_guard::QueryCostLimits;
+use crate::common::thread_affinity::{thread_pool_builder, ThreadKind};
+use crate::ffi::AnalyzedToken;
 use crate::logger::logger_bridge::TantivySearchLogger;
 use crate::tokenizer::dto::index_parameter_dto::IndexParameterDTO;
 use crate::tokenizer::vo::tokenizers_vo::TokenizerConfig;
+use crate::utils::index_discovery_utils::IndexDiscoveryUtils;
 use crate::utils::index_utils::IndexUtils;
+use crate::utils::index_version_utils::IndexVersionUtils;
+use crate::utils::path_utils::PathUtils;
 use crate::DEBUG;
 use crate::FFI_INDEX_SEARCHER_CACHE;
-use crate::{common::constants::LOG_CALLBACK, ERROR};
+use crate::{common::constants::LOG_CALLBACK, ERROR, INFO, WARNING};
 
-use std::{path::Path, sync::Arc};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 
 use crate::search::bridge::index_reader_bridge::IndexReaderBridge;
+use crate::search::bridge::index_reader_percolator::Percolator;
+use crate::search::bridge::index_reader_row_id_mapping::RowIdMappingCache;
+use crate::search::bridge::index_reader_security_filter::{
+    SearchPrincipal, SecurityFilter, SecurityFilters,
+};
+use crate::search::bridge::index_reader_vector_cache::VectorCache;
+use crate::search::implements::strategy::query_strategy::bm25_text_fields;
 use crate::tokenizer::tokenizer_utils::TokenizerUtils;
+use crate::tokenizer::vector_format::VectorFormat;
+use rayon::prelude::*;
+use roaring::RoaringTreemap;
+use serde_json::json;
 use std::collections::HashMap;
+use tantivy::query::QueryParser;
+use tantivy::schema::Field;
+use tantivy::tokenizer::{TextAnalyzer, TokenizerManager};
 use tantivy::Directory;
 use tantivy::IndexReader;
-use tantivy::{Index, ReloadPolicy};
+use tantivy::{Index, ReloadPolicy, Warmer};
 
 pub fn get_index_json_parameter(index_path: &str) -> Result<String, TantivySearchError> {
     // Verify index files directory.
-    let index_files_directory = Path::new(index_path);
+    let index_files_directory: &Path = &PathUtils::index_directory(index_path);
     if !index_files_directory.exists() || !index_files_directory.is_dir() {
         let error_info: String = format!("index_path not exists: {:?}", index_path);
         let error: TantivySearchError = TantivySearchError::IndexNotExists(error_info);
@@ -40,9 +64,112 @@ pub fn get_index_json_parameter(index_path: &str) -> Result<String, TantivySearc
     Ok(index_parameter_dto.tokenizers_json_parameter.clone())
 }
 
+// Tokenizer config of `column_name` in index json parameter, `None` if it's not configured.
+pub fn column_tokenizer_config(
+    function: &str,
+    index_path: &str,
+    column_name: &str,
+) -> Result<Option<TokenizerConfig>, TantivySearchError> {
+    let index_json_parameter: String = get_index_json_parameter(index_path)?;
+    let mut col_tokenizer_map: HashMap<String, TokenizerConfig> =
+        TokenizerUtils::parse_tokenizer_json_to_config_map(&index_json_parameter).map_err(|e| {
+            ERROR!(function: function, "{}", e);
+            TantivySearchError::TokenizerUtilsError(e)
+        })?;
+    Ok(col_tokenizer_map.remove(column_name))
+}
+
+// Analyzer configured for `field` in an index directory or an index json parameter,
+// fields not configured use tantivy default analyzer like `create_index_with_parameter`.
+fn field_text_analyzer(
+    function: &str,
+    index_path_or_config: &str,
+    field: &str,
+) -> Result<TextAnalyzer, TantivySearchError> {
+    let index_json_parameter: String = if Path::new(index_path_or_config).is_dir() {
+        get_index_json_parameter(index_path_or_config)?
+    } else {
+        index_path_or_config.to_string()
+    };
+
+    let col_tokenizer_map: HashMap<String, TokenizerConfig> =
+        TokenizerUtils::parse_tokenizer_json_to_config_map(&index_json_parameter).map_err(|e| {
+            ERROR!(function: function, "{}", e);
+            TantivySearchError::TokenizerUtilsError(e)
+        })?;
+
+    match col_tokenizer_map.get(field) {
+        Some(tokenizer_config) if !tokenizer_config.is_text_field => {
+            let error_info: String = format!(
+                "field:{} with tokenizer:{} is not a text field",
+                field,
+                tokenizer_config.tokenizer_type.name()
+            );
+            ERROR!(function: function, "{}", error_info);
+            Err(TantivySearchError::InvalidArgument(error_info))
+        }
+        Some(tokenizer_config) => Ok(tokenizer_config.text_analyzer.clone()),
+        None => TokenizerManager::default().get("default").ok_or_else(|| {
+            TantivySearchError::InternalError("default tokenizer not exists".to_string())
+        }),
+    }
+}
+
+/// Run analyzer configured for `field` on `text` without indexing anything.
+/// `index_path_or_config` is an index directory or an index json parameter,
+/// fields not configured use tantivy default analyzer like `create_index_with_parameter`.
+pub fn analyze_text(
+    index_path_or_config: &str,
+    field: &str,
+    text: &str,
+) -> Result<Vec<AnalyzedToken>, TantivySearchError> {
+    let mut text_analyzer: TextAnalyzer =
+        field_text_analyzer("analyze_text", index_path_or_config, field)?;
+
+    let mut tokens: Vec<AnalyzedToken> = Vec::new();
+    text_analyzer.token_stream(text).process(&mut |token| {
+        tokens.push(AnalyzedToken {
+            token: token.text.clone(),
+            position: token.position as u64,
+            offset_from: token.offset_from as u64,
+            offset_to: token.offset_to as u64,
+        });
+    });
+    DEBUG!(function:"analyze_text", "field:{}, tokens size:{}", field, tokens.len());
+    Ok(tokens)
+}
+
+/// Normalize a raw `value` the same way `field` is indexed, e.g. lowercase it for a
+/// `keyword_lowercase` field, so exact match filters built by host hit the indexed term.
+/// Empty string is returned if analyzer drops the value, a value split into several terms
+/// is rejected since it can't be matched as one exact term.
+pub fn normalize_term(
+    index_path_or_config: &str,
+    field: &str,
+    value: &str,
+) -> Result<String, TantivySearchError> {
+    let mut text_analyzer: TextAnalyzer =
+        field_text_analyzer("normalize_term", index_path_or_config, field)?;
+
+    let mut terms: Vec<String> = Vec::new();
+    text_analyzer
+        .token_stream(value)
+        .process(&mut |token| terms.push(token.text.clone()));
+    if terms.len() > 1 {
+        let error_info: String = format!(
+            "field:{} analyzes value into {} terms, it isn't an exact value field",
+            field,
+            terms.len()
+        );
+        ERROR!(function:"normalize_term", "{}", error_info);
+        return Err(TantivySearchError::InvalidArgument(error_info));
+    }
+    Ok(terms.pop().unwrap_or_default())
+}
+
 pub fn get_index_meta_json(index_path: &str) -> Result<String, TantivySearchError> {
     // Verify index files directory.
-    let index_files_directory = Path::new(index_path);
+    let index_files_directory: &Path = &PathUtils::index_directory(index_path);
     if !index_files_directory.exists() || !index_files_directory.is_dir() {
         let error_info: String = format!("index_path not exists: {:?}", index_path);
         let error: TantivySearchError = TantivySearchError::IndexNotExists(error_info);
@@ -90,9 +217,59 @@ pub fn get_index_meta_json(index_path: &str) -> Result<String, TantivySearchErro
     Ok(meta_string)
 }
 
+// Analyzers of indexed text registered to a template index, reader index only registers
+// query analyzers under the same tokenizer names.
+fn build_index_tokenizers(
+    index: &Index,
+    col_tokenizer_map: &HashMap<String, TokenizerConfig>,
+) -> Result<TokenizerManager, TantivySearchError> {
+    let mut template_index: Index = Index::create_in_ram(index.schema());
+    for (column_name, tokenizer_config) in col_tokenizer_map.iter() {
+        TokenizerUtils::register_tokenizer_to_index(
+            &mut template_index,
+            tokenizer_config.tokenizer_type.clone(),
+            column_name,
+            tokenizer_config.text_analyzer.clone(),
+        )
+        .map_err(|e| {
+            ERROR!(function:"build_index_tokenizers", "{}", e);
+            TantivySearchError::TokenizerUtilsError(e)
+        })?;
+        TokenizerUtils::register_language_tokenizers(
+            &mut template_index,
+            column_name,
+            tokenizer_config,
+        );
+    }
+    Ok(template_index.tokenizers().clone())
+}
+
+// Load queries registered for reverse search. They're parsed by query analyzers of `index`,
+// percolated docs are analyzed by `index_tokenizers`.
+fn load_percolator(
+    index_files_directory: &Path,
+    index: &Index,
+    index_tokenizers: TokenizerManager,
+) -> Result<Percolator, TantivySearchError> {
+    let query_fields: Vec<Field> = bm25_text_fields(&index.schema(), &Vec::new()).map_err(|e| {
+        ERROR!(function:"load_percolator", "{}", e);
+        TantivySearchError::IndexSearcherError(e)
+    })?;
+    Percolator::load(
+        index_files_directory,
+        index.schema(),
+        index_tokenizers,
+        QueryParser::for_index(index, query_fields),
+    )
+    .map_err(|e| {
+        ERROR!(function:"load_percolator", "{}", e);
+        TantivySearchError::InternalError(e)
+    })
+}
+
 pub fn load_index_reader(index_path: &str) -> Result<bool, TantivySearchError> {
     // Verify index files directory.
-    let index_files_directory = Path::new(index_path);
+    let index_files_directory: &Path = &PathUtils::index_directory(index_path);
     if !index_files_directory.exists() || !index_files_directory.is_dir() {
         let error_info: String = format!("index_path not exists: {:?}", index_path);
         let error: TantivySearchError = TantivySearchError::IndexNotExists(error_info);
@@ -100,6 +277,12 @@ pub fn load_index_reader(index_path: &str) -> Result<bool, TantivySearchError> {
         return Err(error);
     }
 
+    // Refuse index written by a newer version before tantivy reads its files.
+    IndexVersionUtils::check_index(index_files_directory).map_err(|e| {
+        ERROR!(function:"load_index_reader", "{}", e);
+        e
+    })?;
+
     // Load tantivy index with given directory.
     let mut index: Index = Index::open_in_dir(index_files_directory).map_err(|e| {
         let error: TantivySearchError = TantivySearchError::TantivyError(e);
@@ -107,6 +290,14 @@ pub fn load_index_reader(index_path: &str) -> Result<bool, TantivySearchError> {
         error
     })?;
 
+    // Refuse loading before its files are opened by reader.
+    OPEN_FILES_BUDGET
+        .check_load(index_path, IndexBridgeKind::Reader)
+        .map_err(|e| {
+            ERROR!(function:"load_index_reader", "{}", e);
+            TantivySearchError::OpenFilesBudgetExceeded(e)
+        })?;
+
     // Load index parameter DTO from local index files.
     let index_parameter_dto: IndexParameterDTO = IndexUtils::load_custom_index_setting(
         index_files_directory,
@@ -128,18 +319,20 @@ pub fn load_index_reader(index_path: &str) -> Result<bool, TantivySearchError> {
             TantivySearchError::TokenizerUtilsError(e)
         })?;
 
-    // Register tokenizer config into `index`.
+    // Register tokenizer config into `index`, reader index only analyzes query text,
+    // so query analyzer is registered under the tokenizer name of the field.
     for (column_name, tokenizer_config) in col_tokenizer_map.iter() {
         TokenizerUtils::register_tokenizer_to_index(
             &mut index,
             tokenizer_config.tokenizer_type.clone(),
             &column_name,
-            tokenizer_config.text_analyzer.clone(),
+            tokenizer_config.query_analyzer(),
         )
         .map_err(|e| {
             ERROR!(function:"load_index_reader", "{}", e);
             TantivySearchError::TokenizerUtilsError(e)
         })?;
+        TokenizerUtils::register_language_tokenizers(&mut index, column_name, tokenizer_config);
     }
 
     #[cfg(feature = "use-shared-search-pool")]
@@ -166,24 +359,69 @@ pub fn load_index_reader(index_path: &str) -> Result<bool, TantivySearchError> {
             TantivySearchError::TantivyError(e)
         })?;
     }
+    // Search threads spawned by tantivy are named and pinned as threads of this library.
+    if let Err(e) = THREAD_AFFINITY.adopt_threads() {
+        WARNING!(function:"load_index_reader", "Failed to adopt search threads, due to: {}", e);
+    }
 
-    // Create a reader for the index with an appropriate reload policy.
-    // OnCommitWithDeplay: reload when commit; Manual: developer need call IndexReader::reload() to reload.
+    // Create a reader for the index, it's cached and reused by every search on this index.
+    // Manual: no file watcher thread, commits made through this library reload the cached reader,
+    // commits of other processes become visible after `index_reader_reload`.
+    // DocId and row_id mapping is built whenever a new searcher generation is loaded,
+    // so are vectors and HNSW graphs of vector columns.
+    let row_id_mapping: Arc<RowIdMappingCache> = Arc::new(RowIdMappingCache::default());
+    let row_id_mapping_warmer: Arc<dyn Warmer> = row_id_mapping.clone();
+    let vector_formats: HashMap<String, VectorFormat> = col_tokenizer_map
+        .iter()
+        .filter_map(|(column_name, tokenizer_config)| {
+            let vector_format: VectorFormat = tokenizer_config.vector_format.clone()?;
+            Some((column_name.clone(), vector_format))
+        })
+        .collect();
+    let vector_cache: Option<Arc<VectorCache>> = match vector_formats.is_empty() {
+        true => None,
+        false => Some(Arc::new(VectorCache::new(vector_formats))),
+    };
+    let vector_cache_warmer: Option<Arc<dyn Warmer>> = vector_cache
+        .clone()
+        .map(|vector_cache| vector_cache as Arc<dyn Warmer>);
+    let mut warmers = vec![Arc::downgrade(&row_id_mapping_warmer)];
+    warmers.extend(vector_cache_warmer.as_ref().map(Arc::downgrade));
     let reader: IndexReader = index
         .reader_builder()
-        .reload_policy(ReloadPolicy::OnCommitWithDelay)
+        .reload_policy(ReloadPolicy::Manual)
+        .warmers(warmers)
         .try_into()
         .map_err(|e| {
             ERROR!(function:"load_index_reader", "Failed to create tantivy index reader: {}", e);
             TantivySearchError::TantivyError(e)
         })?;
 
+    let index_tokenizers: TokenizerManager = build_index_tokenizers(&index, &col_tokenizer_map)?;
+    let percolator: Percolator =
+        load_percolator(index_files_directory, &index, index_tokenizers.clone())?;
+
     // Save IndexReaderBridge to cache.
-    let index_reader_bridge: IndexReaderBridge = IndexReaderBridge {
-        index,
-        reader,
-        path: index_path.trim_end_matches('/').to_string(),
-    };
+    let mut index_reader_bridge: IndexReaderBridge =
+        IndexReaderBridge::new(PathUtils::index_key(index_path), index, reader)
+            .with_row_id_mapping(row_id_mapping)
+            .with_percolator(Arc::new(percolator))
+            .with_index_tokenizers(index_tokenizers);
+    if let Some(vector_cache) = vector_cache {
+        index_reader_bridge = index_reader_bridge.with_vector_cache(vector_cache);
+    }
+
+    // Keep lightweight deleted row ids and security filters registered on previous reader.
+    if let Ok(previous_reader_bridge) =
+        FFI_INDEX_SEARCHER_CACHE.get_index_reader_bridge(index_path.to_string())
+    {
+        if let Some(deleted_row_ids) = previous_reader_bridge.deleted_row_ids() {
+            let _ = index_reader_bridge.set_deleted_row_ids(deleted_row_ids.as_ref().clone());
+        }
+        index_reader_bridge = index_reader_bridge.with_security_filters(Arc::new(
+            SecurityFilters::with_filters_of(&previous_reader_bridge.security_filters),
+        ));
+    }
 
     FFI_INDEX_SEARCHER_CACHE
         .set_index_reader_bridge(index_path.to_string(), Arc::new(index_reader_bridge))
@@ -216,6 +454,109 @@ pub fn free_index_reader(index_path: &str) -> Result<bool, TantivySearchError> {
     Ok(true)
 }
 
+/// Recover readers of indexes under `root_dir` after a restart, so first queries don't pay for
+/// loading them. Index directories are discovered under root, validated and loaded into reader
+/// cache on a pool of `num_threads` (0 means available parallelism), readers already loaded are
+/// kept. Files of indexes committed within `hot_within_secs` are read through to warm page
+/// cache, 0 warms nothing. Indexes failed to load are reported and skipped.
+/// Summary is returned as json: `{"root_dir":"/data","discovered":3,"loaded":["/data/a", ...],
+/// "warmed":["/data/a"],"warmed_bytes":1024,"failed":[{"index_path":"/data/c","error":"..."}]}`.
+pub fn bootstrap_indexes(
+    root_dir: &str,
+    hot_within_secs: u64,
+    num_threads: usize,
+) -> Result<String, TantivySearchError> {
+    let root_directory: PathBuf = PathUtils::index_directory(root_dir);
+    if !root_directory.is_dir() {
+        let error_info: String = format!("root_dir is not a directory: {:?}", root_dir);
+        ERROR!(function:"bootstrap_indexes", "{}", error_info);
+        return Err(TantivySearchError::InvalidArgument(error_info));
+    }
+    let index_directories: Vec<PathBuf> = IndexDiscoveryUtils::discover_indexes(&root_directory)
+        .map_err(|e| {
+            ERROR!(function:"bootstrap_indexes", "{}", e);
+            TantivySearchError::IndexUtilsError(e)
+        })?;
+    let num_threads: usize = if num_threads == 0 {
+        std::thread::available_parallelism()
+            .map(|threads| threads.get())
+            .unwrap_or(4)
+    } else {
+        num_threads
+    };
+    let pool = thread_pool_builder(ThreadKind::Search, "bootstrap")
+        .num_threads(num_threads.min(index_directories.len().max(1)))
+        .build()
+        .map_err(|e| {
+            let error_info: String = format!("Failed to build bootstrap pool: {}", e);
+            ERROR!(function:"bootstrap_indexes", "{}", error_info);
+            TantivySearchError::InternalError(error_info)
+        })?;
+
+    let hot_within: Duration = Duration::from_secs(hot_within_secs);
+    // Bytes warmed of each index, `None` if it isn't hot.
+    let recovered: Vec<(String, Result<Option<u64>, TantivySearchError>)> = pool.install(|| {
+        index_directories
+            .par_iter()
+            .map(|index_directory| {
+                let index_path: String = index_directory.to_string_lossy().to_string();
+                let result = recover_index_reader(&index_path, index_directory, hot_within);
+                (index_path, result)
+            })
+            .collect()
+    });
+
+    let mut loaded: Vec<String> = Vec::new();
+    let mut warmed: Vec<String> = Vec::new();
+    let mut warmed_bytes: u64 = 0;
+    let mut failed: Vec<serde_json::Value> = Vec::new();
+    for (index_path, result) in recovered {
+        match result {
+            Ok(index_warmed_bytes) => {
+                if let Some(index_warmed_bytes) = index_warmed_bytes {
+                    warmed.push(index_path.clone());
+                    warmed_bytes += index_warmed_bytes;
+                }
+                loaded.push(index_path);
+            }
+            Err(e) => {
+                WARNING!(function:"bootstrap_indexes", "Skip index [{}]: {}", index_path, e);
+                failed.push(json!({"index_path": index_path, "error": e.to_string()}));
+            }
+        }
+    }
+    INFO!(function:"bootstrap_indexes", "Bootstrap root_dir:[{}], discovered {} indexes, loaded {}, warmed {} ({} bytes), failed {}", root_dir, index_directories.len(), loaded.len(), warmed.len(), warmed_bytes, failed.len());
+    Ok(json!({
+        "root_dir": root_dir,
+        "discovered": index_directories.len(),
+        "loaded": loaded,
+        "warmed": warmed,
+        "warmed_bytes": warmed_bytes,
+        "failed": failed,
+    })
+    .to_string())
+}
+
+// Load reader of a discovered index unless it's loaded, and warm its files if it's hot.
+fn recover_index_reader(
+    index_path: &str,
+    index_directory: &Path,
+    hot_within: Duration,
+) -> Result<Option<u64>, TantivySearchError> {
+    if FFI_INDEX_SEARCHER_CACHE
+        .get_index_reader_bridge(index_path.to_string())
+        .is_err()
+    {
+        load_index_reader(index_path)?;
+    }
+    if hot_within.is_zero() || !IndexDiscoveryUtils::committed_within(index_directory, hot_within) {
+        return Ok(None);
+    }
+    IndexDiscoveryUtils::warm_index_files(index_directory)
+        .map(Some)
+        .map_err(TantivySearchError::IndexUtilsError)
+}
+
 pub fn get_indexed_doc_counts(index_path: &str) -> Result<u64, TantivySearchError> {
     // get index_reader_bridge from CACHE
     let index_reader_bridge: Arc<IndexReaderBridge> = FFI_INDEX_SEARCHER_CACHE
@@ -228,3 +569,185 @@ pub fn get_indexed_doc_counts(index_path: &str) -> Result<u64, TantivySearchErro
     let num_docs: u64 = index_reader_bridge.reader.searcher().num_docs();
     Ok(num_docs)
 }
+
+pub fn register_deleted_row_ids(
+    index_path: &str,
+    row_ids: &Vec<u64>,
+) -> Result<bool, TantivySearchError> {
+    // get index_reader_bridge from CACHE
+    let index_reader_bridge: Arc<IndexReaderBridge> = FFI_INDEX_SEARCHER_CACHE
+        .get_index_reader_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function:"register_deleted_row_ids", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+
+    index_reader_bridge
+        .add_deleted_row_ids(row_ids)
+        .map_err(|e| {
+            ERROR!(function:"register_deleted_row_ids", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+    Ok(true)
+}
+
+pub fn clear_deleted_row_ids(index_path: &str) -> Result<bool, TantivySearchError> {
+    // get index_reader_bridge from CACHE
+    let index_reader_bridge: Arc<IndexReaderBridge> = FFI_INDEX_SEARCHER_CACHE
+        .get_index_reader_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function:"clear_deleted_row_ids", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+
+    index_reader_bridge
+        .set_deleted_row_ids(RoaringTreemap::new())
+        .map_err(|e| {
+            ERROR!(function:"clear_deleted_row_ids", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+    Ok(true)
+}
+
+// Loaded index reader holding security filters.
+fn get_security_filters_bridge(
+    function: &str,
+    index_path: &str,
+) -> Result<Arc<IndexReaderBridge>, TantivySearchError> {
+    FFI_INDEX_SEARCHER_CACHE
+        .get_index_reader_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function: function, "{}", e);
+            TantivySearchError::InternalError(e)
+        })
+}
+
+fn register_security_filter(
+    function: &str,
+    index_path: &str,
+    principal: &str,
+    filter: SecurityFilter,
+    security_filters: &SecurityFilters,
+) -> Result<bool, TantivySearchError> {
+    if principal.is_empty() {
+        let error_info: String = "principal can't be empty".to_string();
+        ERROR!(function: function, "{}", error_info);
+        return Err(TantivySearchError::InvalidArgument(error_info));
+    }
+    security_filters.register(principal, filter).map_err(|e| {
+        ERROR!(function: function, "{}", e);
+        TantivySearchError::InternalError(e)
+    })?;
+    DEBUG!(function: function, "Registered security filter of principal:{}, principals:{}, index_path:[{}]", principal, security_filters.num_principals(), index_path);
+    Ok(true)
+}
+
+/// Restrict searches of `principal` on a loaded reader to rows matching `sentence`, a previous
+/// filter of the principal is replaced. Filters are kept in memory, they survive reader reloads
+/// but must be registered again after the reader is freed.
+pub fn register_security_filter_query(
+    index_path: &str,
+    principal: &str,
+    sentence: &str,
+) -> Result<bool, TantivySearchError> {
+    let index_reader_bridge: Arc<IndexReaderBridge> =
+        get_security_filters_bridge("register_security_filter_query", index_path)?;
+    let index: &Index = &index_reader_bridge.index;
+    let query_fields: Vec<Field> = bm25_text_fields(&index.schema(), &Vec::new()).map_err(|e| {
+        ERROR!(function:"register_security_filter_query", "{}", e);
+        TantivySearchError::IndexSearcherError(e)
+    })?;
+    let query = QueryParser::for_index(index, query_fields)
+        .parse_query(sentence)
+        .map_err(|e| {
+            let error_info: String = format!("Error when parse: {}. {}", sentence, e);
+            ERROR!(function:"register_security_filter_query", "{}", error_info);
+            TantivySearchError::InvalidArgument(error_info)
+        })?;
+    let filter = SecurityFilter::Query {
+        sentence: sentence.to_string(),
+        query,
+    };
+    register_security_filter(
+        "register_security_filter_query",
+        index_path,
+        principal,
+        filter,
+        &index_reader_bridge.security_filters,
+    )
+}
+
+/// Restrict searches of `principal` on a loaded reader to `row_ids`, as
+/// `register_security_filter_query`.
+pub fn register_security_filter_row_ids(
+    index_path: &str,
+    principal: &str,
+    row_ids: &[u64],
+) -> Result<bool, TantivySearchError> {
+    let index_reader_bridge: Arc<IndexReaderBridge> =
+        get_security_filters_bridge("register_security_filter_row_ids", index_path)?;
+    let filter = SecurityFilter::RowIds(row_ids.iter().copied().collect());
+    register_security_filter(
+        "register_security_filter_row_ids",
+        index_path,
+        principal,
+        filter,
+        &index_reader_bridge.security_filters,
+    )
+}
+
+/// Remove security filter of `principal`, `false` if it has none.
+/// Searches of the principal see nothing until a filter is registered again.
+pub fn unregister_security_filter(
+    index_path: &str,
+    principal: &str,
+) -> Result<bool, TantivySearchError> {
+    let index_reader_bridge: Arc<IndexReaderBridge> =
+        get_security_filters_bridge("unregister_security_filter", index_path)?;
+    index_reader_bridge
+        .security_filters
+        .unregister(principal)
+        .map_err(|e| {
+            ERROR!(function:"unregister_security_filter", "{}", e);
+            TantivySearchError::InternalError(e)
+        })
+}
+
+/// Bind `principal` to searches of the calling thread, empty `principal` unbinds it.
+/// Security filter of the principal is ANDed into every search of the thread, a principal
+/// without filter on the searched index sees nothing.
+pub fn set_search_principal(principal: &str) -> Result<bool, TantivySearchError> {
+    let principal: Option<String> = match principal.is_empty() {
+        true => None,
+        false => Some(principal.to_string()),
+    };
+    SearchPrincipal::set(principal);
+    Ok(true)
+}
+
+/// Record `sample_rate` of searches of all index readers into query audit log, as
+/// (timestamp, index, principal, query, hit count, latency). Records go to audit callback
+/// installed by host and are appended to `file_path` as json lines, empty `file_path` keeps
+/// no file. Rate 0 disables auditing.
+pub fn configure_query_audit(
+    file_path: &str,
+    sample_rate: f64,
+) -> Result<bool, TantivySearchError> {
+    let file_path: Option<&str> = match file_path.is_empty() {
+        true => None,
+        false => Some(file_path),
+    };
+    QUERY_AUDIT.configure(file_path, sample_rate).map_err(|e| {
+        ERROR!(function: "configure_query_audit", "{}", e);
+        TantivySearchError::InvalidArgument(e)
+    })?;
+    Ok(true)
+}
+
+/// Limit cost of queries of all index readers, queries over a limit fail with
+/// `QueryTooExpensive` before they are executed. 0 means unlimited.
+pub fn set_query_cost_limits(limits: QueryCostLimits) -> Result<bool, TantivySearchError> {
+    QUERY_COST_GUARD.set_limits(limits);
+    DEBUG!(function: "set_query_cost_limits", "Query cost limits: {:?}", QUERY_COST_GUARD.limits());
+    Ok(true)
+}
@@ -1,22 +1,59 @@
 use crate::common::errors::TantivySearchError;
+use crate::common::hyper_log_log::HyperLogLog;
+use crate::common::t_digest::TDigest;
 use crate::ffi::DocWithFreq;
+use crate::ffi::GroupTopDoc;
+use crate::ffi::MatchedOffset;
+use crate::ffi::MatchedTermsTopDoc;
+use crate::ffi::StoredDoc;
+use crate::ffi::TopDocsWithTotalHits;
 use crate::logger::logger_bridge::TantivySearchLogger;
 use crate::search::bridge::index_reader_bridge::IndexReaderBridge;
+use crate::search::bridge::index_reader_percolator::Percolator;
+use crate::search::bridge::index_reader_searcher_handles::SearcherHandle;
+use crate::search::bridge::index_reader_vector_cache::VectorCache;
+use crate::search::implements::api_common_impl::column_tokenizer_config;
+use crate::tokenizer::language_routing::language_subfield_columns;
+use crate::tokenizer::sparse_vector_format::SparseVectorFormat;
 use crate::RowIdWithScore;
 use crate::FFI_INDEX_SEARCHER_CACHE;
+use crate::SEARCHER_HANDLES;
 use crate::{common::constants::LOG_CALLBACK, ERROR, WARNING};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use tantivy::collector::DocSetCollector;
 use tantivy::query::Bm25StatisticsProvider;
+use tantivy::query::Query;
+use tantivy::query::QueryParser;
+use tantivy::query::TermQuery;
 
+use super::strategy::query_dsl::QueryDsl;
+use super::strategy::query_strategy::bm25_text_fields;
 use super::strategy::query_strategy::BM25QueryStrategy64;
+use super::strategy::query_strategy::CardinalityQueryStrategy;
+use super::strategy::query_strategy::GroupedBM25QueryStrategy;
+use super::strategy::query_strategy::KnnQueryStrategy64;
+use super::strategy::query_strategy::MatchedTermsQueryStrategy;
+use super::strategy::query_strategy::OrderByFieldQueryStrategy;
+use super::strategy::query_strategy::PercentilesQueryStrategy;
+use super::strategy::query_strategy::QueryDslStrategy64;
 use super::strategy::query_strategy::QueryExecutor;
+use super::strategy::query_strategy::SparseVectorQueryStrategy64;
+use super::strategy::query_strategy::TotalHitsQueryStrategy;
+use super::strategy::similarity_query::{Bm25Params, Similarity};
 use crate::DEBUG;
 use crate::TRACE;
+use tantivy::schema::Field;
 use tantivy::schema::FieldType;
+use tantivy::schema::IndexRecordOption;
 use tantivy::schema::Schema;
 use tantivy::schema::TextFieldIndexing;
+use tantivy::schema::Value;
 use tantivy::tokenizer::BoxTokenStream;
 use tantivy::tokenizer::TextAnalyzer;
+use tantivy::DocAddress;
+use tantivy::Searcher;
+use tantivy::TantivyDocument;
 use tantivy::Term;
 
 pub fn bm25_search_with_column_names(
@@ -31,15 +68,677 @@ pub fn bm25_search_with_column_names(
     need_doc: bool,
     column_names: &Vec<String>,
 ) -> Result<Vec<RowIdWithScore>, TantivySearchError> {
+    bm25_search_at_generation(
+        index_path,
+        sentence,
+        topk,
+        alived_ids,
+        query_with_filter,
+        query_with_id_range,
+        start_id,
+        end_id,
+        need_doc,
+        column_names,
+        None,
+    )
+}
+
+/// Execute BM25 search over columns of `field_boosts`, scores of a column are multiplied by its boost,
+/// e.g. `{"title": 3.0, "body": 1.0}` ranks docs matched in title above docs matched in body.
+pub fn bm25_search_with_field_boosts(
+    index_path: &str,
+    sentence: &str,
+    topk: u32,
+    alived_ids: &Vec<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    need_doc: bool,
+    field_boosts: &HashMap<String, f32>,
+) -> Result<Vec<RowIdWithScore>, TantivySearchError> {
+    if field_boosts.is_empty() {
+        let error_msg = "field_boosts can't be empty".to_string();
+        ERROR!(function:"bm25_search_with_field_boosts", "{}", error_msg);
+        return Err(TantivySearchError::InvalidArgument(error_msg));
+    }
+    if let Some((column_name, boost)) = field_boosts
+        .iter()
+        .find(|(_, boost)| !boost.is_finite() || **boost <= 0.0)
+    {
+        let error_msg = format!(
+            "Boost of column `{}` should be positive, got {}",
+            column_name, boost
+        );
+        ERROR!(function:"bm25_search_with_field_boosts", "{}", error_msg);
+        return Err(TantivySearchError::InvalidArgument(error_msg));
+    }
+    let mut column_names: Vec<String> = field_boosts.keys().cloned().collect();
+    column_names.sort();
+
+    bm25_search_page(
+        index_path,
+        sentence,
+        topk,
+        0,
+        None,
+        alived_ids,
+        query_with_filter,
+        query_with_id_range,
+        start_id,
+        end_id,
+        need_doc,
+        &column_names,
+        field_boosts,
+        None,
+    )
+}
+
+/// Execute BM25 search with a pinned searcher generation, `None` means the latest searcher.
+pub fn bm25_search_at_generation(
+    index_path: &str,
+    sentence: &str,
+    topk: u32,
+    alived_ids: &Vec<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    need_doc: bool,
+    column_names: &Vec<String>,
+    generation: Option<u64>,
+) -> Result<Vec<RowIdWithScore>, TantivySearchError> {
+    bm25_search_page(
+        index_path,
+        sentence,
+        topk,
+        0,
+        None,
+        alived_ids,
+        query_with_filter,
+        query_with_id_range,
+        start_id,
+        end_id,
+        need_doc,
+        column_names,
+        &HashMap::new(),
+        generation,
+    )
+}
+
+/// Execute BM25 search for one page of results.
+/// Results are ranked by score desc and row_id asc, `offset` skips the first results,
+/// `search_after` only keeps results ranked after the last `(score, row_id)` of previous page.
+pub fn bm25_search_page(
+    index_path: &str,
+    sentence: &str,
+    topk: u32,
+    offset: u32,
+    search_after: Option<(f32, u64)>,
+    alived_ids: &Vec<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    need_doc: bool,
+    column_names: &Vec<String>,
+    field_boosts: &HashMap<String, f32>,
+    generation: Option<u64>,
+) -> Result<Vec<RowIdWithScore>, TantivySearchError> {
+    // Get index_reader_bridge from CACHE
+    let index_reader_bridge: Arc<IndexReaderBridge> = FFI_INDEX_SEARCHER_CACHE
+        .get_index_reader_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function:"bm25_search", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+
+    bm25_search_page_with_bridge(
+        &index_reader_bridge,
+        sentence,
+        topk,
+        offset,
+        search_after,
+        alived_ids,
+        query_with_filter,
+        query_with_id_range,
+        start_id,
+        end_id,
+        need_doc,
+        column_names,
+        field_boosts,
+        generation,
+        false,
+        None,
+        None,
+    )
+}
+
+/// Execute BM25 search scored with BM25 parameters `k1` and `b` instead of the index default ones.
+pub fn bm25_search_with_bm25_params(
+    index_path: &str,
+    sentence: &str,
+    topk: u32,
+    alived_ids: &Vec<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    need_doc: bool,
+    column_names: &Vec<String>,
+    k1: f32,
+    b: f32,
+) -> Result<Vec<RowIdWithScore>, TantivySearchError> {
+    let bm25_params: Bm25Params = Bm25Params::new(k1, b).map_err(|e| {
+        ERROR!(function:"bm25_search_with_bm25_params", "{}", e);
+        TantivySearchError::InvalidArgument(e)
+    })?;
+    let index_reader_bridge: Arc<IndexReaderBridge> = FFI_INDEX_SEARCHER_CACHE
+        .get_index_reader_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function:"bm25_search_with_bm25_params", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+
+    bm25_search_page_with_bridge(
+        &index_reader_bridge,
+        sentence,
+        topk,
+        0,
+        None,
+        alived_ids,
+        query_with_filter,
+        query_with_id_range,
+        start_id,
+        end_id,
+        need_doc,
+        column_names,
+        &HashMap::new(),
+        None,
+        false,
+        Some(Similarity::Bm25(bm25_params)),
+        None,
+    )
+}
+
+/// Execute BM25 search keeping at most `max_per_key` results for each value of a numeric or date
+/// fast field, e.g. at most 2 results per domain. Docs missing the field share one key.
+pub fn bm25_search_diversified(
+    index_path: &str,
+    sentence: &str,
+    topk: u32,
+    diversify_field: &str,
+    max_per_key: u32,
+    alived_ids: &Vec<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    need_doc: bool,
+    column_names: &Vec<String>,
+) -> Result<Vec<RowIdWithScore>, TantivySearchError> {
+    if max_per_key == 0 {
+        let error_msg = "max_per_key should be positive".to_string();
+        ERROR!(function:"bm25_search_diversified", "{}", error_msg);
+        return Err(TantivySearchError::InvalidArgument(error_msg));
+    }
+    let index_reader_bridge: Arc<IndexReaderBridge> = FFI_INDEX_SEARCHER_CACHE
+        .get_index_reader_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function:"bm25_search_diversified", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+
+    bm25_search_page_with_bridge(
+        &index_reader_bridge,
+        sentence,
+        topk,
+        0,
+        None,
+        alived_ids,
+        query_with_filter,
+        query_with_id_range,
+        start_id,
+        end_id,
+        need_doc,
+        column_names,
+        &HashMap::new(),
+        None,
+        false,
+        None,
+        Some((diversify_field.to_string(), max_per_key)),
+    )
+}
+
+/// Execute BM25 query in filter mode, matched docs are collected without computing BM25 score.
+/// All results have the same score, so up to `topk` matched docs are returned in no particular order.
+pub fn bm25_filter_search(
+    index_path: &str,
+    sentence: &str,
+    topk: u32,
+    alived_ids: &Vec<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    column_names: &Vec<String>,
+) -> Result<Vec<RowIdWithScore>, TantivySearchError> {
+    let index_reader_bridge: Arc<IndexReaderBridge> = FFI_INDEX_SEARCHER_CACHE
+        .get_index_reader_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function:"bm25_filter_search", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+
+    bm25_search_page_with_bridge(
+        &index_reader_bridge,
+        sentence,
+        topk,
+        0,
+        None,
+        alived_ids,
+        query_with_filter,
+        query_with_id_range,
+        start_id,
+        end_id,
+        false,
+        column_names,
+        &HashMap::new(),
+        None,
+        true,
+        None,
+        None,
+    )
+}
+
+fn bm25_search_page_with_bridge(
+    index_reader_bridge: &IndexReaderBridge,
+    sentence: &str,
+    topk: u32,
+    offset: u32,
+    search_after: Option<(f32, u64)>,
+    alived_ids: &Vec<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    need_doc: bool,
+    column_names: &Vec<String>,
+    field_boosts: &HashMap<String, f32>,
+    generation: Option<u64>,
+    filter_mode: bool,
+    similarity: Option<Similarity>,
+    diversify: Option<(String, u32)>,
+) -> Result<Vec<RowIdWithScore>, TantivySearchError> {
+    // Choose query strategy to construct query executor.
+    let sentence_query: BM25QueryStrategy64<'_> = BM25QueryStrategy64 {
+        sentence,
+        topk: &topk,
+        alived_ids,
+        query_with_filter: &query_with_filter,
+        query_with_id_range: &query_with_id_range,
+        start_id: &start_id,
+        end_id: &end_id,
+        need_doc: &need_doc,
+        column_names: &column_names,
+        offset: &offset,
+        search_after: &search_after,
+        field_boosts,
+        similarity: &similarity.or(index_reader_bridge.similarity()),
+        diversify: &diversify,
+    };
+
+    let query_executor: QueryExecutor<'_, Vec<RowIdWithScore>> =
+        QueryExecutor::new(&sentence_query)
+            .with_deleted(index_reader_bridge.deleted_row_ids())
+            .with_principal_filter(index_reader_bridge.principal_filter())
+            .with_row_id_mapping(index_reader_bridge.row_id_mapping.clone())
+            .with_slow_query_threshold(
+                &index_reader_bridge.path,
+                index_reader_bridge.slow_query_threshold(),
+            )
+            .with_filter_mode(filter_mode);
+
+    let searcher = &mut index_reader_bridge.searcher_of(generation).map_err(|e| {
+        ERROR!(function:"bm25_search", "{}", e);
+        TantivySearchError::InvalidArgument(e)
+    })?;
+
+    let result: Vec<RowIdWithScore> = query_executor.execute(searcher).map_err(
+        |e: crate::common::errors::IndexSearcherError| {
+            ERROR!(function:"bm25_search", "{}", e);
+            TantivySearchError::IndexSearcherError(e)
+        },
+    )?;
+
+    Ok(result)
+}
+
+/// Execute ranked search with a query of the JSON query DSL, e.g. `dis_max` over title and body.
+pub fn bm25_search_with_query_dsl(
+    index_path: &str,
+    query_json: &str,
+    topk: u32,
+    alived_ids: &Vec<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    need_doc: bool,
+) -> Result<Vec<RowIdWithScore>, TantivySearchError> {
+    let query: QueryDsl = QueryDsl::from_json(query_json).map_err(|e| {
+        ERROR!(function:"bm25_search_with_query_dsl", "{}", e);
+        TantivySearchError::IndexSearcherError(e)
+    })?;
+
+    // Get index_reader_bridge from CACHE
+    let index_reader_bridge: Arc<IndexReaderBridge> = FFI_INDEX_SEARCHER_CACHE
+        .get_index_reader_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function:"bm25_search_with_query_dsl", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+
+    let query_dsl_strategy: QueryDslStrategy64<'_> = QueryDslStrategy64 {
+        query: &query,
+        topk: &topk,
+        alived_ids,
+        query_with_filter: &query_with_filter,
+        query_with_id_range: &query_with_id_range,
+        start_id: &start_id,
+        end_id: &end_id,
+        need_doc: &need_doc,
+    };
+
+    let query_executor: QueryExecutor<'_, Vec<RowIdWithScore>> =
+        QueryExecutor::new(&query_dsl_strategy)
+            .with_deleted(index_reader_bridge.deleted_row_ids())
+            .with_principal_filter(index_reader_bridge.principal_filter())
+            .with_row_id_mapping(index_reader_bridge.row_id_mapping.clone())
+            .with_slow_query_threshold(
+                &index_reader_bridge.path,
+                index_reader_bridge.slow_query_threshold(),
+            );
+
+    let result: Vec<RowIdWithScore> = query_executor
+        .execute(&index_reader_bridge.reader.searcher())
+        .map_err(|e: crate::common::errors::IndexSearcherError| {
+            ERROR!(function:"bm25_search_with_query_dsl", "{}", e);
+            TantivySearchError::IndexSearcherError(e)
+        })?;
+
+    Ok(result)
+}
+
+/// Search `topk` rows whose vectors in `column_name` are nearest to `vector`, scored by metric
+/// of the column. Only rows matching `filter_sentence` are searched if it's not empty,
+/// so text filter and vector similarity are combined in one search.
+pub fn knn_search(
+    index_path: &str,
+    column_name: &str,
+    vector: &Vec<f32>,
+    topk: u32,
+    filter_sentence: &str,
+    alived_ids: &Vec<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    need_doc: bool,
+) -> Result<Vec<RowIdWithScore>, TantivySearchError> {
+    // Get index_reader_bridge from CACHE
+    let index_reader_bridge: Arc<IndexReaderBridge> = FFI_INDEX_SEARCHER_CACHE
+        .get_index_reader_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function:"knn_search", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+    let vector_cache: &Arc<VectorCache> =
+        index_reader_bridge.vector_cache.as_ref().ok_or_else(|| {
+            let error_info: String = format!("index:{} has no vector column", index_path);
+            ERROR!(function:"knn_search", "{}", error_info);
+            TantivySearchError::InvalidArgument(error_info)
+        })?;
+
+    let knn_query: KnnQueryStrategy64<'_> = KnnQueryStrategy64 {
+        column_name,
+        vector,
+        topk: &topk,
+        filter_sentence,
+        alived_ids,
+        query_with_filter: &query_with_filter,
+        query_with_id_range: &query_with_id_range,
+        start_id: &start_id,
+        end_id: &end_id,
+        need_doc: &need_doc,
+        vector_cache,
+    };
+
+    let query_executor: QueryExecutor<'_, Vec<RowIdWithScore>> = QueryExecutor::new(&knn_query)
+        .with_deleted(index_reader_bridge.deleted_row_ids())
+        .with_principal_filter(index_reader_bridge.principal_filter())
+        .with_row_id_mapping(index_reader_bridge.row_id_mapping.clone())
+        .with_slow_query_threshold(
+            &index_reader_bridge.path,
+            index_reader_bridge.slow_query_threshold(),
+        );
+
+    let result: Vec<RowIdWithScore> = query_executor
+        .execute(&index_reader_bridge.reader.searcher())
+        .map_err(|e: crate::common::errors::IndexSearcherError| {
+            ERROR!(function:"knn_search", "{}", e);
+            TantivySearchError::IndexSearcherError(e)
+        })?;
+
+    Ok(result)
+}
+
+/// Search `topk` rows by dot product of `query_json` term weights, e.g. `{"apple": 1.2}`,
+/// and term weights of rows in sparse vector column `column_name`. Only rows matching
+/// `filter_sentence` are searched if it's not empty.
+pub fn sparse_vector_search(
+    index_path: &str,
+    column_name: &str,
+    query_json: &str,
+    topk: u32,
+    filter_sentence: &str,
+    alived_ids: &Vec<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    need_doc: bool,
+) -> Result<Vec<RowIdWithScore>, TantivySearchError> {
+    // Get index_reader_bridge from CACHE
+    let index_reader_bridge: Arc<IndexReaderBridge> = FFI_INDEX_SEARCHER_CACHE
+        .get_index_reader_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function:"sparse_vector_search", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+    let sparse_vector_format: SparseVectorFormat =
+        column_tokenizer_config("sparse_vector_search", index_path, column_name)?
+            .and_then(|tokenizer_config| tokenizer_config.sparse_vector_format)
+            .ok_or_else(|| {
+                let error_info: String =
+                    format!("column:{} is not a sparse vector column", column_name);
+                ERROR!(function:"sparse_vector_search", "{}", error_info);
+                TantivySearchError::InvalidArgument(error_info)
+            })?;
+    let weights: Vec<(String, f32)> = SparseVectorFormat::parse(query_json).map_err(|e| {
+        ERROR!(function:"sparse_vector_search", "{}", e);
+        TantivySearchError::InvalidArgument(e)
+    })?;
+
+    let sparse_vector_query: SparseVectorQueryStrategy64<'_> = SparseVectorQueryStrategy64 {
+        column_name,
+        weights: &weights,
+        sparse_vector_format: &sparse_vector_format,
+        topk: &topk,
+        filter_sentence,
+        alived_ids,
+        query_with_filter: &query_with_filter,
+        query_with_id_range: &query_with_id_range,
+        start_id: &start_id,
+        end_id: &end_id,
+        need_doc: &need_doc,
+    };
+
+    let query_executor: QueryExecutor<'_, Vec<RowIdWithScore>> =
+        QueryExecutor::new(&sparse_vector_query)
+            .with_deleted(index_reader_bridge.deleted_row_ids())
+            .with_principal_filter(index_reader_bridge.principal_filter())
+            .with_row_id_mapping(index_reader_bridge.row_id_mapping.clone())
+            .with_slow_query_threshold(
+                &index_reader_bridge.path,
+                index_reader_bridge.slow_query_threshold(),
+            );
+
+    let result: Vec<RowIdWithScore> = query_executor
+        .execute(&index_reader_bridge.reader.searcher())
+        .map_err(|e: crate::common::errors::IndexSearcherError| {
+            ERROR!(function:"sparse_vector_search", "{}", e);
+            TantivySearchError::IndexSearcherError(e)
+        })?;
+
+    Ok(result)
+}
+
+/// Execute BM25 search and order results by a numeric or date fast field.
+/// BM25 score is the tiebreak of docs with the same field value, docs missing the field are ranked last.
+pub fn bm25_search_order_by_field(
+    index_path: &str,
+    sentence: &str,
+    topk: u32,
+    order_field: &str,
+    ascending: bool,
+    alived_ids: &Vec<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    need_doc: bool,
+    column_names: &Vec<String>,
+) -> Result<Vec<RowIdWithScore>, TantivySearchError> {
+    // Get index_reader_bridge from CACHE
+    let index_reader_bridge: Arc<IndexReaderBridge> = FFI_INDEX_SEARCHER_CACHE
+        .get_index_reader_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function:"bm25_search_order_by_field", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+
+    let order_by_field_query: OrderByFieldQueryStrategy<'_> = OrderByFieldQueryStrategy {
+        sentence,
+        topk: &topk,
+        order_field,
+        ascending: &ascending,
+        alived_ids,
+        query_with_filter: &query_with_filter,
+        query_with_id_range: &query_with_id_range,
+        start_id: &start_id,
+        end_id: &end_id,
+        need_doc: &need_doc,
+        column_names,
+    };
+
+    let query_executor: QueryExecutor<'_, Vec<RowIdWithScore>> =
+        QueryExecutor::new(&order_by_field_query)
+            .with_deleted(index_reader_bridge.deleted_row_ids())
+            .with_principal_filter(index_reader_bridge.principal_filter())
+            .with_row_id_mapping(index_reader_bridge.row_id_mapping.clone())
+            .with_slow_query_threshold(
+                &index_reader_bridge.path,
+                index_reader_bridge.slow_query_threshold(),
+            );
+
+    let result: Vec<RowIdWithScore> = query_executor
+        .execute(&index_reader_bridge.reader.searcher())
+        .map_err(|e: crate::common::errors::IndexSearcherError| {
+            ERROR!(function:"bm25_search_order_by_field", "{}", e);
+            TantivySearchError::IndexSearcherError(e)
+        })?;
+
+    Ok(result)
+}
+
+/// Execute BM25 search and collapse results by a u64 fast field, each group keeps its best hit
+/// and hit count. Groups are ranked by score of their best hit, `topk` limits number of groups.
+pub fn bm25_search_grouped(
+    index_path: &str,
+    sentence: &str,
+    topk: u32,
+    group_field: &str,
+    alived_ids: &Vec<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    need_doc: bool,
+    column_names: &Vec<String>,
+) -> Result<Vec<GroupTopDoc>, TantivySearchError> {
+    // Get index_reader_bridge from CACHE
+    let index_reader_bridge: Arc<IndexReaderBridge> = FFI_INDEX_SEARCHER_CACHE
+        .get_index_reader_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function:"bm25_search_grouped", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+
+    let grouped_query: GroupedBM25QueryStrategy<'_> = GroupedBM25QueryStrategy {
+        sentence,
+        topk: &topk,
+        group_field,
+        alived_ids,
+        query_with_filter: &query_with_filter,
+        query_with_id_range: &query_with_id_range,
+        start_id: &start_id,
+        end_id: &end_id,
+        need_doc: &need_doc,
+        column_names,
+    };
+
+    let query_executor: QueryExecutor<'_, Vec<GroupTopDoc>> = QueryExecutor::new(&grouped_query)
+        .with_deleted(index_reader_bridge.deleted_row_ids())
+        .with_principal_filter(index_reader_bridge.principal_filter())
+        .with_row_id_mapping(index_reader_bridge.row_id_mapping.clone())
+        .with_slow_query_threshold(
+            &index_reader_bridge.path,
+            index_reader_bridge.slow_query_threshold(),
+        );
+
+    let result: Vec<GroupTopDoc> = query_executor
+        .execute(&index_reader_bridge.reader.searcher())
+        .map_err(|e: crate::common::errors::IndexSearcherError| {
+            ERROR!(function:"bm25_search_grouped", "{}", e);
+            TantivySearchError::IndexSearcherError(e)
+        })?;
+
+    Ok(result)
+}
+
+/// Execute BM25 search and report query terms matched in each result and the column they
+/// matched in, so results are explained without analyzing their docs again.
+pub fn bm25_search_with_matched_terms(
+    index_path: &str,
+    sentence: &str,
+    topk: u32,
+    alived_ids: &Vec<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    need_doc: bool,
+    column_names: &Vec<String>,
+) -> Result<Vec<MatchedTermsTopDoc>, TantivySearchError> {
     // Get index_reader_bridge from CACHE
     let index_reader_bridge: Arc<IndexReaderBridge> = FFI_INDEX_SEARCHER_CACHE
         .get_index_reader_bridge(index_path.to_string())
         .map_err(|e| {
-            ERROR!(function:"bm25_search", "{}", e);
+            ERROR!(function:"bm25_search_with_matched_terms", "{}", e);
             TantivySearchError::InternalError(e)
         })?;
 
-    // Choose query strategy to construct query executor.
     let sentence_query: BM25QueryStrategy64<'_> = BM25QueryStrategy64 {
         sentence,
         topk: &topk,
@@ -49,24 +748,578 @@ pub fn bm25_search_with_column_names(
         start_id: &start_id,
         end_id: &end_id,
         need_doc: &need_doc,
-        column_names: &column_names,
+        column_names,
+        offset: &0,
+        search_after: &None,
+        field_boosts: &HashMap::new(),
+        similarity: &index_reader_bridge.similarity(),
+        diversify: &None,
+    };
+    let matched_terms_query: MatchedTermsQueryStrategy<'_> = MatchedTermsQueryStrategy {
+        bm25_query: &sentence_query,
     };
 
-    let query_executor: QueryExecutor<'_, Vec<RowIdWithScore>> =
-        QueryExecutor::new(&sentence_query);
+    let query_executor: QueryExecutor<'_, Vec<MatchedTermsTopDoc>> =
+        QueryExecutor::new(&matched_terms_query)
+            .with_deleted(index_reader_bridge.deleted_row_ids())
+            .with_principal_filter(index_reader_bridge.principal_filter())
+            .with_row_id_mapping(index_reader_bridge.row_id_mapping.clone())
+            .with_slow_query_threshold(
+                &index_reader_bridge.path,
+                index_reader_bridge.slow_query_threshold(),
+            );
+
+    let result: Vec<MatchedTermsTopDoc> = query_executor
+        .execute(&index_reader_bridge.reader.searcher())
+        .map_err(|e: crate::common::errors::IndexSearcherError| {
+            ERROR!(function:"bm25_search_with_matched_terms", "{}", e);
+            TantivySearchError::IndexSearcherError(e)
+        })?;
 
-    let searcher = &mut index_reader_bridge.reader.searcher();
+    Ok(result)
+}
 
-    let result: Vec<RowIdWithScore> = query_executor.execute(searcher).map_err(
-        |e: crate::common::errors::IndexSearcherError| {
-            ERROR!(function:"bm25_search", "{}", e);
+/// Execute BM25 search and count distinct row_ids matching `sentence` in the same pass.
+/// Total hits are exact if `exact_total_hits`, otherwise docs which can't rank in `topk` may be
+/// skipped and the result tells whether total hits are a lower bound.
+pub fn bm25_search_with_total_hits(
+    index_path: &str,
+    sentence: &str,
+    topk: u32,
+    alived_ids: &Vec<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    need_doc: bool,
+    column_names: &Vec<String>,
+    exact_total_hits: bool,
+) -> Result<TopDocsWithTotalHits, TantivySearchError> {
+    // Get index_reader_bridge from CACHE
+    let index_reader_bridge: Arc<IndexReaderBridge> = FFI_INDEX_SEARCHER_CACHE
+        .get_index_reader_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function:"bm25_search_with_total_hits", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+
+    let sentence_query: BM25QueryStrategy64<'_> = BM25QueryStrategy64 {
+        sentence,
+        topk: &topk,
+        alived_ids,
+        query_with_filter: &query_with_filter,
+        query_with_id_range: &query_with_id_range,
+        start_id: &start_id,
+        end_id: &end_id,
+        need_doc: &need_doc,
+        column_names,
+        offset: &0,
+        search_after: &None,
+        field_boosts: &HashMap::new(),
+        similarity: &index_reader_bridge.similarity(),
+        diversify: &None,
+    };
+    let total_hits_query: TotalHitsQueryStrategy<'_> = TotalHitsQueryStrategy {
+        bm25_query: &sentence_query,
+        exact_total_hits: &exact_total_hits,
+    };
+
+    let query_executor: QueryExecutor<'_, TopDocsWithTotalHits> =
+        QueryExecutor::new(&total_hits_query)
+            .with_deleted(index_reader_bridge.deleted_row_ids())
+            .with_principal_filter(index_reader_bridge.principal_filter())
+            .with_row_id_mapping(index_reader_bridge.row_id_mapping.clone())
+            .with_slow_query_threshold(
+                &index_reader_bridge.path,
+                index_reader_bridge.slow_query_threshold(),
+            );
+
+    let result: TopDocsWithTotalHits = query_executor
+        .execute(&index_reader_bridge.reader.searcher())
+        .map_err(|e: crate::common::errors::IndexSearcherError| {
+            ERROR!(function:"bm25_search_with_total_hits", "{}", e);
             TantivySearchError::IndexSearcherError(e)
-        },
-    )?;
+        })?;
+
+    Ok(result)
+}
+
+/// Estimate the number of distinct values of a numeric or date fast field among docs matching
+/// `sentence`. The returned sketch can be merged with sketches of other indexes.
+pub fn estimate_cardinality(
+    index_path: &str,
+    sentence: &str,
+    field_name: &str,
+    alived_ids: &Vec<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    column_names: &Vec<String>,
+) -> Result<HyperLogLog, TantivySearchError> {
+    // Get index_reader_bridge from CACHE
+    let index_reader_bridge: Arc<IndexReaderBridge> = FFI_INDEX_SEARCHER_CACHE
+        .get_index_reader_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function:"estimate_cardinality", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+
+    let cardinality_query: CardinalityQueryStrategy<'_> = CardinalityQueryStrategy {
+        sentence,
+        field_name,
+        alived_ids,
+        query_with_filter: &query_with_filter,
+        query_with_id_range: &query_with_id_range,
+        start_id: &start_id,
+        end_id: &end_id,
+        column_names,
+    };
+
+    let query_executor: QueryExecutor<'_, HyperLogLog> = QueryExecutor::new(&cardinality_query)
+        .with_deleted(index_reader_bridge.deleted_row_ids())
+        .with_principal_filter(index_reader_bridge.principal_filter())
+        .with_row_id_mapping(index_reader_bridge.row_id_mapping.clone())
+        .with_slow_query_threshold(
+            &index_reader_bridge.path,
+            index_reader_bridge.slow_query_threshold(),
+        );
+
+    let result: HyperLogLog = query_executor
+        .execute(&index_reader_bridge.reader.searcher())
+        .map_err(|e: crate::common::errors::IndexSearcherError| {
+            ERROR!(function:"estimate_cardinality", "{}", e);
+            TantivySearchError::IndexSearcherError(e)
+        })?;
+
+    Ok(result)
+}
+
+/// Merge serialized sketches of `estimate_cardinality`, e.g. sketches of different index parts.
+pub fn merge_cardinality_sketches(
+    left: &[u8],
+    right: &[u8],
+) -> Result<HyperLogLog, TantivySearchError> {
+    HyperLogLog::from_bytes(left)
+        .and_then(|mut sketch| {
+            sketch.merge(&HyperLogLog::from_bytes(right)?)?;
+            Ok(sketch)
+        })
+        .map_err(|e| {
+            ERROR!(function:"merge_cardinality_sketches", "{}", e);
+            TantivySearchError::InvalidArgument(e)
+        })
+}
+
+/// Build a t-digest of values of numeric fast field `field_name` in docs matching `sentence`.
+pub fn estimate_percentiles(
+    index_path: &str,
+    sentence: &str,
+    field_name: &str,
+    alived_ids: &Vec<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    column_names: &Vec<String>,
+) -> Result<TDigest, TantivySearchError> {
+    // Get index_reader_bridge from CACHE
+    let index_reader_bridge: Arc<IndexReaderBridge> = FFI_INDEX_SEARCHER_CACHE
+        .get_index_reader_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function:"estimate_percentiles", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+
+    let percentiles_query: PercentilesQueryStrategy<'_> = PercentilesQueryStrategy {
+        sentence,
+        field_name,
+        alived_ids,
+        query_with_filter: &query_with_filter,
+        query_with_id_range: &query_with_id_range,
+        start_id: &start_id,
+        end_id: &end_id,
+        column_names,
+    };
+
+    let query_executor: QueryExecutor<'_, TDigest> = QueryExecutor::new(&percentiles_query)
+        .with_deleted(index_reader_bridge.deleted_row_ids())
+        .with_principal_filter(index_reader_bridge.principal_filter())
+        .with_row_id_mapping(index_reader_bridge.row_id_mapping.clone())
+        .with_slow_query_threshold(
+            &index_reader_bridge.path,
+            index_reader_bridge.slow_query_threshold(),
+        );
+
+    let result: TDigest = query_executor
+        .execute(&index_reader_bridge.reader.searcher())
+        .map_err(|e: crate::common::errors::IndexSearcherError| {
+            ERROR!(function:"estimate_percentiles", "{}", e);
+            TantivySearchError::IndexSearcherError(e)
+        })?;
 
     Ok(result)
 }
 
+/// Merge serialized digests of `estimate_percentiles`, e.g. digests of different index parts.
+pub fn merge_percentiles_digests(left: &[u8], right: &[u8]) -> Result<TDigest, TantivySearchError> {
+    TDigest::from_bytes(left)
+        .and_then(|mut digest| {
+            digest.merge(&TDigest::from_bytes(right)?)?;
+            Ok(digest)
+        })
+        .map_err(|e| {
+            ERROR!(function:"merge_percentiles_digests", "{}", e);
+            TantivySearchError::InvalidArgument(e)
+        })
+}
+
+/// Values of `digest` at each of `percents`, NaN when the digest is empty.
+pub fn digest_percentiles(
+    digest: &mut TDigest,
+    percents: &[f64],
+) -> Result<Vec<f64>, TantivySearchError> {
+    if let Some(percent) = percents
+        .iter()
+        .find(|percent| !(0.0..=100.0).contains(*percent))
+    {
+        let error_info: String = format!("percent should be in [0, 100], got {}", percent);
+        ERROR!(function:"digest_percentiles", "{}", error_info);
+        return Err(TantivySearchError::InvalidArgument(error_info));
+    }
+    Ok(percents
+        .iter()
+        .map(|percent| digest.percentile(*percent))
+        .collect())
+}
+
+/// Read stored values of `field_names` for each row_id, values keep the order of `field_names`.
+/// Row_ids not found or lightweight deleted are skipped, missing values are empty strings.
+pub fn get_stored_fields(
+    index_path: &str,
+    row_ids: &Vec<u64>,
+    field_names: &Vec<String>,
+) -> Result<Vec<StoredDoc>, TantivySearchError> {
+    // Get index_reader_bridge from CACHE
+    let index_reader_bridge: Arc<IndexReaderBridge> = FFI_INDEX_SEARCHER_CACHE
+        .get_index_reader_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function:"get_stored_fields", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+
+    let searcher: Searcher = index_reader_bridge.reader.searcher();
+    get_stored_fields_with_searcher(&index_reader_bridge, &searcher, row_ids, field_names)
+}
+
+fn get_stored_fields_with_searcher(
+    index_reader_bridge: &IndexReaderBridge,
+    searcher: &Searcher,
+    row_ids: &Vec<u64>,
+    field_names: &Vec<String>,
+) -> Result<Vec<StoredDoc>, TantivySearchError> {
+    if field_names.is_empty() {
+        let error_info: String = "field_names can't be empty".to_string();
+        ERROR!(function:"get_stored_fields", "{}", error_info);
+        return Err(TantivySearchError::InvalidArgument(error_info));
+    }
+
+    // Only stored fields can be read back.
+    let schema: Schema = index_reader_bridge.index.schema();
+    let mut fields: Vec<Field> = Vec::with_capacity(field_names.len());
+    for field_name in field_names {
+        let field: Field = schema.get_field(field_name).map_err(|e| {
+            ERROR!(function:"get_stored_fields", "{}", e);
+            TantivySearchError::TantivyError(e)
+        })?;
+        if !schema.get_field_entry(field).is_stored() {
+            let error_info: String = format!("field:{} is not stored", field_name);
+            ERROR!(function:"get_stored_fields", "{}", error_info);
+            return Err(TantivySearchError::InvalidArgument(error_info));
+        }
+        fields.push(field);
+    }
+    let row_id_field: Field = schema.get_field("row_id").map_err(|e| {
+        ERROR!(function:"get_stored_fields", "{}", e);
+        TantivySearchError::TantivyError(e)
+    })?;
+
+    // Rows hidden from the principal are read back as if they were deleted.
+    let deleted_row_ids = index_reader_bridge
+        .excluded_row_ids(searcher)
+        .map_err(|e| {
+            ERROR!(function:"get_stored_fields", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+    let mut stored_docs: Vec<StoredDoc> = Vec::with_capacity(row_ids.len());
+    for row_id in row_ids {
+        if let Some(deleted_row_ids) = &deleted_row_ids {
+            if deleted_row_ids.contains(*row_id) {
+                continue;
+            }
+        }
+        let doc_address: Option<DocAddress> =
+            doc_address_of_row_id(index_reader_bridge, searcher, row_id_field, *row_id).map_err(
+                |e| {
+                    ERROR!(function:"get_stored_fields", "{}", e);
+                    TantivySearchError::TantivyError(e)
+                },
+            )?;
+        let doc_address: DocAddress = match doc_address {
+            Some(doc_address) => doc_address,
+            None => continue,
+        };
+
+        let document: TantivyDocument = searcher.doc(doc_address).map_err(|e| {
+            ERROR!(function:"get_stored_fields", "Error when read doc: {}", e);
+            TantivySearchError::TantivyError(e)
+        })?;
+        let values: Vec<String> = fields
+            .iter()
+            .map(|field| match document.get_first(*field) {
+                Some(value) => {
+                    if let Some(text) = value.as_str() {
+                        text.to_string()
+                    } else if let Some(number) = value.as_i64() {
+                        number.to_string()
+                    } else if let Some(number) = value.as_f64() {
+                        number.to_string()
+                    } else if let Some(number) = value.as_u64() {
+                        number.to_string()
+                    } else if let Some(bytes) = value.as_bytes() {
+                        String::from_utf8_lossy(bytes).to_string()
+                    } else {
+                        String::new()
+                    }
+                }
+                None => String::new(),
+            })
+            .collect();
+        stored_docs.push(StoredDoc {
+            row_id: *row_id,
+            values,
+        });
+    }
+    DEBUG!(function:"get_stored_fields", "row_ids size:{}, stored docs size:{}", row_ids.len(), stored_docs.len());
+    Ok(stored_docs)
+}
+
+// Address of doc `row_id` in `searcher`, `None` if it isn't found.
+fn doc_address_of_row_id(
+    index_reader_bridge: &IndexReaderBridge,
+    searcher: &Searcher,
+    row_id_field: Field,
+    row_id: u64,
+) -> tantivy::Result<Option<DocAddress>> {
+    match &index_reader_bridge.row_id_mapping {
+        Some(row_id_mapping) => Ok(row_id_mapping.doc_address(searcher, row_id)),
+        None => {
+            let term_query: TermQuery = TermQuery::new(
+                Term::from_field_u64(row_id_field, row_id),
+                IndexRecordOption::Basic,
+            );
+            Ok(searcher
+                .search(&term_query, &DocSetCollector)?
+                .into_iter()
+                .next())
+        }
+    }
+}
+
+/// Byte offsets of query terms of `sentence` in stored text of doc `row_id`, ordered by column
+/// and offset. Stored text is analyzed by index analyzers, so offsets point at the original text
+/// and can highlight the copy kept by host. Columns of `column_names` should be stored,
+/// a row_id not found or lightweight deleted has no offsets.
+pub fn get_matched_offsets(
+    index_path: &str,
+    row_id: u64,
+    sentence: &str,
+    column_names: &Vec<String>,
+) -> Result<Vec<MatchedOffset>, TantivySearchError> {
+    // Get index_reader_bridge from CACHE
+    let index_reader_bridge: Arc<IndexReaderBridge> = FFI_INDEX_SEARCHER_CACHE
+        .get_index_reader_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function:"get_matched_offsets", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+
+    let searcher: Searcher = index_reader_bridge.reader.searcher();
+    let schema: Schema = index_reader_bridge.index.schema();
+    let fields: Vec<Field> = bm25_text_fields(&schema, column_names).map_err(|e| {
+        ERROR!(function:"get_matched_offsets", "{}", e);
+        TantivySearchError::IndexSearcherError(e)
+    })?;
+    let subfield_columns: HashMap<Field, String> = language_subfield_columns(&schema);
+    // Stored text of a language sub-field is the text of its column.
+    let mut column_fields: Vec<(Field, Field)> = Vec::with_capacity(fields.len());
+    for field in &fields {
+        let column_field: Field = match subfield_columns.get(field) {
+            Some(column_name) => schema
+                .get_field(column_name)
+                .map_err(TantivySearchError::TantivyError)?,
+            None => *field,
+        };
+        if !schema.get_field_entry(column_field).is_stored() {
+            let error_info: String = format!(
+                "field:{} is not stored",
+                schema.get_field_name(column_field)
+            );
+            ERROR!(function:"get_matched_offsets", "{}", error_info);
+            return Err(TantivySearchError::InvalidArgument(error_info));
+        }
+        column_fields.push((*field, column_field));
+    }
+
+    let query_parser: QueryParser = QueryParser::for_index(&index_reader_bridge.index, fields);
+    let query: Box<dyn Query> = query_parser.parse_query(sentence).map_err(|e| {
+        ERROR!(function:"get_matched_offsets", "Error when parse: {}. {}", sentence, e);
+        TantivySearchError::IndexSearcherError(
+            crate::common::errors::IndexSearcherError::QueryParserError(e.to_string()),
+        )
+    })?;
+    let mut query_terms: HashSet<Term> = HashSet::new();
+    query.query_terms(&mut |term: &Term, _| {
+        query_terms.insert(term.clone());
+    });
+
+    let excluded_row_ids = index_reader_bridge
+        .excluded_row_ids(&searcher)
+        .map_err(|e| {
+            ERROR!(function:"get_matched_offsets", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+    if let Some(deleted_row_ids) = excluded_row_ids {
+        if deleted_row_ids.contains(row_id) {
+            return Ok(Vec::new());
+        }
+    }
+    let row_id_field: Field = schema.get_field("row_id").map_err(|e| {
+        ERROR!(function:"get_matched_offsets", "{}", e);
+        TantivySearchError::TantivyError(e)
+    })?;
+    let doc_address: DocAddress =
+        match doc_address_of_row_id(&index_reader_bridge, &searcher, row_id_field, row_id).map_err(
+            |e| {
+                ERROR!(function:"get_matched_offsets", "{}", e);
+                TantivySearchError::TantivyError(e)
+            },
+        )? {
+            Some(doc_address) => doc_address,
+            None => return Ok(Vec::new()),
+        };
+    let document: TantivyDocument = searcher.doc(doc_address).map_err(|e| {
+        ERROR!(function:"get_matched_offsets", "Error when read doc: {}", e);
+        TantivySearchError::TantivyError(e)
+    })?;
+
+    let mut matched_offsets: Vec<MatchedOffset> = Vec::new();
+    for (field, column_field) in column_fields {
+        let text: &str = match document
+            .get_first(column_field)
+            .and_then(|value| value.as_str())
+        {
+            Some(text) => text,
+            None => continue,
+        };
+        let mut text_analyzer: TextAnalyzer = match schema.get_field_entry(field).field_type() {
+            FieldType::Str(text_options) => {
+                match text_options.get_indexing_options().and_then(|indexing| {
+                    index_reader_bridge
+                        .index_tokenizers
+                        .get(indexing.tokenizer())
+                }) {
+                    Some(text_analyzer) => text_analyzer,
+                    None => continue,
+                }
+            }
+            _ => continue,
+        };
+        let mut token_stream: BoxTokenStream<'_> = text_analyzer.token_stream(text);
+        token_stream.process(&mut |token| {
+            if query_terms.contains(&Term::from_field_text(field, &token.text)) {
+                matched_offsets.push(MatchedOffset {
+                    field: schema.get_field_name(column_field).to_string(),
+                    term: token.text.clone(),
+                    start: token.offset_from as u64,
+                    end: token.offset_to as u64,
+                });
+            }
+        });
+    }
+    // A column and its language sub-field may match the same text.
+    matched_offsets.sort_by(|left, right| {
+        (&left.field, left.start, left.end, &left.term).cmp(&(
+            &right.field,
+            right.start,
+            right.end,
+            &right.term,
+        ))
+    });
+    matched_offsets.dedup();
+    Ok(matched_offsets)
+}
+
+// Percolator of a loaded index reader.
+fn get_percolator(function: &str, index_path: &str) -> Result<Arc<Percolator>, TantivySearchError> {
+    let index_reader_bridge: Arc<IndexReaderBridge> = FFI_INDEX_SEARCHER_CACHE
+        .get_index_reader_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function: function, "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+    index_reader_bridge.percolator.clone().ok_or_else(|| {
+        let error_info: String = format!("percolator of index:{} isn't loaded", index_path);
+        ERROR!(function: function, "{}", error_info);
+        TantivySearchError::InternalError(error_info)
+    })
+}
+
+/// Store `sentence` as query `query_id` for reverse search, it's persisted in the index directory.
+/// A query with the same id is replaced.
+pub fn register_percolator_query(
+    index_path: &str,
+    query_id: u64,
+    sentence: &str,
+) -> Result<bool, TantivySearchError> {
+    let percolator: Arc<Percolator> = get_percolator("register_percolator_query", index_path)?;
+    percolator.register(query_id, sentence).map_err(|e| {
+        ERROR!(function:"register_percolator_query", "{}", e);
+        TantivySearchError::InvalidArgument(e)
+    })?;
+    Ok(true)
+}
+
+/// Remove stored query `query_id`, `false` if it isn't stored.
+pub fn unregister_percolator_query(
+    index_path: &str,
+    query_id: u64,
+) -> Result<bool, TantivySearchError> {
+    let percolator: Arc<Percolator> = get_percolator("unregister_percolator_query", index_path)?;
+    percolator.unregister(query_id).map_err(|e| {
+        ERROR!(function:"unregister_percolator_query", "{}", e);
+        TantivySearchError::InternalError(e)
+    })
+}
+
+/// Ids of stored queries matching a doc given by `column_names` and `column_docs`, ascending.
+/// The doc isn't indexed, it's analyzed as if it were.
+pub fn percolate(
+    index_path: &str,
+    column_names: &Vec<String>,
+    column_docs: &Vec<String>,
+) -> Result<Vec<u64>, TantivySearchError> {
+    let percolator: Arc<Percolator> = get_percolator("percolate", index_path)?;
+    let query_ids: Vec<u64> = percolator
+        .percolate(column_names, column_docs)
+        .map_err(|e| {
+            ERROR!(function:"percolate", "{}", e);
+            TantivySearchError::InvalidArgument(e)
+        })?;
+    DEBUG!(function:"percolate", "matched queries:{}/{}, index_path:[{}]", query_ids.len(), percolator.num_queries(), index_path);
+    Ok(query_ids)
+}
+
 pub fn index_reader_reload(index_path: &str) -> Result<bool, TantivySearchError> {
     // Try reload index reader from CACHE
     let reload_status = match FFI_INDEX_SEARCHER_CACHE
@@ -88,6 +1341,136 @@ pub fn index_reader_reload(index_path: &str) -> Result<bool, TantivySearchError>
     Ok(reload_status)
 }
 
+pub fn pin_searcher(index_path: &str) -> Result<u64, TantivySearchError> {
+    // Get index_reader_bridge from CACHE
+    let index_reader_bridge: Arc<IndexReaderBridge> = FFI_INDEX_SEARCHER_CACHE
+        .get_index_reader_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function:"pin_searcher", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+
+    let generation = index_reader_bridge.pin_searcher().map_err(|e| {
+        ERROR!(function:"pin_searcher", "{}", e);
+        TantivySearchError::InternalError(e)
+    })?;
+    DEBUG!(function:"pin_searcher", "Pinned searcher generation:{}, index_path:[{}]", generation, index_path);
+    Ok(generation)
+}
+
+pub fn unpin_searcher(index_path: &str, generation: u64) -> Result<bool, TantivySearchError> {
+    // Get index_reader_bridge from CACHE
+    let index_reader_bridge: Arc<IndexReaderBridge> = FFI_INDEX_SEARCHER_CACHE
+        .get_index_reader_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function:"unpin_searcher", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+
+    index_reader_bridge.unpin_searcher(generation).map_err(|e| {
+        ERROR!(function:"unpin_searcher", "{}", e);
+        TantivySearchError::InternalError(e)
+    })
+}
+
+fn get_searcher_handle(
+    function: &str,
+    handle: u64,
+) -> Result<Arc<SearcherHandle>, TantivySearchError> {
+    SEARCHER_HANDLES.get(handle).map_err(|e| {
+        ERROR!(function: function, "{}", e);
+        TantivySearchError::InvalidArgument(e)
+    })
+}
+
+/// Pin current searcher of index reader and return an opaque handle id.
+/// Steps of a host operation using the handle see one consistent searcher until it's released.
+pub fn acquire_searcher_handle(index_path: &str) -> Result<u64, TantivySearchError> {
+    // Get index_reader_bridge from CACHE
+    let index_reader_bridge: Arc<IndexReaderBridge> = FFI_INDEX_SEARCHER_CACHE
+        .get_index_reader_bridge(index_path.to_string())
+        .map_err(|e| {
+            ERROR!(function:"acquire_searcher_handle", "{}", e);
+            TantivySearchError::InternalError(e)
+        })?;
+
+    let handle = SEARCHER_HANDLES.acquire(index_reader_bridge).map_err(|e| {
+        ERROR!(function:"acquire_searcher_handle", "{}", e);
+        TantivySearchError::InternalError(e)
+    })?;
+    DEBUG!(function:"acquire_searcher_handle", "Acquired searcher handle:{}, index_path:[{}]", handle, index_path);
+    Ok(handle)
+}
+
+/// Release a searcher handle, `false` if it doesn't exist or has been released.
+pub fn release_searcher_handle(handle: u64) -> Result<bool, TantivySearchError> {
+    SEARCHER_HANDLES.release(handle).map_err(|e| {
+        ERROR!(function:"release_searcher_handle", "{}", e);
+        TantivySearchError::InternalError(e)
+    })
+}
+
+/// Number of docs visible to the searcher of `handle`.
+pub fn searcher_handle_num_docs(handle: u64) -> Result<u64, TantivySearchError> {
+    let searcher_handle = get_searcher_handle("searcher_handle_num_docs", handle)?;
+    let searcher: Searcher = searcher_handle.searcher().map_err(|e| {
+        ERROR!(function:"searcher_handle_num_docs", "{}", e);
+        TantivySearchError::InternalError(e)
+    })?;
+    Ok(searcher.num_docs())
+}
+
+/// Execute BM25 search for one page of results with the searcher of `handle`, same as `bm25_search_page`.
+pub fn bm25_search_with_handle(
+    handle: u64,
+    sentence: &str,
+    topk: u32,
+    offset: u32,
+    search_after: Option<(f32, u64)>,
+    alived_ids: &Vec<u64>,
+    query_with_filter: bool,
+    query_with_id_range: bool,
+    start_id: u64,
+    end_id: u64,
+    need_doc: bool,
+    column_names: &Vec<String>,
+) -> Result<Vec<RowIdWithScore>, TantivySearchError> {
+    let searcher_handle = get_searcher_handle("bm25_search_with_handle", handle)?;
+    bm25_search_page_with_bridge(
+        &searcher_handle.bridge,
+        sentence,
+        topk,
+        offset,
+        search_after,
+        alived_ids,
+        query_with_filter,
+        query_with_id_range,
+        start_id,
+        end_id,
+        need_doc,
+        column_names,
+        &HashMap::new(),
+        Some(searcher_handle.generation),
+        false,
+        None,
+        None,
+    )
+}
+
+/// Read stored fields with the searcher of `handle`, same as `get_stored_fields`.
+pub fn get_stored_fields_with_handle(
+    handle: u64,
+    row_ids: &Vec<u64>,
+    field_names: &Vec<String>,
+) -> Result<Vec<StoredDoc>, TantivySearchError> {
+    let searcher_handle = get_searcher_handle("get_stored_fields_with_handle", handle)?;
+    let searcher: Searcher = searcher_handle.searcher().map_err(|e| {
+        ERROR!(function:"get_stored_fields_with_handle", "{}", e);
+        TantivySearchError::InternalError(e)
+    })?;
+    get_stored_fields_with_searcher(&searcher_handle.bridge, &searcher, row_ids, field_names)
+}
+
 pub fn get_doc_freq(
     index_path: &str,
     sentence: &str,
@@ -0,0 +1,222 @@
+use std::sync::Arc;
+
+use roaring::RoaringBitmap;
+use tantivy::{Searcher, Term};
+
+// Estimated matches above this fraction of docs are returned as a dense range.
+const DENSE_RANGE_MIN_SELECTIVITY: f64 = 0.5;
+
+/// How matched row_ids of a bitmap query are collected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CollectMode {
+    /// Picked from selectivity estimated by term doc frequencies before execution.
+    #[default]
+    Auto,
+    /// Matched row_ids.
+    Bitmap,
+    /// Row_id range of matched row_ids and row_ids not matched in it, small when most rows match.
+    DenseRange,
+    /// Number of matched row_ids only.
+    Count,
+}
+
+impl CollectMode {
+    /// Mode of `name`, one of `auto`, `bitmap`, `dense_range` and `count`.
+    pub fn from_name(name: &str) -> Option<CollectMode> {
+        match name.to_lowercase().as_str() {
+            "auto" | "" => Some(CollectMode::Auto),
+            "bitmap" => Some(CollectMode::Bitmap),
+            "dense_range" => Some(CollectMode::DenseRange),
+            "count" => Some(CollectMode::Count),
+            _ => None,
+        }
+    }
+}
+
+/// Selectivity of a term set query estimated by doc frequencies of its terms.
+/// Doc frequencies include deleted docs, so they only bound matches from above.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SelectivityEstimate {
+    pub max_doc_freq: u64,
+    pub sum_doc_freq: u64,
+    pub num_docs: u64,
+}
+
+impl SelectivityEstimate {
+    pub fn of_terms(searcher: &Searcher, terms: &[Term]) -> tantivy::Result<Self> {
+        let mut estimate = SelectivityEstimate {
+            max_doc_freq: 0,
+            sum_doc_freq: 0,
+            num_docs: searcher.num_docs(),
+        };
+        for term in terms {
+            let doc_freq: u64 = searcher.doc_freq(term)?;
+            estimate.max_doc_freq = estimate.max_doc_freq.max(doc_freq);
+            estimate.sum_doc_freq += doc_freq;
+        }
+        Ok(estimate)
+    }
+
+    /// Docs of different terms are assumed disjoint.
+    pub fn estimated_matches(&self) -> u64 {
+        self.sum_doc_freq.min(self.num_docs)
+    }
+
+    /// No doc has any term, it's exact.
+    pub fn is_empty(&self) -> bool {
+        self.max_doc_freq == 0
+    }
+
+    pub fn is_dense(&self) -> bool {
+        self.estimated_matches() as f64 > self.num_docs as f64 * DENSE_RANGE_MIN_SELECTIVITY
+    }
+
+    /// Mode executed for `mode`, `Auto` only counts when row_ids aren't needed.
+    pub fn resolve(&self, mode: CollectMode, need_row_ids: bool) -> CollectMode {
+        match mode {
+            CollectMode::Auto if !need_row_ids => CollectMode::Count,
+            CollectMode::Auto if self.is_dense() => CollectMode::DenseRange,
+            CollectMode::Auto => CollectMode::Bitmap,
+            mode => mode,
+        }
+    }
+}
+
+/// Matched row_ids of a bitmap query in the representation its collect mode chose.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AdaptiveRows {
+    /// Short-circuited before execution, no doc has any query term.
+    Empty,
+    Count(u64),
+    Bitmap(Arc<RoaringBitmap>),
+    /// Row_ids in `[start, end)` except `excluded`.
+    DenseRange {
+        start: u64,
+        end: u64,
+        excluded: RoaringBitmap,
+    },
+    /// Runs of consecutive row_ids, each of them is `[start, end)`.
+    Ranges(Vec<(u64, u64)>),
+}
+
+impl AdaptiveRows {
+    pub fn dense_range(bitmap: &RoaringBitmap) -> AdaptiveRows {
+        match (bitmap.min(), bitmap.max()) {
+            (Some(min), Some(max)) => {
+                let mut excluded: RoaringBitmap = RoaringBitmap::new();
+                excluded.insert_range(min..=max);
+                excluded -= bitmap;
+                AdaptiveRows::DenseRange {
+                    start: min as u64,
+                    end: max as u64 + 1,
+                    excluded,
+                }
+            }
+            _ => AdaptiveRows::Empty,
+        }
+    }
+
+    pub fn ranges(bitmap: &RoaringBitmap) -> AdaptiveRows {
+        let mut ranges: Vec<(u64, u64)> = Vec::new();
+        for row_id in bitmap.iter() {
+            let row_id: u64 = row_id as u64;
+            match ranges.last_mut() {
+                Some((_, end)) if *end == row_id => *end += 1,
+                _ => ranges.push((row_id, row_id + 1)),
+            }
+        }
+        AdaptiveRows::Ranges(ranges)
+    }
+
+    /// Bytes of row_ids returned to host, u8 bitmap has a bit for each row_id up to the largest.
+    pub fn encoded_size(&self) -> u64 {
+        match self {
+            AdaptiveRows::Empty | AdaptiveRows::Count(_) => 0,
+            AdaptiveRows::Bitmap(bitmap) => bitmap.max().map_or(0, |max| max as u64 / 8 + 1),
+            AdaptiveRows::DenseRange { excluded, .. } => 16 + excluded.len() * 4,
+            AdaptiveRows::Ranges(ranges) => ranges.len() as u64 * 16,
+        }
+    }
+
+    pub fn count(&self) -> u64 {
+        match self {
+            AdaptiveRows::Empty => 0,
+            AdaptiveRows::Count(count) => *count,
+            AdaptiveRows::Bitmap(bitmap) => bitmap.len(),
+            AdaptiveRows::DenseRange {
+                start,
+                end,
+                excluded,
+            } => end - start - excluded.len(),
+            AdaptiveRows::Ranges(ranges) => ranges.iter().map(|(start, end)| end - start).sum(),
+        }
+    }
+
+    pub fn strategy_name(&self) -> &'static str {
+        match self {
+            AdaptiveRows::Empty => "empty",
+            AdaptiveRows::Count(_) => "count",
+            AdaptiveRows::Bitmap(_) => "bitmap",
+            AdaptiveRows::DenseRange { .. } => "dense_range",
+            AdaptiveRows::Ranges(_) => "ranges",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adaptive_collect() {
+        let estimate = |max_doc_freq, sum_doc_freq| SelectivityEstimate {
+            max_doc_freq,
+            sum_doc_freq,
+            num_docs: 100,
+        };
+        assert!(estimate(0, 0).is_empty());
+        assert_eq!(estimate(40, 120).estimated_matches(), 100);
+        let resolve = |sum_doc_freq, need_row_ids| {
+            estimate(sum_doc_freq, sum_doc_freq).resolve(CollectMode::Auto, need_row_ids)
+        };
+        assert_eq!(resolve(10, true), CollectMode::Bitmap);
+        assert_eq!(resolve(50, true), CollectMode::Bitmap);
+        assert_eq!(resolve(51, true), CollectMode::DenseRange);
+        assert_eq!(resolve(51, false), CollectMode::Count);
+        assert_eq!(
+            estimate(90, 90).resolve(CollectMode::Bitmap, true),
+            CollectMode::Bitmap
+        );
+        assert_eq!(
+            CollectMode::from_name("Dense_Range"),
+            Some(CollectMode::DenseRange)
+        );
+        assert_eq!(CollectMode::from_name("sparse"), None);
+
+        let bitmap = RoaringBitmap::from_iter([3u32, 4, 6, 7, 9]);
+        let dense = AdaptiveRows::dense_range(&bitmap);
+        assert_eq!(
+            dense,
+            AdaptiveRows::DenseRange {
+                start: 3,
+                end: 10,
+                excluded: RoaringBitmap::from_iter([5u32, 8]),
+            }
+        );
+        assert_eq!(dense.count(), 5);
+        let ranges = AdaptiveRows::ranges(&bitmap);
+        assert_eq!(ranges, AdaptiveRows::Ranges(vec![(3, 5), (6, 8), (9, 10)]));
+        assert_eq!(ranges.count(), 5);
+        // Contiguous row_ids are a single range.
+        let contiguous = RoaringBitmap::from_iter(1000u32..100_000);
+        assert_eq!(AdaptiveRows::ranges(&contiguous).encoded_size(), 16);
+        assert_eq!(
+            AdaptiveRows::Bitmap(Arc::new(contiguous)).encoded_size(),
+            12_500
+        );
+        assert_eq!(
+            AdaptiveRows::dense_range(&RoaringBitmap::new()),
+            AdaptiveRows::Empty
+        );
+    }
+}
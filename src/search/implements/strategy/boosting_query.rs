@@ -0,0 +1,135 @@
+use std::fmt;
+
+use tantivy::query::{EnableScoring, Explanation, Query, Scorer, Weight};
+use tantivy::{DocId, DocSet, Score, SegmentReader, TantivyError, Term, TERMINATED};
+
+/// Matches docs of `positive`, scores of docs also matching `negative` are multiplied by
+/// `negative_boost`. Unlike a `MustNot` clause, demoted docs are still returned.
+pub struct BoostingQuery {
+    positive: Box<dyn Query>,
+    negative: Box<dyn Query>,
+    negative_boost: Score,
+}
+
+impl BoostingQuery {
+    pub fn new(positive: Box<dyn Query>, negative: Box<dyn Query>, negative_boost: Score) -> Self {
+        BoostingQuery {
+            positive,
+            negative,
+            negative_boost,
+        }
+    }
+}
+
+impl Clone for BoostingQuery {
+    fn clone(&self) -> Self {
+        BoostingQuery {
+            positive: self.positive.box_clone(),
+            negative: self.negative.box_clone(),
+            negative_boost: self.negative_boost,
+        }
+    }
+}
+
+impl fmt::Debug for BoostingQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Boosting(positive={:?}, negative={:?}, negative_boost={})",
+            self.positive, self.negative, self.negative_boost
+        )
+    }
+}
+
+impl Query for BoostingQuery {
+    fn weight(&self, enable_scoring: EnableScoring<'_>) -> tantivy::Result<Box<dyn Weight>> {
+        let positive: Box<dyn Weight> = self.positive.weight(enable_scoring)?;
+        if !enable_scoring.is_scoring_enabled() {
+            return Ok(positive);
+        }
+        // Negative query only demotes docs, its scores are never used.
+        let negative: Box<dyn Weight> = self
+            .negative
+            .weight(EnableScoring::disabled_from_schema(enable_scoring.schema()))?;
+        Ok(Box::new(BoostingWeight {
+            positive,
+            negative,
+            negative_boost: self.negative_boost,
+        }))
+    }
+
+    fn query_terms<'a>(&'a self, visitor: &mut dyn FnMut(&'a Term, bool)) {
+        self.positive.query_terms(visitor);
+    }
+}
+
+struct BoostingWeight {
+    positive: Box<dyn Weight>,
+    negative: Box<dyn Weight>,
+    negative_boost: Score,
+}
+
+impl Weight for BoostingWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> tantivy::Result<Box<dyn Scorer>> {
+        Ok(Box::new(BoostingScorer {
+            positive: self.positive.scorer(reader, boost)?,
+            negative: self.negative.scorer(reader, 1.0)?,
+            negative_boost: self.negative_boost,
+        }))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> tantivy::Result<Explanation> {
+        let mut scorer: Box<dyn Scorer> = self.scorer(reader, 1.0)?;
+        if scorer.seek(doc) != doc {
+            return Err(TantivyError::InvalidArgument(format!(
+                "Document #({}) does not match",
+                doc
+            )));
+        }
+        let mut explanation: Explanation = Explanation::new("BoostingQuery", scorer.score());
+        explanation.add_detail(self.positive.explain(reader, doc)?);
+        if self.negative.explain(reader, doc).is_ok() {
+            explanation.add_const("negative_boost", self.negative_boost);
+        }
+        Ok(explanation)
+    }
+}
+
+struct BoostingScorer {
+    positive: Box<dyn Scorer>,
+    negative: Box<dyn Scorer>,
+    negative_boost: Score,
+}
+
+impl DocSet for BoostingScorer {
+    fn advance(&mut self) -> DocId {
+        self.positive.advance()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        self.positive.seek(target)
+    }
+
+    fn doc(&self) -> DocId {
+        self.positive.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.positive.size_hint()
+    }
+}
+
+impl Scorer for BoostingScorer {
+    fn score(&mut self) -> Score {
+        let doc: DocId = self.positive.doc();
+        let score: Score = self.positive.score();
+        // Docs are scored in ascending order, negative scorer only moves forward.
+        if doc != TERMINATED && self.negative.doc() < doc {
+            self.negative.seek(doc);
+        }
+        match self.negative.doc() == doc {
+            true => score * self.negative_boost,
+            false => score,
+        }
+    }
+}
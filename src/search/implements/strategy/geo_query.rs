@@ -0,0 +1,139 @@
+use tantivy::columnar::Column;
+use tantivy::query::{ConstScorer, EnableScoring, Explanation, Query, Scorer, Weight};
+use tantivy::{DocId, DocSet, Score, SegmentReader, TantivyError, TERMINATED};
+
+use crate::common::geo::{GeoPoint, GeoShape};
+
+/// Matches docs whose geo point column is in `shape`. Points are Morton codes in an u64 fast
+/// field, each segment is scanned and decoded, so it's best combined with selective queries.
+#[derive(Debug, Clone)]
+pub struct GeoQuery {
+    field_name: String,
+    shape: GeoShape,
+}
+
+impl GeoQuery {
+    pub fn new(field_name: String, shape: GeoShape) -> Self {
+        GeoQuery { field_name, shape }
+    }
+}
+
+impl Query for GeoQuery {
+    fn weight(&self, _enable_scoring: EnableScoring<'_>) -> tantivy::Result<Box<dyn Weight>> {
+        Ok(Box::new(GeoWeight {
+            field_name: self.field_name.clone(),
+            shape: self.shape.clone(),
+        }))
+    }
+}
+
+struct GeoWeight {
+    field_name: String,
+    shape: GeoShape,
+}
+
+impl Weight for GeoWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> tantivy::Result<Box<dyn Scorer>> {
+        let column: Column<u64> = reader.fast_fields().u64(&self.field_name)?;
+        let docs: Vec<DocId> = (0..reader.max_doc())
+            .filter(|doc| {
+                column
+                    .values_for_doc(*doc)
+                    .any(|code| self.shape.contains(&GeoPoint::decode(code)))
+            })
+            .collect();
+        Ok(Box::new(ConstScorer::new(GeoDocSet::new(docs), boost)))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> tantivy::Result<Explanation> {
+        let mut scorer: Box<dyn Scorer> = self.scorer(reader, 1.0)?;
+        if scorer.seek(doc) != doc {
+            return Err(TantivyError::InvalidArgument(format!(
+                "Document #({}) does not match",
+                doc
+            )));
+        }
+        Ok(Explanation::new("GeoQuery", scorer.score()))
+    }
+}
+
+// Matched docs of a segment in ascending order.
+struct GeoDocSet {
+    docs: Vec<DocId>,
+    cursor: usize,
+}
+
+impl GeoDocSet {
+    fn new(docs: Vec<DocId>) -> Self {
+        GeoDocSet { docs, cursor: 0 }
+    }
+}
+
+impl DocSet for GeoDocSet {
+    fn advance(&mut self) -> DocId {
+        if self.cursor < self.docs.len() {
+            self.cursor += 1;
+        }
+        self.doc()
+    }
+
+    fn doc(&self) -> DocId {
+        self.docs.get(self.cursor).copied().unwrap_or(TERMINATED)
+    }
+
+    fn size_hint(&self) -> u32 {
+        (self.docs.len() - self.cursor) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tantivy::collector::DocSetCollector;
+    use tantivy::schema::{Schema, FAST};
+    use tantivy::{doc, Index};
+
+    #[test]
+    fn test_geo_query() {
+        let mut schema_builder = Schema::builder();
+        let location = schema_builder.add_u64_field("location", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut writer = index.writer_with_num_threads(1, 15_000_000).unwrap();
+        let points = [(31.2304, 121.4737), (39.9042, 116.4074), (51.5072, -0.1276)];
+        for (lat, lon) in points {
+            let code = GeoPoint::new(lat, lon).unwrap().encode();
+            writer.add_document(doc!(location => code)).unwrap();
+        }
+        writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let matched_docs = |shape: GeoShape| -> Vec<DocId> {
+            let mut docs: Vec<DocId> = searcher
+                .search(
+                    &GeoQuery::new("location".to_string(), shape),
+                    &DocSetCollector,
+                )
+                .unwrap()
+                .into_iter()
+                .map(|doc_address| doc_address.doc_id)
+                .collect();
+            docs.sort();
+            docs
+        };
+
+        let shanghai = GeoPoint::new(31.2304, 121.4737).unwrap();
+        assert_eq!(
+            matched_docs(GeoShape::distance(shanghai, 10_000.0).unwrap()),
+            vec![0]
+        );
+        assert_eq!(
+            matched_docs(GeoShape::distance(shanghai, 1_100_000.0).unwrap()),
+            vec![0, 1]
+        );
+        let europe = GeoShape::bounding_box(
+            GeoPoint::new(35.0, -10.0).unwrap(),
+            GeoPoint::new(60.0, 30.0).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(matched_docs(europe), vec![2]);
+    }
+}
@@ -0,0 +1,342 @@
+use std::fmt;
+use std::sync::Arc;
+
+use roaring::RoaringTreemap;
+use tantivy::query::{EnableScoring, Explanation, Query, Scorer, Weight};
+use tantivy::{DocId, DocSet, Score, SegmentReader, TantivyError, TERMINATED};
+
+use crate::search::bridge::index_reader_row_id_mapping::{RowIdMappingCache, SegmentRowIdReader};
+use crate::search::bridge::index_reader_vector_cache::{SegmentVectors, VectorCache};
+use crate::tokenizer::vector_format::{VectorFormat, VectorIndexType};
+
+/// Row_ids nearest neighbors are limited to. They're applied while searching HNSW graphs,
+/// otherwise filtered rows would take the places of candidates.
+#[derive(Clone, Default)]
+pub struct KnnRowIdFilter {
+    pub alive_row_ids: Option<Arc<RoaringTreemap>>,
+    pub row_id_range: Option<(u64, u64)>,
+    pub deleted_row_ids: Option<Arc<RoaringTreemap>>,
+    pub row_id_mapping: Option<Arc<RowIdMappingCache>>,
+}
+
+impl KnnRowIdFilter {
+    fn is_empty(&self) -> bool {
+        self.alive_row_ids.is_none()
+            && self.row_id_range.is_none()
+            && self.deleted_row_ids.is_none()
+    }
+
+    fn accepts(&self, row_id: u64) -> bool {
+        if let Some(alive_row_ids) = &self.alive_row_ids {
+            if !alive_row_ids.contains(row_id) {
+                return false;
+            }
+        }
+        if let Some((start_id, end_id)) = self.row_id_range {
+            if row_id < start_id || row_id >= end_id {
+                return false;
+            }
+        }
+        if let Some(deleted_row_ids) = &self.deleted_row_ids {
+            if deleted_row_ids.contains(row_id) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Matches `k` docs whose vectors in `field_name` are nearest to `vector`, scored by the metric
+/// of the column. Columns with `hnsw` index keep `max(k, ef_search)` candidates of each segment,
+/// other columns are searched exactly. Only docs matching `filter` are searched if it's given.
+pub struct KnnQuery {
+    field_name: String,
+    vector: Vec<f32>,
+    vector_format: VectorFormat,
+    k: usize,
+    filter: Option<Box<dyn Query>>,
+    row_id_filter: KnnRowIdFilter,
+    vector_cache: Arc<VectorCache>,
+}
+
+impl KnnQuery {
+    pub fn new(
+        field_name: String,
+        vector: Vec<f32>,
+        vector_format: VectorFormat,
+        k: usize,
+        vector_cache: Arc<VectorCache>,
+    ) -> Self {
+        KnnQuery {
+            field_name,
+            vector,
+            vector_format,
+            k,
+            filter: None,
+            row_id_filter: KnnRowIdFilter::default(),
+            vector_cache,
+        }
+    }
+
+    pub fn with_filter(mut self, filter: Box<dyn Query>) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    pub fn with_row_id_filter(mut self, row_id_filter: KnnRowIdFilter) -> Self {
+        self.row_id_filter = row_id_filter;
+        self
+    }
+}
+
+impl Clone for KnnQuery {
+    fn clone(&self) -> Self {
+        KnnQuery {
+            field_name: self.field_name.clone(),
+            vector: self.vector.clone(),
+            vector_format: self.vector_format.clone(),
+            k: self.k,
+            filter: self.filter.as_ref().map(|filter| filter.box_clone()),
+            row_id_filter: self.row_id_filter.clone(),
+            vector_cache: self.vector_cache.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for KnnQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Knn(field={}, dims={}, k={}, filter={:?})",
+            self.field_name,
+            self.vector.len(),
+            self.k,
+            self.filter
+        )
+    }
+}
+
+impl Query for KnnQuery {
+    fn weight(&self, enable_scoring: EnableScoring<'_>) -> tantivy::Result<Box<dyn Weight>> {
+        // Filter only limits docs searched, its scores are never used.
+        let filter: Option<Box<dyn Weight>> = match &self.filter {
+            Some(filter) => {
+                Some(filter.weight(EnableScoring::disabled_from_schema(enable_scoring.schema()))?)
+            }
+            None => None,
+        };
+        Ok(Box::new(KnnWeight {
+            query: self.clone(),
+            filter,
+        }))
+    }
+}
+
+struct KnnWeight {
+    query: KnnQuery,
+    filter: Option<Box<dyn Weight>>,
+}
+
+impl KnnWeight {
+    // Docs of segment matching filter, `None` if there is no filter.
+    fn filtered_docs(&self, reader: &SegmentReader) -> tantivy::Result<Option<Vec<bool>>> {
+        let filter: &dyn Weight = match &self.filter {
+            Some(filter) => filter.as_ref(),
+            None => return Ok(None),
+        };
+        let mut filtered_docs: Vec<bool> = vec![false; reader.max_doc() as usize];
+        let mut scorer: Box<dyn Scorer> = filter.scorer(reader, 1.0)?;
+        let mut doc: DocId = scorer.doc();
+        while doc != TERMINATED {
+            filtered_docs[doc as usize] = true;
+            doc = scorer.advance();
+        }
+        Ok(Some(filtered_docs))
+    }
+
+    fn scored_docs(&self, reader: &SegmentReader) -> tantivy::Result<Vec<(DocId, Score)>> {
+        let vectors: Arc<SegmentVectors> = self
+            .query
+            .vector_cache
+            .segment(reader, &self.query.field_name)?;
+        let filtered_docs: Option<Vec<bool>> = self.filtered_docs(reader)?;
+        let row_id_reader: Option<SegmentRowIdReader> = match self.query.row_id_filter.is_empty() {
+            true => None,
+            false => Some(SegmentRowIdReader::open(
+                reader,
+                "row_id",
+                self.query.row_id_filter.row_id_mapping.as_deref(),
+            )?),
+        };
+        let accept = |node: u32| -> bool {
+            let doc: DocId = vectors.doc_id(node);
+            if reader.is_deleted(doc) {
+                return false;
+            }
+            if let Some(filtered_docs) = &filtered_docs {
+                if !filtered_docs[doc as usize] {
+                    return false;
+                }
+            }
+            match &row_id_reader {
+                Some(row_id_reader) => self.query.row_id_filter.accepts(row_id_reader.row_id(doc)),
+                None => true,
+            }
+        };
+        let metric = self.query.vector_format.metric;
+        let score = |node: u32| -> Score { metric.score(&self.query.vector, vectors.vector(node)) };
+
+        let mut scored_docs: Vec<(DocId, Score)> =
+            match (vectors.graph(), self.query.vector_format.index) {
+                (Some(graph), VectorIndexType::Hnsw { ef_search, .. }) => graph
+                    .search(ef_search.max(self.query.k), score, accept)
+                    .into_iter()
+                    .map(|(node, score)| (vectors.doc_id(node), score))
+                    .collect(),
+                _ => (0..vectors.num_vectors() as u32)
+                    .filter(|node| accept(*node))
+                    .map(|node| (vectors.doc_id(node), score(node)))
+                    .collect(),
+            };
+        scored_docs.sort_unstable_by_key(|(doc, _)| *doc);
+        Ok(scored_docs)
+    }
+}
+
+impl Weight for KnnWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> tantivy::Result<Box<dyn Scorer>> {
+        Ok(Box::new(KnnScorer {
+            scored_docs: self.scored_docs(reader)?,
+            cursor: 0,
+            boost,
+        }))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> tantivy::Result<Explanation> {
+        let mut scorer: Box<dyn Scorer> = self.scorer(reader, 1.0)?;
+        if scorer.seek(doc) != doc {
+            return Err(TantivyError::InvalidArgument(format!(
+                "Document #({}) does not match",
+                doc
+            )));
+        }
+        Ok(Explanation::new("KnnQuery", scorer.score()))
+    }
+}
+
+// Scored docs of a segment in ascending order of doc.
+struct KnnScorer {
+    scored_docs: Vec<(DocId, Score)>,
+    cursor: usize,
+    boost: Score,
+}
+
+impl DocSet for KnnScorer {
+    fn advance(&mut self) -> DocId {
+        if self.cursor < self.scored_docs.len() {
+            self.cursor += 1;
+        }
+        self.doc()
+    }
+
+    fn doc(&self) -> DocId {
+        self.scored_docs
+            .get(self.cursor)
+            .map_or(TERMINATED, |(doc, _)| *doc)
+    }
+
+    fn size_hint(&self) -> u32 {
+        (self.scored_docs.len() - self.cursor) as u32
+    }
+}
+
+impl Scorer for KnnScorer {
+    fn score(&mut self) -> Score {
+        self.scored_docs
+            .get(self.cursor)
+            .map_or(0.0, |(_, score)| score * self.boost)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::vector_format::VectorMetric;
+    use std::collections::HashMap;
+    use tantivy::collector::TopDocs;
+    use tantivy::query::TermQuery;
+    use tantivy::schema::{IndexRecordOption, Schema, FAST, INDEXED, STRING};
+    use tantivy::{doc, Index, Term};
+
+    #[test]
+    fn test_knn_query() {
+        let mut schema_builder = Schema::builder();
+        let row_id = schema_builder.add_u64_field("row_id", FAST | INDEXED);
+        let color = schema_builder.add_text_field("color", STRING);
+        let embedding = schema_builder.add_bytes_field("embedding", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut writer = index.writer_with_num_threads(1, 15_000_000).unwrap();
+        let vectors = [[1.0, 0.0], [0.9, 0.1], [0.0, 1.0], [0.7, 0.7]];
+        let colors = ["red", "blue", "red", "red"];
+        for (id, (vector, color_value)) in vectors.iter().zip(colors).enumerate() {
+            writer
+                .add_document(doc!(
+                    row_id => id as u64,
+                    color => color_value,
+                    embedding => VectorFormat::encode(vector),
+                ))
+                .unwrap();
+        }
+        writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+
+        for index_type in [
+            VectorIndexType::Flat,
+            VectorIndexType::Hnsw {
+                m: 4,
+                ef_construction: 10,
+                ef_search: 10,
+            },
+        ] {
+            let vector_format = VectorFormat {
+                dims: 2,
+                metric: VectorMetric::Cosine,
+                index: index_type,
+            };
+            let vector_cache = Arc::new(VectorCache::new(HashMap::from([(
+                "embedding".to_string(),
+                vector_format.clone(),
+            )])));
+            let knn_query = KnnQuery::new(
+                "embedding".to_string(),
+                vec![1.0, 0.0],
+                vector_format,
+                2,
+                vector_cache,
+            );
+            let nearest_docs = |query: &KnnQuery| -> Vec<u32> {
+                searcher
+                    .search(query, &TopDocs::with_limit(2))
+                    .unwrap()
+                    .into_iter()
+                    .map(|(_, doc_address)| doc_address.doc_id)
+                    .collect()
+            };
+            assert_eq!(nearest_docs(&knn_query), vec![0, 1]);
+
+            // Only red docs are searched.
+            let red_query = knn_query.clone().with_filter(Box::new(TermQuery::new(
+                Term::from_field_text(color, "red"),
+                IndexRecordOption::Basic,
+            )));
+            assert_eq!(nearest_docs(&red_query), vec![0, 3]);
+
+            // Row_id 0 is deleted by host.
+            let deleted_query = red_query.with_row_id_filter(KnnRowIdFilter {
+                deleted_row_ids: Some(Arc::new(RoaringTreemap::from_iter([0u64]))),
+                ..KnnRowIdFilter::default()
+            });
+            assert_eq!(nearest_docs(&deleted_query), vec![3, 2]);
+        }
+    }
+}
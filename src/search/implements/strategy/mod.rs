@@ -1,2 +1,10 @@
+pub mod adaptive_collect;
+pub mod boosting_query;
+pub mod geo_query;
+pub mod knn_query;
+pub mod query_dsl;
+pub mod query_key;
 pub mod query_strategy;
 mod query_strategy_test;
+pub mod similarity_query;
+pub mod sparse_vector_query;
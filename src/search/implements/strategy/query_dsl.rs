@@ -0,0 +1,229 @@
+use serde::Deserialize;
+use tantivy::query::{DisjunctionMaxQuery, Query, QueryParser};
+use tantivy::schema::{Field, FieldType, Schema};
+use tantivy::Searcher;
+
+use super::boosting_query::BoostingQuery;
+use crate::common::constants::LOG_CALLBACK;
+use crate::logger::logger_bridge::TantivySearchLogger;
+use crate::tokenizer::language_routing::language_subfields;
+use crate::{common::errors::IndexSearcherError, ERROR};
+
+/// Node of the JSON query DSL used by ranked search, e.g. search `rust` in title and body
+/// without summing scores of both columns:
+/// `{"type": "dis_max", "tie_breaker": 0.2, "queries": [
+///     {"type": "match", "field": "title", "query": "rust"},
+///     {"type": "match", "field": "body", "query": "rust"}]}`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(tag = "type")]
+pub enum QueryDsl {
+    /// Sentence parsed by query parser in a text column, language sub-fields of it included.
+    #[serde(rename = "match")]
+    Match { field: String, query: String },
+    /// Score of the best matching query, plus `tie_breaker` times scores of other matching queries.
+    /// A doc matched in several columns is not ranked above a doc matched well in one column,
+    /// as it would be with a boolean OR of the queries.
+    #[serde(rename = "dis_max")]
+    DisMax {
+        queries: Vec<QueryDsl>,
+        #[serde(default)]
+        tie_breaker: f32,
+    },
+    /// Docs matching `positive`, scores of docs also matching `negative` are multiplied by
+    /// `negative_boost`, e.g. archived rows are ranked lower but still returned.
+    #[serde(rename = "boosting")]
+    Boosting {
+        positive: Box<QueryDsl>,
+        negative: Box<QueryDsl>,
+        negative_boost: f32,
+    },
+}
+
+impl QueryDsl {
+    pub fn from_json(query_json: &str) -> Result<Self, IndexSearcherError> {
+        let query: QueryDsl = serde_json::from_str(query_json).map_err(|e| {
+            let error = IndexSearcherError::QueryParserError(format!(
+                "Invalid query json: {}, {}",
+                query_json, e
+            ));
+            ERROR!(function:"QueryDsl", "{}", error);
+            error
+        })?;
+        query.validate()?;
+        Ok(query)
+    }
+
+    fn validate(&self) -> Result<(), IndexSearcherError> {
+        match self {
+            QueryDsl::Match { .. } => Ok(()),
+            QueryDsl::DisMax {
+                queries,
+                tie_breaker,
+            } => {
+                if queries.is_empty() {
+                    return Err(IndexSearcherError::QueryParserError(
+                        "dis_max queries can't be empty".to_string(),
+                    ));
+                }
+                if !(0.0..=1.0).contains(tie_breaker) {
+                    return Err(IndexSearcherError::QueryParserError(format!(
+                        "dis_max tie_breaker should be in [0, 1], got {}",
+                        tie_breaker
+                    )));
+                }
+                queries.iter().try_for_each(QueryDsl::validate)
+            }
+            QueryDsl::Boosting {
+                positive,
+                negative,
+                negative_boost,
+            } => {
+                if !(0.0..=1.0).contains(negative_boost) {
+                    return Err(IndexSearcherError::QueryParserError(format!(
+                        "boosting negative_boost should be in [0, 1], got {}",
+                        negative_boost
+                    )));
+                }
+                positive.validate()?;
+                negative.validate()
+            }
+        }
+    }
+
+    /// Columns referred by the query, without duplicates.
+    pub fn column_names(&self) -> Vec<String> {
+        let mut column_names: Vec<String> = Vec::new();
+        self.collect_column_names(&mut column_names);
+        column_names
+    }
+
+    fn collect_column_names(&self, column_names: &mut Vec<String>) {
+        match self {
+            QueryDsl::Match { field, .. } => {
+                if !column_names.contains(field) {
+                    column_names.push(field.clone());
+                }
+            }
+            QueryDsl::DisMax { queries, .. } => {
+                for query in queries {
+                    query.collect_column_names(column_names);
+                }
+            }
+            QueryDsl::Boosting {
+                positive, negative, ..
+            } => {
+                positive.collect_column_names(column_names);
+                negative.collect_column_names(column_names);
+            }
+        }
+    }
+
+    pub fn build(&self, searcher: &Searcher) -> Result<Box<dyn Query>, IndexSearcherError> {
+        match self {
+            QueryDsl::Match { field, query } => {
+                let schema: Schema = searcher.index().schema();
+                let mut fields: Vec<Field> = vec![text_field(&schema, field)?];
+                fields.extend(language_subfields(&schema, field));
+                let query_parser: QueryParser = QueryParser::for_index(searcher.index(), fields);
+                query_parser.parse_query(query).map_err(|e| {
+                    ERROR!(function:"QueryDsl", "Error when parse: {}. {}", query, e);
+                    IndexSearcherError::QueryParserError(e.to_string())
+                })
+            }
+            QueryDsl::DisMax {
+                queries,
+                tie_breaker,
+            } => {
+                let disjuncts: Vec<Box<dyn Query>> = queries
+                    .iter()
+                    .map(|query| query.build(searcher))
+                    .collect::<Result<_, _>>()?;
+                Ok(Box::new(DisjunctionMaxQuery::with_tie_breaker(
+                    disjuncts,
+                    *tie_breaker,
+                )))
+            }
+            QueryDsl::Boosting {
+                positive,
+                negative,
+                negative_boost,
+            } => Ok(Box::new(BoostingQuery::new(
+                positive.build(searcher)?,
+                negative.build(searcher)?,
+                *negative_boost,
+            ))),
+        }
+    }
+}
+
+// Indexed text field of `column_name`.
+fn text_field(schema: &Schema, column_name: &str) -> Result<Field, IndexSearcherError> {
+    let field: Field = schema.get_field(column_name).map_err(|_| {
+        let error = IndexSearcherError::MissingFieldError(column_name.to_string());
+        ERROR!(function:"QueryDsl", "{}", error);
+        error
+    })?;
+    match schema.get_field_entry(field).field_type() {
+        FieldType::Str(text_options) if text_options.get_indexing_options().is_some() => Ok(field),
+        _ => {
+            let error_msg: String = format!("column field:{} is not an indexed text.", column_name);
+            ERROR!(function:"QueryDsl", "{}", error_msg);
+            Err(IndexSearcherError::InternalError(error_msg))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_query_dsl() {
+        let query = QueryDsl::from_json(
+            r#"{"type": "dis_max", "tie_breaker": 0.2, "queries": [
+                {"type": "match", "field": "title", "query": "rust"},
+                {"type": "match", "field": "body", "query": "rust"},
+                {"type": "match", "field": "title", "query": "tantivy"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(query.column_names(), vec!["title", "body"]);
+
+        let query = QueryDsl::from_json(
+            r#"{"type": "dis_max", "queries": [{"type": "match", "field": "a", "query": "b"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            query,
+            QueryDsl::DisMax {
+                queries: vec![QueryDsl::Match {
+                    field: "a".to_string(),
+                    query: "b".to_string()
+                }],
+                tie_breaker: 0.0
+            }
+        );
+
+        assert!(QueryDsl::from_json(r#"{"type": "dis_max", "queries": []}"#).is_err());
+        assert!(QueryDsl::from_json(
+            r#"{"type": "dis_max", "tie_breaker": 1.5,
+                "queries": [{"type": "match", "field": "a", "query": "b"}]}"#
+        )
+        .is_err());
+        let query = QueryDsl::from_json(
+            r#"{"type": "boosting", "negative_boost": 0.5,
+                "positive": {"type": "match", "field": "body", "query": "rust"},
+                "negative": {"type": "match", "field": "status", "query": "archived"}}"#,
+        )
+        .unwrap();
+        assert_eq!(query.column_names(), vec!["body", "status"]);
+        assert!(QueryDsl::from_json(
+            r#"{"type": "boosting", "negative_boost": 2.0,
+                "positive": {"type": "match", "field": "a", "query": "b"},
+                "negative": {"type": "match", "field": "a", "query": "c"}}"#
+        )
+        .is_err());
+        assert!(QueryDsl::from_json(r#"{"type": "match", "field": "a"}"#).is_err());
+        assert!(QueryDsl::from_json(r#"{"type": "unknown"}"#).is_err());
+    }
+}
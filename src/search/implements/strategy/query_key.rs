@@ -0,0 +1,151 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use roaring::RoaringTreemap;
+use tantivy::schema::Field;
+use tantivy::{Opstamp, Searcher, SegmentId, Term};
+
+use crate::common::geo::GeoShape;
+
+/// Canonical form of a bitmap query, queries matching the same docs by construction share it.
+/// Text is analyzed by the analyzer of its column, so case and whitespace are normalized as
+/// indexed text is, and terms of disjunctions are sorted and deduplicated.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum QueryKey {
+    /// Docs having any of the terms.
+    TermSet(Vec<Term>),
+    /// Docs having a term of `field` matching regex `pattern`.
+    Regex { field: Field, pattern: String },
+    /// Docs having a date of `field` in inclusive bounds, in nanoseconds truncated to precision
+    /// of the column, `None` means unbounded.
+    DateRange {
+        field: Field,
+        lower: Option<i64>,
+        upper: Option<i64>,
+    },
+    /// Docs having a point of `field` in a shape, it's kind of shape followed by bits of its
+    /// coordinates in degrees and radius in meters.
+    Geo { field: Field, shape: Vec<u64> },
+}
+
+impl QueryKey {
+    pub fn term_set(mut terms: Vec<Term>) -> QueryKey {
+        terms.sort();
+        terms.dedup();
+        QueryKey::TermSet(terms)
+    }
+
+    pub fn geo(field: Field, shape: &GeoShape) -> QueryKey {
+        // Adding zero turns -0.0 into 0.0, they are the same coordinate.
+        let bits = |value: f64| (value + 0.0).to_bits();
+        let shape: Vec<u64> = match shape {
+            GeoShape::BoundingBox { min, max } => {
+                vec![
+                    0,
+                    bits(min.lat),
+                    bits(min.lon),
+                    bits(max.lat),
+                    bits(max.lon),
+                ]
+            }
+            GeoShape::Distance {
+                center,
+                radius_meters,
+            } => vec![1, bits(center.lat), bits(center.lon), bits(*radius_meters)],
+        };
+        QueryKey::Geo { field, shape }
+    }
+
+    /// Serialized canonical query, unlike `fingerprint` it's the same across processes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = Vec::new();
+        let put_field = |bytes: &mut Vec<u8>, field: &Field| {
+            bytes.extend_from_slice(&field.field_id().to_le_bytes())
+        };
+        let put_slice = |bytes: &mut Vec<u8>, slice: &[u8]| {
+            bytes.extend_from_slice(&(slice.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(slice);
+        };
+        match self {
+            QueryKey::TermSet(terms) => {
+                bytes.push(0);
+                for term in terms {
+                    put_field(&mut bytes, &term.field());
+                    bytes.push(term.typ().to_code());
+                    put_slice(&mut bytes, term.serialized_value_bytes());
+                }
+            }
+            QueryKey::Regex { field, pattern } => {
+                bytes.push(1);
+                put_field(&mut bytes, field);
+                put_slice(&mut bytes, pattern.as_bytes());
+            }
+            QueryKey::DateRange {
+                field,
+                lower,
+                upper,
+            } => {
+                bytes.push(2);
+                put_field(&mut bytes, field);
+                for bound in [lower, upper] {
+                    match bound {
+                        Some(nanos) => {
+                            bytes.push(1);
+                            bytes.extend_from_slice(&nanos.to_le_bytes());
+                        }
+                        None => bytes.push(0),
+                    }
+                }
+            }
+            QueryKey::Geo { field, shape } => {
+                bytes.push(3);
+                put_field(&mut bytes, field);
+                for bits in shape {
+                    bytes.extend_from_slice(&bits.to_le_bytes());
+                }
+            }
+        }
+        bytes
+    }
+
+    /// Hash of the canonical query, it's stable in a process.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// Bitmap query over a searcher snapshot, identical ones running concurrently share execution.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitmapFlightKey {
+    // Segment ids are unique across indexes, delete opstamps change with deletes.
+    segments: Vec<(SegmentId, Option<Opstamp>)>,
+    // Address of excluded row_ids snapshot, lightweight deleted and hidden by security filter,
+    // 0 if nothing is excluded.
+    // Snapshots are immutable and kept alive by the running query, so it isn't reused meanwhile.
+    deleted_row_ids: usize,
+    query_key: QueryKey,
+}
+
+impl BitmapFlightKey {
+    pub fn new(
+        searcher: &Searcher,
+        deleted_row_ids: Option<&Arc<RoaringTreemap>>,
+        query_key: QueryKey,
+    ) -> Self {
+        BitmapFlightKey {
+            segments: searcher
+                .segment_readers()
+                .iter()
+                .map(|segment_reader| {
+                    (segment_reader.segment_id(), segment_reader.delete_opstamp())
+                })
+                .collect(),
+            deleted_row_ids: deleted_row_ids
+                .map_or(0, |deleted_row_ids| Arc::as_ptr(deleted_row_ids) as usize),
+            query_key,
+        }
+    }
+}
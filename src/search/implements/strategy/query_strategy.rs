@@ -1,23 +1,367 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ops::Bound;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use roaring::{RoaringBitmap, RoaringTreemap};
-use tantivy::query::{Query, QueryParser, QueryParserError, RegexQuery, TermQuery, TermSetQuery};
-use tantivy::schema::{Field, FieldType, IndexRecordOption, TextFieldIndexing};
+use tantivy::postings::SegmentPostings;
+use tantivy::query::{
+    BooleanQuery, ConstScoreQuery, Occur, Query, QueryParser, QueryParserError, RangeQuery,
+    RegexQuery, TermQuery, TermSetQuery,
+};
+use tantivy::schema::{
+    DateTimePrecision, Field, FieldType, IndexRecordOption, TextFieldIndexing, Value,
+};
 use tantivy::tokenizer::{BoxTokenStream, TextAnalyzer};
-use tantivy::{schema::Schema, Searcher};
-use tantivy::{TantivyError, Term};
+use tantivy::{schema::Schema, DocAddress, Searcher, TantivyDocument};
+use tantivy::{DateTime, DocId, DocSet, Score, SegmentReader, TantivyError, Term};
 
-use crate::common::constants::LOG_CALLBACK;
+use super::adaptive_collect::{AdaptiveRows, CollectMode, SelectivityEstimate};
+use super::geo_query::GeoQuery;
+use super::knn_query::{KnnQuery, KnnRowIdFilter};
+use super::query_dsl::QueryDsl;
+use super::query_key::{BitmapFlightKey, QueryKey};
+use super::similarity_query::{with_similarity, Similarity};
+use super::sparse_vector_query::SparseVectorQuery;
+use crate::common::constants::{
+    BITMAP_QUERY_FLIGHTS, LOG_CALLBACK, METRICS, QUERY_AUDIT, QUERY_COST_GUARD,
+};
+use crate::common::geo::GeoShape;
+use crate::common::hyper_log_log::HyperLogLog;
+use crate::common::query_audit::QueryAuditRecord;
+use crate::common::t_digest::TDigest;
+use crate::ffi::{MatchedTerm, MatchedTermsTopDoc, TopDocsWithTotalHits};
 use crate::logger::logger_bridge::TantivySearchLogger;
+use crate::search::bridge::index_reader_query_cache::QueryResultCache;
+use crate::search::bridge::index_reader_row_id_mapping::RowIdMappingCache;
+use crate::search::bridge::index_reader_security_filter::PrincipalFilter;
+use crate::search::bridge::index_reader_vector_cache::VectorCache;
+use crate::search::collector::cardinality_collector::CardinalityCollector;
+use crate::search::collector::percentiles_collector::PercentilesCollector;
 use crate::search::collector::row_id_bitmap_collector::RowIdRoaringCollector;
+use crate::search::collector::top_docs_order_by_field_collector::{
+    FieldOrderedDoc, TopDocsOrderByField,
+};
 use crate::search::collector::top_docs_with_bitmap_collector::TopDocsWithFilter;
-use crate::search::collector::top_docs_with_treemap_collector::TopDocsWithFilter64;
+use crate::search::collector::top_docs_with_treemap_collector::{
+    TopDocsWithFilter64, TopDocsWithTotalHits64, TotalHits,
+};
+use crate::search::collector::top_groups_collector::TopGroupsCollector;
 use crate::search::utils::convert_utils::ConvertUtils;
-use crate::INFO;
-use crate::{common::errors::IndexSearcherError, ffi::RowIdWithScore, ERROR};
+use crate::tokenizer::language_routing::{language_subfield_columns, language_subfields};
+use crate::tokenizer::sparse_vector_format::{SparseVectorFormat, SPARSE_VECTOR_TOKENIZER};
+use crate::tokenizer::vector_format::VectorFormat;
+use crate::{common::errors::IndexSearcherError, ffi::GroupTopDoc, ffi::RowIdWithScore, ERROR};
+use crate::{INFO, TRACE_SPAN, WARNING};
 
 pub trait QueryStrategy<T> {
-    fn execute(&self, searcher: &Searcher) -> Result<T, IndexSearcherError>;
+    /// Execute query with searcher, row_ids in `deleted_row_ids` won't be collected.
+    /// Collectors read row_ids from `row_id_mapping` for warmed segments.
+    /// In `filter_mode` matched docs get a constant score, BM25 is not computed.
+    /// Bitmap strategies never score, so they ignore `filter_mode`.
+    fn execute(
+        &self,
+        searcher: &Searcher,
+        deleted_row_ids: Option<Arc<RoaringTreemap>>,
+        row_id_mapping: Option<Arc<RowIdMappingCache>>,
+        filter_mode: bool,
+    ) -> Result<T, IndexSearcherError>;
+
+    /// Canonical key of the query, `None` if identical queries aren't recognized.
+    fn query_key(&self, _searcher: &Searcher) -> Result<Option<QueryKey>, IndexSearcherError> {
+        Ok(None)
+    }
+
+    /// Query text recorded by query audit log.
+    fn audit_query(&self) -> String;
+}
+
+/// Number of hits of a query result, recorded by query audit log.
+pub trait QueryHits {
+    fn hits(&self) -> u64;
+}
+
+impl QueryHits for Arc<RoaringBitmap> {
+    fn hits(&self) -> u64 {
+        self.len()
+    }
+}
+
+impl QueryHits for AdaptiveRows {
+    fn hits(&self) -> u64 {
+        self.count()
+    }
+}
+
+impl<D> QueryHits for Vec<D> {
+    fn hits(&self) -> u64 {
+        self.len() as u64
+    }
+}
+
+impl QueryHits for TopDocsWithTotalHits {
+    fn hits(&self) -> u64 {
+        self.total_hits
+    }
+}
+
+// Aggregations report the number of values they've seen.
+impl QueryHits for HyperLogLog {
+    fn hits(&self) -> u64 {
+        self.estimate()
+    }
+}
+
+impl QueryHits for TDigest {
+    fn hits(&self) -> u64 {
+        self.count()
+    }
+}
+
+// Score of every matched doc in filter mode.
+const FILTER_MODE_SCORE: Score = 1.0;
+
+// Wrap `query` with a constant score in filter mode, then scorers of the query are only
+// used to iterate matched docs. BM25 top docs collectors also skip remaining docs once full,
+// because no later doc can beat a constant score.
+fn filter_mode_query(query: Box<dyn Query>, filter_mode: bool) -> Box<dyn Query> {
+    match filter_mode {
+        true => Box::new(ConstScoreQuery::new(query, FILTER_MODE_SCORE)),
+        false => query,
+    }
+}
+
+// Create a row_id collector which excludes lightweight deleted row_ids.
+fn row_id_collector_with_deleted(
+    deleted_row_ids: Option<Arc<RoaringTreemap>>,
+    row_id_mapping: Option<Arc<RowIdMappingCache>>,
+) -> RowIdRoaringCollector {
+    let mut row_id_collector = RowIdRoaringCollector::with_field("row_id".to_string());
+    if let Some(deleted_row_ids) = deleted_row_ids {
+        row_id_collector = row_id_collector.with_deleted(deleted_row_ids);
+    }
+    if let Some(row_id_mapping) = row_id_mapping {
+        row_id_collector = row_id_collector.with_row_id_mapping(row_id_mapping);
+    }
+    row_id_collector
+}
+
+// Refused query exceeding cost limits of `QUERY_COST_GUARD`, it isn't executed.
+fn query_too_expensive(e: String) -> IndexSearcherError {
+    let error: IndexSearcherError = IndexSearcherError::QueryTooExpensive(e);
+    WARNING!(function:"QueryCostGuard", "{}", error);
+    error
+}
+
+// Leaf clauses of a parsed query are the terms it searches.
+fn check_query_cost(query: &dyn Query) -> Result<(), IndexSearcherError> {
+    let mut clause_count: u64 = 0;
+    query.query_terms(&mut |_, _| clause_count += 1);
+    QUERY_COST_GUARD
+        .check_clause_count(clause_count)
+        .map_err(query_too_expensive)
+}
+
+// Terms of a term set query are its clauses. Docs of its most frequent term all match, deletes
+// aside, so a result bitmap over limit is refused before it's collected.
+fn check_term_set_cost(searcher: &Searcher, terms: &[Term]) -> Result<(), IndexSearcherError> {
+    QUERY_COST_GUARD
+        .check_clause_count(terms.len() as u64)
+        .map_err(query_too_expensive)?;
+    if QUERY_COST_GUARD.max_bitmap_size() == 0 {
+        return Ok(());
+    }
+    let estimate: SelectivityEstimate =
+        SelectivityEstimate::of_terms(searcher, terms).map_err(IndexSearcherError::TantivyError)?;
+    QUERY_COST_GUARD
+        .check_bitmap_size(estimate.max_doc_freq)
+        .map_err(query_too_expensive)
+}
+
+// States of automaton of a LIKE pattern, and terms of `field` it expands to in each segment.
+// Only terms starting with literal prefix of the pattern are scanned, counting stops past limit.
+fn check_like_pattern_cost(
+    searcher: &Searcher,
+    field: Field,
+    like_pattern: &str,
+) -> Result<(), IndexSearcherError> {
+    let regex_pattern: String = ConvertUtils::like_to_regex(like_pattern);
+    QUERY_COST_GUARD
+        .check_regex_states(&regex_pattern)
+        .map_err(query_too_expensive)?;
+    let max_expanded_terms: u64 = QUERY_COST_GUARD.max_expanded_terms();
+    if max_expanded_terms == 0 {
+        return Ok(());
+    }
+    let regex: regex::bytes::Regex = regex::bytes::Regex::new(&format!("^(?s:{})$", regex_pattern))
+        .map_err(|e| IndexSearcherError::QueryParserError(e.to_string()))?;
+    let prefix: String = ConvertUtils::like_literal_prefix(like_pattern);
+    for segment_reader in searcher.segment_readers() {
+        let inverted_index = segment_reader
+            .inverted_index(field)
+            .map_err(IndexSearcherError::TantivyError)?;
+        let mut term_stream = inverted_index
+            .terms()
+            .range()
+            .ge(prefix.as_bytes())
+            .into_stream()
+            .map_err(|e| IndexSearcherError::TantivyError(e.into()))?;
+        let mut expanded_terms: u64 = 0;
+        while expanded_terms <= max_expanded_terms && term_stream.advance() {
+            if !term_stream.key().starts_with(prefix.as_bytes()) {
+                break;
+            }
+            if regex.is_match(term_stream.key()) {
+                expanded_terms += 1;
+            }
+        }
+        QUERY_COST_GUARD
+            .check_expanded_terms(expanded_terms)
+            .map_err(query_too_expensive)?;
+    }
+    Ok(())
+}
+
+// Sparse vector columns are indexed text too, but their terms are weights searched by dot product.
+fn is_bm25_indexing(indexing: Option<&TextFieldIndexing>) -> bool {
+    indexing.is_some_and(|indexing| indexing.tokenizer() != SPARSE_VECTOR_TOKENIZER)
+}
+
+// Indexed text fields searched by BM25, all of them except `row_id` if `column_names` is empty,
+// text fields only kept in doc store are skipped. Language sub-fields of columns are included.
+pub(crate) fn bm25_text_fields(
+    schema: &Schema,
+    column_names: &Vec<String>,
+) -> Result<Vec<Field>, IndexSearcherError> {
+    let fields: Vec<Field> = match column_names.is_empty() {
+        true => schema
+            .fields()
+            .filter(|(field, _)| {
+                schema.get_field_name(*field) != "row_id" && {
+                    match schema.get_field_entry(*field).field_type() {
+                        tantivy::schema::FieldType::Str(ref text_options) => {
+                            is_bm25_indexing(text_options.get_indexing_options())
+                        }
+                        _ => false,
+                    }
+                }
+            })
+            .map(|(field, _)| field)
+            .collect(),
+        false => column_names
+            .iter()
+            .filter(|col_name| match schema.get_field(*col_name) {
+                Ok(field) => match schema.get_field_entry(field).field_type() {
+                    tantivy::schema::FieldType::Str(ref text_options) => {
+                        is_bm25_indexing(text_options.get_indexing_options())
+                    }
+                    _ => false,
+                },
+                Err(e) => {
+                    let error: IndexSearcherError = IndexSearcherError::TantivyError(e);
+                    ERROR!(function:"BM25QueryStrategy", "{}", error);
+                    return false;
+                }
+            })
+            .map(|col_name| {
+                schema.get_field(col_name).map_err(|e| {
+                    let error: IndexSearcherError = IndexSearcherError::TantivyError(e);
+                    ERROR!(function:"BM25QueryStrategy", "{}", error);
+                    error
+                })
+            })
+            .collect::<Result<Vec<Field>, IndexSearcherError>>()?
+            .into_iter()
+            .flat_map(|field| {
+                let mut fields: Vec<Field> = vec![field];
+                fields.extend(language_subfields(schema, schema.get_field_name(field)));
+                fields
+            })
+            .collect(),
+    };
+    Ok(fields)
+}
+
+// Boost scores of `field_boosts` columns, language sub-fields share the boost of their column.
+fn apply_field_boosts(
+    schema: &Schema,
+    query_parser: &mut QueryParser,
+    field_boosts: &HashMap<String, f32>,
+) -> Result<(), IndexSearcherError> {
+    for (column_name, boost) in field_boosts {
+        let field: Field = schema.get_field(column_name).map_err(|_| {
+            let error: IndexSearcherError =
+                IndexSearcherError::MissingFieldError(column_name.clone());
+            ERROR!(function:"BM25QueryStrategy", "{}", error);
+            error
+        })?;
+        query_parser.set_field_boost(field, *boost);
+        for subfield in language_subfields(schema, column_name) {
+            query_parser.set_field_boost(subfield, *boost);
+        }
+    }
+    Ok(())
+}
+
+// Read stored text of `fields` for a collected doc.
+fn read_stored_texts(
+    searcher: &Searcher,
+    fields: &Vec<Field>,
+    row_id_with_score: &mut RowIdWithScore,
+) -> Result<(), IndexSearcherError> {
+    let document: TantivyDocument = searcher
+        .doc(DocAddress::new(
+            row_id_with_score.seg_id,
+            row_id_with_score.doc_id,
+        ))
+        .map_err(|e: TantivyError| {
+            ERROR!(function:"read_stored_texts", "Error when read doc: {}", e);
+            IndexSearcherError::TantivyError(e)
+        })?;
+    for field in fields {
+        if let Some(field_value) = document.get_first(*field) {
+            row_id_with_score
+                .docs
+                .push(field_value.as_str().unwrap_or("").to_string());
+        }
+    }
+    Ok(())
+}
+
+// Terms of language sub-fields of `column_name`, `texts` are analyzed by analyzer of each sub-field.
+// Regex queries aren't fanned out, raw text is always indexed in the column.
+fn language_subfield_terms(
+    searcher: &Searcher,
+    schema: &Schema,
+    column_name: &str,
+    texts: &[&str],
+) -> Vec<Term> {
+    let mut terms: Vec<Term> = Vec::new();
+    for subfield in language_subfields(schema, column_name) {
+        let analyzer: Option<TextAnalyzer> = match schema.get_field_entry(subfield).field_type() {
+            FieldType::Str(ref str_options) => {
+                str_options
+                    .get_indexing_options()
+                    .and_then(|indexing_options| {
+                        searcher
+                            .index()
+                            .tokenizers()
+                            .get(indexing_options.tokenizer())
+                    })
+            }
+            _ => None,
+        };
+        if let Some(mut text_analyzer) = analyzer {
+            for text in texts {
+                let mut token_stream: BoxTokenStream<'_> = text_analyzer.token_stream(text);
+                token_stream.process(&mut |token| {
+                    terms.push(Term::from_field_text(subfield, &token.text));
+                });
+            }
+        }
+    }
+    terms
 }
 
 /// Execute query for a group of terms.
@@ -31,8 +375,9 @@ pub struct TermSetQueryStrategy<'a> {
     pub terms: &'a Vec<String>,
 }
 
-impl<'a> QueryStrategy<Arc<RoaringBitmap>> for TermSetQueryStrategy<'a> {
-    fn execute(&self, searcher: &Searcher) -> Result<Arc<RoaringBitmap>, IndexSearcherError> {
+impl<'a> TermSetQueryStrategy<'a> {
+    /// Terms searched in column and its language sub-fields, `terms` are analyzed by their analyzers.
+    pub fn analyzed_terms(&self, searcher: &Searcher) -> Result<Vec<Term>, IndexSearcherError> {
         let schema: Schema = searcher.index().schema();
 
         let col_field: Field = schema.get_field(self.column_name).map_err(|e| {
@@ -72,16 +417,43 @@ impl<'a> QueryStrategy<Arc<RoaringBitmap>> for TermSetQueryStrategy<'a> {
                     terms.push(Term::from_field_text(col_field, &token.text));
                 });
             }
+            let texts: Vec<&str> = self.terms.iter().map(String::as_str).collect();
+            terms.extend(language_subfield_terms(
+                searcher,
+                &schema,
+                self.column_name,
+                &texts,
+            ));
         } else {
             // Not Expected.
             for term in self.terms {
                 terms.push(Term::from_field_text(col_field, &term));
             }
         }
+        Ok(terms)
+    }
+}
+
+impl<'a> QueryStrategy<Arc<RoaringBitmap>> for TermSetQueryStrategy<'a> {
+    fn audit_query(&self) -> String {
+        format!("{} IN {:?}", self.column_name, self.terms)
+    }
+    fn query_key(&self, searcher: &Searcher) -> Result<Option<QueryKey>, IndexSearcherError> {
+        Ok(Some(QueryKey::term_set(self.analyzed_terms(searcher)?)))
+    }
 
+    fn execute(
+        &self,
+        searcher: &Searcher,
+        deleted_row_ids: Option<Arc<RoaringTreemap>>,
+        row_id_mapping: Option<Arc<RowIdMappingCache>>,
+        _filter_mode: bool,
+    ) -> Result<Arc<RoaringBitmap>, IndexSearcherError> {
+        let terms: Vec<Term> = self.analyzed_terms(searcher)?;
+        check_term_set_cost(searcher, &terms)?;
         let ter_set_query: TermSetQuery = TermSetQuery::new(terms);
         let row_id_collector: RowIdRoaringCollector =
-            RowIdRoaringCollector::with_field("row_id".to_string());
+            row_id_collector_with_deleted(deleted_row_ids, row_id_mapping);
 
         searcher
             .search(&ter_set_query, &row_id_collector)
@@ -92,6 +464,75 @@ impl<'a> QueryStrategy<Arc<RoaringBitmap>> for TermSetQueryStrategy<'a> {
     }
 }
 
+/// Execute query for a group of terms, row_ids are collected in a representation picked by
+/// selectivity estimated from doc frequencies of terms before execution.
+///
+/// Params:
+/// - `term_set`: Terms query to execute.
+/// - `mode`: Collect mode, `Auto` picks one by estimated selectivity.
+/// - `need_row_ids`: Whether `Auto` mode should return row_ids or only count them.
+/// - `accept_ranges`: Whether row_ids can be returned as ranges when they're smaller.
+///
+pub struct AdaptiveTermSetQueryStrategy<'a> {
+    pub term_set: &'a TermSetQueryStrategy<'a>,
+    pub mode: CollectMode,
+    pub need_row_ids: bool,
+    pub accept_ranges: bool,
+}
+
+impl<'a> QueryStrategy<AdaptiveRows> for AdaptiveTermSetQueryStrategy<'a> {
+    fn audit_query(&self) -> String {
+        self.term_set.audit_query()
+    }
+    fn execute(
+        &self,
+        searcher: &Searcher,
+        deleted_row_ids: Option<Arc<RoaringTreemap>>,
+        row_id_mapping: Option<Arc<RowIdMappingCache>>,
+        filter_mode: bool,
+    ) -> Result<AdaptiveRows, IndexSearcherError> {
+        let mut terms: Vec<Term> = self.term_set.analyzed_terms(searcher)?;
+        terms.sort();
+        terms.dedup();
+        let estimate: SelectivityEstimate = SelectivityEstimate::of_terms(searcher, &terms)
+            .map_err(|e| {
+                ERROR!(function:"AdaptiveTermSetQueryStrategy", "{}", e);
+                IndexSearcherError::TantivyError(e)
+            })?;
+        if estimate.is_empty() {
+            return Ok(AdaptiveRows::Empty);
+        }
+        let mode: CollectMode = estimate.resolve(self.mode, self.need_row_ids);
+        // Doc frequency of a single term is its exact count when no doc is deleted.
+        let no_deleted_docs: bool = deleted_row_ids
+            .as_ref()
+            .map_or(true, |deleted_row_ids| deleted_row_ids.is_empty())
+            && searcher
+                .segment_readers()
+                .iter()
+                .all(|segment_reader| !segment_reader.has_deletes());
+        if mode == CollectMode::Count && terms.len() == 1 && no_deleted_docs {
+            return Ok(AdaptiveRows::Count(estimate.max_doc_freq));
+        }
+        let bitmap: Arc<RoaringBitmap> =
+            self.term_set
+                .execute(searcher, deleted_row_ids, row_id_mapping, filter_mode)?;
+        let rows: AdaptiveRows = match mode {
+            CollectMode::Count => return Ok(AdaptiveRows::Count(bitmap.len())),
+            CollectMode::DenseRange => AdaptiveRows::dense_range(&bitmap),
+            _ => AdaptiveRows::Bitmap(bitmap.clone()),
+        };
+        // Contiguous row_ids, e.g. of index sorted by row_id, are much smaller as ranges.
+        if self.accept_ranges {
+            let ranges: AdaptiveRows = AdaptiveRows::ranges(&bitmap);
+            if ranges.encoded_size() < rows.encoded_size() {
+                return Ok(ranges);
+            }
+        }
+        Ok(rows)
+    }
+}
+
 /// Execute query for one term.
 ///
 /// Params:
@@ -104,7 +545,26 @@ pub struct SingleTermQueryStrategy<'a> {
 }
 
 impl<'a> QueryStrategy<Arc<RoaringBitmap>> for SingleTermQueryStrategy<'a> {
-    fn execute(&self, searcher: &Searcher) -> Result<Arc<RoaringBitmap>, IndexSearcherError> {
+    fn audit_query(&self) -> String {
+        format!("{}:{}", self.column_name, self.term)
+    }
+    // Terms are analyzed as a term set of one term.
+    fn query_key(&self, searcher: &Searcher) -> Result<Option<QueryKey>, IndexSearcherError> {
+        let terms: Vec<String> = vec![self.term.to_string()];
+        let term_set = TermSetQueryStrategy {
+            column_name: self.column_name,
+            terms: &terms,
+        };
+        term_set.query_key(searcher)
+    }
+
+    fn execute(
+        &self,
+        searcher: &Searcher,
+        deleted_row_ids: Option<Arc<RoaringTreemap>>,
+        row_id_mapping: Option<Arc<RowIdMappingCache>>,
+        _filter_mode: bool,
+    ) -> Result<Arc<RoaringBitmap>, IndexSearcherError> {
         let schema: Schema = searcher.index().schema();
 
         let col_field: Field = schema.get_field(self.column_name).map_err(|e| {
@@ -141,10 +601,17 @@ impl<'a> QueryStrategy<Arc<RoaringBitmap>> for SingleTermQueryStrategy<'a> {
                 let term: Term = Term::from_field_text(col_field, &token.text);
                 terms.push(term);
             });
+            terms.extend(language_subfield_terms(
+                searcher,
+                &schema,
+                self.column_name,
+                &[self.term],
+            ));
 
+            check_term_set_cost(searcher, &terms)?;
             let ter_set_query: TermSetQuery = TermSetQuery::new(terms);
             let row_id_collector: RowIdRoaringCollector =
-                RowIdRoaringCollector::with_field("row_id".to_string());
+                row_id_collector_with_deleted(deleted_row_ids, row_id_mapping);
 
             searcher
                 .search(&ter_set_query, &row_id_collector)
@@ -157,7 +624,7 @@ impl<'a> QueryStrategy<Arc<RoaringBitmap>> for SingleTermQueryStrategy<'a> {
             let term: Term = Term::from_field_text(col_field, self.term);
             let term_query: TermQuery = TermQuery::new(term, IndexRecordOption::WithFreqs);
             let row_id_collector: RowIdRoaringCollector =
-                RowIdRoaringCollector::with_field("row_id".to_string());
+                row_id_collector_with_deleted(deleted_row_ids, row_id_mapping);
             println!("for not str");
             searcher
                 .search(&term_query, &row_id_collector)
@@ -181,7 +648,32 @@ pub struct RegexQueryStrategy<'a> {
 }
 
 impl<'a> QueryStrategy<Arc<RoaringBitmap>> for RegexQueryStrategy<'a> {
-    fn execute(&self, searcher: &Searcher) -> Result<Arc<RoaringBitmap>, IndexSearcherError> {
+    fn audit_query(&self) -> String {
+        format!("{}:/{}/", self.column_name, self.pattern)
+    }
+    fn query_key(&self, searcher: &Searcher) -> Result<Option<QueryKey>, IndexSearcherError> {
+        let field: Field = searcher
+            .index()
+            .schema()
+            .get_field(self.column_name)
+            .map_err(|e| {
+                let error: IndexSearcherError = IndexSearcherError::TantivyError(e);
+                ERROR!(function:"RegexQueryStrategy", "{}", error);
+                error
+            })?;
+        Ok(Some(QueryKey::Regex {
+            field,
+            pattern: ConvertUtils::like_to_regex(self.pattern),
+        }))
+    }
+
+    fn execute(
+        &self,
+        searcher: &Searcher,
+        deleted_row_ids: Option<Arc<RoaringTreemap>>,
+        row_id_mapping: Option<Arc<RowIdMappingCache>>,
+        _filter_mode: bool,
+    ) -> Result<Arc<RoaringBitmap>, IndexSearcherError> {
         let schema: Schema = searcher.index().schema();
 
         let col_field: Field = schema.get_field(self.column_name).map_err(|e| {
@@ -191,7 +683,8 @@ impl<'a> QueryStrategy<Arc<RoaringBitmap>> for RegexQueryStrategy<'a> {
         })?;
 
         let row_id_collector: RowIdRoaringCollector =
-            RowIdRoaringCollector::with_field("row_id".to_string());
+            row_id_collector_with_deleted(deleted_row_ids, row_id_mapping);
+        check_like_pattern_cost(searcher, col_field, self.pattern)?;
         let regex_query: RegexQuery = RegexQuery::from_pattern(&ConvertUtils::like_to_regex(self.pattern), col_field).map_err(|e|{
             ERROR!(function:"RegexQueryStrategy", "Error when parse regex query:{}. {}", ConvertUtils::like_to_regex(self.pattern), e);
             IndexSearcherError::TantivyError(e)
@@ -204,6 +697,152 @@ impl<'a> QueryStrategy<Arc<RoaringBitmap>> for RegexQueryStrategy<'a> {
     }
 }
 
+/// Execute range query in a date column, both bounds are inclusive.
+/// Bounds are truncated to precision of the column like indexed dates, so a date matches
+/// if it's in the range at column precision.
+///
+/// Params:
+/// - `column_name`: Execute query in which date column.
+/// - `lower`: Lower bound, `None` means unbounded.
+/// - `upper`: Upper bound, `None` means unbounded.
+///
+pub struct DateRangeQueryStrategy<'a> {
+    pub column_name: &'a str,
+    pub lower: &'a Option<DateTime>,
+    pub upper: &'a Option<DateTime>,
+}
+
+impl<'a> DateRangeQueryStrategy<'a> {
+    // Date column field and bounds truncated to precision of the column.
+    fn truncated_bounds(
+        &self,
+        searcher: &Searcher,
+    ) -> Result<(Field, Option<DateTime>, Option<DateTime>), IndexSearcherError> {
+        let schema: Schema = searcher.index().schema();
+
+        let col_field: Field = schema.get_field(self.column_name).map_err(|e| {
+            let error: IndexSearcherError = IndexSearcherError::TantivyError(e);
+            ERROR!(function:"DateRangeQueryStrategy", "{}", error);
+            error
+        })?;
+        let precision: DateTimePrecision = match schema.get_field_entry(col_field).field_type() {
+            FieldType::Date(date_options) => date_options.get_precision(),
+            _ => {
+                let error_msg: String = format!("column field:{} is not a date.", self.column_name);
+                ERROR!(function:"DateRangeQueryStrategy", "{}", error_msg);
+                return Err(IndexSearcherError::InternalError(error_msg));
+            }
+        };
+        let truncate = |date: &Option<DateTime>| date.map(|date| date.truncate(precision));
+        Ok((col_field, truncate(self.lower), truncate(self.upper)))
+    }
+}
+
+impl<'a> QueryStrategy<Arc<RoaringBitmap>> for DateRangeQueryStrategy<'a> {
+    fn audit_query(&self) -> String {
+        format!(
+            "{}:[{:?} TO {:?}]",
+            self.column_name, self.lower, self.upper
+        )
+    }
+    // Bounds differing only below precision of the column match the same rows.
+    fn query_key(&self, searcher: &Searcher) -> Result<Option<QueryKey>, IndexSearcherError> {
+        let (field, lower, upper) = self.truncated_bounds(searcher)?;
+        Ok(Some(QueryKey::DateRange {
+            field,
+            lower: lower.map(|date| date.into_timestamp_nanos()),
+            upper: upper.map(|date| date.into_timestamp_nanos()),
+        }))
+    }
+
+    fn execute(
+        &self,
+        searcher: &Searcher,
+        deleted_row_ids: Option<Arc<RoaringTreemap>>,
+        row_id_mapping: Option<Arc<RowIdMappingCache>>,
+        _filter_mode: bool,
+    ) -> Result<Arc<RoaringBitmap>, IndexSearcherError> {
+        let (_, lower, upper) = self.truncated_bounds(searcher)?;
+        let bound = |date: Option<DateTime>| match date {
+            Some(date) => Bound::Included(date),
+            None => Bound::Unbounded,
+        };
+
+        let row_id_collector: RowIdRoaringCollector =
+            row_id_collector_with_deleted(deleted_row_ids, row_id_mapping);
+        let range_query: RangeQuery =
+            RangeQuery::new_date_bounds(self.column_name.to_string(), bound(lower), bound(upper));
+
+        searcher.search(&range_query, &row_id_collector).map_err(|e|{
+            ERROR!(function:"DateRangeQueryStrategy", "Error when execute range query:{:?}. {}", range_query, e);
+            IndexSearcherError::TantivyError(e)
+        })
+    }
+}
+
+/// Execute geo query in a geo point column.
+///
+/// Params:
+/// - `column_name`: Execute query in which geo point column.
+/// - `shape`: Bounding box or distance radius points should be in.
+///
+pub struct GeoQueryStrategy<'a> {
+    pub column_name: &'a str,
+    pub shape: &'a GeoShape,
+}
+
+impl<'a> QueryStrategy<Arc<RoaringBitmap>> for GeoQueryStrategy<'a> {
+    fn audit_query(&self) -> String {
+        format!("{}:{:?}", self.column_name, self.shape)
+    }
+    fn query_key(&self, searcher: &Searcher) -> Result<Option<QueryKey>, IndexSearcherError> {
+        let field: Field = searcher
+            .index()
+            .schema()
+            .get_field(self.column_name)
+            .map_err(|e| {
+                let error: IndexSearcherError = IndexSearcherError::TantivyError(e);
+                ERROR!(function:"GeoQueryStrategy", "{}", error);
+                error
+            })?;
+        Ok(Some(QueryKey::geo(field, self.shape)))
+    }
+
+    fn execute(
+        &self,
+        searcher: &Searcher,
+        deleted_row_ids: Option<Arc<RoaringTreemap>>,
+        row_id_mapping: Option<Arc<RowIdMappingCache>>,
+        _filter_mode: bool,
+    ) -> Result<Arc<RoaringBitmap>, IndexSearcherError> {
+        let schema: Schema = searcher.index().schema();
+
+        let col_field: Field = schema.get_field(self.column_name).map_err(|e| {
+            let error: IndexSearcherError = IndexSearcherError::TantivyError(e);
+            ERROR!(function:"GeoQueryStrategy", "{}", error);
+            error
+        })?;
+        match schema.get_field_entry(col_field).field_type() {
+            FieldType::U64(numeric_options) if numeric_options.is_fast() => {}
+            _ => {
+                let error_msg: String =
+                    format!("column field:{} is not a geo point.", self.column_name);
+                ERROR!(function:"GeoQueryStrategy", "{}", error_msg);
+                return Err(IndexSearcherError::InternalError(error_msg));
+            }
+        }
+
+        let row_id_collector: RowIdRoaringCollector =
+            row_id_collector_with_deleted(deleted_row_ids, row_id_mapping);
+        let geo_query: GeoQuery = GeoQuery::new(self.column_name.to_string(), self.shape.clone());
+
+        searcher.search(&geo_query, &row_id_collector).map_err(|e|{
+            ERROR!(function:"GeoQueryStrategy", "Error when execute geo query:{:?}. {}", geo_query, e);
+            IndexSearcherError::TantivyError(e)
+        })
+    }
+}
+
 /// Execute query for a sentence, without natural language search.
 /// This sentence can be written by natural language, or just simple terms.
 /// It will convert to terms query when execute.
@@ -218,7 +857,26 @@ pub struct SentenceQueryStrategy<'a> {
 }
 
 impl<'a> QueryStrategy<Arc<RoaringBitmap>> for SentenceQueryStrategy<'a> {
-    fn execute(&self, searcher: &Searcher) -> Result<Arc<RoaringBitmap>, IndexSearcherError> {
+    fn audit_query(&self) -> String {
+        format!("{}:{}", self.column_name, self.sentence)
+    }
+    // Tokens of sentence are searched as a term set, so word order doesn't matter.
+    fn query_key(&self, searcher: &Searcher) -> Result<Option<QueryKey>, IndexSearcherError> {
+        let terms: Vec<String> = vec![self.sentence.to_string()];
+        let term_set = TermSetQueryStrategy {
+            column_name: self.column_name,
+            terms: &terms,
+        };
+        term_set.query_key(searcher)
+    }
+
+    fn execute(
+        &self,
+        searcher: &Searcher,
+        deleted_row_ids: Option<Arc<RoaringTreemap>>,
+        row_id_mapping: Option<Arc<RowIdMappingCache>>,
+        _filter_mode: bool,
+    ) -> Result<Arc<RoaringBitmap>, IndexSearcherError> {
         let schema: Schema = searcher.index().schema();
 
         let col_field: Field = schema.get_field(self.column_name).map_err(|e| {
@@ -257,15 +915,22 @@ impl<'a> QueryStrategy<Arc<RoaringBitmap>> for SentenceQueryStrategy<'a> {
             token_stream.process(&mut |token| {
                 terms.push(Term::from_field_text(col_field, &token.text));
             });
+            terms.extend(language_subfield_terms(
+                searcher,
+                &schema,
+                self.column_name,
+                &[self.sentence],
+            ));
         } else {
             let error_msg = "Not expected, column field type must be str type.";
             ERROR!(function:"SentenceQueryStrategy", "{}", error_msg);
             return Err(IndexSearcherError::InternalError(error_msg.to_string()));
         }
 
+        check_term_set_cost(searcher, &terms)?;
         let ter_set_query: TermSetQuery = TermSetQuery::new(terms);
         let row_id_collector: RowIdRoaringCollector =
-            RowIdRoaringCollector::with_field("row_id".to_string());
+            row_id_collector_with_deleted(deleted_row_ids, row_id_mapping);
 
         searcher
             .search(&ter_set_query, &row_id_collector)
@@ -289,7 +954,16 @@ pub struct ParserQueryStrategy<'a> {
 }
 
 impl<'a> QueryStrategy<Arc<RoaringBitmap>> for ParserQueryStrategy<'a> {
-    fn execute(&self, searcher: &Searcher) -> Result<Arc<RoaringBitmap>, IndexSearcherError> {
+    fn audit_query(&self) -> String {
+        format!("{}:{}", self.column_name, self.sentence)
+    }
+    fn execute(
+        &self,
+        searcher: &Searcher,
+        deleted_row_ids: Option<Arc<RoaringTreemap>>,
+        row_id_mapping: Option<Arc<RowIdMappingCache>>,
+        _filter_mode: bool,
+    ) -> Result<Arc<RoaringBitmap>, IndexSearcherError> {
         let schema: Schema = searcher.index().schema();
 
         let col_field: Field = schema.get_field(self.column_name).map_err(|e| {
@@ -299,14 +973,19 @@ impl<'a> QueryStrategy<Arc<RoaringBitmap>> for ParserQueryStrategy<'a> {
         })?;
 
         let row_id_collector: RowIdRoaringCollector =
-            RowIdRoaringCollector::with_field("row_id".to_string());
-        let query_parser: QueryParser =
-            QueryParser::for_index(searcher.index(), [col_field].to_vec());
+            row_id_collector_with_deleted(deleted_row_ids, row_id_mapping);
+        let mut fields: Vec<Field> = vec![col_field];
+        fields.extend(language_subfields(&schema, self.column_name));
+        let query_parser: QueryParser = QueryParser::for_index(searcher.index(), fields);
 
-        let text_query = query_parser.parse_query(self.sentence).map_err(|e| {
-            ERROR!(function:"ParserQueryStrategy", "Error when parse: {}. {}", self.sentence, e);
-            IndexSearcherError::QueryParserError(e.to_string())
-        })?;
+        let text_query = {
+            TRACE_SPAN!("parse_query");
+            query_parser.parse_query(self.sentence).map_err(|e| {
+                ERROR!(function:"ParserQueryStrategy", "Error when parse: {}. {}", self.sentence, e);
+                IndexSearcherError::QueryParserError(e.to_string())
+            })?
+        };
+        check_query_cost(text_query.as_ref())?;
 
         searcher.search(&text_query, &row_id_collector).map_err(|e|{
             ERROR!(function:"ParserQueryStrategy", "Error when execute: {}. {}", self.sentence, e);
@@ -336,7 +1015,16 @@ pub struct BM25QueryStrategy<'a> {
 }
 
 impl<'a> QueryStrategy<Vec<RowIdWithScore>> for BM25QueryStrategy<'a> {
-    fn execute(&self, searcher: &Searcher) -> Result<Vec<RowIdWithScore>, IndexSearcherError> {
+    fn audit_query(&self) -> String {
+        self.sentence.to_string()
+    }
+    fn execute(
+        &self,
+        searcher: &Searcher,
+        deleted_row_ids: Option<Arc<RoaringTreemap>>,
+        row_id_mapping: Option<Arc<RowIdMappingCache>>,
+        filter_mode: bool,
+    ) -> Result<Vec<RowIdWithScore>, IndexSearcherError> {
         let schema: Schema = searcher.index().schema();
 
         let fields: Vec<Field> = match self.column_names.is_empty() {
@@ -345,7 +1033,9 @@ impl<'a> QueryStrategy<Vec<RowIdWithScore>> for BM25QueryStrategy<'a> {
                 .filter(|(field, _)| {
                     schema.get_field_name(*field) != "row_id" && {
                         match schema.get_field_entry(*field).field_type() {
-                            tantivy::schema::FieldType::Str(_) => true,
+                            tantivy::schema::FieldType::Str(ref text_options) => {
+                                is_bm25_indexing(text_options.get_indexing_options())
+                            }
                             _ => false,
                         }
                     }
@@ -357,7 +1047,9 @@ impl<'a> QueryStrategy<Vec<RowIdWithScore>> for BM25QueryStrategy<'a> {
                 .iter()
                 .filter(|col_name| match schema.get_field(*col_name) {
                     Ok(field) => match schema.get_field_entry(field).field_type() {
-                        tantivy::schema::FieldType::Str(_) => true,
+                        tantivy::schema::FieldType::Str(ref text_options) => {
+                            is_bm25_indexing(text_options.get_indexing_options())
+                        }
                         _ => false,
                     },
                     Err(e) => {
@@ -396,13 +1088,23 @@ impl<'a> QueryStrategy<Vec<RowIdWithScore>> for BM25QueryStrategy<'a> {
             top_docs_collector = top_docs_collector.with_alive(Arc::new(alive_bitmap));
         }
 
+        if let Some(deleted_row_ids) = deleted_row_ids {
+            top_docs_collector = top_docs_collector.with_deleted(deleted_row_ids);
+        }
+        if let Some(row_id_mapping) = row_id_mapping {
+            top_docs_collector = top_docs_collector.with_row_id_mapping(row_id_mapping);
+        }
+
         let query_parser: QueryParser = QueryParser::for_index(searcher.index(), fields);
-        let text_query: Box<dyn Query> = query_parser.parse_query(self.sentence).map_err(
-            |e: QueryParserError| {
+        let text_query: Box<dyn Query> = {
+            TRACE_SPAN!("parse_query");
+            query_parser.parse_query(self.sentence).map_err(|e: QueryParserError| {
                 ERROR!(function:"BM25QueryStrategy", "Error when parse: {}. {}", self.sentence, e);
                 IndexSearcherError::QueryParserError(e.to_string())
-            },
-        )?;
+            })?
+        };
+        check_query_cost(text_query.as_ref())?;
+        let text_query: Box<dyn Query> = filter_mode_query(text_query, filter_mode);
 
         searcher.search(&text_query, &top_docs_collector).map_err(|e: TantivyError|{
             ERROR!(function:"BM25QueryStrategy", "Error when execute: {}. {}", self.sentence, e);
@@ -424,6 +1126,11 @@ impl<'a> QueryStrategy<Vec<RowIdWithScore>> for BM25QueryStrategy<'a> {
 /// - `query_with_id_range`: Whether collect row_ids with `[start_id, end_id)`
 /// - `start_id`: The start of row_ids range
 /// - `end_id`: The end of row_ids range
+/// - `offset`: Number of ranked results to skip
+/// - `search_after`: Only collect results ranked after this `(score, row_id)` cursor
+/// - `field_boosts`: Score factor of columns, columns not given are not boosted
+/// - `similarity`: Score terms with this similarity, `None` uses tantivy BM25
+/// - `diversify`: Keep at most N results for each value of a numeric or date fast field
 ///
 pub struct BM25QueryStrategy64<'a> {
     pub sentence: &'a str,
@@ -435,56 +1142,37 @@ pub struct BM25QueryStrategy64<'a> {
     pub end_id: &'a u64,
     pub need_doc: &'a bool,
     pub column_names: &'a Vec<String>,
+    pub offset: &'a u32,
+    pub search_after: &'a Option<(f32, u64)>,
+    pub field_boosts: &'a HashMap<String, f32>,
+    pub similarity: &'a Option<Similarity>,
+    pub diversify: &'a Option<(String, u32)>,
 }
 
-impl<'a> QueryStrategy<Vec<RowIdWithScore>> for BM25QueryStrategy64<'a> {
-    fn execute(&self, searcher: &Searcher) -> Result<Vec<RowIdWithScore>, IndexSearcherError> {
-        let schema: Schema = searcher.index().schema();
-
-        let fields: Vec<Field> = match self.column_names.is_empty() {
-            true => schema
-                .fields()
-                .filter(|(field, _)| {
-                    schema.get_field_name(*field) != "row_id" && {
-                        match schema.get_field_entry(*field).field_type() {
-                            tantivy::schema::FieldType::Str(_) => true,
-                            _ => false,
-                        }
-                    }
-                })
-                .map(|(field, _)| field)
-                .collect(),
-            false => self
-                .column_names
-                .iter()
-                .filter(|col_name| match schema.get_field(*col_name) {
-                    Ok(field) => match schema.get_field_entry(field).field_type() {
-                        tantivy::schema::FieldType::Str(_) => true,
-                        _ => false,
-                    },
-                    Err(e) => {
-                        let error: IndexSearcherError = IndexSearcherError::TantivyError(e);
-                        ERROR!(function:"BM25QueryStrategy", "{}", error);
-                        return false;
-                    }
-                })
-                .map(|col_name| {
-                    schema.get_field(col_name).map_err(|e| {
-                        let error: IndexSearcherError = IndexSearcherError::TantivyError(e);
-                        ERROR!(function:"BM25QueryStrategy", "{}", error);
-                        error
-                    })
-                })
-                .collect::<Result<Vec<Field>, IndexSearcherError>>()?,
-        };
-
-        INFO!(function:"BM25QueryStrategy", "Fields: {:?}", fields);
-
+impl<'a> BM25QueryStrategy64<'a> {
+    // Collector of ranked results with filters of this query.
+    fn top_docs_collector(
+        &self,
+        searcher: &Searcher,
+        fields: Vec<Field>,
+        deleted_row_ids: Option<Arc<RoaringTreemap>>,
+        row_id_mapping: Option<Arc<RowIdMappingCache>>,
+    ) -> TopDocsWithFilter64 {
         let mut top_docs_collector: TopDocsWithFilter64 =
             TopDocsWithFilter64::with_limit(*self.topk as usize)
                 .with_searcher(searcher.clone())
-                .with_text_fields(fields.clone())
-                .with_stored_text(*self.need_doc);
+                .with_text_fields(fields)
+                .with_stored_text(*self.need_doc)
+                .with_offset(*self.offset as usize);
+
+        if let Some(search_after) = self.search_after {
+            top_docs_collector = top_docs_collector.with_search_after(*search_after);
+        }
+
+        if let Some((diversify_field, max_per_key)) = self.diversify {
+            top_docs_collector =
+                top_docs_collector.with_diversify(diversify_field.clone(), *max_per_key as usize);
+        }
 
         if *self.query_with_filter {
             let mut alive_bitmap: RoaringTreemap = RoaringTreemap::new();
@@ -496,13 +1184,61 @@ impl<'a> QueryStrategy<Vec<RowIdWithScore>> for BM25QueryStrategy64<'a> {
             top_docs_collector = top_docs_collector.with_range((*self.start_id, *self.end_id));
         }
 
-        let query_parser: QueryParser = QueryParser::for_index(searcher.index(), fields);
-        let text_query: Box<dyn Query> = query_parser.parse_query(self.sentence).map_err(
-            |e: QueryParserError| {
+        if let Some(deleted_row_ids) = deleted_row_ids {
+            top_docs_collector = top_docs_collector.with_deleted(deleted_row_ids);
+        }
+        if let Some(row_id_mapping) = row_id_mapping {
+            top_docs_collector = top_docs_collector.with_row_id_mapping(row_id_mapping);
+        }
+        top_docs_collector
+    }
+
+    // Parse `sentence` over `fields` with boosts and similarity of this query.
+    fn text_query(
+        &self,
+        searcher: &Searcher,
+        fields: Vec<Field>,
+        filter_mode: bool,
+    ) -> Result<Box<dyn Query>, IndexSearcherError> {
+        let schema: Schema = searcher.index().schema();
+        let mut query_parser: QueryParser = QueryParser::for_index(searcher.index(), fields);
+        apply_field_boosts(&schema, &mut query_parser, self.field_boosts)?;
+        let text_query: Box<dyn Query> = {
+            TRACE_SPAN!("parse_query");
+            query_parser.parse_query(self.sentence).map_err(|e: QueryParserError| {
                 ERROR!(function:"BM25QueryStrategy", "Error when parse: {}. {}", self.sentence, e);
                 IndexSearcherError::QueryParserError(e.to_string())
-            },
-        )?;
+            })?
+        };
+        check_query_cost(text_query.as_ref())?;
+        let text_query: Box<dyn Query> = match self.similarity {
+            Some(similarity) => with_similarity(text_query.as_ref(), *similarity),
+            None => text_query,
+        };
+        Ok(filter_mode_query(text_query, filter_mode))
+    }
+}
+
+impl<'a> QueryStrategy<Vec<RowIdWithScore>> for BM25QueryStrategy64<'a> {
+    fn audit_query(&self) -> String {
+        self.sentence.to_string()
+    }
+    fn execute(
+        &self,
+        searcher: &Searcher,
+        deleted_row_ids: Option<Arc<RoaringTreemap>>,
+        row_id_mapping: Option<Arc<RowIdMappingCache>>,
+        filter_mode: bool,
+    ) -> Result<Vec<RowIdWithScore>, IndexSearcherError> {
+        let schema: Schema = searcher.index().schema();
+
+        let fields: Vec<Field> = bm25_text_fields(&schema, self.column_names)?;
+
+        INFO!(function:"BM25QueryStrategy", "Fields: {:?}", fields);
+
+        let top_docs_collector: TopDocsWithFilter64 =
+            self.top_docs_collector(searcher, fields.clone(), deleted_row_ids, row_id_mapping);
+        let text_query: Box<dyn Query> = self.text_query(searcher, fields, filter_mode)?;
 
         searcher.search(&text_query, &top_docs_collector).map_err(|e: TantivyError|{
             ERROR!(function:"BM25QueryStrategy", "Error when execute: {}. {}", self.sentence, e);
@@ -511,15 +1247,1013 @@ impl<'a> QueryStrategy<Vec<RowIdWithScore>> for BM25QueryStrategy64<'a> {
     }
 }
 
-pub struct QueryExecutor<'a, T> {
-    strategy: &'a dyn QueryStrategy<T>,
+/// Execute BM25 query of `bm25_query` and count its total hits in the same pass, see
+/// `TopDocsWithTotalHits64`.
+///
+/// Params:
+/// - `bm25_query`: Ranked query whose results and total hits are returned.
+/// - `exact_total_hits`: Whether total hits must be exact, otherwise they may be a lower bound.
+///
+pub struct TotalHitsQueryStrategy<'a> {
+    pub bm25_query: &'a BM25QueryStrategy64<'a>,
+    pub exact_total_hits: &'a bool,
 }
 
-impl<'a, T> QueryExecutor<'a, T> {
-    pub fn new(strategy: &'a dyn QueryStrategy<T>) -> Self {
-        QueryExecutor { strategy }
+impl<'a> QueryStrategy<TopDocsWithTotalHits> for TotalHitsQueryStrategy<'a> {
+    fn audit_query(&self) -> String {
+        self.bm25_query.audit_query()
     }
-    pub fn execute(&self, searcher: &Searcher) -> Result<T, IndexSearcherError> {
-        self.strategy.execute(searcher)
+    fn execute(
+        &self,
+        searcher: &Searcher,
+        deleted_row_ids: Option<Arc<RoaringTreemap>>,
+        row_id_mapping: Option<Arc<RowIdMappingCache>>,
+        filter_mode: bool,
+    ) -> Result<TopDocsWithTotalHits, IndexSearcherError> {
+        let schema: Schema = searcher.index().schema();
+        let fields: Vec<Field> = bm25_text_fields(&schema, self.bm25_query.column_names)?;
+
+        let top_docs_collector: TopDocsWithTotalHits64 = TopDocsWithTotalHits64::new(
+            self.bm25_query.top_docs_collector(
+                searcher,
+                fields.clone(),
+                deleted_row_ids,
+                row_id_mapping,
+            ),
+            *self.exact_total_hits,
+        );
+        let text_query: Box<dyn Query> =
+            self.bm25_query.text_query(searcher, fields, filter_mode)?;
+
+        let (top_docs, total_hits): (Vec<RowIdWithScore>, TotalHits) = searcher
+            .search(&text_query, &top_docs_collector)
+            .map_err(|e: TantivyError| {
+                ERROR!(function:"TotalHitsQueryStrategy", "Error when execute: {}. {}", self.bm25_query.sentence, e);
+                IndexSearcherError::TantivyError(e)
+            })?;
+        Ok(TopDocsWithTotalHits {
+            top_docs,
+            total_hits: total_hits.count(),
+            total_hits_exact: total_hits.exact,
+        })
+    }
+}
+
+/// Execute BM25 query of `bm25_query` and report query terms matched in each result,
+/// terms matched in a language sub-field are reported in its column.
+///
+/// Params:
+/// - `bm25_query`: Ranked query whose results are reported.
+///
+pub struct MatchedTermsQueryStrategy<'a> {
+    pub bm25_query: &'a BM25QueryStrategy64<'a>,
+}
+
+impl<'a> QueryStrategy<Vec<MatchedTermsTopDoc>> for MatchedTermsQueryStrategy<'a> {
+    fn audit_query(&self) -> String {
+        self.bm25_query.audit_query()
+    }
+    fn execute(
+        &self,
+        searcher: &Searcher,
+        deleted_row_ids: Option<Arc<RoaringTreemap>>,
+        row_id_mapping: Option<Arc<RowIdMappingCache>>,
+        filter_mode: bool,
+    ) -> Result<Vec<MatchedTermsTopDoc>, IndexSearcherError> {
+        let top_docs: Vec<RowIdWithScore> =
+            self.bm25_query
+                .execute(searcher, deleted_row_ids, row_id_mapping, filter_mode)?;
+
+        let schema: Schema = searcher.index().schema();
+        let fields: Vec<Field> = bm25_text_fields(&schema, self.bm25_query.column_names)?;
+        let query_parser: QueryParser = QueryParser::for_index(searcher.index(), fields);
+        let text_query: Box<dyn Query> = query_parser
+            .parse_query(self.bm25_query.sentence)
+            .map_err(|e: QueryParserError| {
+                ERROR!(function:"MatchedTermsQueryStrategy", "Error when parse: {}. {}", self.bm25_query.sentence, e);
+                IndexSearcherError::QueryParserError(e.to_string())
+            })?;
+        let mut terms: BTreeSet<Term> = BTreeSet::new();
+        text_query.query_terms(&mut |term: &Term, _| {
+            terms.insert(term.clone());
+        });
+
+        let subfield_columns: HashMap<Field, String> = language_subfield_columns(&schema);
+
+        let mut matched_docs: Vec<MatchedTermsTopDoc> = top_docs
+            .into_iter()
+            .map(|top_doc| MatchedTermsTopDoc {
+                top_doc,
+                matched_terms: Vec::new(),
+            })
+            .collect();
+        // Postings of a term are read once per segment, docs of a segment are seeked in order.
+        let mut segment_docs: BTreeMap<u32, Vec<usize>> = BTreeMap::new();
+        for (idx, matched_doc) in matched_docs.iter().enumerate() {
+            segment_docs
+                .entry(matched_doc.top_doc.seg_id)
+                .or_default()
+                .push(idx);
+        }
+        for (seg_id, mut doc_indexes) in segment_docs {
+            doc_indexes.sort_by_key(|idx| matched_docs[*idx].top_doc.doc_id);
+            let segment_reader: &SegmentReader = searcher.segment_reader(seg_id);
+            for term in &terms {
+                let term_text: String = match term_text(term) {
+                    Some(term_text) => term_text,
+                    None => continue,
+                };
+                let field: &str = match subfield_columns.get(&term.field()) {
+                    Some(column_name) => column_name,
+                    None => schema.get_field_name(term.field()),
+                };
+                let mut postings: SegmentPostings = match segment_reader
+                    .inverted_index(term.field())?
+                    .read_postings(term, IndexRecordOption::Basic)
+                    .map_err(|e| IndexSearcherError::TantivyError(e.into()))?
+                {
+                    Some(postings) => postings,
+                    None => continue,
+                };
+                for idx in &doc_indexes {
+                    let doc_id: DocId = matched_docs[*idx].top_doc.doc_id;
+                    if postings.seek(doc_id) != doc_id {
+                        continue;
+                    }
+                    let matched_terms: &mut Vec<MatchedTerm> =
+                        &mut matched_docs[*idx].matched_terms;
+                    // Column and its sub-field may match the same text.
+                    if !matched_terms
+                        .iter()
+                        .any(|matched| matched.field == field && matched.term == term_text)
+                    {
+                        matched_terms.push(MatchedTerm {
+                            field: field.to_string(),
+                            term: term_text.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        Ok(matched_docs)
+    }
+}
+
+// Text of a term as written in queries, `None` for types not reported.
+fn term_text(term: &Term) -> Option<String> {
+    let value = term.value();
+    value
+        .as_str()
+        .map(|text| text.to_string())
+        .or_else(|| value.as_u64().map(|number| number.to_string()))
+        .or_else(|| value.as_i64().map(|number| number.to_string()))
+        .or_else(|| value.as_f64().map(|number| number.to_string()))
+        .or_else(|| value.as_bool().map(|boolean| boolean.to_string()))
+}
+
+/// Execute a query of the JSON query DSL and get bm25 score.
+///
+/// Params:
+/// - `query`: Parsed query DSL, see `QueryDsl`.
+/// - `topk`: max-heap build with topK
+/// - `alived_ids`: Represent row_ids who are alived.
+/// - `query_with_filter`: Whether collect row_ids with `alived_ids`
+/// - `query_with_id_range`: Whether collect row_ids with `[start_id, end_id)`
+/// - `start_id`: The start of row_ids range
+/// - `end_id`: The end of row_ids range
+///
+pub struct QueryDslStrategy64<'a> {
+    pub query: &'a QueryDsl,
+    pub topk: &'a u32,
+    pub alived_ids: &'a Vec<u64>,
+    pub query_with_filter: &'a bool,
+    pub query_with_id_range: &'a bool,
+    pub start_id: &'a u64,
+    pub end_id: &'a u64,
+    pub need_doc: &'a bool,
+}
+
+impl<'a> QueryStrategy<Vec<RowIdWithScore>> for QueryDslStrategy64<'a> {
+    fn audit_query(&self) -> String {
+        format!("{:?}", self.query)
+    }
+    fn execute(
+        &self,
+        searcher: &Searcher,
+        deleted_row_ids: Option<Arc<RoaringTreemap>>,
+        row_id_mapping: Option<Arc<RowIdMappingCache>>,
+        filter_mode: bool,
+    ) -> Result<Vec<RowIdWithScore>, IndexSearcherError> {
+        let schema: Schema = searcher.index().schema();
+
+        let fields: Vec<Field> = bm25_text_fields(&schema, &self.query.column_names())?;
+
+        let mut top_docs_collector: TopDocsWithFilter64 =
+            TopDocsWithFilter64::with_limit(*self.topk as usize)
+                .with_searcher(searcher.clone())
+                .with_text_fields(fields)
+                .with_stored_text(*self.need_doc);
+
+        if *self.query_with_filter {
+            let mut alive_bitmap: RoaringTreemap = RoaringTreemap::new();
+            alive_bitmap.extend(self.alived_ids);
+            top_docs_collector = top_docs_collector.with_alive(Arc::new(alive_bitmap));
+        }
+
+        if *self.query_with_id_range {
+            top_docs_collector = top_docs_collector.with_range((*self.start_id, *self.end_id));
+        }
+
+        if let Some(deleted_row_ids) = deleted_row_ids {
+            top_docs_collector = top_docs_collector.with_deleted(deleted_row_ids);
+        }
+        if let Some(row_id_mapping) = row_id_mapping {
+            top_docs_collector = top_docs_collector.with_row_id_mapping(row_id_mapping);
+        }
+
+        let query: Box<dyn Query> = {
+            TRACE_SPAN!("parse_query");
+            self.query.build(searcher)?
+        };
+        check_query_cost(query.as_ref())?;
+        let query: Box<dyn Query> = filter_mode_query(query, filter_mode);
+
+        searcher
+            .search(&query, &top_docs_collector)
+            .map_err(|e: TantivyError| {
+                ERROR!(function:"QueryDslStrategy", "Error when execute: {:?}. {}", self.query, e);
+                IndexSearcherError::TantivyError(e)
+            })
+    }
+}
+
+/// Execute nearest neighbors query in a vector column, results are scored by metric of the column.
+/// Rows matching `filter_sentence` in text columns are searched only, so text filter and vector
+/// similarity are combined in one search. Scores are similarities, `filter_mode` is ignored.
+///
+/// Params:
+/// - `column_name`: Execute query in which vector column.
+/// - `vector`: Query vector, it should have dims of the column.
+/// - `topk`: Max number of results.
+/// - `filter_sentence`: Sentence rows should match, empty means all rows are searched.
+/// - `alived_ids`: Represent row_ids who are alived.
+/// - `query_with_filter`: Whether collect row_ids with `alived_ids`
+/// - `query_with_id_range`: Whether collect row_ids with `[start_id, end_id)`
+/// - `start_id`: The start of row_ids range
+/// - `end_id`: The end of row_ids range
+/// - `vector_cache`: Vectors and HNSW graphs warmed by index reader.
+///
+pub struct KnnQueryStrategy64<'a> {
+    pub column_name: &'a str,
+    pub vector: &'a Vec<f32>,
+    pub topk: &'a u32,
+    pub filter_sentence: &'a str,
+    pub alived_ids: &'a Vec<u64>,
+    pub query_with_filter: &'a bool,
+    pub query_with_id_range: &'a bool,
+    pub start_id: &'a u64,
+    pub end_id: &'a u64,
+    pub need_doc: &'a bool,
+    pub vector_cache: &'a Arc<VectorCache>,
+}
+
+impl<'a> QueryStrategy<Vec<RowIdWithScore>> for KnnQueryStrategy64<'a> {
+    fn audit_query(&self) -> String {
+        format!(
+            "knn {} topk:{} filter:{}",
+            self.column_name, self.topk, self.filter_sentence
+        )
+    }
+    fn execute(
+        &self,
+        searcher: &Searcher,
+        deleted_row_ids: Option<Arc<RoaringTreemap>>,
+        row_id_mapping: Option<Arc<RowIdMappingCache>>,
+        _filter_mode: bool,
+    ) -> Result<Vec<RowIdWithScore>, IndexSearcherError> {
+        let schema: Schema = searcher.index().schema();
+
+        let vector_format: VectorFormat = self
+            .vector_cache
+            .vector_format(self.column_name)
+            .cloned()
+            .ok_or_else(|| {
+                let error_msg: String =
+                    format!("column field:{} is not a vector.", self.column_name);
+                ERROR!(function:"KnnQueryStrategy", "{}", error_msg);
+                IndexSearcherError::InternalError(error_msg)
+            })?;
+        vector_format.check(self.vector).map_err(|e| {
+            ERROR!(function:"KnnQueryStrategy", "Invalid query vector. {}", e);
+            IndexSearcherError::QueryParserError(e)
+        })?;
+
+        let fields: Vec<Field> = bm25_text_fields(&schema, &Vec::new())?;
+
+        let mut top_docs_collector: TopDocsWithFilter64 =
+            TopDocsWithFilter64::with_limit(*self.topk as usize)
+                .with_searcher(searcher.clone())
+                .with_text_fields(fields.clone())
+                .with_stored_text(*self.need_doc);
+        // Row_id filters are also applied while searching HNSW graphs.
+        let mut row_id_filter: KnnRowIdFilter = KnnRowIdFilter {
+            row_id_mapping: row_id_mapping.clone(),
+            deleted_row_ids: deleted_row_ids.clone(),
+            ..KnnRowIdFilter::default()
+        };
+
+        if *self.query_with_filter {
+            let mut alive_bitmap: RoaringTreemap = RoaringTreemap::new();
+            alive_bitmap.extend(self.alived_ids);
+            let alive_bitmap: Arc<RoaringTreemap> = Arc::new(alive_bitmap);
+            top_docs_collector = top_docs_collector.with_alive(alive_bitmap.clone());
+            row_id_filter.alive_row_ids = Some(alive_bitmap);
+        }
+
+        if *self.query_with_id_range {
+            top_docs_collector = top_docs_collector.with_range((*self.start_id, *self.end_id));
+            row_id_filter.row_id_range = Some((*self.start_id, *self.end_id));
+        }
+
+        if let Some(deleted_row_ids) = deleted_row_ids {
+            top_docs_collector = top_docs_collector.with_deleted(deleted_row_ids);
+        }
+        if let Some(row_id_mapping) = row_id_mapping {
+            top_docs_collector = top_docs_collector.with_row_id_mapping(row_id_mapping);
+        }
+
+        let mut knn_query: KnnQuery = KnnQuery::new(
+            self.column_name.to_string(),
+            self.vector.clone(),
+            vector_format,
+            *self.topk as usize,
+            self.vector_cache.clone(),
+        )
+        .with_row_id_filter(row_id_filter);
+        if !self.filter_sentence.trim().is_empty() {
+            let query_parser: QueryParser = QueryParser::for_index(searcher.index(), fields);
+            let filter_query: Box<dyn Query> = {
+                TRACE_SPAN!("parse_query");
+                query_parser.parse_query(self.filter_sentence).map_err(|e: QueryParserError| {
+                    ERROR!(function:"KnnQueryStrategy", "Error when parse: {}. {}", self.filter_sentence, e);
+                    IndexSearcherError::QueryParserError(e.to_string())
+                })?
+            };
+            check_query_cost(filter_query.as_ref())?;
+            knn_query = knn_query.with_filter(filter_query);
+        }
+
+        searcher
+            .search(&knn_query, &top_docs_collector)
+            .map_err(|e: TantivyError| {
+                ERROR!(function:"KnnQueryStrategy", "Error when execute: {:?}. {}", knn_query, e);
+                IndexSearcherError::TantivyError(e)
+            })
+    }
+}
+
+/// Execute learned sparse retrieval in a sparse vector column, results are scored by dot product
+/// of `weights` and term weights of rows. Only rows matching `filter_sentence` in text columns
+/// are searched, the filter doesn't change scores.
+///
+/// Params:
+/// - `column_name`: Execute query in which sparse vector column.
+/// - `weights`: Query term weights.
+/// - `sparse_vector_format`: Quantization of the column.
+/// - `topk`: Max number of results.
+/// - `filter_sentence`: Sentence rows should match, empty means all rows are searched.
+/// - `alived_ids`: Represent row_ids who are alived.
+/// - `query_with_filter`: Whether collect row_ids with `alived_ids`
+/// - `query_with_id_range`: Whether collect row_ids with `[start_id, end_id)`
+/// - `start_id`: The start of row_ids range
+/// - `end_id`: The end of row_ids range
+///
+pub struct SparseVectorQueryStrategy64<'a> {
+    pub column_name: &'a str,
+    pub weights: &'a Vec<(String, f32)>,
+    pub sparse_vector_format: &'a SparseVectorFormat,
+    pub topk: &'a u32,
+    pub filter_sentence: &'a str,
+    pub alived_ids: &'a Vec<u64>,
+    pub query_with_filter: &'a bool,
+    pub query_with_id_range: &'a bool,
+    pub start_id: &'a u64,
+    pub end_id: &'a u64,
+    pub need_doc: &'a bool,
+}
+
+impl<'a> QueryStrategy<Vec<RowIdWithScore>> for SparseVectorQueryStrategy64<'a> {
+    fn audit_query(&self) -> String {
+        format!(
+            "{}:{:?} filter:{}",
+            self.column_name, self.weights, self.filter_sentence
+        )
+    }
+    fn execute(
+        &self,
+        searcher: &Searcher,
+        deleted_row_ids: Option<Arc<RoaringTreemap>>,
+        row_id_mapping: Option<Arc<RowIdMappingCache>>,
+        filter_mode: bool,
+    ) -> Result<Vec<RowIdWithScore>, IndexSearcherError> {
+        let schema: Schema = searcher.index().schema();
+
+        let field: Field = schema.get_field(self.column_name).map_err(|e| {
+            let error: IndexSearcherError = IndexSearcherError::TantivyError(e);
+            ERROR!(function:"SparseVectorQueryStrategy", "{}", error);
+            error
+        })?;
+        let fields: Vec<Field> = bm25_text_fields(&schema, &Vec::new())?;
+
+        let mut top_docs_collector: TopDocsWithFilter64 =
+            TopDocsWithFilter64::with_limit(*self.topk as usize)
+                .with_searcher(searcher.clone())
+                .with_text_fields(fields.clone())
+                .with_stored_text(*self.need_doc);
+
+        if *self.query_with_filter {
+            let mut alive_bitmap: RoaringTreemap = RoaringTreemap::new();
+            alive_bitmap.extend(self.alived_ids);
+            top_docs_collector = top_docs_collector.with_alive(Arc::new(alive_bitmap));
+        }
+
+        if *self.query_with_id_range {
+            top_docs_collector = top_docs_collector.with_range((*self.start_id, *self.end_id));
+        }
+
+        if let Some(deleted_row_ids) = deleted_row_ids {
+            top_docs_collector = top_docs_collector.with_deleted(deleted_row_ids);
+        }
+        if let Some(row_id_mapping) = row_id_mapping {
+            top_docs_collector = top_docs_collector.with_row_id_mapping(row_id_mapping);
+        }
+
+        let sparse_vector_query: Box<dyn Query> = Box::new(SparseVectorQuery::new(
+            field,
+            self.weights,
+            *self.sparse_vector_format,
+        ));
+        let sparse_vector_query: Box<dyn Query> = match self.filter_sentence.trim().is_empty() {
+            true => sparse_vector_query,
+            false => {
+                let query_parser: QueryParser = QueryParser::for_index(searcher.index(), fields);
+                let filter_query: Box<dyn Query> = {
+                    TRACE_SPAN!("parse_query");
+                    query_parser.parse_query(self.filter_sentence).map_err(|e: QueryParserError| {
+                        ERROR!(function:"SparseVectorQueryStrategy", "Error when parse: {}. {}", self.filter_sentence, e);
+                        IndexSearcherError::QueryParserError(e.to_string())
+                    })?
+                };
+                Box::new(BooleanQuery::new(vec![
+                    (Occur::Must, sparse_vector_query),
+                    (
+                        Occur::Must,
+                        Box::new(ConstScoreQuery::new(filter_query, 0.0)),
+                    ),
+                ]))
+            }
+        };
+        // Weights of the sparse vector are clauses too.
+        check_query_cost(sparse_vector_query.as_ref())?;
+        let sparse_vector_query: Box<dyn Query> =
+            filter_mode_query(sparse_vector_query, filter_mode);
+
+        searcher
+            .search(&sparse_vector_query, &top_docs_collector)
+            .map_err(|e: TantivyError| {
+                ERROR!(function:"SparseVectorQueryStrategy", "Error when execute: {:?}. {}", sparse_vector_query, e);
+                IndexSearcherError::TantivyError(e)
+            })
+    }
+}
+
+/// Execute BM25 query and order results by a fast field, BM25 score is the tiebreak.
+///
+/// Params:
+/// - `sentence`: Sentence need to be parsed and query.
+/// - `topk`: Max number of results.
+/// - `order_field`: Numeric or date fast field used to order results.
+/// - `ascending`: Order by ascending field value, otherwise descending.
+/// - `alived_ids`: Represent row_ids who are alived.
+/// - `query_with_filter`: Whether collect row_ids with `alived_ids`
+/// - `query_with_id_range`: Whether collect row_ids with `[start_id, end_id)`
+/// - `start_id`: The start of row_ids range
+/// - `end_id`: The end of row_ids range
+///
+pub struct OrderByFieldQueryStrategy<'a> {
+    pub sentence: &'a str,
+    pub topk: &'a u32,
+    pub order_field: &'a str,
+    pub ascending: &'a bool,
+    pub alived_ids: &'a Vec<u64>,
+    pub query_with_filter: &'a bool,
+    pub query_with_id_range: &'a bool,
+    pub start_id: &'a u64,
+    pub end_id: &'a u64,
+    pub need_doc: &'a bool,
+    pub column_names: &'a Vec<String>,
+}
+
+impl<'a> QueryStrategy<Vec<RowIdWithScore>> for OrderByFieldQueryStrategy<'a> {
+    fn audit_query(&self) -> String {
+        self.sentence.to_string()
+    }
+    fn execute(
+        &self,
+        searcher: &Searcher,
+        deleted_row_ids: Option<Arc<RoaringTreemap>>,
+        row_id_mapping: Option<Arc<RowIdMappingCache>>,
+        filter_mode: bool,
+    ) -> Result<Vec<RowIdWithScore>, IndexSearcherError> {
+        let schema: Schema = searcher.index().schema();
+
+        let fields: Vec<Field> = bm25_text_fields(&schema, self.column_names)?;
+
+        let mut top_docs_collector: TopDocsOrderByField = TopDocsOrderByField::with_limit(
+            *self.topk as usize,
+            self.order_field.to_string(),
+            *self.ascending,
+        );
+
+        if *self.query_with_filter {
+            let mut alive_bitmap: RoaringTreemap = RoaringTreemap::new();
+            alive_bitmap.extend(self.alived_ids);
+            top_docs_collector = top_docs_collector.with_alive(Arc::new(alive_bitmap));
+        }
+
+        if *self.query_with_id_range {
+            top_docs_collector = top_docs_collector.with_range((*self.start_id, *self.end_id));
+        }
+
+        if let Some(deleted_row_ids) = deleted_row_ids {
+            top_docs_collector = top_docs_collector.with_deleted(deleted_row_ids);
+        }
+        if let Some(row_id_mapping) = row_id_mapping {
+            top_docs_collector = top_docs_collector.with_row_id_mapping(row_id_mapping);
+        }
+
+        let query_parser: QueryParser = QueryParser::for_index(searcher.index(), fields.clone());
+        let text_query: Box<dyn Query> = {
+            TRACE_SPAN!("parse_query");
+            query_parser.parse_query(self.sentence).map_err(|e: QueryParserError| {
+                ERROR!(function:"OrderByFieldQueryStrategy", "Error when parse: {}. {}", self.sentence, e);
+                IndexSearcherError::QueryParserError(e.to_string())
+            })?
+        };
+        check_query_cost(text_query.as_ref())?;
+        let text_query: Box<dyn Query> = filter_mode_query(text_query, filter_mode);
+
+        let ordered_docs: Vec<FieldOrderedDoc> = searcher
+            .search(&text_query, &top_docs_collector)
+            .map_err(|e: TantivyError| {
+                ERROR!(function:"OrderByFieldQueryStrategy", "Error when execute: {}. {}", self.sentence, e);
+                IndexSearcherError::TantivyError(e)
+            })?;
+
+        // Only read stored text for final results.
+        let mut results: Vec<RowIdWithScore> = Vec::with_capacity(ordered_docs.len());
+        for ordered_doc in ordered_docs {
+            let mut row_id_with_score: RowIdWithScore = ordered_doc.doc;
+            if *self.need_doc {
+                read_stored_texts(searcher, &fields, &mut row_id_with_score)?;
+            }
+            results.push(row_id_with_score);
+        }
+        Ok(results)
+    }
+}
+
+/// Execute BM25 query and collapse results by a grouping fast field.
+///
+/// Params:
+/// - `sentence`: Sentence need to be parsed and query.
+/// - `topk`: Max number of groups.
+/// - `group_field`: u64 fast field used to group results, e.g. user_id.
+/// - `alived_ids`: Represent row_ids who are alived.
+/// - `query_with_filter`: Whether collect row_ids with `alived_ids`
+/// - `query_with_id_range`: Whether collect row_ids with `[start_id, end_id)`
+/// - `start_id`: The start of row_ids range
+/// - `end_id`: The end of row_ids range
+///
+pub struct GroupedBM25QueryStrategy<'a> {
+    pub sentence: &'a str,
+    pub topk: &'a u32,
+    pub group_field: &'a str,
+    pub alived_ids: &'a Vec<u64>,
+    pub query_with_filter: &'a bool,
+    pub query_with_id_range: &'a bool,
+    pub start_id: &'a u64,
+    pub end_id: &'a u64,
+    pub need_doc: &'a bool,
+    pub column_names: &'a Vec<String>,
+}
+
+impl<'a> QueryStrategy<Vec<GroupTopDoc>> for GroupedBM25QueryStrategy<'a> {
+    fn audit_query(&self) -> String {
+        self.sentence.to_string()
+    }
+    fn execute(
+        &self,
+        searcher: &Searcher,
+        deleted_row_ids: Option<Arc<RoaringTreemap>>,
+        row_id_mapping: Option<Arc<RowIdMappingCache>>,
+        filter_mode: bool,
+    ) -> Result<Vec<GroupTopDoc>, IndexSearcherError> {
+        let schema: Schema = searcher.index().schema();
+
+        let fields: Vec<Field> = bm25_text_fields(&schema, self.column_names)?;
+
+        let mut top_groups_collector: TopGroupsCollector =
+            TopGroupsCollector::with_limit(*self.topk as usize, self.group_field.to_string());
+
+        if *self.query_with_filter {
+            let mut alive_bitmap: RoaringTreemap = RoaringTreemap::new();
+            alive_bitmap.extend(self.alived_ids);
+            top_groups_collector = top_groups_collector.with_alive(Arc::new(alive_bitmap));
+        }
+
+        if *self.query_with_id_range {
+            top_groups_collector = top_groups_collector.with_range((*self.start_id, *self.end_id));
+        }
+
+        if let Some(deleted_row_ids) = deleted_row_ids {
+            top_groups_collector = top_groups_collector.with_deleted(deleted_row_ids);
+        }
+        if let Some(row_id_mapping) = row_id_mapping {
+            top_groups_collector = top_groups_collector.with_row_id_mapping(row_id_mapping);
+        }
+
+        let query_parser: QueryParser = QueryParser::for_index(searcher.index(), fields.clone());
+        let text_query: Box<dyn Query> = {
+            TRACE_SPAN!("parse_query");
+            query_parser.parse_query(self.sentence).map_err(|e: QueryParserError| {
+                ERROR!(function:"GroupedBM25QueryStrategy", "Error when parse: {}. {}", self.sentence, e);
+                IndexSearcherError::QueryParserError(e.to_string())
+            })?
+        };
+        check_query_cost(text_query.as_ref())?;
+        let text_query: Box<dyn Query> = filter_mode_query(text_query, filter_mode);
+
+        let mut groups: Vec<GroupTopDoc> = searcher
+            .search(&text_query, &top_groups_collector)
+            .map_err(|e: TantivyError| {
+                ERROR!(function:"GroupedBM25QueryStrategy", "Error when execute: {}. {}", self.sentence, e);
+                IndexSearcherError::TantivyError(e)
+            })?;
+
+        if *self.need_doc {
+            for group in groups.iter_mut() {
+                read_stored_texts(searcher, &fields, &mut group.top_doc)?;
+            }
+        }
+        Ok(groups)
+    }
+}
+
+/// Execute BM25 query and estimate the number of distinct values of a fast field in matched docs.
+///
+/// Params:
+/// - `sentence`: Sentence need to be parsed and query.
+/// - `field_name`: Numeric or date fast field whose distinct values are counted.
+/// - `alived_ids`: Represent row_ids who are alived.
+/// - `query_with_filter`: Whether collect row_ids with `alived_ids`
+/// - `query_with_id_range`: Whether collect row_ids with `[start_id, end_id)`
+/// - `start_id`: The start of row_ids range
+/// - `end_id`: The end of row_ids range
+///
+pub struct CardinalityQueryStrategy<'a> {
+    pub sentence: &'a str,
+    pub field_name: &'a str,
+    pub alived_ids: &'a Vec<u64>,
+    pub query_with_filter: &'a bool,
+    pub query_with_id_range: &'a bool,
+    pub start_id: &'a u64,
+    pub end_id: &'a u64,
+    pub column_names: &'a Vec<String>,
+}
+
+impl<'a> QueryStrategy<HyperLogLog> for CardinalityQueryStrategy<'a> {
+    fn audit_query(&self) -> String {
+        self.sentence.to_string()
+    }
+    // Collector doesn't require scoring, so `filter_mode` changes nothing.
+    fn execute(
+        &self,
+        searcher: &Searcher,
+        deleted_row_ids: Option<Arc<RoaringTreemap>>,
+        row_id_mapping: Option<Arc<RowIdMappingCache>>,
+        _filter_mode: bool,
+    ) -> Result<HyperLogLog, IndexSearcherError> {
+        let schema: Schema = searcher.index().schema();
+
+        let fields: Vec<Field> = bm25_text_fields(&schema, self.column_names)?;
+
+        let mut cardinality_collector: CardinalityCollector =
+            CardinalityCollector::with_field(self.field_name.to_string());
+
+        if *self.query_with_filter {
+            let mut alive_bitmap: RoaringTreemap = RoaringTreemap::new();
+            alive_bitmap.extend(self.alived_ids);
+            cardinality_collector = cardinality_collector.with_alive(Arc::new(alive_bitmap));
+        }
+
+        if *self.query_with_id_range {
+            cardinality_collector =
+                cardinality_collector.with_range((*self.start_id, *self.end_id));
+        }
+
+        if let Some(deleted_row_ids) = deleted_row_ids {
+            cardinality_collector = cardinality_collector.with_deleted(deleted_row_ids);
+        }
+        if let Some(row_id_mapping) = row_id_mapping {
+            cardinality_collector = cardinality_collector.with_row_id_mapping(row_id_mapping);
+        }
+
+        let query_parser: QueryParser = QueryParser::for_index(searcher.index(), fields);
+        let text_query: Box<dyn Query> = {
+            TRACE_SPAN!("parse_query");
+            query_parser.parse_query(self.sentence).map_err(|e: QueryParserError| {
+                ERROR!(function:"CardinalityQueryStrategy", "Error when parse: {}. {}", self.sentence, e);
+                IndexSearcherError::QueryParserError(e.to_string())
+            })?
+        };
+        check_query_cost(text_query.as_ref())?;
+
+        searcher
+            .search(&text_query, &cardinality_collector)
+            .map_err(|e: TantivyError| {
+                ERROR!(function:"CardinalityQueryStrategy", "Error when execute: {}. {}", self.sentence, e);
+                IndexSearcherError::TantivyError(e)
+            })
+    }
+}
+
+/// Execute BM25 query and estimate percentiles of a numeric fast field in matched docs.
+///
+/// Params:
+/// - `sentence`: Sentence need to be parsed and query.
+/// - `field_name`: Numeric fast field whose percentiles are estimated.
+/// - `alived_ids`: Represent row_ids who are alived.
+/// - `query_with_filter`: Whether collect row_ids with `alived_ids`
+/// - `query_with_id_range`: Whether collect row_ids with `[start_id, end_id)`
+/// - `start_id`: The start of row_ids range
+/// - `end_id`: The end of row_ids range
+///
+pub struct PercentilesQueryStrategy<'a> {
+    pub sentence: &'a str,
+    pub field_name: &'a str,
+    pub alived_ids: &'a Vec<u64>,
+    pub query_with_filter: &'a bool,
+    pub query_with_id_range: &'a bool,
+    pub start_id: &'a u64,
+    pub end_id: &'a u64,
+    pub column_names: &'a Vec<String>,
+}
+
+impl<'a> QueryStrategy<TDigest> for PercentilesQueryStrategy<'a> {
+    fn audit_query(&self) -> String {
+        self.sentence.to_string()
+    }
+    // Collector doesn't require scoring, so `filter_mode` changes nothing.
+    fn execute(
+        &self,
+        searcher: &Searcher,
+        deleted_row_ids: Option<Arc<RoaringTreemap>>,
+        row_id_mapping: Option<Arc<RowIdMappingCache>>,
+        _filter_mode: bool,
+    ) -> Result<TDigest, IndexSearcherError> {
+        let schema: Schema = searcher.index().schema();
+
+        let fields: Vec<Field> = bm25_text_fields(&schema, self.column_names)?;
+
+        let mut percentiles_collector: PercentilesCollector =
+            PercentilesCollector::with_field(self.field_name.to_string());
+
+        if *self.query_with_filter {
+            let mut alive_bitmap: RoaringTreemap = RoaringTreemap::new();
+            alive_bitmap.extend(self.alived_ids);
+            percentiles_collector = percentiles_collector.with_alive(Arc::new(alive_bitmap));
+        }
+
+        if *self.query_with_id_range {
+            percentiles_collector =
+                percentiles_collector.with_range((*self.start_id, *self.end_id));
+        }
+
+        if let Some(deleted_row_ids) = deleted_row_ids {
+            percentiles_collector = percentiles_collector.with_deleted(deleted_row_ids);
+        }
+        if let Some(row_id_mapping) = row_id_mapping {
+            percentiles_collector = percentiles_collector.with_row_id_mapping(row_id_mapping);
+        }
+
+        let query_parser: QueryParser = QueryParser::for_index(searcher.index(), fields);
+        let text_query: Box<dyn Query> = {
+            TRACE_SPAN!("parse_query");
+            query_parser.parse_query(self.sentence).map_err(|e: QueryParserError| {
+                ERROR!(function:"PercentilesQueryStrategy", "Error when parse: {}. {}", self.sentence, e);
+                IndexSearcherError::QueryParserError(e.to_string())
+            })?
+        };
+        check_query_cost(text_query.as_ref())?;
+
+        searcher
+            .search(&text_query, &percentiles_collector)
+            .map_err(|e: TantivyError| {
+                ERROR!(function:"PercentilesQueryStrategy", "Error when execute: {}. {}", self.sentence, e);
+                IndexSearcherError::TantivyError(e)
+            })
+    }
+}
+
+pub struct QueryExecutor<'a, T> {
+    strategy: &'a dyn QueryStrategy<T>,
+    deleted_row_ids: Option<Arc<RoaringTreemap>>,
+    row_id_mapping: Option<Arc<RowIdMappingCache>>,
+    // Index path of slow query log and query audit log.
+    index_path: String,
+    slow_query_threshold: Option<Duration>,
+    filter_mode: bool,
+    result_cache: Option<Arc<QueryResultCache>>,
+    principal_filter: Option<PrincipalFilter>,
+}
+
+impl<'a, T: QueryHits> QueryExecutor<'a, T> {
+    pub fn new(strategy: &'a dyn QueryStrategy<T>) -> Self {
+        QueryExecutor {
+            strategy,
+            deleted_row_ids: None,
+            row_id_mapping: None,
+            index_path: String::new(),
+            slow_query_threshold: None,
+            filter_mode: false,
+            result_cache: None,
+            principal_filter: None,
+        }
+    }
+    // Exclude lightweight deleted row_ids from query results.
+    pub fn with_deleted(mut self, deleted_row_ids: Option<Arc<RoaringTreemap>>) -> Self {
+        self.deleted_row_ids = deleted_row_ids;
+        self
+    }
+    // Read row_ids from DocId and row_id mapping warmed by index reader.
+    pub fn with_row_id_mapping(mut self, row_id_mapping: Option<Arc<RowIdMappingCache>>) -> Self {
+        self.row_id_mapping = row_id_mapping;
+        self
+    }
+    // Log queries of `index_path` slower than `threshold`, `None` disables it.
+    pub fn with_slow_query_threshold(
+        mut self,
+        index_path: &str,
+        threshold: Option<Duration>,
+    ) -> Self {
+        self.index_path = index_path.to_string();
+        self.slow_query_threshold = threshold;
+        self
+    }
+    // Skip scoring when only matched docs are needed, all results get the same score.
+    pub fn with_filter_mode(mut self, filter_mode: bool) -> Self {
+        self.filter_mode = filter_mode;
+        self
+    }
+    // Read and persist bitmap query results in `result_cache`, `None` disables it.
+    pub fn with_result_cache(mut self, result_cache: Option<Arc<QueryResultCache>>) -> Self {
+        self.result_cache = result_cache;
+        self
+    }
+    // AND security filter of the principal searching into results, `None` searches unfiltered.
+    pub fn with_principal_filter(mut self, principal_filter: Option<PrincipalFilter>) -> Self {
+        self.principal_filter = principal_filter;
+        self
+    }
+    // Row ids excluded from results over `searcher`, lightweight deleted ones and ones hidden
+    // from the principal searching.
+    fn excluded_row_ids(
+        &self,
+        searcher: &Searcher,
+    ) -> Result<Option<Arc<RoaringTreemap>>, IndexSearcherError> {
+        match &self.principal_filter {
+            Some(principal_filter) => principal_filter
+                .excluded_row_ids(searcher, self.deleted_row_ids.as_ref())
+                .map(Some)
+                .map_err(|e| {
+                    ERROR!(function:"QueryExecutor", "{}", e);
+                    IndexSearcherError::InternalError(e)
+                }),
+            None => Ok(self.deleted_row_ids.clone()),
+        }
+    }
+    pub fn execute(&self, searcher: &Searcher) -> Result<T, IndexSearcherError> {
+        let start = Instant::now();
+        let excluded_row_ids: Option<Arc<RoaringTreemap>> = self.excluded_row_ids(searcher)?;
+        let result: T = self.execute_excluding(searcher, excluded_row_ids)?;
+        self.audit(&result, start.elapsed());
+        Ok(result)
+    }
+    fn execute_excluding(
+        &self,
+        searcher: &Searcher,
+        excluded_row_ids: Option<Arc<RoaringTreemap>>,
+    ) -> Result<T, IndexSearcherError> {
+        TRACE_SPAN!("collect");
+        let start = Instant::now();
+        let result = self.strategy.execute(
+            searcher,
+            excluded_row_ids,
+            self.row_id_mapping.clone(),
+            self.filter_mode,
+        );
+        let elapsed = start.elapsed();
+        METRICS.record_query(elapsed, result.is_ok());
+        if let Some(threshold) = &self.slow_query_threshold {
+            if elapsed >= *threshold {
+                WARNING!(function:"QueryExecutor", "Slow query took {:?}, threshold:{:?}, index_path:[{}]", elapsed, threshold, self.index_path);
+            }
+        }
+        result
+    }
+    // Record sampled successful searches into query audit log.
+    fn audit(&self, result: &T, elapsed: Duration) {
+        if !QUERY_AUDIT.sampled() {
+            return;
+        }
+        let principal: String = match &self.principal_filter {
+            Some(principal_filter) => principal_filter.principal.clone(),
+            None => String::new(),
+        };
+        let record: QueryAuditRecord = QueryAuditRecord::new(
+            &self.index_path,
+            &principal,
+            self.strategy.audit_query(),
+            result.hits(),
+            elapsed,
+        );
+        if let Err(e) = QUERY_AUDIT.record(&record) {
+            WARNING!(function:"QueryExecutor", "{}", e);
+        }
+    }
+}
+
+impl<'a> QueryExecutor<'a, Arc<RoaringBitmap>> {
+    // Identical bitmap queries over the same segments and deletes run once,
+    // callers arriving while one runs share its result.
+    pub fn execute_single_flight(
+        &self,
+        searcher: &Searcher,
+    ) -> Result<Arc<RoaringBitmap>, IndexSearcherError> {
+        let start = Instant::now();
+        let result: Arc<RoaringBitmap> = self.execute_shared(searcher)?;
+        // Bitmaps whose size can't be estimated beforehand are refused before they're returned.
+        QUERY_COST_GUARD
+            .check_bitmap_size(result.len())
+            .map_err(query_too_expensive)?;
+        // Searches answered by result cache or another flight are audited too.
+        self.audit(&result, start.elapsed());
+        Ok(result)
+    }
+
+    fn execute_shared(
+        &self,
+        searcher: &Searcher,
+    ) -> Result<Arc<RoaringBitmap>, IndexSearcherError> {
+        let excluded_row_ids: Option<Arc<RoaringTreemap>> = self.excluded_row_ids(searcher)?;
+        let query_key: QueryKey = match self.strategy.query_key(searcher)? {
+            Some(query_key) => query_key,
+            None => return self.execute_excluding(searcher, excluded_row_ids),
+        };
+        let flight_key: BitmapFlightKey =
+            BitmapFlightKey::new(searcher, excluded_row_ids.as_ref(), query_key.clone());
+        let (result, shared) = BITMAP_QUERY_FLIGHTS.run(flight_key, || {
+            self.execute_result_cached(searcher, &query_key, excluded_row_ids.clone())
+        });
+        if shared {
+            METRICS.shared_queries.fetch_add(1, Ordering::Relaxed);
+        }
+        result
+    }
+
+    // Lightweight deletes and security filters aren't persisted,
+    // so only results without excluded row ids are cached.
+    fn execute_result_cached(
+        &self,
+        searcher: &Searcher,
+        query_key: &QueryKey,
+        excluded_row_ids: Option<Arc<RoaringTreemap>>,
+    ) -> Result<Arc<RoaringBitmap>, IndexSearcherError> {
+        let result_cache: &QueryResultCache = match (&self.result_cache, &excluded_row_ids) {
+            (Some(result_cache), None) => result_cache,
+            _ => return self.execute_excluding(searcher, excluded_row_ids),
+        };
+        if let Some(bitmap) = result_cache.get(searcher, query_key) {
+            METRICS
+                .query_result_cache_hits
+                .fetch_add(1, Ordering::Relaxed);
+            return Ok(Arc::new(bitmap));
+        }
+        let result: Arc<RoaringBitmap> = self.execute_excluding(searcher, None)?;
+        if let Err(e) = result_cache.put(searcher, query_key, &result) {
+            WARNING!(function:"QueryExecutor", "{}", e);
+        }
+        Ok(result)
     }
 }
@@ -1,5 +1,6 @@
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
     use std::sync::Arc;
 
     use roaring::RoaringBitmap;
@@ -9,10 +10,12 @@ mod tests {
     use crate::{
         common::tests::create_3column_names,
         common::tests::index_3column_docs_with_threads_merge,
-        ffi::RowIdWithScore,
+        ffi::{GroupTopDoc, RowIdWithScore},
         search::implements::strategy::query_strategy::{
-            BM25QueryStrategy, BM25QueryStrategy64, ParserQueryStrategy, QueryExecutor,
-            RegexQueryStrategy, SingleTermQueryStrategy, TermSetQueryStrategy,
+            BM25QueryStrategy, BM25QueryStrategy64, GroupedBM25QueryStrategy,
+            OrderByFieldQueryStrategy, ParserQueryStrategy, QueryExecutor, QueryHits,
+            QueryStrategy, RegexQueryStrategy, SentenceQueryStrategy, SingleTermQueryStrategy,
+            TermSetQueryStrategy,
         },
     };
 
@@ -53,6 +56,89 @@ mod tests {
         assert_eq!(result.len(), 2);
     }
 
+    #[test]
+    fn test_query_audit_text() {
+        let terms: Vec<String> = vec!["ancient".to_string(), "balance".to_string()];
+        let terms_query = TermSetQueryStrategy {
+            terms: &terms,
+            column_name: "col1",
+        };
+        assert_eq!(
+            terms_query.audit_query(),
+            r#"col1 IN ["ancient", "balance"]"#
+        );
+        let regex_query = RegexQueryStrategy {
+            column_name: "col2",
+            pattern: "%ance%",
+        };
+        assert_eq!(regex_query.audit_query(), "col2:/%ance%/");
+
+        let bitmap: Arc<RoaringBitmap> = Arc::new(RoaringBitmap::from_iter([1u32, 5, 9]));
+        assert_eq!(bitmap.hits(), 3);
+        assert_eq!(Vec::<RowIdWithScore>::new().hits(), 0);
+    }
+
+    #[test]
+    fn test_bitmap_query_key() {
+        let temp_directory: TempDir = TempDir::new().unwrap();
+        let temp_directory_str: &str = temp_directory.path().to_str().unwrap();
+        let (index_reader, _) = index_3column_docs_with_threads_merge(temp_directory_str);
+        let searcher = index_reader.searcher();
+
+        // Case, whitespace and term order of disjunctions are normalized by analyzer.
+        let terms = [
+            "Balance".to_string(),
+            "ancient".to_string(),
+            "ANCIENT".to_string(),
+        ];
+        let term_set_key = TermSetQueryStrategy {
+            terms: &terms.to_vec(),
+            column_name: "col1",
+        }
+        .query_key(&searcher)
+        .unwrap()
+        .unwrap();
+        let sentence_key = SentenceQueryStrategy {
+            sentence: "  ancient   BALANCE ",
+            column_name: "col1",
+        }
+        .query_key(&searcher)
+        .unwrap()
+        .unwrap();
+        assert_eq!(term_set_key, sentence_key);
+        assert_eq!(term_set_key.fingerprint(), sentence_key.fingerprint());
+        let single_term_key = SingleTermQueryStrategy {
+            term: "Ancient",
+            column_name: "col1",
+        }
+        .query_key(&searcher)
+        .unwrap();
+        assert_ne!(single_term_key, Some(term_set_key));
+
+        // Regex patterns are case sensitive.
+        let regex_key = |pattern: &str| {
+            RegexQueryStrategy {
+                column_name: "col1",
+                pattern,
+            }
+            .query_key(&searcher)
+            .unwrap()
+        };
+        assert_eq!(regex_key("%pressio%"), regex_key("%pressio%"));
+        assert_ne!(regex_key("%pressio%"), regex_key("%Pressio%"));
+
+        // Identical queries executed one after another give the same result.
+        let regex_query = RegexQueryStrategy {
+            column_name: "col1",
+            pattern: "%pressio%",
+        };
+        let query_executor: QueryExecutor<'_, Arc<RoaringBitmap>> =
+            QueryExecutor::new(&regex_query);
+        let result = query_executor.execute_single_flight(&searcher).unwrap();
+        assert_eq!(result, query_executor.execute(&searcher).unwrap());
+        assert_eq!(result.len(), 1);
+    }
+
     #[test]
     fn test_regex_query_strategy() {
         let temp_directory: TempDir = TempDir::new().unwrap();
@@ -162,6 +248,11 @@ mod tests {
             end_id: &0,
             need_doc: &false,
             column_names: &vec![],
+            offset: &0,
+            search_after: &None,
+            field_boosts: &HashMap::new(),
+            similarity: &None,
+            diversify: &None,
         };
         let query_executor: QueryExecutor<'_, Vec<RowIdWithScore>> =
             QueryExecutor::new(&bm25_strategy);
@@ -192,6 +283,11 @@ mod tests {
             end_id: &0,
             need_doc: &false,
             column_names: &create_3column_names(),
+            offset: &0,
+            search_after: &None,
+            field_boosts: &HashMap::new(),
+            similarity: &None,
+            diversify: &None,
         };
         let query_executor: QueryExecutor<'_, Vec<RowIdWithScore>> =
             QueryExecutor::new(&bm25_strategy);
@@ -204,4 +300,163 @@ mod tests {
         assert_eq!(result[1].row_id, 0);
         assert!(result[1].score <= 1.6);
     }
+
+    #[test]
+    fn test_bm25_query_strategy64_filter_mode() {
+        let temp_directory: TempDir = TempDir::new().unwrap();
+        let temp_directory_str: &str = temp_directory.path().to_str().unwrap();
+        let (index_reader, _) = index_3column_docs_with_threads_merge(temp_directory_str);
+        let search = |topk: u32, filter_mode: bool| {
+            let bm25_strategy: BM25QueryStrategy64<'_> = BM25QueryStrategy64 {
+                sentence: "Ancient philosophies",
+                topk: &topk,
+                query_with_filter: &false,
+                alived_ids: &vec![],
+                query_with_id_range: &false,
+                start_id: &0,
+                end_id: &0,
+                need_doc: &false,
+                column_names: &vec![],
+                offset: &0,
+                search_after: &None,
+                field_boosts: &HashMap::new(),
+                similarity: &None,
+                diversify: &None,
+            };
+            QueryExecutor::new(&bm25_strategy)
+                .with_filter_mode(filter_mode)
+                .execute(&index_reader.searcher())
+                .unwrap()
+        };
+
+        let scored: Vec<RowIdWithScore> = search(10, false);
+        assert_eq!(scored[0].row_id, 4);
+        assert!(scored[0].score > scored[1].score);
+
+        // Same matched docs, but all of them have a constant score.
+        let filtered: Vec<RowIdWithScore> = search(10, true);
+        let mut row_ids: Vec<u64> = filtered.iter().map(|doc| doc.row_id).collect();
+        row_ids.sort();
+        assert_eq!(row_ids, vec![0, 4]);
+        assert!(filtered.iter().all(|doc| doc.score == 1.0));
+        assert_eq!(search(1, true).len(), 1);
+    }
+
+    #[test]
+    fn test_bm25_query_strategy64_pagination() {
+        let temp_directory: TempDir = TempDir::new().unwrap();
+        let temp_directory_str: &str = temp_directory.path().to_str().unwrap();
+        let (index_reader, _) = index_3column_docs_with_threads_merge(temp_directory_str);
+        let search_page = |offset: u32, search_after: Option<(f32, u64)>| {
+            let bm25_strategy: BM25QueryStrategy64<'_> = BM25QueryStrategy64 {
+                sentence: "Literary inventions capture philosophical masterpieces.",
+                topk: &1,
+                query_with_filter: &false,
+                alived_ids: &vec![],
+                query_with_id_range: &false,
+                start_id: &0,
+                end_id: &0,
+                need_doc: &false,
+                column_names: &vec![],
+                offset: &offset,
+                search_after: &search_after,
+                field_boosts: &HashMap::new(),
+                similarity: &None,
+                diversify: &None,
+            };
+            QueryExecutor::new(&bm25_strategy)
+                .execute(&index_reader.searcher())
+                .unwrap()
+        };
+
+        let first_page: Vec<RowIdWithScore> = search_page(0, None);
+        assert_eq!(first_page.len(), 1);
+        assert_eq!(first_page[0].row_id, 2);
+
+        // Offset and search_after cursor should both return the second page.
+        let offset_page: Vec<RowIdWithScore> = search_page(1, None);
+        let cursor_page: Vec<RowIdWithScore> =
+            search_page(0, Some((first_page[0].score, first_page[0].row_id)));
+        assert_eq!(offset_page.len(), 1);
+        assert_eq!(offset_page[0].row_id, 0);
+        assert_eq!(cursor_page.len(), 1);
+        assert_eq!(cursor_page[0].row_id, offset_page[0].row_id);
+    }
+
+    #[test]
+    fn test_order_by_field_query_strategy() {
+        let temp_directory: TempDir = TempDir::new().unwrap();
+        let temp_directory_str: &str = temp_directory.path().to_str().unwrap();
+        let (index_reader, _) = index_3column_docs_with_threads_merge(temp_directory_str);
+        let search_ordered = |order_field: &str, ascending: bool| {
+            let order_by_field_strategy: OrderByFieldQueryStrategy<'_> =
+                OrderByFieldQueryStrategy {
+                    sentence: "Literary inventions capture philosophical masterpieces.",
+                    topk: &10,
+                    order_field,
+                    ascending: &ascending,
+                    query_with_filter: &false,
+                    alived_ids: &vec![],
+                    query_with_id_range: &false,
+                    start_id: &0,
+                    end_id: &0,
+                    need_doc: &false,
+                    column_names: &vec![],
+                };
+            QueryExecutor::new(&order_by_field_strategy).execute(&index_reader.searcher())
+        };
+
+        let descending: Vec<u64> = search_ordered("row_id", false)
+            .unwrap()
+            .iter()
+            .map(|item| item.row_id)
+            .collect();
+        let mut ascending: Vec<u64> = search_ordered("row_id", true)
+            .unwrap()
+            .iter()
+            .map(|item| item.row_id)
+            .collect();
+        assert!(descending.len() > 1);
+        assert!(descending.windows(2).all(|pair| pair[0] > pair[1]));
+        ascending.reverse();
+        assert_eq!(ascending, descending);
+
+        // Text field can't be used to order results.
+        assert!(search_ordered("col1", false).is_err());
+    }
+
+    #[test]
+    fn test_grouped_bm25_query_strategy() {
+        let temp_directory: TempDir = TempDir::new().unwrap();
+        let temp_directory_str: &str = temp_directory.path().to_str().unwrap();
+        let (index_reader, _) = index_3column_docs_with_threads_merge(temp_directory_str);
+        let search_grouped = |group_field: &str, topk: u32| {
+            let grouped_strategy: GroupedBM25QueryStrategy<'_> = GroupedBM25QueryStrategy {
+                sentence: "Literary inventions capture philosophical masterpieces.",
+                topk: &topk,
+                group_field,
+                query_with_filter: &false,
+                alived_ids: &vec![],
+                query_with_id_range: &false,
+                start_id: &0,
+                end_id: &0,
+                need_doc: &false,
+                column_names: &vec![],
+            };
+            QueryExecutor::new(&grouped_strategy).execute(&index_reader.searcher())
+        };
+
+        // Every row_id is a group with a single hit, ranked same as BM25.
+        let groups: Vec<GroupTopDoc> = search_grouped("row_id", 10).unwrap();
+        assert!(groups.len() > 1);
+        assert_eq!(groups[0].group_key, 2);
+        for group in &groups {
+            assert_eq!(group.hit_count, 1);
+            assert_eq!(group.group_key, group.top_doc.row_id);
+        }
+        assert_eq!(search_grouped("row_id", 1).unwrap().len(), 1);
+
+        // Text field can't be used to group results.
+        assert!(search_grouped("col1", 10).is_err());
+    }
 }
@@ -0,0 +1,327 @@
+use tantivy::fieldnorm::FieldNormReader;
+use tantivy::postings::{Postings, SegmentPostings};
+use tantivy::query::{
+    BooleanQuery, EmptyScorer, EnableScoring, Explanation, Query, Scorer, TermQuery, Weight,
+};
+use tantivy::schema::IndexRecordOption;
+use tantivy::{DocId, DocSet, Score, SegmentReader, TantivyError, Term};
+
+/// BM25 parameters, tantivy always scores with the default ones.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bm25Params {
+    /// Term frequency saturation.
+    pub k1: Score,
+    /// Document length normalization, 0 disables it.
+    pub b: Score,
+}
+
+impl Default for Bm25Params {
+    fn default() -> Self {
+        Bm25Params { k1: 1.2, b: 0.75 }
+    }
+}
+
+impl Bm25Params {
+    pub fn new(k1: Score, b: Score) -> Result<Self, String> {
+        if !k1.is_finite() || k1 < 0.0 {
+            return Err(format!(
+                "bm25 k1 should be a non-negative number, got {}",
+                k1
+            ));
+        }
+        if !(0.0..=1.0).contains(&b) {
+            return Err(format!("bm25 b should be in [0, 1], got {}", b));
+        }
+        Ok(Bm25Params { k1, b })
+    }
+}
+
+/// How matched terms of ranked search are scored.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Similarity {
+    /// BM25 with given parameters.
+    Bm25(Bm25Params),
+    /// `idf * term_freq`, term frequency isn't saturated and docs aren't normalized by length,
+    /// e.g. for log lines whose length says nothing about relevance.
+    TfOnly,
+    /// Each matched term scores 1, docs are ranked by number of matched terms.
+    Constant,
+}
+
+impl Similarity {
+    /// Similarity of `name`, one of `bm25`, `tf_only` and `constant`.
+    /// BM25 uses `bm25_params`, which are only allowed for it.
+    pub fn from_name(name: &str, bm25_params: Option<Bm25Params>) -> Result<Self, String> {
+        match (name, bm25_params) {
+            ("bm25", bm25_params) => Ok(Similarity::Bm25(bm25_params.unwrap_or_default())),
+            ("tf_only" | "constant", Some(_)) => Err(format!(
+                "bm25 parameters can't be used by similarity {}",
+                name
+            )),
+            ("tf_only", None) => Ok(Similarity::TfOnly),
+            ("constant", None) => Ok(Similarity::Constant),
+            _ => Err(format!("Unknown similarity: {}", name)),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Similarity::Bm25(_) => "bm25",
+            Similarity::TfOnly => "tf_only",
+            Similarity::Constant => "constant",
+        }
+    }
+
+    // Score of a doc containing `term_freq` terms, `weight` includes boost and idf.
+    fn score(
+        &self,
+        weight: Score,
+        term_freq: Score,
+        fieldnorm: Score,
+        average_fieldnorm: Score,
+    ) -> Score {
+        match self {
+            Similarity::Bm25(params) => {
+                let norm: Score =
+                    params.k1 * (1.0 - params.b + params.b * fieldnorm / average_fieldnorm);
+                weight * (1.0 + params.k1) * term_freq / (term_freq + norm)
+            }
+            Similarity::TfOnly => weight * term_freq,
+            Similarity::Constant => weight,
+        }
+    }
+}
+
+/// Rewrite term queries of `query` to be scored by `similarity`, boolean queries are rewritten
+/// recursively. Other queries, e.g. phrases and boosted fields, keep tantivy BM25 scoring.
+pub fn with_similarity(query: &dyn Query, similarity: Similarity) -> Box<dyn Query> {
+    if let Some(term_query) = query.downcast_ref::<TermQuery>() {
+        return Box::new(SimilarityTermQuery::new(
+            term_query.term().clone(),
+            similarity,
+        ));
+    }
+    if let Some(boolean_query) = query.downcast_ref::<BooleanQuery>() {
+        let clauses = boolean_query
+            .clauses()
+            .iter()
+            .map(|(occur, subquery)| (*occur, with_similarity(subquery.as_ref(), similarity)))
+            .collect();
+        return Box::new(BooleanQuery::new(clauses));
+    }
+    query.box_clone()
+}
+
+/// Term query scored by a given similarity.
+#[derive(Debug, Clone)]
+pub struct SimilarityTermQuery {
+    term: Term,
+    similarity: Similarity,
+}
+
+impl SimilarityTermQuery {
+    pub fn new(term: Term, similarity: Similarity) -> Self {
+        SimilarityTermQuery { term, similarity }
+    }
+}
+
+impl Query for SimilarityTermQuery {
+    fn weight(&self, enable_scoring: EnableScoring<'_>) -> tantivy::Result<Box<dyn Weight>> {
+        let statistics_provider = match enable_scoring {
+            EnableScoring::Enabled {
+                statistics_provider,
+                ..
+            } => statistics_provider,
+            EnableScoring::Disabled { .. } => {
+                return TermQuery::new(self.term.clone(), IndexRecordOption::Basic)
+                    .weight(enable_scoring);
+            }
+        };
+        let total_num_docs: u64 = statistics_provider.total_num_docs()?;
+        let total_num_tokens: u64 = statistics_provider.total_num_tokens(self.term.field())?;
+        let doc_freq: u64 = statistics_provider.doc_freq(&self.term)?;
+        let idf: Score = (1.0
+            + (total_num_docs as Score - doc_freq as Score + 0.5) / (doc_freq as Score + 0.5))
+            .ln();
+        let average_fieldnorm: Score = match total_num_docs {
+            0 => 1.0,
+            _ => (total_num_tokens as Score / total_num_docs as Score).max(1.0),
+        };
+        let idf: Score = match self.similarity {
+            Similarity::Constant => 1.0,
+            _ => idf,
+        };
+        Ok(Box::new(SimilarityTermWeight {
+            term: self.term.clone(),
+            similarity: self.similarity,
+            idf,
+            average_fieldnorm,
+        }))
+    }
+
+    fn query_terms<'a>(&'a self, visitor: &mut dyn FnMut(&'a Term, bool)) {
+        visitor(&self.term, false);
+    }
+}
+
+struct SimilarityTermWeight {
+    term: Term,
+    similarity: Similarity,
+    idf: Score,
+    average_fieldnorm: Score,
+}
+
+impl Weight for SimilarityTermWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> tantivy::Result<Box<dyn Scorer>> {
+        let postings: Option<SegmentPostings> = reader
+            .inverted_index(self.term.field())?
+            .read_postings(&self.term, IndexRecordOption::WithFreqs)?;
+        match postings {
+            Some(postings) => Ok(Box::new(SimilarityTermScorer {
+                postings,
+                fieldnorm_reader: reader.get_fieldnorms_reader(self.term.field())?,
+                similarity: self.similarity,
+                weight: boost * self.idf,
+                average_fieldnorm: self.average_fieldnorm,
+            })),
+            None => Ok(Box::new(EmptyScorer)),
+        }
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> tantivy::Result<Explanation> {
+        let mut scorer: Box<dyn Scorer> = self.scorer(reader, 1.0)?;
+        if scorer.seek(doc) != doc {
+            return Err(TantivyError::InvalidArgument(format!(
+                "Document #({}) does not match",
+                doc
+            )));
+        }
+        let mut explanation: Explanation = Explanation::new(
+            format!("SimilarityTermQuery, {:?}", self.similarity),
+            scorer.score(),
+        );
+        explanation.add_const("idf", self.idf);
+        explanation.add_const("average_fieldnorm", self.average_fieldnorm);
+        Ok(explanation)
+    }
+}
+
+struct SimilarityTermScorer {
+    postings: SegmentPostings,
+    fieldnorm_reader: FieldNormReader,
+    similarity: Similarity,
+    // boost * idf
+    weight: Score,
+    average_fieldnorm: Score,
+}
+
+impl DocSet for SimilarityTermScorer {
+    fn advance(&mut self) -> DocId {
+        self.postings.advance()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        self.postings.seek(target)
+    }
+
+    fn doc(&self) -> DocId {
+        self.postings.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.postings.size_hint()
+    }
+}
+
+impl Scorer for SimilarityTermScorer {
+    fn score(&mut self) -> Score {
+        let term_freq: Score = self.postings.term_freq() as Score;
+        let fieldnorm: Score = self.fieldnorm_reader.fieldnorm(self.doc()) as Score;
+        self.similarity
+            .score(self.weight, term_freq, fieldnorm, self.average_fieldnorm)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::tests::index_3column_docs_with_threads_merge;
+    use tantivy::collector::TopDocs;
+    use tantivy::query::QueryParser;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_similarity_query() {
+        assert!(Bm25Params::new(-1.0, 0.5).is_err());
+        assert!(Bm25Params::new(1.2, 1.5).is_err());
+
+        let temp_directory: TempDir = TempDir::new().unwrap();
+        let temp_directory_str: &str = temp_directory.path().to_str().unwrap();
+        let (index_reader, schema) = index_3column_docs_with_threads_merge(temp_directory_str);
+        let searcher = index_reader.searcher();
+        let query_parser =
+            QueryParser::for_index(searcher.index(), vec![schema.get_field("col1").unwrap()]);
+        let query = query_parser.parse_query("Ancient philosophies").unwrap();
+        let top_scores = |query: &dyn Query| -> Vec<Score> {
+            searcher
+                .search(query, &TopDocs::with_limit(10))
+                .unwrap()
+                .into_iter()
+                .map(|(score, _)| score)
+                .collect()
+        };
+
+        // Default parameters score same as tantivy.
+        let scores = top_scores(query.as_ref());
+        let default_scores = top_scores(
+            with_similarity(query.as_ref(), Similarity::Bm25(Bm25Params::default())).as_ref(),
+        );
+        assert_eq!(scores.len(), default_scores.len());
+        for (score, default_score) in scores.iter().zip(default_scores.iter()) {
+            assert!((score - default_score).abs() < 0.05);
+        }
+
+        // `k1` 0 ignores term frequency and length, every matched term scores its idf.
+        let idf_similarity = Similarity::Bm25(Bm25Params::new(0.0, 0.0).unwrap());
+        let idf_scores = top_scores(with_similarity(query.as_ref(), idf_similarity).as_ref());
+        assert_eq!(idf_scores.len(), 2);
+        assert!(idf_scores[0] > idf_scores[1]);
+        assert_ne!(idf_scores, scores);
+
+        // Each matched term appears once, so tf only scores are idf scores.
+        let tf_only_scores =
+            top_scores(with_similarity(query.as_ref(), Similarity::TfOnly).as_ref());
+        assert_eq!(tf_only_scores.len(), 2);
+        for (tf_only_score, idf_score) in tf_only_scores.iter().zip(idf_scores.iter()) {
+            assert!((tf_only_score - idf_score).abs() < 1e-4);
+        }
+
+        // Constant scores count matched terms.
+        let constant_scores =
+            top_scores(with_similarity(query.as_ref(), Similarity::Constant).as_ref());
+        assert!(constant_scores.iter().all(|score| *score == 1.0));
+    }
+
+    #[test]
+    fn test_similarity_from_name() {
+        assert_eq!(
+            Similarity::from_name("bm25", None),
+            Ok(Similarity::Bm25(Bm25Params::default()))
+        );
+        let params = Bm25Params::new(2.0, 0.5).unwrap();
+        assert_eq!(
+            Similarity::from_name("bm25", Some(params)),
+            Ok(Similarity::Bm25(params))
+        );
+        assert_eq!(
+            Similarity::from_name("tf_only", None),
+            Ok(Similarity::TfOnly)
+        );
+        assert_eq!(
+            Similarity::from_name("constant", None),
+            Ok(Similarity::Constant)
+        );
+        assert!(Similarity::from_name("constant", Some(params)).is_err());
+        assert!(Similarity::from_name("dfr", None).is_err());
+    }
+}
@@ -0,0 +1,197 @@
+use tantivy::postings::{Postings, SegmentPostings};
+use tantivy::query::{
+    BooleanQuery, EmptyScorer, EnableScoring, Explanation, Occur, Query, Scorer, Weight,
+};
+use tantivy::schema::{Field, IndexRecordOption};
+use tantivy::{DocId, DocSet, Score, SegmentReader, TantivyError, Term};
+
+use crate::tokenizer::sparse_vector_format::SparseVectorFormat;
+
+/// Matches docs having any query term in a sparse vector column, scored by dot product of
+/// query weights and doc weights. Doc weights are term frequencies of posting lists, so only
+/// postings of query terms are read.
+#[derive(Debug, Clone)]
+pub struct SparseVectorQuery {
+    terms: Vec<(Term, Score)>,
+    format: SparseVectorFormat,
+}
+
+impl SparseVectorQuery {
+    pub fn new(field: Field, weights: &[(String, f32)], format: SparseVectorFormat) -> Self {
+        let terms: Vec<(Term, Score)> = weights
+            .iter()
+            .filter(|(_, weight)| *weight > 0.0)
+            .map(|(term, weight)| (Term::from_field_text(field, term), *weight))
+            .collect();
+        SparseVectorQuery { terms, format }
+    }
+}
+
+impl Query for SparseVectorQuery {
+    fn weight(&self, enable_scoring: EnableScoring<'_>) -> tantivy::Result<Box<dyn Weight>> {
+        // Scores of should clauses are summed, that's the dot product.
+        let clauses: Vec<(Occur, Box<dyn Query>)> = self
+            .terms
+            .iter()
+            .map(|(term, weight)| {
+                let term_query: Box<dyn Query> = Box::new(SparseTermQuery {
+                    term: term.clone(),
+                    weight: *weight,
+                    format: self.format,
+                });
+                (Occur::Should, term_query)
+            })
+            .collect();
+        BooleanQuery::new(clauses).weight(enable_scoring)
+    }
+
+    fn query_terms<'a>(&'a self, visitor: &mut dyn FnMut(&'a Term, bool)) {
+        for (term, _) in &self.terms {
+            visitor(term, false);
+        }
+    }
+}
+
+// One term of a sparse vector query, scored by `weight` times weight of the doc.
+#[derive(Debug, Clone)]
+struct SparseTermQuery {
+    term: Term,
+    weight: Score,
+    format: SparseVectorFormat,
+}
+
+impl Query for SparseTermQuery {
+    fn weight(&self, _enable_scoring: EnableScoring<'_>) -> tantivy::Result<Box<dyn Weight>> {
+        Ok(Box::new(self.clone()))
+    }
+
+    fn query_terms<'a>(&'a self, visitor: &mut dyn FnMut(&'a Term, bool)) {
+        visitor(&self.term, false);
+    }
+}
+
+impl Weight for SparseTermQuery {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> tantivy::Result<Box<dyn Scorer>> {
+        let postings: Option<SegmentPostings> = reader
+            .inverted_index(self.term.field())?
+            .read_postings(&self.term, IndexRecordOption::WithFreqs)?;
+        match postings {
+            Some(postings) => Ok(Box::new(SparseTermScorer {
+                postings,
+                weight: boost * self.weight,
+                format: self.format,
+            })),
+            None => Ok(Box::new(EmptyScorer)),
+        }
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> tantivy::Result<Explanation> {
+        let mut scorer: Box<dyn Scorer> = self.scorer(reader, 1.0)?;
+        if scorer.seek(doc) != doc {
+            return Err(TantivyError::InvalidArgument(format!(
+                "Document #({}) does not match",
+                doc
+            )));
+        }
+        let mut explanation: Explanation = Explanation::new("SparseTermQuery", scorer.score());
+        explanation.add_const("query_weight", self.weight);
+        Ok(explanation)
+    }
+}
+
+struct SparseTermScorer {
+    postings: SegmentPostings,
+    // boost * query weight
+    weight: Score,
+    format: SparseVectorFormat,
+}
+
+impl DocSet for SparseTermScorer {
+    fn advance(&mut self) -> DocId {
+        self.postings.advance()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        self.postings.seek(target)
+    }
+
+    fn doc(&self) -> DocId {
+        self.postings.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.postings.size_hint()
+    }
+}
+
+impl Scorer for SparseTermScorer {
+    fn score(&mut self) -> Score {
+        self.weight * self.format.weight(self.postings.term_freq())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::sparse_vector_format::SPARSE_VECTOR_TOKENIZER;
+    use tantivy::collector::TopDocs;
+    use tantivy::schema::{Schema, TextFieldIndexing, TextOptions};
+    use tantivy::tokenizer::{RawTokenizer, TextAnalyzer};
+    use tantivy::{Index, TantivyDocument};
+
+    #[test]
+    fn test_sparse_vector_query() {
+        let mut schema_builder = Schema::builder();
+        let terms = schema_builder.add_text_field(
+            "terms",
+            TextOptions::default().set_indexing_options(
+                TextFieldIndexing::default()
+                    .set_tokenizer(SPARSE_VECTOR_TOKENIZER)
+                    .set_index_option(IndexRecordOption::WithFreqs)
+                    .set_fieldnorms(false),
+            ),
+        );
+        let index = Index::create_in_ram(schema_builder.build());
+        index.tokenizers().register(
+            SPARSE_VECTOR_TOKENIZER,
+            TextAnalyzer::from(RawTokenizer::default()),
+        );
+        let format = SparseVectorFormat { scale: 100 };
+        let mut writer = index.writer_with_num_threads(1, 15_000_000).unwrap();
+        for weights in [
+            r#"{"apple": 1.5, "fruit": 0.5}"#,
+            r#"{"apple": 0.2, "phone": 2.0}"#,
+            r#"{"fruit": 1.0}"#,
+        ] {
+            let mut doc = TantivyDocument::default();
+            doc.add_pre_tokenized_text(terms, format.tokenize(weights).unwrap());
+            writer.add_document(doc).unwrap();
+        }
+        writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+
+        let query = SparseVectorQuery::new(
+            terms,
+            &SparseVectorFormat::parse(r#"{"apple": 2.0, "fruit": 1.0, "pear": 3.0}"#).unwrap(),
+            format,
+        );
+        let results: Vec<(Score, u32)> = searcher
+            .search(&query, &TopDocs::with_limit(10))
+            .unwrap()
+            .into_iter()
+            .map(|(score, doc_address)| (score, doc_address.doc_id))
+            .collect();
+        let expected = [(3.5, 0), (1.0, 2), (0.4, 1)];
+        assert_eq!(results.len(), expected.len());
+        for ((score, doc), (expected_score, expected_doc)) in results.iter().zip(expected) {
+            assert_eq!(*doc, expected_doc);
+            assert!((score - expected_score).abs() < 1e-4);
+        }
+
+        let empty_query = SparseVectorQuery::new(terms, &[], format);
+        assert!(searcher
+            .search(&empty_query, &TopDocs::with_limit(10))
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -1,13 +1,23 @@
 #[cfg(test)]
 mod tests {
+    use std::sync::atomic::Ordering;
     use tempfile::TempDir;
 
+    use crate::common::constants::{METRICS, QUERY_RESULT_CACHE_DIR_NAME};
     use crate::common::tests::index_3column_docs_with_threads_merge;
+    use crate::index::implements::api_index_impl::{
+        commit_index, create_index_with_parameter, free_index_writer, index_multi_column_docs,
+        update_index_settings,
+    };
     use crate::search::implements::api_clickhouse_impl::{
+        query_date_range_bitmap, query_geo_bounding_box_bitmap, query_geo_distance_bitmap,
         query_sentence_bitmap, query_sentence_with_range, query_term_bitmap, query_term_with_range,
-        query_terms_bitmap, query_terms_with_range, regex_term_bitmap, regex_term_with_range,
+        query_terms_adaptive, query_terms_bitmap, query_terms_with_range, regex_term_bitmap,
+        regex_term_with_range,
     };
-    use crate::search::implements::api_common_impl::load_index_reader;
+    use crate::search::implements::api_common_impl::{free_index_reader, load_index_reader};
+    use crate::search::implements::strategy::adaptive_collect::AdaptiveRows;
+    use crate::TEST_MUTEX;
 
     #[test]
     pub fn test_query_term_with_range() {
@@ -112,6 +122,118 @@ mod tests {
         assert_eq!(res.unwrap()[0], 21);
     }
 
+    #[test]
+    pub fn test_query_terms_bitmap_with_persistent_cache() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+
+        index_3column_docs_with_threads_merge(temp_directory_str);
+        assert!(load_index_reader(temp_directory_str).is_ok());
+        assert!(
+            update_index_settings(temp_directory_str, r#"{"persistent_query_cache_mb": 1}"#)
+                .is_ok()
+        );
+        let terms = vec!["Ancient".to_string(), "Social".to_string()];
+        assert_eq!(
+            query_terms_bitmap(temp_directory_str, "col1", &terms).unwrap(),
+            vec![21]
+        );
+        let cache_directory = temp_directory.path().join(QUERY_RESULT_CACHE_DIR_NAME);
+        assert_eq!(std::fs::read_dir(&cache_directory).unwrap().count(), 1);
+
+        // Result persisted before reader is reloaded is read again.
+        assert!(free_index_reader(temp_directory_str).unwrap());
+        assert!(load_index_reader(temp_directory_str).is_ok());
+        assert!(
+            update_index_settings(temp_directory_str, r#"{"persistent_query_cache_mb": 1}"#)
+                .is_ok()
+        );
+        let hits: u64 = METRICS.query_result_cache_hits.load(Ordering::Relaxed);
+        let reordered = vec!["social".to_string(), "ancient".to_string()];
+        assert_eq!(
+            query_terms_bitmap(temp_directory_str, "col1", &reordered).unwrap(),
+            vec![21]
+        );
+        assert_eq!(
+            METRICS.query_result_cache_hits.load(Ordering::Relaxed),
+            hits + 1
+        );
+        assert!(free_index_reader(temp_directory_str).unwrap());
+    }
+
+    #[test]
+    pub fn test_query_terms_adaptive() {
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+
+        index_3column_docs_with_threads_merge(temp_directory_str);
+        assert!(load_index_reader(temp_directory_str).is_ok());
+
+        let query = |terms: &[&str], mode: &str, need_row_ids: bool| {
+            let terms: Vec<String> = terms.iter().map(|term| term.to_string()).collect();
+            query_terms_adaptive(
+                temp_directory_str,
+                "col1",
+                &terms,
+                mode,
+                need_row_ids,
+                false,
+            )
+            .unwrap()
+        };
+        // No row has the term, query is short-circuited.
+        assert_eq!(query(&["Medieval"], "auto", true), AdaptiveRows::Empty);
+        // 2 of 5 rows match, they are collected as bitmap.
+        let sparse = query(&["Ancient"], "auto", true);
+        assert_eq!(sparse.strategy_name(), "bitmap");
+        assert_eq!(sparse.count(), 2);
+        // 4 of 5 rows match, rows 0..5 except row 1.
+        let dense = query(&["Ancient", "Social", "Strategic"], "auto", true);
+        assert_eq!(dense.strategy_name(), "dense_range");
+        assert_eq!(dense.count(), 4);
+        if let AdaptiveRows::DenseRange {
+            start,
+            end,
+            excluded,
+        } = dense
+        {
+            assert_eq!((start, end), (0, 5));
+            assert_eq!(excluded.iter().collect::<Vec<u32>>(), vec![1]);
+        }
+        assert_eq!(
+            query(&["Ancient", "Social"], "auto", false),
+            AdaptiveRows::Count(3)
+        );
+        assert_eq!(query(&["Ancient"], "count", true), AdaptiveRows::Count(2));
+        assert_eq!(
+            query(&["Ancient", "Social", "Strategic"], "bitmap", true).strategy_name(),
+            "bitmap"
+        );
+        assert!(query_terms_adaptive(
+            temp_directory_str,
+            "col1",
+            &vec!["Ancient".to_string()],
+            "sparse",
+            true,
+            false
+        )
+        .is_err());
+
+        // Bitmap of 5 rows is a byte, smaller than their ranges.
+        let rows = query_terms_adaptive(
+            temp_directory_str,
+            "col1",
+            &vec!["Ancient".to_string(), "Social".to_string()],
+            "bitmap",
+            true,
+            true,
+        )
+        .unwrap();
+        assert_eq!(rows.strategy_name(), "bitmap");
+        assert_eq!(rows.count(), 3);
+    }
+
     #[test]
     pub fn test_query_sentence_bitmap() {
         let temp_directory = TempDir::new().unwrap();
@@ -146,4 +268,173 @@ mod tests {
         assert_eq!(res.clone().unwrap().len(), 1);
         assert_eq!(res.unwrap()[0], 2);
     }
+
+    #[test]
+    pub fn test_query_date_range_bitmap() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec!["title".to_string(), "created".to_string()];
+
+        assert!(create_index_with_parameter(
+            temp_directory_str,
+            &column_names,
+            r#"{"created": {"tokenizer": {"type": "date", "precision": "milliseconds",
+                "input_formats": ["rfc3339", "epoch_seconds"]}}}"#,
+        )
+        .is_ok());
+        let dates = [
+            "2024-03-01T00:00:00.123456Z",
+            "1709251200",
+            "2024-03-02T08:00:00+08:00",
+        ];
+        for (row_id, date) in dates.iter().enumerate() {
+            assert!(index_multi_column_docs(
+                temp_directory_str,
+                row_id as u64,
+                &column_names,
+                &vec!["doc".to_string(), date.to_string()],
+            )
+            .is_ok());
+        }
+        assert!(index_multi_column_docs(
+            temp_directory_str,
+            3,
+            &column_names,
+            &vec!["doc".to_string(), "yesterday".to_string()],
+        )
+        .is_err());
+        assert!(commit_index(temp_directory_str).is_ok());
+        assert!(load_index_reader(temp_directory_str).is_ok());
+
+        // Lower bound is truncated to millis, row 0 is indexed as `.123` and matches it.
+        let res = query_date_range_bitmap(
+            temp_directory_str,
+            "created",
+            "2024-03-01T00:00:00.123999Z",
+            "",
+            0,
+        );
+        assert_eq!(res.unwrap(), vec![0b101]);
+        let res = query_date_range_bitmap(temp_directory_str, "created", "", "1709251200", 0);
+        assert_eq!(res.unwrap(), vec![0b10]);
+
+        // Day of host timezone, rows 0 and 1 are 2024-03-01 08:00 in UTC+8,
+        // row 2 is 2024-03-01 16:00 in UTC-8.
+        let day_bitmap = |utc_offset_seconds: i32| {
+            query_date_range_bitmap(
+                temp_directory_str,
+                "created",
+                "2024-03-01",
+                "2024-03-01",
+                utc_offset_seconds,
+            )
+        };
+        assert_eq!(day_bitmap(0).unwrap(), vec![0b11]);
+        assert_eq!(day_bitmap(8 * 3600).unwrap(), vec![0b11]);
+        assert_eq!(day_bitmap(-8 * 3600).unwrap(), vec![0b100]);
+        assert!(day_bitmap(100 * 3600).is_err());
+
+        assert!(query_date_range_bitmap(temp_directory_str, "created", "today", "", 0).is_err());
+        assert!(query_date_range_bitmap(temp_directory_str, "title", "", "", 0).is_err());
+        assert!(free_index_reader(temp_directory_str).unwrap());
+        assert!(free_index_writer(temp_directory_str).is_ok());
+    }
+
+    #[test]
+    pub fn test_query_geo_bitmap() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec!["title".to_string(), "location".to_string()];
+
+        assert!(create_index_with_parameter(
+            temp_directory_str,
+            &column_names,
+            r#"{"location": {"tokenizer": {"type": "geo_point"}}}"#,
+        )
+        .is_ok());
+        // Shanghai, Beijing, London and Fiji.
+        let points = [
+            "31.2304,121.4737",
+            "39.9042,116.4074",
+            "51.5072,-0.1276",
+            "-17.7134,178.0650",
+        ];
+        for (row_id, point) in points.iter().enumerate() {
+            assert!(index_multi_column_docs(
+                temp_directory_str,
+                row_id as u64,
+                &column_names,
+                &vec!["city".to_string(), point.to_string()],
+            )
+            .is_ok());
+        }
+        assert!(index_multi_column_docs(
+            temp_directory_str,
+            4,
+            &column_names,
+            &vec!["city".to_string(), "95,0".to_string()],
+        )
+        .is_err());
+        assert!(commit_index(temp_directory_str).is_ok());
+        assert!(load_index_reader(temp_directory_str).is_ok());
+
+        let res =
+            query_geo_distance_bitmap(temp_directory_str, "location", 31.23, 121.47, 10_000.0);
+        assert_eq!(res.unwrap(), vec![0b1]);
+        let res =
+            query_geo_distance_bitmap(temp_directory_str, "location", 31.23, 121.47, 1_100_000.0);
+        assert_eq!(res.unwrap(), vec![0b11]);
+        let res =
+            query_geo_bounding_box_bitmap(temp_directory_str, "location", 35.0, -10.0, 60.0, 130.0);
+        assert_eq!(res.unwrap(), vec![0b110]);
+        // Box crossing the antimeridian.
+        let res = query_geo_bounding_box_bitmap(
+            temp_directory_str,
+            "location",
+            -30.0,
+            170.0,
+            0.0,
+            -170.0,
+        );
+        assert_eq!(res.unwrap(), vec![0b1000]);
+
+        // Identical queries of replica threads run concurrently, they share results.
+        let results: Vec<Vec<u8>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| {
+                    scope.spawn(|| {
+                        query_geo_distance_bitmap(
+                            temp_directory_str,
+                            "location",
+                            31.23,
+                            121.47,
+                            1_100_000.0,
+                        )
+                        .unwrap()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        });
+        assert!(results.iter().all(|result| *result == vec![0b11]));
+
+        assert!(query_geo_distance_bitmap(temp_directory_str, "location", 0.0, 0.0, -1.0).is_err());
+        assert!(query_geo_bounding_box_bitmap(
+            temp_directory_str,
+            "location",
+            60.0,
+            0.0,
+            35.0,
+            10.0
+        )
+        .is_err());
+        assert!(query_geo_distance_bitmap(temp_directory_str, "title", 0.0, 0.0, 1.0).is_err());
+        assert!(free_index_reader(temp_directory_str).unwrap());
+        assert!(free_index_writer(temp_directory_str).is_ok());
+    }
 }
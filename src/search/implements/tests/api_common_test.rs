@@ -2,11 +2,27 @@
 mod tests {
     use tempfile::TempDir;
 
-    use crate::common::tests::index_3column_docs_with_threads_merge;
+    use crate::common::constants::INDEX_INFO_FILE_NAME;
+    use crate::common::tests::{
+        create_3column_names, index_3column_docs_with_index_writer_bridge,
+        index_3column_docs_with_threads_merge,
+    };
+    use crate::ffi::AnalyzedToken;
+    use crate::index::implements::api_index_impl::{
+        commit_index, create_index, create_index_with_parameter, free_index_writer,
+        index_multi_column_docs,
+    };
+    use crate::search::implements::api_clickhouse_impl::{
+        query_sentence_bitmap, query_term_bitmap,
+    };
     use crate::search::implements::api_common_impl::{
-        free_index_reader, get_indexed_doc_counts, load_index_reader,
+        analyze_text, bootstrap_indexes, clear_deleted_row_ids, free_index_reader,
+        get_indexed_doc_counts, load_index_reader, normalize_term, register_deleted_row_ids,
+        register_security_filter_query, register_security_filter_row_ids, set_search_principal,
+        unregister_security_filter,
     };
     use crate::FFI_INDEX_SEARCHER_CACHE;
+    use crate::TEST_MUTEX;
 
     #[test]
     pub fn test_load_index_reader() {
@@ -59,4 +75,402 @@ mod tests {
         assert!(res.is_ok());
         assert_eq!(res.clone().unwrap(), 5);
     }
+
+    #[test]
+    pub fn test_cached_reader_reloaded_by_commit() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = create_3column_names();
+
+        assert!(create_index(temp_directory_str, &column_names).is_ok());
+        index_3column_docs_with_index_writer_bridge(temp_directory_str, false);
+        assert!(load_index_reader(temp_directory_str).is_ok());
+        let reader_address = FFI_INDEX_SEARCHER_CACHE
+            .get_index_reader_bridge(temp_directory_str.to_string())
+            .unwrap()
+            .reader_address();
+        assert_eq!(get_indexed_doc_counts(temp_directory_str).unwrap(), 5);
+
+        assert!(index_multi_column_docs(
+            temp_directory_str,
+            100,
+            &column_names,
+            &vec!["col1".to_string(), "col2".to_string(), "col3".to_string()]
+        )
+        .is_ok());
+        assert_eq!(get_indexed_doc_counts(temp_directory_str).unwrap(), 5);
+
+        // Commit reloads the cached reader, no new reader is created.
+        assert!(commit_index(temp_directory_str).is_ok());
+        assert_eq!(get_indexed_doc_counts(temp_directory_str).unwrap(), 6);
+        assert_eq!(
+            FFI_INDEX_SEARCHER_CACHE
+                .get_index_reader_bridge(temp_directory_str.to_string())
+                .unwrap()
+                .reader_address(),
+            reader_address
+        );
+
+        assert!(free_index_writer(temp_directory_str).is_ok());
+        assert!(free_index_reader(temp_directory_str).is_ok());
+    }
+
+    #[test]
+    pub fn test_query_tokenizer() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec!["col1".to_string()];
+        let index_json_parameter = r#"{"col1": {
+            "tokenizer": {"type": "stem", "stem_languages": ["english"]},
+            "query_tokenizer": {"type": "simple"}
+        }}"#;
+
+        assert!(create_index_with_parameter(
+            temp_directory_str,
+            &column_names,
+            index_json_parameter
+        )
+        .is_ok());
+        for (row_id, doc) in ["running fast", "run away"].iter().enumerate() {
+            assert!(index_multi_column_docs(
+                temp_directory_str,
+                row_id as u64,
+                &column_names,
+                &vec![doc.to_string()]
+            )
+            .is_ok());
+        }
+        assert!(commit_index(temp_directory_str).is_ok());
+        assert!(load_index_reader(temp_directory_str).is_ok());
+
+        // Query text isn't stemmed, only the indexed stem matches.
+        assert_eq!(
+            query_sentence_bitmap(temp_directory_str, "col1", "running").unwrap(),
+            Vec::<u8>::new()
+        );
+        assert_eq!(
+            query_sentence_bitmap(temp_directory_str, "col1", "run").unwrap(),
+            vec![0b11]
+        );
+
+        assert!(free_index_writer(temp_directory_str).is_ok());
+        assert!(free_index_reader(temp_directory_str).is_ok());
+
+        // Query tokenizer should be a text tokenizer.
+        assert!(create_index_with_parameter(
+            temp_directory_str,
+            &column_names,
+            r#"{"col1": {"tokenizer": {"type": "stem"}, "query_tokenizer": {"type": "i64"}}}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    pub fn test_language_routing() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec!["text".to_string()];
+        let index_json_parameter = r#"{"text": {
+            "tokenizer": {"type": "default"},
+            "language_routing": {"languages": ["en", "zh"]}
+        }}"#;
+
+        assert!(create_index_with_parameter(
+            temp_directory_str,
+            &column_names,
+            index_json_parameter
+        )
+        .is_ok());
+        let docs = [
+            "Armies were marching across the frozen plains of the north.",
+            "古代帝国的兴衰塑造了历史的进程。",
+        ];
+        for (row_id, doc) in docs.iter().enumerate() {
+            assert!(index_multi_column_docs(
+                temp_directory_str,
+                row_id as u64,
+                &column_names,
+                &vec![doc.to_string()]
+            )
+            .is_ok());
+        }
+        assert!(commit_index(temp_directory_str).is_ok());
+        assert!(load_index_reader(temp_directory_str).is_ok());
+
+        // Stemmed in `text_en`, `march` isn't a term of `text`.
+        assert_eq!(
+            query_sentence_bitmap(temp_directory_str, "text", "march").unwrap(),
+            vec![0b01]
+        );
+        // Segmented in `text_zh`, `text` keeps the whole sentence as one token.
+        assert_eq!(
+            query_sentence_bitmap(temp_directory_str, "text", "历史").unwrap(),
+            vec![0b10]
+        );
+        assert_eq!(
+            query_term_bitmap(temp_directory_str, "text", "armies").unwrap(),
+            vec![0b01]
+        );
+
+        assert!(free_index_writer(temp_directory_str).is_ok());
+        assert!(free_index_reader(temp_directory_str).is_ok());
+
+        // Sub-field name conflicts with a host column.
+        assert!(create_index_with_parameter(
+            temp_directory_str,
+            &vec!["text".to_string(), "text_en".to_string()],
+            index_json_parameter
+        )
+        .is_err());
+    }
+
+    #[test]
+    pub fn test_pinyin_filter() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec!["text".to_string()];
+        let index_json_parameter = r#"{"text": {"tokenizer": {
+            "type": "chinese",
+            "filters": [{"type": "pinyin", "first_letter": true}]
+        }}}"#;
+
+        assert!(create_index_with_parameter(
+            temp_directory_str,
+            &column_names,
+            index_json_parameter
+        )
+        .is_ok());
+        for (row_id, doc) in ["我们去北京", "上海的天气"].iter().enumerate() {
+            assert!(index_multi_column_docs(
+                temp_directory_str,
+                row_id as u64,
+                &column_names,
+                &vec![doc.to_string()]
+            )
+            .is_ok());
+        }
+        assert!(commit_index(temp_directory_str).is_ok());
+        assert!(load_index_reader(temp_directory_str).is_ok());
+
+        // Hanzi, full pinyin and first letters all match.
+        for query in ["北京", "beijing", "bj"] {
+            assert_eq!(
+                query_sentence_bitmap(temp_directory_str, "text", query).unwrap(),
+                vec![0b01]
+            );
+        }
+        assert_eq!(
+            query_sentence_bitmap(temp_directory_str, "text", "shanghai").unwrap(),
+            vec![0b10]
+        );
+
+        assert!(free_index_writer(temp_directory_str).is_ok());
+        assert!(free_index_reader(temp_directory_str).is_ok());
+    }
+
+    #[test]
+    pub fn test_register_and_clear_deleted_row_ids() {
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+
+        index_3column_docs_with_threads_merge(temp_directory_str);
+        assert!(register_deleted_row_ids(temp_directory_str, &vec![0]).is_err());
+        assert!(load_index_reader(temp_directory_str).is_ok());
+
+        // Row 0 and row 4 contain `Ancient`.
+        let res = query_term_bitmap(temp_directory_str, "col1", "Ancient");
+        assert_eq!(res.unwrap()[0], 17);
+
+        assert!(register_deleted_row_ids(temp_directory_str, &vec![0]).is_ok());
+        let res = query_term_bitmap(temp_directory_str, "col1", "Ancient");
+        assert_eq!(res.unwrap()[0], 16);
+
+        // Deleted row ids are kept after reader reloaded.
+        assert!(load_index_reader(temp_directory_str).is_ok());
+        let res = query_term_bitmap(temp_directory_str, "col1", "Ancient");
+        assert_eq!(res.unwrap()[0], 16);
+
+        assert!(clear_deleted_row_ids(temp_directory_str).is_ok());
+        let res = query_term_bitmap(temp_directory_str, "col1", "Ancient");
+        assert_eq!(res.unwrap()[0], 17);
+    }
+
+    #[test]
+    pub fn test_security_filters() {
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+
+        index_3column_docs_with_threads_merge(temp_directory_str);
+        assert!(register_security_filter_row_ids(temp_directory_str, "alice", &[4]).is_err());
+        assert!(load_index_reader(temp_directory_str).is_ok());
+        assert!(register_security_filter_row_ids(temp_directory_str, "", &[4]).is_err());
+        assert!(register_security_filter_query(temp_directory_str, "bob", "col1:(").is_err());
+
+        // Row 0 and row 4 contain `Ancient`, alice may only see row 4.
+        assert!(register_security_filter_row_ids(temp_directory_str, "alice", &[4]).is_ok());
+        assert!(register_security_filter_query(temp_directory_str, "bob", "col1:Ancient").is_ok());
+        assert!(set_search_principal("alice").is_ok());
+        let res = query_term_bitmap(temp_directory_str, "col1", "Ancient");
+        assert_eq!(res.unwrap()[0], 16);
+
+        // Filters are ANDed with lightweight deletes, and kept after reader reloaded.
+        assert!(set_search_principal("bob").is_ok());
+        assert!(register_deleted_row_ids(temp_directory_str, &vec![0]).is_ok());
+        assert!(load_index_reader(temp_directory_str).is_ok());
+        let res = query_term_bitmap(temp_directory_str, "col1", "Ancient");
+        assert_eq!(res.unwrap()[0], 16);
+        assert!(clear_deleted_row_ids(temp_directory_str).is_ok());
+        let res = query_term_bitmap(temp_directory_str, "col1", "Ancient");
+        assert_eq!(res.unwrap()[0], 17);
+
+        // Principal without filter sees nothing.
+        assert!(unregister_security_filter(temp_directory_str, "bob").unwrap());
+        let res = query_term_bitmap(temp_directory_str, "col1", "Ancient").unwrap();
+        assert!(res.iter().all(|byte| *byte == 0));
+
+        // Unbound thread searches unfiltered.
+        assert!(set_search_principal("").is_ok());
+        let res = query_term_bitmap(temp_directory_str, "col1", "Ancient");
+        assert_eq!(res.unwrap()[0], 17);
+    }
+
+    #[test]
+    pub fn test_analyze_text() {
+        let config = r#"{"col1": {"tokenizer": {"type": "whitespace", "case_sensitive": true}}}"#;
+        let tokens = analyze_text(config, "col1", "Hello  World").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                AnalyzedToken {
+                    token: "Hello".to_string(),
+                    position: 0,
+                    offset_from: 0,
+                    offset_to: 5,
+                },
+                AnalyzedToken {
+                    token: "World".to_string(),
+                    position: 1,
+                    offset_from: 7,
+                    offset_to: 12,
+                },
+            ]
+        );
+
+        // Column not configured uses default analyzer.
+        let tokens = analyze_text(config, "col2", "Hello World").unwrap();
+        let texts: Vec<String> = tokens.into_iter().map(|token| token.token).collect();
+        assert_eq!(texts, vec!["hello".to_string(), "world".to_string()]);
+
+        assert!(analyze_text("{abc}", "col1", "Hello").is_err());
+    }
+
+    #[test]
+    pub fn test_normalize_term() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec!["city".to_string()];
+        let index_json_parameter = r#"{"city": {"tokenizer": {"type": "keyword_lowercase"}}}"#;
+
+        assert!(create_index_with_parameter(
+            temp_directory_str,
+            &column_names,
+            index_json_parameter
+        )
+        .is_ok());
+        for (row_id, doc) in ["New York", "York"].iter().enumerate() {
+            assert!(index_multi_column_docs(
+                temp_directory_str,
+                row_id as u64,
+                &column_names,
+                &vec![doc.to_string()]
+            )
+            .is_ok());
+        }
+        assert!(commit_index(temp_directory_str).is_ok());
+        assert!(load_index_reader(temp_directory_str).is_ok());
+
+        // Whole value is one lowercased term.
+        let term = normalize_term(temp_directory_str, "city", "NEW YORK").unwrap();
+        assert_eq!(term, "new york");
+        assert_eq!(
+            query_term_bitmap(temp_directory_str, "city", &term).unwrap(),
+            vec![0b01]
+        );
+
+        // Values split by analyzer can't be an exact term.
+        assert!(normalize_term(index_json_parameter, "other", "New York").is_err());
+        assert_eq!(
+            normalize_term(index_json_parameter, "other", "York").unwrap(),
+            "york"
+        );
+
+        assert!(free_index_writer(temp_directory_str).is_ok());
+        assert!(free_index_reader(temp_directory_str).is_ok());
+    }
+
+    #[test]
+    pub fn test_bootstrap_indexes() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+
+        let temp_directory = TempDir::new().unwrap();
+        let root_dir: &str = temp_directory.path().to_str().unwrap();
+        let column_names = create_3column_names();
+        let index_paths: Vec<String> = ["table_a/part_0", "table_b"]
+            .iter()
+            .map(|name| {
+                temp_directory
+                    .path()
+                    .join(name)
+                    .to_str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect();
+        for index_path in &index_paths {
+            assert!(create_index(index_path, &column_names).is_ok());
+            index_3column_docs_with_index_writer_bridge(index_path, false);
+            assert!(free_index_writer(index_path).is_ok());
+        }
+        // Index with broken settings is skipped and reported.
+        let broken_path = temp_directory.path().join("table_c");
+        std::fs::create_dir_all(&broken_path).unwrap();
+        std::fs::write(broken_path.join(INDEX_INFO_FILE_NAME), "{").unwrap();
+        std::fs::write(broken_path.join("meta.json"), "{}").unwrap();
+
+        assert!(bootstrap_indexes(&format!("{}/missing", root_dir), 0, 0).is_err());
+        let summary: serde_json::Value =
+            serde_json::from_str(&bootstrap_indexes(root_dir, 3600, 2).unwrap()).unwrap();
+        assert_eq!(summary["discovered"], 3);
+        assert_eq!(summary["loaded"], serde_json::json!(index_paths));
+        assert_eq!(summary["warmed"], serde_json::json!(index_paths));
+        assert!(summary["warmed_bytes"].as_u64().unwrap() > 0);
+        assert_eq!(summary["failed"].as_array().unwrap().len(), 1);
+        assert_eq!(
+            summary["failed"][0]["index_path"],
+            broken_path.to_str().unwrap()
+        );
+        for index_path in &index_paths {
+            assert_eq!(get_indexed_doc_counts(index_path).unwrap(), 5);
+        }
+
+        // Loaded readers are kept, nothing is warmed without a hot window.
+        let summary: serde_json::Value =
+            serde_json::from_str(&bootstrap_indexes(root_dir, 0, 0).unwrap()).unwrap();
+        assert_eq!(summary["loaded"], serde_json::json!(index_paths));
+        assert_eq!(summary["warmed_bytes"], 0);
+
+        for index_path in &index_paths {
+            assert!(free_index_reader(index_path).is_ok());
+        }
+    }
 }
@@ -9,13 +9,37 @@ mod tests {
     use tempfile::TempDir;
 
     use crate::common::tests::{
-        get_mocked_docs_for_part0, get_mocked_docs_for_part1, get_mocked_docs_for_part2,
-        get_mocked_docs_for_part3, get_mocked_docs_for_part4, index_documents,
+        get_mocked_docs, get_mocked_docs_for_part0, get_mocked_docs_for_part1,
+        get_mocked_docs_for_part2, get_mocked_docs_for_part3, get_mocked_docs_for_part4,
+        index_3column_docs_with_threads_merge, index_documents,
     };
-    use crate::ffi::{DocWithFreq, FieldTokenNums, RowIdWithScore};
-    use crate::search::implements::api_common_impl::load_index_reader;
+    use crate::ffi::{DocWithFreq, FieldTokenNums, MatchedTermsTopDoc, RowIdWithScore};
+    use crate::index::implements::api_index_impl::{
+        commit_index, create_index_with_parameter, free_index_writer, index_multi_column_docs,
+        update_index_settings,
+    };
+    use crate::search::implements::api_common_impl::{free_index_reader, load_index_reader};
+    use crate::search::implements::api_dingo_impl::bm25_search_with_bm25_params;
     use crate::search::implements::api_dingo_impl::bm25_search_with_column_names;
+    use crate::search::implements::api_dingo_impl::bm25_search_with_field_boosts;
+    use crate::search::implements::api_dingo_impl::bm25_search_with_matched_terms;
+    use crate::search::implements::api_dingo_impl::bm25_search_with_query_dsl;
+    use crate::search::implements::api_dingo_impl::bm25_search_with_total_hits;
     use crate::search::implements::api_dingo_impl::get_doc_freq;
+    use crate::search::implements::api_dingo_impl::get_matched_offsets;
+    use crate::search::implements::api_dingo_impl::knn_search;
+    use crate::search::implements::api_dingo_impl::sparse_vector_search;
+    use crate::search::implements::api_dingo_impl::{
+        acquire_searcher_handle, bm25_search_with_handle, get_stored_fields_with_handle,
+        release_searcher_handle, searcher_handle_num_docs,
+    };
+    use crate::search::implements::api_dingo_impl::{
+        bm25_search_at_generation, pin_searcher, unpin_searcher,
+    };
+    use crate::search::implements::api_dingo_impl::{
+        percolate, register_percolator_query, unregister_percolator_query,
+    };
+    use crate::TEST_MUTEX;
 
     #[allow(dead_code)]
     #[derive(Debug, Clone)]
@@ -341,4 +365,659 @@ mod tests {
         // assert_eq!(optimized_ds, combined);
         assert_eq!(optimized_ds.len(), combined.len());
     }
+
+    #[test]
+    pub fn test_bm25_search_at_generation() {
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+
+        index_3column_docs_with_threads_merge(temp_directory_str);
+        assert!(pin_searcher(temp_directory_str).is_err());
+        assert!(load_index_reader(temp_directory_str).is_ok());
+
+        let generation = pin_searcher(temp_directory_str).unwrap();
+        let result = bm25_search_at_generation(
+            temp_directory_str,
+            "Ancient",
+            10,
+            &vec![],
+            false,
+            false,
+            0,
+            0,
+            false,
+            &vec![],
+            Some(generation),
+        );
+        assert_eq!(result.unwrap().len(), 2);
+
+        assert!(unpin_searcher(temp_directory_str, generation).unwrap());
+        // Unknown generation which is not pinned.
+        let result = bm25_search_at_generation(
+            temp_directory_str,
+            "Ancient",
+            10,
+            &vec![],
+            false,
+            false,
+            0,
+            0,
+            false,
+            &vec![],
+            Some(generation + 100),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn test_bm25_search_with_field_boosts() {
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+
+        index_3column_docs_with_threads_merge(temp_directory_str);
+        assert!(load_index_reader(temp_directory_str).is_ok());
+
+        // `philosophies` is in col1 of row 4, `inventions` is in col3 of row 0.
+        let search = |field_boosts: &[(&str, f32)]| {
+            let field_boosts: HashMap<String, f32> = field_boosts
+                .iter()
+                .map(|(column_name, boost)| (column_name.to_string(), *boost))
+                .collect();
+            bm25_search_with_field_boosts(
+                temp_directory_str,
+                "philosophies inventions",
+                10,
+                &vec![],
+                false,
+                false,
+                0,
+                0,
+                false,
+                &field_boosts,
+            )
+        };
+        let result = search(&[("col1", 10.0), ("col3", 1.0)]).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].row_id, 4);
+        assert!(result[0].score > result[1].score * 5.0);
+        let result = search(&[("col1", 1.0), ("col3", 10.0)]).unwrap();
+        assert_eq!(result[0].row_id, 0);
+        // Columns not boosted are not searched.
+        assert_eq!(search(&[("col3", 1.0)]).unwrap().len(), 1);
+
+        assert!(search(&[]).is_err());
+        assert!(search(&[("col1", 0.0)]).is_err());
+        assert!(search(&[("col1", f32::NAN)]).is_err());
+        assert!(search(&[("not_exist", 1.0)]).is_err());
+        assert!(free_index_reader(temp_directory_str).unwrap());
+    }
+
+    #[test]
+    pub fn test_bm25_search_with_query_dsl() {
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+
+        index_3column_docs_with_threads_merge(temp_directory_str);
+        assert!(load_index_reader(temp_directory_str).is_ok());
+
+        let search = |query_json: &str| {
+            bm25_search_with_query_dsl(
+                temp_directory_str,
+                query_json,
+                10,
+                &vec![],
+                false,
+                false,
+                0,
+                0,
+                false,
+            )
+        };
+        // `dilemmas` is in col1 and col2 of row 4.
+        let col1_score =
+            search(r#"{"type": "match", "field": "col1", "query": "dilemmas"}"#).unwrap()[0].score;
+        let col2_score =
+            search(r#"{"type": "match", "field": "col2", "query": "dilemmas"}"#).unwrap()[0].score;
+        let dis_max = |tie_breaker: f32| {
+            let result = search(&format!(
+                r#"{{"type": "dis_max", "tie_breaker": {}, "queries": [
+                    {{"type": "match", "field": "col1", "query": "dilemmas"}},
+                    {{"type": "match", "field": "col2", "query": "dilemmas"}}]}}"#,
+                tie_breaker
+            ))
+            .unwrap();
+            assert_eq!(result.len(), 1);
+            assert_eq!(result[0].row_id, 4);
+            result[0].score
+        };
+        let best_score = col1_score.max(col2_score);
+        let other_score = col1_score.min(col2_score);
+        assert!((dis_max(0.0) - best_score).abs() < 1e-4);
+        assert!((dis_max(0.5) - (best_score + 0.5 * other_score)).abs() < 1e-4);
+
+        // Row 4 matching `dilemmas` in col2 is demoted but still returned.
+        let score_of = |result: &Vec<RowIdWithScore>, row_id: u64| {
+            result
+                .iter()
+                .find(|doc| doc.row_id == row_id)
+                .unwrap()
+                .score
+        };
+        let matched = search(r#"{"type": "match", "field": "col1", "query": "Ancient"}"#).unwrap();
+        let boosted = search(
+            r#"{"type": "boosting", "negative_boost": 0.1,
+                "positive": {"type": "match", "field": "col1", "query": "Ancient"},
+                "negative": {"type": "match", "field": "col2", "query": "dilemmas"}}"#,
+        )
+        .unwrap();
+        assert_eq!(boosted.len(), 2);
+        assert_eq!(boosted[0].row_id, 0);
+        assert!((score_of(&boosted, 0) - score_of(&matched, 0)).abs() < 1e-4);
+        assert!((score_of(&boosted, 4) - score_of(&matched, 4) * 0.1).abs() < 1e-4);
+
+        assert!(search(r#"{"type": "match", "field": "not_exist", "query": "a"}"#).is_err());
+        assert!(search(r#"{"type": "match", "field": "row_id", "query": "1"}"#).is_err());
+        assert!(search(r#"{"type": "dis_max", "queries": []}"#).is_err());
+        assert!(free_index_reader(temp_directory_str).unwrap());
+    }
+
+    #[test]
+    pub fn test_bm25_search_with_matched_terms() {
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+
+        index_3column_docs_with_threads_merge(temp_directory_str);
+        assert!(load_index_reader(temp_directory_str).is_ok());
+
+        let search = |sentence: &str| {
+            bm25_search_with_matched_terms(
+                temp_directory_str,
+                sentence,
+                10,
+                &vec![],
+                false,
+                false,
+                0,
+                0,
+                false,
+                &vec![],
+            )
+        };
+        let matched_terms = |top_doc: &MatchedTermsTopDoc| {
+            top_doc
+                .matched_terms
+                .iter()
+                .map(|matched| format!("{}({})", matched.field, matched.term))
+                .collect::<Vec<String>>()
+        };
+        // `ancient` is in col1 of row 0 and 4, `dilemmas` is in col1 and col2 of row 4.
+        let result = search("Ancient dilemmas").unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].top_doc.row_id, 4);
+        assert_eq!(
+            matched_terms(&result[0]),
+            vec!["col1(ancient)", "col1(dilemmas)", "col2(dilemmas)"]
+        );
+        assert_eq!(result[1].top_doc.row_id, 0);
+        assert_eq!(matched_terms(&result[1]), vec!["col1(ancient)"]);
+
+        assert!(search("col1:(").is_err());
+        assert!(free_index_reader(temp_directory_str).unwrap());
+    }
+
+    #[test]
+    pub fn test_bm25_search_with_total_hits() {
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+
+        index_3column_docs_with_threads_merge(temp_directory_str);
+        assert!(load_index_reader(temp_directory_str).is_ok());
+
+        let search = |sentence: &str, topk: u32, exact_total_hits: bool| {
+            bm25_search_with_total_hits(
+                temp_directory_str,
+                sentence,
+                topk,
+                &vec![],
+                false,
+                false,
+                0,
+                0,
+                false,
+                &vec![],
+                exact_total_hits,
+            )
+        };
+        // `ancient` is in row 0 and 4.
+        let result = search("Ancient", 1, true).unwrap();
+        assert_eq!(result.top_docs.len(), 1);
+        assert_eq!(result.total_hits, 2);
+        assert!(result.total_hits_exact);
+
+        let result = search("Ancient", 10, false).unwrap();
+        assert_eq!(result.top_docs.len(), 2);
+        assert_eq!(result.total_hits, 2);
+        assert!(result.total_hits_exact);
+
+        // Lower bound at most.
+        let result = search("Ancient", 1, false).unwrap();
+        assert!((1..=2).contains(&result.total_hits));
+
+        assert!(search("col1:(", 10, true).is_err());
+        assert!(free_index_reader(temp_directory_str).unwrap());
+    }
+
+    #[test]
+    pub fn test_get_matched_offsets() {
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+
+        create_index(temp_directory_str, get_mocked_docs, create_schema());
+        assert!(load_index_reader(temp_directory_str).is_ok());
+
+        let offsets = |row_id: u64, sentence: &str, column_names: &[&str]| {
+            get_matched_offsets(
+                temp_directory_str,
+                row_id,
+                sentence,
+                &column_names.iter().map(|name| name.to_string()).collect(),
+            )
+            .unwrap()
+            .iter()
+            .map(|offset| (offset.field.clone(), offset.start, offset.end))
+            .collect::<Vec<(String, u64, u64)>>()
+        };
+        // Row 4 has `Ancient` and `dilemmas` in col1, `dilemmas` in col2.
+        assert_eq!(
+            offsets(4, "ancient dilemmas", &[]),
+            vec![
+                ("col1".to_string(), 0, 7),
+                ("col1".to_string(), 47, 55),
+                ("col2".to_string(), 8, 16),
+            ]
+        );
+        assert_eq!(
+            offsets(4, "dilemmas", &["col2"]),
+            vec![("col2".to_string(), 8, 16)]
+        );
+        assert!(offsets(3, "dilemmas", &[]).is_empty());
+        assert!(offsets(100, "dilemmas", &[]).is_empty());
+        assert!(free_index_reader(temp_directory_str).unwrap());
+
+        // Offsets can't be read from text not stored.
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        index_3column_docs_with_threads_merge(temp_directory_str);
+        assert!(load_index_reader(temp_directory_str).is_ok());
+        assert!(get_matched_offsets(temp_directory_str, 4, "dilemmas", &vec![]).is_err());
+        assert!(free_index_reader(temp_directory_str).unwrap());
+    }
+
+    #[test]
+    pub fn test_bm25_search_with_bm25_params() {
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+
+        index_3column_docs_with_threads_merge(temp_directory_str);
+        assert!(load_index_reader(temp_directory_str).is_ok());
+
+        let search = |k1: f32, b: f32| {
+            bm25_search_with_bm25_params(
+                temp_directory_str,
+                "Ancient",
+                10,
+                &vec![],
+                false,
+                false,
+                0,
+                0,
+                false,
+                &vec!["col1".to_string()],
+                k1,
+                b,
+            )
+        };
+        // `Ancient` is in col1 of row 0 and row 4, row 4 is shorter.
+        let result = search(1.2, 0.75).unwrap();
+        assert_eq!(result[0].row_id, 4);
+        assert!(result[0].score > result[1].score);
+        // Without length normalization both rows score the same.
+        let result = search(1.2, 0.0).unwrap();
+        assert_eq!(result.len(), 2);
+        assert!((result[0].score - result[1].score).abs() < 1e-6);
+
+        assert!(search(1.2, 2.0).is_err());
+        assert!(search(f32::NAN, 0.5).is_err());
+        assert!(free_index_reader(temp_directory_str).unwrap());
+    }
+
+    #[test]
+    pub fn test_bm25_search_with_index_similarity() {
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+
+        index_3column_docs_with_threads_merge(temp_directory_str);
+        assert!(load_index_reader(temp_directory_str).is_ok());
+
+        let search = || {
+            bm25_search_with_column_names(
+                temp_directory_str,
+                "Ancient philosophies",
+                10,
+                &vec![],
+                false,
+                false,
+                0,
+                0,
+                false,
+                &vec!["col1".to_string()],
+            )
+            .unwrap()
+        };
+        let bm25_result = search();
+        assert!(bm25_result[0].score > bm25_result[1].score);
+
+        // Constant similarity scores each matched term 1.
+        assert!(update_index_settings(temp_directory_str, r#"{"similarity": "constant"}"#).is_ok());
+        let constant_result = search();
+        assert_eq!(constant_result.len(), bm25_result.len());
+        assert!(constant_result.iter().all(|row| row.score == 1.0));
+
+        // Similarity given by search overrides the index one.
+        let result = bm25_search_with_bm25_params(
+            temp_directory_str,
+            "Ancient philosophies",
+            10,
+            &vec![],
+            false,
+            false,
+            0,
+            0,
+            false,
+            &vec!["col1".to_string()],
+            1.2,
+            0.75,
+        )
+        .unwrap();
+        assert!((result[0].score - bm25_result[0].score).abs() < 0.05);
+
+        assert!(update_index_settings(temp_directory_str, r#"{"similarity": "bm25"}"#).is_ok());
+        assert!((search()[0].score - bm25_result[0].score).abs() < 0.05);
+        assert!(free_index_reader(temp_directory_str).unwrap());
+    }
+
+    #[test]
+    pub fn test_searcher_handle() {
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+
+        index_3column_docs_with_threads_merge(temp_directory_str);
+        assert!(acquire_searcher_handle(temp_directory_str).is_err());
+        assert!(load_index_reader(temp_directory_str).is_ok());
+
+        let handle = acquire_searcher_handle(temp_directory_str).unwrap();
+        let num_docs = searcher_handle_num_docs(handle).unwrap();
+        assert!(num_docs > 0);
+        let result = bm25_search_with_handle(
+            handle,
+            "Ancient",
+            10,
+            0,
+            None,
+            &vec![],
+            false,
+            false,
+            0,
+            0,
+            false,
+            &vec![],
+        );
+        assert_eq!(result.unwrap().len(), 2);
+
+        // Handle keeps serving its searcher after reader is freed.
+        assert!(free_index_reader(temp_directory_str).unwrap());
+        assert_eq!(searcher_handle_num_docs(handle).unwrap(), num_docs);
+
+        assert!(release_searcher_handle(handle).unwrap());
+        assert!(!release_searcher_handle(handle).unwrap());
+        assert!(searcher_handle_num_docs(handle).is_err());
+        assert!(
+            get_stored_fields_with_handle(handle, &vec![0], &vec!["col1".to_string()]).is_err()
+        );
+    }
+
+    #[test]
+    pub fn test_knn_search() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec!["title".to_string(), "embedding".to_string()];
+
+        assert!(create_index_with_parameter(
+            temp_directory_str,
+            &column_names,
+            r#"{"embedding": {"tokenizer": {"type": "vector", "dims": 2, "metric": "cosine",
+                "index": {"type": "hnsw", "m": 4, "ef_search": 16}}}}"#,
+        )
+        .is_ok());
+        let rows = [
+            ("red apple", "1,0"),
+            ("blue berry", "0.9,0.1"),
+            ("red cherry", "0,1"),
+            ("red grape", "0.7,0.7"),
+        ];
+        for (row_id, (title, embedding)) in rows.iter().enumerate() {
+            assert!(index_multi_column_docs(
+                temp_directory_str,
+                row_id as u64,
+                &column_names,
+                &vec![title.to_string(), embedding.to_string()],
+            )
+            .is_ok());
+        }
+        assert!(index_multi_column_docs(
+            temp_directory_str,
+            4,
+            &column_names,
+            &vec!["red".to_string(), "1,0,0".to_string()],
+        )
+        .is_err());
+        assert!(commit_index(temp_directory_str).is_ok());
+        assert!(load_index_reader(temp_directory_str).is_ok());
+
+        let search = |filter_sentence: &str, alived_ids: &Vec<u64>, query_with_filter: bool| {
+            knn_search(
+                temp_directory_str,
+                "embedding",
+                &vec![1.0, 0.0],
+                2,
+                filter_sentence,
+                alived_ids,
+                query_with_filter,
+                false,
+                0,
+                0,
+                false,
+            )
+            .map(|rows| rows.iter().map(|row| row.row_id).collect::<Vec<u64>>())
+        };
+        assert_eq!(search("", &vec![], false).unwrap(), vec![0, 1]);
+        // Hybrid search, only rows matching text filter are searched.
+        assert_eq!(search("red", &vec![], false).unwrap(), vec![0, 3]);
+        assert_eq!(search("red", &vec![2, 3], true).unwrap(), vec![3, 2]);
+
+        // Query vector should have dims of the column.
+        assert!(knn_search(
+            temp_directory_str,
+            "embedding",
+            &vec![1.0],
+            2,
+            "",
+            &vec![],
+            false,
+            false,
+            0,
+            0,
+            false
+        )
+        .is_err());
+        assert!(knn_search(
+            temp_directory_str,
+            "title",
+            &vec![1.0, 0.0],
+            2,
+            "",
+            &vec![],
+            false,
+            false,
+            0,
+            0,
+            false
+        )
+        .is_err());
+        assert!(free_index_reader(temp_directory_str).unwrap());
+        assert!(free_index_writer(temp_directory_str).is_ok());
+    }
+
+    #[test]
+    pub fn test_sparse_vector_search() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec!["title".to_string(), "terms".to_string()];
+
+        assert!(create_index_with_parameter(
+            temp_directory_str,
+            &column_names,
+            r#"{"terms": {"tokenizer": {"type": "sparse_vector", "scale": 100}}}"#,
+        )
+        .is_ok());
+        let rows = [
+            ("red apple", r#"{"apple": 1.5, "fruit": 0.5}"#),
+            ("blue phone", r#"{"apple": 0.2, "phone": 2.0}"#),
+            ("red cherry", r#"{"fruit": 1.0, "cherry": 1.2}"#),
+        ];
+        for (row_id, (title, terms)) in rows.iter().enumerate() {
+            assert!(index_multi_column_docs(
+                temp_directory_str,
+                row_id as u64,
+                &column_names,
+                &vec![title.to_string(), terms.to_string()],
+            )
+            .is_ok());
+        }
+        assert!(index_multi_column_docs(
+            temp_directory_str,
+            3,
+            &column_names,
+            &vec!["red".to_string(), r#"{"apple": -1}"#.to_string()],
+        )
+        .is_err());
+        assert!(commit_index(temp_directory_str).is_ok());
+        assert!(load_index_reader(temp_directory_str).is_ok());
+
+        let search = |column_name: &str, query_json: &str, filter_sentence: &str| {
+            sparse_vector_search(
+                temp_directory_str,
+                column_name,
+                query_json,
+                10,
+                filter_sentence,
+                &vec![],
+                false,
+                false,
+                0,
+                0,
+                false,
+            )
+            .map(|rows| {
+                rows.iter()
+                    .map(|row| (row.row_id, row.score))
+                    .collect::<Vec<(u64, f32)>>()
+            })
+        };
+        // Scores are dot products of query and row weights.
+        let results = search("terms", r#"{"apple": 2.0, "fruit": 1.0}"#, "").unwrap();
+        let expected = [(0, 3.5), (2, 1.0), (1, 0.4)];
+        assert_eq!(results.len(), expected.len());
+        for ((row_id, score), (expected_row_id, expected_score)) in results.iter().zip(expected) {
+            assert_eq!(*row_id, expected_row_id);
+            assert!((score - expected_score).abs() < 1e-4);
+        }
+        // Filter limits rows without changing scores.
+        let results = search("terms", r#"{"apple": 2.0, "fruit": 1.0}"#, "red").unwrap();
+        assert_eq!(
+            results
+                .iter()
+                .map(|(row_id, _)| *row_id)
+                .collect::<Vec<u64>>(),
+            vec![0, 2]
+        );
+        assert!((results[0].1 - 3.5).abs() < 1e-4);
+
+        // Sparse vector terms aren't matched by BM25 search of all text columns.
+        let bm25_row_ids: Vec<u64> = bm25_search_with_column_names(
+            temp_directory_str,
+            "apple",
+            10,
+            &vec![],
+            false,
+            false,
+            0,
+            0,
+            false,
+            &vec![],
+        )
+        .unwrap()
+        .iter()
+        .map(|row| row.row_id)
+        .collect();
+        assert_eq!(bm25_row_ids, vec![0]);
+
+        assert!(search("terms", "apple", "").is_err());
+        assert!(search("title", r#"{"apple": 1.0}"#, "").is_err());
+        assert!(free_index_reader(temp_directory_str).unwrap());
+        assert!(free_index_writer(temp_directory_str).is_ok());
+    }
+
+    #[test]
+    pub fn test_percolate() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+        let temp_directory = TempDir::new().unwrap();
+        let temp_directory_str = temp_directory.path().to_str().unwrap();
+        let column_names = vec!["title".to_string(), "body".to_string()];
+
+        assert!(create_index_with_parameter(
+            temp_directory_str,
+            &column_names,
+            r#"{"title": {"tokenizer": {"type": "stem", "stem_languages": ["english"]}}}"#,
+        )
+        .is_ok());
+        assert!(commit_index(temp_directory_str).is_ok());
+        assert!(percolate(temp_directory_str, &column_names, &vec![]).is_err());
+        assert!(load_index_reader(temp_directory_str).is_ok());
+
+        assert!(register_percolator_query(temp_directory_str, 1, "title:running").unwrap());
+        assert!(register_percolator_query(temp_directory_str, 2, "shoes AND sale").unwrap());
+        assert!(register_percolator_query(temp_directory_str, 3, "missing:term").is_err());
+        let matched = |title: &str, body: &str| {
+            percolate(
+                temp_directory_str,
+                &column_names,
+                &vec![title.to_string(), body.to_string()],
+            )
+            .unwrap()
+        };
+        // Doc is analyzed by index analyzers, `runs` is stemmed like `running`.
+        assert_eq!(matched("He runs fast", "shoes on sale"), vec![1, 2]);
+        assert_eq!(matched("Walking", "sale"), Vec::<u64>::new());
+
+        // Stored queries are reloaded with index reader.
+        assert!(unregister_percolator_query(temp_directory_str, 1).unwrap());
+        assert!(free_index_reader(temp_directory_str).unwrap());
+        assert!(load_index_reader(temp_directory_str).is_ok());
+        assert_eq!(matched("He runs fast", "shoes on sale"), vec![2]);
+        assert!(free_index_reader(temp_directory_str).unwrap());
+        assert!(free_index_writer(temp_directory_str).is_ok());
+    }
 }
@@ -2,4 +2,4 @@ pub mod api;
 pub mod bridge;
 pub mod collector;
 pub mod implements;
-mod utils;
+pub(crate) mod utils;
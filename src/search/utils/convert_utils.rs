@@ -45,6 +45,23 @@ impl ConvertUtils {
         regex_pattern
     }
 
+    // Literal prefix of a LIKE pattern, every term it matches starts with the prefix.
+    pub fn like_literal_prefix(like_pattern: &str) -> String {
+        let mut prefix = String::new();
+        let mut escape = false;
+        for c in like_pattern.chars() {
+            match c {
+                '\\' if !escape => escape = true,
+                '%' | '_' if !escape => break,
+                _ => {
+                    prefix.push(c);
+                    escape = false;
+                }
+            }
+        }
+        prefix
+    }
+
     // convert u8_bitmap to row_ids
     #[allow(dead_code)]
     pub fn u8_bitmap_to_row_ids(bitmap: &[u8]) -> Vec<u32> {
@@ -134,6 +151,16 @@ mod tests {
             );
         }
 
+        #[test]
+        fn test_like_literal_prefix() {
+            assert_eq!(ConvertUtils::like_literal_prefix("abc%"), "abc");
+            assert_eq!(ConvertUtils::like_literal_prefix("ab_c%"), "ab");
+            assert_eq!(ConvertUtils::like_literal_prefix("%abc"), "");
+            assert_eq!(ConvertUtils::like_literal_prefix("a\\%b%"), "a%b");
+            assert_eq!(ConvertUtils::like_literal_prefix("a\\\\b%"), "a\\b");
+            assert_eq!(ConvertUtils::like_literal_prefix("a.b"), "a.b");
+        }
+
         #[test]
         fn test_u8_bitmap_to_row_ids() {
             // empty bitmap
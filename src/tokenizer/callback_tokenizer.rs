@@ -0,0 +1,215 @@
+use crate::common::constants::{TokenSink, TokenizeCallback};
+use crate::logger::logger_bridge::TantivySearchLogger;
+use crate::{common::constants::LOG_CALLBACK, WARNING};
+use libc::{c_char, c_void};
+use std::collections::HashMap;
+use std::sync::RwLock;
+use tantivy::tokenizer::{Token, TokenStream, Tokenizer};
+
+/// Tokenizer implemented by a host callback, registered by `tantivy_search_register_tokenizer`.
+/// Host reports byte offsets of each token of the text, tokens get positions in report order.
+#[derive(Debug, Clone, Copy)]
+pub struct CallbackTokenizer {
+    callback: TokenizeCallback,
+    // Opaque pointer owned by host, passed back to callback as is.
+    user_data: usize,
+}
+
+// Safety: host guarantees callback and `user_data` can be used from any thread.
+unsafe impl Send for CallbackTokenizer {}
+unsafe impl Sync for CallbackTokenizer {}
+
+// Collect a token reported by host callback, `sink` is `Vec<(usize, usize)>` of token_stream.
+extern "C" fn collect_token_offsets(sink: *mut c_void, offset_from: usize, offset_to: usize) {
+    if sink.is_null() {
+        return;
+    }
+    let offsets = unsafe { &mut *(sink as *mut Vec<(usize, usize)>) };
+    offsets.push((offset_from, offset_to));
+}
+
+impl CallbackTokenizer {
+    pub fn new(callback: TokenizeCallback, user_data: *mut c_void) -> Self {
+        CallbackTokenizer {
+            callback,
+            user_data: user_data as usize,
+        }
+    }
+
+    // Byte offsets of tokens of `text`, invalid offsets reported by host are skipped.
+    fn token_offsets(&self, text: &str) -> Vec<(usize, usize)> {
+        let mut offsets: Vec<(usize, usize)> = Vec::new();
+        let sink: TokenSink = collect_token_offsets;
+        let status = (self.callback)(
+            self.user_data as *mut c_void,
+            text.as_ptr() as *const c_char,
+            text.len(),
+            &mut offsets as *mut Vec<(usize, usize)> as *mut c_void,
+            sink,
+        );
+        if status != 0 {
+            WARNING!(function:"CallbackTokenizer", "Tokenize callback failed with status {}, text is skipped", status);
+            return Vec::new();
+        }
+        let reported = offsets.len();
+        offsets.retain(|(offset_from, offset_to)| {
+            offset_from < offset_to
+                && text.is_char_boundary(*offset_from)
+                && text.is_char_boundary(*offset_to)
+        });
+        if offsets.len() != reported {
+            WARNING!(function:"CallbackTokenizer", "{} invalid token offsets are skipped", reported - offsets.len());
+        }
+        offsets
+    }
+}
+
+impl Tokenizer for CallbackTokenizer {
+    type TokenStream<'a> = CallbackTokenStream<'a>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> CallbackTokenStream<'a> {
+        CallbackTokenStream {
+            text,
+            offsets: self.token_offsets(text).into_iter(),
+            position: 0,
+            token: Token::default(),
+        }
+    }
+}
+
+pub struct CallbackTokenStream<'a> {
+    text: &'a str,
+    offsets: std::vec::IntoIter<(usize, usize)>,
+    position: usize,
+    token: Token,
+}
+
+impl<'a> TokenStream for CallbackTokenStream<'a> {
+    fn advance(&mut self) -> bool {
+        match self.offsets.next() {
+            Some((offset_from, offset_to)) => {
+                self.token.text.clear();
+                self.token.text.push_str(&self.text[offset_from..offset_to]);
+                self.token.offset_from = offset_from;
+                self.token.offset_to = offset_to;
+                self.token.position = self.position;
+                self.position += 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn token(&self) -> &Token {
+        &self.token
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.token
+    }
+}
+
+/// Host tokenizers by name, referred by `{"type": "custom", "name": ...}` in index json parameter.
+/// A tokenizer should be registered before indexes using it are created or loaded.
+pub struct CustomTokenizers {
+    tokenizers: RwLock<HashMap<String, CallbackTokenizer>>,
+}
+
+impl CustomTokenizers {
+    pub fn new() -> Self {
+        CustomTokenizers {
+            tokenizers: RwLock::new(HashMap::new()),
+        }
+    }
+
+    // Register or replace tokenizer of `name`, loaded indexes keep the previous one.
+    pub fn register(&self, name: &str, tokenizer: CallbackTokenizer) {
+        self.tokenizers
+            .write()
+            .unwrap()
+            .insert(name.to_string(), tokenizer);
+    }
+
+    // Remove tokenizer of `name`, returns false if it's not registered.
+    pub fn unregister(&self, name: &str) -> bool {
+        self.tokenizers.write().unwrap().remove(name).is_some()
+    }
+
+    pub fn get(&self, name: &str) -> Option<CallbackTokenizer> {
+        self.tokenizers.read().unwrap().get(name).copied()
+    }
+}
+
+impl Default for CustomTokenizers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::constants::CUSTOM_TOKENIZERS;
+    use crate::tokenizer::tokenizer_utils::TokenizerUtils;
+
+    // Split text by `,`, `user_data` points to the number of calls.
+    extern "C" fn split_by_comma(
+        user_data: *mut c_void,
+        text: *const c_char,
+        text_len: usize,
+        sink: *mut c_void,
+        emit: TokenSink,
+    ) -> i32 {
+        unsafe { *(user_data as *mut usize) += 1 };
+        let bytes = unsafe { std::slice::from_raw_parts(text as *const u8, text_len) };
+        let mut offset_from = 0;
+        for (offset, byte) in bytes.iter().chain([b','].iter()).enumerate() {
+            if *byte == b',' {
+                emit(sink, offset_from, offset);
+                offset_from = offset + 1;
+            }
+        }
+        // Invalid offsets are skipped.
+        emit(sink, 2, 1);
+        0
+    }
+
+    #[test]
+    fn test_callback_tokenizer() {
+        let calls: Box<usize> = Box::new(0);
+        let calls_ptr = Box::into_raw(calls);
+        CUSTOM_TOKENIZERS.register(
+            "comma",
+            CallbackTokenizer::new(split_by_comma, calls_ptr as *mut c_void),
+        );
+
+        let col_tokenizer_map = TokenizerUtils::parse_tokenizer_json_to_config_map(
+            r#"{"col1": {"tokenizer": {"type": "custom", "name": "comma",
+                "filters": [{"type": "lowercase"}]}}}"#,
+        )
+        .unwrap();
+        let mut text_analyzer = col_tokenizer_map["col1"].text_analyzer.clone();
+        let mut tokens: Vec<(String, usize, usize)> = Vec::new();
+        text_analyzer
+            .token_stream("Red,green,,Blue")
+            .process(&mut |token| {
+                tokens.push((token.text.clone(), token.position, token.offset_from))
+            });
+        assert_eq!(
+            tokens,
+            vec![
+                ("red".to_string(), 0, 0),
+                ("green".to_string(), 1, 4),
+                ("blue".to_string(), 2, 11)
+            ]
+        );
+        assert_eq!(unsafe { *calls_ptr }, 1);
+
+        assert!(CUSTOM_TOKENIZERS.unregister("comma"));
+        assert!(TokenizerUtils::parse_tokenizer_json_to_config_map(
+            r#"{"col1": {"tokenizer": {"type": "custom", "name": "comma"}}}"#,
+        )
+        .is_err());
+        drop(unsafe { Box::from_raw(calls_ptr) });
+    }
+}
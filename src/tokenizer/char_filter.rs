@@ -0,0 +1,203 @@
+use regex::Regex;
+use tantivy::tokenizer::{TextAnalyzer, TextAnalyzerBuilder, Tokenizer};
+
+use super::vo::tokenizer_json_vo::ColumnCharFilter;
+
+// Elements whose content is not text.
+const RAW_TEXT_ELEMENTS: [&str; 2] = ["script", "style"];
+
+const HTML_ENTITIES: [(&str, char); 7] = [
+    ("&amp;", '&'),
+    ("&lt;", '<'),
+    ("&gt;", '>'),
+    ("&quot;", '"'),
+    ("&apos;", '\''),
+    ("&#39;", '\''),
+    ("&nbsp;", ' '),
+];
+
+/// Rewrites text before it's tokenized.
+#[derive(Debug, Clone)]
+pub enum CharFilter {
+    /// Blank out html tags, comments and content of `script`, `style`, decode common entities.
+    /// Stripped text keeps the byte length, so token offsets still point into the source text.
+    HtmlStrip,
+    /// Replace all matches of `regex` by `replacement`, `$1` refers to a capture group.
+    /// Token offsets point into the replaced text.
+    PatternReplace { regex: Regex, replacement: String },
+}
+
+impl CharFilter {
+    pub fn from_column_char_filter(char_filter: &ColumnCharFilter) -> Result<Self, String> {
+        match char_filter {
+            ColumnCharFilter::HtmlStrip => Ok(CharFilter::HtmlStrip),
+            ColumnCharFilter::PatternReplace {
+                pattern,
+                replacement,
+            } => Ok(CharFilter::PatternReplace {
+                regex: Regex::new(pattern).map_err(|e| e.to_string())?,
+                replacement: replacement.clone(),
+            }),
+        }
+    }
+
+    pub fn apply(&self, text: &str) -> String {
+        match self {
+            CharFilter::HtmlStrip => strip_html(text),
+            CharFilter::PatternReplace { regex, replacement } => {
+                regex.replace_all(text, replacement.as_str()).into_owned()
+            }
+        }
+    }
+}
+
+// Push `len` bytes of spaces.
+fn blank(stripped: &mut String, len: usize) {
+    stripped.push_str(&" ".repeat(len));
+}
+
+// Byte length of the tag, comment or raw text element starting at `rest`, `None` if `<` is text.
+fn markup_len(rest: &str) -> Option<usize> {
+    if rest.starts_with("<!--") {
+        return Some(rest.find("-->").map(|end| end + 3).unwrap_or(rest.len()));
+    }
+    let next = rest[1..].chars().next()?;
+    if !next.is_ascii_alphabetic() && next != '/' && next != '!' {
+        return None;
+    }
+    let tag_len = rest.find('>')? + 1;
+    let name: String = rest[1..]
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    if !RAW_TEXT_ELEMENTS.contains(&name.as_str()) {
+        return Some(tag_len);
+    }
+    // ASCII lowercase keeps byte offsets.
+    let lowercase = rest.to_ascii_lowercase();
+    let closing = format!("</{}", name);
+    match lowercase[tag_len..].find(&closing) {
+        Some(start) => {
+            let closing_start = tag_len + start;
+            Some(
+                lowercase[closing_start..]
+                    .find('>')
+                    .map(|end| closing_start + end + 1)
+                    .unwrap_or(rest.len()),
+            )
+        }
+        None => Some(rest.len()),
+    }
+}
+
+/// Strip html markup of `text`, see `CharFilter::HtmlStrip`.
+pub fn strip_html(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut pos = 0;
+    while let Some(c) = text[pos..].chars().next() {
+        let rest = &text[pos..];
+        if c == '<' {
+            if let Some(len) = markup_len(rest) {
+                blank(&mut stripped, len);
+                pos += len;
+                continue;
+            }
+        } else if c == '&' {
+            if let Some((entity, decoded)) = HTML_ENTITIES
+                .iter()
+                .find(|(entity, _)| rest.starts_with(entity))
+            {
+                stripped.push(*decoded);
+                blank(&mut stripped, entity.len() - decoded.len_utf8());
+                pos += entity.len();
+                continue;
+            }
+        }
+        stripped.push(c);
+        pos += c.len_utf8();
+    }
+    stripped
+}
+
+/// Tokenizer applying `char_filters` in order before text is tokenized by `inner`.
+#[derive(Clone)]
+pub struct CharFilterTokenizer<T> {
+    char_filters: Vec<CharFilter>,
+    inner: T,
+    buffer: String,
+}
+
+impl<T: Tokenizer> CharFilterTokenizer<T> {
+    pub fn new(inner: T, char_filters: Vec<CharFilter>) -> Self {
+        CharFilterTokenizer {
+            char_filters,
+            inner,
+            buffer: String::new(),
+        }
+    }
+}
+
+impl<T: Tokenizer> Tokenizer for CharFilterTokenizer<T> {
+    type TokenStream<'a> = T::TokenStream<'a>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        self.buffer.clear();
+        self.buffer.push_str(text);
+        for char_filter in &self.char_filters {
+            self.buffer = char_filter.apply(&self.buffer);
+        }
+        self.inner.token_stream(&self.buffer)
+    }
+}
+
+/// Builder of an analyzer tokenizing by `tokenizer`, text is rewritten by `char_filters` first.
+pub fn text_analyzer_builder<T: Tokenizer>(
+    tokenizer: T,
+    char_filters: &[CharFilter],
+) -> TextAnalyzerBuilder {
+    match char_filters.is_empty() {
+        true => TextAnalyzer::builder(tokenizer).dynamic(),
+        false => TextAnalyzer::builder(CharFilterTokenizer::new(tokenizer, char_filters.to_vec()))
+            .dynamic(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tantivy::tokenizer::SimpleTokenizer;
+
+    #[test]
+    fn test_strip_html() {
+        let html =
+            r#"<p class="x">Tom &amp; Jerry</p><script>var a = "<b>";</script><!-- c -->a < b"#;
+        let stripped = strip_html(html);
+        assert_eq!(stripped.len(), html.len());
+        assert_eq!(
+            stripped.split_whitespace().collect::<Vec<&str>>(),
+            vec!["Tom", "&", "Jerry", "a", "<", "b"]
+        );
+        // Offsets of tokens point into source text.
+        let start = stripped.find("Jerry").unwrap();
+        assert_eq!(&html[start..start + 5], "Jerry");
+    }
+
+    #[test]
+    fn test_char_filter_tokenizer() {
+        let char_filters = vec![
+            CharFilter::HtmlStrip,
+            CharFilter::PatternReplace {
+                regex: Regex::new(r"(\d{3})-(\d{4})").unwrap(),
+                replacement: "$1$2".to_string(),
+            },
+        ];
+        let mut text_analyzer =
+            text_analyzer_builder(SimpleTokenizer::default(), &char_filters).build();
+        let mut tokens: Vec<String> = Vec::new();
+        text_analyzer
+            .token_stream("<div>call <em>555-1234</em></div>")
+            .process(&mut |token| tokens.push(token.text.clone()));
+        assert_eq!(tokens, vec!["call".to_string(), "5551234".to_string()]);
+    }
+}
@@ -0,0 +1,204 @@
+use serde::{Deserialize, Serialize};
+use tantivy::schema::DateTimePrecision;
+use tantivy::time::format_description::well_known::Rfc3339;
+use tantivy::time::format_description::{self, FormatItem};
+use tantivy::time::{Date, Duration, OffsetDateTime, PrimitiveDateTime, UtcOffset};
+use tantivy::DateTime;
+
+/// Precision date values are truncated to when indexed and queried.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum DatePrecision {
+    #[default]
+    #[serde(rename = "seconds")]
+    Seconds,
+    #[serde(rename = "milliseconds")]
+    Milliseconds,
+    #[serde(rename = "microseconds")]
+    Microseconds,
+}
+
+impl DatePrecision {
+    pub fn to_tantivy(self) -> DateTimePrecision {
+        match self {
+            DatePrecision::Seconds => DateTimePrecision::Seconds,
+            DatePrecision::Milliseconds => DateTimePrecision::Milliseconds,
+            DatePrecision::Microseconds => DateTimePrecision::Microseconds,
+        }
+    }
+}
+
+/// Format a date value can be given in.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum DateInputFormat {
+    /// e.g. `2024-03-01T08:30:00.125+08:00`.
+    #[serde(rename = "rfc3339")]
+    Rfc3339,
+    /// Integer seconds since unix epoch.
+    #[serde(rename = "epoch_seconds")]
+    EpochSeconds,
+    /// Integer milliseconds since unix epoch.
+    #[serde(rename = "epoch_millis")]
+    EpochMillis,
+}
+
+impl DateInputFormat {
+    fn parse(self, value: &str) -> Option<DateTime> {
+        match self {
+            DateInputFormat::Rfc3339 => OffsetDateTime::parse(value, &Rfc3339)
+                .ok()
+                .map(DateTime::from_utc),
+            DateInputFormat::EpochSeconds => value
+                .parse::<i64>()
+                .ok()
+                .filter(|seconds| seconds.checked_mul(1_000_000_000).is_some())
+                .map(DateTime::from_timestamp_secs),
+            DateInputFormat::EpochMillis => value
+                .parse::<i64>()
+                .ok()
+                .filter(|millis| millis.checked_mul(1_000_000).is_some())
+                .map(DateTime::from_timestamp_millis),
+        }
+    }
+}
+
+/// Formats and precision of a date column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DateFormat {
+    pub input_formats: Vec<DateInputFormat>,
+    pub precision: DatePrecision,
+}
+
+impl DateFormat {
+    /// Parse `value` by the first input format accepting it, truncated to precision.
+    /// Both epoch formats accept any integer, so only the first listed one is used.
+    pub fn parse(&self, value: &str) -> Result<DateTime, String> {
+        let value: &str = value.trim();
+        self.input_formats
+            .iter()
+            .find_map(|input_format| input_format.parse(value))
+            .map(|date| date.truncate(self.precision.to_tantivy()))
+            .ok_or_else(|| {
+                format!(
+                    "Can't parse `{}` as date of formats {:?}",
+                    value, self.input_formats
+                )
+            })
+    }
+
+    /// Parse a bound of range query. Besides input formats of the column, it can be a local date
+    /// `2024-03-01` or date time `2024-03-01 08:30:00[.fraction]` (`T` separator also accepted)
+    /// in timezone of `utc_offset`, e.g. a day boundary in session timezone of host.
+    /// A local date is the start of the day as lower bound and the end of the day as upper bound.
+    pub fn parse_bound(
+        &self,
+        value: &str,
+        utc_offset: UtcOffset,
+        is_upper: bool,
+    ) -> Result<DateTime, String> {
+        if let Ok(date) = self.parse(value) {
+            return Ok(date);
+        }
+        let value: &str = value.trim();
+        let local: Option<OffsetDateTime> = match parse_local_date(value) {
+            Some(date) if is_upper => date
+                .next_day()
+                .map(|next_day| next_day.midnight().assume_offset(utc_offset))
+                .map(|next_day| next_day - Duration::nanoseconds(1)),
+            Some(date) => Some(date.midnight().assume_offset(utc_offset)),
+            None => parse_local_date_time(value).map(|date| date.assume_offset(utc_offset)),
+        };
+        local
+            .map(|date| DateTime::from_utc(date).truncate(self.precision.to_tantivy()))
+            .ok_or_else(|| {
+                format!(
+                    "Can't parse `{}` as date bound of formats {:?} or local date time",
+                    value, self.input_formats
+                )
+            })
+    }
+}
+
+fn parse_local_date(value: &str) -> Option<Date> {
+    let format: Vec<FormatItem<'_>> = format_description::parse("[year]-[month]-[day]").ok()?;
+    Date::parse(value, &format).ok()
+}
+
+fn parse_local_date_time(value: &str) -> Option<PrimitiveDateTime> {
+    let format: Vec<FormatItem<'_>> = format_description::parse(
+        "[year]-[month]-[day] [hour]:[minute]:[second][optional [.[subsecond]]]",
+    )
+    .ok()?;
+    // Date and time are separated by `T` or a space.
+    let value: String = match value.char_indices().nth(10) {
+        Some((index, 'T')) => format!("{} {}", &value[..index], &value[index + 1..]),
+        _ => value.to_string(),
+    };
+    PrimitiveDateTime::parse(&value, &format).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_date_format_parse() {
+        let date_format = DateFormat {
+            input_formats: vec![DateInputFormat::Rfc3339, DateInputFormat::EpochMillis],
+            precision: DatePrecision::Milliseconds,
+        };
+        let date = date_format
+            .parse("2024-03-01T08:30:00.125678+08:00")
+            .unwrap();
+        assert_eq!(date.into_timestamp_micros(), 1_709_253_000_125_000);
+        assert_eq!(
+            date_format.parse("1709253000125").unwrap(),
+            DateTime::from_timestamp_millis(1_709_253_000_125)
+        );
+        assert!(date_format.parse("2024-03-01").is_err());
+        assert!(date_format.parse("").is_err());
+
+        // Seconds precision drops millis, epoch seconds listed first wins over epoch millis.
+        let date_format = DateFormat {
+            input_formats: vec![DateInputFormat::EpochSeconds, DateInputFormat::EpochMillis],
+            precision: DatePrecision::Seconds,
+        };
+        assert_eq!(
+            date_format.parse(" 1709253000 ").unwrap(),
+            DateTime::from_timestamp_secs(1_709_253_000)
+        );
+        assert!(date_format.parse("2024-03-01T08:30:00Z").is_err());
+        assert!(date_format.parse(&i64::MAX.to_string()).is_err());
+    }
+
+    #[test]
+    fn test_date_format_parse_bound() {
+        let date_format = DateFormat {
+            input_formats: vec![DateInputFormat::Rfc3339],
+            precision: DatePrecision::Milliseconds,
+        };
+        let utc_plus_8 = UtcOffset::from_hms(8, 0, 0).unwrap();
+        let parse_bound = |value: &str, is_upper: bool| {
+            date_format
+                .parse_bound(value, utc_plus_8, is_upper)
+                .map(|date| date.into_timestamp_millis())
+        };
+        // 2024-03-01 in UTC+8 is [2024-02-29T16:00:00Z, 2024-03-01T16:00:00Z).
+        assert_eq!(parse_bound("2024-03-01", false), Ok(1_709_222_400_000));
+        assert_eq!(parse_bound("2024-03-01", true), Ok(1_709_308_799_999));
+        assert_eq!(
+            parse_bound("2024-03-01 08:00:00", false),
+            Ok(1_709_251_200_000)
+        );
+        assert_eq!(
+            parse_bound("2024-03-01T08:00:00.5", true),
+            Ok(1_709_251_200_500)
+        );
+        // Bounds with offset keep their own offset.
+        assert_eq!(
+            parse_bound("2024-03-01T00:00:00Z", false),
+            Ok(1_709_251_200_000)
+        );
+        assert!(parse_bound("2024-13-01", false).is_err());
+        assert!(parse_bound("yesterday", true).is_err());
+    }
+}
@@ -6,12 +6,27 @@ use serde::{Deserialize, Serialize};
 pub struct IndexParameterDTO {
     #[serde(default = "empty_json_parameter")]
     pub tokenizers_json_parameter: String,
+    /// Build segments deterministically, see `create_index_with_build_mode`.
+    #[serde(default)]
+    pub deterministic_build: bool,
+    /// Versions of this crate and of tantivy index format which wrote the index,
+    /// zero and empty for indexes written before they were recorded.
+    #[serde(default)]
+    pub format_version: u32,
+    #[serde(default)]
+    pub crate_version: String,
+    #[serde(default)]
+    pub tantivy_format_version: u32,
 }
 
 impl Default for IndexParameterDTO {
     fn default() -> Self {
         Self {
             tokenizers_json_parameter: "{}".to_string(),
+            deterministic_build: false,
+            format_version: 0,
+            crate_version: String::new(),
+            tantivy_format_version: 0,
         }
     }
 }
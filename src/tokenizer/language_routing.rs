@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use cang_jie::{CangJieTokenizer, TokenizerOption};
+use jieba_rs::Jieba;
+use tantivy::schema::{Field, FieldType, Schema};
+use tantivy::tokenizer::{LowerCaser, RemoveLongFilter, SimpleTokenizer, Stemmer, TextAnalyzer};
+
+use super::char_filter::{text_analyzer_builder, CharFilter};
+use super::vo::language::SupportRoutingLanguage;
+
+/// Language sub-field of `column_name`, e.g. `text_en`.
+pub fn language_field_name(column_name: &str, language: SupportRoutingLanguage) -> String {
+    format!("{}_{}", column_name, language.code())
+}
+
+/// Tokenizer of a language sub-field, e.g. `text_lang_en`.
+/// Column tokenizers are named `{column}_{tokenizer type}`, so sub-fields can be told apart
+/// from host columns by their tokenizer name.
+pub fn language_tokenizer_name(column_name: &str, language: SupportRoutingLanguage) -> String {
+    format!("{}_lang_{}", column_name, language.code())
+}
+
+/// Analyzer of a language sub-field, text is rewritten by `char_filters` of the column first.
+pub fn language_analyzer(
+    language: SupportRoutingLanguage,
+    char_filters: &[CharFilter],
+) -> TextAnalyzer {
+    match language.to_language() {
+        Some(stem_language) => text_analyzer_builder(SimpleTokenizer::default(), char_filters)
+            .filter_dynamic(RemoveLongFilter::limit(40))
+            .filter_dynamic(LowerCaser)
+            .filter_dynamic(Stemmer::new(stem_language))
+            .build(),
+        None => text_analyzer_builder(
+            CangJieTokenizer {
+                worker: Arc::new(Jieba::default()),
+                option: TokenizerOption::ForSearch { hmm: false },
+            },
+            char_filters,
+        )
+        .build(),
+    }
+}
+
+/// Detect language of `text`, `None` if it isn't one of `languages` or confidence is too low.
+pub fn detect_language(
+    text: &str,
+    languages: &[SupportRoutingLanguage],
+    min_confidence: f64,
+) -> Option<SupportRoutingLanguage> {
+    let info = whatlang::detect(text)?;
+    if info.confidence() < min_confidence {
+        return None;
+    }
+    languages
+        .iter()
+        .find(|language| language.to_whatlang() == info.lang())
+        .copied()
+}
+
+/// Language sub-fields of `column_name` declared in `schema`, queries of the column fan out to them.
+pub fn language_subfields(schema: &Schema, column_name: &str) -> Vec<Field> {
+    SupportRoutingLanguage::ALL
+        .iter()
+        .filter_map(|language| {
+            let field = schema
+                .get_field(&language_field_name(column_name, *language))
+                .ok()?;
+            match schema.get_field_entry(field).field_type() {
+                FieldType::Str(text_options) => text_options
+                    .get_indexing_options()
+                    .filter(|indexing| {
+                        indexing.tokenizer() == language_tokenizer_name(column_name, *language)
+                    })
+                    .map(|_| field),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Column of each language sub-field declared in `schema`.
+pub fn language_subfield_columns(schema: &Schema) -> HashMap<Field, String> {
+    let mut subfield_columns: HashMap<Field, String> = HashMap::new();
+    for (field, field_entry) in schema.fields() {
+        for subfield in language_subfields(schema, field_entry.name()) {
+            subfield_columns.insert(subfield, schema.get_field_name(field).to_string());
+        }
+    }
+    subfield_columns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tantivy::schema::{TextFieldIndexing, TextOptions, TEXT};
+
+    #[test]
+    fn test_detect_language() {
+        let languages = [
+            SupportRoutingLanguage::English,
+            SupportRoutingLanguage::Chinese,
+        ];
+        assert_eq!(
+            detect_language(
+                "Ancient empires rise and fall, shaping the course of history.",
+                &languages,
+                0.5
+            ),
+            Some(SupportRoutingLanguage::English)
+        );
+        assert_eq!(
+            detect_language("古代帝国的兴衰塑造了历史的进程。", &languages, 0.5),
+            Some(SupportRoutingLanguage::Chinese)
+        );
+        // Languages not routed stay in the column.
+        assert_eq!(
+            detect_language(
+                "Les empires anciens naissent et tombent, façonnant le cours de l'histoire.",
+                &languages,
+                0.5
+            ),
+            None
+        );
+        assert_eq!(detect_language("", &languages, 0.0), None);
+    }
+
+    #[test]
+    fn test_language_subfields() {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("text", TEXT);
+        let text_en_field = schema_builder.add_text_field(
+            "text_en",
+            TextOptions::default().set_indexing_options(
+                TextFieldIndexing::default().set_tokenizer(&language_tokenizer_name(
+                    "text",
+                    SupportRoutingLanguage::English,
+                )),
+            ),
+        );
+        // A host column named like a sub-field isn't a sub-field.
+        schema_builder.add_text_field("text_zh", TEXT);
+        let schema = schema_builder.build();
+
+        assert_eq!(language_subfields(&schema, "text"), vec![text_en_field]);
+        assert!(language_subfields(&schema, "text_en").is_empty());
+    }
+}
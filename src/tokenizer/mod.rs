@@ -1,3 +1,12 @@
+pub mod callback_tokenizer;
+pub mod char_filter;
+pub mod date_format;
 pub mod dto;
+pub mod language_routing;
+pub mod pinyin_filter;
+pub mod shingle_filter;
+pub mod sparse_vector_format;
+pub mod token_predicate_filter;
 pub mod tokenizer_utils;
+pub mod vector_format;
 pub mod vo;
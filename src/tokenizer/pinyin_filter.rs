@@ -0,0 +1,170 @@
+use pinyin::ToPinyin;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream, Tokenizer};
+
+/// `PinyinFilter` transliterates tokens containing hanzi to pinyin, e.g. `中国` to
+/// `zhongguo` (full) and `zg` (first letters). Pinyin tokens share position and offsets
+/// of the hanzi token, so phrase queries and highlights keep working.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PinyinFilter {
+    /// Emit full pinyin of a token.
+    pub full: bool,
+    /// Emit first letters of pinyin of a token.
+    pub first_letter: bool,
+    /// Keep hanzi token, otherwise it's replaced by its pinyin tokens.
+    pub keep_original: bool,
+}
+
+impl Default for PinyinFilter {
+    fn default() -> Self {
+        PinyinFilter {
+            full: true,
+            first_letter: false,
+            keep_original: true,
+        }
+    }
+}
+
+impl PinyinFilter {
+    /// Pinyin tokens of `text`, empty if `text` doesn't contain hanzi.
+    /// Characters without pinyin are kept as they are.
+    pub fn transliterate(&self, text: &str) -> Vec<String> {
+        let mut has_hanzi = false;
+        let mut full = String::new();
+        let mut first_letter = String::new();
+        for (c, pinyin) in text.chars().zip(text.to_pinyin()) {
+            match pinyin {
+                Some(pinyin) => {
+                    has_hanzi = true;
+                    full.push_str(pinyin.plain());
+                    first_letter.push_str(pinyin.first_letter());
+                }
+                None => {
+                    full.push(c);
+                    first_letter.push(c);
+                }
+            }
+        }
+        let mut tokens: Vec<String> = Vec::new();
+        if !has_hanzi {
+            return tokens;
+        }
+        if self.full {
+            tokens.push(full);
+        }
+        if self.first_letter && !tokens.contains(&first_letter) {
+            tokens.push(first_letter);
+        }
+        tokens
+    }
+}
+
+impl TokenFilter for PinyinFilter {
+    type Tokenizer<T: Tokenizer> = PinyinFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> PinyinFilterWrapper<T> {
+        PinyinFilterWrapper {
+            filter: self,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct PinyinFilterWrapper<T> {
+    filter: PinyinFilter,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for PinyinFilterWrapper<T> {
+    type TokenStream<'a> = PinyinTokenStream<T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        PinyinTokenStream {
+            filter: self.filter,
+            tail: self.inner.token_stream(text),
+            token: Token::default(),
+            pending: Vec::new(),
+        }
+    }
+}
+
+pub struct PinyinTokenStream<T> {
+    filter: PinyinFilter,
+    tail: T,
+    token: Token,
+    // Pinyin tokens of current token not emitted yet, in reverse order.
+    pending: Vec<String>,
+}
+
+impl<T: TokenStream> TokenStream for PinyinTokenStream<T> {
+    fn advance(&mut self) -> bool {
+        if let Some(text) = self.pending.pop() {
+            self.token.text = text;
+            return true;
+        }
+        if !self.tail.advance() {
+            return false;
+        }
+        self.token = self.tail.token().clone();
+        self.pending = self.filter.transliterate(&self.token.text);
+        self.pending.reverse();
+        if !self.filter.keep_original {
+            if let Some(text) = self.pending.pop() {
+                self.token.text = text;
+            }
+        }
+        true
+    }
+
+    fn token(&self) -> &Token {
+        &self.token
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.token
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tantivy::tokenizer::{TextAnalyzer, WhitespaceTokenizer};
+
+    fn analyze(filter: PinyinFilter, text: &str) -> Vec<(String, usize)> {
+        let mut text_analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(filter)
+            .build();
+        let mut tokens: Vec<(String, usize)> = Vec::new();
+        text_analyzer
+            .token_stream(text)
+            .process(&mut |token| tokens.push((token.text.clone(), token.position)));
+        tokens
+    }
+
+    #[test]
+    fn test_pinyin_filter() {
+        assert_eq!(
+            analyze(PinyinFilter::default(), "中国 tea"),
+            vec![
+                ("中国".to_string(), 0),
+                ("zhongguo".to_string(), 0),
+                ("tea".to_string(), 1)
+            ]
+        );
+        let filter = PinyinFilter {
+            full: true,
+            first_letter: true,
+            keep_original: false,
+        };
+        assert_eq!(
+            analyze(filter, "北京 2024"),
+            vec![
+                ("beijing".to_string(), 0),
+                ("bj".to_string(), 0),
+                ("2024".to_string(), 1)
+            ]
+        );
+        assert_eq!(filter.transliterate("a股"), vec!["agu", "ag"]);
+        assert!(filter.transliterate("latin").is_empty());
+    }
+}
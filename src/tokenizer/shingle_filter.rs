@@ -0,0 +1,160 @@
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream, Tokenizer};
+
+/// `ShingleFilter` adds word n-grams of adjacent tokens, e.g. `new york city` gets bigrams
+/// `new york` and `york city`. A shingle takes position of its first token and spans offsets
+/// of all its tokens, so word order is matched by terms even if positions aren't indexed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShingleFilter {
+    /// Words of the shortest shingle, at least 2.
+    pub min_shingle_size: usize,
+    /// Words of the longest shingle.
+    pub max_shingle_size: usize,
+    /// Keep single word tokens.
+    pub output_unigrams: bool,
+    /// Joins words of a shingle.
+    pub separator: String,
+}
+
+impl Default for ShingleFilter {
+    fn default() -> Self {
+        ShingleFilter {
+            min_shingle_size: 2,
+            max_shingle_size: 2,
+            output_unigrams: true,
+            separator: " ".to_string(),
+        }
+    }
+}
+
+impl ShingleFilter {
+    // Unigrams and shingles of `tokens` ordered by position, shingles follow their first word.
+    fn shingles(&self, tokens: Vec<Token>) -> Vec<Token> {
+        let mut output: Vec<Token> = Vec::new();
+        for start in 0..tokens.len() {
+            if self.output_unigrams {
+                output.push(tokens[start].clone());
+            }
+            for size in self.min_shingle_size..=self.max_shingle_size {
+                let words = match tokens.get(start..start + size) {
+                    Some(words) => words,
+                    None => break,
+                };
+                let text: Vec<&str> = words.iter().map(|word| word.text.as_str()).collect();
+                output.push(Token {
+                    offset_from: words[0].offset_from,
+                    offset_to: words[size - 1].offset_to,
+                    position: words[0].position,
+                    text: text.join(&self.separator),
+                    position_length: size,
+                });
+            }
+        }
+        output
+    }
+}
+
+impl TokenFilter for ShingleFilter {
+    type Tokenizer<T: Tokenizer> = ShingleFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> ShingleFilterWrapper<T> {
+        ShingleFilterWrapper {
+            filter: self,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ShingleFilterWrapper<T> {
+    filter: ShingleFilter,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for ShingleFilterWrapper<T> {
+    type TokenStream<'a> = ShingleTokenStream;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        // Shingles need the following words, tokens of the text are collected first.
+        let mut tokens: Vec<Token> = Vec::new();
+        self.inner
+            .token_stream(text)
+            .process(&mut |token| tokens.push(token.clone()));
+        let mut pending: Vec<Token> = self.filter.shingles(tokens);
+        pending.reverse();
+        ShingleTokenStream {
+            pending,
+            token: Token::default(),
+        }
+    }
+}
+
+pub struct ShingleTokenStream {
+    // Tokens not emitted yet, in reverse order.
+    pending: Vec<Token>,
+    token: Token,
+}
+
+impl TokenStream for ShingleTokenStream {
+    fn advance(&mut self) -> bool {
+        match self.pending.pop() {
+            Some(token) => {
+                self.token = token;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn token(&self) -> &Token {
+        &self.token
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.token
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tantivy::tokenizer::{SimpleTokenizer, TextAnalyzer};
+
+    fn analyze(filter: ShingleFilter, text: &str) -> Vec<(String, usize)> {
+        let mut text_analyzer = TextAnalyzer::builder(SimpleTokenizer::default())
+            .filter(filter)
+            .build();
+        let mut tokens: Vec<(String, usize)> = Vec::new();
+        text_analyzer
+            .token_stream(text)
+            .process(&mut |token| tokens.push((token.text.clone(), token.position)));
+        tokens
+    }
+
+    #[test]
+    fn test_shingle_filter() {
+        let tokens = analyze(ShingleFilter::default(), "new york city");
+        assert_eq!(
+            tokens,
+            vec![
+                ("new".to_string(), 0),
+                ("new york".to_string(), 0),
+                ("york".to_string(), 1),
+                ("york city".to_string(), 1),
+                ("city".to_string(), 2),
+            ]
+        );
+
+        let filter = ShingleFilter {
+            min_shingle_size: 2,
+            max_shingle_size: 3,
+            output_unigrams: false,
+            separator: "_".to_string(),
+        };
+        let tokens: Vec<String> = analyze(filter.clone(), "a b c")
+            .into_iter()
+            .map(|(text, _)| text)
+            .collect();
+        assert_eq!(tokens, vec!["a_b", "a_b_c", "b_c"]);
+        assert!(analyze(filter, "single").is_empty());
+    }
+}
@@ -0,0 +1,109 @@
+use serde_json::{Map, Value};
+use tantivy::tokenizer::{PreTokenizedString, Token};
+
+/// Tokenizer of sparse vector columns. Their terms are pre-tokenized, the name only tells
+/// sparse vector columns apart from text columns.
+pub const SPARSE_VECTOR_TOKENIZER: &str = "sparse_vector";
+
+/// Largest quantized weight of a term, each unit is one token added while indexing.
+pub const MAX_QUANTIZED_WEIGHT: u32 = 1 << 16;
+
+/// Quantization of a sparse vector column. Term weights are indexed as term frequencies
+/// `round(weight * scale)`, so the posting lists hold them and weights have precision `1 / scale`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SparseVectorFormat {
+    pub scale: u32,
+}
+
+impl SparseVectorFormat {
+    /// Parse term weights of a JSON object, e.g. `{"apple": 1.2, "fruit": 0.4}`.
+    /// Weights should be finite and non-negative.
+    pub fn parse(value: &str) -> Result<Vec<(String, f32)>, String> {
+        let object: Map<String, Value> = serde_json::from_str(value)
+            .map_err(|e| format!("Can't parse `{}` as sparse vector: {}", value, e))?;
+        object
+            .into_iter()
+            .map(|(term, weight)| {
+                let weight: f32 = weight
+                    .as_f64()
+                    .map(|weight| weight as f32)
+                    .filter(|weight| weight.is_finite() && *weight >= 0.0)
+                    .ok_or_else(|| {
+                        format!(
+                            "weight of term `{}` should be a non-negative number, got {}",
+                            term, weight
+                        )
+                    })?;
+                Ok((term, weight))
+            })
+            .collect()
+    }
+
+    pub fn quantize(&self, weight: f32) -> Result<u32, String> {
+        let quantized: f64 = (weight as f64 * self.scale as f64).round();
+        if quantized > MAX_QUANTIZED_WEIGHT as f64 {
+            return Err(format!(
+                "weight {} exceeds {} with scale {}",
+                weight,
+                MAX_QUANTIZED_WEIGHT as f64 / self.scale as f64,
+                self.scale
+            ));
+        }
+        Ok(quantized as u32)
+    }
+
+    /// Weight indexed as `term_freq`.
+    #[inline]
+    pub fn weight(&self, term_freq: u32) -> f32 {
+        term_freq as f32 / self.scale as f32
+    }
+
+    /// Tokens of `text` parsed by `parse`, each term is repeated by its quantized weight.
+    /// Terms quantized to 0 are dropped.
+    pub fn tokenize(&self, text: &str) -> Result<PreTokenizedString, String> {
+        let mut tokens: Vec<Token> = Vec::new();
+        for (term, weight) in Self::parse(text)? {
+            let term_freq: u32 = self.quantize(weight)?;
+            for _ in 0..term_freq {
+                tokens.push(Token {
+                    offset_from: 0,
+                    offset_to: 0,
+                    position: tokens.len(),
+                    text: term.clone(),
+                    position_length: 1,
+                });
+            }
+        }
+        Ok(PreTokenizedString {
+            text: String::new(),
+            tokens,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparse_vector_format() {
+        let weights = SparseVectorFormat::parse(r#"{"apple": 1.25, "fruit": 0}"#).unwrap();
+        assert_eq!(
+            weights,
+            vec![("apple".to_string(), 1.25), ("fruit".to_string(), 0.0)]
+        );
+        assert!(SparseVectorFormat::parse(r#"{"apple": -1}"#).is_err());
+        assert!(SparseVectorFormat::parse(r#"{"apple": "1"}"#).is_err());
+        assert!(SparseVectorFormat::parse("[1, 2]").is_err());
+
+        let format = SparseVectorFormat { scale: 10 };
+        assert_eq!(format.quantize(1.25), Ok(13));
+        assert_eq!(format.weight(13), 1.3);
+        assert!(format.quantize(10_000.0).is_err());
+
+        // Terms are repeated by quantized weights, `fruit` is dropped.
+        let tokens = format.tokenize(r#"{"apple": 0.3, "fruit": 0.01}"#).unwrap();
+        assert_eq!(tokens.tokens.len(), 3);
+        assert!(tokens.tokens.iter().all(|token| token.text == "apple"));
+    }
+}
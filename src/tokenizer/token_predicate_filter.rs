@@ -0,0 +1,122 @@
+use regex::Regex;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream, Tokenizer};
+
+/// Decides whether a token is kept by `TokenPredicateFilter`.
+#[derive(Debug, Clone)]
+pub enum TokenPredicate {
+    /// Keep tokens of `min..=max` chars, `None` means no upper limit.
+    Length { min: usize, max: Option<usize> },
+    /// Keep (`keep` is true) or drop tokens whose whole text matches `regex`.
+    Pattern { regex: Regex, keep: bool },
+}
+
+impl TokenPredicate {
+    /// Pattern predicate matching whole token text.
+    pub fn pattern(pattern: &str, keep: bool) -> Result<Self, String> {
+        let regex = Regex::new(&format!("^(?:{})$", pattern)).map_err(|e| e.to_string())?;
+        Ok(TokenPredicate::Pattern { regex, keep })
+    }
+
+    pub fn accepts(&self, text: &str) -> bool {
+        match self {
+            TokenPredicate::Length { min, max } => {
+                let length = text.chars().count();
+                length >= *min && !matches!(max, Some(max) if length > *max)
+            }
+            TokenPredicate::Pattern { regex, keep } => regex.is_match(text) == *keep,
+        }
+    }
+}
+
+/// `TokenPredicateFilter` drops tokens not accepted by its predicate, e.g. serial numbers
+/// or base64 blobs which would only bloat the term dictionary.
+#[derive(Debug, Clone)]
+pub struct TokenPredicateFilter(pub TokenPredicate);
+
+impl TokenFilter for TokenPredicateFilter {
+    type Tokenizer<T: Tokenizer> = TokenPredicateFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> TokenPredicateFilterWrapper<T> {
+        TokenPredicateFilterWrapper {
+            predicate: self.0,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct TokenPredicateFilterWrapper<T> {
+    predicate: TokenPredicate,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for TokenPredicateFilterWrapper<T> {
+    type TokenStream<'a> = TokenPredicateTokenStream<'a, T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        TokenPredicateTokenStream {
+            predicate: &self.predicate,
+            tail: self.inner.token_stream(text),
+        }
+    }
+}
+
+pub struct TokenPredicateTokenStream<'a, T> {
+    predicate: &'a TokenPredicate,
+    tail: T,
+}
+
+impl<'a, T: TokenStream> TokenStream for TokenPredicateTokenStream<'a, T> {
+    fn advance(&mut self) -> bool {
+        while self.tail.advance() {
+            if self.predicate.accepts(&self.tail.token().text) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tantivy::tokenizer::{TextAnalyzer, WhitespaceTokenizer};
+
+    fn analyze(predicate: TokenPredicate, text: &str) -> Vec<String> {
+        let mut text_analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(TokenPredicateFilter(predicate))
+            .build();
+        let mut tokens: Vec<String> = Vec::new();
+        text_analyzer
+            .token_stream(text)
+            .process(&mut |token| tokens.push(token.text.clone()));
+        tokens
+    }
+
+    #[test]
+    fn test_token_predicate_filter() {
+        let length = TokenPredicate::Length {
+            min: 2,
+            max: Some(5),
+        };
+        assert_eq!(analyze(length, "a bb 中文 longest"), vec!["bb", "中文"]);
+
+        // Pattern matches whole token.
+        let drop_serials = TokenPredicate::pattern(r"[A-Z0-9]{8,}", false).unwrap();
+        assert_eq!(
+            analyze(drop_serials, "order SN4F7K29QX shipped A1"),
+            vec!["order", "shipped", "A1"]
+        );
+        let keep_words = TokenPredicate::pattern(r"\p{Alphabetic}+", true).unwrap();
+        assert_eq!(analyze(keep_words, "abc a1 x"), vec!["abc", "x"]);
+        assert!(TokenPredicate::pattern("(", true).is_err());
+    }
+}
@@ -3,20 +3,33 @@ use std::{str::FromStr, sync::Arc};
 use cang_jie::{CangJieTokenizer, TokenizerOption};
 use jieba_rs::Jieba;
 use tantivy::{
+    schema::IndexRecordOption,
     tokenizer::{
-        LowerCaser, NgramTokenizer, RawTokenizer, RemoveLongFilter, SimpleTokenizer, Stemmer,
-        StopWordFilter, TextAnalyzer, WhitespaceTokenizer,
+        AsciiFoldingFilter, LowerCaser, NgramTokenizer, RawTokenizer, RemoveLongFilter,
+        SimpleTokenizer, Stemmer, StopWordFilter, TextAnalyzer, TextAnalyzerBuilder,
+        WhitespaceTokenizer,
     },
     Index,
 };
 
+use crate::common::constants::CUSTOM_TOKENIZERS;
 use crate::common::errors::TokenizerUtilsError;
 
+use super::char_filter::{text_analyzer_builder, CharFilter};
+use super::date_format::DateFormat;
+use super::language_routing::{language_analyzer, language_field_name, language_tokenizer_name};
+use super::pinyin_filter::PinyinFilter;
+use super::shingle_filter::ShingleFilter;
+use super::sparse_vector_format::{SparseVectorFormat, SPARSE_VECTOR_TOKENIZER};
+use super::token_predicate_filter::{TokenPredicate, TokenPredicateFilter};
+use super::vector_format::VectorFormat;
 use super::vo::{
-    language::{SupportFilterLanguage, SupportLanguageAlgorithm},
-    tokenizer_json_vo::ColumnTokenizer,
+    language::{SupportFilterLanguage, SupportLanguageAlgorithm, SupportRoutingLanguage},
+    tokenizer_json_vo::{
+        ColumnRecordOption, ColumnTokenFilter, ColumnTokenizer, IndexSort, PatternFilterMode,
+    },
     tokenizer_types::TokenizerType,
-    tokenizers_vo::TokenizerConfig,
+    tokenizers_vo::{LanguageRoutingConfig, TokenizerConfig},
 };
 
 pub struct TokenizerUtils;
@@ -49,6 +62,15 @@ impl TokenizerUtils {
                     column_name, tokenizer_name
                 ))
             }
+            TokenizerType::KeywordLowercase(tokenizer_name) => {
+                index
+                    .tokenizers()
+                    .register(&format!("{}_{}", column_name, tokenizer_name), tokenizer);
+                Ok(format!(
+                    "`{}-{}` tokenizer has been registered",
+                    column_name, tokenizer_name
+                ))
+            }
             TokenizerType::Simple(tokenizer_name) => {
                 index
                     .tokenizers()
@@ -94,6 +116,15 @@ impl TokenizerUtils {
                     column_name, tokenizer_name
                 ))
             }
+            TokenizerType::Custom(tokenizer_name) => {
+                index
+                    .tokenizers()
+                    .register(&format!("{}_{}", column_name, tokenizer_name), tokenizer);
+                Ok(format!(
+                    "`{}-{}` tokenizer has been registered",
+                    column_name, tokenizer_name
+                ))
+            }
             TokenizerType::I64(tokenizer_name) => Ok(format!(
                 "`{}-{}` tokenizer is not text-based, no need to register",
                 column_name, tokenizer_name
@@ -106,12 +137,59 @@ impl TokenizerUtils {
                 "`{}-{}` tokenizer is not text-based, no need to register",
                 column_name, tokenizer_name
             )),
+            TokenizerType::Date(tokenizer_name) => Ok(format!(
+                "`{}-{}` tokenizer is not text-based, no need to register",
+                column_name, tokenizer_name
+            )),
+            TokenizerType::GeoPoint(tokenizer_name) => Ok(format!(
+                "`{}-{}` tokenizer is not text-based, no need to register",
+                column_name, tokenizer_name
+            )),
+            TokenizerType::Vector(tokenizer_name) => Ok(format!(
+                "`{}-{}` tokenizer is not text-based, no need to register",
+                column_name, tokenizer_name
+            )),
+            // Terms are pre-tokenized, tantivy still requires the tokenizer of indexed text.
+            TokenizerType::SparseVector(tokenizer_name) => {
+                index.tokenizers().register(
+                    SPARSE_VECTOR_TOKENIZER,
+                    TextAnalyzer::from(RawTokenizer::default()),
+                );
+                Ok(format!(
+                    "`{}-{}` tokenizer has been registered",
+                    column_name, tokenizer_name
+                ))
+            }
             _ => Err(TokenizerUtilsError::UnsupportedTokenizerType(
                 tokenizer_type.name().to_string(),
             )),
         }
     }
 
+    /// Register analyzers of language sub-fields of `column_name` to the index.
+    pub fn register_language_tokenizers(
+        index: &mut Index,
+        column_name: &str,
+        tokenizer_config: &TokenizerConfig,
+    ) {
+        if let Some(language_routing) = &tokenizer_config.language_routing {
+            for (language, analyzer) in &language_routing.languages {
+                index.tokenizers().register(
+                    &language_tokenizer_name(column_name, *language),
+                    analyzer.clone(),
+                );
+            }
+        }
+    }
+
+    /// Parse index sort option from index json parameter, `None` if it isn't configured.
+    pub fn parse_index_sort(json_str: &str) -> Result<Option<IndexSort>, TokenizerUtilsError> {
+        let config: crate::tokenizer::vo::tokenizer_json_vo::Config =
+            serde_json::from_str(json_str)
+                .map_err(|e| TokenizerUtilsError::JsonDeserializeError(e.to_string()))?;
+        Ok(config.get_index_sort().cloned())
+    }
+
     pub fn parse_tokenizer_json_to_config_map(
         json_str: &str,
     ) -> Result<std::collections::HashMap<String, TokenizerConfig>, TokenizerUtilsError> {
@@ -123,239 +201,566 @@ impl TokenizerUtils {
             std::collections::HashMap::new();
 
         for (col_name, col) in config.get_columns() {
-            match col.get_tokenizer() {
-                ColumnTokenizer::Default { store_doc } => {
-                    let analyzer = TextAnalyzer::builder(SimpleTokenizer::default())
-                        .filter(RemoveLongFilter::limit(40))
-                        .filter(LowerCaser)
-                        .build();
-                    let tokenizer_config = TokenizerConfig::new(
-                        TokenizerType::Default("default".to_string()),
-                        analyzer,
-                        *store_doc,
-                    );
-                    tokenizer_map.insert(col_name.to_string(), tokenizer_config);
-                }
-                ColumnTokenizer::Raw { store_doc } => {
-                    let analyzer = TextAnalyzer::builder(RawTokenizer::default()).build();
-                    let tokenizer_config = TokenizerConfig::new(
-                        TokenizerType::Raw("raw".to_string()),
-                        analyzer,
-                        *store_doc,
-                    );
-                    tokenizer_map.insert(col_name.to_string(), tokenizer_config);
-                }
-                ColumnTokenizer::Simple {
-                    store_doc,
-                    stop_word_filters,
-                    length_limit,
-                    case_sensitive,
-                } => {
-                    let mut builder = TextAnalyzer::builder(SimpleTokenizer::default()).dynamic();
+            let char_filters: Vec<CharFilter> = col
+                .get_char_filters()
+                .iter()
+                .map(CharFilter::from_column_char_filter)
+                .collect::<Result<Vec<CharFilter>, String>>()
+                .map_err(TokenizerUtilsError::ConfigTokenizerError)?;
+            let mut tokenizer_config =
+                Self::build_tokenizer_config(col.get_tokenizer(), &char_filters)?;
+            if !char_filters.is_empty() && !tokenizer_config.is_text_field {
+                return Err(TokenizerUtilsError::ConfigTokenizerError(format!(
+                    "char_filters of column `{}` only applies to text columns",
+                    col_name
+                )));
+            }
 
-                    builder = builder.filter_dynamic(RemoveLongFilter::limit(*length_limit));
+            // Query tokenizer replaces index analyzer when the column is searched.
+            if let Some(query_tokenizer) = col.get_query_tokenizer() {
+                let query_config = Self::build_tokenizer_config(query_tokenizer, &char_filters)?;
+                if !tokenizer_config.is_text_field || !query_config.is_text_field {
+                    return Err(TokenizerUtilsError::ConfigTokenizerError(format!(
+                        "query_tokenizer of column `{}` only applies to text tokenizers",
+                        col_name
+                    )));
+                }
+                tokenizer_config.query_text_analyzer = Some(query_config.text_analyzer);
+            }
 
-                    for stop_word_filter in stop_word_filters {
-                        let language = SupportFilterLanguage::from_str(stop_word_filter.as_str())
-                            .unwrap()
-                            .to_language();
-                        if language.is_some() {
-                            builder = builder
-                                .filter_dynamic(StopWordFilter::new(language.unwrap()).unwrap());
-                        }
+            // Language routing adds sub-fields to a text column.
+            if let Some(language_routing) = col.get_language_routing() {
+                if !tokenizer_config.is_text_field {
+                    return Err(TokenizerUtilsError::ConfigTokenizerError(format!(
+                        "language_routing of column `{}` only applies to text columns",
+                        col_name
+                    )));
+                }
+                let mut languages: Vec<(SupportRoutingLanguage, TextAnalyzer)> = Vec::new();
+                for language in &language_routing.languages {
+                    let language = SupportRoutingLanguage::from_str(language)
+                        .map_err(TokenizerUtilsError::ConfigTokenizerError)?;
+                    let subfield_name = language_field_name(col_name, language);
+                    if config.get_columns().contains_key(&subfield_name) {
+                        return Err(TokenizerUtilsError::ConfigTokenizerError(format!(
+                            "column `{}` conflicts with language sub-field of column `{}`",
+                            subfield_name, col_name
+                        )));
                     }
+                    if languages.iter().all(|(routed, _)| *routed != language) {
+                        languages.push((language, language_analyzer(language, &char_filters)));
+                    }
+                }
+                tokenizer_config.language_routing = Some(LanguageRoutingConfig {
+                    languages,
+                    min_confidence: language_routing.min_confidence,
+                });
+            }
+            tokenizer_map.insert(col_name.to_string(), tokenizer_config);
 
-                    if *case_sensitive == false {
-                        builder = builder.filter_dynamic(LowerCaser);
+            // Index record option only applies to text columns.
+            if let Some(tokenizer_config) = tokenizer_map.get_mut(col_name) {
+                let record_option = col.get_index_record_option();
+                if !tokenizer_config.is_text_field
+                    && record_option != ColumnRecordOption::WithFreqsAndPositions
+                {
+                    return Err(TokenizerUtilsError::ConfigTokenizerError(format!(
+                        "index_record_option of column `{}` only applies to text columns",
+                        col_name
+                    )));
+                }
+                tokenizer_config.index_record_option = match record_option {
+                    ColumnRecordOption::Basic => IndexRecordOption::Basic,
+                    ColumnRecordOption::WithFreqs => IndexRecordOption::WithFreqs,
+                    ColumnRecordOption::WithFreqsAndPositions => {
+                        IndexRecordOption::WithFreqsAndPositions
                     }
+                };
 
-                    let tokenizer_config = TokenizerConfig::new(
-                        TokenizerType::Simple("simple".to_string()),
-                        builder.build(),
-                        *store_doc,
-                    );
-                    tokenizer_map.insert(col_name.to_string(), tokenizer_config);
+                // Column level `stored` and `indexed` override tokenizer settings.
+                if col.get_stored().is_none() && col.get_indexed().is_none() {
+                    continue;
+                }
+                if let Some(stored) = col.get_stored() {
+                    tokenizer_config.doc_store = stored;
+                }
+                if let Some(indexed) = col.get_indexed() {
+                    tokenizer_config.doc_index = indexed;
+                }
+                if !tokenizer_config.doc_store && !tokenizer_config.doc_index {
+                    return Err(TokenizerUtilsError::ConfigTokenizerError(format!(
+                        "column `{}` should be either stored or indexed",
+                        col_name
+                    )));
+                }
+            }
+        }
+        Ok(tokenizer_map)
+    }
+
+    // Append an explicit `filters` chain in order, legacy filter options can't be mixed with it.
+    fn chain_token_filters(
+        mut builder: TextAnalyzerBuilder,
+        filters: &[ColumnTokenFilter],
+        stop_word_filters: &[String],
+        stem_languages: &[String],
+    ) -> Result<TextAnalyzerBuilder, TokenizerUtilsError> {
+        if !stop_word_filters.is_empty() || !stem_languages.is_empty() {
+            return Err(TokenizerUtilsError::ConfigTokenizerError(
+                "`filters` can't be used with `stop_word_filters` or `stem_languages`".to_string(),
+            ));
+        }
+        for filter in filters {
+            builder = match filter {
+                ColumnTokenFilter::LowerCase => builder.filter_dynamic(LowerCaser),
+                ColumnTokenFilter::AsciiFolding => builder.filter_dynamic(AsciiFoldingFilter),
+                ColumnTokenFilter::RemoveLong { limit } => {
+                    builder.filter_dynamic(RemoveLongFilter::limit(*limit))
+                }
+                ColumnTokenFilter::StopWords { language } => {
+                    let stop_word_filter = SupportFilterLanguage::from_str(language)
+                        .map_err(TokenizerUtilsError::ConfigTokenizerError)?
+                        .to_language()
+                        .and_then(StopWordFilter::new)
+                        .ok_or_else(|| {
+                            TokenizerUtilsError::ConfigTokenizerError(format!(
+                                "Stop words of {} are not available",
+                                language
+                            ))
+                        })?;
+                    builder.filter_dynamic(stop_word_filter)
+                }
+                ColumnTokenFilter::Stemmer { language } => {
+                    let stem_language = SupportLanguageAlgorithm::from_str(language)
+                        .map_err(TokenizerUtilsError::ConfigTokenizerError)?
+                        .to_language()
+                        .ok_or_else(|| {
+                            TokenizerUtilsError::ConfigTokenizerError(format!(
+                                "Stemmer of {} is not available",
+                                language
+                            ))
+                        })?;
+                    builder.filter_dynamic(Stemmer::new(stem_language))
+                }
+                ColumnTokenFilter::Pinyin {
+                    full,
+                    first_letter,
+                    keep_original,
+                } => {
+                    if !*full && !*first_letter {
+                        return Err(TokenizerUtilsError::ConfigTokenizerError(
+                            "pinyin filter should emit `full` or `first_letter` pinyin".to_string(),
+                        ));
+                    }
+                    builder.filter_dynamic(PinyinFilter {
+                        full: *full,
+                        first_letter: *first_letter,
+                        keep_original: *keep_original,
+                    })
                 }
-                ColumnTokenizer::Stem {
-                    stop_word_filters,
-                    stem_languages,
-                    store_doc,
-                    length_limit,
-                    case_sensitive,
+                ColumnTokenFilter::Shingle {
+                    min_shingle_size,
+                    max_shingle_size,
+                    output_unigrams,
+                    separator,
                 } => {
-                    let mut builder = TextAnalyzer::builder(SimpleTokenizer::default()).dynamic();
+                    if *min_shingle_size < 2 || min_shingle_size > max_shingle_size {
+                        return Err(TokenizerUtilsError::ConfigTokenizerError(format!(
+                            "shingle size should be 2 <= min_shingle_size({}) <= max_shingle_size({})",
+                            min_shingle_size, max_shingle_size
+                        )));
+                    }
+                    builder.filter_dynamic(ShingleFilter {
+                        min_shingle_size: *min_shingle_size,
+                        max_shingle_size: *max_shingle_size,
+                        output_unigrams: *output_unigrams,
+                        separator: separator.clone(),
+                    })
+                }
+                ColumnTokenFilter::Length { min, max } => {
+                    if matches!(max, Some(max) if max < min) {
+                        return Err(TokenizerUtilsError::ConfigTokenizerError(format!(
+                            "length filter `min`({}) should not be greater than `max`",
+                            min
+                        )));
+                    }
+                    builder.filter_dynamic(TokenPredicateFilter(TokenPredicate::Length {
+                        min: *min,
+                        max: *max,
+                    }))
+                }
+                ColumnTokenFilter::Pattern { pattern, mode } => {
+                    let predicate =
+                        TokenPredicate::pattern(pattern, *mode == PatternFilterMode::Keep)
+                            .map_err(TokenizerUtilsError::ConfigTokenizerError)?;
+                    builder.filter_dynamic(TokenPredicateFilter(predicate))
+                }
+            };
+        }
+        Ok(builder)
+    }
 
-                    builder = builder.filter_dynamic(RemoveLongFilter::limit(*length_limit));
+    // Build tokenizer config of a column tokenizer, text is rewritten by `char_filters` first.
+    fn build_tokenizer_config(
+        col_tokenizer: &ColumnTokenizer,
+        char_filters: &[CharFilter],
+    ) -> Result<TokenizerConfig, TokenizerUtilsError> {
+        match col_tokenizer {
+            ColumnTokenizer::Default { store_doc } => {
+                let analyzer = text_analyzer_builder(SimpleTokenizer::default(), char_filters)
+                    .filter_dynamic(RemoveLongFilter::limit(40))
+                    .filter_dynamic(LowerCaser)
+                    .build();
+                let tokenizer_config = TokenizerConfig::new(
+                    TokenizerType::Default("default".to_string()),
+                    analyzer,
+                    *store_doc,
+                );
+                Ok(tokenizer_config)
+            }
+            ColumnTokenizer::Raw { store_doc } => {
+                let analyzer = text_analyzer_builder(RawTokenizer::default(), char_filters).build();
+                let tokenizer_config = TokenizerConfig::new(
+                    TokenizerType::Raw("raw".to_string()),
+                    analyzer,
+                    *store_doc,
+                );
+                Ok(tokenizer_config)
+            }
+            ColumnTokenizer::KeywordLowercase { store_doc } => {
+                let analyzer = text_analyzer_builder(RawTokenizer::default(), char_filters)
+                    .filter_dynamic(LowerCaser)
+                    .build();
+                let tokenizer_config = TokenizerConfig::new(
+                    TokenizerType::KeywordLowercase("keyword_lowercase".to_string()),
+                    analyzer,
+                    *store_doc,
+                );
+                Ok(tokenizer_config)
+            }
+            ColumnTokenizer::Simple {
+                store_doc,
+                stop_word_filters,
+                length_limit,
+                case_sensitive,
+                filters,
+            } => {
+                let mut builder = text_analyzer_builder(SimpleTokenizer::default(), char_filters);
 
-                    for stop_word_filter in stop_word_filters {
-                        let language = SupportFilterLanguage::from_str(stop_word_filter.as_str())
-                            .unwrap()
-                            .to_language();
-                        if language.is_some() {
-                            builder = builder
-                                .filter_dynamic(StopWordFilter::new(language.unwrap()).unwrap());
+                builder = match filters {
+                    Some(filters) => {
+                        Self::chain_token_filters(builder, filters, stop_word_filters, &[])?
+                    }
+                    None => {
+                        builder = builder.filter_dynamic(RemoveLongFilter::limit(*length_limit));
+
+                        for stop_word_filter in stop_word_filters {
+                            let language =
+                                SupportFilterLanguage::from_str(stop_word_filter.as_str())
+                                    .unwrap()
+                                    .to_language();
+                            if language.is_some() {
+                                builder = builder.filter_dynamic(
+                                    StopWordFilter::new(language.unwrap()).unwrap(),
+                                );
+                            }
+                        }
+
+                        if *case_sensitive == false {
+                            builder = builder.filter_dynamic(LowerCaser);
                         }
+                        builder
                     }
+                };
 
-                    for stem_language in stem_languages {
-                        let language = SupportLanguageAlgorithm::from_str(stem_language.as_str())
-                            .unwrap()
-                            .to_language();
-                        if language.is_some() {
-                            builder = builder.filter_dynamic(Stemmer::new(language.unwrap()));
+                let tokenizer_config = TokenizerConfig::new(
+                    TokenizerType::Simple("simple".to_string()),
+                    builder.build(),
+                    *store_doc,
+                );
+                Ok(tokenizer_config)
+            }
+            ColumnTokenizer::Stem {
+                stop_word_filters,
+                stem_languages,
+                store_doc,
+                length_limit,
+                case_sensitive,
+                filters,
+            } => {
+                let mut builder = text_analyzer_builder(SimpleTokenizer::default(), char_filters);
+
+                builder = match filters {
+                    Some(filters) => Self::chain_token_filters(
+                        builder,
+                        filters,
+                        stop_word_filters,
+                        stem_languages,
+                    )?,
+                    None => {
+                        builder = builder.filter_dynamic(RemoveLongFilter::limit(*length_limit));
+
+                        for stop_word_filter in stop_word_filters {
+                            let language =
+                                SupportFilterLanguage::from_str(stop_word_filter.as_str())
+                                    .unwrap()
+                                    .to_language();
+                            if language.is_some() {
+                                builder = builder.filter_dynamic(
+                                    StopWordFilter::new(language.unwrap()).unwrap(),
+                                );
+                            }
                         }
+
+                        for stem_language in stem_languages {
+                            let language =
+                                SupportLanguageAlgorithm::from_str(stem_language.as_str())
+                                    .unwrap()
+                                    .to_language();
+                            if language.is_some() {
+                                builder = builder.filter_dynamic(Stemmer::new(language.unwrap()));
+                            }
+                        }
+
+                        if *case_sensitive == false {
+                            builder = builder.filter_dynamic(LowerCaser);
+                        }
+                        builder
                     }
+                };
+
+                let tokenizer_config = TokenizerConfig::new(
+                    TokenizerType::Stem("stem".to_string()),
+                    builder.build(),
+                    *store_doc,
+                );
+                Ok(tokenizer_config)
+            }
+            ColumnTokenizer::Whitespace {
+                store_doc,
+                stop_word_filters,
+                length_limit,
+                case_sensitive,
+                filters,
+            } => {
+                let mut builder =
+                    text_analyzer_builder(WhitespaceTokenizer::default(), char_filters);
 
-                    if *case_sensitive == false {
-                        builder = builder.filter_dynamic(LowerCaser);
+                builder = match filters {
+                    Some(filters) => {
+                        Self::chain_token_filters(builder, filters, stop_word_filters, &[])?
                     }
+                    None => {
+                        builder = builder.filter_dynamic(RemoveLongFilter::limit(*length_limit));
 
-                    let tokenizer_config = TokenizerConfig::new(
-                        TokenizerType::Stem("stem".to_string()),
-                        builder.build(),
-                        *store_doc,
-                    );
-                    tokenizer_map.insert(col_name.to_string(), tokenizer_config);
-                }
-                ColumnTokenizer::Whitespace {
-                    store_doc,
-                    stop_word_filters,
-                    length_limit,
-                    case_sensitive,
-                } => {
-                    let mut builder =
-                        TextAnalyzer::builder(WhitespaceTokenizer::default()).dynamic();
-
-                    builder = builder.filter_dynamic(RemoveLongFilter::limit(*length_limit));
-
-                    for stop_word_filter in stop_word_filters {
-                        let language = SupportFilterLanguage::from_str(stop_word_filter.as_str())
-                            .unwrap()
-                            .to_language();
-                        if language.is_some() {
-                            builder = builder
-                                .filter_dynamic(StopWordFilter::new(language.unwrap()).unwrap());
+                        for stop_word_filter in stop_word_filters {
+                            let language =
+                                SupportFilterLanguage::from_str(stop_word_filter.as_str())
+                                    .unwrap()
+                                    .to_language();
+                            if language.is_some() {
+                                builder = builder.filter_dynamic(
+                                    StopWordFilter::new(language.unwrap()).unwrap(),
+                                );
+                            }
                         }
-                    }
 
-                    if *case_sensitive == false {
-                        builder = builder.filter_dynamic(LowerCaser);
+                        if *case_sensitive == false {
+                            builder = builder.filter_dynamic(LowerCaser);
+                        }
+                        builder
                     }
+                };
 
-                    let tokenizer_config = TokenizerConfig::new(
-                        TokenizerType::WhiteSpace("whitespace".to_string()),
-                        builder.build(),
-                        *store_doc,
-                    );
-                    tokenizer_map.insert(col_name.to_string(), tokenizer_config);
+                let tokenizer_config = TokenizerConfig::new(
+                    TokenizerType::WhiteSpace("whitespace".to_string()),
+                    builder.build(),
+                    *store_doc,
+                );
+                Ok(tokenizer_config)
+            }
+            ColumnTokenizer::Ngram {
+                min_gram,
+                max_gram,
+                prefix_only,
+                store_doc,
+                stop_word_filters,
+                length_limit,
+                case_sensitive,
+                filters,
+            } => {
+                if min_gram >= max_gram || (*min_gram == 0 && *max_gram == 0) {
+                    return Err(TokenizerUtilsError::JsonParseError(
+                        "`min_gram` should be smaller than `max_gram`".to_string(),
+                    ));
                 }
-                ColumnTokenizer::Ngram {
-                    min_gram,
-                    max_gram,
-                    prefix_only,
-                    store_doc,
-                    stop_word_filters,
-                    length_limit,
-                    case_sensitive,
-                } => {
-                    if min_gram >= max_gram || (*min_gram == 0 && *max_gram == 0) {
-                        return Err(TokenizerUtilsError::JsonParseError(
-                            "`min_gram` should be smaller than `max_gram`".to_string(),
-                        ));
+
+                let mut builder = text_analyzer_builder(
+                    NgramTokenizer::new(*min_gram, *max_gram, *prefix_only)
+                        .map_err(|e| TokenizerUtilsError::ConfigTokenizerError(e.to_string()))?,
+                    char_filters,
+                );
+
+                builder = match filters {
+                    Some(filters) => {
+                        Self::chain_token_filters(builder, filters, stop_word_filters, &[])?
                     }
+                    None => {
+                        builder = builder.filter_dynamic(RemoveLongFilter::limit(*length_limit));
 
-                    let mut builder = TextAnalyzer::builder(
-                        NgramTokenizer::new(*min_gram, *max_gram, *prefix_only).map_err(|e| {
-                            TokenizerUtilsError::ConfigTokenizerError(e.to_string())
-                        })?,
-                    )
-                    .dynamic();
-
-                    builder = builder.filter_dynamic(RemoveLongFilter::limit(*length_limit));
-
-                    for stop_word_filter in stop_word_filters {
-                        let language = SupportFilterLanguage::from_str(stop_word_filter.as_str())
-                            .unwrap()
-                            .to_language();
-                        if language.is_some() {
-                            builder = builder
-                                .filter_dynamic(StopWordFilter::new(language.unwrap()).unwrap());
+                        for stop_word_filter in stop_word_filters {
+                            let language =
+                                SupportFilterLanguage::from_str(stop_word_filter.as_str())
+                                    .unwrap()
+                                    .to_language();
+                            if language.is_some() {
+                                builder = builder.filter_dynamic(
+                                    StopWordFilter::new(language.unwrap()).unwrap(),
+                                );
+                            }
                         }
-                    }
 
-                    if *case_sensitive == false {
-                        builder = builder.filter_dynamic(LowerCaser);
+                        if *case_sensitive == false {
+                            builder = builder.filter_dynamic(LowerCaser);
+                        }
+                        builder
                     }
+                };
 
-                    let tokenizer_config = TokenizerConfig::new(
-                        TokenizerType::Ngram("ngram".to_string()),
-                        builder.build(),
-                        *store_doc,
-                    );
-                    tokenizer_map.insert(col_name.to_string(), tokenizer_config);
-                }
-                ColumnTokenizer::Chinese {
-                    jieba,
-                    mode,
-                    hmm,
-                    store_doc,
-                    // length_limit,
-                } => {
-                    let jieba_mode: Jieba = match jieba.as_str() {
-                        "default" => Jieba::default(),
-                        "empty" => Jieba::empty(),
-                        _ => Jieba::empty(),
-                    };
-
-                    let tokenizer_option: TokenizerOption = match mode.as_str() {
-                        "all" => TokenizerOption::All,
-                        "unicode" => TokenizerOption::Unicode,
-                        "default" => TokenizerOption::Default { hmm: *hmm },
-                        "search" => TokenizerOption::ForSearch { hmm: *hmm },
-                        _ => TokenizerOption::Unicode, // default option
-                    };
-
-                    let builder = TextAnalyzer::builder(CangJieTokenizer {
+                let tokenizer_config = TokenizerConfig::new(
+                    TokenizerType::Ngram("ngram".to_string()),
+                    builder.build(),
+                    *store_doc,
+                );
+                Ok(tokenizer_config)
+            }
+            ColumnTokenizer::Chinese {
+                jieba,
+                mode,
+                hmm,
+                store_doc,
+                filters,
+                // length_limit,
+            } => {
+                let jieba_mode: Jieba = match jieba.as_str() {
+                    "default" => Jieba::default(),
+                    "empty" => Jieba::empty(),
+                    _ => Jieba::empty(),
+                };
+
+                let tokenizer_option: TokenizerOption = match mode.as_str() {
+                    "all" => TokenizerOption::All,
+                    "unicode" => TokenizerOption::Unicode,
+                    "default" => TokenizerOption::Default { hmm: *hmm },
+                    "search" => TokenizerOption::ForSearch { hmm: *hmm },
+                    _ => TokenizerOption::Unicode, // default option
+                };
+
+                let mut builder = text_analyzer_builder(
+                    CangJieTokenizer {
                         worker: Arc::new(jieba_mode),
                         option: tokenizer_option,
-                    })
-                    .dynamic();
-                    // builder = builder.filter_dynamic(RemoveLongFilter::limit(*length_limit));
-
-                    let tokenizer_config = TokenizerConfig::new(
-                        TokenizerType::Chinese("chinese".to_string()),
-                        builder.build(),
-                        *store_doc,
-                    );
-                    tokenizer_map.insert(col_name.to_string(), tokenizer_config);
-                }
-                ColumnTokenizer::I64 { store_doc, indexed } => {
-                    let tokenizer_config = TokenizerConfig::new_non_text(
-                        TokenizerType::I64("i64".to_string()),
-                        *store_doc,
-                        *indexed,
-                    );
-                    tokenizer_map.insert(col_name.to_string(), tokenizer_config);
+                    },
+                    char_filters,
+                );
+                // builder = builder.filter_dynamic(RemoveLongFilter::limit(*length_limit));
+                if let Some(filters) = filters {
+                    builder = Self::chain_token_filters(builder, filters, &[], &[])?;
                 }
-                ColumnTokenizer::F64 { store_doc, indexed } => {
-                    let tokenizer_config = TokenizerConfig::new_non_text(
-                        TokenizerType::F64("f64".to_string()),
-                        *store_doc,
-                        *indexed,
-                    );
-                    tokenizer_map.insert(col_name.to_string(), tokenizer_config);
-                }
-                ColumnTokenizer::Bytes { store_doc, indexed } => {
-                    let tokenizer_config = TokenizerConfig::new_non_text(
-                        TokenizerType::Bytes("bytes".to_string()),
-                        *store_doc,
-                        *indexed,
-                    );
-                    tokenizer_map.insert(col_name.to_string(), tokenizer_config);
+
+                let tokenizer_config = TokenizerConfig::new(
+                    TokenizerType::Chinese("chinese".to_string()),
+                    builder.build(),
+                    *store_doc,
+                );
+                Ok(tokenizer_config)
+            }
+            ColumnTokenizer::Custom {
+                name,
+                store_doc,
+                filters,
+            } => {
+                let tokenizer = CUSTOM_TOKENIZERS.get(name).ok_or_else(|| {
+                    TokenizerUtilsError::ConfigTokenizerError(format!(
+                        "custom tokenizer `{}` is not registered",
+                        name
+                    ))
+                })?;
+                let mut builder = text_analyzer_builder(tokenizer, char_filters);
+                if let Some(filters) = filters {
+                    builder = Self::chain_token_filters(builder, filters, &[], &[])?;
                 }
+
+                let tokenizer_config = TokenizerConfig::new(
+                    TokenizerType::Custom("custom".to_string()),
+                    builder.build(),
+                    *store_doc,
+                );
+                Ok(tokenizer_config)
+            }
+            ColumnTokenizer::I64 { store_doc, indexed } => {
+                let tokenizer_config = TokenizerConfig::new_non_text(
+                    TokenizerType::I64("i64".to_string()),
+                    *store_doc,
+                    *indexed,
+                );
+                Ok(tokenizer_config)
+            }
+            ColumnTokenizer::F64 { store_doc, indexed } => {
+                let tokenizer_config = TokenizerConfig::new_non_text(
+                    TokenizerType::F64("f64".to_string()),
+                    *store_doc,
+                    *indexed,
+                );
+                Ok(tokenizer_config)
+            }
+            ColumnTokenizer::Bytes { store_doc, indexed } => {
+                let tokenizer_config = TokenizerConfig::new_non_text(
+                    TokenizerType::Bytes("bytes".to_string()),
+                    *store_doc,
+                    *indexed,
+                );
+                Ok(tokenizer_config)
+            }
+            ColumnTokenizer::GeoPoint {} => {
+                let tokenizer_config = TokenizerConfig::new_non_text(
+                    TokenizerType::GeoPoint("geo_point".to_string()),
+                    false,
+                    false,
+                );
+                Ok(tokenizer_config)
+            }
+            ColumnTokenizer::Date {
+                store_doc,
+                indexed,
+                precision,
+                input_formats,
+            } => {
+                let date_format = DateFormat {
+                    input_formats: input_formats.clone(),
+                    precision: *precision,
+                };
+                let tokenizer_config = TokenizerConfig::new_date(date_format, *store_doc, *indexed);
+                Ok(tokenizer_config)
+            }
+            ColumnTokenizer::Vector {
+                dims,
+                metric,
+                index,
+            } => {
+                let vector_format = VectorFormat {
+                    dims: *dims,
+                    metric: *metric,
+                    index: *index,
+                };
+                Ok(TokenizerConfig::new_vector(vector_format))
+            }
+            ColumnTokenizer::SparseVector { scale } => {
+                Ok(TokenizerConfig::new_sparse_vector(SparseVectorFormat {
+                    scale: *scale,
+                }))
             }
         }
-        Ok(tokenizer_map)
     }
 
     pub fn varify_json_parameter(json_str: &str) -> Result<bool, TokenizerUtilsError> {
@@ -364,3 +769,119 @@ impl TokenizerUtils {
         Ok(true)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analyze(tokenizer_config: &TokenizerConfig, text: &str) -> Vec<String> {
+        let mut text_analyzer = tokenizer_config.text_analyzer.clone();
+        let mut tokens: Vec<String> = Vec::new();
+        text_analyzer
+            .token_stream(text)
+            .process(&mut |token| tokens.push(token.text.clone()));
+        tokens
+    }
+
+    #[test]
+    fn test_token_filter_chain() {
+        let col_tokenizer_map = TokenizerUtils::parse_tokenizer_json_to_config_map(
+            r#"{"col1": {"tokenizer": {"type": "stem", "filters": [
+                {"type": "lowercase"},
+                {"type": "stop_words", "language": "english"},
+                {"type": "stemmer", "language": "english"}
+            ]}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            analyze(&col_tokenizer_map["col1"], "The Running Dogs"),
+            vec!["run".to_string(), "dog".to_string()]
+        );
+
+        let col_tokenizer_map = TokenizerUtils::parse_tokenizer_json_to_config_map(
+            r#"{"col1": {"tokenizer": {"type": "whitespace", "filters": [
+                {"type": "ascii_folding"},
+                {"type": "remove_long", "limit": 8}
+            ]}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            analyze(&col_tokenizer_map["col1"], "Café Übersetzungen"),
+            vec!["Cafe".to_string()]
+        );
+
+        // Legacy options can't be mixed with `filters`.
+        assert!(TokenizerUtils::parse_tokenizer_json_to_config_map(
+            r#"{"col1": {"tokenizer": {"type": "stem", "stem_languages": ["english"],
+                "filters": [{"type": "lowercase"}]}}}"#,
+        )
+        .is_err());
+        assert!(TokenizerUtils::parse_tokenizer_json_to_config_map(
+            r#"{"col1": {"tokenizer": {"type": "simple",
+                "filters": [{"type": "stemmer", "language": "klingon"}]}}}"#,
+        )
+        .is_err());
+
+        let col_tokenizer_map = TokenizerUtils::parse_tokenizer_json_to_config_map(
+            r#"{"col1": {"tokenizer": {"type": "simple", "filters": [
+                {"type": "lowercase"},
+                {"type": "shingle", "output_unigrams": false}
+            ]}, "index_record_option": "freqs"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            analyze(&col_tokenizer_map["col1"], "New York City"),
+            vec!["new york".to_string(), "york city".to_string()]
+        );
+        assert!(TokenizerUtils::parse_tokenizer_json_to_config_map(
+            r#"{"col1": {"tokenizer": {"type": "simple",
+                "filters": [{"type": "shingle", "min_shingle_size": 3, "max_shingle_size": 2}]}}}"#,
+        )
+        .is_err());
+
+        let col_tokenizer_map = TokenizerUtils::parse_tokenizer_json_to_config_map(
+            r#"{"col1": {"tokenizer": {"type": "whitespace", "filters": [
+                {"type": "length", "min": 2, "max": 20},
+                {"type": "pattern", "pattern": "[A-Za-z0-9+/]{12,}={0,2}"}
+            ]}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            analyze(&col_tokenizer_map["col1"], "x payload aGVsbG8gd29ybGQ= ok"),
+            vec!["payload".to_string(), "ok".to_string()]
+        );
+        assert!(TokenizerUtils::parse_tokenizer_json_to_config_map(
+            r#"{"col1": {"tokenizer": {"type": "whitespace",
+                "filters": [{"type": "length", "min": 5, "max": 2}]}}}"#,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_char_filters() {
+        let col_tokenizer_map = TokenizerUtils::parse_tokenizer_json_to_config_map(
+            r#"{"col1": {"tokenizer": {"type": "default"}, "char_filters": [
+                {"type": "html_strip"},
+                {"type": "pattern_replace", "pattern": "(\\w+)@example\\.com", "replacement": "$1"}
+            ]}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            analyze(
+                &col_tokenizer_map["col1"],
+                "<a href=\"mailto:tom@example.com\">Mail &lt;tom@example.com&gt;</a>"
+            ),
+            vec!["mail".to_string(), "tom".to_string()]
+        );
+
+        assert!(TokenizerUtils::parse_tokenizer_json_to_config_map(
+            r#"{"col1": {"tokenizer": {"type": "default"},
+                "char_filters": [{"type": "pattern_replace", "pattern": "("}]}}"#,
+        )
+        .is_err());
+        assert!(TokenizerUtils::parse_tokenizer_json_to_config_map(
+            r#"{"col1": {"tokenizer": {"type": "i64"}, "char_filters": [{"type": "html_strip"}]}}"#,
+        )
+        .is_err());
+    }
+}
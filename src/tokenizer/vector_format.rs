@@ -0,0 +1,197 @@
+use serde::{Deserialize, Serialize};
+
+/// Similarity of vectors, larger means closer.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum VectorMetric {
+    /// Cosine of the angle between vectors, in `[-1, 1]`.
+    #[default]
+    #[serde(rename = "cosine")]
+    Cosine,
+    /// Dot product, vectors are expected to be normalized by host.
+    #[serde(rename = "dot_product")]
+    DotProduct,
+    /// `1 / (1 + d²)` of euclidean distance `d`, in `(0, 1]`.
+    #[serde(rename = "l2")]
+    L2,
+}
+
+impl VectorMetric {
+    pub fn score(self, a: &[f32], b: &[f32]) -> f32 {
+        match self {
+            VectorMetric::Cosine => {
+                let norm: f32 = dot(a, a).sqrt() * dot(b, b).sqrt();
+                match norm > 0.0 {
+                    true => dot(a, b) / norm,
+                    false => 0.0,
+                }
+            }
+            VectorMetric::DotProduct => dot(a, b),
+            VectorMetric::L2 => {
+                let squared_distance: f32 = a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum();
+                1.0 / (1.0 + squared_distance)
+            }
+        }
+    }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// How nearest vectors of a column are searched.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+#[serde(tag = "type")]
+pub enum VectorIndexType {
+    /// Exact search, every vector is scored.
+    #[default]
+    #[serde(rename = "flat")]
+    Flat,
+    /// Approximate search in a HNSW graph of each segment, graphs are built when the reader
+    /// loads a segment. `m` is the number of neighbors per node, `ef_construction` and
+    /// `ef_search` are candidates kept while building and searching, larger is more accurate.
+    #[serde(rename = "hnsw")]
+    Hnsw {
+        #[serde(default = "default_hnsw_m")]
+        m: usize,
+        #[serde(default = "default_hnsw_ef_construction")]
+        ef_construction: usize,
+        #[serde(default = "default_hnsw_ef_search")]
+        ef_search: usize,
+    },
+}
+
+fn default_hnsw_m() -> usize {
+    16
+}
+
+fn default_hnsw_ef_construction() -> usize {
+    100
+}
+
+fn default_hnsw_ef_search() -> usize {
+    64
+}
+
+impl VectorIndexType {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            VectorIndexType::Flat => Ok(()),
+            VectorIndexType::Hnsw {
+                m,
+                ef_construction,
+                ef_search,
+            } => {
+                if *m < 2 || *ef_construction == 0 || *ef_search == 0 {
+                    return Err(format!(
+                        "hnsw m should be at least 2 and ef should be positive, got m:{}, ef_construction:{}, ef_search:{}",
+                        m, ef_construction, ef_search
+                    ));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Dimensions, metric and index type of a vector column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorFormat {
+    pub dims: usize,
+    pub metric: VectorMetric,
+    pub index: VectorIndexType,
+}
+
+impl VectorFormat {
+    /// Parse comma separated components, e.g. `0.1,0.2,0.3` or `[0.1, 0.2, 0.3]`.
+    pub fn parse(&self, value: &str) -> Result<Vec<f32>, String> {
+        let value: &str = value.trim();
+        let components: &str = value
+            .strip_prefix('[')
+            .and_then(|value| value.strip_suffix(']'))
+            .unwrap_or(value);
+        let vector: Vec<f32> = components
+            .split(',')
+            .map(|component| component.trim().parse::<f32>().ok())
+            .collect::<Option<Vec<f32>>>()
+            .ok_or_else(|| format!("Can't parse `{}` as vector", value))?;
+        self.check(&vector)?;
+        Ok(vector)
+    }
+
+    // Vectors should have `dims` finite components.
+    pub fn check(&self, vector: &[f32]) -> Result<(), String> {
+        if vector.len() != self.dims {
+            return Err(format!(
+                "vector should have {} dims, got {}",
+                self.dims,
+                vector.len()
+            ));
+        }
+        if vector.iter().any(|component| !component.is_finite()) {
+            return Err("vector components should be finite".to_string());
+        }
+        Ok(())
+    }
+
+    /// Little endian bytes kept in the bytes fast field of the column.
+    pub fn encode(vector: &[f32]) -> Vec<u8> {
+        vector
+            .iter()
+            .flat_map(|component| component.to_le_bytes())
+            .collect()
+    }
+
+    /// Append vector encoded in `bytes` to `output`, `false` if it doesn't have `dims` components.
+    pub fn decode_into(&self, bytes: &[u8], output: &mut Vec<f32>) -> bool {
+        if bytes.len() != self.dims * 4 {
+            return false;
+        }
+        output.extend(
+            bytes
+                .chunks_exact(4)
+                .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]])),
+        );
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vector_format() {
+        let vector_format = VectorFormat {
+            dims: 3,
+            metric: VectorMetric::Cosine,
+            index: VectorIndexType::Flat,
+        };
+        let vector = vector_format.parse(" [1, 0.5, -2] ").unwrap();
+        assert_eq!(vector, vec![1.0, 0.5, -2.0]);
+        assert_eq!(vector_format.parse("1,0.5,-2").unwrap(), vector);
+        assert!(vector_format.parse("1,0.5").is_err());
+        assert!(vector_format.parse("1,a,2").is_err());
+        assert!(vector_format.parse("1,NaN,2").is_err());
+
+        let mut decoded: Vec<f32> = Vec::new();
+        assert!(vector_format.decode_into(&VectorFormat::encode(&vector), &mut decoded));
+        assert_eq!(decoded, vector);
+        assert!(!vector_format.decode_into(&[0u8; 8], &mut decoded));
+    }
+
+    #[test]
+    fn test_vector_metric() {
+        let a = [1.0, 0.0];
+        let b = [0.0, 2.0];
+        assert_eq!(VectorMetric::Cosine.score(&a, &[3.0, 0.0]), 1.0);
+        assert_eq!(VectorMetric::Cosine.score(&a, &b), 0.0);
+        assert_eq!(VectorMetric::Cosine.score(&a, &[0.0, 0.0]), 0.0);
+        assert_eq!(
+            VectorMetric::DotProduct.score(&[1.0, 2.0], &[3.0, 4.0]),
+            11.0
+        );
+        assert_eq!(VectorMetric::L2.score(&a, &a), 1.0);
+        assert_eq!(VectorMetric::L2.score(&a, &b), 1.0 / 6.0);
+    }
+}
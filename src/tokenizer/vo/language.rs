@@ -143,3 +143,138 @@ impl SupportLanguageAlgorithm {
         }
     }
 }
+
+/// Languages text can be routed to by language detection, named by ISO 639-1 code.
+/// Chinese is analyzed by jieba, others by stemmer of the language.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Copy, Clone)]
+pub enum SupportRoutingLanguage {
+    Arabic,
+    Chinese,
+    Danish,
+    Dutch,
+    English,
+    Finnish,
+    French,
+    German,
+    Greek,
+    Hungarian,
+    Italian,
+    Norwegian,
+    Portuguese,
+    Romanian,
+    Russian,
+    Spanish,
+    Swedish,
+    Tamil,
+    Turkish,
+}
+
+impl FromStr for SupportRoutingLanguage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SupportRoutingLanguage::ALL
+            .iter()
+            .find(|language| language.code() == s.to_lowercase())
+            .copied()
+            .ok_or_else(|| format!("Unsupported routing language: {}", s))
+    }
+}
+
+impl SupportRoutingLanguage {
+    pub const ALL: [SupportRoutingLanguage; 19] = [
+        SupportRoutingLanguage::Arabic,
+        SupportRoutingLanguage::Chinese,
+        SupportRoutingLanguage::Danish,
+        SupportRoutingLanguage::Dutch,
+        SupportRoutingLanguage::English,
+        SupportRoutingLanguage::Finnish,
+        SupportRoutingLanguage::French,
+        SupportRoutingLanguage::German,
+        SupportRoutingLanguage::Greek,
+        SupportRoutingLanguage::Hungarian,
+        SupportRoutingLanguage::Italian,
+        SupportRoutingLanguage::Norwegian,
+        SupportRoutingLanguage::Portuguese,
+        SupportRoutingLanguage::Romanian,
+        SupportRoutingLanguage::Russian,
+        SupportRoutingLanguage::Spanish,
+        SupportRoutingLanguage::Swedish,
+        SupportRoutingLanguage::Tamil,
+        SupportRoutingLanguage::Turkish,
+    ];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            SupportRoutingLanguage::Arabic => "ar",
+            SupportRoutingLanguage::Chinese => "zh",
+            SupportRoutingLanguage::Danish => "da",
+            SupportRoutingLanguage::Dutch => "nl",
+            SupportRoutingLanguage::English => "en",
+            SupportRoutingLanguage::Finnish => "fi",
+            SupportRoutingLanguage::French => "fr",
+            SupportRoutingLanguage::German => "de",
+            SupportRoutingLanguage::Greek => "el",
+            SupportRoutingLanguage::Hungarian => "hu",
+            SupportRoutingLanguage::Italian => "it",
+            SupportRoutingLanguage::Norwegian => "no",
+            SupportRoutingLanguage::Portuguese => "pt",
+            SupportRoutingLanguage::Romanian => "ro",
+            SupportRoutingLanguage::Russian => "ru",
+            SupportRoutingLanguage::Spanish => "es",
+            SupportRoutingLanguage::Swedish => "sv",
+            SupportRoutingLanguage::Tamil => "ta",
+            SupportRoutingLanguage::Turkish => "tr",
+        }
+    }
+
+    // Language reported by whatlang detector.
+    pub fn to_whatlang(&self) -> whatlang::Lang {
+        match self {
+            SupportRoutingLanguage::Arabic => whatlang::Lang::Ara,
+            SupportRoutingLanguage::Chinese => whatlang::Lang::Cmn,
+            SupportRoutingLanguage::Danish => whatlang::Lang::Dan,
+            SupportRoutingLanguage::Dutch => whatlang::Lang::Nld,
+            SupportRoutingLanguage::English => whatlang::Lang::Eng,
+            SupportRoutingLanguage::Finnish => whatlang::Lang::Fin,
+            SupportRoutingLanguage::French => whatlang::Lang::Fra,
+            SupportRoutingLanguage::German => whatlang::Lang::Deu,
+            SupportRoutingLanguage::Greek => whatlang::Lang::Ell,
+            SupportRoutingLanguage::Hungarian => whatlang::Lang::Hun,
+            SupportRoutingLanguage::Italian => whatlang::Lang::Ita,
+            SupportRoutingLanguage::Norwegian => whatlang::Lang::Nob,
+            SupportRoutingLanguage::Portuguese => whatlang::Lang::Por,
+            SupportRoutingLanguage::Romanian => whatlang::Lang::Ron,
+            SupportRoutingLanguage::Russian => whatlang::Lang::Rus,
+            SupportRoutingLanguage::Spanish => whatlang::Lang::Spa,
+            SupportRoutingLanguage::Swedish => whatlang::Lang::Swe,
+            SupportRoutingLanguage::Tamil => whatlang::Lang::Tam,
+            SupportRoutingLanguage::Turkish => whatlang::Lang::Tur,
+        }
+    }
+
+    // Stemmer language, `None` for Chinese.
+    pub fn to_language(&self) -> Option<Language> {
+        match self {
+            SupportRoutingLanguage::Chinese => None,
+            SupportRoutingLanguage::Arabic => Some(Language::Arabic),
+            SupportRoutingLanguage::Danish => Some(Language::Danish),
+            SupportRoutingLanguage::Dutch => Some(Language::Dutch),
+            SupportRoutingLanguage::English => Some(Language::English),
+            SupportRoutingLanguage::Finnish => Some(Language::Finnish),
+            SupportRoutingLanguage::French => Some(Language::French),
+            SupportRoutingLanguage::German => Some(Language::German),
+            SupportRoutingLanguage::Greek => Some(Language::Greek),
+            SupportRoutingLanguage::Hungarian => Some(Language::Hungarian),
+            SupportRoutingLanguage::Italian => Some(Language::Italian),
+            SupportRoutingLanguage::Norwegian => Some(Language::Norwegian),
+            SupportRoutingLanguage::Portuguese => Some(Language::Portuguese),
+            SupportRoutingLanguage::Romanian => Some(Language::Romanian),
+            SupportRoutingLanguage::Russian => Some(Language::Russian),
+            SupportRoutingLanguage::Spanish => Some(Language::Spanish),
+            SupportRoutingLanguage::Swedish => Some(Language::Swedish),
+            SupportRoutingLanguage::Tamil => Some(Language::Tamil),
+            SupportRoutingLanguage::Turkish => Some(Language::Turkish),
+        }
+    }
+}
@@ -2,11 +2,16 @@ use std::str::FromStr;
 
 use serde::{Deserialize, Deserializer, Serialize};
 
-use super::language::{SupportFilterLanguage, SupportLanguageAlgorithm};
+use super::language::{SupportFilterLanguage, SupportLanguageAlgorithm, SupportRoutingLanguage};
+use crate::tokenizer::date_format::{DateInputFormat, DatePrecision};
+use crate::tokenizer::vector_format::{VectorIndexType, VectorMetric};
 
 /// ColumnTokenizer stores the specific configuration information
 /// for the tokenizer of each column. During the development process,
 /// developers need to pay attention to handling the edge cases for each tokenizer type.
+/// `filters` of `simple`, `stem`, `whitespace`, `ngram`, `chinese` and `custom` is an ordered token
+/// filter chain, e.g. lowercase → stop words → stemmer, it replaces `stop_word_filters`, `stem_languages`,
+/// `length_limit` and `case_sensitive` when given.
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
 #[serde(tag = "type")]
@@ -21,6 +26,12 @@ pub enum ColumnTokenizer {
         #[serde(default)]
         store_doc: bool,
     },
+    /// Whole value as one lowercased term, used by case insensitive exact match filters.
+    #[serde(rename = "keyword_lowercase")]
+    KeywordLowercase {
+        #[serde(default)]
+        store_doc: bool,
+    },
     #[serde(rename = "simple")]
     Simple {
         #[serde(default, deserialize_with = "stop_word_filters_validator")]
@@ -31,6 +42,8 @@ pub enum ColumnTokenizer {
         length_limit: usize,
         #[serde(default)]
         case_sensitive: bool,
+        #[serde(default)]
+        filters: Option<Vec<ColumnTokenFilter>>,
     },
     #[serde(rename = "stem")]
     Stem {
@@ -44,6 +57,8 @@ pub enum ColumnTokenizer {
         length_limit: usize,
         #[serde(default)]
         case_sensitive: bool,
+        #[serde(default)]
+        filters: Option<Vec<ColumnTokenFilter>>,
     },
     #[serde(rename = "whitespace")]
     Whitespace {
@@ -55,6 +70,8 @@ pub enum ColumnTokenizer {
         length_limit: usize,
         #[serde(default)]
         case_sensitive: bool,
+        #[serde(default)]
+        filters: Option<Vec<ColumnTokenFilter>>,
     },
     #[serde(rename = "ngram")]
     Ngram {
@@ -72,6 +89,8 @@ pub enum ColumnTokenizer {
         length_limit: usize,
         #[serde(default)]
         case_sensitive: bool,
+        #[serde(default)]
+        filters: Option<Vec<ColumnTokenFilter>>,
     },
     #[serde(rename = "chinese")]
     Chinese {
@@ -89,6 +108,17 @@ pub enum ColumnTokenizer {
         hmm: bool,
         #[serde(default)]
         store_doc: bool,
+        #[serde(default)]
+        filters: Option<Vec<ColumnTokenFilter>>,
+    },
+    /// Tokenizer registered by host under `name`, see `tantivy_search_register_tokenizer`.
+    #[serde(rename = "custom")]
+    Custom {
+        name: String,
+        #[serde(default)]
+        store_doc: bool,
+        #[serde(default)]
+        filters: Option<Vec<ColumnTokenFilter>>,
     },
     #[serde(rename = "i64")]
     I64 {
@@ -111,12 +141,149 @@ pub enum ColumnTokenizer {
         #[serde(default = "default_indexed")]
         indexed: bool,
     },
+    /// Geo point column, values are `lat,lon` in degrees, filtered by bounding box
+    /// or distance radius. Points are only kept in a fast field, they're neither stored nor indexed.
+    #[serde(rename = "geo_point")]
+    GeoPoint {},
+    /// Date column, values are parsed by the first of `input_formats` accepting them
+    /// and truncated to `precision`, range queries are truncated to it as well.
+    #[serde(rename = "date")]
+    Date {
+        #[serde(default)]
+        store_doc: bool,
+        #[serde(default = "default_indexed")]
+        indexed: bool,
+        #[serde(default)]
+        precision: DatePrecision,
+        #[serde(
+            default = "default_date_input_formats",
+            deserialize_with = "date_input_formats_validator"
+        )]
+        input_formats: Vec<DateInputFormat>,
+    },
+    /// Dense embedding column, values are `dims` comma separated floats, e.g. `0.1,0.2,0.3`.
+    /// Vectors are only kept in a fast field and searched by nearest neighbors of `metric`.
+    #[serde(rename = "vector")]
+    Vector {
+        #[serde(deserialize_with = "vector_dims_validator")]
+        dims: usize,
+        #[serde(default)]
+        metric: VectorMetric,
+        #[serde(default, deserialize_with = "vector_index_validator")]
+        index: VectorIndexType,
+    },
+    /// Learned sparse vector column, values are JSON objects of term weights,
+    /// e.g. `{"apple": 1.2, "fruit": 0.4}`. Weights are indexed as term frequencies
+    /// `round(weight * scale)` and searched by dot product over posting lists.
+    #[serde(rename = "sparse_vector")]
+    SparseVector {
+        #[serde(
+            default = "default_sparse_vector_scale",
+            deserialize_with = "sparse_vector_scale_validator"
+        )]
+        scale: u32,
+    },
+}
+
+/// ColumnTokenFilter is one step of the `filters` chain of a text tokenizer,
+/// filters are applied to tokens in the order they are listed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+#[serde(tag = "type")]
+pub enum ColumnTokenFilter {
+    #[serde(rename = "lowercase")]
+    LowerCase,
+    #[serde(rename = "ascii_folding")]
+    AsciiFolding,
+    #[serde(rename = "remove_long")]
+    RemoveLong {
+        #[serde(default = "default_length_limit")]
+        limit: usize,
+    },
+    #[serde(rename = "stop_words")]
+    StopWords {
+        #[serde(deserialize_with = "stop_word_language_validator")]
+        language: String,
+    },
+    #[serde(rename = "stemmer")]
+    Stemmer {
+        #[serde(deserialize_with = "stem_language_validator")]
+        language: String,
+    },
+    /// Transliterates hanzi tokens to pinyin, so chinese text can be searched by latin letters.
+    #[serde(rename = "pinyin")]
+    Pinyin {
+        #[serde(default = "default_pinyin_full")]
+        full: bool,
+        #[serde(default)]
+        first_letter: bool,
+        #[serde(default = "default_pinyin_keep_original")]
+        keep_original: bool,
+    },
+    /// Adds word n-grams of adjacent tokens, useful when positions of a column aren't indexed.
+    #[serde(rename = "shingle")]
+    Shingle {
+        #[serde(default = "default_shingle_size")]
+        min_shingle_size: usize,
+        #[serde(default = "default_shingle_size")]
+        max_shingle_size: usize,
+        #[serde(default = "default_output_unigrams")]
+        output_unigrams: bool,
+        #[serde(default = "default_shingle_separator")]
+        separator: String,
+    },
+    /// Keeps tokens of `min..=max` chars.
+    #[serde(rename = "length")]
+    Length {
+        #[serde(default)]
+        min: usize,
+        #[serde(default)]
+        max: Option<usize>,
+    },
+    /// Keeps or drops tokens whose whole text matches a regex `pattern`.
+    #[serde(rename = "pattern")]
+    Pattern {
+        #[serde(deserialize_with = "pattern_validator")]
+        pattern: String,
+        #[serde(default)]
+        mode: PatternFilterMode,
+    },
+}
+
+/// Whether tokens matched by a `pattern` token filter are kept or dropped.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PatternFilterMode {
+    #[serde(rename = "drop")]
+    #[default]
+    Drop,
+    #[serde(rename = "keep")]
+    Keep,
 }
 
 fn default_length_limit() -> usize {
     40
 }
 
+fn default_pinyin_full() -> bool {
+    true
+}
+
+fn default_pinyin_keep_original() -> bool {
+    true
+}
+
+fn default_shingle_size() -> usize {
+    2
+}
+
+fn default_output_unigrams() -> bool {
+    true
+}
+
+fn default_shingle_separator() -> String {
+    " ".to_string()
+}
+
 fn default_min_gram() -> usize {
     2
 }
@@ -125,6 +292,10 @@ fn default_max_gram() -> usize {
     3
 }
 
+fn default_date_input_formats() -> Vec<DateInputFormat> {
+    vec![DateInputFormat::Rfc3339, DateInputFormat::EpochMillis]
+}
+
 fn default_indexed() -> bool {
     true
 }
@@ -182,6 +353,59 @@ where
     Ok(stop_word_filters)
 }
 
+fn date_input_formats_validator<'de, D>(deserializer: D) -> Result<Vec<DateInputFormat>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let input_formats: Vec<DateInputFormat> = Vec::deserialize(deserializer)?;
+    if input_formats.is_empty() {
+        return Err(serde::de::Error::custom(
+            "date input_formats can't be empty".to_string(),
+        ));
+    }
+    Ok(input_formats)
+}
+
+fn vector_dims_validator<'de, D>(deserializer: D) -> Result<usize, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let dims: usize = usize::deserialize(deserializer)?;
+    if dims == 0 {
+        return Err(serde::de::Error::custom(
+            "vector dims should be positive".to_string(),
+        ));
+    }
+    Ok(dims)
+}
+
+fn vector_index_validator<'de, D>(deserializer: D) -> Result<VectorIndexType, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let index: VectorIndexType = VectorIndexType::deserialize(deserializer)?;
+    index.validate().map_err(serde::de::Error::custom)?;
+    Ok(index)
+}
+
+fn default_sparse_vector_scale() -> u32 {
+    100
+}
+
+fn sparse_vector_scale_validator<'de, D>(deserializer: D) -> Result<u32, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let scale: u32 = u32::deserialize(deserializer)?;
+    if scale == 0 || scale > 10_000 {
+        return Err(serde::de::Error::custom(format!(
+            "sparse vector scale should be in [1, 10000], got {}",
+            scale
+        )));
+    }
+    Ok(scale)
+}
+
 fn stem_languages_filters_validator<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
 where
     D: Deserializer<'de>,
@@ -199,26 +423,202 @@ where
     Ok(stem_languages)
 }
 
+fn stop_word_language_validator<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let language = String::deserialize(deserializer)?;
+    SupportFilterLanguage::from_str(&language).map_err(|err| {
+        serde::de::Error::custom(format!(
+            "Invalid stop word filter: {}, error: {}",
+            language, err
+        ))
+    })?;
+    Ok(language)
+}
+
+fn stem_language_validator<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let language = String::deserialize(deserializer)?;
+    SupportLanguageAlgorithm::from_str(&language).map_err(|err| {
+        serde::de::Error::custom(format!(
+            "Invalid stem language: {}, error: {}",
+            language, err
+        ))
+    })?;
+    Ok(language)
+}
+
+fn routing_languages_validator<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let languages: Vec<String> = Vec::deserialize(deserializer)?;
+    languages
+        .iter()
+        .try_for_each(|item| match SupportRoutingLanguage::from_str(item) {
+            Ok(_) => Ok(()),
+            Err(err) => Err(serde::de::Error::custom(format!(
+                "Invalid routing language: {}, error: {}",
+                item, err
+            ))),
+        })?;
+    Ok(languages)
+}
+
+fn default_min_confidence() -> f64 {
+    0.5
+}
+
+/// LanguageRouting detects language of each text at ingest, text of a routed language
+/// is also indexed in sub-field `{column}_{language}` analyzed for the language,
+/// e.g. `text_en` is stemmed by english stemmer and `text_zh` is segmented by jieba.
+/// Queries of the column fan out across its sub-fields.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct LanguageRouting {
+    /// ISO 639-1 codes of languages that get a sub-field.
+    #[serde(deserialize_with = "routing_languages_validator")]
+    pub languages: Vec<String>,
+    /// Text detected with lower confidence is only indexed in the column.
+    #[serde(default = "default_min_confidence")]
+    pub min_confidence: f64,
+}
+
+fn pattern_validator<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let pattern = String::deserialize(deserializer)?;
+    regex::Regex::new(&pattern).map_err(|err| {
+        serde::de::Error::custom(format!("Invalid pattern: {}, error: {}", pattern, err))
+    })?;
+    Ok(pattern)
+}
+
+/// ColumnCharFilter rewrites text of a text column before it's tokenized,
+/// char filters are applied in the order they are listed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+#[serde(tag = "type")]
+pub enum ColumnCharFilter {
+    /// Strips html tags, comments, scripts and styles, decodes common entities.
+    #[serde(rename = "html_strip")]
+    HtmlStrip,
+    /// Replaces all matches of a regex `pattern` by `replacement`.
+    #[serde(rename = "pattern_replace")]
+    PatternReplace {
+        #[serde(deserialize_with = "pattern_validator")]
+        pattern: String,
+        #[serde(default)]
+        replacement: String,
+    },
+}
+
+/// ColumnRecordOption controls what is recorded in postings of a text column.
+/// Fields that never need phrase queries can skip positions to shrink the index.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum ColumnRecordOption {
+    /// Only doc ids are recorded, BM25 scores treat each term frequency as 1.
+    #[serde(rename = "basic")]
+    Basic,
+    /// Doc ids and term frequencies are recorded, phrase queries aren't supported.
+    #[serde(rename = "freqs")]
+    WithFreqs,
+    /// Doc ids, term frequencies and positions are recorded.
+    #[serde(rename = "positions")]
+    #[default]
+    WithFreqsAndPositions,
+}
+
 /// Column represents the specific configurations for each column.
-/// Currently, it only supports adding the tokenizer configuration for each column.
-/// In the future, it can be further extended to include more configuration options.
+/// It supports the tokenizer and the index record option of each column.
+/// `stored` and `indexed` override `store_doc` and `indexed` of tokenizer when given,
+/// e.g. a text column whose source is kept by host can be indexed without being stored,
+/// and a column only read back by `get_stored_fields` can be stored without being indexed.
+/// `query_tokenizer` analyzes query text of a text column instead of `tokenizer`,
+/// e.g. a `stem` column can be queried by a `simple` tokenizer to match only exact words.
+/// `language_routing` adds language specific sub-fields to a text column of mixed languages.
+/// `char_filters` rewrite text of a text column before tokenization, e.g. strip html markup,
+/// they apply to indexed text, query text and language sub-fields.
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct Column {
     tokenizer: ColumnTokenizer,
+    #[serde(default)]
+    query_tokenizer: Option<ColumnTokenizer>,
+    #[serde(default)]
+    language_routing: Option<LanguageRouting>,
+    #[serde(default)]
+    char_filters: Vec<ColumnCharFilter>,
+    #[serde(default)]
+    index_record_option: ColumnRecordOption,
+    #[serde(default)]
+    stored: Option<bool>,
+    #[serde(default)]
+    indexed: Option<bool>,
 }
 
 impl Column {
     pub fn get_tokenizer(&self) -> &ColumnTokenizer {
         &self.tokenizer
     }
+
+    pub fn get_query_tokenizer(&self) -> Option<&ColumnTokenizer> {
+        self.query_tokenizer.as_ref()
+    }
+
+    pub fn get_language_routing(&self) -> Option<&LanguageRouting> {
+        self.language_routing.as_ref()
+    }
+
+    pub fn get_char_filters(&self) -> &Vec<ColumnCharFilter> {
+        &self.char_filters
+    }
+
+    pub fn get_index_record_option(&self) -> ColumnRecordOption {
+        self.index_record_option
+    }
+
+    pub fn get_stored(&self) -> Option<bool> {
+        self.stored
+    }
+
+    pub fn get_indexed(&self) -> Option<bool> {
+        self.indexed
+    }
+}
+
+/// Order of documents sorted by `IndexSort`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IndexSortOrder {
+    #[serde(rename = "asc")]
+    #[default]
+    Asc,
+    #[serde(rename = "desc")]
+    Desc,
+}
+
+/// IndexSort sorts documents by a fast field when they are added to index writer,
+/// `field` should be `row_id` or a numeric column, numeric sort column is stored as fast field.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct IndexSort {
+    pub field: String,
+    #[serde(default)]
+    pub order: IndexSortOrder,
 }
 
 /// Config represents the parameter configuration passed by ClickHouse when creating a Tantivy index.
 /// Currently, ClickHouse only passes a single string to Tantivy,
 /// and this string needs to conform to the JSON5 specification and be parsed into the Config struct.
+/// `index_sort` is an index level option, it can't be used as a column name.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Config {
+    #[serde(default)]
+    index_sort: Option<IndexSort>,
     #[serde(flatten)]
     columns: std::collections::HashMap<String, Column>,
 }
@@ -227,4 +627,8 @@ impl Config {
     pub fn get_columns(&self) -> &std::collections::HashMap<String, Column> {
         &self.columns
     }
+
+    pub fn get_index_sort(&self) -> Option<&IndexSort> {
+        self.index_sort.as_ref()
+    }
 }
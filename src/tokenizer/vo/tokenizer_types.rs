@@ -2,14 +2,20 @@
 pub enum TokenizerType {
     Default(String),
     Raw(String),
+    KeywordLowercase(String),
     Simple(String),
     Stem(String),
     WhiteSpace(String),
     Ngram(String),
     Chinese(String),
+    Custom(String),
     I64(String),
     F64(String),
     Bytes(String),
+    Date(String),
+    GeoPoint(String),
+    Vector(String),
+    SparseVector(String),
 }
 
 impl TokenizerType {
@@ -17,14 +23,20 @@ impl TokenizerType {
         match self {
             TokenizerType::Default(name) => name,
             TokenizerType::Raw(name) => name,
+            TokenizerType::KeywordLowercase(name) => name,
             TokenizerType::Simple(name) => name,
             TokenizerType::Stem(name) => name,
             TokenizerType::WhiteSpace(name) => name,
             TokenizerType::Ngram(name) => name,
             TokenizerType::Chinese(name) => name,
+            TokenizerType::Custom(name) => name,
             TokenizerType::I64(name) => name,
             TokenizerType::F64(name) => name,
             TokenizerType::Bytes(name) => name,
+            TokenizerType::Date(name) => name,
+            TokenizerType::GeoPoint(name) => name,
+            TokenizerType::Vector(name) => name,
+            TokenizerType::SparseVector(name) => name,
         }
     }
 }
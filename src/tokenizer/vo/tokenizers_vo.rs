@@ -1,13 +1,35 @@
+use tantivy::schema::IndexRecordOption;
 use tantivy::tokenizer::TextAnalyzer;
 
+use super::language::SupportRoutingLanguage;
 use super::tokenizer_types::TokenizerType;
+use crate::tokenizer::date_format::DateFormat;
+use crate::tokenizer::sparse_vector_format::SparseVectorFormat;
+use crate::tokenizer::vector_format::VectorFormat;
+
+/// Language sub-fields of a column configured by `language_routing`.
+pub struct LanguageRoutingConfig {
+    pub languages: Vec<(SupportRoutingLanguage, TextAnalyzer)>,
+    pub min_confidence: f64,
+}
 
 pub struct TokenizerConfig {
     pub tokenizer_type: TokenizerType,
     pub text_analyzer: TextAnalyzer,
+    // Analyzer of query text, `None` if query text uses `text_analyzer`.
+    pub query_text_analyzer: Option<TextAnalyzer>,
+    // Language sub-fields of a text column, `None` if language routing isn't configured.
+    pub language_routing: Option<LanguageRoutingConfig>,
     pub doc_store: bool,
     pub doc_index: bool,
     pub is_text_field: bool,
+    pub index_record_option: IndexRecordOption,
+    // Formats and precision of a date column, `None` for other columns.
+    pub date_format: Option<DateFormat>,
+    // Dimensions, metric and index type of a vector column, `None` for other columns.
+    pub vector_format: Option<VectorFormat>,
+    // Quantization of a sparse vector column, `None` for other columns.
+    pub sparse_vector_format: Option<SparseVectorFormat>,
 }
 
 impl TokenizerConfig {
@@ -15,9 +37,15 @@ impl TokenizerConfig {
         Self {
             tokenizer_type: tokenizer_type.clone(),
             text_analyzer: analyzer.clone(),
+            query_text_analyzer: None,
+            language_routing: None,
             doc_store: stored,
             doc_index: true,
             is_text_field: true,
+            index_record_option: IndexRecordOption::WithFreqsAndPositions,
+            date_format: None,
+            vector_format: None,
+            sparse_vector_format: None,
         }
     }
 
@@ -25,9 +53,48 @@ impl TokenizerConfig {
         Self {
             tokenizer_type: tokenizer_type.clone(),
             text_analyzer: TextAnalyzer::default(),
+            query_text_analyzer: None,
+            language_routing: None,
             doc_store: stored,
             doc_index: indexed,
             is_text_field: false,
+            index_record_option: IndexRecordOption::WithFreqsAndPositions,
+            date_format: None,
+            vector_format: None,
+            sparse_vector_format: None,
+        }
+    }
+
+    pub fn new_date(date_format: DateFormat, stored: bool, indexed: bool) -> Self {
+        Self {
+            date_format: Some(date_format),
+            ..Self::new_non_text(TokenizerType::Date("date".to_string()), stored, indexed)
+        }
+    }
+
+    pub fn new_vector(vector_format: VectorFormat) -> Self {
+        Self {
+            vector_format: Some(vector_format),
+            ..Self::new_non_text(TokenizerType::Vector("vector".to_string()), false, false)
+        }
+    }
+
+    pub fn new_sparse_vector(sparse_vector_format: SparseVectorFormat) -> Self {
+        Self {
+            sparse_vector_format: Some(sparse_vector_format),
+            index_record_option: IndexRecordOption::WithFreqs,
+            ..Self::new_non_text(
+                TokenizerType::SparseVector("sparse_vector".to_string()),
+                false,
+                true,
+            )
         }
     }
+
+    // Analyzer used by queries of the column.
+    pub fn query_analyzer(&self) -> TextAnalyzer {
+        self.query_text_analyzer
+            .clone()
+            .unwrap_or_else(|| self.text_analyzer.clone())
+    }
 }
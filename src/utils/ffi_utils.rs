@@ -1,4 +1,8 @@
+use crate::common::constants::UTF8_POLICY;
+use crate::common::metrics::tantivy_metrics_prometheus;
+use crate::common::utf8_policy::Utf8Policy;
 use crate::logger::logger_bridge::TantivySearchLogger;
+use crate::logger::trace_profiler::TraceProfiler;
 use crate::tokenizer::tokenizer_utils::TokenizerUtils;
 use crate::BoolResult;
 use crate::CXX_STRING_CONERTER;
@@ -36,3 +40,105 @@ pub fn ffi_varify_index_parameter(index_json_parameter: &CxxString) -> BoolResul
         }
     }
 }
+
+pub fn ffi_set_utf8_policy(policy: &CxxString) -> BoolResult {
+    let policy: String = match CXX_STRING_CONERTER.convert(policy) {
+        Ok(policy) => policy,
+        Err(e) => {
+            ERROR!(function: "ffi_set_utf8_policy", "{}", e);
+            return BoolResult {
+                result: false,
+                error_code: 1,
+                error_msg: format!("Error convert utf-8 policy: {}", e),
+            };
+        }
+    };
+    match Utf8Policy::from_name(&policy) {
+        Some(policy) => {
+            UTF8_POLICY.set(policy);
+            BoolResult {
+                result: true,
+                error_code: 0,
+                error_msg: String::new(),
+            }
+        }
+        None => {
+            let error_msg = format!(
+                "Unknown utf-8 policy `{}`, expect `reject`, `lossy` or `skip_row`",
+                policy
+            );
+            ERROR!(function: "ffi_set_utf8_policy", "{}", error_msg);
+            BoolResult {
+                result: false,
+                error_code: 1,
+                error_msg,
+            }
+        }
+    }
+}
+
+pub fn ffi_start_trace_profiling(
+    output_path: &CxxString,
+    format: &CxxString,
+    duration_secs: u64,
+) -> BoolResult {
+    let output_path: String = match CXX_STRING_CONERTER.convert(output_path) {
+        Ok(path) => path,
+        Err(e) => {
+            ERROR!(function: "ffi_start_trace_profiling", "{}", e);
+            return BoolResult {
+                result: false,
+                error_code: 1,
+                error_msg: format!("Error convert output path: {}", e),
+            };
+        }
+    };
+    let format: String = match CXX_STRING_CONERTER.convert(format) {
+        Ok(format) => format,
+        Err(e) => {
+            ERROR!(function: "ffi_start_trace_profiling", "{}", e);
+            return BoolResult {
+                result: false,
+                error_code: 1,
+                error_msg: format!("Error convert trace format: {}", e),
+            };
+        }
+    };
+    match TraceProfiler::start(&output_path, &format, duration_secs) {
+        Ok(_) => BoolResult {
+            result: true,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_start_trace_profiling", "{}", e);
+            BoolResult {
+                result: false,
+                error_code: 1,
+                error_msg: e,
+            }
+        }
+    }
+}
+
+pub fn ffi_stop_trace_profiling() -> BoolResult {
+    match TraceProfiler::stop() {
+        Ok(stopped) => BoolResult {
+            result: stopped,
+            error_code: 0,
+            error_msg: String::new(),
+        },
+        Err(e) => {
+            ERROR!(function: "ffi_stop_trace_profiling", "{}", e);
+            BoolResult {
+                result: false,
+                error_code: 1,
+                error_msg: e,
+            }
+        }
+    }
+}
+
+pub fn ffi_metrics_prometheus() -> String {
+    tantivy_metrics_prometheus()
+}
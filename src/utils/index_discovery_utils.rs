@@ -0,0 +1,164 @@
+use crate::common::constants::INDEX_INFO_FILE_NAME;
+use crate::common::errors::IndexUtilsError;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+// Directories built or swapped aside by reindex and upgrade, they aren't live indexes.
+const TRANSIENT_DIRECTORY_SUFFIXES: [&str; 2] = [".reindex", ".swap"];
+
+/// `IndexDiscoveryUtils` finds index directories under a root directory after a restart,
+/// and warms files of the ones which are likely to be searched soon.
+pub struct IndexDiscoveryUtils;
+
+impl IndexDiscoveryUtils {
+    /// Whether `directory` looks like an index, i.e. it holds index settings and tantivy meta.
+    pub fn is_index_directory(directory: &Path) -> bool {
+        directory.join(INDEX_INFO_FILE_NAME).is_file() && directory.join("meta.json").is_file()
+    }
+
+    /// Index directories under `root_directory` (itself included) in path order. Index
+    /// directories aren't descended, hidden and transient directories are skipped, symlinks
+    /// aren't followed so links can't loop.
+    pub fn discover_indexes(root_directory: &Path) -> Result<Vec<PathBuf>, IndexUtilsError> {
+        let mut index_directories: Vec<PathBuf> = Vec::new();
+        let mut pending: Vec<PathBuf> = vec![root_directory.to_path_buf()];
+        while let Some(directory) = pending.pop() {
+            if Self::is_index_directory(&directory) {
+                index_directories.push(directory);
+                continue;
+            }
+            let entries = fs::read_dir(&directory).map_err(|e| {
+                IndexUtilsError::DirectoryIOError(format!(
+                    "Failed to list {:?}, message: {}",
+                    directory, e
+                ))
+            })?;
+            for entry in entries.flatten() {
+                let is_directory: bool = entry
+                    .file_type()
+                    .map_or(false, |file_type| file_type.is_dir());
+                let name = entry.file_name();
+                let name: &str = match name.to_str() {
+                    Some(name) => name,
+                    None => continue,
+                };
+                if !is_directory
+                    || name.starts_with('.')
+                    || TRANSIENT_DIRECTORY_SUFFIXES
+                        .iter()
+                        .any(|suffix| name.ends_with(suffix))
+                {
+                    continue;
+                }
+                pending.push(entry.path());
+            }
+        }
+        index_directories.sort();
+        Ok(index_directories)
+    }
+
+    /// Whether index in `index_directory` was committed within `within`.
+    pub fn committed_within(index_directory: &Path, within: Duration) -> bool {
+        fs::metadata(index_directory.join("meta.json"))
+            .and_then(|metadata| metadata.modified())
+            .map_or(false, |modified| {
+                SystemTime::now()
+                    .duration_since(modified)
+                    .map_or(true, |elapsed| elapsed <= within)
+            })
+    }
+
+    /// Read files of `index_directory` through, so first searches find them in page cache.
+    /// Hidden files, i.e. locks and tantivy bookkeeping, are skipped. Return bytes read.
+    pub fn warm_index_files(index_directory: &Path) -> Result<u64, IndexUtilsError> {
+        let read_error = |path: &Path, e: io::Error| {
+            IndexUtilsError::DirectoryIOError(format!("Failed to read {:?}, message: {}", path, e))
+        };
+        let mut warmed_bytes: u64 = 0;
+        let entries = fs::read_dir(index_directory).map_err(|e| read_error(index_directory, e))?;
+        for entry in entries.flatten() {
+            let is_file: bool = entry
+                .file_type()
+                .map_or(false, |file_type| file_type.is_file());
+            if !is_file || entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let path: PathBuf = entry.path();
+            let mut file: File = match File::open(&path) {
+                Ok(file) => file,
+                // Garbage collected by a writer meanwhile.
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(read_error(&path, e)),
+            };
+            warmed_bytes +=
+                io::copy(&mut file, &mut io::sink()).map_err(|e| read_error(&path, e))?;
+        }
+        Ok(warmed_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn mock_index(directory: &Path) {
+        fs::create_dir_all(directory).unwrap();
+        fs::write(directory.join(INDEX_INFO_FILE_NAME), "{}").unwrap();
+        fs::write(directory.join("meta.json"), "{}").unwrap();
+    }
+
+    #[test]
+    fn test_discover_indexes() {
+        let temp_directory = TempDir::new().unwrap();
+        let root: &Path = temp_directory.path();
+        mock_index(&root.join("table_a/part_1"));
+        mock_index(&root.join("table_a/part_0"));
+        mock_index(&root.join("table_b"));
+        // Nested in an index, hidden, transient, or incomplete directories aren't indexes.
+        mock_index(&root.join("table_b/nested"));
+        mock_index(&root.join(".trash/table_c"));
+        mock_index(&root.join("table_a.reindex"));
+        fs::create_dir_all(root.join("empty")).unwrap();
+        fs::write(root.join("empty/meta.json"), "{}").unwrap();
+
+        assert_eq!(
+            IndexDiscoveryUtils::discover_indexes(root).unwrap(),
+            vec![
+                root.join("table_a/part_0"),
+                root.join("table_a/part_1"),
+                root.join("table_b"),
+            ]
+        );
+        assert!(IndexDiscoveryUtils::discover_indexes(&root.join("missing")).is_err());
+        // Root itself can be an index.
+        assert_eq!(
+            IndexDiscoveryUtils::discover_indexes(&root.join("table_b")).unwrap(),
+            vec![root.join("table_b")]
+        );
+    }
+
+    #[test]
+    fn test_warm_index_files() {
+        let temp_directory = TempDir::new().unwrap();
+        let index_directory: &Path = temp_directory.path();
+        mock_index(index_directory);
+        fs::write(index_directory.join("segment.idx"), vec![0u8; 1000]).unwrap();
+        fs::write(index_directory.join(".tantivy-writer.lock"), "lock").unwrap();
+
+        assert_eq!(
+            IndexDiscoveryUtils::warm_index_files(index_directory).unwrap(),
+            1004
+        );
+        assert!(IndexDiscoveryUtils::committed_within(
+            index_directory,
+            Duration::from_secs(3600)
+        ));
+        assert!(!IndexDiscoveryUtils::committed_within(
+            &index_directory.join("missing"),
+            Duration::from_secs(3600)
+        ));
+    }
+}
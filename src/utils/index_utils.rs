@@ -5,6 +5,7 @@ use crate::tokenizer::dto::index_parameter_dto::IndexParameterDTO;
 use crate::{common::constants::LOG_CALLBACK, WARNING};
 use std::fs::File;
 use std::io::{Read, Write};
+use std::path::PathBuf;
 use std::{fs, path::Path};
 
 /// `IndexUtils` serves as a collection of utility functions for index operations.
@@ -101,4 +102,61 @@ impl IndexUtils {
         })?;
         Ok(result)
     }
+
+    /// Swap contents of directories `path` and `other`, both must exist.
+    /// On linux they are exchanged atomically, elsewhere (or on file systems without exchange)
+    /// `path` is renamed aside first, a crash in between leaves it at `other` with `.swap` suffix.
+    pub fn swap_directories(path: &Path, other: &Path) -> Result<(), IndexUtilsError> {
+        if Self::exchange_directories(path, other).is_ok() {
+            return Ok(());
+        }
+        let mut aside = other.as_os_str().to_owned();
+        aside.push(".swap");
+        let aside: PathBuf = PathBuf::from(aside);
+        let rename = |from: &Path, to: &Path| {
+            fs::rename(from, to).map_err(|e| {
+                IndexUtilsError::DirectoryIOError(format!(
+                    "Failed to rename {:?} to {:?}, message: {}",
+                    from, to, e
+                ))
+            })
+        };
+        rename(path, &aside)?;
+        rename(other, path)?;
+        rename(&aside, other)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn exchange_directories(path: &Path, other: &Path) -> std::io::Result<()> {
+        use std::ffi::CString;
+        use std::os::unix::ffi::OsStrExt;
+        let to_c_string = |path: &Path| {
+            CString::new(path.as_os_str().as_bytes())
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))
+        };
+        let (path, other) = (to_c_string(path)?, to_c_string(other)?);
+        // Safety: both paths are nul terminated and outlive the call.
+        let status = unsafe {
+            libc::syscall(
+                libc::SYS_renameat2,
+                libc::AT_FDCWD,
+                path.as_ptr(),
+                libc::AT_FDCWD,
+                other.as_ptr(),
+                libc::RENAME_EXCHANGE,
+            )
+        };
+        if status != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn exchange_directories(_path: &Path, _other: &Path) -> std::io::Result<()> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "atomic exchange is only supported on linux",
+        ))
+    }
 }
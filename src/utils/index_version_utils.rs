@@ -0,0 +1,129 @@
+use crate::common::constants::INDEX_FORMAT_VERSION;
+use crate::common::errors::TantivySearchError;
+use crate::tokenizer::dto::index_parameter_dto::IndexParameterDTO;
+use crate::utils::index_utils::IndexUtils;
+use std::path::Path;
+
+// Version of this crate, recorded to tell users which version wrote an index.
+const CRATE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// `IndexVersionUtils` records versions which wrote an index into its `IndexParameterDTO`,
+/// and checks them before index files are opened, so a downgraded library refuses indexes it
+/// can't read instead of failing deep in tantivy or misreading them.
+pub struct IndexVersionUtils;
+
+impl IndexVersionUtils {
+    /// Record versions of this library into `index_parameter_dto`.
+    pub fn stamp(index_parameter_dto: &mut IndexParameterDTO) {
+        index_parameter_dto.format_version = INDEX_FORMAT_VERSION;
+        index_parameter_dto.crate_version = CRATE_VERSION.to_string();
+        index_parameter_dto.tantivy_format_version = tantivy::INDEX_FORMAT_VERSION;
+    }
+
+    /// Check recorded versions can be read by this library, versions not recorded pass.
+    pub fn check_compatible(index_parameter_dto: &IndexParameterDTO) -> Result<(), String> {
+        let written_by = if index_parameter_dto.crate_version.is_empty() {
+            "an unknown version".to_string()
+        } else {
+            format!("version {}", index_parameter_dto.crate_version)
+        };
+        if index_parameter_dto.format_version > INDEX_FORMAT_VERSION {
+            return Err(format!(
+                "index format version {} is newer than supported version {}, index was written by {}, this library is version {}",
+                index_parameter_dto.format_version, INDEX_FORMAT_VERSION, written_by, CRATE_VERSION
+            ));
+        }
+        let tantivy_format_version: u32 = index_parameter_dto.tantivy_format_version;
+        if tantivy_format_version > tantivy::INDEX_FORMAT_VERSION {
+            return Err(format!(
+                "tantivy format version {} is newer than supported version {}, index was written by {}, this library is version {}",
+                tantivy_format_version, tantivy::INDEX_FORMAT_VERSION, written_by, CRATE_VERSION
+            ));
+        }
+        if tantivy_format_version != 0
+            && tantivy_format_version < tantivy::INDEX_FORMAT_OLDEST_SUPPORTED_VERSION
+        {
+            return Err(format!(
+                "tantivy format version {} is older than oldest supported version {}, index was written by {}, it must be rebuilt",
+                tantivy_format_version, tantivy::INDEX_FORMAT_OLDEST_SUPPORTED_VERSION, written_by
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether index was written by an older version, or before versions were recorded,
+    /// and its segments should be rewritten by `upgrade_index`.
+    pub fn is_outdated(index_parameter_dto: &IndexParameterDTO) -> bool {
+        index_parameter_dto.format_version < INDEX_FORMAT_VERSION
+            || index_parameter_dto.tantivy_format_version < tantivy::INDEX_FORMAT_VERSION
+    }
+
+    /// Load `IndexParameterDTO` of `index_files_directory` and check its versions.
+    pub fn check_index(
+        index_files_directory: &Path,
+    ) -> Result<IndexParameterDTO, TantivySearchError> {
+        let index_parameter_dto: IndexParameterDTO =
+            IndexUtils::load_custom_index_setting(index_files_directory)?;
+        Self::check_compatible(&index_parameter_dto).map_err(|e| {
+            TantivySearchError::IncompatibleIndexVersion(format!(
+                "{}, index_path:[{:?}]",
+                e, index_files_directory
+            ))
+        })?;
+        Ok(index_parameter_dto)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_compatible() {
+        let mut index_parameter_dto = IndexParameterDTO::default();
+        assert!(IndexVersionUtils::check_compatible(&index_parameter_dto).is_ok());
+        assert!(IndexVersionUtils::is_outdated(&index_parameter_dto));
+
+        IndexVersionUtils::stamp(&mut index_parameter_dto);
+        assert!(IndexVersionUtils::check_compatible(&index_parameter_dto).is_ok());
+        assert!(!IndexVersionUtils::is_outdated(&index_parameter_dto));
+
+        // Written by a newer version.
+        let mut newer = IndexParameterDTO {
+            format_version: INDEX_FORMAT_VERSION + 1,
+            crate_version: "99.0.0".to_string(),
+            ..IndexParameterDTO::default()
+        };
+        let error: String = IndexVersionUtils::check_compatible(&newer).unwrap_err();
+        assert!(error.contains("index format version"));
+        assert!(error.contains("99.0.0"));
+
+        newer.format_version = INDEX_FORMAT_VERSION;
+        newer.tantivy_format_version = tantivy::INDEX_FORMAT_VERSION + 1;
+        let error: String = IndexVersionUtils::check_compatible(&newer).unwrap_err();
+        assert!(error.contains("tantivy format version"));
+
+        // Tantivy format too old to be read, let alone upgraded.
+        if tantivy::INDEX_FORMAT_OLDEST_SUPPORTED_VERSION > 1 {
+            newer.tantivy_format_version = tantivy::INDEX_FORMAT_OLDEST_SUPPORTED_VERSION - 1;
+            assert!(IndexVersionUtils::check_compatible(&newer)
+                .unwrap_err()
+                .contains("must be rebuilt"));
+        }
+    }
+
+    #[test]
+    fn test_recorded_versions_round_trip() {
+        // Settings written before versions were recorded still load.
+        let old: IndexParameterDTO =
+            serde_json::from_str(r#"{"tokenizers_json_parameter":"{}"}"#).unwrap();
+        assert_eq!(old, IndexParameterDTO::default());
+
+        let mut index_parameter_dto = IndexParameterDTO::default();
+        IndexVersionUtils::stamp(&mut index_parameter_dto);
+        let json: String = serde_json::to_string(&index_parameter_dto).unwrap();
+        let loaded: IndexParameterDTO = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, index_parameter_dto);
+        assert_eq!(loaded.crate_version, CRATE_VERSION);
+    }
+}
@@ -1,2 +1,9 @@
 pub mod ffi_utils;
+pub mod index_discovery_utils;
 pub mod index_utils;
+pub mod index_version_utils;
+pub mod orphan_file_utils;
+pub mod path_utils;
+pub mod segment_id_utils;
+pub mod time_utils;
+pub mod writer_lock_utils;
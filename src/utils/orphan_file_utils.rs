@@ -0,0 +1,201 @@
+use crate::common::constants::QUERY_RESULT_CACHE_DIR_NAME;
+use crate::common::errors::IndexUtilsError;
+use crate::utils::segment_id_utils::META_FILE_NAME;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+// Query result cache is written by readers of other processes too,
+// only their temp files older than it are orphans.
+static QUERY_CACHE_TEMP_MIN_AGE: Duration = Duration::from_secs(3600);
+
+/// Orphaned files removed from an index directory and their bytes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ReclaimedFiles {
+    pub files: u64,
+    pub bytes: u64,
+}
+
+/// `OrphanFileUtils` removes files left in index directory by a crashed process.
+///
+/// Temp files of atomic writes are orphans once their writer is gone. Segment files whose
+/// segment isn't in `meta.json` are left by aborted merges and uncommitted segments, and delete
+/// files of other opstamps than `meta.json` records are left by aborted commits.
+/// Tantivy only garbage collects files after commits and merges of a running writer.
+/// It must be called while writer lock is held and before the writer has created any segment.
+pub struct OrphanFileUtils;
+
+impl OrphanFileUtils {
+    // Tantivy names temp files of atomic writes `.tmp*`, ours end with `.tmp`.
+    fn is_temp_file(file_name: &str) -> bool {
+        file_name.starts_with(".tmp") || file_name.ends_with(".tmp")
+    }
+
+    // Segment files are named `{segment_id}.{component}`, delete files `{segment_id}.{opstamp}.del`.
+    // Returns segment id and opstamp of delete file.
+    fn parse_segment_file(file_name: &str) -> Option<(&str, Option<u64>)> {
+        let (segment_id, component) = file_name.split_once('.')?;
+        if segment_id.len() != 32 || !segment_id.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        match component.strip_suffix(".del") {
+            Some(opstamp) => Some((segment_id, Some(opstamp.parse().ok()?))),
+            None => Some((segment_id, None)),
+        }
+    }
+
+    // Segment ids in `meta.json` and opstamps of their delete files, `None` without deletes.
+    fn committed_segments(
+        index_path: &Path,
+    ) -> Result<HashMap<String, Option<u64>>, IndexUtilsError> {
+        let meta_path = index_path.join(META_FILE_NAME);
+        let contents = fs::read_to_string(&meta_path).map_err(|e| {
+            IndexUtilsError::ReadFileError(format!("file: {:?}, message: {}", meta_path, e))
+        })?;
+        let meta: Value = serde_json::from_str(&contents).map_err(|e| {
+            IndexUtilsError::JsonDeserializeError(format!("file: {:?}, message: {}", meta_path, e))
+        })?;
+        let segments = meta
+            .get("segments")
+            .and_then(|segments| segments.as_array())
+            .ok_or_else(|| {
+                IndexUtilsError::JsonParseError(format!(
+                    "file: {:?}, message: missing `segments`",
+                    meta_path
+                ))
+            })?;
+        let mut committed: HashMap<String, Option<u64>> = HashMap::new();
+        for segment in segments {
+            let segment_id: String = segment
+                .get("segment_id")
+                .and_then(|id| id.as_str())
+                .map(|id| id.replace('-', ""))
+                .ok_or_else(|| {
+                    IndexUtilsError::JsonParseError(format!(
+                        "file: {:?}, message: missing `segment_id`",
+                        meta_path
+                    ))
+                })?;
+            let delete_opstamp: Option<u64> = segment
+                .get("deletes")
+                .and_then(|deletes| deletes.get("opstamp"))
+                .and_then(|opstamp| opstamp.as_u64());
+            committed.insert(segment_id, delete_opstamp);
+        }
+        Ok(committed)
+    }
+
+    fn remove_file(path: &Path, reclaimed: &mut ReclaimedFiles) {
+        let bytes: u64 = fs::metadata(path).map_or(0, |metadata| metadata.len());
+        if fs::remove_file(path).is_ok() {
+            reclaimed.files += 1;
+            reclaimed.bytes += bytes;
+        }
+    }
+
+    /// Remove orphaned files of index in `index_path`, returns files and bytes reclaimed.
+    pub fn clean_orphan_files(index_path: &Path) -> Result<ReclaimedFiles, IndexUtilsError> {
+        let committed: HashMap<String, Option<u64>> = Self::committed_segments(index_path)?;
+        let mut reclaimed = ReclaimedFiles::default();
+        let entries = fs::read_dir(index_path).map_err(|e| {
+            IndexUtilsError::DirectoryIOError(format!("path: {:?}, message: {}", index_path, e))
+        })?;
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            if !entry
+                .file_type()
+                .map_or(false, |file_type| file_type.is_file())
+            {
+                continue;
+            }
+            let file_name: String = entry.file_name().to_string_lossy().to_string();
+            let orphan: bool = Self::is_temp_file(&file_name)
+                || match Self::parse_segment_file(&file_name) {
+                    Some((segment_id, delete_opstamp)) => match committed.get(segment_id) {
+                        Some(committed_opstamp) => {
+                            delete_opstamp.is_some() && delete_opstamp != *committed_opstamp
+                        }
+                        None => true,
+                    },
+                    None => false,
+                };
+            if orphan {
+                Self::remove_file(&entry.path(), &mut reclaimed);
+            }
+        }
+
+        let cache_path = index_path.join(QUERY_RESULT_CACHE_DIR_NAME);
+        if let Ok(entries) = fs::read_dir(&cache_path) {
+            let now = SystemTime::now();
+            for entry in entries.filter_map(|entry| entry.ok()) {
+                let file_name: String = entry.file_name().to_string_lossy().to_string();
+                let age: Duration = entry
+                    .metadata()
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+                    .and_then(|modified| now.duration_since(modified).ok())
+                    .unwrap_or_default();
+                if Self::is_temp_file(&file_name) && age >= QUERY_CACHE_TEMP_MIN_AGE {
+                    Self::remove_file(&entry.path(), &mut reclaimed);
+                }
+            }
+        }
+        Ok(reclaimed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::tests::index_3column_docs_with_threads_merge;
+    use tantivy::Index;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_clean_orphan_files() {
+        let temp_directory = TempDir::new().unwrap();
+        let index_path = temp_directory.path();
+        let (index_reader, _) = index_3column_docs_with_threads_merge(index_path.to_str().unwrap());
+        let committed: Vec<String> = OrphanFileUtils::committed_segments(index_path)
+            .unwrap()
+            .into_keys()
+            .collect();
+        let committed_files: Vec<String> = fs::read_dir(index_path)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+
+        // Temp files, segment of an aborted merge and delete file of an aborted commit.
+        let orphans: [(String, usize); 4] = [
+            (".tmpA1b2C3".to_string(), 10),
+            ("meta.json.tmp".to_string(), 20),
+            (format!("{}.idx", "ab".repeat(16)), 30),
+            (format!("{}.99.del", committed[0]), 40),
+        ];
+        for (file_name, bytes) in orphans.iter() {
+            fs::write(index_path.join(file_name), vec![0u8; *bytes]).unwrap();
+        }
+        assert_eq!(
+            OrphanFileUtils::clean_orphan_files(index_path).unwrap(),
+            ReclaimedFiles {
+                files: 4,
+                bytes: 100
+            }
+        );
+        for file_name in committed_files.iter() {
+            assert!(index_path.join(file_name).exists());
+        }
+        assert_eq!(
+            OrphanFileUtils::clean_orphan_files(index_path).unwrap(),
+            ReclaimedFiles::default()
+        );
+
+        // Index is still readable.
+        let index = Index::open_in_dir(index_path).unwrap();
+        assert_eq!(
+            index.reader().unwrap().searcher().num_docs(),
+            index_reader.searcher().num_docs()
+        );
+    }
+}
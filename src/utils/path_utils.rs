@@ -0,0 +1,132 @@
+use std::fs;
+use std::path::PathBuf;
+
+pub struct PathUtils;
+
+impl PathUtils {
+    /// Key of `index_path` in index writer and reader caches, different spellings of one index
+    /// directory share the key so they never load two conflicting writers of the same index.
+    /// Paths are normalized lexically, symlinks and mapped drives aren't resolved.
+    pub fn index_key(index_path: &str) -> String {
+        if cfg!(windows) {
+            Self::windows_key(index_path)
+        } else {
+            Self::unix_key(index_path)
+        }
+    }
+
+    /// Canonical path of an existing `index_path` with relative components and symlinks
+    /// resolved, normalized as `index_key`. `None` if it doesn't exist or can't be resolved.
+    pub fn canonical_key(index_path: &str) -> Option<String> {
+        let canonical_path: PathBuf = fs::canonicalize(Self::index_directory(index_path)).ok()?;
+        canonical_path.to_str().map(Self::index_key)
+    }
+
+    /// Directory of `index_path` for file system access. Windows verbatim paths (`\\?\`), used
+    /// for paths longer than `MAX_PATH`, don't accept `/` as separator, so it's replaced there.
+    /// Other long paths are extended by std itself.
+    pub fn index_directory(index_path: &str) -> PathBuf {
+        if cfg!(windows) && index_path.starts_with(r"\\?\") {
+            PathBuf::from(index_path.replace('/', "\\"))
+        } else {
+            PathBuf::from(index_path)
+        }
+    }
+
+    // Repeated separators and `.` components are dropped, `..` is kept since it may cross symlinks.
+    fn unix_key(index_path: &str) -> String {
+        let components: Vec<&str> = index_path
+            .split('/')
+            .filter(|component| !component.is_empty() && *component != ".")
+            .collect();
+        if index_path.starts_with('/') {
+            format!("/{}", components.join("/"))
+        } else {
+            components.join("/")
+        }
+    }
+
+    // Verbatim and device prefixes are stripped, both separators are accepted,
+    // case is folded since Windows file systems are case-insensitive.
+    fn windows_key(index_path: &str) -> String {
+        let path: String = index_path.replace('/', "\\");
+        let (root, rest): (&str, &str) = if let Some(rest) = path.strip_prefix(r"\\?\UNC\") {
+            (r"\\", rest)
+        } else if let Some(rest) = path
+            .strip_prefix(r"\\?\")
+            .or_else(|| path.strip_prefix(r"\\.\"))
+        {
+            ("", rest)
+        } else if let Some(rest) = path.strip_prefix(r"\\") {
+            (r"\\", rest)
+        } else if let Some(rest) = path.strip_prefix('\\') {
+            ("\\", rest)
+        } else {
+            ("", path.as_str())
+        };
+        let components: Vec<&str> = rest
+            .split('\\')
+            .filter(|component| !component.is_empty() && *component != ".")
+            .collect();
+        format!("{}{}", root, components.join("\\")).to_lowercase()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_unix_key() {
+        assert_eq!(PathUtils::unix_key("/data/index/"), "/data/index");
+        assert_eq!(PathUtils::unix_key("/data//./index"), "/data/index");
+        assert_eq!(PathUtils::unix_key("./data/index//"), "data/index");
+        assert_eq!(PathUtils::unix_key("/data/../index"), "/data/../index");
+        assert_eq!(PathUtils::unix_key("/Data/Index"), "/Data/Index");
+    }
+
+    #[test]
+    fn test_canonical_key() {
+        let temp_directory = TempDir::new().unwrap();
+        let index_path: PathBuf = fs::canonicalize(temp_directory.path()).unwrap();
+        let index_key: String = PathUtils::index_key(index_path.to_str().unwrap());
+        let spelling: String = format!(
+            "{}/../{}/",
+            index_key,
+            index_key.rsplit('/').next().unwrap()
+        );
+        assert_eq!(PathUtils::canonical_key(&spelling), Some(index_key.clone()));
+        assert_eq!(
+            PathUtils::canonical_key(&format!("{}/missing", index_key)),
+            None
+        );
+
+        #[cfg(unix)]
+        {
+            let link_path = temp_directory.path().join("link");
+            std::os::unix::fs::symlink(&index_path, &link_path).unwrap();
+            assert_eq!(
+                PathUtils::canonical_key(link_path.to_str().unwrap()),
+                Some(index_key)
+            );
+        }
+    }
+
+    #[test]
+    fn test_windows_key() {
+        let key: String = PathUtils::windows_key(r"C:\Data\Index");
+        assert_eq!(key, r"c:\data\index");
+        assert_eq!(PathUtils::windows_key("c:/data/index/"), key);
+        assert_eq!(PathUtils::windows_key(r"\\?\C:\Data\.\Index\"), key);
+        assert_eq!(PathUtils::windows_key(r"C:\\data\\index"), key);
+
+        let unc_key: String = PathUtils::windows_key(r"\\Server\Share\Index");
+        assert_eq!(unc_key, r"\\server\share\index");
+        assert_eq!(
+            PathUtils::windows_key(r"\\?\UNC\server\share\index"),
+            unc_key
+        );
+        assert_eq!(PathUtils::windows_key("//server/share/index/"), unc_key);
+    }
+}
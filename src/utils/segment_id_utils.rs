@@ -0,0 +1,320 @@
+use crate::common::errors::IndexUtilsError;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::Path;
+
+pub(crate) static META_FILE_NAME: &str = "meta.json";
+static MANAGED_FILE_NAME: &str = ".managed.json";
+
+/// `SegmentIdUtils` derives segment ids from segment content for deterministic build.
+///
+/// Tantivy generates random segment ids, they appear in segment file names and `meta.json`.
+/// Deriving them from content makes replicas indexing the same input byte-comparable.
+/// It must be called when no index writer is alive on the index directory.
+pub struct SegmentIdUtils;
+
+impl SegmentIdUtils {
+    fn read_json(file_path: &Path) -> Result<Value, IndexUtilsError> {
+        let contents = fs::read_to_string(file_path).map_err(|e| {
+            IndexUtilsError::ReadFileError(format!("file: {:?}, message: {}", file_path, e))
+        })?;
+        serde_json::from_str(&contents).map_err(|e| {
+            IndexUtilsError::JsonDeserializeError(format!("file: {:?}, message: {}", file_path, e))
+        })
+    }
+
+    // Write to a temporary file first, then rename it, like tantivy `atomic_write`.
+    fn write_json(file_path: &Path, value: &Value, pretty: bool) -> Result<(), IndexUtilsError> {
+        let mut contents = if pretty {
+            serde_json::to_string_pretty(value)
+        } else {
+            serde_json::to_string(value)
+        }
+        .map_err(|e| {
+            IndexUtilsError::JsonSerializeError(format!("file: {:?}, message: {}", file_path, e))
+        })?;
+        contents.push('\n');
+        let temp_path = file_path.with_extension("json.tmp");
+        fs::write(&temp_path, contents).map_err(|e| {
+            IndexUtilsError::WriteFileError(format!("file: {:?}, message: {}", temp_path, e))
+        })?;
+        fs::rename(&temp_path, file_path).map_err(|e| {
+            IndexUtilsError::WriteFileError(format!("file: {:?}, message: {}", file_path, e))
+        })
+    }
+
+    // Segment files are named `{segment_id}.{component}`, returns sorted `(component, file_name)`.
+    fn segment_files(file_names: &[String], segment_id: &str) -> BTreeMap<String, String> {
+        let prefix = format!("{}.", segment_id);
+        file_names
+            .iter()
+            .filter_map(|file_name| {
+                file_name
+                    .strip_prefix(&prefix)
+                    .map(|component| (component.to_string(), file_name.clone()))
+            })
+            .collect()
+    }
+
+    // Hash `salt`, segment components and their content with sha256, its first 128 bits are the
+    // segment id. sha256 output is fixed by its specification, so replicas built by different
+    // toolchains or platforms derive the same ids. Lengths are hashed ahead of components and
+    // contents so different splits of the same bytes don't collide.
+    fn hash_segment(
+        index_path: &Path,
+        segment_files: &BTreeMap<String, String>,
+        salt: u64,
+    ) -> Result<String, IndexUtilsError> {
+        let mut hasher = Sha256::new();
+        hasher.update(salt.to_le_bytes());
+        for (component, file_name) in segment_files.iter() {
+            let file_path = index_path.join(file_name);
+            let read_error = |e: io::Error| {
+                IndexUtilsError::ReadFileError(format!("file: {:?}, message: {}", file_path, e))
+            };
+            let mut file = File::open(&file_path).map_err(read_error)?;
+            let file_len: u64 = file.metadata().map_err(read_error)?.len();
+            hasher.update((component.len() as u64).to_le_bytes());
+            hasher.update(component.as_bytes());
+            hasher.update(file_len.to_le_bytes());
+            let mut buffer = vec![0u8; 64 * 1024];
+            loop {
+                let read = file.read(&mut buffer).map_err(read_error)?;
+                if read == 0 {
+                    break;
+                }
+                hasher.update(&buffer[..read]);
+            }
+        }
+        let digest = hasher.finalize();
+        Ok(digest[..16]
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect())
+    }
+
+    // `meta.json` records segment id as hyphenated uuid, file names use the simple form.
+    fn hyphenated(segment_id: &str) -> String {
+        format!(
+            "{}-{}-{}-{}-{}",
+            &segment_id[0..8],
+            &segment_id[8..12],
+            &segment_id[12..16],
+            &segment_id[16..20],
+            &segment_id[20..32]
+        )
+    }
+
+    /// Rename segments with ids derived from their content, returns renamed segments count.
+    pub fn derive_segment_ids(index_path: &Path) -> Result<usize, IndexUtilsError> {
+        let meta_path = index_path.join(META_FILE_NAME);
+        if !meta_path.exists() {
+            return Ok(0);
+        }
+        let mut meta = Self::read_json(&meta_path)?;
+        let file_names: Vec<String> = fs::read_dir(index_path)
+            .map_err(|e| {
+                IndexUtilsError::DirectoryIOError(format!("path: {:?}, message: {}", index_path, e))
+            })?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+
+        let segments = match meta.get_mut("segments").and_then(|s| s.as_array_mut()) {
+            Some(segments) => segments,
+            None => {
+                return Err(IndexUtilsError::JsonParseError(format!(
+                    "file: {:?}, message: missing `segments`",
+                    meta_path
+                )))
+            }
+        };
+
+        let mut old_ids: Vec<String> = Vec::with_capacity(segments.len());
+        for segment in segments.iter() {
+            let old_id: String = match segment.get("segment_id").and_then(|id| id.as_str()) {
+                Some(id) => id.replace('-', ""),
+                None => {
+                    return Err(IndexUtilsError::JsonParseError(format!(
+                        "file: {:?}, message: missing `segment_id`",
+                        meta_path
+                    )))
+                }
+            };
+            if old_id.len() != 32 {
+                return Err(IndexUtilsError::JsonParseError(format!(
+                    "file: {:?}, message: invalid segment_id `{}`",
+                    meta_path, old_id
+                )));
+            }
+            old_ids.push(old_id);
+        }
+
+        // Identical segments share the same hash, they are told apart by salting the hash.
+        // A segment keeps its id if it's already one of the derived candidates.
+        let mut groups: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        for (ordinal, old_id) in old_ids.iter().enumerate() {
+            let segment_files = Self::segment_files(&file_names, old_id);
+            groups
+                .entry(Self::hash_segment(index_path, &segment_files, 0)?)
+                .or_default()
+                .push(ordinal);
+        }
+        let mut new_ids: Vec<String> = old_ids.clone();
+        for (hash, ordinals) in groups.iter() {
+            let mut candidates: Vec<String> = vec![hash.clone()];
+            for salt in 1..ordinals.len() as u64 {
+                let segment_files = Self::segment_files(&file_names, &old_ids[ordinals[0]]);
+                candidates.push(Self::hash_segment(index_path, &segment_files, salt)?);
+            }
+            let kept: HashSet<&String> = ordinals
+                .iter()
+                .map(|ordinal| &old_ids[*ordinal])
+                .filter(|old_id| candidates.contains(old_id))
+                .collect();
+            let mut free_candidates = candidates.iter().filter(|id| !kept.contains(id));
+            for ordinal in ordinals.iter() {
+                if !kept.contains(&old_ids[*ordinal]) {
+                    if let Some(new_id) = free_candidates.next() {
+                        new_ids[*ordinal] = new_id.clone();
+                    }
+                }
+            }
+        }
+
+        // Map from old segment file name to new segment file name.
+        let mut renamed_files: BTreeMap<String, String> = BTreeMap::new();
+        let mut renamed_segments: usize = 0;
+        for (ordinal, segment) in segments.iter_mut().enumerate() {
+            let (old_id, new_id) = (&old_ids[ordinal], &new_ids[ordinal]);
+            if old_id == new_id {
+                continue;
+            }
+            for (component, file_name) in Self::segment_files(&file_names, old_id).iter() {
+                renamed_files.insert(file_name.clone(), format!("{}.{}", new_id, component));
+            }
+            segment["segment_id"] = Value::String(Self::hyphenated(new_id));
+            renamed_segments += 1;
+        }
+        // Segments order in `meta.json` follows derived ids.
+        segments.sort_by(|a, b| {
+            let a = a.get("segment_id").and_then(|id| id.as_str()).unwrap_or("");
+            let b = b.get("segment_id").and_then(|id| id.as_str()).unwrap_or("");
+            a.cmp(b)
+        });
+
+        // Files are copied before `meta.json` is replaced and old files removed after,
+        // so `meta.json` never points to missing files if it fails halfway.
+        for (old_name, new_name) in renamed_files.iter() {
+            fs::copy(index_path.join(old_name), index_path.join(new_name)).map_err(|e| {
+                IndexUtilsError::WriteFileError(format!("file: {:?}, message: {}", new_name, e))
+            })?;
+        }
+        Self::write_json(&meta_path, &meta, true)?;
+        let new_names: HashSet<&String> = renamed_files.values().collect();
+        for old_name in renamed_files.keys() {
+            if !new_names.contains(old_name) {
+                let _ = fs::remove_file(index_path.join(old_name));
+            }
+        }
+
+        // Tantivy garbage collects files recorded in `.managed.json`, it's sorted to be comparable.
+        let managed_path = index_path.join(MANAGED_FILE_NAME);
+        if managed_path.exists() {
+            let managed = Self::read_json(&managed_path)?;
+            let mut managed_files: Vec<String> = managed
+                .as_array()
+                .map(|files| {
+                    files
+                        .iter()
+                        .filter_map(|file| file.as_str())
+                        .map(|file| renamed_files.get(file).cloned().unwrap_or(file.to_string()))
+                        .collect()
+                })
+                .unwrap_or_default();
+            managed_files.sort();
+            managed_files.dedup();
+            let managed = Value::Array(managed_files.into_iter().map(Value::String).collect());
+            Self::write_json(&managed_path, &managed, false)?;
+        }
+        Ok(renamed_segments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::tests::{create_3column_names, index_3column_docs_with_index_writer_bridge};
+    use crate::index::implements::api_index_impl::{
+        create_index_with_build_mode, free_index_writer,
+    };
+    use crate::TEST_MUTEX;
+    use tempfile::TempDir;
+
+    fn index_files(index_path: &Path) -> BTreeMap<String, Vec<u8>> {
+        fs::read_dir(index_path)
+            .unwrap()
+            .map(|entry| entry.unwrap())
+            .map(|entry| {
+                (
+                    entry.file_name().to_string_lossy().to_string(),
+                    fs::read(entry.path()).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    fn build_deterministic_index(index_path: &str) {
+        assert!(
+            create_index_with_build_mode(index_path, &create_3column_names(), "{}", true).unwrap()
+        );
+        // Two commits produce two segments.
+        index_3column_docs_with_index_writer_bridge(index_path, false);
+        index_3column_docs_with_index_writer_bridge(index_path, false);
+        assert!(free_index_writer(index_path).unwrap());
+    }
+
+    #[test]
+    fn test_hash_segment_is_pinned() {
+        let temp_directory = TempDir::new().unwrap();
+        let index_path = temp_directory.path();
+        fs::write(index_path.join("seg.idx"), b"tantivy").unwrap();
+        fs::write(index_path.join("seg.store"), b"search").unwrap();
+        let segment_files =
+            SegmentIdUtils::segment_files(&["seg.idx".to_string(), "seg.store".to_string()], "seg");
+
+        // Derived ids must never change across releases, or replicas stop being comparable.
+        let segment_id = SegmentIdUtils::hash_segment(index_path, &segment_files, 7).unwrap();
+        assert_eq!(segment_id, "f5ca42c85501af8306424b64e0bb521f");
+        assert_ne!(
+            SegmentIdUtils::hash_segment(index_path, &segment_files, 8).unwrap(),
+            segment_id
+        );
+    }
+
+    #[test]
+    fn test_deterministic_build_is_byte_comparable() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+        let replica_a = TempDir::new().unwrap();
+        let replica_b = TempDir::new().unwrap();
+        build_deterministic_index(replica_a.path().to_str().unwrap());
+        build_deterministic_index(replica_b.path().to_str().unwrap());
+
+        let files_a = index_files(replica_a.path());
+        let files_b = index_files(replica_b.path());
+        assert_eq!(
+            files_a.keys().collect::<Vec<_>>(),
+            files_b.keys().collect::<Vec<_>>()
+        );
+        assert!(files_a == files_b);
+
+        // Ids are derived already, deriving again renames nothing.
+        assert_eq!(
+            SegmentIdUtils::derive_segment_ids(replica_a.path()).unwrap(),
+            0
+        );
+    }
+}
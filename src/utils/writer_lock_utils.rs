@@ -0,0 +1,196 @@
+use crate::common::constants::{WRITER_LOCK_FILE_NAME, WRITER_OWNER_FILE_NAME};
+use crate::common::errors::IndexUtilsError;
+use crate::logger::logger_bridge::TantivySearchLogger;
+use crate::utils::time_utils::TimeUtils;
+use crate::{common::constants::LOG_CALLBACK, WARNING};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// `WriterOwner` records which process holds the index writer lock, and when it took the lock.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct WriterOwner {
+    pub pid: u32,
+    // Owner files written by older versions name it `heartbeat_millis`.
+    #[serde(alias = "heartbeat_millis")]
+    pub recorded_millis: u64,
+}
+
+/// `WriterLockUtils` is used to detect and recover stale tantivy writer lock.
+pub struct WriterLockUtils;
+
+impl WriterLockUtils {
+    /// Record current process as the index writer owner.
+    pub fn record_writer_owner(index_path: &Path) -> Result<(), IndexUtilsError> {
+        let file_path = index_path.join(WRITER_OWNER_FILE_NAME);
+        let owner = WriterOwner {
+            pid: std::process::id(),
+            recorded_millis: TimeUtils::now_millis(),
+        };
+        let owner_json = serde_json::to_string(&owner).map_err(|e| {
+            IndexUtilsError::JsonSerializeError(format!(
+                "file: {:?}, message: {}",
+                file_path,
+                e.to_string()
+            ))
+        })?;
+        fs::write(&file_path, owner_json).map_err(|e| {
+            IndexUtilsError::WriteFileError(format!(
+                "file: {:?}, message: {}",
+                file_path,
+                e.to_string()
+            ))
+        })
+    }
+
+    /// Load the index writer owner, return `None` if owner file not exists.
+    pub fn load_writer_owner(index_path: &Path) -> Result<Option<WriterOwner>, IndexUtilsError> {
+        let file_path = index_path.join(WRITER_OWNER_FILE_NAME);
+        if !file_path.exists() {
+            return Ok(None);
+        }
+        let contents = fs::read_to_string(&file_path).map_err(|e| {
+            IndexUtilsError::ReadFileError(format!(
+                "file: {:?}, message: {}",
+                file_path,
+                e.to_string()
+            ))
+        })?;
+        let owner: WriterOwner = serde_json::from_str(&contents).map_err(|e| {
+            IndexUtilsError::JsonDeserializeError(format!(
+                "file: {:?}, message: {}",
+                file_path,
+                e.to_string()
+            ))
+        })?;
+        Ok(Some(owner))
+    }
+
+    /// Remove the index writer owner file, it's fine if file not exists.
+    pub fn remove_writer_owner(index_path: &Path) -> Result<(), IndexUtilsError> {
+        let file_path = index_path.join(WRITER_OWNER_FILE_NAME);
+        if !file_path.exists() {
+            return Ok(());
+        }
+        fs::remove_file(&file_path).map_err(|e| {
+            IndexUtilsError::WriterLockError(format!(
+                "file: {:?}, message: {}",
+                file_path,
+                e.to_string()
+            ))
+        })
+    }
+
+    /// Check whether a process is still alive.
+    pub fn is_process_alive(pid: u32) -> bool {
+        if pid == 0 || pid > libc::pid_t::MAX as u32 {
+            return false;
+        }
+        // Signal 0 only performs error checking, only ESRCH proves the process is gone,
+        // e.g. EPERM means it exists but belongs to others.
+        let status = unsafe { libc::kill(pid as libc::pid_t, 0) };
+        status == 0 || std::io::Error::last_os_error().raw_os_error() != Some(libc::ESRCH)
+    }
+
+    /// An owner is stale only if its process has died. A live owner keeps its lock however long
+    /// it's idle, tantivy writer lock is a file lock released by the OS when its process exits,
+    /// breaking it for a live process would let two writers open the same index.
+    pub fn is_owner_stale(owner: &WriterOwner) -> bool {
+        !Self::is_process_alive(owner.pid)
+    }
+
+    /// Force break the tantivy writer lock and its owner file.
+    pub fn break_writer_lock(index_path: &Path) -> Result<(), IndexUtilsError> {
+        let lock_path = index_path.join(WRITER_LOCK_FILE_NAME);
+        if lock_path.exists() {
+            WARNING!(function:"break_writer_lock", "Breaking writer lock file: {:?}", lock_path);
+            fs::remove_file(&lock_path).map_err(|e| {
+                IndexUtilsError::WriterLockError(format!(
+                    "file: {:?}, message: {}",
+                    lock_path,
+                    e.to_string()
+                ))
+            })?;
+        }
+        Self::remove_writer_owner(index_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_record_and_load_writer_owner() {
+        let temp_directory = TempDir::new().unwrap();
+        let index_path = temp_directory.path();
+
+        assert_eq!(
+            WriterLockUtils::load_writer_owner(index_path).unwrap(),
+            None
+        );
+        assert!(WriterLockUtils::record_writer_owner(index_path).is_ok());
+
+        let owner = WriterLockUtils::load_writer_owner(index_path)
+            .unwrap()
+            .expect("Owner should be recorded");
+        assert_eq!(owner.pid, std::process::id());
+        assert!(!WriterLockUtils::is_owner_stale(&owner));
+
+        assert!(WriterLockUtils::remove_writer_owner(index_path).is_ok());
+        assert_eq!(
+            WriterLockUtils::load_writer_owner(index_path).unwrap(),
+            None
+        );
+        // Remove a not exist owner file is fine.
+        assert!(WriterLockUtils::remove_writer_owner(index_path).is_ok());
+    }
+
+    #[test]
+    fn test_is_owner_stale() {
+        // Process with pid 0 can't be an owner.
+        let dead_owner = WriterOwner {
+            pid: 0,
+            recorded_millis: TimeUtils::now_millis(),
+        };
+        assert!(WriterLockUtils::is_owner_stale(&dead_owner));
+
+        // Alive process is never stale, however long ago it took the lock.
+        let idle_owner = WriterOwner {
+            pid: std::process::id(),
+            recorded_millis: 0,
+        };
+        assert!(!WriterLockUtils::is_owner_stale(&idle_owner));
+
+        // An exited child process is stale.
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let exited_owner = WriterOwner {
+            pid: child.id(),
+            recorded_millis: TimeUtils::now_millis(),
+        };
+        child.wait().unwrap();
+        assert!(WriterLockUtils::is_owner_stale(&exited_owner));
+
+        // Owner files written by older versions are still read.
+        let owner: WriterOwner = serde_json::from_str(r#"{"pid":1,"heartbeat_millis":2}"#).unwrap();
+        assert_eq!(owner.recorded_millis, 2);
+    }
+
+    #[test]
+    fn test_break_writer_lock() {
+        let temp_directory = TempDir::new().unwrap();
+        let index_path = temp_directory.path();
+        let lock_path = index_path.join(WRITER_LOCK_FILE_NAME);
+
+        fs::write(&lock_path, "").unwrap();
+        assert!(WriterLockUtils::record_writer_owner(index_path).is_ok());
+
+        assert!(WriterLockUtils::break_writer_lock(index_path).is_ok());
+        assert!(!lock_path.exists());
+        assert_eq!(
+            WriterLockUtils::load_writer_owner(index_path).unwrap(),
+            None
+        );
+    }
+}